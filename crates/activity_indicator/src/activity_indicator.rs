@@ -174,6 +174,35 @@ impl ActivityIndicator {
     }
 
     fn content_to_render(&mut self, cx: &mut ViewContext<Self>) -> Content {
+        // Show the progress of any large files being opened.
+        let mut large_file_loads = self.project.read(cx).large_file_loads();
+        if let Some((path, progress)) = large_file_loads.next() {
+            let mut message = format!(
+                "Loading {} ({}%)",
+                path.path.to_string_lossy(),
+                progress.percentage()
+            );
+            let additional_load_count = large_file_loads.count();
+            if additional_load_count > 0 {
+                write!(&mut message, " + {} more", additional_load_count).unwrap();
+            }
+
+            return Content {
+                icon: Some(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .with_animation(
+                            "arrow-circle",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        )
+                        .into_any_element(),
+                ),
+                message,
+                on_click: None,
+            };
+        }
+
         // Show any language server has pending activity.
         let mut pending_work = self.pending_language_server_work(cx);
         if let Some(PendingWork {
@@ -95,12 +95,38 @@ pub struct Request {
     pub stream: bool,
     pub system: String,
     pub max_tokens: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<Tool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct RequestMessage {
     pub role: Role,
-    pub content: String,
+    pub content: Vec<RequestContent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RequestContent {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+    },
 }
 
 #[derive(Deserialize, Debug)]
@@ -150,13 +176,21 @@ pub struct Usage {
 #[derive(Deserialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentBlock {
-    Text { text: String },
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
 }
 
 #[derive(Deserialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TextDelta {
     TextDelta { text: String },
+    InputJsonDelta { partial_json: String },
 }
 
 pub async fn stream_completion(
@@ -229,11 +263,14 @@ pub async fn stream_completion(
 //             model: Model::Claude3Opus,
 //             messages: vec![RequestMessage {
 //                 role: Role::User,
-//                 content: "Ping".to_string(),
+//                 content: vec![RequestContent::Text {
+//                     text: "Ping".to_string(),
+//                 }],
 //             }],
 //             stream: true,
 //             system: "Respond to ping with pong".to_string(),
 //             max_tokens: 4096,
+//             tools: Vec::new(),
 //         };
 
 //         let stream = stream_completion(
@@ -6,14 +6,19 @@ mod inline_assistant;
 mod model_selector;
 mod prompt_library;
 mod prompts;
+mod proposed_changes;
 mod search;
 mod slash_command;
 mod streaming_diff;
 mod terminal_inline_assistant;
+mod tools;
 
 pub use assistant_panel::{AssistantPanel, AssistantPanelEvent};
-use assistant_settings::{AnthropicModel, AssistantSettings, CloudModel, OllamaModel, OpenAiModel};
+use assistant_settings::{
+    AnthropicModel, AssistantSettings, CloudModel, GoogleModel, OllamaModel, OpenAiModel,
+};
 use assistant_slash_command::SlashCommandRegistry;
+use assistant_tooling::{SavedToolFunctionCall, ToolFunctionDefinition};
 use client::{proto, Client};
 use command_palette_hooks::CommandPaletteFilter;
 pub(crate) use completion_provider::*;
@@ -23,6 +28,7 @@ use gpui::{actions, AppContext, Global, SharedString, UpdateGlobal};
 use indexed_docs::IndexedDocsRegistry;
 pub(crate) use inline_assistant::*;
 pub(crate) use model_selector::*;
+pub use proposed_changes::ProposedChangesEditor;
 use semantic_index::{CloudEmbeddingProvider, SemanticIndex};
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
@@ -51,7 +57,10 @@ actions!(
         ToggleHistory,
         ApplyEdit,
         ConfirmCommand,
-        ToggleModelSelector
+        ToggleModelSelector,
+        AcceptProposedHunk,
+        RejectProposedHunk,
+        ApplyProposedChanges
     ]
 );
 
@@ -94,6 +103,7 @@ pub enum LanguageModel {
     OpenAi(OpenAiModel),
     Anthropic(AnthropicModel),
     Ollama(OllamaModel),
+    Google(GoogleModel),
 }
 
 impl Default for LanguageModel {
@@ -109,6 +119,7 @@ impl LanguageModel {
             LanguageModel::Anthropic(model) => format!("anthropic/{}", model.id()),
             LanguageModel::Cloud(model) => format!("zed.dev/{}", model.id()),
             LanguageModel::Ollama(model) => format!("ollama/{}", model.id()),
+            LanguageModel::Google(model) => format!("google/{}", model.id()),
         }
     }
 
@@ -118,6 +129,7 @@ impl LanguageModel {
             LanguageModel::Anthropic(model) => model.display_name().into(),
             LanguageModel::Cloud(model) => model.display_name().into(),
             LanguageModel::Ollama(model) => model.display_name().into(),
+            LanguageModel::Google(model) => model.display_name().into(),
         }
     }
 
@@ -127,6 +139,7 @@ impl LanguageModel {
             LanguageModel::Anthropic(model) => model.max_token_count(),
             LanguageModel::Cloud(model) => model.max_token_count(),
             LanguageModel::Ollama(model) => model.max_token_count(),
+            LanguageModel::Google(model) => model.max_token_count(),
         }
     }
 
@@ -136,6 +149,7 @@ impl LanguageModel {
             LanguageModel::Anthropic(model) => model.id(),
             LanguageModel::Cloud(model) => model.id(),
             LanguageModel::Ollama(model) => model.id(),
+            LanguageModel::Google(model) => model.id(),
         }
     }
 }
@@ -144,6 +158,12 @@ impl LanguageModel {
 pub struct LanguageModelRequestMessage {
     pub role: Role,
     pub content: String,
+    /// The tools the assistant asked to call in this message.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<LanguageModelToolCall>,
+    /// The results of running this message's tool calls, sent back to the model.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_results: Vec<LanguageModelToolResult>,
 }
 
 impl LanguageModelRequestMessage {
@@ -161,12 +181,28 @@ impl LanguageModelRequestMessage {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct LanguageModelToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct LanguageModelToolResult {
+    pub tool_call_id: String,
+    pub content: String,
+}
+
 #[derive(Debug, Default, Serialize)]
 pub struct LanguageModelRequest {
     pub model: LanguageModel,
     pub messages: Vec<LanguageModelRequestMessage>,
     pub stop: Vec<String>,
     pub temperature: f32,
+    /// The tools the model is allowed to call while completing this request.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<ToolFunctionDefinition>,
 }
 
 impl LanguageModelRequest {
@@ -187,6 +223,7 @@ impl LanguageModelRequest {
             LanguageModel::OpenAi(_) => {}
             LanguageModel::Anthropic(_) => {}
             LanguageModel::Ollama(_) => {}
+            LanguageModel::Google(_) => {}
             LanguageModel::Cloud(model) => match model {
                 CloudModel::Claude3Opus
                 | CloudModel::Claude3Sonnet
@@ -200,6 +237,26 @@ impl LanguageModelRequest {
     }
 }
 
+/// A single event produced while streaming a completion.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LanguageModelCompletionEvent {
+    Text(String),
+    ToolCallDelta(LanguageModelToolCallDelta),
+}
+
+/// An incremental update to one of the tool calls in a streaming completion.
+///
+/// Deltas for the same tool call share an `index`. Names and arguments arrive in
+/// chunks that must be concatenated, matching the shape expected by
+/// [`ToolRegistry::update_tool_call`](assistant_tooling::ToolRegistry::update_tool_call).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LanguageModelToolCallDelta {
+    pub index: usize,
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub arguments: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct LanguageModelResponseMessage {
     pub role: Option<Role>,
@@ -224,6 +281,21 @@ pub struct LanguageModelChoiceDelta {
 struct MessageMetadata {
     role: Role,
     status: MessageStatus,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<MessageToolCall>,
+}
+
+/// A tool call made by the assistant, along with the output that was sent back to the model.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct MessageToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+    #[serde(default)]
+    output: Option<String>,
+    /// The state of the tool's view, used to render the call again after reloading the context.
+    #[serde(default)]
+    saved_call: Option<SavedToolFunctionCall>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -8,14 +8,16 @@ use crate::{
         SlashCommandRegistry,
     },
     terminal_inline_assistant::TerminalInlineAssistant,
-    ApplyEdit, Assist, CompletionProvider, ConfirmCommand, ContextStore, CycleMessageRole,
-    InlineAssist, InlineAssistant, InsertIntoEditor, LanguageModelRequest,
-    LanguageModelRequestMessage, MessageId, MessageMetadata, MessageStatus, ModelSelector,
-    QuoteSelection, ResetKey, Role, SavedContext, SavedContextMetadata, SavedMessage, Split,
-    ToggleFocus, ToggleHistory, ToggleModelSelector,
+    tools, ApplyEdit, Assist, CompletionProvider, ConfirmCommand, ContextStore, CycleMessageRole,
+    InlineAssist, InlineAssistant, InsertIntoEditor, LanguageModelCompletionEvent,
+    LanguageModelRequest, LanguageModelRequestMessage, LanguageModelToolCall,
+    LanguageModelToolCallDelta, LanguageModelToolResult, MessageId, MessageMetadata, MessageStatus,
+    ModelSelector, QuoteSelection, ResetKey, Role, SavedContext, SavedContextMetadata,
+    SavedMessage, Split, ToggleFocus, ToggleHistory, ToggleModelSelector,
 };
 use anyhow::{anyhow, Result};
 use assistant_slash_command::{SlashCommand, SlashCommandOutput, SlashCommandOutputSection};
+use assistant_tooling::{ProjectContext, SavedToolFunctionCall, ToolFunctionCall, ToolRegistry};
 use client::telemetry::Telemetry;
 use collections::{BTreeSet, HashMap, HashSet};
 use editor::{
@@ -74,6 +76,10 @@ use workspace::{
     Save, ToggleZoom, Toolbar, Workspace,
 };
 
+/// The number of rounds of tool calls the model can make in a row before it has to wait for
+/// the user.
+const MAX_TOOL_CALL_ROUNDS: usize = 16;
+
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
         |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
@@ -111,6 +117,7 @@ pub struct AssistantPanel {
     toolbar: View<Toolbar>,
     languages: Arc<LanguageRegistry>,
     slash_commands: Arc<SlashCommandRegistry>,
+    tool_registry: Arc<ToolRegistry>,
     fs: Arc<dyn Fs>,
     telemetry: Arc<Telemetry>,
     _subscriptions: Vec<Subscription>,
@@ -288,6 +295,11 @@ impl AssistantPanel {
                         toolbar,
                         languages: workspace.app_state().languages.clone(),
                         slash_commands: SlashCommandRegistry::global(cx),
+                        tool_registry: tools::tool_registry(
+                            workspace.project().clone(),
+                            workspace_handle.clone(),
+                            cx,
+                        ),
                         fs: workspace.app_state().fs.clone(),
                         telemetry: workspace.client().telemetry().clone(),
                         width: None,
@@ -511,6 +523,7 @@ impl AssistantPanel {
             ContextEditor::new(
                 self.languages.clone(),
                 self.slash_commands.clone(),
+                self.tool_registry.clone(),
                 self.fs.clone(),
                 workspace,
                 cx,
@@ -781,6 +794,7 @@ impl AssistantPanel {
         let fs = self.fs.clone();
         let workspace = self.workspace.clone();
         let slash_commands = self.slash_commands.clone();
+        let tool_registry = self.tool_registry.clone();
         let languages = self.languages.clone();
         let telemetry = self.telemetry.clone();
 
@@ -798,6 +812,7 @@ impl AssistantPanel {
                 path.clone(),
                 languages,
                 slash_commands,
+                tool_registry,
                 Some(telemetry),
                 &mut cx,
             )
@@ -1088,6 +1103,13 @@ enum ContextEvent {
     SummaryChanged,
     EditSuggestionsChanged,
     StreamedCompletion,
+    ToolCallsUpdated {
+        message_id: MessageId,
+        delta: LanguageModelToolCallDelta,
+    },
+    ToolCallsFinished {
+        message_id: MessageId,
+    },
     PendingSlashCommandsUpdated {
         removed: Vec<Range<language::Anchor>>,
         updated: Vec<PendingSlashCommand>,
@@ -1127,6 +1149,7 @@ pub struct Context {
     _subscriptions: Vec<Subscription>,
     telemetry: Option<Arc<Telemetry>>,
     slash_command_registry: Arc<SlashCommandRegistry>,
+    tool_registry: Arc<ToolRegistry>,
     language_registry: Arc<LanguageRegistry>,
 }
 
@@ -1136,6 +1159,7 @@ impl Context {
     fn new(
         language_registry: Arc<LanguageRegistry>,
         slash_command_registry: Arc<SlashCommandRegistry>,
+        tool_registry: Arc<ToolRegistry>,
         telemetry: Option<Arc<Telemetry>>,
         cx: &mut ModelContext<Self>,
     ) -> Self {
@@ -1169,6 +1193,7 @@ impl Context {
             telemetry,
             language_registry,
             slash_command_registry,
+            tool_registry,
         };
 
         let message = MessageAnchor {
@@ -1181,6 +1206,7 @@ impl Context {
             MessageMetadata {
                 role: Role::User,
                 status: MessageStatus::Done,
+                tool_calls: Vec::new(),
             },
         );
 
@@ -1234,6 +1260,7 @@ impl Context {
         path: PathBuf,
         language_registry: Arc<LanguageRegistry>,
         slash_command_registry: Arc<SlashCommandRegistry>,
+        tool_registry: Arc<ToolRegistry>,
         telemetry: Option<Arc<Telemetry>>,
         cx: &mut AsyncAppContext,
     ) -> Result<Model<Self>> {
@@ -1307,6 +1334,7 @@ impl Context {
                 telemetry,
                 language_registry,
                 slash_command_registry,
+                tool_registry,
             };
             this.set_language(cx);
             this.reparse_edit_suggestions(cx);
@@ -1688,7 +1716,6 @@ impl Context {
             }
 
             let request = self.to_completion_request(cx);
-            let stream = CompletionProvider::global(cx).complete(request);
             let assistant_message = self
                 .insert_message_after(last_message_id, Role::Assistant, MessageStatus::Pending, cx)
                 .unwrap();
@@ -1699,115 +1726,283 @@ impl Context {
                 .unwrap();
             user_messages.push(user_message);
 
-            let task = cx.spawn({
-                |this, mut cx| async move {
-                    let assistant_message_id = assistant_message.id;
-                    let mut response_latency = None;
-                    let stream_completion = async {
-                        let request_start = Instant::now();
-                        let mut messages = stream.await?;
-
-                        while let Some(message) = messages.next().await {
-                            if response_latency.is_none() {
-                                response_latency = Some(request_start.elapsed());
-                            }
-                            let text = message?;
-
-                            this.update(&mut cx, |this, cx| {
-                                let message_ix = this
-                                    .message_anchors
-                                    .iter()
-                                    .position(|message| message.id == assistant_message_id)?;
-                                let message_range = this.buffer.update(cx, |buffer, cx| {
-                                    let message_start_offset =
-                                        this.message_anchors[message_ix].start.to_offset(buffer);
-                                    let message_old_end_offset = this.message_anchors
-                                        [message_ix + 1..]
-                                        .iter()
-                                        .find(|message| message.start.is_valid(buffer))
-                                        .map_or(buffer.len(), |message| {
-                                            message.start.to_offset(buffer).saturating_sub(1)
-                                        });
-                                    let message_new_end_offset =
-                                        message_old_end_offset + text.len();
-                                    buffer.edit(
-                                        [(message_old_end_offset..message_old_end_offset, text)],
-                                        None,
-                                        cx,
-                                    );
-                                    message_start_offset..message_new_end_offset
-                                });
-                                this.reparse_edit_suggestions_in_range(message_range, cx);
-                                cx.emit(ContextEvent::StreamedCompletion);
+            self.stream_assistant_message(assistant_message, request, cx);
+        }
 
-                                Some(())
-                            })?;
-                            smol::future::yield_now().await;
-                        }
+        user_messages
+    }
 
-                        this.update(&mut cx, |this, cx| {
-                            this.pending_completions
-                                .retain(|completion| completion.id != this.completion_count);
-                            this.summarize(cx);
-                        })?;
+    /// Asks the model to continue once the outputs of all the tool calls made in the given
+    /// assistant message are known, streaming its response into a new assistant message.
+    ///
+    /// The model is only allowed [`MAX_TOOL_CALL_ROUNDS`] rounds of tool calls in a row, so that
+    /// a model which keeps calling tools doesn't keep running forever.
+    fn continue_after_tool_calls(&mut self, message_id: MessageId, cx: &mut ModelContext<Self>) {
+        if !CompletionProvider::global(cx).is_authenticated() {
+            log::info!("completion provider has no credentials");
+            return;
+        }
 
-                        anyhow::Ok(())
-                    };
+        if self.tool_call_rounds(message_id, cx) >= MAX_TOOL_CALL_ROUNDS {
+            log::info!("stopping after {MAX_TOOL_CALL_ROUNDS} rounds of tool calls");
+            return;
+        }
 
-                    let result = stream_completion.await;
+        // Leave out anything after the message that called the tools, such as the queued reply.
+        let mut reached_tool_calls = false;
+        let messages = self
+            .messages(cx)
+            .take_while(|message| {
+                let include = !reached_tool_calls;
+                reached_tool_calls |= message.id == message_id;
+                include
+            })
+            .collect::<Vec<_>>();
+        let request = self.completion_request(messages.into_iter(), cx);
 
-                    this.update(&mut cx, |this, cx| {
-                        if let Some(metadata) =
-                            this.messages_metadata.get_mut(&assistant_message.id)
-                        {
-                            let error_message = result
-                                .err()
-                                .map(|error| error.to_string().trim().to_string());
-                            if let Some(error_message) = error_message.as_ref() {
-                                metadata.status =
-                                    MessageStatus::Error(SharedString::from(error_message.clone()));
-                            } else {
-                                metadata.status = MessageStatus::Done;
-                            }
+        if let Some(assistant_message) =
+            self.insert_message_after(message_id, Role::Assistant, MessageStatus::Pending, cx)
+        {
+            self.stream_assistant_message(assistant_message, request, cx);
+        }
+    }
 
-                            if let Some(telemetry) = this.telemetry.as_ref() {
-                                let model = CompletionProvider::global(cx).model();
-                                telemetry.report_assistant_event(
-                                    this.id.clone(),
-                                    AssistantKind::Panel,
-                                    model.telemetry_id(),
-                                    response_latency,
-                                    error_message,
-                                );
+    /// Counts the consecutive assistant messages that made tool calls, up to and including
+    /// the given message.
+    fn tool_call_rounds(&self, message_id: MessageId, cx: &AppContext) -> usize {
+        let mut reached_message = false;
+        let messages = self
+            .messages(cx)
+            .take_while(|message| {
+                let include = !reached_message;
+                reached_message |= message.id == message_id;
+                include
+            })
+            .collect::<Vec<_>>();
+        messages
+            .iter()
+            .rev()
+            .take_while(|message| {
+                message.role == Role::Assistant
+                    && self
+                        .messages_metadata
+                        .get(&message.id)
+                        .map_or(false, |metadata| !metadata.tool_calls.is_empty())
+            })
+            .count()
+    }
+
+    fn stream_assistant_message(
+        &mut self,
+        assistant_message: MessageAnchor,
+        mut request: LanguageModelRequest,
+        cx: &mut ModelContext<Self>,
+    ) {
+        request.tools = self.tool_registry.definitions();
+        let stream = CompletionProvider::global(cx).stream_completion(request);
+
+        let task = cx.spawn({
+            |this, mut cx| async move {
+                let assistant_message_id = assistant_message.id;
+                let mut response_latency = None;
+                let stream_completion = async {
+                    let request_start = Instant::now();
+                    let mut events = stream.await?;
+
+                    while let Some(event) = events.next().await {
+                        if response_latency.is_none() {
+                            response_latency = Some(request_start.elapsed());
+                        }
+
+                        match event? {
+                            LanguageModelCompletionEvent::Text(text) => {
+                                this.update(&mut cx, |this, cx| {
+                                    let message_ix = this
+                                        .message_anchors
+                                        .iter()
+                                        .position(|message| message.id == assistant_message_id)?;
+                                    let message_range = this.buffer.update(cx, |buffer, cx| {
+                                        let message_start_offset = this.message_anchors[message_ix]
+                                            .start
+                                            .to_offset(buffer);
+                                        let message_old_end_offset = this.message_anchors
+                                            [message_ix + 1..]
+                                            .iter()
+                                            .find(|message| message.start.is_valid(buffer))
+                                            .map_or(buffer.len(), |message| {
+                                                message.start.to_offset(buffer).saturating_sub(1)
+                                            });
+                                        let message_new_end_offset =
+                                            message_old_end_offset + text.len();
+                                        buffer.edit(
+                                            [(
+                                                message_old_end_offset..message_old_end_offset,
+                                                text,
+                                            )],
+                                            None,
+                                            cx,
+                                        );
+                                        message_start_offset..message_new_end_offset
+                                    });
+                                    this.reparse_edit_suggestions_in_range(message_range, cx);
+                                    cx.emit(ContextEvent::StreamedCompletion);
+
+                                    Some(())
+                                })?;
+                            }
+                            LanguageModelCompletionEvent::ToolCallDelta(delta) => {
+                                this.update(&mut cx, |this, cx| {
+                                    this.apply_tool_call_delta(assistant_message_id, delta, cx)
+                                })?;
                             }
+                        }
+                        smol::future::yield_now().await;
+                    }
 
-                            cx.emit(ContextEvent::MessagesEdited);
+                    this.update(&mut cx, |this, cx| {
+                        this.pending_completions
+                            .retain(|completion| completion.id != this.completion_count);
+                        this.summarize(cx);
+                    })?;
+
+                    anyhow::Ok(())
+                };
+
+                let result = stream_completion.await;
+
+                this.update(&mut cx, |this, cx| {
+                    if let Some(metadata) = this.messages_metadata.get_mut(&assistant_message.id) {
+                        let error_message = result
+                            .err()
+                            .map(|error| error.to_string().trim().to_string());
+                        if let Some(error_message) = error_message.as_ref() {
+                            metadata.status =
+                                MessageStatus::Error(SharedString::from(error_message.clone()));
+                        } else {
+                            metadata.status = MessageStatus::Done;
+                        }
+                        let made_tool_calls = !metadata.tool_calls.is_empty();
+
+                        if let Some(telemetry) = this.telemetry.as_ref() {
+                            let model = CompletionProvider::global(cx).model();
+                            telemetry.report_assistant_event(
+                                this.id.clone(),
+                                AssistantKind::Panel,
+                                model.telemetry_id(),
+                                response_latency,
+                                error_message.clone(),
+                            );
                         }
-                    })
-                    .ok();
-                }
-            });
 
-            self.pending_completions.push(PendingCompletion {
-                id: post_inc(&mut self.completion_count),
-                _task: task,
-            });
+                        cx.emit(ContextEvent::MessagesEdited);
+                        if error_message.is_none() && made_tool_calls {
+                            cx.emit(ContextEvent::ToolCallsFinished {
+                                message_id: assistant_message.id,
+                            });
+                        }
+                    }
+                })
+                .ok();
+            }
+        });
+
+        self.pending_completions.push(PendingCompletion {
+            id: post_inc(&mut self.completion_count),
+            _task: task,
+        });
+    }
+
+    fn apply_tool_call_delta(
+        &mut self,
+        message_id: MessageId,
+        delta: LanguageModelToolCallDelta,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(metadata) = self.messages_metadata.get_mut(&message_id) else {
+            return;
+        };
+
+        if metadata.tool_calls.len() <= delta.index {
+            metadata
+                .tool_calls
+                .resize_with(delta.index + 1, Default::default);
+        }
+        let tool_call = &mut metadata.tool_calls[delta.index];
+        if let Some(id) = delta.id.as_ref() {
+            tool_call.id.push_str(id);
+        }
+        if let Some(name) = delta.name.as_ref() {
+            tool_call.name.push_str(name);
+        }
+        if let Some(arguments) = delta.arguments.as_ref() {
+            tool_call.arguments.push_str(arguments);
         }
 
-        user_messages
+        cx.emit(ContextEvent::ToolCallsUpdated { message_id, delta });
+    }
+
+    /// Records what each tool call in the given message produced, so it can be sent back to the
+    /// model and saved along with the context.
+    fn set_tool_call_outputs(
+        &mut self,
+        message_id: MessageId,
+        outputs: Vec<ToolCallOutput>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(metadata) = self.messages_metadata.get_mut(&message_id) {
+            for output in outputs {
+                if let Some(tool_call) = metadata
+                    .tool_calls
+                    .iter_mut()
+                    .find(|tool_call| tool_call.id == output.tool_call_id)
+                {
+                    tool_call.output = Some(output.content);
+                    tool_call.saved_call = output.saved_call;
+                }
+            }
+            cx.emit(ContextEvent::MessagesEdited);
+        }
     }
 
     pub fn to_completion_request(&self, cx: &AppContext) -> LanguageModelRequest {
-        let messages = self
-            .messages(cx)
+        self.completion_request(self.messages(cx), cx)
+    }
+
+    fn completion_request(
+        &self,
+        messages: impl Iterator<Item = Message>,
+        cx: &AppContext,
+    ) -> LanguageModelRequest {
+        let buffer = self.buffer.read(cx);
+        let messages = messages
             .filter(|message| matches!(message.status, MessageStatus::Done))
-            .map(|message| message.to_request_message(self.buffer.read(cx)));
+            .map(|message| {
+                let mut request_message = message.to_request_message(buffer);
+                if let Some(metadata) = self.messages_metadata.get(&message.id) {
+                    // Calls whose output is unknown can't be answered, so they're left out.
+                    for tool_call in &metadata.tool_calls {
+                        if let Some(output) = tool_call.output.clone() {
+                            request_message.tool_calls.push(LanguageModelToolCall {
+                                id: tool_call.id.clone(),
+                                name: tool_call.name.clone(),
+                                arguments: tool_call.arguments.clone(),
+                            });
+                            request_message.tool_results.push(LanguageModelToolResult {
+                                tool_call_id: tool_call.id.clone(),
+                                content: output,
+                            });
+                        }
+                    }
+                }
+                request_message
+            });
 
         LanguageModelRequest {
             model: CompletionProvider::global(cx).model(),
             messages: messages.collect(),
             stop: vec![],
             temperature: 1.0,
+            tools: Vec::new(),
         }
     }
 
@@ -1860,8 +2055,14 @@ impl Context {
             };
             self.message_anchors
                 .insert(next_message_ix, message.clone());
-            self.messages_metadata
-                .insert(message.id, MessageMetadata { role, status });
+            self.messages_metadata.insert(
+                message.id,
+                MessageMetadata {
+                    role,
+                    status,
+                    tool_calls: Vec::new(),
+                },
+            );
             cx.emit(ContextEvent::MessagesEdited);
             Some(message)
         } else {
@@ -1919,6 +2120,7 @@ impl Context {
                 MessageMetadata {
                     role,
                     status: MessageStatus::Done,
+                    tool_calls: Vec::new(),
                 },
             );
 
@@ -1963,6 +2165,7 @@ impl Context {
                         MessageMetadata {
                             role,
                             status: MessageStatus::Done,
+                            tool_calls: Vec::new(),
                         },
                     );
                     (Some(selection), Some(suffix))
@@ -1989,12 +2192,15 @@ impl Context {
                 .chain(Some(LanguageModelRequestMessage {
                     role: Role::User,
                     content: "Summarize the context into a short title without punctuation.".into(),
+                    tool_calls: Vec::new(),
+                    tool_results: Vec::new(),
                 }));
             let request = LanguageModelRequest {
                 model: CompletionProvider::global(cx).model(),
                 messages: messages.collect(),
                 stop: vec![],
                 temperature: 1.0,
+                tools: Vec::new(),
             };
 
             let stream = CompletionProvider::global(cx).complete(request);
@@ -2151,7 +2357,7 @@ impl Context {
                 };
 
                 fs.create_dir(contexts_dir().as_ref()).await?;
-                fs.atomic_write(path.clone(), serde_json::to_string(&context).unwrap())
+                fs.atomic_write(path.clone(), serde_json::to_vec(&context).unwrap())
                     .await?;
                 this.update(&mut cx, |this, _| this.path = Some(path))?;
             }
@@ -2274,6 +2480,13 @@ struct PendingCompletion {
     _task: Task<()>,
 }
 
+/// What a tool call produced, matched back to the call by its id.
+struct ToolCallOutput {
+    tool_call_id: String,
+    content: String,
+    saved_call: Option<SavedToolFunctionCall>,
+}
+
 enum ContextEditorEvent {
     Edited,
     TabContentChanged,
@@ -2296,6 +2509,7 @@ pub struct ContextEditor {
     scroll_position: Option<ScrollPosition>,
     pending_slash_command_creases: HashMap<Range<language::Anchor>, CreaseId>,
     pending_slash_command_blocks: HashMap<Range<language::Anchor>, BlockId>,
+    tool_calls: HashMap<MessageId, Vec<ToolFunctionCall>>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -2303,6 +2517,7 @@ impl ContextEditor {
     fn new(
         language_registry: Arc<LanguageRegistry>,
         slash_command_registry: Arc<SlashCommandRegistry>,
+        tool_registry: Arc<ToolRegistry>,
         fs: Arc<dyn Fs>,
         workspace: View<Workspace>,
         cx: &mut ViewContext<Self>,
@@ -2315,6 +2530,7 @@ impl ContextEditor {
             Context::new(
                 language_registry,
                 slash_command_registry,
+                tool_registry,
                 Some(telemetry),
                 cx,
             )
@@ -2371,8 +2587,10 @@ impl ContextEditor {
             workspace: workspace.downgrade(),
             pending_slash_command_creases: HashMap::default(),
             pending_slash_command_blocks: HashMap::default(),
+            tool_calls: HashMap::default(),
             _subscriptions,
         };
+        this.restore_tool_calls(cx);
         this.update_message_headers(cx);
         this.insert_slash_command_output_sections(sections, cx);
         this
@@ -2629,6 +2847,30 @@ impl ContextEditor {
                     }
                 });
             }
+            ContextEvent::ToolCallsUpdated { message_id, delta } => {
+                let tool_registry = self.context.read(cx).tool_registry.clone();
+                let tool_calls = self.tool_calls.entry(*message_id).or_default();
+                let added_tool_call = tool_calls.len() <= delta.index;
+                if added_tool_call {
+                    tool_calls.resize_with(delta.index + 1, Default::default);
+                }
+                let tool_call = &mut tool_calls[delta.index];
+                if let Some(id) = delta.id.as_ref() {
+                    tool_call.id.push_str(id);
+                }
+                tool_registry.update_tool_call(
+                    tool_call,
+                    delta.name.as_deref(),
+                    delta.arguments.as_deref(),
+                    cx,
+                );
+                if added_tool_call {
+                    self.update_message_headers(cx);
+                }
+            }
+            ContextEvent::ToolCallsFinished { message_id } => {
+                self.execute_tool_calls(*message_id, cx);
+            }
             ContextEvent::PendingSlashCommandsUpdated { removed, updated } => {
                 self.editor.update(cx, |editor, cx| {
                     let buffer = editor.buffer().read(cx).snapshot(cx);
@@ -2899,11 +3141,13 @@ impl ContextEditor {
     }
 
     fn update_message_headers(&mut self, cx: &mut ViewContext<Self>) {
+        let context_editor = cx.view().downgrade();
         self.editor.update(cx, |editor, cx| {
             let buffer = editor.buffer().read(cx).snapshot(cx);
-            let excerpt_id = *buffer.as_singleton().unwrap().0;
+            let (excerpt_id, _, context_buffer) = buffer.as_singleton().unwrap();
+            let excerpt_id = *excerpt_id;
             let old_blocks = std::mem::take(&mut self.blocks);
-            let new_blocks = self
+            let mut new_blocks = self
                 .context
                 .read(cx)
                 .messages(cx)
@@ -2971,12 +3215,146 @@ impl ContextEditor {
                 })
                 .collect::<Vec<_>>();
 
+            // Show the tool calls made by a message below its last line.
+            new_blocks.extend(self.context.read(cx).messages(cx).filter_map(|message| {
+                let tool_call_count = self.tool_calls.get(&message.id)?.len();
+                if tool_call_count == 0 {
+                    return None;
+                }
+
+                let last_offset = message
+                    .offset_range
+                    .end
+                    .saturating_sub(1)
+                    .max(message.offset_range.start);
+                let message_id = message.id;
+                let context_editor = context_editor.clone();
+                Some(BlockProperties {
+                    position: buffer
+                        .anchor_in_excerpt(excerpt_id, context_buffer.anchor_before(last_offset))
+                        .unwrap(),
+                    height: (tool_call_count + 1) as u8,
+                    style: BlockStyle::Sticky,
+                    render: Box::new(move |cx| {
+                        let tool_calls = context_editor
+                            .update(cx.context, |context_editor, cx| {
+                                context_editor.render_tool_calls(message_id, cx)
+                            })
+                            .unwrap_or_default();
+                        v_flex()
+                            .pl(cx.gutter_dimensions.full_width())
+                            .py_1()
+                            .gap_1()
+                            .children(tool_calls)
+                            .into_any_element()
+                    }),
+                    disposition: BlockDisposition::Below,
+                })
+            }));
+
             editor.remove_blocks(old_blocks, None, cx);
             let ids = editor.insert_blocks(new_blocks, None, cx);
             self.blocks = HashSet::from_iter(ids);
         });
     }
 
+    fn render_tool_calls(
+        &self,
+        message_id: MessageId,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<AnyElement> {
+        let Some(tool_calls) = self.tool_calls.get(&message_id) else {
+            return Vec::new();
+        };
+        let tool_registry = self.context.read(cx).tool_registry.clone();
+        tool_calls
+            .iter()
+            .filter_map(|tool_call| tool_registry.render_tool_call(tool_call, cx))
+            .collect()
+    }
+
+    /// Runs the tool calls made by the given message, then lets the model continue with
+    /// their outputs.
+    fn execute_tool_calls(&mut self, message_id: MessageId, cx: &mut ViewContext<Self>) {
+        let Some(project) = self
+            .workspace
+            .upgrade()
+            .map(|workspace| workspace.read(cx).project().clone())
+        else {
+            return;
+        };
+        let tool_registry = self.context.read(cx).tool_registry.clone();
+        let Some(tool_calls) = self.tool_calls.get_mut(&message_id) else {
+            return;
+        };
+        let tasks = tool_calls
+            .iter_mut()
+            .filter_map(|tool_call| tool_registry.execute_tool_call(tool_call, cx))
+            .collect::<Vec<_>>();
+
+        let fs = self.fs.clone();
+        cx.spawn(|this, mut cx| async move {
+            for task in tasks {
+                task.await.log_err();
+            }
+
+            this.update(&mut cx, |this, cx| {
+                let mut project_context = ProjectContext::new(project.downgrade(), fs);
+                let outputs = this
+                    .tool_calls
+                    .get(&message_id)
+                    .into_iter()
+                    .flatten()
+                    .map(|tool_call| ToolCallOutput {
+                        tool_call_id: tool_call.id.clone(),
+                        content: tool_registry.content_for_tool_call(
+                            tool_call,
+                            &mut project_context,
+                            cx,
+                        ),
+                        saved_call: tool_registry.serialize_tool_call(tool_call, cx).log_err(),
+                    })
+                    .collect();
+                this.context.update(cx, |context, cx| {
+                    context.set_tool_call_outputs(message_id, outputs, cx);
+                    context.continue_after_tool_calls(message_id, cx);
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Rebuilds the views of tool calls saved with the context.
+    fn restore_tool_calls(&mut self, cx: &mut ViewContext<Self>) {
+        let context = self.context.read(cx);
+        let tool_registry = context.tool_registry.clone();
+        let saved_tool_calls = context
+            .messages_metadata
+            .iter()
+            .map(|(message_id, metadata)| {
+                let saved_calls = metadata
+                    .tool_calls
+                    .iter()
+                    .filter_map(|tool_call| tool_call.saved_call.clone())
+                    .collect::<Vec<_>>();
+                (*message_id, saved_calls)
+            })
+            .filter(|(_, saved_calls)| !saved_calls.is_empty())
+            .collect::<Vec<_>>();
+
+        for (message_id, saved_calls) in saved_tool_calls {
+            let tool_calls = saved_calls
+                .iter()
+                .filter_map(|saved_call| {
+                    tool_registry
+                        .deserialize_tool_call(saved_call, cx)
+                        .log_err()
+                })
+                .collect();
+            self.tool_calls.insert(message_id, tool_calls);
+        }
+    }
+
     fn insert_selection(
         workspace: &mut Workspace,
         _: &InsertIntoEditor,
@@ -3361,6 +3739,8 @@ impl Message {
         LanguageModelRequestMessage {
             role: self.role,
             content: buffer.text_for_range(self.offset_range.clone()).collect(),
+            tool_calls: Vec::new(),
+            tool_results: Vec::new(),
         }
     }
 }
@@ -3489,7 +3869,15 @@ mod tests {
         init(cx);
         let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
 
-        let context = cx.new_model(|cx| Context::new(registry, Default::default(), None, cx));
+        let context = cx.new_model(|cx| {
+            Context::new(
+                registry,
+                Default::default(),
+                Arc::new(ToolRegistry::new()),
+                None,
+                cx,
+            )
+        });
         let buffer = context.read(cx).buffer.clone();
 
         let message_1 = context.read(cx).message_anchors[0].clone();
@@ -3620,7 +4008,15 @@ mod tests {
         init(cx);
         let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
 
-        let context = cx.new_model(|cx| Context::new(registry, Default::default(), None, cx));
+        let context = cx.new_model(|cx| {
+            Context::new(
+                registry,
+                Default::default(),
+                Arc::new(ToolRegistry::new()),
+                None,
+                cx,
+            )
+        });
         let buffer = context.read(cx).buffer.clone();
 
         let message_1 = context.read(cx).message_anchors[0].clone();
@@ -3713,7 +4109,15 @@ mod tests {
         cx.set_global(settings_store);
         init(cx);
         let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
-        let context = cx.new_model(|cx| Context::new(registry, Default::default(), None, cx));
+        let context = cx.new_model(|cx| {
+            Context::new(
+                registry,
+                Default::default(),
+                Arc::new(ToolRegistry::new()),
+                None,
+                cx,
+            )
+        });
         let buffer = context.read(cx).buffer.clone();
 
         let message_1 = context.read(cx).message_anchors[0].clone();
@@ -3818,8 +4222,15 @@ mod tests {
         slash_command_registry.register_command(active_command::ActiveSlashCommand, false);
 
         let registry = Arc::new(LanguageRegistry::test(cx.executor()));
-        let context =
-            cx.new_model(|cx| Context::new(registry.clone(), slash_command_registry, None, cx));
+        let context = cx.new_model(|cx| {
+            Context::new(
+                registry.clone(),
+                slash_command_registry,
+                Arc::new(ToolRegistry::new()),
+                None,
+                cx,
+            )
+        });
 
         let output_ranges = Rc::new(RefCell::new(HashSet::default()));
         context.update(cx, |_, cx| {
@@ -3992,8 +4403,15 @@ mod tests {
         cx.set_global(CompletionProvider::Fake(FakeCompletionProvider::default()));
         cx.update(init);
         let registry = Arc::new(LanguageRegistry::test(cx.executor()));
-        let context =
-            cx.new_model(|cx| Context::new(registry.clone(), Default::default(), None, cx));
+        let context = cx.new_model(|cx| {
+            Context::new(
+                registry.clone(),
+                Default::default(),
+                Arc::new(ToolRegistry::new()),
+                None,
+                cx,
+            )
+        });
         let buffer = context.read_with(cx, |context, _| context.buffer.clone());
         let message_0 = context.read_with(cx, |context, _| context.message_anchors[0].id);
         let message_1 = context.update(cx, |context, cx| {
@@ -4031,6 +4449,7 @@ mod tests {
             Default::default(),
             registry.clone(),
             Default::default(),
+            Arc::new(ToolRegistry::new()),
             None,
             &mut cx.to_async(),
         )
@@ -4052,6 +4471,132 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_tool_call_outputs_are_matched_by_id(cx: &mut AppContext) {
+        let context = tool_calling_context(cx);
+        let message_1 = context.read(cx).message_anchors[0].clone();
+        let message_2 = context.update(cx, |context, cx| {
+            let message = context
+                .insert_message_after(message_1.id, Role::Assistant, MessageStatus::Done, cx)
+                .unwrap();
+            call_tool(context, message.id, 0, "call-1", cx);
+            call_tool(context, message.id, 1, "call-2", cx);
+            message
+        });
+
+        // The outputs can be produced in any order, and outputs of unknown calls are ignored.
+        context.update(cx, |context, cx| {
+            context.set_tool_call_outputs(
+                message_2.id,
+                vec![
+                    ToolCallOutput {
+                        tool_call_id: "call-2".into(),
+                        content: "second".into(),
+                        saved_call: None,
+                    },
+                    ToolCallOutput {
+                        tool_call_id: "call-3".into(),
+                        content: "unknown".into(),
+                        saved_call: None,
+                    },
+                    ToolCallOutput {
+                        tool_call_id: "call-1".into(),
+                        content: "first".into(),
+                        saved_call: None,
+                    },
+                ],
+                cx,
+            )
+        });
+        let context = context.read(cx);
+        let outputs = context.messages_metadata[&message_2.id]
+            .tool_calls
+            .iter()
+            .map(|tool_call| (tool_call.id.as_str(), tool_call.output.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            [("call-1", Some("first")), ("call-2", Some("second"))]
+        );
+    }
+
+    #[gpui::test]
+    fn test_tool_call_rounds_are_limited(cx: &mut AppContext) {
+        let context = tool_calling_context(cx);
+        let mut message_ids = vec![context.read(cx).message_anchors[0].id];
+        for round in 0..MAX_TOOL_CALL_ROUNDS {
+            let message_id = context.update(cx, |context, cx| {
+                let message = context
+                    .insert_message_after(
+                        *message_ids.last().unwrap(),
+                        Role::Assistant,
+                        MessageStatus::Done,
+                        cx,
+                    )
+                    .unwrap();
+                call_tool(context, message.id, 0, &format!("call-{round}"), cx);
+                message.id
+            });
+            message_ids.push(message_id);
+        }
+
+        let last_message_id = *message_ids.last().unwrap();
+        assert_eq!(
+            context.read(cx).tool_call_rounds(last_message_id, cx),
+            MAX_TOOL_CALL_ROUNDS
+        );
+
+        // The model doesn't get to continue after the last allowed round of tool calls.
+        let message_count = context.read(cx).message_anchors.len();
+        context.update(cx, |context, cx| {
+            context.continue_after_tool_calls(last_message_id, cx)
+        });
+        assert_eq!(context.read(cx).message_anchors.len(), message_count);
+
+        // It does before that.
+        let previous_message_id = message_ids[message_ids.len() - 2];
+        context.update(cx, |context, cx| {
+            context.continue_after_tool_calls(previous_message_id, cx)
+        });
+        assert_eq!(context.read(cx).message_anchors.len(), message_count + 1);
+    }
+
+    fn tool_calling_context(cx: &mut AppContext) -> Model<Context> {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(CompletionProvider::Fake(FakeCompletionProvider::default()));
+        cx.set_global(settings_store);
+        init(cx);
+        let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+        cx.new_model(|cx| {
+            Context::new(
+                registry,
+                Default::default(),
+                Arc::new(ToolRegistry::new()),
+                None,
+                cx,
+            )
+        })
+    }
+
+    fn call_tool(
+        context: &mut Context,
+        message_id: MessageId,
+        index: usize,
+        id: &str,
+        cx: &mut ModelContext<Context>,
+    ) {
+        context.apply_tool_call_delta(
+            message_id,
+            LanguageModelToolCallDelta {
+                index,
+                id: Some(id.into()),
+                name: Some("read_file".into()),
+                arguments: Some("{}".into()),
+            },
+            cx,
+        );
+    }
+
     fn messages(context: &Model<Context>, cx: &AppContext) -> Vec<(MessageId, Role, Range<usize>)> {
         context
             .read(cx)
@@ -1,6 +1,7 @@
 use std::fmt;
 
 pub use anthropic::Model as AnthropicModel;
+pub use google_ai::Model as GoogleModel;
 use gpui::Pixels;
 pub use ollama::Model as OllamaModel;
 pub use open_ai::Model as OpenAiModel;
@@ -181,6 +182,11 @@ pub enum AssistantProvider {
         api_url: String,
         low_speed_timeout_in_seconds: Option<u64>,
     },
+    Google {
+        model: GoogleModel,
+        api_url: String,
+        low_speed_timeout_in_seconds: Option<u64>,
+    },
 }
 
 impl Default for AssistantProvider {
@@ -218,6 +224,12 @@ pub enum AssistantProviderContent {
         api_url: Option<String>,
         low_speed_timeout_in_seconds: Option<u64>,
     },
+    #[serde(rename = "google")]
+    Google {
+        default_model: Option<GoogleModel>,
+        api_url: Option<String>,
+        low_speed_timeout_in_seconds: Option<u64>,
+    },
 }
 
 #[derive(Debug, Default)]
@@ -339,6 +351,14 @@ impl AssistantSettingsContent {
                             *model = Some(new_model);
                         }
                     }
+                    Some(AssistantProviderContent::Google {
+                        default_model: model,
+                        ..
+                    }) => {
+                        if let LanguageModel::Google(new_model) = new_model {
+                            *model = Some(new_model);
+                        }
+                    }
                     provider => match new_model {
                         LanguageModel::Cloud(model) => {
                             *provider = Some(AssistantProviderContent::ZedDotDev {
@@ -367,6 +387,13 @@ impl AssistantSettingsContent {
                                 low_speed_timeout_in_seconds: None,
                             })
                         }
+                        LanguageModel::Google(model) => {
+                            *provider = Some(AssistantProviderContent::Google {
+                                default_model: Some(model),
+                                api_url: None,
+                                low_speed_timeout_in_seconds: None,
+                            })
+                        }
                     },
                 },
             },
@@ -556,6 +583,27 @@ impl Settings for AssistantSettings {
                                 Some(low_speed_timeout_in_seconds_override);
                         }
                     }
+                    (
+                        AssistantProvider::Google {
+                            model,
+                            api_url,
+                            low_speed_timeout_in_seconds,
+                        },
+                        AssistantProviderContent::Google {
+                            default_model: model_override,
+                            api_url: api_url_override,
+                            low_speed_timeout_in_seconds: low_speed_timeout_in_seconds_override,
+                        },
+                    ) => {
+                        merge(model, model_override);
+                        merge(api_url, api_url_override);
+                        if let Some(low_speed_timeout_in_seconds_override) =
+                            low_speed_timeout_in_seconds_override
+                        {
+                            *low_speed_timeout_in_seconds =
+                                Some(low_speed_timeout_in_seconds_override);
+                        }
+                    }
                     (provider, provider_override) => {
                         *provider = match provider_override {
                             AssistantProviderContent::ZedDotDev {
@@ -593,6 +641,15 @@ impl Settings for AssistantSettings {
                                 api_url: api_url.unwrap_or_else(|| ollama::OLLAMA_API_URL.into()),
                                 low_speed_timeout_in_seconds,
                             },
+                            AssistantProviderContent::Google {
+                                default_model: model,
+                                api_url,
+                                low_speed_timeout_in_seconds,
+                            } => AssistantProvider::Google {
+                                model: model.unwrap_or_default(),
+                                api_url: api_url.unwrap_or_else(|| google_ai::API_URL.into()),
+                                low_speed_timeout_in_seconds,
+                            },
                         };
                     }
                 }
@@ -700,5 +757,37 @@ mod tests {
                 model: CloudModel::Custom("custom".into())
             }
         );
+
+        // Google models can be configured with their context size.
+        SettingsStore::update_global(cx, |store, cx| {
+            store
+                .set_user_settings(
+                    r#"{
+                        "assistant": {
+                            "version": "1",
+                            "provider": {
+                                "name": "google",
+                                "default_model": {
+                                    "name": "gemini-1.5-flash",
+                                    "max_tokens": 1048576
+                                }
+                            }
+                        }
+                    }"#,
+                    cx,
+                )
+                .unwrap();
+        });
+        assert_eq!(
+            AssistantSettings::get_global(cx).provider,
+            AssistantProvider::Google {
+                model: GoogleModel {
+                    name: "gemini-1.5-flash".into(),
+                    max_tokens: 1048576,
+                },
+                api_url: google_ai::API_URL.into(),
+                low_speed_timeout_in_seconds: None,
+            }
+        );
     }
 }
@@ -2,6 +2,7 @@ mod anthropic;
 mod cloud;
 #[cfg(test)]
 mod fake;
+mod google;
 mod ollama;
 mod open_ai;
 
@@ -9,16 +10,17 @@ pub use anthropic::*;
 pub use cloud::*;
 #[cfg(test)]
 pub use fake::*;
+pub use google::*;
 pub use ollama::*;
 pub use open_ai::*;
 
 use crate::{
     assistant_settings::{AssistantProvider, AssistantSettings},
-    LanguageModel, LanguageModelRequest,
+    LanguageModel, LanguageModelCompletionEvent, LanguageModelRequest,
 };
 use anyhow::Result;
 use client::Client;
-use futures::{future::BoxFuture, stream::BoxStream};
+use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
 use gpui::{AnyView, AppContext, BorrowAppContext, Task, WindowContext};
 use settings::{Settings, SettingsStore};
 use std::sync::Arc;
@@ -79,6 +81,17 @@ pub fn init(client: Arc<Client>, cx: &mut AppContext) {
             settings_version,
             cx,
         )),
+        AssistantProvider::Google {
+            model,
+            api_url,
+            low_speed_timeout_in_seconds,
+        } => CompletionProvider::Google(GoogleCompletionProvider::new(
+            model.clone(),
+            api_url.clone(),
+            client.http_client(),
+            low_speed_timeout_in_seconds.map(Duration::from_secs),
+            settings_version,
+        )),
     };
     cx.set_global(provider);
 
@@ -135,6 +148,22 @@ pub fn init(client: Arc<Client>, cx: &mut AppContext) {
                     );
                 }
 
+                (
+                    CompletionProvider::Google(provider),
+                    AssistantProvider::Google {
+                        model,
+                        api_url,
+                        low_speed_timeout_in_seconds,
+                    },
+                ) => {
+                    provider.update(
+                        model.clone(),
+                        api_url.clone(),
+                        low_speed_timeout_in_seconds.map(Duration::from_secs),
+                        settings_version,
+                    );
+                }
+
                 (CompletionProvider::Cloud(provider), AssistantProvider::ZedDotDev { model }) => {
                     provider.update(model.clone(), settings_version);
                 }
@@ -196,6 +225,22 @@ pub fn init(client: Arc<Client>, cx: &mut AppContext) {
                         cx,
                     ));
                 }
+                (
+                    _,
+                    AssistantProvider::Google {
+                        model,
+                        api_url,
+                        low_speed_timeout_in_seconds,
+                    },
+                ) => {
+                    *provider = CompletionProvider::Google(GoogleCompletionProvider::new(
+                        model.clone(),
+                        api_url.clone(),
+                        client.http_client(),
+                        low_speed_timeout_in_seconds.map(Duration::from_secs),
+                        settings_version,
+                    ));
+                }
             }
         })
     })
@@ -209,6 +254,7 @@ pub enum CompletionProvider {
     #[cfg(test)]
     Fake(FakeCompletionProvider),
     Ollama(OllamaCompletionProvider),
+    Google(GoogleCompletionProvider),
 }
 
 impl gpui::Global for CompletionProvider {}
@@ -236,6 +282,10 @@ impl CompletionProvider {
                 .available_models()
                 .map(|model| LanguageModel::Ollama(model.clone()))
                 .collect(),
+            CompletionProvider::Google(provider) => provider
+                .available_models()
+                .map(LanguageModel::Google)
+                .collect(),
             #[cfg(test)]
             CompletionProvider::Fake(_) => unimplemented!(),
         }
@@ -247,6 +297,7 @@ impl CompletionProvider {
             CompletionProvider::Anthropic(provider) => provider.settings_version(),
             CompletionProvider::Cloud(provider) => provider.settings_version(),
             CompletionProvider::Ollama(provider) => provider.settings_version(),
+            CompletionProvider::Google(provider) => provider.settings_version(),
             #[cfg(test)]
             CompletionProvider::Fake(_) => unimplemented!(),
         }
@@ -258,6 +309,7 @@ impl CompletionProvider {
             CompletionProvider::Anthropic(provider) => provider.is_authenticated(),
             CompletionProvider::Cloud(provider) => provider.is_authenticated(),
             CompletionProvider::Ollama(provider) => provider.is_authenticated(),
+            CompletionProvider::Google(provider) => provider.is_authenticated(),
             #[cfg(test)]
             CompletionProvider::Fake(_) => true,
         }
@@ -269,6 +321,7 @@ impl CompletionProvider {
             CompletionProvider::Anthropic(provider) => provider.authenticate(cx),
             CompletionProvider::Cloud(provider) => provider.authenticate(cx),
             CompletionProvider::Ollama(provider) => provider.authenticate(cx),
+            CompletionProvider::Google(provider) => provider.authenticate(cx),
             #[cfg(test)]
             CompletionProvider::Fake(_) => Task::ready(Ok(())),
         }
@@ -280,6 +333,7 @@ impl CompletionProvider {
             CompletionProvider::Anthropic(provider) => provider.authentication_prompt(cx),
             CompletionProvider::Cloud(provider) => provider.authentication_prompt(cx),
             CompletionProvider::Ollama(provider) => provider.authentication_prompt(cx),
+            CompletionProvider::Google(provider) => provider.authentication_prompt(cx),
             #[cfg(test)]
             CompletionProvider::Fake(_) => unimplemented!(),
         }
@@ -291,6 +345,7 @@ impl CompletionProvider {
             CompletionProvider::Anthropic(provider) => provider.reset_credentials(cx),
             CompletionProvider::Cloud(_) => Task::ready(Ok(())),
             CompletionProvider::Ollama(provider) => provider.reset_credentials(cx),
+            CompletionProvider::Google(provider) => provider.reset_credentials(cx),
             #[cfg(test)]
             CompletionProvider::Fake(_) => Task::ready(Ok(())),
         }
@@ -302,6 +357,7 @@ impl CompletionProvider {
             CompletionProvider::Anthropic(provider) => LanguageModel::Anthropic(provider.model()),
             CompletionProvider::Cloud(provider) => LanguageModel::Cloud(provider.model()),
             CompletionProvider::Ollama(provider) => LanguageModel::Ollama(provider.model()),
+            CompletionProvider::Google(provider) => LanguageModel::Google(provider.model()),
             #[cfg(test)]
             CompletionProvider::Fake(_) => LanguageModel::default(),
         }
@@ -317,6 +373,7 @@ impl CompletionProvider {
             CompletionProvider::Anthropic(provider) => provider.count_tokens(request, cx),
             CompletionProvider::Cloud(provider) => provider.count_tokens(request, cx),
             CompletionProvider::Ollama(provider) => provider.count_tokens(request, cx),
+            CompletionProvider::Google(provider) => provider.count_tokens(request, cx),
             #[cfg(test)]
             CompletionProvider::Fake(_) => futures::FutureExt::boxed(futures::future::ready(Ok(0))),
         }
@@ -326,13 +383,48 @@ impl CompletionProvider {
         &self,
         request: LanguageModelRequest,
     ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+        let events = self.stream_completion(request);
+        async move {
+            Ok(events
+                .await?
+                .filter_map(|event| async move {
+                    match event {
+                        Ok(LanguageModelCompletionEvent::Text(text)) => Some(Ok(text)),
+                        Ok(LanguageModelCompletionEvent::ToolCallDelta(_)) => None,
+                        Err(error) => Some(Err(error)),
+                    }
+                })
+                .boxed())
+        }
+        .boxed()
+    }
+
+    /// Streams a completion, including any calls the model makes to the tools in the request.
+    pub fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
         match self {
-            CompletionProvider::OpenAi(provider) => provider.complete(request),
-            CompletionProvider::Anthropic(provider) => provider.complete(request),
-            CompletionProvider::Cloud(provider) => provider.complete(request),
-            CompletionProvider::Ollama(provider) => provider.complete(request),
+            CompletionProvider::OpenAi(provider) => provider.stream_completion(request),
+            CompletionProvider::Anthropic(provider) => provider.stream_completion(request),
+            CompletionProvider::Ollama(provider) => provider.stream_completion(request),
+            CompletionProvider::Google(provider) => provider.stream_completion(request),
+            CompletionProvider::Cloud(provider) => text_completion_events(provider.complete(request)),
             #[cfg(test)]
-            CompletionProvider::Fake(provider) => provider.complete(),
+            CompletionProvider::Fake(provider) => text_completion_events(provider.complete()),
         }
     }
 }
+
+/// Adapts a provider that only streams text, and therefore never calls tools.
+fn text_completion_events(
+    completion: BoxFuture<'static, Result<BoxStream<'static, Result<String>>>>,
+) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
+    async move {
+        Ok(completion
+            .await?
+            .map(|text| text.map(LanguageModelCompletionEvent::Text))
+            .boxed())
+    }
+    .boxed()
+}
@@ -1,12 +1,17 @@
 use crate::{
-    assistant_settings::AnthropicModel, CompletionProvider, LanguageModel, LanguageModelRequest,
-    Role,
+    assistant_settings::AnthropicModel, CompletionProvider, LanguageModel,
+    LanguageModelCompletionEvent, LanguageModelRequest, LanguageModelToolCallDelta, Role,
 };
 use crate::{count_open_ai_tokens, LanguageModelRequestMessage};
-use anthropic::{stream_completion, Request, RequestMessage};
+use anthropic::{stream_completion, Request, RequestContent, RequestMessage};
 use anyhow::{anyhow, Result};
+use collections::HashMap;
 use editor::{Editor, EditorElement, EditorStyle};
-use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
+use futures::{
+    future::{self, BoxFuture},
+    stream::BoxStream,
+    FutureExt, StreamExt,
+};
 use gpui::{AnyView, AppContext, FontStyle, Task, TextStyle, View, WhiteSpace};
 use http::HttpClient;
 use settings::Settings;
@@ -122,10 +127,10 @@ impl AnthropicCompletionProvider {
         count_open_ai_tokens(request, cx.background_executor())
     }
 
-    pub fn complete(
+    pub fn stream_completion(
         &self,
         request: LanguageModelRequest,
-    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
         let request = self.to_anthropic_request(request);
 
         let http_client = self.http_client.clone();
@@ -142,24 +147,56 @@ impl AnthropicCompletionProvider {
                 low_speed_timeout,
             );
             let response = request.await?;
+
+            // Anthropic numbers tool uses by their content block, which also counts text
+            // blocks, so we renumber them to be contiguous.
+            let mut tool_call_indices = HashMap::default();
             let stream = response
-                .filter_map(|response| async move {
-                    match response {
-                        Ok(response) => match response {
-                            anthropic::ResponseEvent::ContentBlockStart {
-                                content_block, ..
-                            } => match content_block {
-                                anthropic::ContentBlock::Text { text } => Some(Ok(text)),
-                            },
-                            anthropic::ResponseEvent::ContentBlockDelta { delta, .. } => {
-                                match delta {
-                                    anthropic::TextDelta::TextDelta { text } => Some(Ok(text)),
-                                }
+                .filter_map(move |response| {
+                    let event = match response {
+                        Ok(anthropic::ResponseEvent::ContentBlockStart {
+                            index,
+                            content_block,
+                        }) => match content_block {
+                            anthropic::ContentBlock::Text { text } => {
+                                Some(Ok(LanguageModelCompletionEvent::Text(text)))
+                            }
+                            anthropic::ContentBlock::ToolUse { id, name, .. } => {
+                                let tool_call_index = tool_call_indices.len();
+                                tool_call_indices.insert(index, tool_call_index);
+                                Some(Ok(LanguageModelCompletionEvent::ToolCallDelta(
+                                    LanguageModelToolCallDelta {
+                                        index: tool_call_index,
+                                        id: Some(id),
+                                        name: Some(name),
+                                        arguments: None,
+                                    },
+                                )))
                             }
-                            _ => None,
                         },
+                        Ok(anthropic::ResponseEvent::ContentBlockDelta { index, delta }) => {
+                            match delta {
+                                anthropic::TextDelta::TextDelta { text } => {
+                                    Some(Ok(LanguageModelCompletionEvent::Text(text)))
+                                }
+                                anthropic::TextDelta::InputJsonDelta { partial_json } => {
+                                    tool_call_indices.get(&index).map(|tool_call_index| {
+                                        Ok(LanguageModelCompletionEvent::ToolCallDelta(
+                                            LanguageModelToolCallDelta {
+                                                index: *tool_call_index,
+                                                id: None,
+                                                name: None,
+                                                arguments: Some(partial_json),
+                                            },
+                                        ))
+                                    })
+                                }
+                            }
+                        }
+                        Ok(_) => None,
                         Err(error) => Some(Err(error)),
-                    }
+                    };
+                    future::ready(event)
                 })
                 .boxed();
             Ok(stream)
@@ -184,25 +221,81 @@ impl AnthropicCompletionProvider {
             system_message = request.messages.remove(0).content;
         }
 
+        let mut messages: Vec<RequestMessage> = Vec::new();
+        for message in request.messages {
+            let role = match message.role {
+                Role::User => anthropic::Role::User,
+                Role::Assistant => anthropic::Role::Assistant,
+                Role::System => unreachable!("filtered out by preprocess_request"),
+            };
+
+            let mut content = Vec::new();
+            if !message.content.is_empty() {
+                content.push(RequestContent::Text {
+                    text: message.content,
+                });
+            }
+            content.extend(message.tool_calls.into_iter().map(|tool_call| {
+                RequestContent::ToolUse {
+                    id: tool_call.id,
+                    input: serde_json::from_str(&tool_call.arguments)
+                        .unwrap_or_else(|_| serde_json::Value::Object(Default::default())),
+                    name: tool_call.name,
+                }
+            }));
+            push_anthropic_message(&mut messages, role, content);
+
+            // Anthropic expects tool results to be reported back by the user.
+            let tool_results = message
+                .tool_results
+                .into_iter()
+                .map(|tool_result| RequestContent::ToolResult {
+                    tool_use_id: tool_result.tool_call_id,
+                    content: tool_result.content,
+                })
+                .collect();
+            push_anthropic_message(&mut messages, anthropic::Role::User, tool_results);
+        }
+
         Request {
             model,
-            messages: request
-                .messages
-                .iter()
-                .map(|msg| RequestMessage {
-                    role: match msg.role {
-                        Role::User => anthropic::Role::User,
-                        Role::Assistant => anthropic::Role::Assistant,
-                        Role::System => unreachable!("filtered out by preprocess_request"),
-                    },
-                    content: msg.content.clone(),
-                })
-                .collect(),
+            messages,
             stream: true,
             system: system_message,
             max_tokens: 4092,
+            tools: request
+                .tools
+                .into_iter()
+                .map(|tool| anthropic::Tool {
+                    name: tool.name,
+                    description: tool.description,
+                    input_schema: serde_json::to_value(tool.parameters)
+                        .unwrap_or_else(|_| serde_json::Value::Object(Default::default())),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Appends content to the conversation, merging it into the previous message when the roles
+/// match because Anthropic requires user and assistant messages to alternate.
+fn push_anthropic_message(
+    messages: &mut Vec<RequestMessage>,
+    role: anthropic::Role,
+    content: Vec<RequestContent>,
+) {
+    if content.is_empty() {
+        return;
+    }
+
+    if let Some(last_message) = messages.last_mut() {
+        if last_message.role == role {
+            last_message.content.extend(content);
+            return;
         }
     }
+
+    messages.push(RequestMessage { role, content });
 }
 
 pub fn preprocess_anthropic_request(request: &mut LanguageModelRequest) {
@@ -210,16 +303,25 @@ pub fn preprocess_anthropic_request(request: &mut LanguageModelRequest) {
     let mut system_message = String::new();
 
     for message in request.messages.drain(..) {
-        if message.content.is_empty() {
+        if message.content.is_empty()
+            && message.tool_calls.is_empty()
+            && message.tool_results.is_empty()
+        {
             continue;
         }
 
         match message.role {
             Role::User | Role::Assistant => {
                 if let Some(last_message) = new_messages.last_mut() {
-                    if last_message.role == message.role {
-                        last_message.content.push_str("\n\n");
-                        last_message.content.push_str(&message.content);
+                    if last_message.role == message.role && last_message.tool_results.is_empty() {
+                        if !message.content.is_empty() {
+                            if !last_message.content.is_empty() {
+                                last_message.content.push_str("\n\n");
+                            }
+                            last_message.content.push_str(&message.content);
+                        }
+                        last_message.tool_calls.extend(message.tool_calls);
+                        last_message.tool_results.extend(message.tool_results);
                         continue;
                     }
                 }
@@ -241,6 +343,8 @@ pub fn preprocess_anthropic_request(request: &mut LanguageModelRequest) {
             LanguageModelRequestMessage {
                 role: Role::System,
                 content: system_message,
+                tool_calls: Vec::new(),
+                tool_results: Vec::new(),
             },
         );
     }
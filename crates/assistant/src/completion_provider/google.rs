@@ -0,0 +1,520 @@
+use crate::{
+    assistant_settings::GoogleModel, CompletionProvider, LanguageModel,
+    LanguageModelCompletionEvent, LanguageModelRequest, LanguageModelRequestMessage, Role,
+};
+use anyhow::{anyhow, Result};
+use editor::{Editor, EditorElement, EditorStyle};
+use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
+use google_ai::{
+    count_tokens, list_models, stream_generate_content, Content, CountTokensRequest,
+    GenerateContentRequest, GenerationConfig, Part, TextPart,
+};
+use gpui::{AnyView, AppContext, FontStyle, Task, TextStyle, View, WhiteSpace};
+use http::HttpClient;
+use settings::Settings;
+use std::time::Duration;
+use std::{env, sync::Arc};
+use theme::ThemeSettings;
+use ui::prelude::*;
+use util::ResultExt;
+
+pub struct GoogleCompletionProvider {
+    api_key: Option<String>,
+    api_url: String,
+    model: GoogleModel,
+    http_client: Arc<dyn HttpClient>,
+    low_speed_timeout: Option<Duration>,
+    settings_version: usize,
+    available_models: Vec<GoogleModel>,
+}
+
+impl GoogleCompletionProvider {
+    pub fn new(
+        model: GoogleModel,
+        api_url: String,
+        http_client: Arc<dyn HttpClient>,
+        low_speed_timeout: Option<Duration>,
+        settings_version: usize,
+    ) -> Self {
+        Self {
+            api_key: None,
+            api_url,
+            model,
+            http_client,
+            low_speed_timeout,
+            settings_version,
+            available_models: Vec::new(),
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        model: GoogleModel,
+        api_url: String,
+        low_speed_timeout: Option<Duration>,
+        settings_version: usize,
+    ) {
+        self.model = model;
+        self.api_url = api_url;
+        self.low_speed_timeout = low_speed_timeout;
+        self.settings_version = settings_version;
+    }
+
+    pub fn available_models(&self) -> impl Iterator<Item = GoogleModel> {
+        if self.available_models.is_empty() {
+            vec![self.model.clone()].into_iter()
+        } else {
+            self.available_models.clone().into_iter()
+        }
+    }
+
+    pub fn settings_version(&self) -> usize {
+        self.settings_version
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        self.api_key.is_some()
+    }
+
+    pub fn authenticate(&self, cx: &AppContext) -> Task<Result<()>> {
+        if self.is_authenticated() {
+            Task::ready(Ok(()))
+        } else {
+            let api_url = self.api_url.clone();
+            cx.spawn(|mut cx| async move {
+                let api_key = if let Ok(api_key) = env::var("GOOGLE_AI_API_KEY") {
+                    api_key
+                } else {
+                    let (_, api_key) = cx
+                        .update(|cx| cx.read_credentials(&api_url))?
+                        .await?
+                        .ok_or_else(|| anyhow!("credentials not found"))?;
+                    String::from_utf8(api_key)?
+                };
+                let fetch_models = cx.update_global::<CompletionProvider, _>(|provider, cx| {
+                    if let CompletionProvider::Google(provider) = provider {
+                        provider.api_key = Some(api_key);
+                        provider.fetch_models(cx)
+                    } else {
+                        Task::ready(Ok(()))
+                    }
+                })?;
+                // The configured model can still be used if the models can't be listed.
+                fetch_models.await.log_err();
+                Ok(())
+            })
+        }
+    }
+
+    pub fn reset_credentials(&self, cx: &AppContext) -> Task<Result<()>> {
+        let delete_credentials = cx.delete_credentials(&self.api_url);
+        cx.spawn(|mut cx| async move {
+            delete_credentials.await.log_err();
+            cx.update_global::<CompletionProvider, _>(|provider, _cx| {
+                if let CompletionProvider::Google(provider) = provider {
+                    provider.api_key = None;
+                    provider.available_models.clear();
+                }
+            })
+        })
+    }
+
+    pub fn fetch_models(&self, cx: &AppContext) -> Task<Result<()>> {
+        let Some(api_key) = self.api_key.clone() else {
+            return Task::ready(Err(anyhow!("missing api key")));
+        };
+        let http_client = self.http_client.clone();
+        let api_url = self.api_url.clone();
+
+        cx.spawn(|mut cx| async move {
+            let models = list_models(http_client.as_ref(), &api_url, &api_key).await?;
+            let mut models: Vec<GoogleModel> = models
+                .into_iter()
+                .map(|model| {
+                    let mut google_model = GoogleModel::new(model.id());
+                    if let Some(input_token_limit) = model.input_token_limit {
+                        google_model.max_tokens = input_token_limit;
+                    }
+                    google_model
+                })
+                .collect();
+            models.sort_by(|a, b| a.name.cmp(&b.name));
+
+            cx.update_global::<CompletionProvider, _>(|provider, _cx| {
+                if let CompletionProvider::Google(provider) = provider {
+                    provider.available_models = models;
+                }
+            })
+        })
+    }
+
+    pub fn authentication_prompt(&self, cx: &mut WindowContext) -> AnyView {
+        cx.new_view(|cx| AuthenticationPrompt::new(self.api_url.clone(), cx))
+            .into()
+    }
+
+    pub fn model(&self) -> GoogleModel {
+        self.model.clone()
+    }
+
+    pub fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        _cx: &AppContext,
+    ) -> BoxFuture<'static, Result<usize>> {
+        let model = match &request.model {
+            LanguageModel::Google(model) => model.clone(),
+            _ => self.model(),
+        };
+        let request = count_tokens_request_to_google_ai(request);
+
+        let http_client = self.http_client.clone();
+        let api_key = self.api_key.clone();
+        let api_url = self.api_url.clone();
+        async move {
+            let api_key = api_key.ok_or_else(|| anyhow!("missing api key"))?;
+            let response = count_tokens(
+                http_client.as_ref(),
+                &api_url,
+                &api_key,
+                model.id(),
+                request,
+            )
+            .await?;
+            Ok(response.total_tokens)
+        }
+        .boxed()
+    }
+
+    pub fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
+        let model = match &request.model {
+            LanguageModel::Google(model) => model.clone(),
+            _ => self.model(),
+        };
+        let request = self.to_google_ai_request(request);
+
+        let http_client = self.http_client.clone();
+        let api_key = self.api_key.clone();
+        let api_url = self.api_url.clone();
+        let low_speed_timeout = self.low_speed_timeout;
+        async move {
+            let api_key = api_key.ok_or_else(|| anyhow!("missing api key"))?;
+            let response = stream_generate_content(
+                http_client,
+                &api_url,
+                &api_key,
+                model.id(),
+                request,
+                low_speed_timeout,
+            )
+            .await?;
+            let stream = response
+                .filter_map(|response| async move {
+                    match response {
+                        Ok(response) => {
+                            let text = response
+                                .candidates
+                                .into_iter()
+                                .flatten()
+                                .flat_map(|candidate| candidate.content.parts)
+                                .filter_map(|part| match part {
+                                    Part::TextPart(part) => Some(part.text),
+                                    Part::InlineDataPart(_) => None,
+                                })
+                                .collect::<String>();
+                            if text.is_empty() {
+                                None
+                            } else {
+                                Some(Ok(LanguageModelCompletionEvent::Text(text)))
+                            }
+                        }
+                        Err(error) => Some(Err(error)),
+                    }
+                })
+                .boxed();
+            Ok(stream)
+        }
+        .boxed()
+    }
+
+    fn to_google_ai_request(&self, request: LanguageModelRequest) -> GenerateContentRequest {
+        GenerateContentRequest {
+            contents: google_ai_contents(request.messages),
+            generation_config: Some(GenerationConfig {
+                candidate_count: Some(1),
+                stop_sequences: (!request.stop.is_empty()).then_some(request.stop),
+                max_output_tokens: None,
+                temperature: Some(request.temperature as f64),
+                top_p: None,
+                top_k: None,
+            }),
+            safety_settings: None,
+        }
+    }
+}
+
+pub fn count_tokens_request_to_google_ai(request: LanguageModelRequest) -> CountTokensRequest {
+    CountTokensRequest {
+        contents: google_ai_contents(request.messages),
+    }
+}
+
+/// Converts messages to Google AI contents, which only distinguish between the user and the
+/// model. System messages are sent by the user, and consecutive messages from the same role
+/// are merged because Google AI expects the roles to alternate.
+fn google_ai_contents(messages: Vec<LanguageModelRequestMessage>) -> Vec<Content> {
+    let mut contents: Vec<Content> = Vec::new();
+    for message in messages {
+        if message.content.is_empty() {
+            continue;
+        }
+
+        let role = match message.role {
+            Role::User | Role::System => google_ai::Role::User,
+            Role::Assistant => google_ai::Role::Model,
+        };
+        let part = Part::TextPart(TextPart {
+            text: message.content,
+        });
+        if let Some(last_content) = contents.last_mut() {
+            if matches!(
+                (&last_content.role, &role),
+                (google_ai::Role::User, google_ai::Role::User)
+                    | (google_ai::Role::Model, google_ai::Role::Model)
+            ) {
+                last_content.parts.push(part);
+                continue;
+            }
+        }
+        contents.push(Content {
+            parts: vec![part],
+            role,
+        });
+    }
+    contents
+}
+
+struct AuthenticationPrompt {
+    api_key: View<Editor>,
+    api_url: String,
+}
+
+impl AuthenticationPrompt {
+    fn new(api_url: String, cx: &mut WindowContext) -> Self {
+        Self {
+            api_key: cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("AIzaSy0000000000000000000000000000000000", cx);
+                editor
+            }),
+            api_url,
+        }
+    }
+
+    fn save_api_key(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let api_key = self.api_key.read(cx).text(cx);
+        if api_key.is_empty() {
+            return;
+        }
+
+        let write_credentials = cx.write_credentials(&self.api_url, "Bearer", api_key.as_bytes());
+        cx.spawn(|_, mut cx| async move {
+            write_credentials.await?;
+            let fetch_models = cx.update_global::<CompletionProvider, _>(|provider, cx| {
+                if let CompletionProvider::Google(provider) = provider {
+                    provider.api_key = Some(api_key);
+                    provider.fetch_models(cx)
+                } else {
+                    Task::ready(Ok(()))
+                }
+            })?;
+            fetch_models.await.log_err();
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_api_key_editor(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.ui_font.family.clone(),
+            font_features: settings.ui_font.features.clone(),
+            font_size: rems(0.875).into(),
+            font_weight: settings.ui_font.weight,
+            font_style: FontStyle::Normal,
+            line_height: relative(1.3),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+            white_space: WhiteSpace::Normal,
+        };
+        EditorElement::new(
+            &self.api_key,
+            EditorStyle {
+                background: cx.theme().colors().editor_background,
+                local_player: cx.theme().players().local(),
+                text: text_style,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+impl Render for AuthenticationPrompt {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        const INSTRUCTIONS: [&str; 4] = [
+            "To use the assistant panel or inline assistant, you need to add your Google AI API key.",
+            "You can create an API key at: https://aistudio.google.com/app/apikey",
+            "",
+            "Paste your Google AI API key below and hit enter to use the assistant:",
+        ];
+
+        v_flex()
+            .p_4()
+            .size_full()
+            .on_action(cx.listener(Self::save_api_key))
+            .children(
+                INSTRUCTIONS.map(|instruction| Label::new(instruction).size(LabelSize::Small)),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .my_2()
+                    .px_2()
+                    .py_1()
+                    .bg(cx.theme().colors().editor_background)
+                    .rounded_md()
+                    .child(self.render_api_key_editor(cx)),
+            )
+            .child(
+                Label::new(
+                    "You can also assign the GOOGLE_AI_API_KEY environment variable and restart Zed.",
+                )
+                .size(LabelSize::Small),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new("Click on").size(LabelSize::Small))
+                    .child(Icon::new(IconName::ZedAssistant).size(IconSize::XSmall))
+                    .child(
+                        Label::new("in the status bar to close this panel.").size(LabelSize::Small),
+                    ),
+            )
+            .into_any()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::AsyncReadExt;
+    use gpui::TestAppContext;
+    use http::{FakeHttpClient, Response};
+    use parking_lot::Mutex;
+
+    #[gpui::test]
+    async fn test_google_completion_provider(cx: &mut TestAppContext) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let requests = requests.clone();
+            move |mut request| {
+                let requests = requests.clone();
+                async move {
+                    let mut body = String::new();
+                    request.body_mut().read_to_string(&mut body).await?;
+                    let uri = request.uri().to_string();
+                    requests.lock().push((uri.clone(), body));
+
+                    if uri.contains(":streamGenerateContent") {
+                        Ok(Response::new(
+                            concat!(
+                                "data: {\"candidates\": [{\"index\": 0, \"content\": {\"role\": \"model\", \"parts\": [{\"text\": \"Hello\"}]}}]}\n\n",
+                                "data: {\"candidates\": [{\"index\": 0, \"content\": {\"role\": \"model\", \"parts\": [{\"text\": \", world\"}]}}]}\n\n",
+                            )
+                            .into(),
+                        ))
+                    } else if uri.contains(":countTokens") {
+                        Ok(Response::new("{\"totalTokens\": 42}".into()))
+                    } else {
+                        Ok(Response::builder().status(404).body("not found".into())?)
+                    }
+                }
+            }
+        });
+
+        let mut provider = GoogleCompletionProvider::new(
+            GoogleModel::new("gemini-1.5-flash"),
+            "http://localhost".into(),
+            http_client,
+            None,
+            0,
+        );
+        provider.api_key = Some("the-api-key".into());
+
+        let request = || LanguageModelRequest {
+            model: LanguageModel::Google(GoogleModel::new("gemini-1.5-flash")),
+            messages: vec![
+                LanguageModelRequestMessage {
+                    role: Role::System,
+                    content: "Be brief.".into(),
+                    tool_calls: Vec::new(),
+                    tool_results: Vec::new(),
+                },
+                LanguageModelRequestMessage {
+                    role: Role::User,
+                    content: "Say hello".into(),
+                    tool_calls: Vec::new(),
+                    tool_results: Vec::new(),
+                },
+            ],
+            stop: Vec::new(),
+            temperature: 1.0,
+            tools: Vec::new(),
+        };
+
+        let events = provider
+            .stream_completion(request())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        let text = events
+            .into_iter()
+            .map(|event| match event.unwrap() {
+                LanguageModelCompletionEvent::Text(text) => text,
+                LanguageModelCompletionEvent::ToolCallDelta(_) => unreachable!(),
+            })
+            .collect::<String>();
+        assert_eq!(text, "Hello, world");
+
+        let token_count = cx
+            .update(|cx| provider.count_tokens(request(), cx))
+            .await
+            .unwrap();
+        assert_eq!(token_count, 42);
+
+        let requests = requests.lock();
+        assert_eq!(
+            requests[0].0,
+            "http://localhost/v1beta/models/gemini-1.5-flash:streamGenerateContent?alt=sse&key=the-api-key"
+        );
+        // The system prompt and the user's message are merged into a single user turn.
+        let body: serde_json::Value = serde_json::from_str(&requests[0].1).unwrap();
+        assert_eq!(
+            body["contents"],
+            serde_json::json!([{
+                "role": "user",
+                "parts": [{"text": "Be brief."}, {"text": "Say hello"}]
+            }])
+        );
+        assert_eq!(
+            requests[1].0,
+            "http://localhost/v1beta/models/gemini-1.5-flash:countTokens?key=the-api-key"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
-    assistant_settings::OllamaModel, CompletionProvider, LanguageModel, LanguageModelRequest, Role,
+    assistant_settings::OllamaModel, CompletionProvider, LanguageModel,
+    LanguageModelCompletionEvent, LanguageModelRequest, LanguageModelToolCallDelta, Role,
 };
 use anyhow::Result;
 use futures::StreamExt as _;
@@ -8,11 +9,13 @@ use gpui::{AnyView, AppContext, Task};
 use http::HttpClient;
 use ollama::{
     get_models, preload_model, stream_chat_completion, ChatMessage, ChatOptions, ChatRequest,
-    Role as OllamaRole,
+    OllamaFunctionCall, OllamaFunctionTool, OllamaTool, OllamaToolCall, Role as OllamaRole,
 };
 use std::sync::Arc;
 use std::time::Duration;
 use ui::{prelude::*, ButtonLike, ElevationIndex};
+use util::post_inc;
+use uuid::Uuid;
 
 const OLLAMA_DOWNLOAD_URL: &str = "https://ollama.com/download";
 const OLLAMA_LIBRARY_URL: &str = "https://ollama.com/library";
@@ -184,10 +187,10 @@ impl OllamaCompletionProvider {
         async move { Ok(token_count) }.boxed()
     }
 
-    pub fn complete(
+    pub fn stream_completion(
         &self,
         request: LanguageModelRequest,
-    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
         let request = self.to_ollama_request(request);
 
         let http_client = self.http_client.clone();
@@ -197,19 +200,41 @@ impl OllamaCompletionProvider {
             let request =
                 stream_chat_completion(http_client.as_ref(), &api_url, request, low_speed_timeout);
             let response = request.await?;
+
+            // Ollama sends each tool call whole and without an id, so we number them ourselves.
+            let mut next_tool_call_index = 0;
             let stream = response
-                .filter_map(|response| async move {
+                .flat_map(move |response| {
+                    let mut events = Vec::new();
                     match response {
                         Ok(delta) => {
-                            let content = match delta.message {
-                                ChatMessage::User { content } => content,
-                                ChatMessage::Assistant { content } => content,
-                                ChatMessage::System { content } => content,
+                            let (content, tool_calls) = match delta.message {
+                                ChatMessage::User { content } => (content, Vec::new()),
+                                ChatMessage::Assistant {
+                                    content,
+                                    tool_calls,
+                                } => (content, tool_calls),
+                                ChatMessage::System { content } => (content, Vec::new()),
+                                ChatMessage::Tool { content } => (content, Vec::new()),
                             };
-                            Some(Ok(content))
+                            if !content.is_empty() || tool_calls.is_empty() {
+                                events.push(Ok(LanguageModelCompletionEvent::Text(content)));
+                            }
+                            for tool_call in tool_calls {
+                                let OllamaToolCall::Function(function) = tool_call;
+                                events.push(Ok(LanguageModelCompletionEvent::ToolCallDelta(
+                                    LanguageModelToolCallDelta {
+                                        index: post_inc(&mut next_tool_call_index),
+                                        id: Some(Uuid::new_v4().to_string()),
+                                        name: Some(function.name),
+                                        arguments: Some(function.arguments.to_string()),
+                                    },
+                                )));
+                            }
                         }
-                        Err(error) => Some(Err(error)),
+                        Err(error) => events.push(Err(error)),
                     }
+                    futures::stream::iter(events)
                 })
                 .boxed();
             Ok(stream)
@@ -223,23 +248,44 @@ impl OllamaCompletionProvider {
             _ => self.model(),
         };
 
+        let mut messages = Vec::new();
+        for msg in request.messages {
+            match msg.role {
+                Role::User => messages.push(ChatMessage::User {
+                    content: msg.content,
+                }),
+                Role::Assistant => {
+                    messages.push(ChatMessage::Assistant {
+                        content: msg.content,
+                        tool_calls: msg
+                            .tool_calls
+                            .into_iter()
+                            .map(|tool_call| {
+                                OllamaToolCall::Function(OllamaFunctionCall {
+                                    name: tool_call.name,
+                                    arguments: serde_json::from_str(&tool_call.arguments)
+                                        .unwrap_or_else(|_| {
+                                            serde_json::Value::Object(Default::default())
+                                        }),
+                                })
+                            })
+                            .collect(),
+                    });
+                    messages.extend(msg.tool_results.into_iter().map(|tool_result| {
+                        ChatMessage::Tool {
+                            content: tool_result.content,
+                        }
+                    }));
+                }
+                Role::System => messages.push(ChatMessage::System {
+                    content: msg.content,
+                }),
+            }
+        }
+
         ChatRequest {
             model: model.name,
-            messages: request
-                .messages
-                .into_iter()
-                .map(|msg| match msg.role {
-                    Role::User => ChatMessage::User {
-                        content: msg.content,
-                    },
-                    Role::Assistant => ChatMessage::Assistant {
-                        content: msg.content,
-                    },
-                    Role::System => ChatMessage::System {
-                        content: msg.content,
-                    },
-                })
-                .collect(),
+            messages,
             keep_alive: model.keep_alive.unwrap_or_default(),
             stream: true,
             options: Some(ChatOptions {
@@ -248,6 +294,17 @@ impl OllamaCompletionProvider {
                 temperature: Some(request.temperature),
                 ..Default::default()
             }),
+            tools: request
+                .tools
+                .into_iter()
+                .map(|tool| OllamaTool::Function {
+                    function: OllamaFunctionTool {
+                        name: tool.name,
+                        description: Some(tool.description),
+                        parameters: serde_json::to_value(tool.parameters).ok(),
+                    },
+                })
+                .collect(),
         }
     }
 }
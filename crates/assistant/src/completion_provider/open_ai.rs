@@ -1,14 +1,18 @@
 use crate::assistant_settings::CloudModel;
 use crate::assistant_settings::{AssistantProvider, AssistantSettings};
 use crate::{
-    assistant_settings::OpenAiModel, CompletionProvider, LanguageModel, LanguageModelRequest, Role,
+    assistant_settings::OpenAiModel, CompletionProvider, LanguageModel,
+    LanguageModelCompletionEvent, LanguageModelRequest, LanguageModelToolCallDelta, Role,
 };
 use anyhow::{anyhow, Result};
 use editor::{Editor, EditorElement, EditorStyle};
 use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
 use gpui::{AnyView, AppContext, FontStyle, Task, TextStyle, View, WhiteSpace};
 use http::HttpClient;
-use open_ai::{stream_completion, Request, RequestMessage, Role as OpenAiRole};
+use open_ai::{
+    stream_completion, FunctionContent, FunctionDefinition, Request, RequestMessage,
+    Role as OpenAiRole, ToolCall, ToolCallContent, ToolDefinition,
+};
 use settings::Settings;
 use std::time::Duration;
 use std::{env, sync::Arc};
@@ -140,10 +144,10 @@ impl OpenAiCompletionProvider {
         count_open_ai_tokens(request, cx.background_executor())
     }
 
-    pub fn complete(
+    pub fn stream_completion(
         &self,
         request: LanguageModelRequest,
-    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
         let request = self.to_open_ai_request(request);
 
         let http_client = self.http_client.clone();
@@ -161,11 +165,33 @@ impl OpenAiCompletionProvider {
             );
             let response = request.await?;
             let stream = response
-                .filter_map(|response| async move {
+                .flat_map(|response| {
+                    let mut events = Vec::new();
                     match response {
-                        Ok(mut response) => Some(Ok(response.choices.pop()?.delta.content?)),
-                        Err(error) => Some(Err(error)),
+                        Ok(mut response) => {
+                            if let Some(choice) = response.choices.pop() {
+                                if let Some(content) = choice.delta.content {
+                                    events.push(Ok(LanguageModelCompletionEvent::Text(content)));
+                                }
+                                for tool_call in choice.delta.tool_calls {
+                                    let (name, arguments) =
+                                        tool_call.function.map_or((None, None), |function| {
+                                            (function.name, function.arguments)
+                                        });
+                                    events.push(Ok(LanguageModelCompletionEvent::ToolCallDelta(
+                                        LanguageModelToolCallDelta {
+                                            index: tool_call.index,
+                                            id: tool_call.id,
+                                            name,
+                                            arguments,
+                                        },
+                                    )));
+                                }
+                            }
+                        }
+                        Err(error) => events.push(Err(error)),
                     }
+                    futures::stream::iter(events)
                 })
                 .boxed();
             Ok(stream)
@@ -179,28 +205,62 @@ impl OpenAiCompletionProvider {
             _ => self.model(),
         };
 
+        let mut messages = Vec::new();
+        for msg in request.messages {
+            match msg.role {
+                Role::User => messages.push(RequestMessage::User {
+                    content: msg.content,
+                }),
+                Role::Assistant => {
+                    messages.push(RequestMessage::Assistant {
+                        content: Some(msg.content),
+                        tool_calls: msg
+                            .tool_calls
+                            .into_iter()
+                            .map(|tool_call| ToolCall {
+                                id: tool_call.id,
+                                content: ToolCallContent::Function {
+                                    function: FunctionContent {
+                                        name: tool_call.name,
+                                        arguments: tool_call.arguments,
+                                    },
+                                },
+                            })
+                            .collect(),
+                    });
+                    messages.extend(msg.tool_results.into_iter().map(|tool_result| {
+                        RequestMessage::Tool {
+                            content: tool_result.content,
+                            tool_call_id: tool_result.tool_call_id,
+                        }
+                    }));
+                }
+                Role::System => messages.push(RequestMessage::System {
+                    content: msg.content,
+                }),
+            }
+        }
+
         Request {
             model,
-            messages: request
-                .messages
+            messages,
+            stream: true,
+            stop: request.stop,
+            temperature: request.temperature,
+            tools: request
+                .tools
                 .into_iter()
-                .map(|msg| match msg.role {
-                    Role::User => RequestMessage::User {
-                        content: msg.content,
-                    },
-                    Role::Assistant => RequestMessage::Assistant {
-                        content: Some(msg.content),
-                        tool_calls: Vec::new(),
-                    },
-                    Role::System => RequestMessage::System {
-                        content: msg.content,
+                .map(|tool| ToolDefinition::Function {
+                    function: FunctionDefinition {
+                        name: tool.name,
+                        description: Some(tool.description),
+                        parameters: match serde_json::to_value(tool.parameters) {
+                            Ok(serde_json::Value::Object(parameters)) => Some(parameters),
+                            _ => None,
+                        },
                     },
                 })
                 .collect(),
-            stream: true,
-            stop: request.stop,
-            temperature: request.temperature,
-            tools: Vec::new(),
             tool_choice: None,
         }
     }
@@ -844,6 +844,8 @@ impl InlineAssistant {
             messages.push(LanguageModelRequestMessage {
                 role: Role::User,
                 content: prompt,
+                tool_calls: Vec::new(),
+                tool_results: Vec::new(),
             });
 
             Ok(LanguageModelRequest {
@@ -851,6 +853,7 @@ impl InlineAssistant {
                 messages,
                 stop: vec!["|END|>".to_string()],
                 temperature,
+                tools: Vec::new(),
             })
         })
     }
@@ -743,9 +743,12 @@ impl PromptLibrary {
                                     messages: vec![LanguageModelRequestMessage {
                                         role: Role::System,
                                         content: body.to_string(),
+                                        tool_calls: Vec::new(),
+                                        tool_results: Vec::new(),
                                     }],
                                     stop: Vec::new(),
                                     temperature: 1.,
+                                    tools: Vec::new(),
                                 },
                                 cx,
                             )
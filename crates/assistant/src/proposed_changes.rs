@@ -0,0 +1,473 @@
+use crate::{AcceptProposedHunk, ApplyProposedChanges, RejectProposedHunk};
+use collections::HashSet;
+use editor::{
+    actions::{ExpandAllHunkDiffs, RevertSelectedHunks},
+    Editor, EditorEvent, DEFAULT_MULTIBUFFER_CONTEXT,
+};
+use futures::future;
+use gpui::{
+    AnyElement, AnyView, AppContext, EventEmitter, FocusHandle, FocusableView, Model, Render,
+    SharedString, Subscription, View, WeakView,
+};
+use language::{Buffer, BufferSnapshot, Point};
+use multi_buffer::{MultiBuffer, MultiBufferRow};
+use project::ProjectTransaction;
+use std::{any::TypeId, ops::Range};
+use ui::prelude::*;
+use workspace::{
+    item::{Item, TabContentParams},
+    Workspace,
+};
+
+/// Presents edits proposed by the assistant across several buffers as a
+/// multibuffer of diff hunks that can be accepted or rejected one at a time.
+///
+/// Every original buffer is paired with two scratch buffers: the proposed
+/// buffer, which holds the assistant's version of the text and is the one
+/// displayed, and the accepted buffer, which starts as a copy of the original
+/// and receives each hunk as it is accepted. The accepted text is used as the
+/// diff base of the proposed buffer, so only undecided hunks are displayed.
+pub struct ProposedChangesEditor {
+    editor: View<Editor>,
+    workspace: WeakView<Workspace>,
+    title: SharedString,
+    proposals: Vec<BufferProposal>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct BufferProposal {
+    original: Model<Buffer>,
+    original_snapshot: BufferSnapshot,
+    accepted: Model<Buffer>,
+    accepted_base: BufferSnapshot,
+    proposed: Model<Buffer>,
+}
+
+impl ProposedChangesEditor {
+    /// Creates an editor for the given edits, expressed as offset ranges into
+    /// the current text of each buffer.
+    pub fn new(
+        title: impl Into<SharedString>,
+        edits: Vec<(Model<Buffer>, Vec<(Range<usize>, String)>)>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let title = title.into();
+        let multibuffer = cx.new_model(|_| {
+            MultiBuffer::new(0, language::Capability::ReadWrite).with_title(title.to_string())
+        });
+
+        let mut proposals = Vec::new();
+        let mut subscriptions = Vec::new();
+        for (original, mut buffer_edits) in edits {
+            buffer_edits.sort_by_key(|(range, _)| range.start);
+
+            let original_snapshot = original.read(cx).snapshot();
+            let text = original_snapshot.text();
+            let language = original.read(cx).language().cloned();
+            let accepted = cx.new_model(|cx| Buffer::local(text.clone(), cx));
+            let accepted_base = accepted.read(cx).snapshot();
+            let proposed = cx.new_model(|cx| {
+                let mut buffer = Buffer::local(text.clone(), cx);
+                buffer.set_language(language, cx);
+                buffer
+            });
+
+            let edited_ranges = proposed.update(cx, |buffer, cx| {
+                buffer.start_transaction();
+                buffer.edit(buffer_edits, None, cx);
+                let transaction_id = buffer.end_transaction(cx);
+                buffer.set_diff_base(Some(text), cx);
+                transaction_id
+                    .map(|transaction_id| {
+                        buffer
+                            .edited_ranges_for_transaction_id::<Point>(transaction_id)
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default()
+            });
+            multibuffer.update(cx, |multibuffer, cx| {
+                multibuffer.push_excerpts_with_context_lines(
+                    proposed.clone(),
+                    edited_ranges,
+                    DEFAULT_MULTIBUFFER_CONTEXT,
+                    cx,
+                );
+            });
+
+            // Proposed buffers are not tracked by the project, so their diffs
+            // have to be kept up to date as hunks are rejected or edited by hand.
+            subscriptions.push(cx.subscribe(&proposed, |_, buffer, event, cx| {
+                if let language::Event::Edited = event {
+                    buffer.update(cx, |buffer, cx| {
+                        if let Some(task) = buffer.git_diff_recalc(cx) {
+                            task.detach();
+                        }
+                    });
+                }
+            }));
+
+            proposals.push(BufferProposal {
+                original,
+                original_snapshot,
+                accepted,
+                accepted_base,
+                proposed,
+            });
+        }
+
+        let editor = cx.new_view(|cx| Editor::for_multibuffer(multibuffer, None, true, cx));
+
+        let diff_tasks = proposals
+            .iter()
+            .filter_map(|proposal| {
+                proposal
+                    .proposed
+                    .update(cx, |buffer, cx| buffer.git_diff_recalc(cx))
+            })
+            .collect::<Vec<_>>();
+        let weak_editor = editor.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            future::join_all(diff_tasks).await;
+            weak_editor.update(&mut cx, |editor, cx| {
+                editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+
+        Self {
+            editor,
+            workspace,
+            title,
+            proposals,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Moves the hunks touched by the selections into the accepted text.
+    fn accept_hunk(&mut self, _: &AcceptProposedHunk, cx: &mut ViewContext<Self>) {
+        let hunks = self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let mut seen = HashSet::default();
+            let mut hunks = Vec::new();
+            for selection in editor.selections.all::<Point>(cx) {
+                let rows =
+                    MultiBufferRow(selection.start.row)..MultiBufferRow(selection.end.row + 1);
+                for hunk in snapshot.git_diff_hunks_in_range(rows) {
+                    if seen.insert((hunk.buffer_id, hunk.diff_base_byte_range.start)) {
+                        hunks.push(hunk);
+                    }
+                }
+            }
+            hunks
+        });
+
+        for proposal in &self.proposals {
+            let buffer_id = proposal.proposed.read(cx).remote_id();
+            let proposed = proposal.proposed.read(cx);
+            let mut edits = hunks
+                .iter()
+                .filter(|hunk| hunk.buffer_id == buffer_id)
+                .map(|hunk| {
+                    let new_text = proposed
+                        .text_for_range(hunk.buffer_range.clone())
+                        .collect::<String>();
+                    (hunk.diff_base_byte_range.clone(), new_text)
+                })
+                .collect::<Vec<_>>();
+            if edits.is_empty() {
+                continue;
+            }
+            edits.sort_by_key(|(range, _)| range.start);
+
+            let accepted_text = proposal.accepted.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+                buffer.text()
+            });
+            proposal.proposed.update(cx, |buffer, cx| {
+                buffer.set_diff_base(Some(accepted_text), cx)
+            });
+        }
+    }
+
+    /// Restores the original text of the hunks touched by the selections.
+    fn reject_hunk(&mut self, _: &RejectProposedHunk, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            editor.revert_selected_hunks(&RevertSelectedHunks, cx)
+        });
+    }
+
+    /// Applies every accepted hunk to the original buffers as a single project
+    /// transaction, and opens the result so it can be reviewed or undone at once.
+    fn apply(&mut self, _: &ApplyProposedChanges, cx: &mut ViewContext<Self>) {
+        let mut project_transaction = ProjectTransaction::default();
+        for proposal in &self.proposals {
+            let accepted = proposal.accepted.read(cx);
+            let edits = accepted
+                .edits_since::<usize>(proposal.accepted_base.version())
+                .map(|edit| {
+                    let range = proposal.original_snapshot.anchor_after(edit.old.start)
+                        ..proposal.original_snapshot.anchor_before(edit.old.end);
+                    let new_text = accepted.text_for_range(edit.new).collect::<String>();
+                    (range, new_text)
+                })
+                .collect::<Vec<_>>();
+            if edits.is_empty() {
+                continue;
+            }
+
+            let transaction = proposal.original.update(cx, |buffer, cx| {
+                buffer.finalize_last_transaction();
+                buffer.start_transaction();
+                buffer.edit(edits, None, cx);
+                if buffer.end_transaction(cx).is_some() {
+                    buffer.finalize_last_transaction().cloned()
+                } else {
+                    None
+                }
+            });
+            if let Some(transaction) = transaction {
+                project_transaction
+                    .0
+                    .insert(proposal.original.clone(), transaction);
+            }
+        }
+
+        if project_transaction.0.is_empty() {
+            return;
+        }
+
+        let editor = self.editor.downgrade();
+        let workspace = self.workspace.clone();
+        let title = self.title.to_string();
+        cx.spawn(|this, mut cx| async move {
+            Editor::open_project_transaction(
+                &editor,
+                workspace,
+                project_transaction,
+                title,
+                cx.clone(),
+            )
+            .await?;
+            this.update(&mut cx, |_, cx| cx.emit(EditorEvent::Closed))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn has_accepted_hunks(&self, cx: &AppContext) -> bool {
+        self.proposals.iter().any(|proposal| {
+            proposal
+                .accepted
+                .read(cx)
+                .has_edits_since(proposal.accepted_base.version())
+        })
+    }
+}
+
+impl EventEmitter<EditorEvent> for ProposedChangesEditor {}
+
+impl FocusableView for ProposedChangesEditor {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for ProposedChangesEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let has_accepted_hunks = self.has_accepted_hunks(cx);
+        v_flex()
+            .key_context("ProposedChangesEditor")
+            .size_full()
+            .on_action(cx.listener(Self::accept_hunk))
+            .on_action(cx.listener(Self::reject_hunk))
+            .on_action(cx.listener(Self::apply))
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(self.title.clone()))
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("reject", "Reject Hunk")
+                                    .icon(IconName::Close)
+                                    .icon_position(IconPosition::Start)
+                                    .on_click(cx.listener(|this, _, cx| {
+                                        this.reject_hunk(&RejectProposedHunk, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("accept", "Accept Hunk")
+                                    .icon(IconName::Check)
+                                    .icon_position(IconPosition::Start)
+                                    .on_click(cx.listener(|this, _, cx| {
+                                        this.accept_hunk(&AcceptProposedHunk, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("apply", "Apply Accepted")
+                                    .style(ButtonStyle::Filled)
+                                    .disabled(!has_accepted_hunks)
+                                    .on_click(cx.listener(|this, _, cx| {
+                                        this.apply(&ApplyProposedChanges, cx)
+                                    })),
+                            ),
+                    ),
+            )
+            .child(self.editor.clone())
+    }
+}
+
+impl Item for ProposedChangesEditor {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(workspace::item::ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new(self.title.clone())
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some("Proposed Changes".into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("proposed changes")
+    }
+
+    fn display_name(&self) -> Option<&'static str> {
+        Some("proposed changes")
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn navigate(&mut self, data: Box<dyn std::any::Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        false
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+}
+
+/// Opens the given edits for review in the workspace's active pane.
+pub(crate) fn open_proposed_changes(
+    workspace: &mut Workspace,
+    title: impl Into<SharedString>,
+    edits: Vec<(Model<Buffer>, Vec<(Range<usize>, String)>)>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let weak_workspace = workspace.weak_handle();
+    let editor = cx.new_view(|cx| ProposedChangesEditor::new(title, edits, weak_workspace, cx));
+    workspace.add_item_to_active_pane(Box::new(editor), None, cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::Project;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_accept_and_apply_proposed_hunks(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+
+        let fs = project::FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        let buffer = cx.new_model(|cx| Buffer::local("one\ntwo\nthree\nfour\nfive\n", cx));
+        let proposal = workspace.update(cx, |workspace, cx| {
+            let weak_workspace = workspace.weak_handle();
+            cx.new_view(|cx| {
+                ProposedChangesEditor::new(
+                    "Test",
+                    vec![(
+                        buffer.clone(),
+                        vec![(4..7, "TWO".to_string()), (19..23, "FIVE".to_string())],
+                    )],
+                    weak_workspace,
+                    cx,
+                )
+            })
+        });
+        cx.run_until_parked();
+
+        // Accept the first hunk and reject the second.
+        proposal.update(cx, |proposal, cx| {
+            proposal.editor.update(cx, |editor, cx| {
+                editor.change_selections(None, cx, |s| {
+                    s.select_ranges([Point::new(1, 0)..Point::new(1, 0)])
+                });
+            });
+            proposal.accept_hunk(&AcceptProposedHunk, cx);
+        });
+        cx.run_until_parked();
+        proposal.update(cx, |proposal, cx| {
+            proposal.editor.update(cx, |editor, cx| {
+                editor.change_selections(None, cx, |s| {
+                    s.select_ranges([Point::new(4, 0)..Point::new(4, 0)])
+                });
+            });
+            proposal.reject_hunk(&RejectProposedHunk, cx);
+        });
+        cx.run_until_parked();
+
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "one\ntwo\nthree\nfour\nfive\n"
+        );
+        proposal.update(cx, |proposal, cx| proposal.apply(&ApplyProposedChanges, cx));
+        cx.run_until_parked();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "one\nTWO\nthree\nfour\nfive\n"
+        );
+
+        // All of the accepted hunks are undone together.
+        buffer.update(cx, |buffer, cx| buffer.undo(cx));
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "one\ntwo\nthree\nfour\nfive\n"
+        );
+    }
+}
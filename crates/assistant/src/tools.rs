@@ -0,0 +1,69 @@
+pub mod diagnostics_tool;
+pub mod edit_files_tool;
+pub mod project_search_tool;
+pub mod read_file_tool;
+pub mod semantic_search_tool;
+
+use assistant_tooling::ToolRegistry;
+use gpui::{AnyElement, AppContext, Model, SharedString, WeakView};
+use project::Project;
+use semantic_index::SemanticIndex;
+use std::sync::Arc;
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::Workspace;
+
+/// Builds the registry of tools the assistant can call while working in the given project.
+///
+/// Semantic search is only offered when the semantic index is available. Proposed edits
+/// are opened for review in the given workspace.
+pub(crate) fn tool_registry(
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    cx: &AppContext,
+) -> Arc<ToolRegistry> {
+    let mut registry = ToolRegistry::new();
+    registry
+        .register(read_file_tool::ReadFileTool::new(project.clone()))
+        .log_err();
+    registry
+        .register(project_search_tool::ProjectSearchTool::new(project.clone()))
+        .log_err();
+    registry
+        .register(diagnostics_tool::DiagnosticsTool::new(project.clone()))
+        .log_err();
+    registry
+        .register(edit_files_tool::EditFilesTool::new(
+            project.clone(),
+            workspace,
+        ))
+        .log_err();
+    if cx.has_global::<SemanticIndex>() {
+        registry
+            .register(semantic_search_tool::SemanticSearchTool::new(project))
+            .log_err();
+    }
+    Arc::new(registry)
+}
+
+/// Renders the summary line shown in the conversation for a single tool call.
+///
+/// A tool call without an output is still running.
+fn render_tool_call<T>(
+    icon: IconName,
+    label: impl Into<SharedString>,
+    output: Option<&Result<T, String>>,
+) -> AnyElement {
+    let status = match output {
+        None => Label::new("Running…").color(Color::Muted),
+        Some(Ok(_)) => Label::new("Done").color(Color::Muted),
+        Some(Err(error)) => Label::new(error.clone()).color(Color::Error),
+    };
+
+    h_flex()
+        .gap_2()
+        .child(Icon::new(icon).size(IconSize::Small).color(Color::Muted))
+        .child(Label::new(label).size(LabelSize::Small))
+        .child(status.size(LabelSize::Small).single_line())
+        .into_any_element()
+}
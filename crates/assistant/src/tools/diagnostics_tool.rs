@@ -0,0 +1,218 @@
+use super::render_tool_call;
+use anyhow::{anyhow, Result};
+use assistant_tooling::{LanguageModelTool, ProjectContext, ToolView};
+use collections::BTreeMap;
+use gpui::{Model, Task, View, WindowContext};
+use language::{DiagnosticSeverity, Point};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, path::Path};
+use ui::prelude::*;
+
+pub struct DiagnosticsTool {
+    project: Model<Project>,
+}
+
+impl DiagnosticsTool {
+    pub fn new(project: Model<Project>) -> Self {
+        Self { project }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DiagnosticsInput {
+    /// The path of a file whose errors and warnings should be listed, starting with the
+    /// name of one of the project's root directories.
+    ///
+    /// When omitted, the number of errors and warnings in each file of the project is returned.
+    path: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum DiagnosticsOutput {
+    Project(Vec<PathDiagnosticSummary>),
+    File(Vec<FileDiagnostic>),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PathDiagnosticSummary {
+    path: String,
+    error_count: usize,
+    warning_count: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FileDiagnostic {
+    line: u32,
+    is_error: bool,
+    message: String,
+}
+
+impl LanguageModelTool for DiagnosticsTool {
+    type View = DiagnosticsView;
+
+    fn name(&self) -> String {
+        "diagnostics".into()
+    }
+
+    fn description(&self) -> String {
+        "Lists the errors and warnings reported by language servers, either for the whole project or for a single file."
+            .into()
+    }
+
+    fn view(&self, cx: &mut WindowContext) -> View<Self::View> {
+        cx.new_view(|_| DiagnosticsView {
+            project: self.project.clone(),
+            input: None,
+            output: None,
+        })
+    }
+}
+
+pub struct DiagnosticsView {
+    project: Model<Project>,
+    input: Option<DiagnosticsInput>,
+    output: Option<Result<DiagnosticsOutput, String>>,
+}
+
+impl Render for DiagnosticsView {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        let label = match self.input.as_ref().and_then(|input| input.path.as_ref()) {
+            Some(path) => format!("Check diagnostics in {path}"),
+            None => "Check project diagnostics".into(),
+        };
+        render_tool_call(IconName::ExclamationTriangle, label, self.output.as_ref())
+    }
+}
+
+impl ToolView for DiagnosticsView {
+    type Input = DiagnosticsInput;
+    type SerializedState = Option<Result<DiagnosticsOutput, String>>;
+
+    fn generate(&self, _: &mut ProjectContext, _: &mut ViewContext<Self>) -> String {
+        let mut text = String::new();
+        match &self.output {
+            Some(Ok(DiagnosticsOutput::Project(summaries))) => {
+                if summaries.is_empty() {
+                    return "The project has no errors or warnings".into();
+                }
+                for summary in summaries {
+                    writeln!(
+                        text,
+                        "{}: {} error(s), {} warning(s)",
+                        summary.path, summary.error_count, summary.warning_count
+                    )
+                    .unwrap();
+                }
+            }
+            Some(Ok(DiagnosticsOutput::File(diagnostics))) => {
+                if diagnostics.is_empty() {
+                    return "The file has no errors or warnings".into();
+                }
+                for diagnostic in diagnostics {
+                    let severity = if diagnostic.is_error {
+                        "error"
+                    } else {
+                        "warning"
+                    };
+                    writeln!(
+                        text,
+                        "line {}: {}: {}",
+                        diagnostic.line, severity, diagnostic.message
+                    )
+                    .unwrap();
+                }
+            }
+            Some(Err(error)) => write!(text, "Failed to get diagnostics: {error}").unwrap(),
+            None => text.push_str("Diagnostics have not been collected yet"),
+        }
+        text
+    }
+
+    fn set_input(&mut self, input: Self::Input, cx: &mut ViewContext<Self>) {
+        self.input = Some(input);
+        cx.notify();
+    }
+
+    fn execute(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(input) = self.input.as_ref() else {
+            return Task::ready(Err(anyhow!("no input")));
+        };
+
+        let Some(path) = input.path.as_ref() else {
+            let project = self.project.read(cx);
+            let mut summaries = BTreeMap::default();
+            for (project_path, _, summary) in project.diagnostic_summaries(false, cx) {
+                let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx) else {
+                    continue;
+                };
+                let path = Path::new(worktree.read(cx).root_name()).join(&project_path.path);
+                let entry = summaries
+                    .entry(path.to_string_lossy().into_owned())
+                    .or_insert((0, 0));
+                entry.0 += summary.error_count;
+                entry.1 += summary.warning_count;
+            }
+
+            self.output = Some(Ok(DiagnosticsOutput::Project(
+                summaries
+                    .into_iter()
+                    .map(
+                        |(path, (error_count, warning_count))| PathDiagnosticSummary {
+                            path,
+                            error_count,
+                            warning_count,
+                        },
+                    )
+                    .collect(),
+            )));
+            cx.notify();
+            return Task::ready(Ok(()));
+        };
+
+        let open_buffer = self.project.update(cx, |project, cx| {
+            project.open_buffer_for_full_path(Path::new(path), cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            let result = async {
+                let buffer = open_buffer.await?;
+                buffer.read_with(&cx, |buffer, _| {
+                    buffer
+                        .snapshot()
+                        .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                        .filter(|entry| entry.diagnostic.severity <= DiagnosticSeverity::WARNING)
+                        .map(|entry| FileDiagnostic {
+                            line: entry.range.start.row + 1,
+                            is_error: entry.diagnostic.severity == DiagnosticSeverity::ERROR,
+                            message: entry.diagnostic.message,
+                        })
+                        .collect::<Vec<_>>()
+                })
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.output = Some(
+                    result
+                        .map(DiagnosticsOutput::File)
+                        .map_err(|error| error.to_string()),
+                );
+                cx.notify();
+            })
+        })
+    }
+
+    fn serialize(&self, _: &mut ViewContext<Self>) -> Self::SerializedState {
+        self.output.clone()
+    }
+
+    fn deserialize(
+        &mut self,
+        output: Self::SerializedState,
+        _: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        self.output = output;
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+use super::render_tool_call;
+use crate::proposed_changes::open_proposed_changes;
+use anyhow::{anyhow, Result};
+use assistant_tooling::{LanguageModelTool, ProjectContext, ToolView};
+use collections::HashMap;
+use futures::future;
+use gpui::{Model, Task, View, WeakView, WindowContext};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{ops::Range, path::Path};
+use ui::prelude::*;
+use workspace::Workspace;
+
+pub struct EditFilesTool {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+}
+
+impl EditFilesTool {
+    pub fn new(project: Model<Project>, workspace: WeakView<Workspace>) -> Self {
+        Self { project, workspace }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EditFilesInput {
+    /// A short summary of the change, used as the title of the proposal.
+    title: String,
+    /// The edits to propose. Edits may span any number of files.
+    edits: Vec<FileEdit>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FileEdit {
+    /// The path of the file to edit, starting with the name of one of the project's
+    /// root directories.
+    path: String,
+    /// The text to replace. It must appear exactly once in the file, so include enough
+    /// surrounding lines to make it unique.
+    old_text: String,
+    /// The text to replace `old_text` with.
+    new_text: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EditFilesOutput {
+    file_count: usize,
+    edit_count: usize,
+}
+
+impl LanguageModelTool for EditFilesTool {
+    type View = EditFilesView;
+
+    fn name(&self) -> String {
+        "edit_files".into()
+    }
+
+    fn description(&self) -> String {
+        "Proposes edits to one or more files in the project. The edits are shown to the user, who decides which of them to apply."
+            .into()
+    }
+
+    fn view(&self, cx: &mut WindowContext) -> View<Self::View> {
+        cx.new_view(|_| EditFilesView {
+            project: self.project.clone(),
+            workspace: self.workspace.clone(),
+            input: None,
+            output: None,
+        })
+    }
+}
+
+pub struct EditFilesView {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    input: Option<EditFilesInput>,
+    output: Option<Result<EditFilesOutput, String>>,
+}
+
+impl Render for EditFilesView {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        let label = match &self.input {
+            Some(input) => format!("Propose edits: {}", input.title),
+            None => "Propose edits".into(),
+        };
+        render_tool_call(IconName::Pencil, label, self.output.as_ref())
+    }
+}
+
+impl ToolView for EditFilesView {
+    type Input = EditFilesInput;
+    type SerializedState = Option<Result<EditFilesOutput, String>>;
+
+    fn generate(&self, _: &mut ProjectContext, _: &mut ViewContext<Self>) -> String {
+        match &self.output {
+            Some(Ok(output)) => format!(
+                "Proposed {} edit(s) in {} file(s). The user will review them before they are applied.",
+                output.edit_count, output.file_count
+            ),
+            Some(Err(error)) => format!("Failed to propose edits: {error}"),
+            None => "Edits have not been proposed yet".into(),
+        }
+    }
+
+    fn set_input(&mut self, input: Self::Input, cx: &mut ViewContext<Self>) {
+        self.input = Some(input);
+        cx.notify();
+    }
+
+    fn execute(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(input) = self.input.as_ref() else {
+            return Task::ready(Err(anyhow!("no input")));
+        };
+
+        let mut edits_by_path = HashMap::<String, Vec<(String, String)>>::default();
+        let mut paths = Vec::new();
+        for edit in &input.edits {
+            let edits = edits_by_path.entry(edit.path.clone()).or_insert_with(|| {
+                paths.push(edit.path.clone());
+                Vec::new()
+            });
+            edits.push((edit.old_text.clone(), edit.new_text.clone()));
+        }
+        let edit_count = input.edits.len();
+        let title = input.title.clone();
+        let open_buffers = self.project.update(cx, |project, cx| {
+            paths
+                .iter()
+                .map(|path| project.open_buffer_for_full_path(Path::new(path), cx))
+                .collect::<Vec<_>>()
+        });
+        let workspace = self.workspace.clone();
+
+        cx.spawn(|this, mut cx| async move {
+            let result = async {
+                let buffers = future::try_join_all(open_buffers).await?;
+                let mut proposal = Vec::new();
+                for (path, buffer) in paths.iter().zip(buffers) {
+                    let text = buffer.read_with(&cx, |buffer, _| buffer.text())?;
+                    let edits = resolve_edits(&text, &edits_by_path[path])
+                        .map_err(|error| anyhow!("{path}: {error}"))?;
+                    proposal.push((buffer, edits));
+                }
+
+                let file_count = proposal.len();
+                workspace.update(&mut cx, |workspace, cx| {
+                    open_proposed_changes(workspace, title, proposal, cx)
+                })?;
+                anyhow::Ok(EditFilesOutput {
+                    file_count,
+                    edit_count,
+                })
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.output = Some(result.map_err(|error| error.to_string()));
+                cx.notify();
+            })
+        })
+    }
+
+    fn serialize(&self, _: &mut ViewContext<Self>) -> Self::SerializedState {
+        self.output.clone()
+    }
+
+    fn deserialize(
+        &mut self,
+        output: Self::SerializedState,
+        _: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        self.output = output;
+        Ok(())
+    }
+}
+
+/// Locates the text replaced by each edit, rejecting edits whose `old_text` is
+/// missing, ambiguous, or overlaps another edit in the same file.
+fn resolve_edits(text: &str, edits: &[(String, String)]) -> Result<Vec<(Range<usize>, String)>> {
+    let mut resolved = Vec::with_capacity(edits.len());
+    for (old_text, new_text) in edits {
+        if old_text.is_empty() {
+            return Err(anyhow!("old_text must not be empty"));
+        }
+        let mut matches = text.match_indices(old_text.as_str());
+        let Some((start, _)) = matches.next() else {
+            return Err(anyhow!("could not find {old_text:?}"));
+        };
+        if matches.next().is_some() {
+            return Err(anyhow!("{old_text:?} appears more than once"));
+        }
+        resolved.push((start..start + old_text.len(), new_text.clone()));
+    }
+
+    resolved.sort_by_key(|(range, _)| range.start);
+    if resolved
+        .windows(2)
+        .any(|pair| pair[0].0.end > pair[1].0.start)
+    {
+        return Err(anyhow!("edits overlap"));
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_edits() {
+        let text = "fn one() {}\nfn two() {}\nfn three() {}\n";
+        assert_eq!(
+            resolve_edits(
+                text,
+                &[
+                    ("fn three".into(), "fn THREE".into()),
+                    ("fn one".into(), "fn ONE".into()),
+                ]
+            )
+            .unwrap(),
+            vec![(0..6, "fn ONE".into()), (24..32, "fn THREE".into())]
+        );
+        assert!(resolve_edits(text, &[("fn four".into(), "".into())]).is_err());
+        assert!(resolve_edits(text, &[("() {}".into(), "".into())]).is_err());
+        assert!(resolve_edits(
+            text,
+            &[
+                ("one() {}\nfn two".into(), "".into()),
+                ("two() {}".into(), "".into()),
+            ]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use super::render_tool_call;
+use anyhow::{anyhow, Result};
+use assistant_tooling::{LanguageModelTool, ProjectContext, ToolView};
+use gpui::{Model, Task, View, WindowContext};
+use language::{Point, ToPoint as _};
+use project::{search::SearchQuery, Project, SearchResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use ui::prelude::*;
+use util::paths::PathMatcher;
+
+/// The maximum number of matching lines reported back to the model.
+const MAX_MATCHES: usize = 50;
+
+pub struct ProjectSearchTool {
+    project: Model<Project>,
+}
+
+impl ProjectSearchTool {
+    pub fn new(project: Model<Project>) -> Self {
+        Self { project }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ProjectSearchInput {
+    /// The text to search for.
+    query: String,
+    /// Whether the query is a regular expression.
+    #[serde(default)]
+    regex: bool,
+    /// Whether the search should be case sensitive.
+    #[serde(default)]
+    case_sensitive: bool,
+    /// An optional glob restricting which files are searched, e.g. `**/*.rs`.
+    include: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SearchMatch {
+    path: String,
+    line: u32,
+    text: String,
+}
+
+impl LanguageModelTool for ProjectSearchTool {
+    type View = ProjectSearchView;
+
+    fn name(&self) -> String {
+        "project_search".into()
+    }
+
+    fn description(&self) -> String {
+        "Searches the project's files for text or a regex and returns the matching lines.".into()
+    }
+
+    fn view(&self, cx: &mut WindowContext) -> View<Self::View> {
+        cx.new_view(|_| ProjectSearchView {
+            project: self.project.clone(),
+            input: None,
+            output: None,
+        })
+    }
+}
+
+pub struct ProjectSearchView {
+    project: Model<Project>,
+    input: Option<ProjectSearchInput>,
+    output: Option<Result<Vec<SearchMatch>, String>>,
+}
+
+impl ProjectSearchView {
+    fn search_query(input: &ProjectSearchInput) -> Result<SearchQuery> {
+        let files_to_include = match &input.include {
+            Some(glob) => PathMatcher::new(&[glob.clone()])?,
+            None => PathMatcher::default(),
+        };
+
+        if input.regex {
+            SearchQuery::regex(
+                &input.query,
+                false,
+                input.case_sensitive,
+                false,
+                files_to_include,
+                PathMatcher::default(),
+            )
+        } else {
+            SearchQuery::text(
+                &input.query,
+                false,
+                input.case_sensitive,
+                false,
+                files_to_include,
+                PathMatcher::default(),
+            )
+        }
+    }
+}
+
+impl Render for ProjectSearchView {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        let label = match &self.input {
+            Some(input) => format!("Search project for \"{}\"", input.query),
+            None => "Search project".into(),
+        };
+        render_tool_call(IconName::MagnifyingGlass, label, self.output.as_ref())
+    }
+}
+
+impl ToolView for ProjectSearchView {
+    type Input = ProjectSearchInput;
+    type SerializedState = Option<Result<Vec<SearchMatch>, String>>;
+
+    fn generate(&self, _: &mut ProjectContext, _: &mut ViewContext<Self>) -> String {
+        match &self.output {
+            Some(Ok(matches)) if matches.is_empty() => "No matches found".into(),
+            Some(Ok(matches)) => {
+                let mut text = String::new();
+                for search_match in matches {
+                    writeln!(
+                        text,
+                        "{}:{}: {}",
+                        search_match.path, search_match.line, search_match.text
+                    )
+                    .unwrap();
+                }
+                if matches.len() >= MAX_MATCHES {
+                    writeln!(text, "(results truncated to {MAX_MATCHES} matches)").unwrap();
+                }
+                text
+            }
+            Some(Err(error)) => format!("Search failed: {error}"),
+            None => "Search has not finished".into(),
+        }
+    }
+
+    fn set_input(&mut self, input: Self::Input, cx: &mut ViewContext<Self>) {
+        self.input = Some(input);
+        cx.notify();
+    }
+
+    fn execute(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(input) = self.input.as_ref() else {
+            return Task::ready(Err(anyhow!("no input")));
+        };
+
+        let query = match Self::search_query(input) {
+            Ok(query) => query,
+            Err(error) => {
+                self.output = Some(Err(error.to_string()));
+                cx.notify();
+                return Task::ready(Ok(()));
+            }
+        };
+
+        let results = self
+            .project
+            .update(cx, |project, cx| project.search(query, cx));
+        cx.spawn(|this, mut cx| async move {
+            let mut matches = Vec::new();
+            while let Ok(result) = results.recv().await {
+                let SearchResult::Buffer { buffer, ranges } = result else {
+                    break;
+                };
+
+                buffer.read_with(&cx, |buffer, cx| {
+                    let path = buffer
+                        .file()
+                        .map(|file| file.full_path(cx).to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let mut last_row = None;
+                    for range in ranges {
+                        let row = range.start.to_point(buffer).row;
+                        if last_row == Some(row) {
+                            continue;
+                        }
+                        last_row = Some(row);
+
+                        let line_range = Point::new(row, 0)..Point::new(row, buffer.line_len(row));
+                        matches.push(SearchMatch {
+                            path: path.clone(),
+                            line: row + 1,
+                            text: buffer.text_for_range(line_range).collect(),
+                        });
+                    }
+                })?;
+
+                if matches.len() >= MAX_MATCHES {
+                    matches.truncate(MAX_MATCHES);
+                    break;
+                }
+            }
+
+            this.update(&mut cx, |this, cx| {
+                this.output = Some(Ok(matches));
+                cx.notify();
+            })
+        })
+    }
+
+    fn serialize(&self, _: &mut ViewContext<Self>) -> Self::SerializedState {
+        self.output.clone()
+    }
+
+    fn deserialize(
+        &mut self,
+        output: Self::SerializedState,
+        _: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        self.output = output;
+        Ok(())
+    }
+}
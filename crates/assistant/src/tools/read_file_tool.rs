@@ -0,0 +1,140 @@
+use super::render_tool_call;
+use anyhow::{anyhow, Result};
+use assistant_tooling::{LanguageModelTool, ProjectContext, ToolView};
+use gpui::{Model, Task, View, WindowContext};
+use language::Point;
+use project::Project;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::path::Path;
+use ui::prelude::*;
+
+pub struct ReadFileTool {
+    project: Model<Project>,
+}
+
+impl ReadFileTool {
+    pub fn new(project: Model<Project>) -> Self {
+        Self { project }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReadFileInput {
+    /// The path of the file to read.
+    ///
+    /// The path must start with the name of one of the project's root directories,
+    /// e.g. `zed/crates/gpui/src/gpui.rs`.
+    path: String,
+    /// The 1-based line number to start reading from. Defaults to the start of the file.
+    start_line: Option<u32>,
+    /// The 1-based line number to stop reading at, inclusive. Defaults to the end of the file.
+    end_line: Option<u32>,
+}
+
+impl LanguageModelTool for ReadFileTool {
+    type View = ReadFileView;
+
+    fn name(&self) -> String {
+        "read_file".into()
+    }
+
+    fn description(&self) -> String {
+        "Reads the contents of a file in the project, optionally restricted to a range of lines."
+            .into()
+    }
+
+    fn view(&self, cx: &mut WindowContext) -> View<Self::View> {
+        cx.new_view(|_| ReadFileView {
+            project: self.project.clone(),
+            input: None,
+            output: None,
+        })
+    }
+}
+
+pub struct ReadFileView {
+    project: Model<Project>,
+    input: Option<ReadFileInput>,
+    output: Option<Result<String, String>>,
+}
+
+impl Render for ReadFileView {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        let label = match &self.input {
+            Some(input) => format!("Read {}", input.path),
+            None => "Read file".into(),
+        };
+        render_tool_call(IconName::File, label, self.output.as_ref())
+    }
+}
+
+impl ToolView for ReadFileView {
+    type Input = ReadFileInput;
+    type SerializedState = Option<Result<String, String>>;
+
+    fn generate(&self, _: &mut ProjectContext, _: &mut ViewContext<Self>) -> String {
+        let Some(input) = self.input.as_ref() else {
+            return "No input".into();
+        };
+
+        match &self.output {
+            Some(Ok(text)) => format!("```{}\n{}\n```", input.path, text),
+            Some(Err(error)) => format!("Failed to read {}: {}", input.path, error),
+            None => format!("Still reading {}", input.path),
+        }
+    }
+
+    fn set_input(&mut self, input: Self::Input, cx: &mut ViewContext<Self>) {
+        self.input = Some(input);
+        cx.notify();
+    }
+
+    fn execute(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(input) = self.input.as_ref() else {
+            return Task::ready(Err(anyhow!("no input")));
+        };
+
+        let start_line = input.start_line;
+        let end_line = input.end_line;
+        let open_buffer = self.project.update(cx, |project, cx| {
+            project.open_buffer_for_full_path(Path::new(&input.path), cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            let result = async {
+                let buffer = open_buffer.await?;
+                buffer.read_with(&cx, |buffer, _| {
+                    let max_row = buffer.max_point().row;
+                    let start_row = start_line
+                        .map_or(0, |line| line.saturating_sub(1))
+                        .min(max_row);
+                    let end_row = end_line
+                        .map_or(max_row, |line| line.saturating_sub(1))
+                        .clamp(start_row, max_row);
+                    let range =
+                        Point::new(start_row, 0)..Point::new(end_row, buffer.line_len(end_row));
+                    buffer.text_for_range(range).collect::<String>()
+                })
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.output = Some(result.map_err(|error| error.to_string()));
+                cx.notify();
+            })
+        })
+    }
+
+    fn serialize(&self, _: &mut ViewContext<Self>) -> Self::SerializedState {
+        self.output.clone()
+    }
+
+    fn deserialize(
+        &mut self,
+        output: Self::SerializedState,
+        _: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        self.output = output;
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use super::render_tool_call;
+use anyhow::{anyhow, Result};
+use assistant_tooling::{LanguageModelTool, ProjectContext, ToolView};
+use gpui::{Model, Task, View, WindowContext};
+use language::LineEnding;
+use project::Project;
+use schemars::JsonSchema;
+use semantic_index::SemanticIndex;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, path::PathBuf};
+use ui::prelude::*;
+use util::ResultExt;
+
+const DEFAULT_LIMIT: usize = 8;
+
+pub struct SemanticSearchTool {
+    project: Model<Project>,
+}
+
+impl SemanticSearchTool {
+    pub fn new(project: Model<Project>) -> Self {
+        Self { project }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SemanticSearchInput {
+    /// A natural language description of the code to look for.
+    query: String,
+    /// The maximum number of excerpts to return. Defaults to 8.
+    limit: Option<usize>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SemanticSearchExcerpt {
+    path: String,
+    start_line: u32,
+    end_line: u32,
+    text: String,
+}
+
+impl LanguageModelTool for SemanticSearchTool {
+    type View = SemanticSearchView;
+
+    fn name(&self) -> String {
+        "semantic_search".into()
+    }
+
+    fn description(&self) -> String {
+        "Searches the project's semantic index for code related to a natural language query and returns the most relevant excerpts."
+            .into()
+    }
+
+    fn view(&self, cx: &mut WindowContext) -> View<Self::View> {
+        cx.new_view(|_| SemanticSearchView {
+            project: self.project.clone(),
+            input: None,
+            output: None,
+        })
+    }
+}
+
+pub struct SemanticSearchView {
+    project: Model<Project>,
+    input: Option<SemanticSearchInput>,
+    output: Option<Result<Vec<SemanticSearchExcerpt>, String>>,
+}
+
+impl Render for SemanticSearchView {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        let label = match &self.input {
+            Some(input) => format!("Semantic search for \"{}\"", input.query),
+            None => "Semantic search".into(),
+        };
+        render_tool_call(IconName::MagnifyingGlass, label, self.output.as_ref())
+    }
+}
+
+impl ToolView for SemanticSearchView {
+    type Input = SemanticSearchInput;
+    type SerializedState = Option<Result<Vec<SemanticSearchExcerpt>, String>>;
+
+    fn generate(&self, _: &mut ProjectContext, _: &mut ViewContext<Self>) -> String {
+        match &self.output {
+            Some(Ok(excerpts)) if excerpts.is_empty() => "No results found".into(),
+            Some(Ok(excerpts)) => {
+                let mut text = String::new();
+                for excerpt in excerpts {
+                    writeln!(
+                        text,
+                        "```{}:{}-{}\n{}\n```",
+                        excerpt.path, excerpt.start_line, excerpt.end_line, excerpt.text
+                    )
+                    .unwrap();
+                }
+                text
+            }
+            Some(Err(error)) => format!("Semantic search failed: {error}"),
+            None => "Semantic search has not finished".into(),
+        }
+    }
+
+    fn set_input(&mut self, input: Self::Input, cx: &mut ViewContext<Self>) {
+        self.input = Some(input);
+        cx.notify();
+    }
+
+    fn execute(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(input) = self.input.as_ref() else {
+            return Task::ready(Err(anyhow!("no input")));
+        };
+        if !cx.has_global::<SemanticIndex>() {
+            self.output = Some(Err("semantic search is not available".into()));
+            cx.notify();
+            return Task::ready(Ok(()));
+        }
+
+        let query = input.query.clone();
+        let limit = input.limit.unwrap_or(DEFAULT_LIMIT);
+        let fs = self.project.read(cx).fs().clone();
+        let project = self.project.clone();
+        let project_index =
+            cx.update_global(|index: &mut SemanticIndex, cx| index.project_index(project, cx));
+
+        cx.spawn(|this, mut cx| async move {
+            let result = async {
+                let results = project_index
+                    .read_with(&cx, |project_index, cx| {
+                        project_index.search(query, limit, cx)
+                    })?
+                    .await?;
+
+                let mut excerpts = Vec::new();
+                for result in results {
+                    let (full_path, abs_path) = result.worktree.read_with(&cx, |worktree, _| {
+                        let mut full_path = PathBuf::from(worktree.root_name());
+                        full_path.push(&result.path);
+                        (full_path, worktree.abs_path().join(&result.path))
+                    })?;
+                    let Some(content) = fs.load(&abs_path).await.log_err() else {
+                        continue;
+                    };
+
+                    let range_start = result.range.start.min(content.len());
+                    let range_end = result.range.end.min(content.len());
+                    let start_line_offset =
+                        content[..range_start].rfind('\n').map_or(0, |ix| ix + 1);
+                    let end_line_offset = content[range_end..]
+                        .find('\n')
+                        .map_or(content.len(), |ix| range_end + ix);
+
+                    let mut text = content[start_line_offset..end_line_offset].to_string();
+                    LineEnding::normalize(&mut text);
+                    excerpts.push(SemanticSearchExcerpt {
+                        path: full_path.to_string_lossy().into_owned(),
+                        start_line: content[..range_start].matches('\n').count() as u32 + 1,
+                        end_line: content[..range_end].matches('\n').count() as u32 + 1,
+                        text,
+                    });
+                }
+                anyhow::Ok(excerpts)
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.output = Some(result.map_err(|error| error.to_string()));
+                cx.notify();
+            })
+        })
+    }
+
+    fn serialize(&self, _: &mut ViewContext<Self>) -> Self::SerializedState {
+        self.output.clone()
+    }
+
+    fn deserialize(
+        &mut self,
+        output: Self::SerializedState,
+        _: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        self.output = output;
+        Ok(())
+    }
+}
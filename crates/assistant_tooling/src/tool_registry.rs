@@ -44,7 +44,7 @@ trait InternalToolView {
     fn deserialize_output(&self, raw_value: &RawValue, cx: &mut WindowContext) -> Result<()>;
 }
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SavedToolFunctionCall {
     id: String,
     name: String,
@@ -52,7 +52,7 @@ pub struct SavedToolFunctionCall {
     state: SavedToolFunctionCallState,
 }
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 enum SavedToolFunctionCallState {
     #[default]
     Initializing,
@@ -61,7 +61,7 @@ enum SavedToolFunctionCallState {
     ExecutedTool(Box<RawValue>),
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct ToolFunctionDefinition {
     pub name: String,
     pub description: String,
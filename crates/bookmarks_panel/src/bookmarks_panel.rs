@@ -0,0 +1,553 @@
+mod bookmarks_panel_settings;
+mod persistence;
+
+use std::{path::Path, sync::Arc};
+
+use anyhow::Context as _;
+use bookmarks_panel_settings::{BookmarksPanelDockPosition, BookmarksPanelSettings};
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use gpui::{
+    actions, Action, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView,
+    KeyContext, Model, Pixels, Render, Subscription, Task, View, ViewContext, WeakView,
+};
+use language::Point;
+use menu::{Cancel, Confirm, SelectNext, SelectPrev};
+use persistence::BOOKMARKS_DB;
+use project::{
+    bookmark_store::{BookmarkId, BookmarkStore, BookmarkStoreEvent},
+    Fs, Project,
+};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use ui::{prelude::*, IconButtonShape, ListItem, ListItemSpacing, Tooltip};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+actions!(
+    bookmarks_panel,
+    [ToggleFocus, RenameBookmark, RemoveBookmark, ClearBookmarks]
+);
+
+const BOOKMARKS_PANEL_KEY: &str = "BookmarksPanel";
+
+#[derive(Serialize, Deserialize)]
+struct SerializedBookmarksPanel {
+    width: Option<Pixels>,
+    active: Option<bool>,
+}
+
+pub fn init(cx: &mut AppContext) {
+    BookmarksPanelSettings::register(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<BookmarksPanel>(cx);
+        });
+        restore_bookmarks(workspace, cx);
+    })
+    .detach();
+}
+
+/// Restores the bookmarks stored for a workspace, and stores them again whenever they
+/// change.
+fn restore_bookmarks(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let Some(workspace_id) = workspace.database_id() else {
+        return;
+    };
+    let project = workspace.project().clone();
+    if !project.read(cx).is_local() {
+        return;
+    }
+
+    cx.spawn(|_, mut cx| async move {
+        let bookmarks = cx
+            .background_executor()
+            .spawn(async move { BOOKMARKS_DB.bookmarks(workspace_id) })
+            .await
+            .context("loading bookmarks")
+            .log_err()
+            .unwrap_or_default();
+
+        project.update(&mut cx, |project, cx| {
+            let store = project.bookmark_store().clone();
+            let added_while_loading = !store.read(cx).is_empty();
+            if !bookmarks.is_empty() {
+                project.restore_bookmarks(bookmarks, cx);
+            }
+
+            // Only start storing bookmarks once they've been restored, so that the stored
+            // ones aren't overwritten while they're still loading.
+            let save_bookmarks = move |store: &Model<BookmarkStore>, cx: &mut AppContext| {
+                let bookmarks = store.read(cx).serialize(cx);
+                cx.background_executor()
+                    .spawn(BOOKMARKS_DB.save_bookmarks(workspace_id, bookmarks))
+                    .detach_and_log_err(cx);
+            };
+            if added_while_loading {
+                save_bookmarks(&store, cx);
+            }
+            cx.subscribe(&store, move |_, store, event, cx| match event {
+                BookmarkStoreEvent::BookmarksChanged => save_bookmarks(&store, cx),
+            })
+            .detach();
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Lists the bookmarks of the project, grouped by file.
+pub struct BookmarksPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    active: bool,
+    selected_bookmark: Option<BookmarkId>,
+    /// The bookmark whose label is being edited, with the editor used to edit it.
+    label_editor: Option<(BookmarkId, View<Editor>)>,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl BookmarksPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<View<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(BOOKMARKS_PANEL_KEY) })
+            .await
+            .context("loading bookmarks panel")
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedBookmarksPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = Self::new(workspace, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.active = serialized_panel.active.unwrap_or(false);
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace = workspace.weak_handle();
+        cx.new_view(|cx| {
+            let store = project.read(cx).bookmark_store().clone();
+            let store_subscription = cx.observe(&store, |this, store, cx| {
+                if let Some(selected_bookmark) = this.selected_bookmark {
+                    if store.read(cx).bookmark(selected_bookmark).is_none() {
+                        this.selected_bookmark = None;
+                    }
+                }
+                cx.notify();
+            });
+            Self {
+                workspace,
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                active: false,
+                selected_bookmark: None,
+                label_editor: None,
+                pending_serialization: Task::ready(None),
+                _subscriptions: vec![store_subscription],
+            }
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        let active = Some(self.active);
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        BOOKMARKS_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedBookmarksPanel { width, active })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn dispatch_context(&self, cx: &ViewContext<Self>) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("BookmarksPanel");
+        dispatch_context.add("menu");
+        let identifier = if self
+            .label_editor
+            .as_ref()
+            .is_some_and(|(_, editor)| editor.focus_handle(cx).is_focused(cx))
+        {
+            "editing"
+        } else {
+            "not_editing"
+        };
+        dispatch_context.add(identifier);
+        dispatch_context
+    }
+
+    fn bookmark_ids(&self, cx: &AppContext) -> Vec<BookmarkId> {
+        self.project
+            .read(cx)
+            .bookmark_store()
+            .read(cx)
+            .sorted_bookmarks(cx)
+            .into_iter()
+            .map(|(bookmark, _)| bookmark.id)
+            .collect()
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let ids = self.bookmark_ids(cx);
+        let ix = self
+            .selected_bookmark
+            .and_then(|id| ids.iter().position(|candidate| *candidate == id))
+            .map_or(0, |ix| (ix + 1).min(ids.len().saturating_sub(1)));
+        self.selected_bookmark = ids.get(ix).copied();
+        cx.notify();
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        let ids = self.bookmark_ids(cx);
+        let ix = self
+            .selected_bookmark
+            .and_then(|id| ids.iter().position(|candidate| *candidate == id))
+            .map_or(ids.len().saturating_sub(1), |ix| ix.saturating_sub(1));
+        self.selected_bookmark = ids.get(ix).copied();
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if self.label_editor.is_some() {
+            self.confirm_label(cx);
+        } else if let Some(bookmark_id) = self.selected_bookmark {
+            self.open_bookmark(bookmark_id, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
+        if self.label_editor.take().is_some() {
+            self.focus_handle.focus(cx);
+            cx.notify();
+        }
+    }
+
+    fn rename_bookmark(&mut self, _: &RenameBookmark, cx: &mut ViewContext<Self>) {
+        if let Some(bookmark_id) = self.selected_bookmark {
+            self.edit_label(bookmark_id, cx);
+        }
+    }
+
+    fn remove_bookmark(&mut self, _: &RemoveBookmark, cx: &mut ViewContext<Self>) {
+        if let Some(bookmark_id) = self.selected_bookmark {
+            let store = self.project.read(cx).bookmark_store().clone();
+            store.update(cx, |store, cx| store.remove_bookmark(bookmark_id, cx));
+        }
+    }
+
+    fn clear_bookmarks(&mut self, _: &ClearBookmarks, cx: &mut ViewContext<Self>) {
+        let store = self.project.read(cx).bookmark_store().clone();
+        store.update(cx, |store, cx| store.clear(cx));
+    }
+
+    fn open_bookmark(&mut self, bookmark_id: BookmarkId, cx: &mut ViewContext<Self>) {
+        self.selected_bookmark = Some(bookmark_id);
+        self.workspace
+            .update(cx, |workspace, cx| {
+                editor::open_bookmark(workspace, bookmark_id, cx).detach_and_log_err(cx);
+            })
+            .log_err();
+        cx.notify();
+    }
+
+    fn edit_label(&mut self, bookmark_id: BookmarkId, cx: &mut ViewContext<Self>) {
+        let label = self
+            .project
+            .read(cx)
+            .bookmark_store()
+            .read(cx)
+            .bookmark(bookmark_id)
+            .and_then(|bookmark| bookmark.label.clone())
+            .unwrap_or_default();
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Bookmark label", cx);
+            editor.set_text(label, cx);
+            editor.select_all(&editor::actions::SelectAll, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(cx);
+        self.selected_bookmark = Some(bookmark_id);
+        self.label_editor = Some((bookmark_id, editor));
+        cx.notify();
+    }
+
+    fn confirm_label(&mut self, cx: &mut ViewContext<Self>) {
+        let Some((bookmark_id, editor)) = self.label_editor.take() else {
+            return;
+        };
+        let label = editor.read(cx).text(cx);
+        let store = self.project.read(cx).bookmark_store().clone();
+        store.update(cx, |store, cx| store.set_label(bookmark_id, label, cx));
+        self.focus_handle.focus(cx);
+        cx.notify();
+    }
+
+    /// Returns the path of a file relative to the worktree containing it.
+    fn display_path(&self, abs_path: &Path, cx: &AppContext) -> String {
+        let project = self.project.read(cx);
+        let include_root = project.visible_worktrees(cx).count() > 1;
+        project
+            .visible_worktrees(cx)
+            .find_map(|worktree| {
+                let worktree = worktree.read(cx);
+                let path = abs_path.strip_prefix(worktree.abs_path()).ok()?;
+                Some(if include_root {
+                    Path::new(worktree.root_name()).join(path)
+                } else {
+                    path.to_path_buf()
+                })
+            })
+            .unwrap_or_else(|| abs_path.to_path_buf())
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn render_bookmark(
+        &self,
+        bookmark_id: BookmarkId,
+        row: u32,
+        label: Option<String>,
+        mark: Option<char>,
+        line_text: Option<String>,
+        cx: &mut ViewContext<Self>,
+    ) -> ListItem {
+        let editing = self
+            .label_editor
+            .as_ref()
+            .filter(|(id, _)| *id == bookmark_id)
+            .map(|(_, editor)| editor.clone());
+        let title = label.or(line_text).unwrap_or_default();
+
+        ListItem::new(("bookmark", bookmark_id.to_usize()))
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(self.selected_bookmark == Some(bookmark_id))
+            .start_slot(
+                Icon::new(IconName::Bookmark)
+                    .size(IconSize::XSmall)
+                    .color(Color::Accent),
+            )
+            .child(match editing {
+                Some(editor) => h_flex().w_full().child(editor).into_any_element(),
+                None => h_flex()
+                    .gap_2()
+                    .child(Label::new(format!("{}", row + 1)).color(Color::Muted))
+                    .when_some(mark, |this, mark| {
+                        this.child(Label::new(format!("'{mark}")).color(Color::Accent))
+                    })
+                    .child(Label::new(title).single_line())
+                    .into_any_element(),
+            })
+            .end_hover_slot(
+                h_flex()
+                    .child(
+                        IconButton::new(
+                            ("rename-bookmark", bookmark_id.to_usize()),
+                            IconName::Pencil,
+                        )
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::XSmall)
+                        .tooltip(|cx| Tooltip::text("Edit Label", cx))
+                        .on_click(cx.listener(move |this, _, cx| {
+                            this.edit_label(bookmark_id, cx);
+                        })),
+                    )
+                    .child(
+                        IconButton::new(
+                            ("remove-bookmark", bookmark_id.to_usize()),
+                            IconName::Close,
+                        )
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::XSmall)
+                        .tooltip(|cx| Tooltip::text("Remove Bookmark", cx))
+                        .on_click(cx.listener(move |this, _, cx| {
+                            let store = this.project.read(cx).bookmark_store().clone();
+                            store.update(cx, |store, cx| store.remove_bookmark(bookmark_id, cx));
+                        })),
+                    ),
+            )
+            .on_click(cx.listener(move |this, _, cx| {
+                this.open_bookmark(bookmark_id, cx);
+            }))
+    }
+}
+
+impl Panel for BookmarksPanel {
+    fn persistent_name() -> &'static str {
+        "Bookmarks Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        match BookmarksPanelSettings::get_global(cx).dock {
+            BookmarksPanelDockPosition::Left => DockPosition::Left,
+            BookmarksPanelDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<BookmarksPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| {
+                let dock = match position {
+                    DockPosition::Left | DockPosition::Bottom => BookmarksPanelDockPosition::Left,
+                    DockPosition::Right => BookmarksPanelDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| BookmarksPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        BookmarksPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::Bookmark)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Bookmarks Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _: &WindowContext) -> bool {
+        self.active
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        self.active = active;
+        self.serialize(cx);
+    }
+}
+
+impl FocusableView for BookmarksPanel {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for BookmarksPanel {}
+
+impl Render for BookmarksPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let store = self.project.read(cx).bookmark_store().clone();
+        let mut groups = Vec::<(Arc<Path>, Vec<_>)>::new();
+        for (bookmark, row) in store.read(cx).sorted_bookmarks(cx) {
+            let line_text = store.read(cx).buffer(bookmark).map(|buffer| {
+                let buffer = buffer.read(cx);
+                let row = row.min(buffer.max_point().row);
+                buffer
+                    .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+                    .collect::<String>()
+                    .trim()
+                    .to_string()
+            });
+            let entry = (
+                bookmark.id,
+                row,
+                bookmark.label.clone(),
+                bookmark.mark,
+                line_text,
+            );
+            match groups.last_mut() {
+                Some((abs_path, entries)) if *abs_path == bookmark.abs_path => entries.push(entry),
+                _ => groups.push((bookmark.abs_path.clone(), vec![entry])),
+            }
+        }
+
+        let panel = v_flex()
+            .id("bookmarks-panel")
+            .key_context(self.dispatch_context(cx))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::rename_bookmark))
+            .on_action(cx.listener(Self::remove_bookmark))
+            .on_action(cx.listener(Self::clear_bookmarks))
+            .track_focus(&self.focus_handle)
+            .size_full();
+
+        if groups.is_empty() {
+            return panel
+                .justify_center()
+                .p_4()
+                .child(h_flex().justify_center().child(Label::new("No bookmarks")))
+                .child(
+                    h_flex().justify_center().child(
+                        Label::new("Toggle a bookmark on a line of an editor to add it here.")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+                );
+        }
+
+        panel
+            .overflow_y_scroll()
+            .py_1()
+            .children(groups.into_iter().map(|(abs_path, entries)| {
+                v_flex()
+                    .child(
+                        h_flex().px_2().py_1().child(
+                            Label::new(self.display_path(&abs_path, cx))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .children(entries.into_iter().map(
+                        |(bookmark_id, row, label, mark, line_text)| {
+                            self.render_bookmark(bookmark_id, row, label, mark, line_text, cx)
+                        },
+                    ))
+            }))
+    }
+}
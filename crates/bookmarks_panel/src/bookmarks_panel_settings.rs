@@ -0,0 +1,47 @@
+use gpui::Pixels;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BookmarksPanelDockPosition {
+    Left,
+    Right,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct BookmarksPanelSettings {
+    pub button: bool,
+    pub default_width: Pixels,
+    pub dock: BookmarksPanelDockPosition,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct BookmarksPanelSettingsContent {
+    /// Whether to show the bookmarks panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Customise default width (in pixels) taken by bookmarks panel
+    ///
+    /// Default: 240
+    pub default_width: Option<f32>,
+    /// The position of bookmarks panel
+    ///
+    /// Default: right
+    pub dock: Option<BookmarksPanelDockPosition>,
+}
+
+impl Settings for BookmarksPanelSettings {
+    const KEY: Option<&'static str> = Some("bookmarks_panel");
+
+    type FileContent = BookmarksPanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
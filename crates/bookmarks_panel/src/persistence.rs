@@ -0,0 +1,79 @@
+use anyhow::Result;
+use db::{define_connection, query, sqlez_macros::sql};
+use project::bookmark_store::SerializedBookmark;
+use std::path::PathBuf;
+use workspace::{WorkspaceDb, WorkspaceId};
+
+define_connection! {
+    pub static ref BOOKMARKS_DB: BookmarksDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE bookmarks (
+                workspace_id INTEGER NOT NULL,
+                abs_path BLOB NOT NULL,
+                row INTEGER NOT NULL,
+                label TEXT,
+                mark TEXT,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        ),
+        sql!(
+            ALTER TABLE bookmarks ADD COLUMN col INTEGER NOT NULL DEFAULT 0;
+        )];
+}
+
+impl BookmarksDb {
+    /// Replaces the stored bookmarks of a workspace.
+    pub async fn save_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+        bookmarks: Vec<SerializedBookmark>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_bookmarks", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM bookmarks WHERE workspace_id = ?
+                ))?(workspace_id)?;
+                for bookmark in bookmarks {
+                    conn.exec_bound(sql!(
+                        INSERT INTO bookmarks(workspace_id, abs_path, row, col, label, mark)
+                        VALUES (?, ?, ?, ?, ?, ?)
+                    ))?((
+                        workspace_id,
+                        bookmark.abs_path,
+                        bookmark.row,
+                        bookmark.column,
+                        bookmark.label,
+                        bookmark.mark.map(String::from),
+                    ))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    pub fn bookmarks(&self, workspace_id: WorkspaceId) -> Result<Vec<SerializedBookmark>> {
+        Ok(self
+            .bookmark_rows(workspace_id)?
+            .into_iter()
+            .map(|(abs_path, row, column, label, mark)| SerializedBookmark {
+                abs_path: abs_path.into(),
+                row,
+                column,
+                label,
+                mark: mark.and_then(|mark| mark.chars().next()),
+            })
+            .collect())
+    }
+
+    query! {
+        fn bookmark_rows(
+            workspace_id: WorkspaceId
+        ) -> Result<Vec<(PathBuf, u32, u32, Option<String>, Option<String>)>> {
+            SELECT abs_path, row, col, label, mark
+            FROM bookmarks
+            WHERE workspace_id = ?
+        }
+    }
+}
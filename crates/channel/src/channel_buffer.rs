@@ -1,15 +1,16 @@
 use crate::{Channel, ChannelStore};
-use anyhow::Result;
-use client::{ChannelId, Client, Collaborator, UserStore, ZED_ALWAYS_ACTIVE};
-use collections::HashMap;
+use anyhow::{anyhow, Result};
+use client::{ChannelId, Client, Collaborator, User, UserStore, ZED_ALWAYS_ACTIVE};
+use collections::{HashMap, HashSet};
 use gpui::{AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task};
 use language::proto::serialize_version;
 use rpc::{
     proto::{self, PeerId},
     TypedEnvelope,
 };
-use std::{sync::Arc, time::Duration};
+use std::{ops::Range, sync::Arc, time::Duration};
 use text::BufferId;
+use time::OffsetDateTime;
 use util::ResultExt;
 
 pub const ACKNOWLEDGE_DEBOUNCE_INTERVAL: Duration = Duration::from_millis(250);
@@ -32,6 +33,25 @@ pub struct ChannelBuffer {
     acknowledge_task: Option<Task<Result<()>>>,
 }
 
+/// A point in the history of a channel buffer, identified by the last operation it includes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelBufferRevision {
+    pub epoch: u64,
+    pub lamport_timestamp: u32,
+    pub replica_id: u32,
+    pub author: Option<Arc<User>>,
+    pub timestamp: Option<OffsetDateTime>,
+}
+
+/// The contents of a channel buffer at a given revision, along with the
+/// collaborator who wrote each range of it.
+#[derive(Clone, Debug)]
+pub struct ChannelBufferRevisionContent {
+    pub revision: Option<ChannelBufferRevision>,
+    pub text: String,
+    pub authorship: Vec<(Range<usize>, Option<Arc<User>>)>,
+}
+
 pub enum ChannelBufferEvent {
     CollaboratorsChanged,
     Disconnected,
@@ -223,6 +243,134 @@ impl ChannelBuffer {
         }));
     }
 
+    /// Loads the revisions of this buffer, oldest first.
+    pub fn load_history(
+        &self,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<ChannelBufferRevision>>> {
+        let request = self.client.request(proto::GetChannelBufferHistory {
+            channel_id: self.channel_id.0,
+        });
+        let user_store = self.user_store.clone();
+        cx.spawn(|_, mut cx| async move {
+            let response = request.await?;
+            let users = load_authors(
+                response
+                    .revisions
+                    .iter()
+                    .filter_map(|revision| revision.user_id),
+                &user_store,
+                &mut cx,
+            )
+            .await?;
+            response
+                .revisions
+                .into_iter()
+                .map(|revision| ChannelBufferRevision::from_proto(revision, &users))
+                .collect()
+        })
+    }
+
+    /// Reconstructs the contents of this buffer as of the given revision.
+    pub fn load_revision(
+        &self,
+        revision: &ChannelBufferRevision,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ChannelBufferRevisionContent>> {
+        self.load_revision_at(
+            proto::get_channel_buffer_revision::At::Revision(revision.to_proto()),
+            cx,
+        )
+    }
+
+    /// Reconstructs the contents of this buffer as of the start of the given epoch.
+    pub fn load_epoch(
+        &self,
+        epoch: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ChannelBufferRevisionContent>> {
+        self.load_revision_at(proto::get_channel_buffer_revision::At::Epoch(epoch), cx)
+    }
+
+    /// Reconstructs the contents of this buffer as they were at the given time.
+    pub fn load_timestamp(
+        &self,
+        timestamp: OffsetDateTime,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ChannelBufferRevisionContent>> {
+        self.load_revision_at(
+            proto::get_channel_buffer_revision::At::Timestamp(
+                timestamp.unix_timestamp().max(0) as u64
+            ),
+            cx,
+        )
+    }
+
+    fn load_revision_at(
+        &self,
+        at: proto::get_channel_buffer_revision::At,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ChannelBufferRevisionContent>> {
+        let request = self.client.request(proto::GetChannelBufferRevision {
+            channel_id: self.channel_id.0,
+            at: Some(at),
+        });
+        let user_store = self.user_store.clone();
+        cx.spawn(|_, mut cx| async move {
+            let response = request.await?;
+            let users = load_authors(
+                response
+                    .revision
+                    .iter()
+                    .filter_map(|revision| revision.user_id)
+                    .chain(response.authorship.iter().filter_map(|range| range.user_id)),
+                &user_store,
+                &mut cx,
+            )
+            .await?;
+            Ok(ChannelBufferRevisionContent {
+                revision: response
+                    .revision
+                    .map(|revision| ChannelBufferRevision::from_proto(revision, &users))
+                    .transpose()?,
+                text: response.text,
+                authorship: response
+                    .authorship
+                    .into_iter()
+                    .map(|range| {
+                        (
+                            range.start as usize..range.end as usize,
+                            range
+                                .user_id
+                                .and_then(|user_id| users.get(&user_id).cloned()),
+                        )
+                    })
+                    .collect(),
+            })
+        })
+    }
+
+    /// Replaces the contents of this buffer with an earlier version's text, as a
+    /// regular edit that is shared with the other collaborators.
+    pub fn restore_revision(
+        &mut self,
+        content: &ChannelBufferRevisionContent,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if !self.connected || self.buffer.read(cx).read_only() {
+            return Task::ready(Err(anyhow!("cannot edit these channel notes")));
+        }
+        let buffer = self.buffer.clone();
+        let diff = buffer.read(cx).diff(content.text.clone(), cx);
+        cx.spawn(|_, mut cx| async move {
+            let diff = diff.await;
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.apply_diff(diff, cx);
+            })?;
+            Ok(())
+        })
+    }
+
     pub fn epoch(&self) -> u64 {
         self.buffer_epoch
     }
@@ -265,3 +413,47 @@ impl ChannelBuffer {
         self.buffer.read(cx).replica_id()
     }
 }
+
+impl ChannelBufferRevision {
+    fn from_proto(
+        revision: proto::ChannelBufferRevision,
+        users: &HashMap<u64, Arc<User>>,
+    ) -> Result<Self> {
+        Ok(Self {
+            epoch: revision.epoch,
+            lamport_timestamp: revision.lamport_timestamp,
+            replica_id: revision.replica_id,
+            author: revision
+                .user_id
+                .and_then(|user_id| users.get(&user_id).cloned()),
+            timestamp: revision
+                .timestamp
+                .map(|timestamp| OffsetDateTime::from_unix_timestamp(timestamp as i64))
+                .transpose()?,
+        })
+    }
+
+    fn to_proto(&self) -> proto::ChannelBufferRevision {
+        proto::ChannelBufferRevision {
+            epoch: self.epoch,
+            lamport_timestamp: self.lamport_timestamp,
+            replica_id: self.replica_id,
+            user_id: self.author.as_ref().map(|author| author.id),
+            timestamp: self
+                .timestamp
+                .map(|timestamp| timestamp.unix_timestamp() as u64),
+        }
+    }
+}
+
+async fn load_authors(
+    user_ids: impl Iterator<Item = u64>,
+    user_store: &Model<UserStore>,
+    cx: &mut AsyncAppContext,
+) -> Result<HashMap<u64, Arc<User>>> {
+    let user_ids = user_ids.collect::<HashSet<_>>().into_iter().collect();
+    let users = user_store
+        .update(cx, |user_store, cx| user_store.get_users(user_ids, cx))?
+        .await?;
+    Ok(users.into_iter().map(|user| (user.id, user)).collect())
+}
@@ -5,7 +5,7 @@ use client::{
     user::{User, UserStore},
     ChannelId, Client, Subscription, TypedEnvelope, UserId,
 };
-use collections::HashSet;
+use collections::{HashMap, HashSet};
 use futures::lock::Mutex;
 use gpui::{
     AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task, WeakModel,
@@ -28,6 +28,9 @@ pub struct ChannelChat {
     last_acknowledged_id: Option<u64>,
     next_pending_message_id: usize,
     first_loaded_message_id: Option<u64>,
+    /// The replies in the threads that were loaded, by the id of the message that
+    /// started the thread.
+    threads: HashMap<u64, Vec<ChannelMessage>>,
     user_store: Model<UserStore>,
     rpc: Arc<Client>,
     outgoing_messages_lock: Arc<Mutex<()>>,
@@ -52,6 +55,23 @@ pub struct ChannelMessage {
     pub mentions: Vec<(Range<usize>, UserId)>,
     pub reply_to_message_id: Option<u64>,
     pub edited_at: Option<OffsetDateTime>,
+    /// The message that started the thread this message is a reply in.
+    pub thread_parent_id: Option<u64>,
+    pub thread_reply_count: usize,
+    pub reactions: Vec<ChannelMessageReaction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelMessageReaction {
+    pub emoji: String,
+    pub user_ids: Vec<UserId>,
+}
+
+/// A channel message found by [`ChannelStore::search_messages`].
+#[derive(Clone, Debug)]
+pub struct ChannelMessageSearchResult {
+    pub channel_id: ChannelId,
+    pub message: ChannelMessage,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -92,6 +112,9 @@ pub enum ChannelChatEvent {
         channel_id: ChannelId,
         message_id: u64,
     },
+    ThreadUpdated {
+        thread_parent_id: u64,
+    },
 }
 
 impl EventEmitter<ChannelChatEvent> for ChannelChat {}
@@ -99,6 +122,7 @@ pub fn init(client: &Arc<Client>) {
     client.add_model_message_handler(ChannelChat::handle_message_sent);
     client.add_model_message_handler(ChannelChat::handle_message_removed);
     client.add_model_message_handler(ChannelChat::handle_message_updated);
+    client.add_model_message_handler(ChannelChat::handle_reactions_updated);
 }
 
 impl ChannelChat {
@@ -133,6 +157,7 @@ impl ChannelChat {
                 last_acknowledged_id: None,
                 rng: StdRng::from_entropy(),
                 first_loaded_message_id: None,
+                threads: Default::default(),
                 _subscription: subscription.set_model(&cx.handle(), &mut cx.to_async()),
             }
         })?;
@@ -196,6 +221,9 @@ impl ChannelChat {
                     nonce,
                     reply_to_message_id: message.reply_to_message_id,
                     edited_at: None,
+                    thread_parent_id: None,
+                    thread_reply_count: 0,
+                    reactions: Vec::new(),
                 },
                 &(),
             ),
@@ -214,6 +242,7 @@ impl ChannelChat {
                 nonce: Some(nonce.into()),
                 mentions: mentions_to_proto(&message.mentions),
                 reply_to_message_id: message.reply_to_message_id,
+                thread_parent_id: None,
             });
             let response = request.await?;
             drop(outgoing_message_guard);
@@ -231,14 +260,17 @@ impl ChannelChat {
     }
 
     pub fn remove_message(&mut self, id: u64, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let thread_parent_id = self.thread_parent_id_for_reply(id);
         let response = self.rpc.request(proto::RemoveChannelMessage {
             channel_id: self.channel_id.0,
             message_id: id,
+            thread_parent_id,
         });
         cx.spawn(move |this, mut cx| async move {
             response.await?;
-            this.update(&mut cx, |this, cx| {
-                this.message_removed(id, cx);
+            this.update(&mut cx, |this, cx| match thread_parent_id {
+                Some(thread_parent_id) => this.thread_reply_removed(thread_parent_id, id, cx),
+                None => this.message_removed(id, cx),
             })?;
             Ok(())
         })
@@ -250,13 +282,22 @@ impl ChannelChat {
         message: MessageParams,
         cx: &mut ModelContext<Self>,
     ) -> Result<Task<Result<()>>> {
-        self.message_update(
-            ChannelMessageId::Saved(id),
-            message.text.clone(),
-            message.mentions.clone(),
-            Some(OffsetDateTime::now_utc()),
-            cx,
-        );
+        let edited_at = Some(OffsetDateTime::now_utc());
+        if let Some(thread_parent_id) = self.thread_parent_id_for_reply(id) {
+            self.thread_reply_updated(thread_parent_id, id, cx, |reply| {
+                reply.body = message.text.clone();
+                reply.mentions = message.mentions.clone();
+                reply.edited_at = edited_at;
+            });
+        } else {
+            self.message_update(
+                ChannelMessageId::Saved(id),
+                message.text.clone(),
+                message.mentions.clone(),
+                edited_at,
+                cx,
+            );
+        }
 
         let nonce: u128 = self.rng.gen();
 
@@ -273,6 +314,104 @@ impl ChannelChat {
         }))
     }
 
+    /// Replies in the thread started by the given message.
+    pub fn send_thread_reply(
+        &mut self,
+        thread_parent_id: u64,
+        message: MessageParams,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<Task<Result<u64>>> {
+        if message.text.trim().is_empty() {
+            Err(anyhow!("message body can't be empty"))?;
+        }
+
+        let user_store = self.user_store.clone();
+        let request = self.rpc.request(proto::SendChannelMessage {
+            channel_id: self.channel_id.0,
+            body: message.text,
+            nonce: Some(self.rng.gen::<u128>().into()),
+            mentions: mentions_to_proto(&message.mentions),
+            reply_to_message_id: None,
+            thread_parent_id: Some(thread_parent_id),
+        });
+        Ok(cx.spawn(move |this, mut cx| async move {
+            let response = request.await?;
+            let message = response.message.ok_or_else(|| anyhow!("invalid message"))?;
+            let id = message.id;
+            let message = ChannelMessage::from_proto(message, &user_store, &mut cx).await?;
+            this.update(&mut cx, |this, cx| {
+                this.thread_reply_received(thread_parent_id, message, cx)
+            })?;
+            Ok(id)
+        }))
+    }
+
+    /// Loads the replies in the thread started by the given message. They're kept up to
+    /// date until the thread is closed.
+    pub fn load_thread(
+        &mut self,
+        thread_parent_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let user_store = self.user_store.clone();
+        let request = self.rpc.request(proto::GetChannelMessageThread {
+            channel_id: self.channel_id.0,
+            message_id: thread_parent_id,
+        });
+        cx.spawn(move |this, mut cx| async move {
+            let response = request.await?;
+            let replies =
+                ChannelMessage::from_proto_vec(response.messages, &user_store, &mut cx).await?;
+            this.update(&mut cx, |this, cx| {
+                this.threads.insert(thread_parent_id, replies);
+                cx.emit(ChannelChatEvent::ThreadUpdated { thread_parent_id });
+                cx.notify();
+            })
+        })
+    }
+
+    pub fn close_thread(&mut self, thread_parent_id: u64) {
+        self.threads.remove(&thread_parent_id);
+    }
+
+    /// Returns the replies in a thread, if it was loaded.
+    pub fn thread(&self, thread_parent_id: u64) -> Option<&[ChannelMessage]> {
+        self.threads.get(&thread_parent_id).map(Vec::as_slice)
+    }
+
+    /// Adds the current user's reaction to a message, or removes it if they already
+    /// reacted with the same emoji.
+    pub fn toggle_reaction(
+        &mut self,
+        message_id: u64,
+        emoji: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(user_id) = self.user_store.read(cx).current_user().map(|user| user.id) else {
+            return Task::ready(Err(anyhow!("current_user is not present")));
+        };
+        let message = self.find_loaded_message(message_id).or_else(|| {
+            self.threads
+                .values()
+                .flatten()
+                .find(|reply| reply.id == ChannelMessageId::Saved(message_id))
+        });
+        let Some(message) = message else {
+            return Task::ready(Err(anyhow!("no such message")));
+        };
+        let reacted = !message.has_reaction(&emoji, user_id);
+        let request = self.rpc.request(proto::SetChannelMessageReaction {
+            channel_id: self.channel_id.0,
+            message_id,
+            emoji,
+            reacted,
+        });
+        cx.background_executor().spawn(async move {
+            request.await?;
+            Ok(())
+        })
+    }
+
     pub fn load_more_messages(&mut self, cx: &mut ModelContext<Self>) -> Option<Task<Option<()>>> {
         if self.loaded_all_messages {
             return None;
@@ -468,6 +607,7 @@ impl ChannelChat {
                         mentions: mentions_to_proto(&pending_message.mentions),
                         nonce: Some(pending_message.nonce.into()),
                         reply_to_message_id: pending_message.reply_to_message_id,
+                        thread_parent_id: None,
                     });
                     let response = request.await?;
                     let message = ChannelMessage::from_proto(
@@ -539,6 +679,10 @@ impl ChannelChat {
 
         let message = ChannelMessage::from_proto(message, &user_store, &mut cx).await?;
         this.update(&mut cx, |this, cx| {
+            if let Some(thread_parent_id) = message.thread_parent_id {
+                this.thread_reply_received(thread_parent_id, message, cx);
+                return;
+            }
             this.insert_messages(SumTree::from_item(message, &()), cx);
             cx.emit(ChannelChatEvent::NewMessage {
                 channel_id: this.channel_id,
@@ -554,8 +698,11 @@ impl ChannelChat {
         message: TypedEnvelope<proto::RemoveChannelMessage>,
         mut cx: AsyncAppContext,
     ) -> Result<()> {
-        this.update(&mut cx, |this, cx| {
-            this.message_removed(message.payload.message_id, cx)
+        this.update(&mut cx, |this, cx| match message.payload.thread_parent_id {
+            Some(thread_parent_id) => {
+                this.thread_reply_removed(thread_parent_id, message.payload.message_id, cx)
+            }
+            None => this.message_removed(message.payload.message_id, cx),
         })?;
         Ok(())
     }
@@ -574,17 +721,139 @@ impl ChannelChat {
         let message = ChannelMessage::from_proto(message, &user_store, &mut cx).await?;
 
         this.update(&mut cx, |this, cx| {
-            this.message_update(
-                message.id,
-                message.body,
-                message.mentions,
-                message.edited_at,
-                cx,
-            )
+            if let (Some(thread_parent_id), ChannelMessageId::Saved(id)) =
+                (message.thread_parent_id, message.id)
+            {
+                this.thread_reply_updated(thread_parent_id, id, cx, |reply| {
+                    reply.body = message.body;
+                    reply.mentions = message.mentions;
+                    reply.edited_at = message.edited_at;
+                });
+            } else {
+                this.message_update(
+                    message.id,
+                    message.body,
+                    message.mentions,
+                    message.edited_at,
+                    cx,
+                )
+            }
+        })?;
+        Ok(())
+    }
+
+    async fn handle_reactions_updated(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::ChannelMessageReactionsUpdated>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let message_id = message.payload.message_id;
+        let reactions = reactions_from_proto(message.payload.reactions);
+        this.update(&mut cx, |this, cx| {
+            if let Some(thread_parent_id) = this.thread_parent_id_for_reply(message_id) {
+                this.thread_reply_updated(thread_parent_id, message_id, cx, |reply| {
+                    reply.reactions = reactions;
+                });
+            } else {
+                this.saved_message_updated(message_id, cx, |message| {
+                    message.reactions = reactions;
+                });
+            }
         })?;
         Ok(())
     }
 
+    fn thread_parent_id_for_reply(&self, id: u64) -> Option<u64> {
+        self.threads.iter().find_map(|(thread_parent_id, replies)| {
+            replies
+                .iter()
+                .any(|reply| reply.id == ChannelMessageId::Saved(id))
+                .then_some(*thread_parent_id)
+        })
+    }
+
+    fn thread_reply_received(
+        &mut self,
+        thread_parent_id: u64,
+        reply: ChannelMessage,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(replies) = self.threads.get_mut(&thread_parent_id) {
+            if replies.iter().any(|existing| existing.id == reply.id) {
+                return;
+            }
+            let ix = replies.partition_point(|existing| existing.id < reply.id);
+            replies.insert(ix, reply);
+            cx.emit(ChannelChatEvent::ThreadUpdated { thread_parent_id });
+        }
+        self.saved_message_updated(thread_parent_id, cx, |parent| {
+            parent.thread_reply_count += 1;
+        });
+    }
+
+    fn thread_reply_removed(
+        &mut self,
+        thread_parent_id: u64,
+        id: u64,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(replies) = self.threads.get_mut(&thread_parent_id) {
+            replies.retain(|reply| reply.id != ChannelMessageId::Saved(id));
+            cx.emit(ChannelChatEvent::ThreadUpdated { thread_parent_id });
+        }
+        self.saved_message_updated(thread_parent_id, cx, |parent| {
+            parent.thread_reply_count = parent.thread_reply_count.saturating_sub(1);
+        });
+    }
+
+    fn thread_reply_updated(
+        &mut self,
+        thread_parent_id: u64,
+        id: u64,
+        cx: &mut ModelContext<Self>,
+        update: impl FnOnce(&mut ChannelMessage),
+    ) {
+        let Some(replies) = self.threads.get_mut(&thread_parent_id) else {
+            return;
+        };
+        if let Some(reply) = replies
+            .iter_mut()
+            .find(|reply| reply.id == ChannelMessageId::Saved(id))
+        {
+            update(reply);
+            cx.emit(ChannelChatEvent::ThreadUpdated { thread_parent_id });
+            cx.notify();
+        }
+    }
+
+    /// Updates a loaded message of the channel's list of messages in place.
+    fn saved_message_updated(
+        &mut self,
+        id: u64,
+        cx: &mut ModelContext<Self>,
+        update: impl FnOnce(&mut ChannelMessage),
+    ) {
+        let id = ChannelMessageId::Saved(id);
+        let mut cursor = self.messages.cursor::<ChannelMessageId>();
+        let mut messages = cursor.slice(&id, Bias::Left, &());
+        let ix = messages.summary().count;
+        let Some(mut message) = cursor.item().filter(|message| message.id == id).cloned() else {
+            return;
+        };
+        update(&mut message);
+        messages.push(message, &());
+        cursor.next(&());
+        messages.append(cursor.suffix(&()), &());
+        drop(cursor);
+        self.messages = messages;
+
+        cx.emit(ChannelChatEvent::UpdateMessage {
+            message_id: id,
+            message_ix: ix,
+        });
+        cx.notify();
+    }
+
     fn insert_messages(&mut self, messages: SumTree<ChannelMessage>, cx: &mut ModelContext<Self>) {
         if let Some((first_message, last_message)) = messages.first().zip(messages.last()) {
             let nonces = messages
@@ -759,6 +1028,9 @@ impl ChannelMessage {
                 .into(),
             reply_to_message_id: message.reply_to_message_id,
             edited_at,
+            thread_parent_id: message.thread_parent_id,
+            thread_reply_count: message.thread_reply_count as usize,
+            reactions: reactions_from_proto(message.reactions),
         })
     }
 
@@ -766,6 +1038,12 @@ impl ChannelMessage {
         matches!(self.id, ChannelMessageId::Pending(_))
     }
 
+    pub fn has_reaction(&self, emoji: &str, user_id: UserId) -> bool {
+        self.reactions
+            .iter()
+            .any(|reaction| reaction.emoji == emoji && reaction.user_ids.contains(&user_id))
+    }
+
     pub async fn from_proto_vec(
         proto_messages: Vec<proto::ChannelMessage>,
         user_store: &Model<UserStore>,
@@ -791,6 +1069,18 @@ impl ChannelMessage {
     }
 }
 
+fn reactions_from_proto(
+    reactions: Vec<proto::ChannelMessageReaction>,
+) -> Vec<ChannelMessageReaction> {
+    reactions
+        .into_iter()
+        .map(|reaction| ChannelMessageReaction {
+            emoji: reaction.emoji,
+            user_ids: reaction.user_ids,
+        })
+        .collect()
+}
+
 pub fn mentions_to_proto(mentions: &[(Range<usize>, UserId)]) -> Vec<proto::ChatMention> {
     mentions
         .iter()
@@ -1,6 +1,9 @@
 mod channel_index;
 
-use crate::{channel_buffer::ChannelBuffer, channel_chat::ChannelChat, ChannelMessage};
+use crate::{
+    channel_buffer::ChannelBuffer, channel_chat::ChannelChat, ChannelMessage,
+    ChannelMessageSearchResult,
+};
 use anyhow::{anyhow, Result};
 use channel_index::ChannelIndex;
 use client::{ChannelId, Client, ClientSettings, ProjectId, Subscription, User, UserId, UserStore};
@@ -382,6 +385,40 @@ impl ChannelStore {
         })
     }
 
+    /// Searches the messages of all the channels the user can see, or of a single channel.
+    pub fn search_messages(
+        &self,
+        query: String,
+        channel_id: Option<ChannelId>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<ChannelMessageSearchResult>>> {
+        let request = self.client.request(proto::SearchChannelMessages {
+            query,
+            channel_id: channel_id.map(|channel_id| channel_id.0),
+        });
+        let user_store = self.user_store.clone();
+        cx.spawn(|_, mut cx| async move {
+            let response = request.await?;
+            let mut channel_ids = Vec::with_capacity(response.results.len());
+            let mut messages = Vec::with_capacity(response.results.len());
+            for result in response.results {
+                if let Some(message) = result.message {
+                    channel_ids.push(ChannelId(result.channel_id));
+                    messages.push(message);
+                }
+            }
+            let messages = ChannelMessage::from_proto_vec(messages, &user_store, &mut cx).await?;
+            Ok(channel_ids
+                .into_iter()
+                .zip(messages)
+                .map(|(channel_id, message)| ChannelMessageSearchResult {
+                    channel_id,
+                    message,
+                })
+                .collect())
+        })
+    }
+
     pub fn has_channel_buffer_changed(&self, channel_id: ChannelId) -> bool {
         self.channel_states
             .get(&channel_id)
@@ -37,6 +37,8 @@ struct Args {
     ///
     /// Use `path:line:row` syntax to open a file at a specific location.
     /// Non-existing paths and directories will ignore `:line:row` suffix.
+    ///
+    /// Use `ssh://[user@]host[:port]/path` to open a project on another machine over ssh.
     #[arg(value_parser = parse_path_with_position)]
     paths_with_position: Vec<PathLikeWithPosition<PathBuf>>,
     /// Print Zed's version and the app path.
@@ -56,6 +58,13 @@ struct Args {
 fn parse_path_with_position(
     argument_str: &str,
 ) -> Result<PathLikeWithPosition<PathBuf>, std::convert::Infallible> {
+    if argument_str.starts_with("ssh://") {
+        return Ok(PathLikeWithPosition {
+            path_like: PathBuf::from(argument_str),
+            row: None,
+            column: None,
+        });
+    }
     PathLikeWithPosition::parse_str(argument_str, |_, path_str| {
         Ok(Path::new(path_str).to_path_buf())
     })
@@ -94,6 +103,14 @@ fn main() -> Result<()> {
     let curdir = env::current_dir()?;
     let mut paths = vec![];
     for path in args.paths_with_position {
+        if let Some(url) = path
+            .path_like
+            .to_str()
+            .filter(|path| path.starts_with("ssh://"))
+        {
+            paths.push(url.to_string());
+            continue;
+        }
         let canonicalized = path.map_path_like(|path| match fs::canonicalize(&path) {
             Ok(path) => Ok(path),
             Err(e) => {
@@ -286,6 +286,7 @@ impl Status {
 struct ClientState {
     credentials: Option<Credentials>,
     status: (watch::Sender<Status>, watch::Receiver<Status>),
+    connected_directly: bool,
     entity_id_extractors: HashMap<TypeId, fn(&dyn AnyTypedEnvelope) -> u64>,
     _reconnect_task: Option<Task<()>>,
     entities_by_type_and_remote_id: HashMap<(TypeId, u64), WeakSubscriber>,
@@ -361,6 +362,7 @@ impl Default for ClientState {
         Self {
             credentials: None,
             status: watch::channel_with(Status::SignedOut),
+            connected_directly: false,
             entity_id_extractors: Default::default(),
             _reconnect_task: None,
             models_by_message_type: Default::default(),
@@ -620,6 +622,9 @@ impl Client {
             Status::Connected { .. } => {
                 state._reconnect_task = None;
             }
+            Status::ConnectionLost if state.connected_directly => {
+                state._reconnect_task = None;
+            }
             Status::ConnectionLost => {
                 let this = self.clone();
                 state._reconnect_task = Some(cx.spawn(move |cx| async move {
@@ -966,6 +971,22 @@ impl Client {
         }
     }
 
+    /// Connects to a peer other than the collaboration server, such as a host reached over
+    /// SSH, without authenticating. The client doesn't reconnect when the connection is lost.
+    pub async fn connect_with(
+        self: &Arc<Self>,
+        connection: Connection,
+        cx: &AsyncAppContext,
+    ) -> Result<()> {
+        self.state.write().connected_directly = true;
+        self.set_status(Status::Connecting, cx);
+        let result = self.set_connection(connection, cx).await;
+        if result.is_err() {
+            self.set_status(Status::ConnectionError, cx);
+        }
+        result
+    }
+
     async fn set_connection(
         self: &Arc<Self>,
         conn: Connection,
@@ -1110,6 +1131,10 @@ impl Client {
                 .to_str()
                 .map_err(EstablishConnectionError::other)?
                 .to_string()
+        } else if response.status() == StatusCode::UNAUTHORIZED {
+            // A self-hosted collab server answers `/rpc` itself, rejecting
+            // the request because it isn't authenticated.
+            url
         } else {
             Err(anyhow!(
                 "unexpected /rpc response status {}",
@@ -9,13 +9,20 @@ CREATE TABLE "users" (
     "connected_once" BOOLEAN NOT NULL DEFAULT false,
     "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     "metrics_id" TEXT,
-    "github_user_id" INTEGER
+    "github_user_id" INTEGER,
+    "disabled" BOOLEAN NOT NULL DEFAULT false
 );
 CREATE UNIQUE INDEX "index_users_github_login" ON "users" ("github_login");
 CREATE UNIQUE INDEX "index_invite_code_users" ON "users" ("invite_code");
 CREATE INDEX "index_users_on_email_address" ON "users" ("email_address");
 CREATE INDEX "index_users_on_github_user_id" ON "users" ("github_user_id");
 
+CREATE TABLE "user_passwords" (
+    "user_id" INTEGER PRIMARY KEY NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "hash" VARCHAR(128) NOT NULL,
+    "updated_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
 CREATE TABLE "access_tokens" (
     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
     "user_id" INTEGER REFERENCES users (id),
@@ -222,10 +229,12 @@ CREATE TABLE IF NOT EXISTS "channel_messages" (
     "sent_at" TIMESTAMP,
     "edited_at" TIMESTAMP,
     "nonce" BLOB NOT NULL,
-    "reply_to_message_id" INTEGER DEFAULT NULL
+    "reply_to_message_id" INTEGER DEFAULT NULL,
+    "thread_parent_id" INTEGER REFERENCES channel_messages (id) ON DELETE CASCADE
 );
 CREATE INDEX "index_channel_messages_on_channel_id" ON "channel_messages" ("channel_id");
 CREATE UNIQUE INDEX "index_channel_messages_on_sender_id_nonce" ON "channel_messages" ("sender_id", "nonce");
+CREATE INDEX "index_channel_messages_on_thread_parent_id" ON "channel_messages" ("thread_parent_id");
 
 CREATE TABLE "channel_message_mentions" (
     "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
@@ -235,6 +244,20 @@ CREATE TABLE "channel_message_mentions" (
     PRIMARY KEY(message_id, start_offset)
 );
 
+CREATE TABLE "channel_message_reactions" (
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "emoji" VARCHAR NOT NULL,
+    PRIMARY KEY(message_id, user_id, emoji)
+);
+
+CREATE TABLE "channel_message_search_terms" (
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "term" VARCHAR NOT NULL,
+    PRIMARY KEY(message_id, term)
+);
+CREATE INDEX "index_channel_message_search_terms_on_term" ON "channel_message_search_terms" ("term");
+
 CREATE TABLE "channel_members" (
     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
     "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
@@ -263,6 +286,8 @@ CREATE TABLE "buffer_operations" (
     "replica_id" INTEGER NOT NULL,
     "lamport_timestamp" INTEGER NOT NULL,
     "value" BLOB NOT NULL,
+    "user_id" INTEGER REFERENCES users (id) ON DELETE SET NULL,
+    "created_at" TIMESTAMP,
     PRIMARY KEY(buffer_id, epoch, lamport_timestamp, replica_id)
 );
 
@@ -416,3 +441,27 @@ CREATE TABLE dev_server_projects (
     dev_server_id INTEGER NOT NULL REFERENCES dev_servers(id),
     path TEXT NOT NULL
 );
+
+CREATE TABLE "buffer_comment_threads" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "host_user_id" INTEGER REFERENCES users (id) ON DELETE CASCADE,
+    "dev_server_project_id" INTEGER REFERENCES dev_server_projects (id) ON DELETE CASCADE,
+    "worktree_root" VARCHAR NOT NULL,
+    "path" VARCHAR NOT NULL,
+    "row" INTEGER NOT NULL,
+    "column" INTEGER NOT NULL DEFAULT 0,
+    "anchor_project_id" INTEGER REFERENCES projects (id) ON DELETE SET NULL,
+    "anchor" BLOB,
+    "resolved" BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX "index_buffer_comment_threads_on_host_user_id_and_worktree_root" ON "buffer_comment_threads" ("host_user_id", "worktree_root");
+CREATE INDEX "index_buffer_comment_threads_on_dev_server_project_id_and_worktree_root" ON "buffer_comment_threads" ("dev_server_project_id", "worktree_root");
+
+CREATE TABLE "buffer_comments" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "thread_id" INTEGER NOT NULL REFERENCES buffer_comment_threads (id) ON DELETE CASCADE,
+    "sender_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "sent_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX "index_buffer_comments_on_thread_id" ON "buffer_comments" ("thread_id");
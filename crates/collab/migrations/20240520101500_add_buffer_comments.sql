@@ -0,0 +1,25 @@
+-- Comment threads outlive the projects they were left in: they belong to the worktree
+-- root and path of the file they were left on, on the host that shared it.
+CREATE TABLE "buffer_comment_threads" (
+    "id" SERIAL PRIMARY KEY,
+    "host_user_id" INTEGER REFERENCES users (id) ON DELETE CASCADE,
+    "dev_server_project_id" INTEGER REFERENCES dev_server_projects (id) ON DELETE CASCADE,
+    "worktree_root" VARCHAR NOT NULL,
+    "path" VARCHAR NOT NULL,
+    "row" INTEGER NOT NULL,
+    "column" INTEGER NOT NULL DEFAULT 0,
+    "anchor_project_id" INTEGER REFERENCES projects (id) ON DELETE SET NULL,
+    "anchor" BYTEA,
+    "resolved" BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX "index_buffer_comment_threads_on_host_user_id_and_worktree_root" ON "buffer_comment_threads" ("host_user_id", "worktree_root");
+CREATE INDEX "index_buffer_comment_threads_on_dev_server_project_id_and_worktree_root" ON "buffer_comment_threads" ("dev_server_project_id", "worktree_root");
+
+CREATE TABLE "buffer_comments" (
+    "id" SERIAL PRIMARY KEY,
+    "thread_id" INTEGER NOT NULL REFERENCES buffer_comment_threads (id) ON DELETE CASCADE,
+    "sender_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "sent_at" TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX "index_buffer_comments_on_thread_id" ON "buffer_comments" ("thread_id");
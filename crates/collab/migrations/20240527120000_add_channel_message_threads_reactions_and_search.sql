@@ -0,0 +1,22 @@
+ALTER TABLE "channel_messages" ADD COLUMN "thread_parent_id" INTEGER REFERENCES channel_messages (id) ON DELETE CASCADE;
+CREATE INDEX "index_channel_messages_on_thread_parent_id" ON "channel_messages" ("thread_parent_id");
+
+CREATE TABLE "channel_message_reactions" (
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "emoji" VARCHAR NOT NULL,
+    PRIMARY KEY(message_id, user_id, emoji)
+);
+
+CREATE TABLE "channel_message_search_terms" (
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "term" VARCHAR NOT NULL,
+    PRIMARY KEY(message_id, term)
+);
+CREATE INDEX "index_channel_message_search_terms_on_term" ON "channel_message_search_terms" ("term" varchar_pattern_ops);
+
+INSERT INTO "channel_message_search_terms" ("message_id", "term")
+SELECT DISTINCT "id", left(lower("term"), 64)
+FROM "channel_messages", regexp_split_to_table("body", '[^[:alnum:]]+') AS "term"
+WHERE "term" <> ''
+ON CONFLICT DO NOTHING;
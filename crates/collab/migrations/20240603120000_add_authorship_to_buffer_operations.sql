@@ -0,0 +1,3 @@
+ALTER TABLE "buffer_operations"
+    ADD "user_id" INTEGER REFERENCES users (id) ON DELETE SET NULL,
+    ADD "created_at" TIMESTAMP WITHOUT TIME ZONE;
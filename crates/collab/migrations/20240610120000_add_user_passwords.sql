@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS "user_passwords" (
+    "user_id" INTEGER PRIMARY KEY NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "hash" VARCHAR(128) NOT NULL,
+    "updated_at" TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1 @@
+ALTER TABLE "users" ADD "disabled" BOOLEAN NOT NULL DEFAULT FALSE;
@@ -1,6 +1,8 @@
+pub mod admin;
 pub mod events;
 pub mod extensions;
 pub mod ips_file;
+pub mod local_auth;
 pub mod slack;
 
 use crate::{
@@ -33,6 +35,7 @@ pub fn routes(rpc_server: Option<Arc<rpc::Server>>, state: Arc<AppState>) -> Rou
         .route("/rpc_server_snapshot", get(get_rpc_server_snapshot))
         .route("/contributors", get(get_contributors).post(add_contributor))
         .route("/contributor", get(check_is_contributor))
+        .merge(admin::routes())
         .layer(
             ServiceBuilder::new()
                 .layer(Extension(state))
@@ -208,6 +211,12 @@ async fn create_access_token(
         .get_user_by_id(user_id)
         .await?
         .ok_or_else(|| anyhow!("user not found"))?;
+    if user.disabled {
+        return Err(Error::Http(
+            StatusCode::UNAUTHORIZED,
+            format!("user {user_id} is disabled"),
+        ));
+    }
 
     let mut impersonated_user_id = None;
     if let Some(impersonate) = params.impersonate {
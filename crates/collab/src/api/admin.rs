@@ -0,0 +1,451 @@
+//! Endpoints for administering the server, authenticated with the API token.
+
+use crate::{
+    auth,
+    db::{AccessTokenId, ChannelId, ChannelRole, ChannelVisibility, PrincipalId, User, UserId},
+    rpc::{self, ZedVersion},
+    AppState, Error, Result,
+};
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    routing::{delete, get, post, put},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/admin/users", get(get_users).post(create_user))
+        .route("/admin/users/:id", get(get_user))
+        .route("/admin/users/:id/disable", post(disable_user))
+        .route("/admin/users/:id/enable", post(enable_user))
+        .route(
+            "/admin/users/:id/feature_flags/:flag",
+            put(add_user_feature_flag).delete(remove_user_feature_flag),
+        )
+        .route(
+            "/admin/users/:id/access_tokens",
+            get(get_user_access_tokens).delete(revoke_user_access_tokens),
+        )
+        .route("/admin/access_tokens/:id", delete(revoke_access_token))
+        .route(
+            "/admin/feature_flags",
+            get(get_feature_flags).post(create_feature_flag),
+        )
+        .route("/admin/channels", get(get_channels))
+        .route(
+            "/admin/channels/:id",
+            get(get_channel).delete(delete_channel),
+        )
+        .route(
+            "/admin/channels/:id/members/:user_id",
+            delete(remove_channel_member),
+        )
+        .route("/admin/peers", get(get_peers))
+}
+
+#[derive(Debug, Deserialize)]
+struct GetUsersParams {
+    #[serde(default)]
+    page: u32,
+    limit: Option<u32>,
+}
+
+async fn get_users(
+    Query(params): Query<GetUsersParams>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<User>>> {
+    let limit = params.limit.unwrap_or(100).min(1000);
+    Ok(Json(app.db.get_all_users(params.page, limit).await?))
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateUserParams {
+    login: String,
+    email_address: Option<String>,
+    #[serde(default)]
+    admin: bool,
+    /// When given, the user can sign in with local authentication.
+    password: Option<String>,
+}
+
+async fn create_user(
+    Extension(app): Extension<Arc<AppState>>,
+    Json(params): Json<CreateUserParams>,
+) -> Result<Json<User>> {
+    if app
+        .db
+        .get_user_by_github_login(&params.login)
+        .await?
+        .is_some()
+    {
+        return Err(Error::Http(
+            StatusCode::CONFLICT,
+            format!("user {} already exists", params.login),
+        ));
+    }
+
+    let password_hash = params
+        .password
+        .as_deref()
+        .map(auth::hash_password)
+        .transpose()?;
+    let user = app
+        .db
+        .create_local_user(
+            &params.login,
+            params.email_address.as_deref(),
+            params.admin,
+            password_hash.as_deref(),
+        )
+        .await?;
+    Ok(Json(user))
+}
+
+#[derive(Debug, Serialize)]
+struct UserDetails {
+    user: User,
+    feature_flags: Vec<String>,
+    connection_count: usize,
+}
+
+async fn get_user(
+    Path(user_id): Path<UserId>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Option<Arc<rpc::Server>>>,
+) -> Result<Json<UserDetails>> {
+    let user = find_user(&app, user_id).await?;
+    let feature_flags = app.db.get_user_flags(user_id).await?;
+    let connection_count = rpc_server.map_or(0, |rpc_server| {
+        rpc_server
+            .connection_pool
+            .lock()
+            .user_connection_ids(user_id)
+            .count()
+    });
+    Ok(Json(UserDetails {
+        user,
+        feature_flags,
+        connection_count,
+    }))
+}
+
+async fn disable_user(
+    Path(user_id): Path<UserId>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Option<Arc<rpc::Server>>>,
+) -> Result<Json<User>> {
+    find_user(&app, user_id).await?;
+    app.db.set_user_disabled(user_id, true).await?;
+    if let Some(rpc_server) = rpc_server {
+        rpc_server.disconnect_user(user_id);
+    }
+    Ok(Json(find_user(&app, user_id).await?))
+}
+
+async fn enable_user(
+    Path(user_id): Path<UserId>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<User>> {
+    find_user(&app, user_id).await?;
+    app.db.set_user_disabled(user_id, false).await?;
+    Ok(Json(find_user(&app, user_id).await?))
+}
+
+#[derive(Debug, Serialize)]
+struct FeatureFlag {
+    id: i32,
+    flag: String,
+}
+
+async fn get_feature_flags(
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<FeatureFlag>>> {
+    let flags = app.db.get_feature_flags().await?;
+    Ok(Json(
+        flags
+            .into_iter()
+            .map(|flag| FeatureFlag {
+                id: flag.id.0,
+                flag: flag.flag,
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateFeatureFlagParams {
+    flag: String,
+}
+
+async fn create_feature_flag(
+    Extension(app): Extension<Arc<AppState>>,
+    Json(params): Json<CreateFeatureFlagParams>,
+) -> Result<Json<FeatureFlag>> {
+    if app.db.get_feature_flag(&params.flag).await?.is_some() {
+        return Err(Error::Http(
+            StatusCode::CONFLICT,
+            format!("feature flag {} already exists", params.flag),
+        ));
+    }
+
+    let id = app.db.create_user_flag(&params.flag).await?;
+    Ok(Json(FeatureFlag {
+        id: id.0,
+        flag: params.flag,
+    }))
+}
+
+async fn add_user_feature_flag(
+    Path((user_id, flag)): Path<(UserId, String)>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<String>>> {
+    find_user(&app, user_id).await?;
+    let flag = find_feature_flag(&app, &flag).await?;
+    if !app.db.get_user_flags(user_id).await?.contains(&flag.flag) {
+        app.db.add_user_flag(user_id, flag.id).await?;
+    }
+    Ok(Json(app.db.get_user_flags(user_id).await?))
+}
+
+async fn remove_user_feature_flag(
+    Path((user_id, flag)): Path<(UserId, String)>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<String>>> {
+    find_user(&app, user_id).await?;
+    let flag = find_feature_flag(&app, &flag).await?;
+    app.db.remove_user_flag(user_id, flag.id).await?;
+    Ok(Json(app.db.get_user_flags(user_id).await?))
+}
+
+#[derive(Debug, Serialize)]
+struct AccessToken {
+    id: AccessTokenId,
+    impersonated_user_id: Option<UserId>,
+}
+
+async fn get_user_access_tokens(
+    Path(user_id): Path<UserId>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<AccessToken>>> {
+    find_user(&app, user_id).await?;
+    let tokens = app.db.get_access_tokens_for_user(user_id).await?;
+    Ok(Json(
+        tokens
+            .into_iter()
+            .map(|token| AccessToken {
+                id: token.id,
+                impersonated_user_id: token.impersonated_user_id,
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Debug, Serialize)]
+struct RevokeAccessTokensResponse {
+    revoked_count: u64,
+}
+
+async fn revoke_user_access_tokens(
+    Path(user_id): Path<UserId>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<RevokeAccessTokensResponse>> {
+    find_user(&app, user_id).await?;
+    let revoked_count = app.db.delete_access_tokens_for_user(user_id).await?;
+    Ok(Json(RevokeAccessTokensResponse { revoked_count }))
+}
+
+async fn revoke_access_token(
+    Path(access_token_id): Path<AccessTokenId>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<()> {
+    if !app.db.delete_access_token(access_token_id).await? {
+        return Err(Error::Http(
+            StatusCode::NOT_FOUND,
+            format!("access token {access_token_id} not found"),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct Channel {
+    id: ChannelId,
+    name: String,
+    parent_id: Option<ChannelId>,
+    visibility: &'static str,
+    requires_zed_cla: bool,
+}
+
+impl From<crate::db::channel::Model> for Channel {
+    fn from(channel: crate::db::channel::Model) -> Self {
+        Self {
+            id: channel.id,
+            parent_id: channel.parent_id(),
+            visibility: match channel.visibility {
+                ChannelVisibility::Public => "public",
+                ChannelVisibility::Members => "members",
+            },
+            requires_zed_cla: channel.requires_zed_cla,
+            name: channel.name,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GetChannelsParams {
+    #[serde(default)]
+    page: u32,
+    limit: Option<u32>,
+}
+
+async fn get_channels(
+    Query(params): Query<GetChannelsParams>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<Channel>>> {
+    let limit = params.limit.unwrap_or(100).min(1000);
+    let channels = app.db.get_all_channels(params.page, limit).await?;
+    Ok(Json(channels.into_iter().map(Channel::from).collect()))
+}
+
+#[derive(Debug, Serialize)]
+struct ChannelMember {
+    user_id: UserId,
+    role: ChannelRole,
+    accepted: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ChannelDetails {
+    channel: Channel,
+    /// The explicit memberships of the channel. Only root channels have members;
+    /// everyone else inherits their role from the root.
+    members: Vec<ChannelMember>,
+}
+
+async fn get_channel(
+    Path(channel_id): Path<ChannelId>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<ChannelDetails>> {
+    let channel = find_channel(&app, channel_id).await?;
+    let members = app.db.get_channel_memberships(channel_id).await?;
+    Ok(Json(ChannelDetails {
+        channel: channel.into(),
+        members: members
+            .into_iter()
+            .map(|member| ChannelMember {
+                user_id: member.user_id,
+                role: member.role,
+                accepted: member.accepted,
+            })
+            .collect(),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+struct DeleteChannelResponse {
+    deleted_channel_ids: Vec<ChannelId>,
+}
+
+async fn delete_channel(
+    Path(channel_id): Path<ChannelId>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Option<Arc<rpc::Server>>>,
+) -> Result<Json<DeleteChannelResponse>> {
+    find_channel(&app, channel_id).await?;
+    let (root_channel_id, deleted_channel_ids) =
+        app.db.delete_channel_as_server_admin(channel_id).await?;
+    if let Some(rpc_server) = rpc_server {
+        rpc_server.channels_deleted(root_channel_id, &deleted_channel_ids);
+    }
+    Ok(Json(DeleteChannelResponse {
+        deleted_channel_ids,
+    }))
+}
+
+async fn remove_channel_member(
+    Path((channel_id, user_id)): Path<(ChannelId, UserId)>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Option<Arc<rpc::Server>>>,
+) -> Result<()> {
+    find_channel(&app, channel_id).await?;
+    let is_member = app
+        .db
+        .get_channel_memberships(channel_id)
+        .await?
+        .iter()
+        .any(|member| member.user_id == user_id);
+    if !is_member {
+        return Err(Error::Http(
+            StatusCode::NOT_FOUND,
+            format!("user {user_id} is not a member of channel {channel_id}"),
+        ));
+    }
+
+    let result = app
+        .db
+        .remove_channel_member_as_server_admin(channel_id, user_id)
+        .await?;
+    if let Some(rpc_server) = rpc_server {
+        rpc_server.channel_member_removed(user_id, result);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct Peer {
+    connection_id: String,
+    principal_id: PrincipalId,
+    admin: bool,
+    zed_version: ZedVersion,
+}
+
+async fn get_peers(
+    Extension(rpc_server): Extension<Option<Arc<rpc::Server>>>,
+) -> Result<Json<Vec<Peer>>> {
+    let Some(rpc_server) = rpc_server else {
+        return Err(Error::Http(
+            StatusCode::NOT_IMPLEMENTED,
+            "rpc server is not available".into(),
+        ));
+    };
+
+    let pool = rpc_server.connection_pool.lock();
+    Ok(Json(
+        pool.connections_by_id()
+            .map(|(connection_id, connection)| Peer {
+                connection_id: connection_id.to_string(),
+                principal_id: connection.principal_id,
+                admin: connection.admin,
+                zed_version: connection.zed_version,
+            })
+            .collect(),
+    ))
+}
+
+async fn find_user(app: &AppState, user_id: UserId) -> Result<User> {
+    app.db
+        .get_user_by_id(user_id)
+        .await?
+        .ok_or_else(|| Error::Http(StatusCode::NOT_FOUND, format!("user {user_id} not found")))
+}
+
+async fn find_channel(app: &AppState, channel_id: ChannelId) -> Result<crate::db::channel::Model> {
+    app.db.get_channel_by_id(channel_id).await?.ok_or_else(|| {
+        Error::Http(
+            StatusCode::NOT_FOUND,
+            format!("channel {channel_id} not found"),
+        )
+    })
+}
+
+async fn find_feature_flag(app: &AppState, flag: &str) -> Result<crate::db::feature_flag::Model> {
+    app.db.get_feature_flag(flag).await?.ok_or_else(|| {
+        Error::Http(
+            StatusCode::NOT_FOUND,
+            format!("feature flag {flag} not found"),
+        )
+    })
+}
@@ -1,7 +1,6 @@
 use super::ips_file::IpsFile;
 use crate::{api::slack, AppState, Error, Result};
 use anyhow::{anyhow, Context};
-use aws_sdk_s3::primitives::ByteStream;
 use axum::{
     body::Bytes,
     headers::Header,
@@ -111,26 +110,19 @@ pub async fn post_crash(
     }
     let app_version = app_version.unwrap();
 
-    if let Some(blob_store_client) = app.blob_store_client.as_ref() {
-        let response = blob_store_client
-            .head_object()
-            .bucket(CRASH_REPORTS_BUCKET)
-            .key(report.header.incident_id.clone() + ".ips")
-            .send()
-            .await;
-
-        if response.is_ok() {
+    if let Some(blob_store) = app.blob_store.as_ref() {
+        let key = report.header.incident_id.clone() + ".ips";
+        if blob_store
+            .exists(CRASH_REPORTS_BUCKET, &key)
+            .await
+            .unwrap_or(false)
+        {
             log::info!("We've already uploaded this crash");
             return Ok(());
         }
 
-        blob_store_client
-            .put_object()
-            .bucket(CRASH_REPORTS_BUCKET)
-            .key(report.header.incident_id.clone() + ".ips")
-            .acl(aws_sdk_s3::types::ObjectCannedAcl::PublicRead)
-            .body(ByteStream::from(body.to_vec()))
-            .send()
+        blob_store
+            .put_public(CRASH_REPORTS_BUCKET, &key, body.to_vec())
             .await
             .map_err(|e| log::error!("Failed to upload crash: {}", e))
             .ok();
@@ -248,14 +240,13 @@ pub async fn post_hang(
     let incident_id = Uuid::new_v4().to_string();
 
     // dump JSON into S3 so we can get frame offsets if we need to.
-    if let Some(blob_store_client) = app.blob_store_client.as_ref() {
-        blob_store_client
-            .put_object()
-            .bucket(CRASH_REPORTS_BUCKET)
-            .key(incident_id.clone() + ".hang.json")
-            .acl(aws_sdk_s3::types::ObjectCannedAcl::PublicRead)
-            .body(ByteStream::from(body.to_vec()))
-            .send()
+    if let Some(blob_store) = app.blob_store.as_ref() {
+        blob_store
+            .put_public(
+                CRASH_REPORTS_BUCKET,
+                &(incident_id.clone() + ".hang.json"),
+                body.to_vec(),
+            )
             .await
             .map_err(|e| log::error!("Failed to upload crash: {}", e))
             .ok();
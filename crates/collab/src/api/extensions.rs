@@ -1,11 +1,10 @@
 use crate::db::ExtensionVersionConstraints;
-use crate::{db::NewExtensionVersion, AppState, Error, Result};
+use crate::{blob_store::BlobStore, db::NewExtensionVersion, AppState, Error, Result};
 use anyhow::{anyhow, Context as _};
-use aws_sdk_s3::presigning::PresigningConfig;
 use axum::{
     extract::{Path, Query},
-    http::StatusCode,
-    response::Redirect,
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
     routing::get,
     Extension, Json, Router,
 };
@@ -130,7 +129,7 @@ async fn download_latest_extension(
     Extension(app): Extension<Arc<AppState>>,
     Path(params): Path<DownloadLatestExtensionPathParams>,
     Query(query): Query<DownloadLatestExtensionQueryParams>,
-) -> Result<Redirect> {
+) -> Result<Response> {
     let constraints = maybe!({
         let min_schema_version = query.min_schema_version?;
         let max_schema_version = query.max_schema_version?;
@@ -167,9 +166,9 @@ struct DownloadExtensionParams {
 async fn download_extension(
     Extension(app): Extension<Arc<AppState>>,
     Path(params): Path<DownloadExtensionParams>,
-) -> Result<Redirect> {
-    let Some((blob_store_client, bucket)) = app
-        .blob_store_client
+) -> Result<Response> {
+    let Some((blob_store, bucket)) = app
+        .blob_store
         .clone()
         .zip(app.config.blob_store_bucket.clone())
     else {
@@ -196,25 +195,24 @@ async fn download_extension(
         ))?;
     }
 
-    let url = blob_store_client
-        .get_object()
-        .bucket(bucket)
-        .key(format!(
-            "extensions/{extension_id}/{version}/archive.tar.gz"
-        ))
-        .presigned(PresigningConfig::expires_in(EXTENSION_DOWNLOAD_URL_LIFETIME).unwrap())
-        .await
-        .map_err(|e| anyhow!("failed to create presigned extension download url {e}"))?;
+    let key = format!("extensions/{extension_id}/{version}/archive.tar.gz");
+    if let Some(url) = blob_store
+        .download_url(&bucket, &key, EXTENSION_DOWNLOAD_URL_LIFETIME)
+        .await?
+    {
+        return Ok(Redirect::temporary(&url).into_response());
+    }
 
-    Ok(Redirect::temporary(url.uri()))
+    let archive = blob_store.get(&bucket, &key).await?;
+    Ok(([(header::CONTENT_TYPE, "application/gzip")], archive.bytes).into_response())
 }
 
 const EXTENSION_FETCH_INTERVAL: Duration = Duration::from_secs(5 * 60);
 const EXTENSION_DOWNLOAD_URL_LIFETIME: Duration = Duration::from_secs(3 * 60);
 
 pub fn fetch_extensions_from_blob_store_periodically(app_state: Arc<AppState>) {
-    let Some(blob_store_client) = app_state.blob_store_client.clone() else {
-        log::info!("no blob store client");
+    let Some(blob_store) = app_state.blob_store.clone() else {
+        log::info!("no blob store");
         return;
     };
     let Some(blob_store_bucket) = app_state.config.blob_store_bucket.clone() else {
@@ -227,66 +225,43 @@ pub fn fetch_extensions_from_blob_store_periodically(app_state: Arc<AppState>) {
         let executor = executor.clone();
         async move {
             loop {
-                fetch_extensions_from_blob_store(
-                    &blob_store_client,
-                    &blob_store_bucket,
-                    &app_state,
-                )
-                .await
-                .log_err();
+                fetch_extensions_from_blob_store(&blob_store, &blob_store_bucket, &app_state)
+                    .await
+                    .log_err();
                 executor.sleep(EXTENSION_FETCH_INTERVAL).await;
             }
         }
     });
 }
 
-async fn fetch_extensions_from_blob_store(
-    blob_store_client: &aws_sdk_s3::Client,
-    blob_store_bucket: &String,
+pub async fn fetch_extensions_from_blob_store(
+    blob_store: &BlobStore,
+    blob_store_bucket: &str,
     app_state: &Arc<AppState>,
 ) -> anyhow::Result<()> {
     log::info!("fetching extensions from blob store");
 
-    let mut next_marker = None;
     let mut published_versions = HashMap::<String, Vec<String>>::default();
 
-    loop {
-        let list = blob_store_client
-            .list_objects()
-            .bucket(blob_store_bucket)
-            .prefix("extensions/")
-            .set_marker(next_marker.clone())
-            .send()
-            .await?;
-        let objects = list.contents.unwrap_or_default();
-        log::info!("fetched {} object(s) from blob store", objects.len());
-
-        for object in &objects {
-            let Some(key) = object.key.as_ref() else {
-                continue;
-            };
-            let mut parts = key.split('/');
-            let Some(_) = parts.next().filter(|part| *part == "extensions") else {
-                continue;
-            };
-            let Some(extension_id) = parts.next() else {
-                continue;
-            };
-            let Some(version) = parts.next() else {
-                continue;
-            };
-            if parts.next() == Some("manifest.json") {
-                published_versions
-                    .entry(extension_id.to_owned())
-                    .or_default()
-                    .push(version.to_owned());
-            }
-        }
-
-        if let (Some(true), Some(last_object)) = (list.is_truncated, objects.last()) {
-            next_marker.clone_from(&last_object.key);
-        } else {
-            break;
+    let keys = blob_store.list(blob_store_bucket, "extensions/").await?;
+    log::info!("fetched {} object(s) from blob store", keys.len());
+
+    for key in &keys {
+        let mut parts = key.split('/');
+        let Some(_) = parts.next().filter(|part| *part == "extensions") else {
+            continue;
+        };
+        let Some(extension_id) = parts.next() else {
+            continue;
+        };
+        let Some(version) = parts.next() else {
+            continue;
+        };
+        if parts.next() == Some("manifest.json") {
+            published_versions
+                .entry(extension_id.to_owned())
+                .or_default()
+                .push(version.to_owned());
         }
     }
 
@@ -305,7 +280,7 @@ async fn fetch_extensions_from_blob_store(
                 .is_err()
             {
                 if let Some(extension) = fetch_extension_manifest(
-                    blob_store_client,
+                    blob_store,
                     blob_store_bucket,
                     &extension_id,
                     &published_version,
@@ -336,26 +311,21 @@ async fn fetch_extensions_from_blob_store(
 }
 
 async fn fetch_extension_manifest(
-    blob_store_client: &aws_sdk_s3::Client,
-    blob_store_bucket: &String,
+    blob_store: &BlobStore,
+    blob_store_bucket: &str,
     extension_id: &str,
     version: &str,
 ) -> Result<NewExtensionVersion, anyhow::Error> {
-    let object = blob_store_client
-        .get_object()
-        .bucket(blob_store_bucket)
-        .key(format!("extensions/{extension_id}/{version}/manifest.json"))
-        .send()
-        .await?;
-    let manifest_bytes = object
-        .body
-        .collect()
+    let object = blob_store
+        .get(
+            blob_store_bucket,
+            &format!("extensions/{extension_id}/{version}/manifest.json"),
+        )
         .await
-        .map(|data| data.into_bytes())
         .with_context(|| {
             format!("failed to download manifest for extension {extension_id} version {version}")
-        })?
-        .to_vec();
+        })?;
+    let manifest_bytes = object.bytes;
     let manifest =
         serde_json::from_slice::<ExtensionApiManifest>(&manifest_bytes).with_context(|| {
             format!(
@@ -366,7 +336,6 @@ async fn fetch_extension_manifest(
     let published_at = object.last_modified.ok_or_else(|| {
         anyhow!("missing last modified timestamp for extension {extension_id} version {version}")
     })?;
-    let published_at = time::OffsetDateTime::from_unix_timestamp_nanos(published_at.as_nanos())?;
     let published_at = PrimitiveDateTime::new(published_at.date(), published_at.time());
     let version = semver::Version::parse(&manifest.version).with_context(|| {
         format!("invalid version for extension {extension_id} version {version}")
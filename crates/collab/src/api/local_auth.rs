@@ -0,0 +1,222 @@
+//! A sign-in flow for servers that authenticate users with a username and
+//! password instead of relying on zed.dev and GitHub.
+//!
+//! The editor's browser-based sign-in opens `/native_app_signin` on the
+//! configured server with the port of a local HTTP server and a public key.
+//! We serve a login form there, and once the password has been verified, we
+//! redirect the browser to the editor's local server with an access token
+//! that's been encrypted with the editor's public key.
+
+use crate::{auth, AppState, Error, RateLimit, Result};
+use axum::{
+    extract::{ConnectInfo, Query},
+    http::StatusCode,
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::get,
+    Extension, Form, Router,
+};
+use serde::Deserialize;
+use std::{
+    net::SocketAddr,
+    sync::{Arc, OnceLock},
+};
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/native_app_signin",
+            get(get_sign_in_page).post(post_sign_in),
+        )
+        .route("/native_app_signin_succeeded", get(get_sign_in_succeeded))
+}
+
+#[derive(Debug, Deserialize)]
+struct SignInPageParams {
+    native_app_port: u16,
+    native_app_public_key: String,
+}
+
+async fn get_sign_in_page(Query(params): Query<SignInPageParams>) -> Html<String> {
+    Html(sign_in_page(
+        params.native_app_port,
+        &params.native_app_public_key,
+        None,
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct SignInForm {
+    native_app_port: u16,
+    native_app_public_key: String,
+    login: String,
+    password: String,
+}
+
+/// Limits how often passwords can be guessed from a single address.
+///
+/// The address is the one the connection came from, so behind a reverse proxy or load
+/// balancer every client shares the proxy's limit. Forwarded-for headers aren't trusted,
+/// as any client can send them.
+struct SignInFromAddressRateLimit;
+
+impl RateLimit for SignInFromAddressRateLimit {
+    fn capacity() -> usize {
+        30
+    }
+
+    fn refill_duration() -> chrono::Duration {
+        chrono::Duration::minutes(15)
+    }
+
+    fn db_name() -> &'static str {
+        "sign-in-from-address"
+    }
+}
+
+/// Limits how often the password of a single user can be guessed, from any address.
+struct SignInAsUserRateLimit;
+
+impl RateLimit for SignInAsUserRateLimit {
+    fn capacity() -> usize {
+        10
+    }
+
+    fn refill_duration() -> chrono::Duration {
+        chrono::Duration::minutes(15)
+    }
+
+    fn db_name() -> &'static str {
+        "sign-in-as-user"
+    }
+}
+
+async fn post_sign_in(
+    Extension(app): Extension<Arc<AppState>>,
+    ConnectInfo(socket_address): ConnectInfo<SocketAddr>,
+    Form(form): Form<SignInForm>,
+) -> Result<Response> {
+    let too_many_attempts = || {
+        let page = sign_in_page(
+            form.native_app_port,
+            &form.native_app_public_key,
+            Some("Too many sign-in attempts. Try again later."),
+        );
+        Ok((StatusCode::TOO_MANY_REQUESTS, Html(page)).into_response())
+    };
+    if app
+        .rate_limiter
+        .check_ip::<SignInFromAddressRateLimit>(socket_address.ip())
+        .is_err()
+    {
+        return too_many_attempts();
+    }
+
+    let user_and_password_hash = app
+        .db
+        .get_user_and_password_hash_by_login(&form.login)
+        .await?;
+    if let Some((user, _)) = &user_and_password_hash {
+        if app
+            .rate_limiter
+            .check::<SignInAsUserRateLimit>(user.id)
+            .await
+            .is_err()
+        {
+            return too_many_attempts();
+        }
+    }
+
+    // A password is verified even when no user has the login, so that how long signing in
+    // takes doesn't reveal which logins exist. Verifying is deliberately slow, so it's kept
+    // off the async workers.
+    let (user, password_hash) = user_and_password_hash.unzip();
+    let password = form.password.clone();
+    let password_matches = tokio::task::spawn_blocking(move || {
+        let password_hash = password_hash.unwrap_or_else(|| dummy_password_hash().to_string());
+        auth::verify_password(&password, &password_hash)
+    })
+    .await
+    .map_err(anyhow::Error::new)??;
+
+    let user = match user {
+        Some(user) if password_matches && !user.disabled => user,
+        _ => {
+            let page = sign_in_page(
+                form.native_app_port,
+                &form.native_app_public_key,
+                Some("Invalid username or password."),
+            );
+            return Ok((StatusCode::UNAUTHORIZED, Html(page)).into_response());
+        }
+    };
+
+    let access_token = auth::create_access_token(app.db.as_ref(), user.id, None).await?;
+    let encrypted_access_token =
+        auth::encrypt_access_token(&access_token, form.native_app_public_key)
+            .map_err(|error| Error::Http(StatusCode::BAD_REQUEST, error.to_string()))?;
+
+    Ok(Redirect::to(&format!(
+        "http://127.0.0.1:{}/?user_id={}&access_token={}",
+        form.native_app_port, user.id, encrypted_access_token
+    ))
+    .into_response())
+}
+
+/// The hash of a password no user has, for logins that don't exist.
+fn dummy_password_hash() -> &'static str {
+    static DUMMY_PASSWORD_HASH: OnceLock<String> = OnceLock::new();
+    DUMMY_PASSWORD_HASH.get_or_init(|| {
+        auth::hash_password("no user has this password").expect("failed to hash a password")
+    })
+}
+
+async fn get_sign_in_succeeded() -> Html<&'static str> {
+    Html(
+        r#"<!DOCTYPE html>
+<html>
+<head><title>Signed in to Zed</title></head>
+<body>
+<p>You are now signed in. You can close this window and return to Zed.</p>
+</body>
+</html>"#,
+    )
+}
+
+fn sign_in_page(native_app_port: u16, native_app_public_key: &str, error: Option<&str>) -> String {
+    let error = error
+        .map(|error| format!("<p role=\"alert\">{}</p>", escape_html(error)))
+        .unwrap_or_default();
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><title>Sign in to Zed</title></head>
+<body>
+<h1>Sign in to Zed</h1>
+{error}
+<form method="post" action="/native_app_signin">
+<input type="hidden" name="native_app_port" value="{native_app_port}">
+<input type="hidden" name="native_app_public_key" value="{public_key}">
+<p><label>Username <input type="text" name="login" autocomplete="username" required autofocus></label></p>
+<p><label>Password <input type="password" name="password" autocomplete="current-password" required></label></p>
+<p><button type="submit">Sign in</button></p>
+</form>
+</body>
+</html>"#,
+        public_key = escape_html(native_app_public_key),
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
@@ -12,7 +12,7 @@ use axum::{
 use prometheus::{exponential_buckets, register_histogram, Histogram};
 pub use rpc::auth::random_token;
 use scrypt::{
-    password_hash::{PasswordHash, PasswordVerifier},
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Scrypt,
 };
 use serde::{Deserialize, Serialize};
@@ -94,6 +94,12 @@ pub async fn validate_header<B>(mut req: Request<B>, next: Next<B>) -> impl Into
                 .get_user_by_id(user_id)
                 .await?
                 .ok_or_else(|| anyhow!("user {} not found", user_id))?;
+            if user.disabled {
+                Err(Error::Http(
+                    StatusCode::UNAUTHORIZED,
+                    "user is disabled".to_string(),
+                ))?
+            }
 
             if let Some(impersonator_id) = validate_result.impersonator_id {
                 let admin = state
@@ -231,6 +237,36 @@ pub async fn verify_access_token(
     })
 }
 
+/// Hashes a password chosen by a user who signs in with local authentication. Unlike
+/// access tokens, passwords aren't random, so they're hashed with scrypt.
+pub fn hash_password(password: &str) -> Result<String> {
+    // Avoid slow hashing in tests. Debug builds of the server may still hold real
+    // passwords, so they're hashed with the same cost as in release builds.
+    let params = if cfg!(test) {
+        scrypt::Params::new(1, 1, 1).unwrap()
+    } else {
+        scrypt::Params::new(14, 8, 1).unwrap()
+    };
+
+    Ok(Scrypt
+        .hash_password(
+            password.as_bytes(),
+            None,
+            params,
+            &SaltString::generate(rand::thread_rng()),
+        )
+        .map_err(anyhow::Error::new)?
+        .to_string())
+}
+
+/// Checks the given password against a hash produced by [`hash_password`].
+pub fn verify_password(password: &str, password_hash: &str) -> Result<bool> {
+    let password_hash = PasswordHash::new(password_hash).map_err(anyhow::Error::new)?;
+    Ok(Scrypt
+        .verify_password(password.as_bytes(), &password_hash)
+        .is_ok())
+}
+
 pub fn generate_dev_server_token(id: usize, access_token: String) -> String {
     format!("{}.{}", id, access_token)
 }
@@ -269,7 +305,6 @@ pub fn split_dev_server_token(dev_server_token: &str) -> anyhow::Result<(DevServ
 #[cfg(test)]
 mod test {
     use rand::thread_rng;
-    use scrypt::password_hash::{PasswordHasher, SaltString};
     use sea_orm::EntityTrait;
 
     use super::*;
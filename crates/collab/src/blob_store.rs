@@ -0,0 +1,209 @@
+use anyhow::{anyhow, Context as _, Result};
+use aws_sdk_s3::{presigning::PresigningConfig, primitives::ByteStream};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use time::OffsetDateTime;
+
+/// Where the server stores blobs such as extension archives and crash reports.
+///
+/// Blobs are addressed by a bucket and a `/`-separated key, as in S3. The
+/// filesystem store maps them to `<root>/<bucket>/<key>`, which lets the server
+/// run without any cloud services.
+#[derive(Clone)]
+pub enum BlobStore {
+    S3 { client: aws_sdk_s3::Client },
+    FileSystem { root: PathBuf },
+}
+
+pub struct Blob {
+    pub bytes: Vec<u8>,
+    pub last_modified: Option<OffsetDateTime>,
+}
+
+impl BlobStore {
+    /// Returns the keys of all the blobs in the bucket that start with the given prefix.
+    pub async fn list(&self, bucket: &str, prefix: &str) -> Result<Vec<String>> {
+        match self {
+            BlobStore::S3 { client } => {
+                let mut keys = Vec::new();
+                let mut next_marker = None;
+                loop {
+                    let list = client
+                        .list_objects()
+                        .bucket(bucket)
+                        .prefix(prefix)
+                        .set_marker(next_marker.clone())
+                        .send()
+                        .await?;
+                    let objects = list.contents.unwrap_or_default();
+                    keys.extend(objects.iter().filter_map(|object| object.key.clone()));
+
+                    if let (Some(true), Some(last_object)) = (list.is_truncated, objects.last()) {
+                        next_marker.clone_from(&last_object.key);
+                    } else {
+                        break;
+                    }
+                }
+                Ok(keys)
+            }
+            BlobStore::FileSystem { root } => {
+                let bucket_path = bucket_path(root, bucket)?;
+                let mut keys = Vec::new();
+                let mut pending_dirs = vec![(bucket_path, String::new())];
+                while let Some((dir, dir_key)) = pending_dirs.pop() {
+                    let mut entries = match tokio::fs::read_dir(&dir).await {
+                        Ok(entries) => entries,
+                        Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+                        Err(error) => Err(error)?,
+                    };
+                    while let Some(entry) = entries.next_entry().await? {
+                        let Ok(name) = entry.file_name().into_string() else {
+                            continue;
+                        };
+                        let key = format!("{dir_key}{name}");
+                        if entry.file_type().await?.is_dir() {
+                            pending_dirs.push((entry.path(), format!("{key}/")));
+                        } else if key.starts_with(prefix) {
+                            keys.push(key);
+                        }
+                    }
+                }
+                keys.sort();
+                Ok(keys)
+            }
+        }
+    }
+
+    pub async fn get(&self, bucket: &str, key: &str) -> Result<Blob> {
+        match self {
+            BlobStore::S3 { client } => {
+                let object = client.get_object().bucket(bucket).key(key).send().await?;
+                let last_modified = object
+                    .last_modified
+                    .map(|last_modified| {
+                        OffsetDateTime::from_unix_timestamp_nanos(last_modified.as_nanos())
+                    })
+                    .transpose()?;
+                let bytes = object
+                    .body
+                    .collect()
+                    .await
+                    .with_context(|| format!("failed to download {bucket}/{key}"))?
+                    .into_bytes()
+                    .to_vec();
+                Ok(Blob {
+                    bytes,
+                    last_modified,
+                })
+            }
+            BlobStore::FileSystem { root } => {
+                let path = blob_path(root, bucket, key)?;
+                let bytes = tokio::fs::read(&path)
+                    .await
+                    .with_context(|| format!("failed to read {path:?}"))?;
+                let last_modified = tokio::fs::metadata(&path)
+                    .await?
+                    .modified()
+                    .ok()
+                    .map(OffsetDateTime::from);
+                Ok(Blob {
+                    bytes,
+                    last_modified,
+                })
+            }
+        }
+    }
+
+    pub async fn exists(&self, bucket: &str, key: &str) -> Result<bool> {
+        match self {
+            BlobStore::S3 { client } => Ok(client
+                .head_object()
+                .bucket(bucket)
+                .key(key)
+                .send()
+                .await
+                .is_ok()),
+            BlobStore::FileSystem { root } => {
+                Ok(tokio::fs::try_exists(blob_path(root, bucket, key)?).await?)
+            }
+        }
+    }
+
+    /// Stores a blob that anyone who knows its key may read.
+    pub async fn put_public(&self, bucket: &str, key: &str, bytes: Vec<u8>) -> Result<()> {
+        match self {
+            BlobStore::S3 { client } => {
+                client
+                    .put_object()
+                    .bucket(bucket)
+                    .key(key)
+                    .acl(aws_sdk_s3::types::ObjectCannedAcl::PublicRead)
+                    .body(ByteStream::from(bytes))
+                    .send()
+                    .await?;
+                Ok(())
+            }
+            BlobStore::FileSystem { root } => {
+                let path = blob_path(root, bucket, key)?;
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&path, bytes)
+                    .await
+                    .with_context(|| format!("failed to write {path:?}"))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns a URL from which the blob can be downloaded directly, if the store
+    /// supports it. Otherwise, the blob's contents must be served by the server.
+    pub async fn download_url(
+        &self,
+        bucket: &str,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<Option<String>> {
+        match self {
+            BlobStore::S3 { client } => {
+                let request = client
+                    .get_object()
+                    .bucket(bucket)
+                    .key(key)
+                    .presigned(PresigningConfig::expires_in(expires_in)?)
+                    .await
+                    .map_err(|e| anyhow!("failed to create presigned url for {key}: {e}"))?;
+                Ok(Some(request.uri().to_string()))
+            }
+            BlobStore::FileSystem { .. } => Ok(None),
+        }
+    }
+}
+
+fn bucket_path(root: &Path, bucket: &str) -> Result<PathBuf> {
+    validate_path_component(bucket)?;
+    Ok(root.join(bucket))
+}
+
+fn blob_path(root: &Path, bucket: &str, key: &str) -> Result<PathBuf> {
+    let mut path = bucket_path(root, bucket)?;
+    for component in key.split('/') {
+        validate_path_component(component)?;
+        path.push(component);
+    }
+    Ok(path)
+}
+
+/// Prevents keys from escaping the store's root directory.
+fn validate_path_component(component: &str) -> Result<()> {
+    if component.is_empty()
+        || component == "."
+        || component == ".."
+        || component.contains(['\\', ':', '\0'])
+    {
+        Err(anyhow!("invalid blob store path component {component:?}"))?;
+    }
+    Ok(())
+}
@@ -518,11 +518,22 @@ pub struct UpdatedChannelMessage {
     pub participant_connection_ids: Vec<ConnectionId>,
     pub notifications: NotificationBatch,
     pub reply_to_message_id: Option<MessageId>,
+    pub thread_parent_id: Option<MessageId>,
     pub timestamp: PrimitiveDateTime,
     pub deleted_mention_notification_ids: Vec<NotificationId>,
     pub updated_mention_notifications: Vec<rpc::proto::Notification>,
 }
 
+pub struct UpdatedChannelMessageReactions {
+    pub participant_connection_ids: Vec<ConnectionId>,
+    pub reactions: Vec<proto::ChannelMessageReaction>,
+}
+
+pub struct UpdatedBufferCommentThread {
+    pub thread: proto::BufferCommentThread,
+    pub notifications: NotificationBatch,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, FromQueryResult, Serialize, Deserialize)]
 pub struct Invite {
     pub email_address: String,
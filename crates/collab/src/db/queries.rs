@@ -1,6 +1,7 @@
 use super::*;
 
 pub mod access_tokens;
+pub mod buffer_comments;
 pub mod buffers;
 pub mod channels;
 pub mod contacts;
@@ -73,4 +73,43 @@ impl Database {
         })
         .await
     }
+
+    /// Returns the access tokens that can be used to sign in as the given user.
+    pub async fn get_access_tokens_for_user(
+        &self,
+        user_id: UserId,
+    ) -> Result<Vec<access_token::Model>> {
+        self.transaction(|tx| async move {
+            Ok(access_token::Entity::find()
+                .filter(access_token::Column::UserId.eq(user_id))
+                .order_by_asc(access_token::Column::Id)
+                .all(&*tx)
+                .await?)
+        })
+        .await
+    }
+
+    /// Revokes the access token with the given ID. Returns whether it existed.
+    pub async fn delete_access_token(&self, access_token_id: AccessTokenId) -> Result<bool> {
+        self.transaction(|tx| async move {
+            let result = access_token::Entity::delete_by_id(access_token_id)
+                .exec(&*tx)
+                .await?;
+            Ok(result.rows_affected > 0)
+        })
+        .await
+    }
+
+    /// Revokes all of the given user's access tokens, including the ones they use
+    /// to impersonate other users. Returns how many tokens were revoked.
+    pub async fn delete_access_tokens_for_user(&self, user_id: UserId) -> Result<u64> {
+        self.transaction(|tx| async move {
+            let result = access_token::Entity::delete_many()
+                .filter(access_token::Column::UserId.eq(user_id))
+                .exec(&*tx)
+                .await?;
+            Ok(result.rows_affected)
+        })
+        .await
+    }
 }
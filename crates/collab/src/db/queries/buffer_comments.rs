@@ -0,0 +1,402 @@
+use super::*;
+use prost::Message as _;
+use rpc::Notification;
+use time::OffsetDateTime;
+
+impl Database {
+    /// Returns the comment threads left on the buffers of a shared project, including the
+    /// ones left while its worktrees were shared in earlier projects of the same host.
+    pub async fn get_buffer_comment_threads(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::BufferCommentThread>> {
+        self.project_transaction(project_id, |tx| async move {
+            let (project, _) = self
+                .access_project(
+                    project_id,
+                    connection_id,
+                    PrincipalId::UserId(user_id),
+                    Capability::ReadOnly,
+                    &tx,
+                )
+                .await?;
+
+            let worktree_roots = self.project_worktree_roots(project_id, &tx).await?;
+            let threads = buffer_comment_thread::Entity::find()
+                .filter(self.buffer_comment_threads_on(&project, &worktree_roots)?)
+                .order_by_asc(buffer_comment_thread::Column::Id)
+                .all(&*tx)
+                .await?;
+            self.buffer_comment_threads_to_proto(project_id, &worktree_roots, threads, &tx)
+                .await
+        })
+        .await
+        .map(|guard| guard.into_inner())
+    }
+
+    /// Starts a comment thread at the given position in a buffer of a shared project.
+    ///
+    /// Anyone who can read the project can comment on it. Mentioned users are notified if
+    /// they are collaborators in the project.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_buffer_comment_thread(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        user_id: UserId,
+        worktree_id: u64,
+        path: &str,
+        anchor: &proto::Anchor,
+        position: (u32, u32),
+        body: &str,
+        mentioned_user_ids: &[UserId],
+        timestamp: OffsetDateTime,
+    ) -> Result<UpdatedBufferCommentThread> {
+        self.project_transaction(project_id, |tx| async move {
+            let (project, _) = self
+                .access_project(
+                    project_id,
+                    connection_id,
+                    PrincipalId::UserId(user_id),
+                    Capability::ReadOnly,
+                    &tx,
+                )
+                .await?;
+
+            let worktree_roots = self.project_worktree_roots(project_id, &tx).await?;
+            let worktree_root = worktree_roots
+                .iter()
+                .find_map(|(root, id)| (*id == worktree_id).then(|| root.clone()))
+                .ok_or_else(|| anyhow!("no such worktree"))?;
+            if project.host_user_id.is_none() && project.dev_server_project_id.is_none() {
+                Err(anyhow!("project has no host to keep comments for"))?;
+            }
+
+            let (row, column) = position;
+            let thread =
+                buffer_comment_thread::Entity::insert(buffer_comment_thread::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    host_user_id: ActiveValue::Set(project.host_user_id),
+                    dev_server_project_id: ActiveValue::Set(project.dev_server_project_id),
+                    worktree_root: ActiveValue::Set(worktree_root),
+                    path: ActiveValue::Set(path.to_string()),
+                    row: ActiveValue::Set(row as i32),
+                    column: ActiveValue::Set(column as i32),
+                    anchor_project_id: ActiveValue::Set(Some(project_id)),
+                    anchor: ActiveValue::Set(Some(anchor.encode_to_vec())),
+                    resolved: ActiveValue::Set(false),
+                })
+                .exec_with_returning(&*tx)
+                .await?;
+
+            let comment_id = self
+                .insert_buffer_comment(thread.id, user_id, body, timestamp, &tx)
+                .await?;
+            let notifications = self
+                .notify_buffer_comment_mentions(
+                    &project,
+                    worktree_id,
+                    &thread,
+                    comment_id,
+                    user_id,
+                    body,
+                    mentioned_user_ids,
+                    &tx,
+                )
+                .await?;
+
+            let thread = self
+                .buffer_comment_threads_to_proto(project_id, &worktree_roots, vec![thread], &tx)
+                .await?
+                .pop()
+                .ok_or_else(|| anyhow!("failed to create comment thread"))?;
+            Ok(UpdatedBufferCommentThread {
+                thread,
+                notifications,
+            })
+        })
+        .await
+        .map(|guard| guard.into_inner())
+    }
+
+    /// Adds a reply to a comment thread.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_buffer_comment(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        user_id: UserId,
+        thread_id: BufferCommentThreadId,
+        body: &str,
+        mentioned_user_ids: &[UserId],
+        timestamp: OffsetDateTime,
+    ) -> Result<UpdatedBufferCommentThread> {
+        self.project_transaction(project_id, |tx| async move {
+            let (project, _) = self
+                .access_project(
+                    project_id,
+                    connection_id,
+                    PrincipalId::UserId(user_id),
+                    Capability::ReadOnly,
+                    &tx,
+                )
+                .await?;
+            let worktree_roots = self.project_worktree_roots(project_id, &tx).await?;
+            let thread = self
+                .get_buffer_comment_thread(&project, &worktree_roots, thread_id, &tx)
+                .await?;
+
+            let comment_id = self
+                .insert_buffer_comment(thread.id, user_id, body, timestamp, &tx)
+                .await?;
+            let notifications = self
+                .notify_buffer_comment_mentions(
+                    &project,
+                    worktree_roots[&thread.worktree_root],
+                    &thread,
+                    comment_id,
+                    user_id,
+                    body,
+                    mentioned_user_ids,
+                    &tx,
+                )
+                .await?;
+
+            let thread = self
+                .buffer_comment_threads_to_proto(project_id, &worktree_roots, vec![thread], &tx)
+                .await?
+                .pop()
+                .ok_or_else(|| anyhow!("no such comment thread"))?;
+            Ok(UpdatedBufferCommentThread {
+                thread,
+                notifications,
+            })
+        })
+        .await
+        .map(|guard| guard.into_inner())
+    }
+
+    /// Marks a comment thread as resolved, or reopens it.
+    pub async fn set_buffer_comment_thread_resolved(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        user_id: UserId,
+        thread_id: BufferCommentThreadId,
+        resolved: bool,
+    ) -> Result<proto::BufferCommentThread> {
+        self.project_transaction(project_id, |tx| async move {
+            let (project, _) = self
+                .access_project(
+                    project_id,
+                    connection_id,
+                    PrincipalId::UserId(user_id),
+                    Capability::ReadOnly,
+                    &tx,
+                )
+                .await?;
+            let worktree_roots = self.project_worktree_roots(project_id, &tx).await?;
+            let thread = self
+                .get_buffer_comment_thread(&project, &worktree_roots, thread_id, &tx)
+                .await?;
+
+            let thread =
+                buffer_comment_thread::Entity::update(buffer_comment_thread::ActiveModel {
+                    resolved: ActiveValue::Set(resolved),
+                    ..thread.into_active_model()
+                })
+                .exec(&*tx)
+                .await?;
+            let thread = self
+                .buffer_comment_threads_to_proto(project_id, &worktree_roots, vec![thread], &tx)
+                .await?
+                .pop()
+                .ok_or_else(|| anyhow!("no such comment thread"))?;
+            Ok(thread)
+        })
+        .await
+        .map(|guard| guard.into_inner())
+    }
+
+    async fn get_buffer_comment_thread(
+        &self,
+        project: &project::Model,
+        worktree_roots: &HashMap<String, u64>,
+        thread_id: BufferCommentThreadId,
+        tx: &DatabaseTransaction,
+    ) -> Result<buffer_comment_thread::Model> {
+        Ok(buffer_comment_thread::Entity::find_by_id(thread_id)
+            .filter(self.buffer_comment_threads_on(project, worktree_roots)?)
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such comment thread"))?)
+    }
+
+    /// Returns the ids of the worktrees of a project, keyed by the absolute path of their
+    /// root on the host.
+    async fn project_worktree_roots(
+        &self,
+        project_id: ProjectId,
+        tx: &DatabaseTransaction,
+    ) -> Result<HashMap<String, u64>> {
+        Ok(worktree::Entity::find()
+            .filter(worktree::Column::ProjectId.eq(project_id))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|worktree| (worktree.abs_path, worktree.id as u64))
+            .collect())
+    }
+
+    /// Matches the threads left on the given worktrees of the project's host, in this
+    /// project or in any project it shared them in before.
+    fn buffer_comment_threads_on(
+        &self,
+        project: &project::Model,
+        worktree_roots: &HashMap<String, u64>,
+    ) -> Result<Condition> {
+        let host = if let Some(dev_server_project_id) = project.dev_server_project_id {
+            buffer_comment_thread::Column::DevServerProjectId.eq(dev_server_project_id)
+        } else if let Some(host_user_id) = project.host_user_id {
+            buffer_comment_thread::Column::HostUserId.eq(host_user_id)
+        } else {
+            Err(anyhow!("project has no host to keep comments for"))?
+        };
+        Ok(Condition::all()
+            .add(host)
+            .add(buffer_comment_thread::Column::WorktreeRoot.is_in(worktree_roots.keys().cloned())))
+    }
+
+    async fn insert_buffer_comment(
+        &self,
+        thread_id: BufferCommentThreadId,
+        user_id: UserId,
+        body: &str,
+        timestamp: OffsetDateTime,
+        tx: &DatabaseTransaction,
+    ) -> Result<BufferCommentId> {
+        let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+        let timestamp = time::PrimitiveDateTime::new(timestamp.date(), timestamp.time());
+        let comment = buffer_comment::Entity::insert(buffer_comment::ActiveModel {
+            id: ActiveValue::NotSet,
+            thread_id: ActiveValue::Set(thread_id),
+            sender_id: ActiveValue::Set(user_id),
+            body: ActiveValue::Set(body.to_string()),
+            sent_at: ActiveValue::Set(timestamp),
+        })
+        .exec(tx)
+        .await?;
+        Ok(comment.last_insert_id)
+    }
+
+    /// Notifies the users mentioned in a comment, ignoring the ones that aren't
+    /// collaborators in the project.
+    #[allow(clippy::too_many_arguments)]
+    async fn notify_buffer_comment_mentions(
+        &self,
+        project: &project::Model,
+        worktree_id: u64,
+        thread: &buffer_comment_thread::Model,
+        comment_id: BufferCommentId,
+        sender_id: UserId,
+        body: &str,
+        mentioned_user_ids: &[UserId],
+        tx: &DatabaseTransaction,
+    ) -> Result<NotificationBatch> {
+        if mentioned_user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut collaborator_ids = project_collaborator::Entity::find()
+            .filter(project_collaborator::Column::ProjectId.eq(project.id))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|collaborator| collaborator.user_id)
+            .collect::<HashSet<_>>();
+        collaborator_ids.extend(project.host_user_id);
+
+        let mut notifications = Vec::new();
+        let mentioned_user_ids = mentioned_user_ids.iter().collect::<HashSet<_>>();
+        for mentioned_user_id in mentioned_user_ids {
+            if *mentioned_user_id == sender_id || !collaborator_ids.contains(mentioned_user_id) {
+                continue;
+            }
+            notifications.extend(
+                self.create_notification(
+                    *mentioned_user_id,
+                    Notification::BufferCommentMention {
+                        comment_id: comment_id.to_proto(),
+                        sender_id: sender_id.to_proto(),
+                        project_id: project.id.to_proto(),
+                        worktree_id,
+                        path: thread.path.clone(),
+                        body: body.to_string(),
+                    },
+                    false,
+                    tx,
+                )
+                .await?,
+            );
+        }
+        Ok(notifications)
+    }
+
+    async fn buffer_comment_threads_to_proto(
+        &self,
+        project_id: ProjectId,
+        worktree_roots: &HashMap<String, u64>,
+        threads: Vec<buffer_comment_thread::Model>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::BufferCommentThread>> {
+        let mut comments = buffer_comment::Entity::find()
+            .filter(buffer_comment::Column::ThreadId.is_in(threads.iter().map(|thread| thread.id)))
+            .order_by_asc(buffer_comment::Column::Id)
+            .stream(tx)
+            .await?;
+
+        let mut result = Vec::with_capacity(threads.len());
+        for thread in threads {
+            let worktree_id = *worktree_roots
+                .get(&thread.worktree_root)
+                .ok_or_else(|| anyhow!("comment thread outside of the project"))?;
+            // Anchors only make sense in the buffers of the project they were created in.
+            let anchor = match thread
+                .anchor
+                .filter(|_| thread.anchor_project_id == Some(project_id))
+            {
+                Some(anchor) => Some(
+                    proto::Anchor::decode(anchor.as_slice())
+                        .map_err(|error| anyhow!("invalid comment thread anchor: {error}"))?,
+                ),
+                None => None,
+            };
+            result.push(proto::BufferCommentThread {
+                id: thread.id.to_proto(),
+                worktree_id,
+                path: thread.path,
+                anchor,
+                row: thread.row as u32,
+                column: thread.column as u32,
+                resolved: thread.resolved,
+                comments: Vec::new(),
+            });
+        }
+
+        while let Some(comment) = comments.next().await {
+            let comment = comment?;
+            let thread_id = comment.thread_id.to_proto();
+            if let Some(thread) = result.iter_mut().find(|thread| thread.id == thread_id) {
+                thread.comments.push(proto::BufferComment {
+                    id: comment.id.to_proto(),
+                    sender_id: comment.sender_id.to_proto(),
+                    body: comment.body,
+                    timestamp: comment.sent_at.assume_utc().unix_timestamp() as u64,
+                });
+            }
+        }
+        Ok(result)
+    }
+}
@@ -1,6 +1,12 @@
 use super::*;
 use prost::Message;
+use std::collections::VecDeque;
 use text::{EditOperation, UndoOperation};
+use time::{Duration, OffsetDateTime};
+
+/// Consecutive edits by the same collaborator within this interval are grouped
+/// into a single revision of a channel buffer's history.
+const BUFFER_REVISION_INTERVAL: Duration = Duration::minutes(5);
 
 pub struct LeftChannelBuffer {
     pub channel_id: ChannelId,
@@ -469,9 +475,13 @@ impl Database {
                 .get_buffer_operation_serialization_version(buffer.id, buffer.epoch, &tx)
                 .await?;
 
+            let now = OffsetDateTime::now_utc();
+            let now = PrimitiveDateTime::new(now.date(), now.time());
             let operations = operations
                 .iter()
-                .filter_map(|op| operation_to_storage(op, &buffer, serialization_version))
+                .filter_map(|op| {
+                    operation_to_storage(op, &buffer, user, now, serialization_version)
+                })
                 .collect::<Vec<_>>();
 
             let max_version;
@@ -668,6 +678,8 @@ impl Database {
                 lamport_timestamp: row.lamport_timestamp,
                 replica_id: row.replica_id,
                 value: Default::default(),
+                user_id: row.user_id,
+                created_at: row.created_at,
             });
             operations.push(proto::Operation {
                 variant: Some(operation_from_storage(row, version)?),
@@ -720,6 +732,250 @@ impl Database {
         Ok(())
     }
 
+    /// Returns the revisions of a channel buffer, oldest first. Each revision groups
+    /// consecutive operations made by a single collaborator within an epoch.
+    pub async fn get_channel_buffer_history(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::ChannelBufferRevision>> {
+        #[derive(Debug, FromQueryResult)]
+        struct OperationMetadata {
+            epoch: i32,
+            lamport_timestamp: i32,
+            replica_id: i32,
+            user_id: Option<UserId>,
+            created_at: Option<PrimitiveDateTime>,
+        }
+
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+            let buffer = self.get_channel_buffer(channel_id, &tx).await?;
+
+            let mut rows = buffer_operation::Entity::find()
+                .select_only()
+                .columns([
+                    buffer_operation::Column::Epoch,
+                    buffer_operation::Column::LamportTimestamp,
+                    buffer_operation::Column::ReplicaId,
+                    buffer_operation::Column::UserId,
+                    buffer_operation::Column::CreatedAt,
+                ])
+                .filter(buffer_operation::Column::BufferId.eq(buffer.id))
+                .order_by_asc(buffer_operation::Column::Epoch)
+                .order_by_asc(buffer_operation::Column::LamportTimestamp)
+                .order_by_asc(buffer_operation::Column::ReplicaId)
+                .into_model::<OperationMetadata>()
+                .stream(&*tx)
+                .await?;
+
+            let mut revisions: Vec<proto::ChannelBufferRevision> = Vec::new();
+            let mut revision_started_at = None;
+            while let Some(row) = rows.next().await {
+                let row = row?;
+                let revision = proto::ChannelBufferRevision {
+                    epoch: row.epoch as u64,
+                    lamport_timestamp: row.lamport_timestamp as u32,
+                    replica_id: row.replica_id as u32,
+                    user_id: row.user_id.map(UserId::to_proto),
+                    timestamp: row
+                        .created_at
+                        .map(|t| t.assume_utc().unix_timestamp() as u64),
+                };
+
+                if let Some(last_revision) = revisions.last_mut() {
+                    let within_interval = match (revision_started_at, row.created_at) {
+                        (Some(started_at), Some(created_at)) => {
+                            created_at - started_at <= BUFFER_REVISION_INTERVAL
+                        }
+                        (None, None) => true,
+                        _ => false,
+                    };
+                    if last_revision.epoch == revision.epoch
+                        && last_revision.user_id == revision.user_id
+                        && within_interval
+                    {
+                        *last_revision = revision;
+                        continue;
+                    }
+                }
+
+                revision_started_at = row.created_at;
+                revisions.push(revision);
+            }
+
+            Ok(revisions)
+        })
+        .await
+    }
+
+    /// Reconstructs the text of a channel buffer at the given revision, epoch or
+    /// timestamp, along with the collaborator who authored each range of it.
+    pub async fn get_channel_buffer_revision(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        at: proto::get_channel_buffer_revision::At,
+    ) -> Result<proto::GetChannelBufferRevisionResponse> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+            let buffer = self.get_channel_buffer(channel_id, &tx).await?;
+
+            // The operations to include are those up to and including `end` in
+            // (epoch, lamport timestamp, replica id) order, which is causal order.
+            let end = match at {
+                proto::get_channel_buffer_revision::At::Revision(revision) => Some((
+                    revision.epoch as i32,
+                    revision.lamport_timestamp as i32,
+                    revision.replica_id as i32,
+                )),
+                proto::get_channel_buffer_revision::At::Epoch(epoch) => {
+                    Some((epoch as i32, -1, -1))
+                }
+                proto::get_channel_buffer_revision::At::Timestamp(timestamp) => {
+                    let timestamp = OffsetDateTime::from_unix_timestamp(timestamp as i64)
+                        .map_err(|error| anyhow!("invalid timestamp: {error}"))?;
+                    let timestamp = PrimitiveDateTime::new(timestamp.date(), timestamp.time());
+                    // Timestamps are sent with a precision of one second, so include
+                    // every operation made within that second.
+                    buffer_operation::Entity::find()
+                        .filter(buffer_operation::Column::BufferId.eq(buffer.id))
+                        .filter(
+                            buffer_operation::Column::CreatedAt
+                                .lt(timestamp + Duration::seconds(1)),
+                        )
+                        .order_by_desc(buffer_operation::Column::Epoch)
+                        .order_by_desc(buffer_operation::Column::LamportTimestamp)
+                        .order_by_desc(buffer_operation::Column::ReplicaId)
+                        .one(&*tx)
+                        .await?
+                        .map(|row| (row.epoch, row.lamport_timestamp, row.replica_id))
+                }
+            };
+            let Some(end @ (end_epoch, _, _)) = end else {
+                return Ok(proto::GetChannelBufferRevisionResponse::default());
+            };
+            if end_epoch > buffer.epoch {
+                Err(anyhow!("no such epoch"))?;
+            }
+
+            let snapshots = buffer_snapshot::Entity::find()
+                .filter(buffer_snapshot::Column::BufferId.eq(buffer.id))
+                .filter(buffer_snapshot::Column::Epoch.lte(end_epoch))
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .map(|snapshot| (snapshot.epoch, snapshot))
+                .collect::<HashMap<_, _>>();
+
+            let mut rows = buffer_operation::Entity::find()
+                .filter(buffer_operation::Column::BufferId.eq(buffer.id))
+                .filter(buffer_operation::Column::Epoch.lte(end_epoch))
+                .order_by_asc(buffer_operation::Column::Epoch)
+                .order_by_asc(buffer_operation::Column::LamportTimestamp)
+                .order_by_asc(buffer_operation::Column::ReplicaId)
+                .stream(&*tx)
+                .await?;
+
+            let mut epoch = 0;
+            let mut serialization_version = storage::SERIALIZATION_VERSION;
+            let mut text_buffer =
+                text::Buffer::new(0, text::BufferId::new(1).unwrap(), String::new());
+            let mut authorship = Vec::new();
+            let mut revision = None;
+            while let Some(row) = rows.next().await {
+                let row = row?;
+                if (row.epoch, row.lamport_timestamp, row.replica_id) > end {
+                    break;
+                }
+
+                // Each epoch starts from the snapshot taken at the end of the previous one.
+                if row.epoch != epoch {
+                    epoch = row.epoch;
+                    let snapshot = snapshots
+                        .get(&epoch)
+                        .ok_or_else(|| anyhow!("no such snapshot"))?;
+                    serialization_version = snapshot.operation_serialization_version;
+                    if snapshot.text != text_buffer.text() {
+                        authorship = vec![AuthorshipRun {
+                            len: snapshot.text.len(),
+                            user_id: None,
+                        }];
+                    }
+                    text_buffer = text::Buffer::new(
+                        0,
+                        text::BufferId::new(1).unwrap(),
+                        snapshot.text.clone(),
+                    );
+                }
+
+                revision = Some(proto::ChannelBufferRevision {
+                    epoch: row.epoch as u64,
+                    lamport_timestamp: row.lamport_timestamp as u32,
+                    replica_id: row.replica_id as u32,
+                    user_id: row.user_id.map(UserId::to_proto),
+                    timestamp: row
+                        .created_at
+                        .map(|t| t.assume_utc().unix_timestamp() as u64),
+                });
+
+                let author = row.user_id;
+                let operation = proto::Operation {
+                    variant: Some(operation_from_storage(row, serialization_version)?),
+                };
+                let version = text_buffer.version();
+                text_buffer.apply_ops(operation_from_wire(operation))?;
+                authorship = edit_authorship(
+                    authorship,
+                    text_buffer.edits_since::<usize>(&version),
+                    author,
+                );
+            }
+
+            // The requested epoch may not have any operations of its own yet.
+            if epoch < end_epoch {
+                if let Some(snapshot) = snapshots.get(&end_epoch) {
+                    if snapshot.text != text_buffer.text() {
+                        authorship = vec![AuthorshipRun {
+                            len: snapshot.text.len(),
+                            user_id: None,
+                        }];
+                    }
+                    text_buffer = text::Buffer::new(
+                        0,
+                        text::BufferId::new(1).unwrap(),
+                        snapshot.text.clone(),
+                    );
+                }
+            }
+
+            let mut offset = 0;
+            let authorship = authorship
+                .into_iter()
+                .map(|run| {
+                    let start = offset;
+                    offset += run.len;
+                    proto::ChannelBufferAuthorship {
+                        start: start as u64,
+                        end: offset as u64,
+                        user_id: run.user_id.map(UserId::to_proto),
+                    }
+                })
+                .collect();
+
+            Ok(proto::GetChannelBufferRevisionResponse {
+                revision,
+                text: text_buffer.text(),
+                authorship,
+            })
+        })
+        .await
+    }
+
     pub async fn observe_buffer_version(
         &self,
         buffer_id: BufferId,
@@ -777,9 +1033,81 @@ impl Database {
     }
 }
 
+/// A run of consecutive bytes in a channel buffer written by the same collaborator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct AuthorshipRun {
+    len: usize,
+    user_id: Option<UserId>,
+}
+
+fn edit_authorship(
+    runs: Vec<AuthorshipRun>,
+    edits: impl IntoIterator<Item = text::Edit<usize>>,
+    user_id: Option<UserId>,
+) -> Vec<AuthorshipRun> {
+    let mut old_runs = VecDeque::from(runs);
+    let mut new_runs = Vec::new();
+    let mut old_offset = 0;
+    for edit in edits {
+        take_authorship(
+            &mut old_runs,
+            edit.old.start - old_offset,
+            Some(&mut new_runs),
+        );
+        take_authorship(&mut old_runs, edit.old_len(), None);
+        push_authorship(
+            &mut new_runs,
+            AuthorshipRun {
+                len: edit.new_len(),
+                user_id,
+            },
+        );
+        old_offset = edit.old.end;
+    }
+    for run in old_runs {
+        push_authorship(&mut new_runs, run);
+    }
+    new_runs
+}
+
+fn take_authorship(
+    runs: &mut VecDeque<AuthorshipRun>,
+    mut len: usize,
+    mut output: Option<&mut Vec<AuthorshipRun>>,
+) {
+    while len > 0 {
+        let Some(mut run) = runs.pop_front() else {
+            break;
+        };
+        if run.len > len {
+            runs.push_front(AuthorshipRun {
+                len: run.len - len,
+                user_id: run.user_id,
+            });
+            run.len = len;
+        }
+        len -= run.len;
+        if let Some(output) = output.as_mut() {
+            push_authorship(output, run);
+        }
+    }
+}
+
+fn push_authorship(runs: &mut Vec<AuthorshipRun>, run: AuthorshipRun) {
+    if run.len == 0 {
+        return;
+    }
+    match runs.last_mut() {
+        Some(last) if last.user_id == run.user_id => last.len += run.len,
+        _ => runs.push(run),
+    }
+}
+
 fn operation_to_storage(
     operation: &proto::Operation,
     buffer: &buffer::Model,
+    user_id: UserId,
+    created_at: PrimitiveDateTime,
     _format: i32,
 ) -> Option<buffer_operation::ActiveModel> {
     let (replica_id, lamport_timestamp, value) = match operation.variant.as_ref()? {
@@ -829,6 +1157,8 @@ fn operation_to_storage(
         replica_id: ActiveValue::Set(replica_id as i32),
         lamport_timestamp: ActiveValue::Set(lamport_timestamp as i32),
         value: ActiveValue::Set(value.encode_to_vec()),
+        user_id: ActiveValue::Set(Some(user_id)),
+        created_at: ActiveValue::Set(Some(created_at)),
     })
 }
 
@@ -236,25 +236,45 @@ impl Database {
             let channel = self.get_channel_internal(channel_id, &tx).await?;
             self.check_user_is_channel_admin(&channel, user_id, &tx)
                 .await?;
+            self.delete_channel_internal(&channel, &tx).await
+        })
+        .await
+    }
 
-            let channels_to_remove = self
-                .get_channel_descendants_excluding_self([&channel], &tx)
-                .await?
-                .into_iter()
-                .map(|channel| channel.id)
-                .chain(Some(channel_id))
-                .collect::<Vec<_>>();
-
-            channel::Entity::delete_many()
-                .filter(channel::Column::Id.is_in(channels_to_remove.iter().copied()))
-                .exec(&*tx)
-                .await?;
-
-            Ok((channel.root_id(), channels_to_remove))
+    /// Deletes a channel and its descendants on behalf of the server's administrators,
+    /// without checking the permissions of any user.
+    pub async fn delete_channel_as_server_admin(
+        &self,
+        channel_id: ChannelId,
+    ) -> Result<(ChannelId, Vec<ChannelId>)> {
+        self.transaction(move |tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.delete_channel_internal(&channel, &tx).await
         })
         .await
     }
 
+    async fn delete_channel_internal(
+        &self,
+        channel: &channel::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<(ChannelId, Vec<ChannelId>)> {
+        let channels_to_remove = self
+            .get_channel_descendants_excluding_self([channel], tx)
+            .await?
+            .into_iter()
+            .map(|channel| channel.id)
+            .chain(Some(channel.id))
+            .collect::<Vec<_>>();
+
+        channel::Entity::delete_many()
+            .filter(channel::Column::Id.is_in(channels_to_remove.iter().copied()))
+            .exec(tx)
+            .await?;
+
+        Ok((channel.root_id(), channels_to_remove))
+    }
+
     /// Invites a user to a channel as a member.
     pub async fn invite_channel_member(
         &self,
@@ -450,35 +470,100 @@ impl Database {
                     .await?;
             }
 
-            let result = channel_member::Entity::delete_many()
-                .filter(
-                    channel_member::Column::ChannelId
-                        .eq(channel_id)
-                        .and(channel_member::Column::UserId.eq(member_id)),
+            self.remove_channel_member_internal(&channel, member_id, &tx)
+                .await
+        })
+        .await
+    }
+
+    /// Removes a channel member on behalf of the server's administrators, without
+    /// checking the permissions of any user.
+    pub async fn remove_channel_member_as_server_admin(
+        &self,
+        channel_id: ChannelId,
+        member_id: UserId,
+    ) -> Result<RemoveChannelMemberResult> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.remove_channel_member_internal(&channel, member_id, &tx)
+                .await
+        })
+        .await
+    }
+
+    async fn remove_channel_member_internal(
+        &self,
+        channel: &channel::Model,
+        member_id: UserId,
+        tx: &DatabaseTransaction,
+    ) -> Result<RemoveChannelMemberResult> {
+        let result = channel_member::Entity::delete_many()
+            .filter(
+                channel_member::Column::ChannelId
+                    .eq(channel.id)
+                    .and(channel_member::Column::UserId.eq(member_id)),
+            )
+            .exec(tx)
+            .await?;
+
+        if result.rows_affected == 0 {
+            Err(anyhow!("no such member"))?;
+        }
+
+        Ok(RemoveChannelMemberResult {
+            membership_update: self
+                .calculate_membership_updated(channel, member_id, tx)
+                .await?,
+            notification_id: self
+                .remove_notification(
+                    member_id,
+                    rpc::Notification::ChannelInvitation {
+                        channel_id: channel.id.to_proto(),
+                        channel_name: Default::default(),
+                        inviter_id: Default::default(),
+                    },
+                    tx,
                 )
-                .exec(&*tx)
-                .await?;
+                .await?,
+        })
+    }
 
-            if result.rows_affected == 0 {
-                Err(anyhow!("no such member"))?;
-            }
+    /// Returns a page of the channels on the server. There are no access checks
+    /// here, so this should only be used internally.
+    pub async fn get_all_channels(&self, page: u32, limit: u32) -> Result<Vec<channel::Model>> {
+        self.transaction(|tx| async move {
+            Ok(channel::Entity::find()
+                .order_by_asc(channel::Column::ParentPath)
+                .order_by_asc(channel::Column::Id)
+                .limit(limit as u64)
+                .offset(page as u64 * limit as u64)
+                .all(&*tx)
+                .await?)
+        })
+        .await
+    }
 
-            Ok(RemoveChannelMemberResult {
-                membership_update: self
-                    .calculate_membership_updated(&channel, member_id, &tx)
-                    .await?,
-                notification_id: self
-                    .remove_notification(
-                        member_id,
-                        rpc::Notification::ChannelInvitation {
-                            channel_id: channel_id.to_proto(),
-                            channel_name: Default::default(),
-                            inviter_id: Default::default(),
-                        },
-                        &tx,
-                    )
-                    .await?,
-            })
+    /// Returns the channel with the given id, if it exists. There are no access
+    /// checks here, so this should only be used internally.
+    pub async fn get_channel_by_id(&self, channel_id: ChannelId) -> Result<Option<channel::Model>> {
+        self.transaction(|tx| async move {
+            Ok(channel::Entity::find_by_id(channel_id).one(&*tx).await?)
+        })
+        .await
+    }
+
+    /// Returns the memberships of the given channel, including pending invitations.
+    /// There are no access checks here, so this should only be used internally.
+    pub async fn get_channel_memberships(
+        &self,
+        channel_id: ChannelId,
+    ) -> Result<Vec<channel_member::Model>> {
+        self.transaction(|tx| async move {
+            Ok(channel_member::Entity::find()
+                .filter(channel_member::Column::ChannelId.eq(channel_id))
+                .order_by_asc(channel_member::Column::UserId)
+                .all(&*tx)
+                .await?)
         })
         .await
     }
@@ -1,9 +1,16 @@
 use super::*;
 use rpc::Notification;
-use sea_orm::{SelectColumns, TryInsertResult};
+use sea_orm::{sea_query::Query, SelectColumns, TryInsertResult};
+use std::collections::BTreeSet;
 use time::OffsetDateTime;
 use util::ResultExt;
 
+/// The maximum length of a term in the message search index, in characters.
+const MAX_SEARCH_TERM_LEN: usize = 64;
+
+/// The maximum number of terms of a search query that are matched against messages.
+const MAX_SEARCH_QUERY_TERMS: usize = 8;
+
 impl Database {
     /// Inserts a record representing a user joining the chat for a given channel.
     pub async fn join_channel_chat(
@@ -77,7 +84,7 @@ impl Database {
         .await
     }
 
-    /// Retrieves the messages in the specified channel.
+    /// Retrieves the messages in the specified channel, excluding replies in threads.
     ///
     /// Use `before_message_id` to paginate through the channel's messages.
     pub async fn get_channel_messages(
@@ -92,8 +99,9 @@ impl Database {
             self.check_user_is_channel_participant(&channel, user_id, &tx)
                 .await?;
 
-            let mut condition =
-                Condition::all().add(channel_message::Column::ChannelId.eq(channel_id));
+            let mut condition = Condition::all()
+                .add(channel_message::Column::ChannelId.eq(channel_id))
+                .add(channel_message::Column::ThreadParentId.is_null());
 
             if let Some(before_message_id) = before_message_id {
                 condition = condition.add(channel_message::Column::Id.lt(before_message_id));
@@ -111,6 +119,182 @@ impl Database {
         .await
     }
 
+    /// Retrieves the replies in the thread started by the given message, oldest first.
+    pub async fn get_channel_message_thread(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        thread_parent_id: MessageId,
+    ) -> Result<Vec<proto::ChannelMessage>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let rows = channel_message::Entity::find()
+                .filter(channel_message::Column::ChannelId.eq(channel_id))
+                .filter(channel_message::Column::ThreadParentId.eq(thread_parent_id))
+                .order_by_desc(channel_message::Column::Id)
+                .all(&*tx)
+                .await?;
+
+            self.load_channel_messages(rows, &tx).await
+        })
+        .await
+    }
+
+    /// Searches the messages of the channels the user can see, or of a single channel.
+    ///
+    /// Every word of the query has to match the beginning of a word in a message,
+    /// ignoring case. The most recent messages are returned first.
+    pub async fn search_channel_messages(
+        &self,
+        user_id: UserId,
+        query: &str,
+        channel_id: Option<ChannelId>,
+        limit: usize,
+    ) -> Result<Vec<proto::ChannelMessageSearchResult>> {
+        self.transaction(|tx| async move {
+            let query_terms = search_terms(query)
+                .take(MAX_SEARCH_QUERY_TERMS)
+                .collect::<Vec<_>>();
+            if query_terms.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let channel_ids = if let Some(channel_id) = channel_id {
+                let channel = self.get_channel_internal(channel_id, &tx).await?;
+                self.check_user_is_channel_participant(&channel, user_id, &tx)
+                    .await?;
+                vec![channel_id]
+            } else {
+                self.get_user_channels(user_id, None, false, &tx)
+                    .await?
+                    .channels
+                    .into_iter()
+                    .map(|channel| channel.id)
+                    .collect()
+            };
+            if channel_ids.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let mut condition =
+                Condition::all().add(channel_message::Column::ChannelId.is_in(channel_ids));
+            for term in query_terms {
+                condition = condition.add(
+                    channel_message::Column::Id.in_subquery(
+                        Query::select()
+                            .column(channel_message_search_term::Column::MessageId)
+                            .from(channel_message_search_term::Entity)
+                            .and_where(
+                                channel_message_search_term::Column::Term.like(format!("{term}%")),
+                            )
+                            .to_owned(),
+                    ),
+                );
+            }
+
+            let rows = channel_message::Entity::find()
+                .filter(condition)
+                .order_by_desc(channel_message::Column::Id)
+                .limit(limit as u64)
+                .all(&*tx)
+                .await?;
+            let channel_ids_by_message_id = rows
+                .iter()
+                .map(|row| (row.id.to_proto(), row.channel_id))
+                .collect::<HashMap<_, _>>();
+
+            let mut messages = self.load_channel_messages(rows, &tx).await?;
+            messages.reverse();
+            Ok(messages
+                .into_iter()
+                .filter_map(|message| {
+                    let channel_id = channel_ids_by_message_id.get(&message.id)?;
+                    Some(proto::ChannelMessageSearchResult {
+                        channel_id: channel_id.to_proto(),
+                        message: Some(message),
+                    })
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Adds or removes the user's reaction to a channel message.
+    pub async fn set_channel_message_reaction(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        emoji: &str,
+        reacted: bool,
+    ) -> Result<UpdatedChannelMessageReactions> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let participants = channel_chat_participant::Entity::find()
+                .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
+                .all(&*tx)
+                .await?;
+            if !participants
+                .iter()
+                .any(|participant| participant.user_id == user_id)
+            {
+                Err(anyhow!("not a chat participant"))?;
+            }
+
+            channel_message::Entity::find_by_id(message_id)
+                .filter(channel_message::Column::ChannelId.eq(channel_id))
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such message"))?;
+
+            if reacted {
+                channel_message_reaction::Entity::insert(channel_message_reaction::ActiveModel {
+                    message_id: ActiveValue::Set(message_id),
+                    user_id: ActiveValue::Set(user_id),
+                    emoji: ActiveValue::Set(emoji.to_string()),
+                })
+                .on_conflict(
+                    OnConflict::columns([
+                        channel_message_reaction::Column::MessageId,
+                        channel_message_reaction::Column::UserId,
+                        channel_message_reaction::Column::Emoji,
+                    ])
+                    .do_nothing()
+                    .to_owned(),
+                )
+                .exec_without_returning(&*tx)
+                .await?;
+            } else {
+                channel_message_reaction::Entity::delete_many()
+                    .filter(channel_message_reaction::Column::MessageId.eq(message_id))
+                    .filter(channel_message_reaction::Column::UserId.eq(user_id))
+                    .filter(channel_message_reaction::Column::Emoji.eq(emoji))
+                    .exec(&*tx)
+                    .await?;
+            }
+
+            let reactions = self
+                .channel_message_reactions(&[message_id], &tx)
+                .await?
+                .remove(&message_id)
+                .unwrap_or_default();
+            Ok(UpdatedChannelMessageReactions {
+                participant_connection_ids: participants
+                    .into_iter()
+                    .map(|participant| participant.connection())
+                    .collect(),
+                reactions,
+            })
+        })
+        .await
+    }
+
     /// Returns the channel messages with the given IDs.
     pub async fn get_channel_messages_by_id(
         &self,
@@ -166,11 +350,28 @@ impl Database {
                     edited_at: row
                         .edited_at
                         .map(|t| t.assume_utc().unix_timestamp() as u64),
+                    thread_parent_id: row.thread_parent_id.map(|id| id.to_proto()),
+                    thread_reply_count: 0,
+                    reactions: Vec::new(),
                 }
             })
             .collect::<Vec<_>>();
         messages.reverse();
 
+        let message_ids = messages
+            .iter()
+            .map(|message| MessageId::from_proto(message.id))
+            .collect::<Vec<_>>();
+        let mut reactions = self.channel_message_reactions(&message_ids, tx).await?;
+        let reply_counts = self
+            .channel_message_thread_reply_counts(&message_ids, tx)
+            .await?;
+        for message in &mut messages {
+            let message_id = MessageId::from_proto(message.id);
+            message.reactions = reactions.remove(&message_id).unwrap_or_default();
+            message.thread_reply_count = reply_counts.get(&message_id).copied().unwrap_or(0);
+        }
+
         let mut mentions = channel_message_mention::Entity::find()
             .filter(channel_message_mention::Column::MessageId.is_in(messages.iter().map(|m| m.id)))
             .order_by_asc(channel_message_mention::Column::MessageId)
@@ -203,6 +404,91 @@ impl Database {
         Ok(messages)
     }
 
+    /// Returns the reactions to the given messages, grouped by emoji.
+    async fn channel_message_reactions(
+        &self,
+        message_ids: &[MessageId],
+        tx: &DatabaseTransaction,
+    ) -> Result<HashMap<MessageId, Vec<proto::ChannelMessageReaction>>> {
+        let mut rows = channel_message_reaction::Entity::find()
+            .filter(channel_message_reaction::Column::MessageId.is_in(message_ids.iter().copied()))
+            .order_by_asc(channel_message_reaction::Column::MessageId)
+            .order_by_asc(channel_message_reaction::Column::Emoji)
+            .order_by_asc(channel_message_reaction::Column::UserId)
+            .stream(tx)
+            .await?;
+
+        let mut reactions = HashMap::<MessageId, Vec<proto::ChannelMessageReaction>>::default();
+        while let Some(row) = rows.next().await {
+            let row = row?;
+            let message_reactions = reactions.entry(row.message_id).or_default();
+            match message_reactions.last_mut() {
+                Some(reaction) if reaction.emoji == row.emoji => {
+                    reaction.user_ids.push(row.user_id.to_proto());
+                }
+                _ => message_reactions.push(proto::ChannelMessageReaction {
+                    emoji: row.emoji,
+                    user_ids: vec![row.user_id.to_proto()],
+                }),
+            }
+        }
+        Ok(reactions)
+    }
+
+    /// Returns the number of replies in the threads started by the given messages.
+    async fn channel_message_thread_reply_counts(
+        &self,
+        message_ids: &[MessageId],
+        tx: &DatabaseTransaction,
+    ) -> Result<HashMap<MessageId, u32>> {
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+        enum QueryThreadReplyCounts {
+            ThreadParentId,
+            Count,
+        }
+
+        let rows = channel_message::Entity::find()
+            .filter(channel_message::Column::ThreadParentId.is_in(message_ids.iter().copied()))
+            .select_only()
+            .column(channel_message::Column::ThreadParentId)
+            .column_as(channel_message::Column::Id.count(), "count")
+            .group_by(channel_message::Column::ThreadParentId)
+            .into_values::<(MessageId, i64), QueryThreadReplyCounts>()
+            .all(tx)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(thread_parent_id, count)| (thread_parent_id, count as u32))
+            .collect())
+    }
+
+    /// Replaces the terms under which a message can be found when searching.
+    async fn index_channel_message(
+        &self,
+        message_id: MessageId,
+        body: &str,
+        tx: &DatabaseTransaction,
+    ) -> Result<()> {
+        channel_message_search_term::Entity::delete_many()
+            .filter(channel_message_search_term::Column::MessageId.eq(message_id))
+            .exec(tx)
+            .await?;
+
+        let terms = search_terms(body)
+            .map(|term| channel_message_search_term::ActiveModel {
+                message_id: ActiveValue::Set(message_id),
+                term: ActiveValue::Set(term),
+            })
+            .collect::<Vec<_>>();
+        if !terms.is_empty() {
+            channel_message_search_term::Entity::insert_many(terms)
+                .exec_without_returning(tx)
+                .await?;
+        }
+        Ok(())
+    }
+
     fn format_mentions_to_entities(
         &self,
         message_id: MessageId,
@@ -239,12 +525,73 @@ impl Database {
         timestamp: OffsetDateTime,
         nonce: u128,
         reply_to_message_id: Option<MessageId>,
+    ) -> Result<CreatedChannelMessage> {
+        self.insert_channel_message(
+            channel_id,
+            user_id,
+            body,
+            mentions,
+            timestamp,
+            nonce,
+            reply_to_message_id,
+            None,
+        )
+        .await
+    }
+
+    /// Creates a reply in the thread started by the given channel message.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_channel_thread_reply(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        thread_parent_id: MessageId,
+        body: &str,
+        mentions: &[proto::ChatMention],
+        timestamp: OffsetDateTime,
+        nonce: u128,
+    ) -> Result<CreatedChannelMessage> {
+        self.insert_channel_message(
+            channel_id,
+            user_id,
+            body,
+            mentions,
+            timestamp,
+            nonce,
+            None,
+            Some(thread_parent_id),
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_channel_message(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        body: &str,
+        mentions: &[proto::ChatMention],
+        timestamp: OffsetDateTime,
+        nonce: u128,
+        reply_to_message_id: Option<MessageId>,
+        thread_parent_id: Option<MessageId>,
     ) -> Result<CreatedChannelMessage> {
         self.transaction(|tx| async move {
             let channel = self.get_channel_internal(channel_id, &tx).await?;
             self.check_user_is_channel_participant(&channel, user_id, &tx)
                 .await?;
 
+            if let Some(thread_parent_id) = thread_parent_id {
+                let thread_parent = channel_message::Entity::find_by_id(thread_parent_id)
+                    .filter(channel_message::Column::ChannelId.eq(channel_id))
+                    .one(&*tx)
+                    .await?
+                    .ok_or_else(|| anyhow!("no such message"))?;
+                if thread_parent.thread_parent_id.is_some() {
+                    Err(anyhow!("can't start a thread in a thread"))?;
+                }
+            }
+
             let mut rows = channel_chat_participant::Entity::find()
                 .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
                 .stream(&*tx)
@@ -279,6 +626,7 @@ impl Database {
                 id: ActiveValue::NotSet,
                 reply_to_message_id: ActiveValue::Set(reply_to_message_id),
                 edited_at: ActiveValue::NotSet,
+                thread_parent_id: ActiveValue::Set(thread_parent_id),
             })
             .on_conflict(
                 OnConflict::columns([
@@ -306,6 +654,7 @@ impl Database {
                             .exec(&*tx)
                             .await?;
                     }
+                    self.index_channel_message(message_id, body, &tx).await?;
 
                     for mentioned_user in mentioned_user_ids {
                         notifications.extend(
@@ -323,8 +672,12 @@ impl Database {
                         );
                     }
 
-                    self.observe_channel_message_internal(channel_id, user_id, message_id, &tx)
-                        .await?;
+                    // Replies in threads aren't part of the channel's list of messages, so
+                    // they can't be acknowledged as the latest message.
+                    if thread_parent_id.is_none() {
+                        self.observe_channel_message_internal(channel_id, user_id, message_id, &tx)
+                            .await?;
+                    }
                 }
                 _ => {
                     message_id = channel_message::Entity::find()
@@ -453,7 +806,8 @@ impl Database {
                     ) as row_number
                 FROM channel_messages
                 WHERE
-                    channel_id in ({values})
+                    channel_id in ({values}) AND
+                    thread_parent_id IS NULL
             ) AS messages
             WHERE
                 row_number = 1
@@ -484,13 +838,15 @@ impl Database {
             .map(|kind| kind.0 .0)
     }
 
-    /// Removes the channel message with the given ID.
+    /// Removes the channel message with the given ID, along with the replies in its thread.
+    ///
+    /// Returns the id of the thread the message was a reply in, if any.
     pub async fn remove_channel_message(
         &self,
         channel_id: ChannelId,
         message_id: MessageId,
         user_id: UserId,
-    ) -> Result<(Vec<ConnectionId>, Vec<NotificationId>)> {
+    ) -> Result<(Vec<ConnectionId>, Vec<NotificationId>, Option<MessageId>)> {
         self.transaction(|tx| async move {
             let mut rows = channel_chat_participant::Entity::find()
                 .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
@@ -512,6 +868,11 @@ impl Database {
                 Err(anyhow!("not a chat participant"))?;
             }
 
+            let thread_parent_id = channel_message::Entity::find_by_id(message_id)
+                .one(&*tx)
+                .await?
+                .and_then(|message| message.thread_parent_id);
+
             let result = channel_message::Entity::delete_by_id(message_id)
                 .filter(channel_message::Column::SenderId.eq(user_id))
                 .exec(&*tx)
@@ -557,7 +918,11 @@ impl Database {
                 .exec(&*tx)
                 .await?;
 
-            Ok((participant_connection_ids, existing_notification_ids))
+            Ok((
+                participant_connection_ids,
+                existing_notification_ids,
+                thread_parent_id,
+            ))
         })
         .await
     }
@@ -620,6 +985,7 @@ impl Database {
                 sender_id: ActiveValue::Unchanged(user_id),
                 sent_at: ActiveValue::Unchanged(channel_message.sent_at),
                 nonce: ActiveValue::Unchanged(channel_message.nonce),
+                thread_parent_id: ActiveValue::Unchanged(channel_message.thread_parent_id),
             };
 
             let result = channel_message::Entity::update_many()
@@ -654,6 +1020,7 @@ impl Database {
                     .exec(&*tx)
                     .await?;
             }
+            self.index_channel_message(message_id, body, &tx).await?;
 
             let mut update_mention_user_ids = HashSet::default();
             let mut new_mention_user_ids =
@@ -713,6 +1080,7 @@ impl Database {
                 participant_connection_ids,
                 notifications,
                 reply_to_message_id: channel_message.reply_to_message_id,
+                thread_parent_id: channel_message.thread_parent_id,
                 timestamp: channel_message.sent_at,
                 deleted_mention_notification_ids: deleted_notification_ids
                     .into_iter()
@@ -723,3 +1091,17 @@ impl Database {
         .await
     }
 }
+
+/// Splits text into the lowercase words under which a message is indexed for search.
+fn search_terms(text: &str) -> impl Iterator<Item = String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            word.to_lowercase()
+                .chars()
+                .take(MAX_SEARCH_TERM_LEN)
+                .collect()
+        })
+        .collect::<BTreeSet<String>>()
+        .into_iter()
+}
@@ -1101,6 +1101,30 @@ impl Database {
         .map(|guard| guard.into_inner())
     }
 
+    /// Returns the host connection for a guest asking the host of a shared project to run
+    /// one of its tasks, along with the role of that guest.
+    pub async fn host_for_task_request(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        user_id: UserId,
+    ) -> Result<(ConnectionId, ChannelRole)> {
+        self.project_transaction(project_id, |tx| async move {
+            let (project, role) = self
+                .access_project(
+                    project_id,
+                    connection_id,
+                    PrincipalId::UserId(user_id),
+                    Capability::ReadOnly,
+                    &tx,
+                )
+                .await?;
+            Ok((project.host_connection()?, role))
+        })
+        .await
+        .map(|guard| guard.into_inner())
+    }
+
     /// Returns the host connection for a request to join a shared project.
     pub async fn host_for_owner_project_request(
         &self,
@@ -1167,6 +1191,41 @@ impl Database {
         .await
     }
 
+    /// Returns the connection IDs of the guests in the given project, for a message sent by
+    /// its host.
+    ///
+    /// The provided `connection_id` must be the host of the project, otherwise an error
+    /// will be returned.
+    pub async fn project_connection_ids_for_host(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+    ) -> Result<TransactionGuard<HashSet<ConnectionId>>> {
+        self.project_transaction(project_id, |tx| async move {
+            let project = project::Entity::find_by_id(project_id)
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such project"))?;
+            if project.host_connection().ok() != Some(connection_id) {
+                Err(anyhow!("only the host can send this message to a project"))?;
+            }
+
+            let mut collaborators = project_collaborator::Entity::find()
+                .filter(project_collaborator::Column::ProjectId.eq(project_id))
+                .stream(&*tx)
+                .await?;
+            let mut connection_ids = HashSet::default();
+            while let Some(collaborator) = collaborators.next().await {
+                let collaborator = collaborator?;
+                if !collaborator.is_host {
+                    connection_ids.insert(collaborator.connection());
+                }
+            }
+            Ok(connection_ids)
+        })
+        .await
+    }
+
     /// Returns the connection IDs in the given project.
     ///
     /// The provided `connection_id` must also be a collaborator in the project,
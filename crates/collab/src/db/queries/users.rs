@@ -216,6 +216,123 @@ impl Database {
         .await
     }
 
+    /// Creates a user that isn't linked to a GitHub account. If a password hash is
+    /// given, the user can sign in with local authentication.
+    pub async fn create_local_user(
+        &self,
+        login: &str,
+        email_address: Option<&str>,
+        admin: bool,
+        password_hash: Option<&str>,
+    ) -> Result<User> {
+        self.transaction(|tx| async move {
+            let existing_user = user::Entity::find()
+                .filter(user::Column::GithubLogin.eq(login))
+                .one(&*tx)
+                .await?;
+            if existing_user.is_some() {
+                Err(anyhow!("user {} already exists", login))?;
+            }
+
+            let user = user::Entity::insert(user::ActiveModel {
+                github_login: ActiveValue::set(login.into()),
+                email_address: ActiveValue::set(email_address.map(Into::into)),
+                admin: ActiveValue::set(admin),
+                metrics_id: ActiveValue::set(Uuid::new_v4()),
+                ..Default::default()
+            })
+            .exec_with_returning(&*tx)
+            .await?;
+            if let Some(password_hash) = password_hash {
+                self.set_user_password_hash_internal(user.id, password_hash, &tx)
+                    .await?;
+            }
+            Ok(user)
+        })
+        .await
+    }
+
+    /// Sets the password hash used to sign the given user in with local authentication.
+    pub async fn set_user_password_hash(&self, user_id: UserId, password_hash: &str) -> Result<()> {
+        self.transaction(|tx| async move {
+            self.set_user_password_hash_internal(user_id, password_hash, &tx)
+                .await
+        })
+        .await
+    }
+
+    async fn set_user_password_hash_internal(
+        &self,
+        user_id: UserId,
+        password_hash: &str,
+        tx: &DatabaseTransaction,
+    ) -> Result<()> {
+        let now = time::OffsetDateTime::now_utc();
+        user_password::Entity::insert(user_password::ActiveModel {
+            user_id: ActiveValue::set(user_id),
+            hash: ActiveValue::set(password_hash.into()),
+            updated_at: ActiveValue::set(PrimitiveDateTime::new(now.date(), now.time())),
+        })
+        .on_conflict(
+            OnConflict::column(user_password::Column::UserId)
+                .update_columns([
+                    user_password::Column::Hash,
+                    user_password::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the user with the given login along with their password hash, if they
+    /// have one. There are no access checks here, so this should only be used internally.
+    pub async fn get_user_and_password_hash_by_login(
+        &self,
+        login: &str,
+    ) -> Result<Option<(User, String)>> {
+        self.transaction(|tx| async move {
+            let Some((user, password)) = user::Entity::find()
+                .filter(user::Column::GithubLogin.eq(login))
+                .find_also_related(user_password::Entity)
+                .one(&*tx)
+                .await?
+            else {
+                return Ok(None);
+            };
+            Ok(password.map(|password| (user, password.hash)))
+        })
+        .await
+    }
+
+    /// Disables or re-enables the given user. Disabling a user also revokes their
+    /// access tokens, so they need to sign in again once they're re-enabled.
+    pub async fn set_user_disabled(&self, id: UserId, disabled: bool) -> Result<()> {
+        self.transaction(|tx| async move {
+            let result = user::Entity::update_many()
+                .filter(user::Column::Id.eq(id))
+                .set(user::ActiveModel {
+                    disabled: ActiveValue::set(disabled),
+                    ..Default::default()
+                })
+                .exec(&*tx)
+                .await?;
+            if result.rows_affected == 0 {
+                Err(anyhow!("no such user {}", id))?;
+            }
+
+            if disabled {
+                access_token::Entity::delete_many()
+                    .filter(access_token::Column::UserId.eq(id))
+                    .exec(&*tx)
+                    .await?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
     /// Find users where github_login ILIKE name_query.
     pub async fn fuzzy_search_users(&self, name_query: &str, limit: u32) -> Result<Vec<User>> {
         self.transaction(|tx| async {
@@ -271,6 +388,28 @@ impl Database {
         .await
     }
 
+    /// Returns all of the feature flags.
+    pub async fn get_feature_flags(&self) -> Result<Vec<feature_flag::Model>> {
+        self.transaction(|tx| async move {
+            Ok(feature_flag::Entity::find()
+                .order_by_asc(feature_flag::Column::Flag)
+                .all(&*tx)
+                .await?)
+        })
+        .await
+    }
+
+    /// Returns the feature flag with the given name.
+    pub async fn get_feature_flag(&self, flag: &str) -> Result<Option<feature_flag::Model>> {
+        self.transaction(|tx| async move {
+            Ok(feature_flag::Entity::find()
+                .filter(feature_flag::Column::Flag.eq(flag))
+                .one(&*tx)
+                .await?)
+        })
+        .await
+    }
+
     /// Add the given user to the feature flag
     pub async fn add_user_flag(&self, user: UserId, flag: FlagId) -> Result<()> {
         self.transaction(|tx| async move {
@@ -286,6 +425,23 @@ impl Database {
         .await
     }
 
+    /// Removes the given user from the feature flag.
+    pub async fn remove_user_flag(&self, user: UserId, flag: FlagId) -> Result<()> {
+        self.transaction(|tx| async move {
+            user_feature::Entity::delete_many()
+                .filter(
+                    user_feature::Column::UserId
+                        .eq(user)
+                        .and(user_feature::Column::FeatureId.eq(flag)),
+                )
+                .exec(&*tx)
+                .await?;
+
+            Ok(())
+        })
+        .await
+    }
+
     /// Returns the active flags for the user.
     pub async fn get_user_flags(&self, user: UserId) -> Result<Vec<String>> {
         self.transaction(|tx| async move {
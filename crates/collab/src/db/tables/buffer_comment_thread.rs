@@ -0,0 +1,37 @@
+use crate::db::{BufferCommentThreadId, DevServerProjectId, ProjectId, UserId};
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "buffer_comment_threads")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: BufferCommentThreadId,
+    /// The user who shared the file the thread was left on, unless a dev server did.
+    pub host_user_id: Option<UserId>,
+    pub dev_server_project_id: Option<DevServerProjectId>,
+    /// The absolute path of the root of the worktree containing the file, on the host.
+    pub worktree_root: String,
+    pub path: String,
+    pub row: i32,
+    pub column: i32,
+    /// The project in which `anchor` was created. Anchors can't be resolved once the
+    /// project stopped being shared, so they're only used while it still is.
+    pub anchor_project_id: Option<ProjectId>,
+    /// The position of the thread in its buffer, encoded as a `proto::Anchor`.
+    pub anchor: Option<Vec<u8>>,
+    pub resolved: bool,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::buffer_comment::Entity")]
+    Comments,
+}
+
+impl Related<super::buffer_comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Comments.def()
+    }
+}
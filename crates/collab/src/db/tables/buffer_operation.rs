@@ -1,5 +1,6 @@
-use crate::db::BufferId;
+use crate::db::{BufferId, UserId};
 use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
 #[sea_orm(table_name = "buffer_operations")]
@@ -13,6 +14,8 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub replica_id: i32,
     pub value: Vec<u8>,
+    pub user_id: Option<UserId>,
+    pub created_at: Option<PrimitiveDateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -23,6 +26,12 @@ pub enum Relation {
         to = "super::buffer::Column::Id"
     )]
     Buffer,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
 }
 
 impl Related<super::buffer::Entity> for Entity {
@@ -31,4 +40,10 @@ impl Related<super::buffer::Entity> for Entity {
     }
 }
 
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
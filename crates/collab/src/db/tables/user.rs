@@ -18,6 +18,8 @@ pub struct Model {
     pub connected_once: bool,
     pub metrics_id: Uuid,
     pub created_at: DateTime,
+    /// Disabled users can't sign in or connect.
+    pub disabled: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -34,6 +36,8 @@ pub enum Relation {
     UserFeatures,
     #[sea_orm(has_one = "super::contributor::Entity")]
     Contributor,
+    #[sea_orm(has_one = "super::user_password::Entity")]
+    Password,
 }
 
 impl Related<super::access_token::Entity> for Entity {
@@ -66,6 +70,12 @@ impl Related<super::user_feature::Entity> for Entity {
     }
 }
 
+impl Related<super::user_password::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Password.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
 pub struct UserFlags;
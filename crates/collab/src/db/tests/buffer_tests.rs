@@ -1,6 +1,7 @@
 use super::*;
 use crate::test_both_dbs;
 use language::proto::{self, serialize_version};
+use std::ops::Range;
 use text::Buffer;
 
 test_both_dbs!(
@@ -313,6 +314,188 @@ async fn test_channel_buffers_last_operations(db: &Database) {
     );
 }
 
+test_both_dbs!(
+    test_channel_buffer_history,
+    test_channel_buffer_history_postgres,
+    test_channel_buffer_history_sqlite
+);
+
+async fn test_channel_buffer_history(db: &Arc<Database>) {
+    let a_id = db
+        .create_user(
+            "user_a@example.com",
+            false,
+            NewUserParams {
+                github_login: "user_a".into(),
+                github_user_id: 101,
+            },
+        )
+        .await
+        .unwrap()
+        .user_id;
+    let b_id = db
+        .create_user(
+            "user_b@example.com",
+            false,
+            NewUserParams {
+                github_login: "user_b".into(),
+                github_user_id: 102,
+            },
+        )
+        .await
+        .unwrap()
+        .user_id;
+
+    let owner_id = db.create_server("production").await.unwrap().0 as u32;
+    let zed_id = db.create_root_channel("zed", a_id).await.unwrap();
+    db.invite_channel_member(zed_id, b_id, a_id, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(zed_id, b_id, true)
+        .await
+        .unwrap();
+
+    let connection_id_a = ConnectionId { owner_id, id: 1 };
+    let connection_id_b = ConnectionId { owner_id, id: 2 };
+    db.join_channel_buffer(zed_id, a_id, connection_id_a)
+        .await
+        .unwrap();
+    db.join_channel_buffer(zed_id, b_id, connection_id_b)
+        .await
+        .unwrap();
+
+    let mut buffer_a = Buffer::new(0, text::BufferId::new(1).unwrap(), "".to_string());
+    let mut buffer_b = Buffer::new(1, text::BufferId::new(1).unwrap(), "".to_string());
+    let operation = buffer_a.edit([(0..0, "hello world")]);
+    buffer_b.apply_ops([operation.clone()]).unwrap();
+    update_buffer(zed_id, a_id, db, vec![operation]).await;
+    let operation = buffer_b.edit([(5..5, ", cruel")]);
+    update_buffer(zed_id, b_id, db, vec![operation]).await;
+
+    let history = db.get_channel_buffer_history(zed_id, a_id).await.unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|revision| (revision.epoch, revision.user_id))
+            .collect::<Vec<_>>(),
+        &[(0, Some(a_id.to_proto())), (0, Some(b_id.to_proto()))]
+    );
+
+    let revision = db
+        .get_channel_buffer_revision(
+            zed_id,
+            b_id,
+            rpc::proto::get_channel_buffer_revision::At::Revision(history[0].clone()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(revision.text, "hello world");
+    assert_eq!(authorship(&revision), &[(0..11, Some(a_id))]);
+
+    let revision = db
+        .get_channel_buffer_revision(
+            zed_id,
+            b_id,
+            rpc::proto::get_channel_buffer_revision::At::Revision(history[1].clone()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(revision.text, "hello, cruel world");
+    assert_eq!(
+        authorship(&revision),
+        &[
+            (0..5, Some(a_id)),
+            (5..12, Some(b_id)),
+            (12..18, Some(a_id))
+        ]
+    );
+
+    let revision = db
+        .get_channel_buffer_revision(
+            zed_id,
+            b_id,
+            rpc::proto::get_channel_buffer_revision::At::Epoch(0),
+        )
+        .await
+        .unwrap();
+    assert_eq!(revision.text, "");
+    assert_eq!(revision.revision, None);
+
+    // Once everyone leaves, the buffer moves to a new epoch, but the authorship of
+    // the text is preserved.
+    db.leave_channel_buffer(zed_id, connection_id_a)
+        .await
+        .unwrap();
+    db.leave_channel_buffer(zed_id, connection_id_b)
+        .await
+        .unwrap();
+    let response = db
+        .join_channel_buffer(zed_id, a_id, connection_id_a)
+        .await
+        .unwrap();
+    assert_eq!(response.epoch, 1);
+    let mut buffer_a = Buffer::new(
+        response.replica_id as u16,
+        text::BufferId::new(1).unwrap(),
+        response.base_text,
+    );
+    let operation = buffer_a.edit([(18..18, "!")]);
+    update_buffer(zed_id, a_id, db, vec![operation]).await;
+
+    let history = db.get_channel_buffer_history(zed_id, b_id).await.unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|revision| (revision.epoch, revision.user_id))
+            .collect::<Vec<_>>(),
+        &[
+            (0, Some(a_id.to_proto())),
+            (0, Some(b_id.to_proto())),
+            (1, Some(a_id.to_proto()))
+        ]
+    );
+
+    let revision = db
+        .get_channel_buffer_revision(
+            zed_id,
+            b_id,
+            rpc::proto::get_channel_buffer_revision::At::Revision(history[2].clone()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(revision.text, "hello, cruel world!");
+    assert_eq!(
+        authorship(&revision),
+        &[
+            (0..5, Some(a_id)),
+            (5..12, Some(b_id)),
+            (12..19, Some(a_id))
+        ]
+    );
+
+    let revision = db
+        .get_channel_buffer_revision(
+            zed_id,
+            b_id,
+            rpc::proto::get_channel_buffer_revision::At::Epoch(1),
+        )
+        .await
+        .unwrap();
+    assert_eq!(revision.text, "hello, cruel world");
+    assert_eq!(revision.revision, Some(history[1].clone()));
+
+    // Any revision can be looked up by the time it was made.
+    let revision = db
+        .get_channel_buffer_revision(
+            zed_id,
+            b_id,
+            rpc::proto::get_channel_buffer_revision::At::Timestamp(history[2].timestamp.unwrap()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(revision.text, "hello, cruel world!");
+}
+
 async fn update_buffer(
     channel_id: ChannelId,
     user_id: UserId,
@@ -327,3 +510,18 @@ async fn update_buffer(
         .await
         .unwrap();
 }
+
+fn authorship(
+    revision: &rpc::proto::GetChannelBufferRevisionResponse,
+) -> Vec<(Range<usize>, Option<UserId>)> {
+    revision
+        .authorship
+        .iter()
+        .map(|range| {
+            (
+                range.start as usize..range.end as usize,
+                range.user_id.map(UserId::from_proto),
+            )
+        })
+        .collect()
+}
@@ -555,6 +555,77 @@ async fn test_project_count(db: &Arc<Database>) {
     assert_eq!(db.project_count_excluding_admins().await.unwrap(), 0);
 }
 
+test_both_dbs!(
+    test_project_connection_ids_for_host,
+    test_project_connection_ids_for_host_postgres,
+    test_project_connection_ids_for_host_sqlite
+);
+
+async fn test_project_connection_ids_for_host(db: &Arc<Database>) {
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    let host_connection = ConnectionId { owner_id, id: 0 };
+    let guest_connection = ConnectionId { owner_id, id: 1 };
+
+    let user1 = db
+        .create_user(
+            "user1@example.com",
+            false,
+            NewUserParams {
+                github_login: "user1".into(),
+                github_user_id: 0,
+            },
+        )
+        .await
+        .unwrap();
+    let user2 = db
+        .create_user(
+            "user2@example.com",
+            false,
+            NewUserParams {
+                github_login: "user2".into(),
+                github_user_id: 1,
+            },
+        )
+        .await
+        .unwrap();
+
+    let room_id = RoomId::from_proto(
+        db.create_room(user1.user_id, host_connection, "")
+            .await
+            .unwrap()
+            .id,
+    );
+    db.call(room_id, user1.user_id, host_connection, user2.user_id, None)
+        .await
+        .unwrap();
+    db.join_room(room_id, user2.user_id, guest_connection)
+        .await
+        .unwrap();
+
+    let project_id = db
+        .share_project(room_id, host_connection, &[], None)
+        .await
+        .unwrap()
+        .0;
+    db.join_project(project_id, guest_connection, user2.user_id)
+        .await
+        .unwrap();
+
+    // The host can broadcast to its guests.
+    let connection_ids = db
+        .project_connection_ids_for_host(project_id, host_connection)
+        .await
+        .unwrap();
+    assert_eq!(*connection_ids, HashSet::from_iter([guest_connection]));
+    drop(connection_ids);
+
+    // Guests can't send messages that only the host may send.
+    assert!(db
+        .project_connection_ids_for_host(project_id, guest_connection)
+        .await
+        .is_err());
+}
+
 #[test]
 fn test_fuzzy_like_string() {
     assert_eq!(Database::fuzzy_like_string("abcd"), "%a%b%c%d%");
@@ -419,3 +419,234 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         ]
     );
 }
+
+test_both_dbs!(
+    test_channel_message_threads_and_reactions,
+    test_channel_message_threads_and_reactions_postgres,
+    test_channel_message_threads_and_reactions_sqlite
+);
+
+async fn test_channel_message_threads_and_reactions(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+    let channel = db.create_root_channel("channel", user_a).await.unwrap();
+    db.invite_channel_member(channel, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, user_b, true)
+        .await
+        .unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 0 }, user_a)
+        .await
+        .unwrap();
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 1 }, user_b)
+        .await
+        .unwrap();
+
+    let parent_id = db
+        .create_channel_message(
+            channel,
+            user_a,
+            "shall we ship it?",
+            &[],
+            OffsetDateTime::now_utc(),
+            1,
+            None,
+        )
+        .await
+        .unwrap()
+        .message_id;
+    for (nonce, body) in [(2, "yes"), (3, "after the review")] {
+        db.create_channel_thread_reply(
+            channel,
+            user_b,
+            parent_id,
+            body,
+            &[],
+            OffsetDateTime::now_utc(),
+            nonce,
+        )
+        .await
+        .unwrap();
+    }
+
+    // Replies in the thread aren't part of the channel's messages, and threads can't be nested.
+    let messages = db
+        .get_channel_messages(channel, user_a, 10, None)
+        .await
+        .unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].thread_reply_count, 2);
+    let replies = db
+        .get_channel_message_thread(channel, user_a, parent_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        replies.iter().map(|m| m.body.as_str()).collect::<Vec<_>>(),
+        ["yes", "after the review"]
+    );
+    assert!(replies
+        .iter()
+        .all(|reply| reply.thread_parent_id == Some(parent_id.to_proto())));
+    assert!(db
+        .create_channel_thread_reply(
+            channel,
+            user_a,
+            MessageId::from_proto(replies[0].id),
+            "nested",
+            &[],
+            OffsetDateTime::now_utc(),
+            4,
+        )
+        .await
+        .is_err());
+
+    // Reactions are grouped by emoji.
+    db.set_channel_message_reaction(channel, parent_id, user_a, "👍", true)
+        .await
+        .unwrap();
+    db.set_channel_message_reaction(channel, parent_id, user_b, "👍", true)
+        .await
+        .unwrap();
+    let reactions = db
+        .set_channel_message_reaction(channel, parent_id, user_b, "🎉", true)
+        .await
+        .unwrap()
+        .reactions;
+    assert_eq!(
+        reactions
+            .iter()
+            .map(|r| (r.emoji.as_str(), r.user_ids.clone()))
+            .collect::<Vec<_>>(),
+        [
+            ("👍", vec![user_a.to_proto(), user_b.to_proto()]),
+            ("🎉", vec![user_b.to_proto()]),
+        ]
+    );
+    db.set_channel_message_reaction(channel, parent_id, user_a, "👍", false)
+        .await
+        .unwrap();
+    let messages = db
+        .get_channel_messages(channel, user_b, 10, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        messages[0]
+            .reactions
+            .iter()
+            .map(|r| (r.emoji.as_str(), r.user_ids.len()))
+            .collect::<Vec<_>>(),
+        [("👍", 1), ("🎉", 1)]
+    );
+
+    // Removing the message removes its thread.
+    db.remove_channel_message(channel, parent_id, user_a)
+        .await
+        .unwrap();
+    assert!(db
+        .get_channel_message_thread(channel, user_a, parent_id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+test_both_dbs!(
+    test_channel_message_search,
+    test_channel_message_search_postgres,
+    test_channel_message_search_sqlite
+);
+
+async fn test_channel_message_search(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+    let channel_1 = db.create_root_channel("channel-1", user_a).await.unwrap();
+    let channel_2 = db.create_root_channel("channel-2", user_a).await.unwrap();
+    db.invite_channel_member(channel_1, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel_1, user_b, true)
+        .await
+        .unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    let connection_id = rpc::ConnectionId { owner_id, id: 0 };
+    db.join_channel_chat(channel_1, connection_id, user_a)
+        .await
+        .unwrap();
+    db.join_channel_chat(channel_2, connection_id, user_a)
+        .await
+        .unwrap();
+
+    let mut message_ids = Vec::new();
+    for (nonce, (channel, body)) in [
+        (channel_1, "We decided to use Postgres for the index."),
+        (channel_1, "The DECISION is final"),
+        (channel_2, "Postgres decision in a private channel"),
+        (channel_1, "unrelated"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        message_ids.push(
+            db.create_channel_message(
+                channel,
+                user_a,
+                body,
+                &[],
+                OffsetDateTime::now_utc(),
+                nonce as u128,
+                None,
+            )
+            .await
+            .unwrap()
+            .message_id
+            .to_proto(),
+        );
+    }
+
+    let search = |user_id, query: &'static str, channel_id| async move {
+        db.search_channel_messages(user_id, query, channel_id, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|result| result.message.unwrap().id)
+            .collect::<Vec<_>>()
+    };
+
+    // Words match by prefix, ignoring case, and the latest messages come first.
+    assert_eq!(
+        search(user_a, "decis", None).await,
+        [message_ids[2], message_ids[1]]
+    );
+    assert_eq!(
+        search(user_a, "postgres DECIDED", None).await,
+        [message_ids[0]]
+    );
+    assert_eq!(
+        search(user_a, "decision", Some(channel_2)).await,
+        [message_ids[2]]
+    );
+    assert!(search(user_a, "  ", None).await.is_empty());
+
+    // Users only find messages in the channels they can see.
+    assert_eq!(search(user_b, "decision", None).await, [message_ids[1]]);
+
+    // Edited messages are found by their new body.
+    db.update_channel_message(
+        channel_1,
+        MessageId::from_proto(message_ids[3]),
+        user_a,
+        "actually related to the decision",
+        &[],
+        OffsetDateTime::now_utc(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        search(user_b, "decision", None).await,
+        [message_ids[3], message_ids[1]]
+    );
+    assert!(search(user_b, "unrelated", None).await.is_empty());
+}
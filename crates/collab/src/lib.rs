@@ -1,6 +1,7 @@
 pub mod ai;
 pub mod api;
 pub mod auth;
+pub mod blob_store;
 pub mod db;
 pub mod env;
 pub mod executor;
@@ -14,6 +15,7 @@ mod tests;
 use anyhow::anyhow;
 use aws_config::{BehaviorVersion, Region};
 use axum::{http::StatusCode, response::IntoResponse};
+use blob_store::BlobStore;
 use db::{ChannelId, Database};
 use executor::Executor;
 pub use rate_limiter::*;
@@ -131,6 +133,13 @@ pub struct Config {
     pub blob_store_access_key: Option<String>,
     pub blob_store_secret_key: Option<String>,
     pub blob_store_bucket: Option<String>,
+    /// When set, blobs are stored in this directory instead of S3.
+    pub blob_store_path: Option<PathBuf>,
+    /// Allows users to sign in with a username and password, for servers that
+    /// can't rely on zed.dev to authenticate users with GitHub.
+    pub local_auth_enabled: Option<bool>,
+    /// Enables the telemetry endpoints. Off unless explicitly enabled.
+    pub telemetry_enabled: Option<bool>,
     pub zed_environment: Arc<str>,
     pub openai_api_key: Option<Arc<str>>,
     pub google_ai_api_key: Option<Arc<str>>,
@@ -145,12 +154,20 @@ impl Config {
     pub fn is_development(&self) -> bool {
         self.zed_environment == "development".into()
     }
+
+    pub fn is_local_auth_enabled(&self) -> bool {
+        self.local_auth_enabled.unwrap_or(false)
+    }
+
+    pub fn is_telemetry_enabled(&self) -> bool {
+        self.telemetry_enabled.unwrap_or(false)
+    }
 }
 
 pub struct AppState {
     pub db: Arc<Database>,
     pub live_kit_client: Option<Arc<dyn live_kit_server::api::Client>>,
-    pub blob_store_client: Option<aws_sdk_s3::Client>,
+    pub blob_store: Option<BlobStore>,
     pub rate_limiter: Arc<RateLimiter>,
     pub executor: Executor,
     pub clickhouse_client: Option<clickhouse::Client>,
@@ -183,12 +200,13 @@ impl AppState {
         let this = Self {
             db: db.clone(),
             live_kit_client,
-            blob_store_client: build_blob_store_client(&config).await.log_err(),
+            blob_store: build_blob_store(&config).await.log_err(),
             rate_limiter: Arc::new(RateLimiter::new(db)),
             executor,
             clickhouse_client: config
                 .clickhouse_url
                 .as_ref()
+                .filter(|_| config.is_telemetry_enabled())
                 .and_then(|_| build_clickhouse_client(&config).log_err()),
             config,
         };
@@ -196,6 +214,16 @@ impl AppState {
     }
 }
 
+async fn build_blob_store(config: &Config) -> anyhow::Result<BlobStore> {
+    if let Some(root) = config.blob_store_path.clone() {
+        return Ok(BlobStore::FileSystem { root });
+    }
+
+    Ok(BlobStore::S3 {
+        client: build_blob_store_client(config).await?,
+    })
+}
+
 async fn build_blob_store_client(config: &Config) -> anyhow::Result<aws_sdk_s3::Client> {
     let keys = aws_sdk_s3::config::Credentials::new(
         config
@@ -54,6 +54,32 @@ async fn main() -> Result<()> {
 
             collab::seed::seed(&config, &db, true).await?;
         }
+        Some("create-user") => {
+            let usage = "usage: collab create-user <login> [--admin]";
+            let login = args.next().ok_or_else(|| anyhow!(usage))?;
+            let admin = match args.next().as_deref() {
+                Some("--admin") => true,
+                Some(_) => Err(anyhow!(usage))?,
+                None => false,
+            };
+
+            let config = envy::from_env::<Config>().expect("error loading config");
+            let db_options = db::ConnectOptions::new(config.database_url.clone());
+            let db = Database::new(db_options, Executor::Production).await?;
+
+            // Read the password from the terminal without echoing it.
+            let password = rpassword::prompt_password(format!("enter a password for {login}: "))
+                .map_err(anyhow::Error::from)?;
+            if password.is_empty() {
+                Err(anyhow!("password must not be empty"))?;
+            }
+
+            let password_hash = collab::auth::hash_password(&password)?;
+            let user = db
+                .create_local_user(&login, None, admin, Some(&password_hash))
+                .await?;
+            println!("created user {} with id {}", user.github_login, user.id);
+        }
         Some("serve") => {
             let (is_api, is_collab) = if let Some(next) = args.next() {
                 (next == "api", next == "collab")
@@ -62,7 +88,7 @@ async fn main() -> Result<()> {
             };
             if !is_api && !is_collab {
                 Err(anyhow!(
-                    "usage: collab <version | migrate | seed | serve [api|collab]>"
+                    "usage: collab <version | migrate | seed | create-user | serve [api|collab]>"
                 ))?;
             }
 
@@ -102,15 +128,18 @@ async fn main() -> Result<()> {
             if let Some(rpc_server) = rpc_server.clone() {
                 app = app.merge(collab::rpc::routes(rpc_server))
             }
+            let mut public_routes = Router::new()
+                .route("/", get(handle_root))
+                .route("/healthz", get(handle_liveness_probe))
+                .merge(collab::api::extensions::router());
+            if state.config.is_telemetry_enabled() {
+                public_routes = public_routes.merge(collab::api::events::router());
+            }
+            if state.config.is_local_auth_enabled() {
+                public_routes = public_routes.merge(collab::api::local_auth::router());
+            }
             app = app
-                .merge(
-                    Router::new()
-                        .route("/", get(handle_root))
-                        .route("/healthz", get(handle_liveness_probe))
-                        .merge(collab::api::extensions::router())
-                        .merge(collab::api::events::router())
-                        .layer(Extension(state.clone())),
-                )
+                .merge(public_routes.layer(Extension(state.clone())))
                 .layer(
                     TraceLayer::new_for_http()
                         .make_span_with(|request: &Request<_>| {
@@ -181,7 +210,7 @@ async fn main() -> Result<()> {
         }
         _ => {
             Err(anyhow!(
-                "usage: collab <version | migrate | seed | serve [api|collab]>"
+                "usage: collab <version | migrate | seed | create-user | serve [api|collab]>"
             ))?;
         }
     }
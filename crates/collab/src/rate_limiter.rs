@@ -3,7 +3,7 @@ use anyhow::anyhow;
 use chrono::{DateTime, Duration, Utc};
 use dashmap::{DashMap, DashSet};
 use sea_orm::prelude::DateTimeUtc;
-use std::sync::Arc;
+use std::{net::IpAddr, sync::Arc};
 use util::ResultExt;
 
 pub trait RateLimit: 'static {
@@ -12,10 +12,11 @@ pub trait RateLimit: 'static {
     fn db_name() -> &'static str;
 }
 
-/// Used to enforce per-user rate limits
+/// Used to enforce per-user and per-address rate limits
 pub struct RateLimiter {
     buckets: DashMap<(UserId, String), RateBucket>,
     dirty_buckets: DashSet<(UserId, String)>,
+    ip_buckets: DashMap<(IpAddr, &'static str), RateBucket>,
     db: Arc<Database>,
 }
 
@@ -24,6 +25,7 @@ impl RateLimiter {
         RateLimiter {
             buckets: DashMap::new(),
             dirty_buckets: DashSet::new(),
+            ip_buckets: DashMap::new(),
             db,
         }
     }
@@ -72,6 +74,24 @@ impl RateLimiter {
         }
     }
 
+    /// Returns an error if requests from the given address have exceeded the specified
+    /// `RateLimit`. Unlike the limits of users, these are only kept in memory.
+    pub fn check_ip<T: RateLimit>(&self, ip: IpAddr) -> Result<()> {
+        self.check_ip_internal::<T>(ip, Utc::now())
+    }
+
+    fn check_ip_internal<T: RateLimit>(&self, ip: IpAddr, now: DateTimeUtc) -> Result<()> {
+        let mut bucket = self
+            .ip_buckets
+            .entry((ip, T::db_name()))
+            .or_insert_with(|| RateBucket::new::<T>(now));
+        if bucket.value_mut().allow(now) {
+            Ok(())
+        } else {
+            Err(anyhow!("rate limit exceeded"))?
+        }
+    }
+
     async fn load_bucket<T: RateLimit>(
         &self,
         user_id: UserId,
@@ -274,6 +294,40 @@ mod tests {
             .unwrap_err();
     }
 
+    #[gpui::test]
+    async fn test_ip_rate_limiter(cx: &mut TestAppContext) {
+        let test_db = TestDb::sqlite(cx.executor().clone());
+        let rate_limiter = RateLimiter::new(test_db.db().clone());
+        let ip_1 = IpAddr::from([10, 0, 0, 1]);
+        let ip_2 = IpAddr::from([10, 0, 0, 2]);
+        let mut now = Utc::now();
+
+        // Each address has its own bucket.
+        rate_limiter
+            .check_ip_internal::<RateLimitA>(ip_1, now)
+            .unwrap();
+        rate_limiter
+            .check_ip_internal::<RateLimitA>(ip_1, now)
+            .unwrap();
+        rate_limiter
+            .check_ip_internal::<RateLimitA>(ip_1, now)
+            .unwrap_err();
+        rate_limiter
+            .check_ip_internal::<RateLimitA>(ip_2, now)
+            .unwrap();
+        rate_limiter
+            .check_ip_internal::<RateLimitB>(ip_1, now)
+            .unwrap();
+
+        now += Duration::seconds(1);
+        rate_limiter
+            .check_ip_internal::<RateLimitA>(ip_1, now)
+            .unwrap();
+        rate_limiter
+            .check_ip_internal::<RateLimitA>(ip_1, now)
+            .unwrap_err();
+    }
+
     struct RateLimitA;
 
     impl RateLimit for RateLimitA {
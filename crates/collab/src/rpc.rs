@@ -3,11 +3,12 @@ mod connection_pool;
 use crate::{
     auth,
     db::{
-        self, dev_server, BufferId, Capability, Channel, ChannelId, ChannelRole, ChannelsForUser,
-        CreatedChannelMessage, Database, DevServerId, DevServerProjectId, InviteMemberResult,
-        MembershipUpdated, MessageId, NotificationId, PrincipalId, Project, ProjectId,
-        RejoinedProject, RemoveChannelMemberResult, ReplicaId, RespondToChannelInvite, RoomId,
-        ServerId, UpdatedChannelMessage, User, UserId,
+        self, dev_server, BufferCommentThreadId, BufferId, Capability, Channel, ChannelId,
+        ChannelRole, ChannelsForUser, CreatedChannelMessage, Database, DevServerId,
+        DevServerProjectId, InviteMemberResult, MembershipUpdated, MessageId, NotificationId,
+        PrincipalId, Project, ProjectId, RejoinedProject, RemoveChannelMemberResult, ReplicaId,
+        RespondToChannelInvite, RoomId, ServerId, UpdatedBufferCommentThread,
+        UpdatedChannelMessage, UpdatedChannelMessageReactions, User, UserId,
     },
     executor::Executor,
     AppState, Error, RateLimit, RateLimiter, Result,
@@ -84,6 +85,7 @@ pub const CLEANUP_TIMEOUT: Duration = Duration::from_secs(15);
 
 const MESSAGE_COUNT_PER_PAGE: usize = 100;
 const MAX_MESSAGE_LEN: usize = 1024;
+const MAX_REACTION_LEN: usize = 32;
 const NOTIFICATION_COUNT_PER_PAGE: usize = 50;
 
 type MessageHandler =
@@ -447,17 +449,21 @@ impl Server {
             .add_message_handler(update_diagnostic_summary)
             .add_message_handler(update_worktree_settings)
             .add_request_handler(user_handler(
-                forward_project_request_for_owner::<proto::TaskContextForLocation>,
+                forward_read_only_project_request::<proto::TaskContextForLocation>,
             ))
             .add_request_handler(user_handler(
-                forward_project_request_for_owner::<proto::TaskTemplates>,
+                forward_read_only_project_request::<proto::TaskTemplates>,
             ))
+            .add_request_handler(user_handler(spawn_host_task))
             .add_request_handler(user_handler(
                 forward_read_only_project_request::<proto::GetHover>,
             ))
             .add_request_handler(user_handler(
                 forward_read_only_project_request::<proto::GetDefinition>,
             ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetDeclaration>,
+            ))
             .add_request_handler(user_handler(
                 forward_read_only_project_request::<proto::GetTypeDefinition>,
             ))
@@ -551,6 +557,9 @@ impl Server {
             .add_request_handler(user_handler(
                 forward_mutating_project_request::<proto::LinkedEditingRange>,
             ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::TerminalInput>,
+            ))
             .add_message_handler(create_buffer_for_peer)
             .add_request_handler(update_buffer)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshInlayHints>)
@@ -558,6 +567,13 @@ impl Server {
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateDiffBase>)
+            .add_message_handler(share_terminal)
+            .add_message_handler(unshare_terminal)
+            .add_message_handler(update_terminal)
+            .add_request_handler(user_handler(get_buffer_comment_threads))
+            .add_request_handler(user_handler(create_buffer_comment_thread))
+            .add_request_handler(user_handler(create_buffer_comment))
+            .add_request_handler(user_handler(set_buffer_comment_thread_resolved))
             .add_request_handler(get_users)
             .add_request_handler(user_handler(fuzzy_search_users))
             .add_request_handler(user_handler(request_contact))
@@ -575,6 +591,8 @@ impl Server {
             .add_request_handler(user_handler(leave_channel_buffer))
             .add_message_handler(user_message_handler(update_channel_buffer))
             .add_request_handler(user_handler(rejoin_channel_buffers))
+            .add_request_handler(user_handler(get_channel_buffer_history))
+            .add_request_handler(user_handler(get_channel_buffer_revision))
             .add_request_handler(user_handler(get_channel_members))
             .add_request_handler(user_handler(respond_to_channel_invite))
             .add_request_handler(user_handler(join_channel))
@@ -585,6 +603,9 @@ impl Server {
             .add_request_handler(user_handler(update_channel_message))
             .add_request_handler(user_handler(get_channel_messages))
             .add_request_handler(user_handler(get_channel_messages_by_id))
+            .add_request_handler(user_handler(get_channel_message_thread))
+            .add_request_handler(user_handler(set_channel_message_reaction))
+            .add_request_handler(user_handler(search_channel_messages))
             .add_request_handler(user_handler(get_notifications))
             .add_request_handler(user_handler(mark_notification_as_read))
             .add_request_handler(user_handler(move_channel))
@@ -1230,6 +1251,57 @@ impl Server {
         Ok(())
     }
 
+    /// Closes all of the given user's connections, e.g. after they've been disabled.
+    pub fn disconnect_user(self: &Arc<Self>, user_id: UserId) {
+        let connection_ids = self
+            .connection_pool
+            .lock()
+            .user_connection_ids(user_id)
+            .collect::<Vec<_>>();
+        for connection_id in connection_ids {
+            self.peer.disconnect(connection_id);
+        }
+    }
+
+    /// Notifies the members of a channel that was deleted outside of an RPC request.
+    pub fn channels_deleted(
+        self: &Arc<Self>,
+        root_channel_id: ChannelId,
+        deleted_channel_ids: &[ChannelId],
+    ) {
+        let mut update = proto::UpdateChannels::default();
+        update
+            .delete_channels
+            .extend(deleted_channel_ids.iter().map(|id| id.to_proto()));
+
+        let pool = self.connection_pool.lock();
+        for (connection_id, _) in pool.channel_connection_ids(root_channel_id) {
+            self.peer.send(connection_id, update.clone()).trace_err();
+        }
+    }
+
+    /// Notifies a user who was removed from a channel outside of an RPC request.
+    pub fn channel_member_removed(
+        self: &Arc<Self>,
+        member_id: UserId,
+        result: RemoveChannelMemberResult,
+    ) {
+        let mut pool = self.connection_pool.lock();
+        notify_membership_updated(&mut pool, result.membership_update, member_id, &self.peer);
+        if let Some(notification_id) = result.notification_id {
+            for connection_id in pool.user_connection_ids(member_id) {
+                self.peer
+                    .send(
+                        connection_id,
+                        proto::DeleteNotification {
+                            notification_id: notification_id.to_proto(),
+                        },
+                    )
+                    .trace_err();
+            }
+        }
+    }
+
     pub async fn snapshot<'a>(self: &'a Arc<Self>) -> ServerSnapshot<'a> {
         ServerSnapshot {
             connection_pool: ConnectionPoolGuard {
@@ -3065,7 +3137,7 @@ async fn broadcast_project_message_from_host<T: EntityMessage<Entity = ShareProj
     let project_connection_ids = session
         .db()
         .await
-        .project_connection_ids(project_id, session.connection_id, false)
+        .project_connection_ids_for_host(project_id, session.connection_id)
         .await?;
 
     broadcast(
@@ -3080,6 +3152,242 @@ async fn broadcast_project_message_from_host<T: EntityMessage<Entity = ShareProj
     Ok(())
 }
 
+/// Ask the host of a project to run one of its tasks, telling it the role of the guest asking.
+async fn spawn_host_task(
+    mut request: proto::SpawnHostTask,
+    response: Response<proto::SpawnHostTask>,
+    session: UserSession,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let (host_connection_id, role) = session
+        .db()
+        .await
+        .host_for_task_request(project_id, session.connection_id, session.user_id())
+        .await?;
+    request.set_requester_role(role.into());
+    let payload = session
+        .peer
+        .forward_request(session.connection_id, host_connection_id, request)
+        .await?;
+    response.send(payload)?;
+    Ok(())
+}
+
+/// Notify guests that the host shared a terminal with them.
+async fn share_terminal(request: proto::ShareTerminal, session: Session) -> Result<()> {
+    forward_terminal_message_from_host(request.guest_id, request, session).await
+}
+
+/// Notify guests that the host stopped sharing a terminal with them.
+async fn unshare_terminal(request: proto::UnshareTerminal, session: Session) -> Result<()> {
+    forward_terminal_message_from_host(request.guest_id, request, session).await
+}
+
+/// Send the new contents of a shared terminal to the guests it's shared with.
+async fn update_terminal(request: proto::UpdateTerminal, session: Session) -> Result<()> {
+    forward_terminal_message_from_host(request.guest_id, request, session).await
+}
+
+/// Forward a message about a shared terminal to the only guest it's shared with, or to every
+/// guest of the project when it isn't shared with a single guest.
+async fn forward_terminal_message_from_host<T: EntityMessage<Entity = ShareProject>>(
+    guest_id: Option<proto::PeerId>,
+    request: T,
+    session: Session,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.remote_entity_id());
+    let project_connection_ids = session
+        .db()
+        .await
+        .project_connection_ids_for_host(project_id, session.connection_id)
+        .await?;
+
+    if let Some(guest_id) = guest_id {
+        let guest_connection_id = ConnectionId::from(guest_id);
+        if project_connection_ids.contains(&guest_connection_id) {
+            session
+                .peer
+                .forward_send(session.connection_id, guest_connection_id, request)?;
+        }
+        return Ok(());
+    }
+
+    broadcast(
+        Some(session.connection_id),
+        project_connection_ids.iter().copied(),
+        |connection_id| {
+            session
+                .peer
+                .forward_send(session.connection_id, connection_id, request.clone())
+        },
+    );
+    Ok(())
+}
+
+/// Get the comment threads left on the buffers of a project.
+async fn get_buffer_comment_threads(
+    request: proto::GetBufferCommentThreads,
+    response: Response<proto::GetBufferCommentThreads>,
+    session: UserSession,
+) -> Result<()> {
+    let threads = session
+        .db()
+        .await
+        .get_buffer_comment_threads(
+            ProjectId::from_proto(request.project_id),
+            session.connection_id,
+            session.user_id(),
+        )
+        .await?;
+    response.send(proto::GetBufferCommentThreadsResponse { threads })?;
+    Ok(())
+}
+
+/// Start a comment thread at a position in a project's buffer.
+async fn create_buffer_comment_thread(
+    request: proto::CreateBufferCommentThread,
+    response: Response<proto::CreateBufferCommentThread>,
+    session: UserSession,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let body = validate_buffer_comment_body(&request.body)?;
+    let anchor = request
+        .anchor
+        .ok_or_else(|| anyhow!("invalid comment thread anchor"))?;
+    let mentioned_user_ids = request
+        .mentioned_user_ids
+        .iter()
+        .copied()
+        .map(UserId::from_proto)
+        .collect::<Vec<_>>();
+
+    let UpdatedBufferCommentThread {
+        thread,
+        notifications,
+    } = session
+        .db()
+        .await
+        .create_buffer_comment_thread(
+            project_id,
+            session.connection_id,
+            session.user_id(),
+            request.worktree_id,
+            &request.path,
+            &anchor,
+            (request.row, request.column),
+            &body,
+            &mentioned_user_ids,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    broadcast_buffer_comment_thread(project_id, thread, notifications, response, &session).await
+}
+
+/// Reply to a comment thread.
+async fn create_buffer_comment(
+    request: proto::CreateBufferComment,
+    response: Response<proto::CreateBufferComment>,
+    session: UserSession,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let body = validate_buffer_comment_body(&request.body)?;
+    let mentioned_user_ids = request
+        .mentioned_user_ids
+        .iter()
+        .copied()
+        .map(UserId::from_proto)
+        .collect::<Vec<_>>();
+
+    let UpdatedBufferCommentThread {
+        thread,
+        notifications,
+    } = session
+        .db()
+        .await
+        .create_buffer_comment(
+            project_id,
+            session.connection_id,
+            session.user_id(),
+            BufferCommentThreadId::from_proto(request.thread_id),
+            &body,
+            &mentioned_user_ids,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    broadcast_buffer_comment_thread(project_id, thread, notifications, response, &session).await
+}
+
+/// Resolve or reopen a comment thread.
+async fn set_buffer_comment_thread_resolved(
+    request: proto::SetBufferCommentThreadResolved,
+    response: Response<proto::SetBufferCommentThreadResolved>,
+    session: UserSession,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let thread = session
+        .db()
+        .await
+        .set_buffer_comment_thread_resolved(
+            project_id,
+            session.connection_id,
+            session.user_id(),
+            BufferCommentThreadId::from_proto(request.thread_id),
+            request.resolved,
+        )
+        .await?;
+    broadcast_buffer_comment_thread(project_id, thread, Vec::new(), response, &session).await
+}
+
+fn validate_buffer_comment_body(body: &str) -> Result<String> {
+    let body = body.trim().to_string();
+    if body.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow!("comment is too long"))?;
+    }
+    if body.is_empty() {
+        return Err(anyhow!("comment can't be blank"))?;
+    }
+    Ok(body)
+}
+
+/// Send an updated comment thread to the requester and to the other collaborators in the
+/// project, and notify the users mentioned in it.
+async fn broadcast_buffer_comment_thread<T>(
+    project_id: ProjectId,
+    thread: proto::BufferCommentThread,
+    notifications: db::NotificationBatch,
+    response: Response<T>,
+    session: &UserSession,
+) -> Result<()>
+where
+    T: RequestMessage<Response = proto::BufferCommentThreadResponse>,
+{
+    let project_connection_ids = session
+        .db()
+        .await
+        .project_connection_ids(project_id, session.connection_id, false)
+        .await?;
+    broadcast(
+        Some(session.connection_id),
+        project_connection_ids.iter().copied(),
+        |connection_id| {
+            session.peer.send(
+                connection_id,
+                proto::UpdateBufferCommentThread {
+                    project_id: project_id.to_proto(),
+                    thread: Some(thread.clone()),
+                },
+            )
+        },
+    );
+    response.send(proto::BufferCommentThreadResponse {
+        thread: Some(thread),
+    })?;
+
+    let pool = &*session.connection_pool().await;
+    send_notifications(pool, &session.peer, notifications);
+    Ok(())
+}
+
 /// Start following another user in a call.
 async fn follow(
     request: proto::Follow,
@@ -4052,6 +4360,41 @@ async fn rejoin_channel_buffers(
     Ok(())
 }
 
+/// List the revisions of the channel notes
+async fn get_channel_buffer_history(
+    request: proto::GetChannelBufferHistory,
+    response: Response<proto::GetChannelBufferHistory>,
+    session: UserSession,
+) -> Result<()> {
+    let revisions = session
+        .db()
+        .await
+        .get_channel_buffer_history(ChannelId::from_proto(request.channel_id), session.user_id())
+        .await?;
+    response.send(proto::GetChannelBufferHistoryResponse { revisions })?;
+    Ok(())
+}
+
+/// Reconstruct the channel notes as they were at an earlier revision
+async fn get_channel_buffer_revision(
+    request: proto::GetChannelBufferRevision,
+    response: Response<proto::GetChannelBufferRevision>,
+    session: UserSession,
+) -> Result<()> {
+    let at = request.at.ok_or_else(|| anyhow!("missing revision"))?;
+    let revision = session
+        .db()
+        .await
+        .get_channel_buffer_revision(
+            ChannelId::from_proto(request.channel_id),
+            session.user_id(),
+            at,
+        )
+        .await?;
+    response.send(revision)?;
+    Ok(())
+}
+
 /// Stop editing the channel notes
 async fn leave_channel_buffer(
     request: proto::LeaveChannelBuffer,
@@ -4137,14 +4480,24 @@ async fn send_channel_message(
         .ok_or_else(|| anyhow!("nonce can't be blank"))?;
 
     let channel_id = ChannelId::from_proto(request.channel_id);
+    let db = session.db().await;
     let CreatedChannelMessage {
         message_id,
         participant_connection_ids,
         notifications,
-    } = session
-        .db()
-        .await
-        .create_channel_message(
+    } = if let Some(thread_parent_id) = request.thread_parent_id {
+        db.create_channel_thread_reply(
+            channel_id,
+            session.user_id(),
+            MessageId::from_proto(thread_parent_id),
+            &body,
+            &request.mentions,
+            timestamp,
+            nonce.clone().into(),
+        )
+        .await?
+    } else {
+        db.create_channel_message(
             channel_id,
             session.user_id(),
             &body,
@@ -4156,7 +4509,9 @@ async fn send_channel_message(
                 None => None,
             },
         )
-        .await?;
+        .await?
+    };
+    drop(db);
 
     let message = proto::ChannelMessage {
         sender_id: session.user_id().to_proto(),
@@ -4167,6 +4522,9 @@ async fn send_channel_message(
         nonce: Some(nonce),
         reply_to_message_id: request.reply_to_message_id,
         edited_at: None,
+        thread_parent_id: request.thread_parent_id,
+        thread_reply_count: 0,
+        reactions: Vec::new(),
     };
     broadcast(
         Some(session.connection_id),
@@ -4186,6 +4544,13 @@ async fn send_channel_message(
     })?;
 
     let pool = &*session.connection_pool().await;
+    send_notifications(pool, &session.peer, notifications);
+
+    // Replies in threads don't make the channel's chat unread.
+    if request.thread_parent_id.is_some() {
+        return Ok(());
+    }
+
     let non_participants =
         pool.channel_connection_ids(channel_id)
             .filter_map(|(connection_id, _)| {
@@ -4207,7 +4572,6 @@ async fn send_channel_message(
             },
         )
     });
-    send_notifications(pool, &session.peer, notifications);
 
     Ok(())
 }
@@ -4220,11 +4584,15 @@ async fn remove_channel_message(
 ) -> Result<()> {
     let channel_id = ChannelId::from_proto(request.channel_id);
     let message_id = MessageId::from_proto(request.message_id);
-    let (connection_ids, existing_notification_ids) = session
+    let (connection_ids, existing_notification_ids, thread_parent_id) = session
         .db()
         .await
         .remove_channel_message(channel_id, message_id, session.user_id())
         .await?;
+    let request = proto::RemoveChannelMessage {
+        thread_parent_id: thread_parent_id.map(|id| id.to_proto()),
+        ..request
+    };
 
     broadcast(
         Some(session.connection_id),
@@ -4261,6 +4629,7 @@ async fn update_channel_message(
         participant_connection_ids,
         notifications,
         reply_to_message_id,
+        thread_parent_id,
         timestamp,
         deleted_mention_notification_ids,
         updated_mention_notifications,
@@ -4291,6 +4660,9 @@ async fn update_channel_message(
         nonce: Some(nonce),
         reply_to_message_id: reply_to_message_id.map(|id| id.to_proto()),
         edited_at: Some(updated_at.unix_timestamp() as u64),
+        thread_parent_id: thread_parent_id.map(|id| id.to_proto()),
+        thread_reply_count: 0,
+        reactions: Vec::new(),
     };
 
     response.send(proto::Ack {})?;
@@ -4504,6 +4876,7 @@ async fn complete_with_google_ai(
         api_key.as_ref(),
         &request.model.clone(),
         crate::ai::language_model_request_to_google_ai(request)?,
+        None,
     )
     .await
     .context("google_ai::stream_generate_content request failed")?;
@@ -4561,11 +4934,15 @@ async fn complete_with_anthropic(
             match message.role() {
                 LanguageModelRole::LanguageModelUser => Some(anthropic::RequestMessage {
                     role: anthropic::Role::User,
-                    content: message.content,
+                    content: vec![anthropic::RequestContent::Text {
+                        text: message.content,
+                    }],
                 }),
                 LanguageModelRole::LanguageModelAssistant => Some(anthropic::RequestMessage {
                     role: anthropic::Role::Assistant,
-                    content: message.content,
+                    content: vec![anthropic::RequestContent::Text {
+                        text: message.content,
+                    }],
                 }),
                 // Anthropic's API breaks system instructions out as a separate field rather
                 // than having a system message role.
@@ -4593,6 +4970,7 @@ async fn complete_with_anthropic(
             stream: true,
             system: system_message,
             max_tokens: 4092,
+            tools: Vec::new(),
         },
         None,
     )
@@ -4630,6 +5008,8 @@ async fn complete_with_anthropic(
                             })?;
                         }
                     }
+                    // Tools are not forwarded to Anthropic, so it never asks to use them.
+                    anthropic::ContentBlock::ToolUse { .. } => {}
                 }
             }
             anthropic::ResponseEvent::ContentBlockDelta { delta, .. } => match delta {
@@ -4646,6 +5026,7 @@ async fn complete_with_anthropic(
                         }],
                     })?;
                 }
+                anthropic::TextDelta::InputJsonDelta { .. } => {}
             },
             anthropic::ResponseEvent::MessageDelta { delta, .. } => {
                 if let Some(stop_reason) = delta.stop_reason {
@@ -4712,6 +5093,7 @@ async fn count_tokens_with_language_model(
         session.http_client.as_ref(),
         google_ai::API_URL,
         &api_key,
+        &request.model.clone(),
         crate::ai::count_tokens_request_to_google_ai(request)?,
     )
     .await?;
@@ -4939,6 +5321,89 @@ async fn get_channel_messages_by_id(
     Ok(())
 }
 
+/// Retrieve the replies in a channel message's thread
+async fn get_channel_message_thread(
+    request: proto::GetChannelMessageThread,
+    response: Response<proto::GetChannelMessageThread>,
+    session: UserSession,
+) -> Result<()> {
+    let messages = session
+        .db()
+        .await
+        .get_channel_message_thread(
+            ChannelId::from_proto(request.channel_id),
+            session.user_id(),
+            MessageId::from_proto(request.message_id),
+        )
+        .await?;
+    response.send(proto::GetChannelMessagesResponse {
+        messages,
+        done: true,
+    })?;
+    Ok(())
+}
+
+/// Add or remove a reaction to a channel message
+async fn set_channel_message_reaction(
+    request: proto::SetChannelMessageReaction,
+    response: Response<proto::SetChannelMessageReaction>,
+    session: UserSession,
+) -> Result<()> {
+    let emoji = request.emoji.trim();
+    if emoji.is_empty() || emoji.len() > MAX_REACTION_LEN || emoji.contains(char::is_whitespace) {
+        return Err(anyhow!("invalid reaction"))?;
+    }
+
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let UpdatedChannelMessageReactions {
+        participant_connection_ids,
+        reactions,
+    } = session
+        .db()
+        .await
+        .set_channel_message_reaction(
+            channel_id,
+            MessageId::from_proto(request.message_id),
+            session.user_id(),
+            emoji,
+            request.reacted,
+        )
+        .await?;
+    response.send(proto::Ack {})?;
+
+    broadcast(None, participant_connection_ids, |connection| {
+        session.peer.send(
+            connection,
+            proto::ChannelMessageReactionsUpdated {
+                channel_id: request.channel_id,
+                message_id: request.message_id,
+                reactions: reactions.clone(),
+            },
+        )
+    });
+    Ok(())
+}
+
+/// Search the messages of the channels the user can see
+async fn search_channel_messages(
+    request: proto::SearchChannelMessages,
+    response: Response<proto::SearchChannelMessages>,
+    session: UserSession,
+) -> Result<()> {
+    let results = session
+        .db()
+        .await
+        .search_channel_messages(
+            session.user_id(),
+            &request.query,
+            request.channel_id.map(ChannelId::from_proto),
+            MESSAGE_COUNT_PER_PAGE,
+        )
+        .await?;
+    response.send(proto::SearchChannelMessagesResponse { results })?;
+    Ok(())
+}
+
 /// Retrieve the current users notifications
 async fn get_notifications(
     request: proto::GetNotifications,
@@ -153,6 +153,12 @@ impl ConnectionPool {
         self.connections.values()
     }
 
+    pub fn connections_by_id(&self) -> impl Iterator<Item = (ConnectionId, &Connection)> {
+        self.connections
+            .iter()
+            .map(|(connection_id, connection)| (*connection_id, connection))
+    }
+
     pub fn user_connections(&self, user_id: UserId) -> impl Iterator<Item = &Connection> + '_ {
         self.connected_users
             .get(&user_id)
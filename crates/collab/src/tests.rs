@@ -4,6 +4,8 @@ use call::Room;
 use client::ChannelId;
 use gpui::{Model, TestAppContext};
 
+mod admin_api_tests;
+mod buffer_comment_tests;
 mod channel_buffer_tests;
 mod channel_guest_tests;
 mod channel_message_tests;
@@ -16,6 +18,8 @@ mod notification_tests;
 mod random_channel_buffer_tests;
 mod random_project_collaboration_tests;
 mod randomized_test_helpers;
+mod self_hosted_tests;
+mod terminal_tests;
 mod test_server;
 
 use language::{tree_sitter_rust, Language, LanguageConfig, LanguageMatcher};
@@ -0,0 +1,437 @@
+use crate::{
+    api, auth,
+    db::{self, UserId},
+    tests::TestServer,
+    AppState,
+};
+use axum::{
+    body::{Body, HttpBody as _},
+    http::{header, Method, Request, StatusCode},
+    middleware,
+    routing::get,
+    Extension, Router,
+};
+use gpui::{BackgroundExecutor, TestAppContext};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::ServiceExt as _;
+
+#[gpui::test]
+async fn test_admin_api_users(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let router = api::routes(Some(server.rpc_server()), server.app_state.clone());
+    let user_a_id = client_a.current_user_id(cx_a);
+    let user_b_id = client_b.current_user_id(cx_b);
+
+    // Requests without the API token are rejected.
+    let response = router
+        .clone()
+        .oneshot(
+            Request::get("/admin/users")
+                .header(header::AUTHORIZATION, "token wrong-token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let (status, users) = send(&router, Method::GET, "/admin/users", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(logins(&users), ["user_a", "user_b"]);
+
+    let (status, user) = send(
+        &router,
+        Method::POST,
+        "/admin/users",
+        Some(json!({"login": "user_c", "password": "hunter2"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["github_login"], "user_c");
+    assert!(server
+        .app_state
+        .db
+        .get_user_and_password_hash_by_login("user_c")
+        .await
+        .unwrap()
+        .is_some());
+
+    let (status, _) = send(
+        &router,
+        Method::POST,
+        "/admin/users",
+        Some(json!({"login": "user_c"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, users) = send(&router, Method::GET, "/admin/users?limit=2", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(logins(&users), ["user_a", "user_b"]);
+
+    let (status, details) = send(
+        &router,
+        Method::GET,
+        &format!("/admin/users/{user_b_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(details["user"]["github_login"], "user_b");
+    assert_eq!(details["connection_count"], 1);
+
+    let (status, _) = send(&router, Method::GET, "/admin/users/12345", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let access_token = auth::create_access_token(server.app_state.db.as_ref(), user_b_id, None)
+        .await
+        .unwrap();
+    let authorization = format!("{user_b_id} {access_token}");
+    assert_eq!(
+        authenticate(&server.app_state, &authorization).await,
+        StatusCode::OK
+    );
+
+    // Disabling a user disconnects them and prevents them from reconnecting.
+    let (status, user) = send(
+        &router,
+        Method::POST,
+        &format!("/admin/users/{user_b_id}/disable"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["disabled"], true);
+    executor.run_until_parked();
+    assert_eq!(connection_count(&server, user_b_id), 0);
+    assert_eq!(connection_count(&server, user_a_id), 1);
+    assert!(!client_b.status().borrow().is_connected());
+    assert_eq!(
+        authenticate(&server.app_state, &authorization).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    let (status, _) = send(
+        &router,
+        Method::POST,
+        &format!("/users/{user_b_id}/access_tokens?public_key=the-public-key"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, user) = send(
+        &router,
+        Method::POST,
+        &format!("/admin/users/{user_b_id}/enable"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["disabled"], false);
+    assert_eq!(
+        authenticate(&server.app_state, &authorization).await,
+        StatusCode::OK
+    );
+    assert!(
+        !server
+            .app_state
+            .db
+            .get_user_by_id(user_b_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .disabled
+    );
+}
+
+#[gpui::test]
+async fn test_admin_api_feature_flags_and_access_tokens(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let router = api::routes(Some(server.rpc_server()), server.app_state.clone());
+    let user_a_id = client_a.current_user_id(cx_a);
+
+    let (status, flag) = send(
+        &router,
+        Method::POST,
+        "/admin/feature_flags",
+        Some(json!({"flag": "new-feature"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(flag["flag"], "new-feature");
+
+    let (status, _) = send(
+        &router,
+        Method::POST,
+        "/admin/feature_flags",
+        Some(json!({"flag": "new-feature"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, flags) = send(&router, Method::GET, "/admin/feature_flags", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(flags.as_array().unwrap().len(), 1);
+
+    let flag_path = format!("/admin/users/{user_a_id}/feature_flags/new-feature");
+    let (status, flags) = send(&router, Method::PUT, &flag_path, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(flags, json!(["new-feature"]));
+    let (status, flags) = send(&router, Method::PUT, &flag_path, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(flags, json!(["new-feature"]));
+    let (status, flags) = send(&router, Method::DELETE, &flag_path, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(flags, json!([]));
+
+    let (status, _) = send(
+        &router,
+        Method::PUT,
+        &format!("/admin/users/{user_a_id}/feature_flags/unknown-feature"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Access tokens are listed without their hashes and can be revoked.
+    let db = &server.app_state.db;
+    crate::auth::create_access_token(db, user_a_id, None)
+        .await
+        .unwrap();
+    crate::auth::create_access_token(db, user_a_id, None)
+        .await
+        .unwrap();
+
+    let tokens_path = format!("/admin/users/{user_a_id}/access_tokens");
+    let (status, tokens) = send(&router, Method::GET, &tokens_path, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let tokens = tokens.as_array().unwrap().clone();
+    assert_eq!(tokens.len(), 2);
+    assert!(tokens[0].get("hash").is_none());
+
+    let (status, _) = send(
+        &router,
+        Method::DELETE,
+        &format!("/admin/access_tokens/{}", tokens[0]["id"]),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &router,
+        Method::DELETE,
+        &format!("/admin/access_tokens/{}", tokens[0]["id"]),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, response) = send(&router, Method::DELETE, &tokens_path, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["revoked_count"], 1);
+    let (_, tokens) = send(&router, Method::GET, &tokens_path, None).await;
+    assert_eq!(tokens, json!([]));
+}
+
+#[gpui::test]
+async fn test_admin_api_channels_and_peers(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let router = api::routes(Some(server.rpc_server()), server.app_state.clone());
+    let user_a_id = client_a.current_user_id(cx_a);
+    let user_b_id = client_b.current_user_id(cx_b);
+
+    let zed_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+    let crdb_id = server
+        .make_channel("crdb", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+    executor.run_until_parked();
+
+    let (status, channels) = send(&router, Method::GET, "/admin/channels", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let names = channels
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|channel| channel["name"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["zed", "crdb"]);
+
+    let (status, channels) =
+        send(&router, Method::GET, "/admin/channels?page=1&limit=1", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(channels.as_array().unwrap().len(), 1);
+    assert_eq!(channels[0]["name"], "crdb");
+
+    let (status, _) = send(&router, Method::GET, "/admin/channels/999", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, details) = send(
+        &router,
+        Method::GET,
+        &format!("/admin/channels/{zed_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(details["channel"]["visibility"], "members");
+    let mut members = details["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|member| {
+            (
+                UserId(member["user_id"].as_i64().unwrap() as i32),
+                member["role"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    members.sort();
+    assert_eq!(
+        members,
+        [
+            (user_a_id, "Admin".to_string()),
+            (user_b_id, "Member".to_string())
+        ]
+    );
+
+    // Removing a member is reflected in their channel list.
+    let (status, _) = send(
+        &router,
+        Method::DELETE,
+        &format!("/admin/channels/{crdb_id}/members/{user_b_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &router,
+        Method::DELETE,
+        &format!("/admin/channels/{crdb_id}/members/{user_b_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    executor.run_until_parked();
+    client_b.channel_store().read_with(cx_b, |channels, _| {
+        assert!(channels.channel_for_id(zed_id).is_some());
+        assert!(channels.channel_for_id(crdb_id).is_none());
+    });
+
+    // Deleting a channel removes it for everyone.
+    let (status, response) = send(
+        &router,
+        Method::DELETE,
+        &format!("/admin/channels/{zed_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["deleted_channel_ids"], json!([zed_id.0]));
+    executor.run_until_parked();
+    client_a.channel_store().read_with(cx_a, |channels, _| {
+        assert!(channels.channel_for_id(zed_id).is_none());
+        assert!(channels.channel_for_id(crdb_id).is_some());
+    });
+    client_b.channel_store().read_with(cx_b, |channels, _| {
+        assert!(channels.channel_for_id(zed_id).is_none());
+    });
+    assert!(server
+        .app_state
+        .db
+        .get_channel_memberships(db::ChannelId::from_proto(zed_id.0))
+        .await
+        .unwrap()
+        .is_empty());
+
+    let (status, peers) = send(&router, Method::GET, "/admin/peers", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let mut peer_user_ids = peers
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|peer| peer["principal_id"]["UserId"].as_i64().unwrap() as i32)
+        .collect::<Vec<_>>();
+    peer_user_ids.sort();
+    assert_eq!(peer_user_ids, [user_a_id.0, user_b_id.0]);
+}
+
+async fn send(
+    router: &Router,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, "token test-api-token");
+    let request = if let Some(body) = body {
+        request
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+    } else {
+        request.body(Body::empty())
+    };
+    let response = router.clone().oneshot(request.unwrap()).await.unwrap();
+
+    let status = response.status();
+    let mut body = response.into_body();
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        bytes.extend_from_slice(&chunk.unwrap());
+    }
+    let value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, value)
+}
+
+/// Sends a request with the given authorization header through the middleware that
+/// authenticates the clients connecting to the server.
+async fn authenticate(app_state: &Arc<AppState>, authorization: &str) -> StatusCode {
+    let router = Router::new()
+        .route("/rpc", get(|| async {}))
+        .layer(middleware::from_fn(auth::validate_header))
+        .layer(Extension(app_state.clone()));
+    let request = Request::get("/rpc")
+        .header(header::AUTHORIZATION, authorization)
+        .body(Body::empty())
+        .unwrap();
+    router.oneshot(request).await.unwrap().status()
+}
+
+fn logins(users: &Value) -> Vec<&str> {
+    users
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|user| user["github_login"].as_str().unwrap())
+        .collect()
+}
+
+fn connection_count(server: &TestServer, user_id: UserId) -> usize {
+    server
+        .rpc_server()
+        .connection_pool
+        .lock()
+        .user_connection_ids(user_id)
+        .count()
+}
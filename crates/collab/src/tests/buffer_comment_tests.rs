@@ -0,0 +1,193 @@
+use crate::tests::TestServer;
+use call::ActiveCall;
+use gpui::{BackgroundExecutor, Model, TestAppContext};
+use language::Point;
+use project::buffer_comment_store::{BufferCommentStore, BufferCommentThread};
+use rpc::Notification;
+
+#[gpui::test]
+async fn test_buffer_comment_threads(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    let project_a = client_a.build_test_project(cx_a).await;
+    let worktree_id = project_a.read_with(cx_a, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    let buffer_a = project_a
+        .update(cx_a, |project, cx| {
+            project.open_buffer((worktree_id, "1.txt"), cx)
+        })
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |project, cx| {
+            project.open_buffer((worktree_id, "1.txt"), cx)
+        })
+        .await
+        .unwrap();
+
+    // Client A starts a thread on the second line, mentioning client B.
+    let store_a = project_a.read_with(cx_a, |project, _| project.buffer_comment_store().clone());
+    let store_b = project_b.read_with(cx_b, |project, _| project.buffer_comment_store().clone());
+    let body = "@user_b can this be removed?".to_string();
+    let mentioned_user_ids = store_a.read_with(cx_a, |store, cx| {
+        let collaborators = project_a.read(cx).collaborators();
+        store.mentioned_user_ids(
+            &body,
+            collaborators
+                .values()
+                .map(|collaborator| collaborator.user_id),
+            cx,
+        )
+    });
+    assert_eq!(mentioned_user_ids, [client_b.id()]);
+    let position = buffer_a.read_with(cx_a, |buffer, _| buffer.anchor_before(Point::new(1, 0)));
+    let thread_id = store_a
+        .update(cx_a, |store, cx| {
+            store.create_thread(&buffer_a, position, body, mentioned_user_ids, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let thread = only_thread(&store_b, cx_b);
+    assert_eq!(thread.id, thread_id);
+    assert!(!thread.resolved);
+    assert_eq!(comment_bodies(&thread), ["@user_b can this be removed?"]);
+    assert_eq!(thread.comments[0].sender_id, client_a.id());
+    assert_eq!(thread_row(&store_b, &buffer_b, cx_b), 1);
+
+    // Client B is notified that they were mentioned.
+    client_b.notification_store().read_with(cx_b, |store, _| {
+        assert!(matches!(
+            &store.notification_at(0).unwrap().notification,
+            Notification::BufferCommentMention { sender_id, path, .. }
+                if *sender_id == client_a.id() && path == "1.txt"
+        ));
+    });
+
+    // The thread follows the edits made to the buffer.
+    buffer_a.update(cx_a, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+    executor.run_until_parked();
+    assert_eq!(thread_row(&store_a, &buffer_a, cx_a), 2);
+    assert_eq!(thread_row(&store_b, &buffer_b, cx_b), 2);
+
+    // Client B replies, and client A resolves the thread.
+    store_b
+        .update(cx_b, |store, cx| {
+            store.reply(thread_id, "Yes, it's unused.".into(), Vec::new(), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    let thread = only_thread(&store_a, cx_a);
+    assert_eq!(
+        comment_bodies(&thread),
+        ["@user_b can this be removed?", "Yes, it's unused."]
+    );
+    assert_eq!(thread.comments[1].sender_id, client_b.id());
+
+    store_a
+        .update(cx_a, |store, cx| store.set_resolved(thread_id, true, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(only_thread(&store_b, cx_b).resolved);
+
+    // Client B reopens it.
+    store_b
+        .update(cx_b, |store, cx| store.set_resolved(thread_id, false, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(!only_thread(&store_a, cx_a).resolved);
+
+    // Blank comments are rejected.
+    let reply = store_b.update(cx_b, |store, cx| {
+        store.reply(thread_id, "  ".into(), Vec::new(), cx)
+    });
+    assert!(reply.await.is_err());
+
+    // Threads are loaded when joining the project, and cleared when it's no longer shared.
+    let project_c = client_c.build_dev_server_project(project_id, cx_c).await;
+    executor.run_until_parked();
+    let store_c = project_c.read_with(cx_c, |project, _| project.buffer_comment_store().clone());
+    assert_eq!(comment_bodies(&only_thread(&store_c, cx_c)).len(), 2);
+
+    project_a
+        .update(cx_a, |project, cx| project.unshare(cx))
+        .unwrap();
+    executor.run_until_parked();
+    store_a.read_with(cx_a, |store, _| {
+        assert!(!store.is_available());
+        assert_eq!(store.threads().count(), 0);
+    });
+    store_b.read_with(cx_b, |store, _| {
+        assert_eq!(store.threads().count(), 0);
+    });
+    store_c.read_with(cx_c, |store, _| {
+        assert_eq!(store.threads().count(), 0);
+    });
+
+    // Threads belong to the file rather than to the project, so they're back once it's
+    // shared again, at the position they were left at.
+    active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    let thread = only_thread(&store_a, cx_a);
+    assert_eq!(thread.id, thread_id);
+    assert_eq!(comment_bodies(&thread).len(), 2);
+    assert!(thread.anchor.is_none());
+    assert_eq!(thread.position, Point::new(1, 0));
+    assert_eq!(thread_row(&store_a, &buffer_a, cx_a), 1);
+}
+
+fn only_thread(store: &Model<BufferCommentStore>, cx: &mut TestAppContext) -> BufferCommentThread {
+    store.read_with(cx, |store, _| {
+        let threads = store.threads().cloned().collect::<Vec<_>>();
+        assert_eq!(threads.len(), 1);
+        threads[0].clone()
+    })
+}
+
+fn thread_row(
+    store: &Model<BufferCommentStore>,
+    buffer: &Model<language::Buffer>,
+    cx: &mut TestAppContext,
+) -> u32 {
+    let thread = only_thread(store, cx);
+    store.read_with(cx, |store, cx| {
+        let buffer = buffer.read(cx);
+        let anchor = store.anchor_in_buffer(&thread, buffer);
+        buffer.summary_for_anchor::<Point>(&anchor).row
+    })
+}
+
+fn comment_bodies(thread: &BufferCommentThread) -> Vec<&str> {
+    thread
+        .comments
+        .iter()
+        .map(|comment| comment.body.as_str())
+        .collect()
+}
@@ -676,6 +676,97 @@ async fn test_channel_buffer_changes_persist(
     });
 }
 
+#[gpui::test]
+async fn test_channel_notes_history(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let buffer_a = channel_buffer_a.read_with(cx_a, |buffer, _| buffer.buffer());
+    let buffer_b = channel_buffer_b.read_with(cx_b, |buffer, _| buffer.buffer());
+
+    buffer_a.update(cx_a, |buffer, cx| {
+        buffer.edit([(0..0, "hello world")], None, cx)
+    });
+    executor.run_until_parked();
+    buffer_b.update(cx_b, |buffer, cx| {
+        buffer.edit([(5..5, ", cruel")], None, cx)
+    });
+    executor.run_until_parked();
+
+    // Each collaborator's edits show up as a separate revision.
+    let history = channel_buffer_b
+        .update(cx_b, |buffer, cx| buffer.load_history(cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|revision| revision.author.as_ref().unwrap().github_login.as_str())
+            .collect::<Vec<_>>(),
+        &["user_a", "user_b"]
+    );
+
+    let content = channel_buffer_b
+        .update(cx_b, |buffer, cx| buffer.load_revision(&history[1], cx))
+        .await
+        .unwrap();
+    assert_eq!(content.text, "hello, cruel world");
+    assert_eq!(
+        content
+            .authorship
+            .iter()
+            .map(|(range, author)| (
+                range.clone(),
+                author.as_ref().unwrap().github_login.as_str()
+            ))
+            .collect::<Vec<_>>(),
+        &[(0..5, "user_a"), (5..12, "user_b"), (12..18, "user_a")]
+    );
+
+    // Restoring an older revision is an ordinary edit, seen by everyone.
+    let content = channel_buffer_b
+        .update(cx_b, |buffer, cx| buffer.load_revision(&history[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(content.text, "hello world");
+    channel_buffer_b
+        .update(cx_b, |buffer, cx| buffer.restore_revision(&content, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(buffer_text(&buffer_a, cx_a), "hello world");
+    assert_eq!(buffer_text(&buffer_b, cx_b), "hello world");
+
+    let history = channel_buffer_a
+        .update(cx_a, |buffer, cx| buffer.load_history(cx))
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 3);
+    assert_eq!(
+        history[2].author.as_ref().unwrap().github_login,
+        "user_b".to_string()
+    );
+}
+
 #[track_caller]
 fn assert_collaborators(collaborators: &HashMap<PeerId, Collaborator>, ids: &[Option<UserId>]) {
     let mut user_ids = collaborators
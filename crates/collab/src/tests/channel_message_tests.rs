@@ -498,6 +498,140 @@ async fn test_chat_replies(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
     });
 }
 
+#[gpui::test]
+async fn test_chat_threads_reactions_and_search(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_chat_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+
+    let parent_id = channel_chat_a
+        .update(cx_a, |c, cx| {
+            c.send_message("which database for search?".into(), cx)
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    // Client A follows the thread while client B replies in it.
+    channel_chat_a
+        .update(cx_a, |c, cx| c.load_thread(parent_id, cx))
+        .await
+        .unwrap();
+    channel_chat_b
+        .update(cx_b, |c, cx| {
+            c.send_thread_reply(parent_id, "both of them".into(), cx)
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    channel_chat_a.update(cx_a, |c, _| {
+        assert_eq!(c.message_count(), 1);
+        assert_eq!(
+            c.find_loaded_message(parent_id).unwrap().thread_reply_count,
+            1
+        );
+        assert_eq!(
+            c.thread(parent_id)
+                .unwrap()
+                .iter()
+                .map(|reply| reply.body.as_str())
+                .collect::<Vec<_>>(),
+            ["both of them"]
+        );
+    });
+    channel_chat_b.update(cx_b, |c, _| {
+        assert_eq!(c.message_count(), 1);
+        assert_eq!(
+            c.find_loaded_message(parent_id).unwrap().thread_reply_count,
+            1
+        );
+    });
+
+    // Both clients react, and client A takes their reaction back.
+    for (chat, cx) in [(&channel_chat_a, &mut *cx_a), (&channel_chat_b, &mut *cx_b)] {
+        chat.update(cx, |c, cx| c.toggle_reaction(parent_id, "👍".into(), cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+    }
+    channel_chat_b.update(cx_b, |c, _| {
+        let reactions = &c.find_loaded_message(parent_id).unwrap().reactions;
+        assert_eq!(reactions.len(), 1);
+        assert_eq!(reactions[0].user_ids, [client_a.id(), client_b.id()]);
+    });
+    channel_chat_a
+        .update(cx_a, |c, cx| c.toggle_reaction(parent_id, "👍".into(), cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    channel_chat_b.update(cx_b, |c, _| {
+        let reactions = &c.find_loaded_message(parent_id).unwrap().reactions;
+        assert_eq!(reactions[0].user_ids, [client_b.id()]);
+    });
+
+    // Both the message and the reply in its thread can be found.
+    let results = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.search_messages("BOTH".into(), None, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].channel_id, channel_id);
+    assert_eq!(results[0].message.thread_parent_id, Some(parent_id));
+    assert_eq!(results[0].message.sender.github_login, "user_b");
+
+    // Removing the reply updates the thread.
+    let ChannelMessageId::Saved(reply_id) = results[0].message.id else {
+        panic!("search results must be saved messages");
+    };
+    channel_chat_b
+        .update(cx_b, |c, cx| c.load_thread(parent_id, cx))
+        .await
+        .unwrap();
+    channel_chat_b
+        .update(cx_b, |c, cx| c.remove_message(reply_id, cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    for (chat, cx) in [(&channel_chat_a, &mut *cx_a), (&channel_chat_b, &mut *cx_b)] {
+        chat.update(cx, |c, _| {
+            assert_eq!(
+                c.find_loaded_message(parent_id).unwrap().thread_reply_count,
+                0
+            );
+            assert!(c.thread(parent_id).unwrap().is_empty());
+        });
+    }
+}
+
 #[gpui::test]
 async fn test_chat_editing(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
     let mut server = TestServer::start(cx_a.executor()).await;
@@ -3,12 +3,18 @@ use std::{path::Path, sync::Arc};
 use call::ActiveCall;
 use editor::Editor;
 use fs::Fs;
-use gpui::{TestAppContext, VisualTestContext, WindowHandle};
-use rpc::{proto::DevServerStatus, ErrorCode, ErrorExt};
+use gpui::{TestAppContext, VisualTestContext, WindowContext, WindowHandle};
+use recent_projects::remote_connection_indicator::{
+    RemoteConnectionIndicator, RemoteConnectionStatus,
+};
+use rpc::{proto::DevServerStatus, ErrorCode, ErrorExt, RECEIVE_TIMEOUT};
 use serde_json::json;
 use workspace::{AppState, Workspace};
 
-use crate::tests::{following_tests::join_channel, TestServer};
+use crate::{
+    rpc::RECONNECT_TIMEOUT,
+    tests::{following_tests::join_channel, TestServer},
+};
 
 use super::TestClient;
 
@@ -527,6 +533,137 @@ async fn test_dev_server_restart(cx1: &mut gpui::TestAppContext, cx2: &mut gpui:
         .unwrap();
 }
 
+#[gpui::test]
+async fn test_dev_server_network_partition(
+    cx1: &mut gpui::TestAppContext,
+    cx2: &mut gpui::TestAppContext,
+) {
+    let (server, client1) = TestServer::start1(cx1).await;
+    cx1.update(recent_projects::init);
+
+    let (dev_server, remote_workspace) =
+        create_dev_server_project(&server, client1.app_state.clone(), cx1, cx2).await;
+    let executor = cx1.executor();
+    let cx = VisualTestContext::from_window(remote_workspace.into(), cx1).as_mut();
+
+    cx.simulate_keystrokes("cmd-p 1 enter");
+
+    // Cut the client off for longer than the server waits for it to come back.
+    server.forbid_connections();
+    server.disconnect_client(client1.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    cx.run_until_parked();
+
+    // The project keeps trying to reconnect, without blocking the workspace.
+    remote_workspace
+        .update(cx, |ws, cx| {
+            let project = ws.project().read(cx);
+            assert!(project.is_reconnecting());
+            assert!(!project.is_disconnected());
+            assert!(!project.is_read_only());
+            assert!(!ws.has_active_modal(cx));
+            assert_eq!(
+                remote_connection_status(ws, cx),
+                RemoteConnectionStatus::Reconnecting
+            );
+        })
+        .unwrap();
+
+    // Edits made while offline are kept until the project is rejoined.
+    cx.simulate_input("offline ");
+    executor.advance_clock(RECONNECT_TIMEOUT);
+    cx.simulate_input("edits ");
+
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    cx.run_until_parked();
+
+    remote_workspace
+        .update(cx, |ws, cx| {
+            assert!(!ws.project().read(cx).is_reconnecting());
+            assert_eq!(
+                remote_connection_status(ws, cx),
+                RemoteConnectionStatus::Connected
+            );
+            ws.active_item_as::<Editor>(cx)
+                .unwrap()
+                .update(cx, |ed, cx| {
+                    assert_eq!(
+                        ed.text(cx).to_string(),
+                        "offline edits remote\nremote\nremote"
+                    );
+                })
+        })
+        .unwrap();
+
+    // The dev server has received the offline edits, so saving writes them to disk.
+    cx.simulate_keystrokes("cmd-s");
+    cx.run_until_parked();
+    let content = dev_server
+        .fs()
+        .load(&Path::new("/remote/1.txt"))
+        .await
+        .unwrap();
+    assert_eq!(content, "offline edits remote\nremote\nremote\n");
+}
+
+#[gpui::test]
+async fn test_dev_server_network_partition_outlasting_dev_server(
+    cx1: &mut gpui::TestAppContext,
+    cx2: &mut gpui::TestAppContext,
+) {
+    let (server, client1) = TestServer::start1(cx1).await;
+    cx1.update(recent_projects::init);
+
+    let (dev_server, remote_workspace) =
+        create_dev_server_project(&server, client1.app_state.clone(), cx1, cx2).await;
+    let executor = cx1.executor();
+    let cx = VisualTestContext::from_window(remote_workspace.into(), cx1).as_mut();
+
+    // Cut off both the client and the dev server, for long enough that the server stops
+    // sharing the dev server's project.
+    server.forbid_connections();
+    server.disconnect_client(client1.peer_id().unwrap());
+    server.disconnect_client(dev_server.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    cx.run_until_parked();
+
+    remote_workspace
+        .update(cx, |ws, cx| {
+            assert!(ws.project().read(cx).is_reconnecting());
+        })
+        .unwrap();
+
+    // Once the client is back, the project can't be rejoined, so it's shown as disconnected
+    // without closing the window.
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    cx.run_until_parked();
+
+    remote_workspace
+        .update(cx, |ws, cx| {
+            let project = ws.project().read(cx);
+            assert!(project.is_disconnected());
+            assert!(!project.is_reconnecting());
+            assert!(!ws.has_active_modal(cx));
+            assert_eq!(
+                remote_connection_status(ws, cx),
+                RemoteConnectionStatus::Disconnected
+            );
+        })
+        .unwrap();
+}
+
+fn remote_connection_status(workspace: &Workspace, cx: &WindowContext) -> RemoteConnectionStatus {
+    workspace
+        .status_bar()
+        .read(cx)
+        .item_of_type::<RemoteConnectionIndicator>()
+        .unwrap()
+        .read(cx)
+        .status(cx)
+}
+
 #[gpui::test]
 async fn test_create_dev_server_project_path_validation(
     cx1: &mut gpui::TestAppContext,
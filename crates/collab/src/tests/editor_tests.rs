@@ -30,7 +30,9 @@ use project::{
     project_settings::{InlineBlameSettings, ProjectSettings},
     SERVER_PROGRESS_THROTTLE_TIMEOUT,
 };
-use recent_projects::disconnected_overlay::DisconnectedOverlay;
+use recent_projects::remote_connection_indicator::{
+    RemoteConnectionIndicator, RemoteConnectionStatus,
+};
 use rpc::RECEIVE_TIMEOUT;
 use serde_json::json;
 use settings::SettingsStore;
@@ -124,10 +126,18 @@ async fn test_host_disconnect(
 
     assert!(worktree_a.read_with(cx_a, |tree, _| !tree.has_update_observer()));
 
-    // Ensure client B's edited state is reset and that the whole window is blurred.
+    // Ensure client B's edited state is reset and that the disconnection is shown in the status bar.
     workspace_b
         .update(cx_b, |workspace, cx| {
-            assert!(workspace.active_modal::<DisconnectedOverlay>(cx).is_some());
+            let indicator = workspace
+                .status_bar()
+                .read(cx)
+                .item_of_type::<RemoteConnectionIndicator>()
+                .unwrap();
+            assert_eq!(
+                indicator.read(cx).status(cx),
+                RemoteConnectionStatus::Disconnected
+            );
             assert!(!workspace.is_edited());
         })
         .unwrap();
@@ -12,8 +12,10 @@ use gpui::{
 };
 use language::Capability;
 use live_kit_client::MacOSDisplay;
+use markdown_preview::markdown_preview_view::{MarkdownPreviewMode, MarkdownPreviewView};
 use project::WorktreeSettings;
 use rpc::proto::PeerId;
+use search::{project_search::ProjectSearchBar, ProjectSearchView};
 use serde_json::json;
 use settings::SettingsStore;
 use workspace::{
@@ -2149,3 +2151,188 @@ async fn test_following_while_deactivated(cx_a: &mut TestAppContext, cx_b: &mut
         assert_eq!(editor.tab_description(0, cx).unwrap(), "2.js");
     });
 }
+
+#[gpui::test]
+async fn test_following_project_search(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_server, client_a, client_b, channel) = TestServer::start2(cx_a, cx_b).await;
+    cx_a.update(search::init);
+    cx_b.update(search::init);
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    join_channel(channel, &client_a, cx_a).await.unwrap();
+    share_workspace(&workspace_a, cx_a).await.unwrap();
+    workspace_a.update(cx_a, |workspace, cx| {
+        workspace.active_pane().update(cx, |pane, cx| {
+            pane.toolbar().update(cx, |toolbar, cx| {
+                toolbar.add_item(cx.new_view(|_| ProjectSearchBar::new()), cx)
+            })
+        })
+    });
+
+    // b joins channel and is following a
+    join_channel(channel, &client_b, cx_b).await.unwrap();
+    cx_b.run_until_parked();
+    let (workspace_b, cx_b) = client_b.active_workspace(cx_b);
+
+    // a searches the project
+    cx_a.dispatch_action(workspace::DeploySearch::find());
+    cx_a.simulate_input("one");
+    cx_a.simulate_keystrokes("enter");
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+
+    // b is taken to a search view that ran the same query
+    let search_b = workspace_b.update(cx_b, |workspace, cx| {
+        assert_eq!(
+            workspace.leader_for_pane(workspace.active_pane()),
+            Some(client_a.peer_id().unwrap())
+        );
+        workspace.active_item_as::<ProjectSearchView>(cx).unwrap()
+    });
+    search_b.update(cx_b, |search, _| assert!(search.has_matches()));
+
+    // a searches for something else, and b's search view follows along
+    cx_a.dispatch_action(workspace::DeploySearch::find());
+    cx_a.simulate_input("two");
+    cx_a.simulate_keystrokes("enter");
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        let search = workspace.active_item_as::<ProjectSearchView>(cx).unwrap();
+        assert_eq!(search.entity_id(), search_b.entity_id());
+        assert!(search.read(cx).has_matches());
+    });
+}
+
+#[gpui::test]
+async fn test_following_project_diagnostics(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_server, client_a, client_b, channel) = TestServer::start2(cx_a, cx_b).await;
+    cx_a.update(diagnostics::init);
+    cx_b.update(diagnostics::init);
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    join_channel(channel, &client_a, cx_a).await.unwrap();
+    share_workspace(&workspace_a, cx_a).await.unwrap();
+
+    join_channel(channel, &client_b, cx_b).await.unwrap();
+    cx_b.run_until_parked();
+    let (workspace_b, cx_b) = client_b.active_workspace(cx_b);
+
+    // a opens the project diagnostics, and b follows them there
+    cx_a.dispatch_action(diagnostics::Deploy);
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        assert_eq!(
+            workspace.leader_for_pane(workspace.active_pane()),
+            Some(client_a.peer_id().unwrap())
+        );
+        assert_eq!(
+            workspace.unfollowable_view_for_pane(workspace.active_pane()),
+            None
+        );
+        let item = workspace.active_item(cx).unwrap();
+        assert_eq!(item.display_name(cx), Some("project diagnostics"));
+    });
+}
+
+#[gpui::test]
+async fn test_following_markdown_preview(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_server, client_a, client_b, channel) = TestServer::start2(cx_a, cx_b).await;
+    cx_a.update(markdown_preview::init);
+    cx_b.update(markdown_preview::init);
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    join_channel(channel, &client_a, cx_a).await.unwrap();
+    share_workspace(&workspace_a, cx_a).await.unwrap();
+
+    // a opens 1.txt
+    cx_a.simulate_keystrokes("cmd-p 1 enter");
+    cx_a.run_until_parked();
+
+    join_channel(channel, &client_b, cx_b).await.unwrap();
+    cx_b.run_until_parked();
+    let (workspace_b, cx_b) = client_b.active_workspace(cx_b);
+
+    // a previews the file, and b is shown a preview of the same buffer
+    workspace_a.update(cx_a, |workspace, cx| {
+        let editor = workspace.active_item_as::<Editor>(cx).unwrap();
+        let language_registry = workspace.project().read(cx).languages().clone();
+        let preview = MarkdownPreviewView::new(
+            MarkdownPreviewMode::Default,
+            editor,
+            workspace.weak_handle(),
+            language_registry,
+            None,
+            cx,
+        );
+        workspace.add_item_to_active_pane(Box::new(preview), None, cx);
+    });
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+
+    workspace_b.update(cx_b, |workspace, cx| {
+        assert_eq!(
+            workspace.leader_for_pane(workspace.active_pane()),
+            Some(client_a.peer_id().unwrap())
+        );
+        let preview = workspace.active_item_as::<MarkdownPreviewView>(cx).unwrap();
+        assert_eq!(preview.tab_description(0, cx).unwrap(), "Preview 1.txt");
+    });
+}
+
+#[gpui::test]
+async fn test_following_into_unfollowable_view(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let (_server, client_a, client_b, channel) = TestServer::start2(cx_a, cx_b).await;
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    join_channel(channel, &client_a, cx_a).await.unwrap();
+    share_workspace(&workspace_a, cx_a).await.unwrap();
+
+    // a opens 1.txt
+    cx_a.simulate_keystrokes("cmd-p 1 enter");
+    cx_a.run_until_parked();
+
+    join_channel(channel, &client_b, cx_b).await.unwrap();
+    cx_b.run_until_parked();
+    let (workspace_b, cx_b) = client_b.active_workspace(cx_b);
+
+    // a focuses a panel that has nothing to follow
+    let panel = cx_a.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+    workspace_a.update(cx_a, |workspace, cx| {
+        workspace.add_panel(panel, cx);
+        workspace.toggle_panel_focus::<TestPanel>(cx);
+    });
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+
+    // b keeps following, and is told why a can't be followed
+    workspace_b.update(cx_b, |workspace, _| {
+        assert_eq!(
+            workspace.leader_for_pane(workspace.active_pane()),
+            Some(client_a.peer_id().unwrap())
+        );
+        assert_eq!(
+            workspace.unfollowable_view_for_pane(workspace.active_pane()),
+            Some("current view".into())
+        );
+    });
+
+    // a returns to the editor, and b is taken back to it
+    workspace_a.update(cx_a, |workspace, cx| {
+        workspace.toggle_panel_focus::<TestPanel>(cx);
+    });
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        assert_eq!(
+            workspace.unfollowable_view_for_pane(workspace.active_pane()),
+            None
+        );
+        let editor = workspace.active_item_as::<Editor>(cx).unwrap();
+        assert_eq!(editor.tab_description(0, cx).unwrap(), "1.txt");
+    });
+}
@@ -0,0 +1,332 @@
+use crate::{
+    api::{self, extensions::fetch_extensions_from_blob_store},
+    auth,
+    blob_store::BlobStore,
+    tests::TestServer,
+    AppState,
+};
+use axum::{
+    body::{Body, HttpBody as _},
+    extract::connect_info::MockConnectInfo,
+    http::{header, Request, StatusCode},
+    response::Response,
+    Extension, Router,
+};
+use gpui::BackgroundExecutor;
+use rpc::ExtensionApiManifest;
+use std::{net::SocketAddr, sync::Arc};
+use tower::ServiceExt as _;
+
+#[gpui::test]
+async fn test_sign_in_with_local_password(executor: BackgroundExecutor) {
+    // Passwords are verified on tokio's blocking thread pool.
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let _runtime_guard = runtime.enter();
+    executor.allow_parking();
+
+    let server = TestServer::start(executor.clone()).await;
+    let app_state = server.app_state.clone();
+    let password_hash = auth::hash_password("correct-horse").unwrap();
+    let user = app_state
+        .db
+        .create_local_user(
+            "alice",
+            Some("alice@example.com"),
+            false,
+            Some(&password_hash),
+        )
+        .await
+        .unwrap();
+    assert!(app_state
+        .db
+        .create_local_user("alice", None, false, Some(&password_hash))
+        .await
+        .is_err());
+
+    let router = api::local_auth::router()
+        .layer(Extension(app_state.clone()))
+        .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 1234))));
+    let (public_key, private_key) = rpc::auth::keypair().unwrap();
+    let public_key = String::try_from(public_key).unwrap();
+
+    // The editor opens the sign-in page in the browser.
+    let response = send(
+        &router,
+        Request::get(format!(
+            "/native_app_signin?native_app_port=4321&native_app_public_key={public_key}"
+        ))
+        .body(Body::empty())
+        .unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let page = read_body(response).await;
+    assert!(page.contains(&format!(
+        r#"name="native_app_public_key" value="{public_key}""#
+    )));
+
+    // Wrong passwords and unknown users are rejected.
+    for (login, password) in [("alice", "wrong-password"), ("bob", "correct-horse")] {
+        let response = send(
+            &router,
+            sign_in_request(&[
+                ("native_app_port", "4321"),
+                ("native_app_public_key", &public_key),
+                ("login", login),
+                ("password", password),
+            ]),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(read_body(response)
+            .await
+            .contains("Invalid username or password."));
+    }
+
+    // Signing in redirects the browser back to the editor with an encrypted access token.
+    let response = send(
+        &router,
+        sign_in_request(&[
+            ("native_app_port", "4321"),
+            ("native_app_public_key", &public_key),
+            ("login", "alice"),
+            ("password", "correct-horse"),
+        ]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let location = response.headers()[header::LOCATION].to_str().unwrap();
+    let query = location
+        .strip_prefix("http://127.0.0.1:4321/?")
+        .expect("redirect should target the editor's local server");
+    let mut user_id = None;
+    let mut encrypted_access_token = None;
+    for pair in query.split('&') {
+        let (key, value) = pair.split_once('=').unwrap();
+        match key {
+            "user_id" => user_id = Some(value.parse::<i32>().unwrap()),
+            "access_token" => encrypted_access_token = Some(value),
+            _ => panic!("unexpected query parameter {key}"),
+        }
+    }
+    assert_eq!(user_id, Some(user.id.0));
+
+    let access_token = private_key
+        .decrypt_string(encrypted_access_token.unwrap())
+        .unwrap();
+    let result = auth::verify_access_token(&access_token, user.id, &app_state.db)
+        .await
+        .unwrap();
+    assert!(result.is_valid);
+    assert_eq!(result.impersonator_id, None);
+
+    // Changing the password invalidates the old one.
+    app_state
+        .db
+        .set_user_password_hash(user.id, &auth::hash_password("battery-staple").unwrap())
+        .await
+        .unwrap();
+    let response = send(
+        &router,
+        sign_in_request(&[
+            ("native_app_port", "4321"),
+            ("native_app_public_key", &public_key),
+            ("login", "alice"),
+            ("password", "correct-horse"),
+        ]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Once a user's password was guessed too many times, signing in as them is refused
+    // for a while, even with the right password.
+    for _ in 0..7 {
+        let response = send(
+            &router,
+            sign_in_request(&[
+                ("native_app_port", "4321"),
+                ("native_app_public_key", &public_key),
+                ("login", "alice"),
+                ("password", "wrong-password"),
+            ]),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+    let response = send(
+        &router,
+        sign_in_request(&[
+            ("native_app_port", "4321"),
+            ("native_app_public_key", &public_key),
+            ("login", "alice"),
+            ("password", "battery-staple"),
+        ]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(read_body(response)
+        .await
+        .contains("Too many sign-in attempts."));
+
+    let response = send(
+        &router,
+        Request::get("/native_app_signin_succeeded")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[gpui::test]
+async fn test_extensions_from_filesystem_blob_store(executor: BackgroundExecutor) {
+    // The filesystem blob store uses tokio's blocking thread pool.
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let _runtime_guard = runtime.enter();
+    executor.allow_parking();
+
+    let server = TestServer::start(executor.clone()).await;
+    let root = tempfile::tempdir().unwrap();
+    let blob_store = BlobStore::FileSystem {
+        root: root.path().into(),
+    };
+    let bucket = "extensions-bucket";
+
+    let manifest = ExtensionApiManifest {
+        name: "Test Extension".into(),
+        version: "0.1.0".into(),
+        description: Some("An extension served from disk".into()),
+        authors: vec!["Alice".into()],
+        repository: "https://example.com/test-extension".into(),
+        schema_version: Some(1),
+        wasm_api_version: None,
+    };
+    blob_store
+        .put_public(
+            bucket,
+            "extensions/test-extension/0.1.0/manifest.json",
+            serde_json::to_vec(&manifest).unwrap(),
+        )
+        .await
+        .unwrap();
+    blob_store
+        .put_public(
+            bucket,
+            "extensions/test-extension/0.1.0/archive.tar.gz",
+            b"the-archive".to_vec(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        blob_store.list(bucket, "extensions/").await.unwrap(),
+        [
+            "extensions/test-extension/0.1.0/archive.tar.gz",
+            "extensions/test-extension/0.1.0/manifest.json",
+        ]
+    );
+    assert!(blob_store
+        .list("other-bucket", "")
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(blob_store
+        .exists(bucket, "extensions/test-extension/0.1.0/manifest.json")
+        .await
+        .unwrap());
+    assert!(!blob_store
+        .exists(bucket, "extensions/test-extension/0.2.0/manifest.json")
+        .await
+        .unwrap());
+
+    // Keys can't escape the store's root directory.
+    for key in ["../outside", "extensions//manifest.json", "/etc/passwd"] {
+        assert!(blob_store
+            .put_public(bucket, key, Vec::new())
+            .await
+            .is_err());
+    }
+    assert!(blob_store.get("..", "anything").await.is_err());
+
+    let mut config = TestServer::test_config();
+    config.blob_store_bucket = Some(bucket.into());
+    let app_state = Arc::new(AppState {
+        db: server.app_state.db.clone(),
+        live_kit_client: None,
+        blob_store: Some(blob_store.clone()),
+        rate_limiter: server.app_state.rate_limiter.clone(),
+        executor: server.app_state.executor.clone(),
+        clickhouse_client: None,
+        config,
+    });
+
+    fetch_extensions_from_blob_store(&blob_store, bucket, &app_state)
+        .await
+        .unwrap();
+    let extensions = app_state.db.get_extensions(None, 1, 10).await.unwrap();
+    assert_eq!(extensions.len(), 1);
+    assert_eq!(extensions[0].id.as_ref(), "test-extension");
+    assert_eq!(extensions[0].manifest.name, "Test Extension");
+
+    // Without presigned URLs, the server serves the archive itself.
+    let router = api::extensions::router().layer(Extension(app_state.clone()));
+    let response = send(
+        &router,
+        Request::get("/extensions/test-extension/0.1.0/download")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_body(response).await, "the-archive");
+
+    let response = send(
+        &router,
+        Request::get("/extensions/test-extension/0.2.0/download")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+async fn send(router: &Router, request: Request<Body>) -> Response {
+    router.clone().oneshot(request).await.unwrap()
+}
+
+async fn read_body(response: Response) -> String {
+    let mut body = response.into_body();
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        bytes.extend_from_slice(&chunk.unwrap());
+    }
+    String::from_utf8(bytes).unwrap()
+}
+
+fn sign_in_request(fields: &[(&str, &str)]) -> Request<Body> {
+    let body = fields
+        .iter()
+        .map(|(key, value)| format!("{key}={}", form_encode(value)))
+        .collect::<Vec<_>>()
+        .join("&");
+    Request::post("/native_app_signin")
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+fn form_encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.*".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
@@ -0,0 +1,387 @@
+use crate::tests::TestServer;
+use call::ActiveCall;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+use gpui::{BackgroundExecutor, Context, Model, TestAppContext};
+use language::{Anchor, Location};
+use parking_lot::Mutex;
+use project::{
+    project_settings::{GuestTaskPermission, ProjectSettings},
+    GuestTaskApproval, Project,
+};
+use rpc::proto;
+use serde_json::json;
+use settings::SettingsStore;
+use std::{sync::Arc, time::Duration};
+use terminal::{terminal_settings::AlternateScroll, Terminal, TerminalBuilder};
+
+#[gpui::test]
+async fn test_share_terminal(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    let project_a = client_a.build_test_project(cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    // The terminal is shared before the guest joins.
+    let (terminal_a, mut input_a) = build_host_terminal(cx_a);
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"$ echo hello\r\nhello\r\n$ ", cx)
+    });
+    let terminal_id = project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, true, cx)
+        })
+        .unwrap();
+
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+    executor.run_until_parked();
+    let terminal_b = remote_terminal(&project_b, cx_b);
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert!(terminal.is_display_only());
+        assert!(!terminal.is_read_only());
+        assert_eq!(terminal.title(false), "host shell");
+        assert_eq!(first_lines(terminal, 3), "$ echo hello\nhello\n$");
+    });
+
+    // Output is streamed to the guest.
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"ls\r\n1.txt 2.js 3.rs\r\n$ ", cx);
+        terminal.set_title("host shell — ls".into(), cx);
+    });
+    executor.advance_clock(Duration::from_millis(100));
+    executor.run_until_parked();
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert_eq!(terminal.title(false), "host shell — ls");
+        assert_eq!(
+            first_lines(terminal, 5),
+            "$ echo hello\nhello\n$ ls\n1.txt 2.js 3.rs\n$"
+        );
+    });
+
+    // Input is forwarded to the host.
+    terminal_b.update(cx_b, |terminal, _| terminal.input("pwd\r".into()));
+    executor.run_until_parked();
+    assert_eq!(input_a.try_next().unwrap().unwrap(), b"pwd\r");
+
+    // The guest's terminal goes away once the host stops sharing it.
+    project_a
+        .update(cx_a, |project, cx| {
+            project.unshare_terminal(terminal_id, cx)
+        })
+        .unwrap();
+    executor.run_until_parked();
+    project_b.read_with(cx_b, |project, _| {
+        assert_eq!(project.remote_terminals().count(), 0);
+    });
+    terminal_b.update(cx_b, |terminal, _| terminal.input("whoami\r".into()));
+    executor.run_until_parked();
+    assert!(input_a.try_next().is_err());
+}
+
+#[gpui::test]
+async fn test_share_terminal_read_only(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    let project_a = client_a.build_test_project(cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+
+    let (terminal_a, mut input_a) = build_host_terminal(cx_a);
+    let terminal_id = project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, false, cx)
+        })
+        .unwrap();
+    executor.run_until_parked();
+
+    let terminal_b = remote_terminal(&project_b, cx_b);
+    assert!(terminal_b.read_with(cx_b, |terminal, _| terminal.is_read_only()));
+    terminal_b.update(cx_b, |terminal, _| terminal.input("ls\r".into()));
+    executor.run_until_parked();
+    assert!(input_a.try_next().is_err());
+
+    // The host rejects input for a read-only terminal.
+    let response = client_b.client().request(proto::TerminalInput {
+        project_id,
+        terminal_id,
+        input: b"ls\r".to_vec(),
+    });
+    assert!(response.await.is_err());
+    assert!(input_a.try_next().is_err());
+}
+
+#[gpui::test]
+async fn test_share_terminal_with_channel_guest(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    let channel_id = server
+        .make_public_channel("the-channel", &client_a, cx_a)
+        .await;
+    let project_a = client_a.build_test_project(cx_a).await;
+    active_call_a
+        .update(cx_a, |call, cx| call.join_channel(channel_id, cx))
+        .await
+        .unwrap();
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    let (terminal_a, mut input_a) = build_host_terminal(cx_a);
+    let terminal_id = project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, true, cx)
+        })
+        .unwrap();
+
+    // Client B joins the channel as a guest, so it can't type into the terminal.
+    cx_b.update(|cx| workspace::join_channel(channel_id, client_b.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let project_b = cx_b
+        .read(ActiveCall::global)
+        .read_with(cx_b, |call, _| call.location().unwrap().upgrade().unwrap());
+    assert!(project_b.read_with(cx_b, |project, _| project.is_read_only()));
+    let terminal_b = remote_terminal(&project_b, cx_b);
+    assert!(terminal_b.read_with(cx_b, |terminal, _| terminal.is_read_only()));
+
+    let response = client_b.client().request(proto::TerminalInput {
+        project_id,
+        terminal_id,
+        input: b"ls\r".to_vec(),
+    });
+    assert!(response.await.is_err());
+    executor.run_until_parked();
+    assert!(input_a.try_next().is_err());
+}
+
+#[gpui::test]
+async fn test_guest_runs_host_task(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/a",
+            json!({
+                ".zed": {
+                    "tasks.json": r#"[
+                        {"label": "build", "command": "cargo", "args": ["build"]},
+                        {"label": "check", "command": "cargo", "args": ["check", "$ZED_FILENAME"]},
+                        {"label": "deploy", "command": "./deploy.sh"}
+                    ]"#,
+                },
+                "main.rs": "fn main() {}",
+                "lib.rs": "fn lib() {}",
+            }),
+        )
+        .await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+    executor.run_until_parked();
+    assert!(project_b.read_with(cx_b, |project, _| project.runs_tasks_on_host()));
+
+    // The host runs the tasks it approves in a terminal of its own, and denies the others.
+    let (terminal_a, _input_a) = build_host_terminal(cx_a);
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let _subscription = cx_a.update(|cx| {
+        let requests = requests.clone();
+        cx.subscribe(&project_a, move |_, event, _| {
+            if let project::Event::GuestTaskRequested(request) = event {
+                let spawn = request.spawn_in_terminal();
+                requests
+                    .lock()
+                    .push((spawn.command_label.clone(), request.needs_approval()));
+                if spawn.label == "deploy" {
+                    request.respond(Err(anyhow::anyhow!("denied")));
+                } else {
+                    request.respond(Ok((GuestTaskApproval::Task, terminal_a.clone())));
+                }
+            }
+        })
+    });
+
+    let templates = project_b
+        .update(cx_b, |project, cx| project.task_templates(None, None, cx))
+        .await
+        .unwrap();
+    let run_task_on_host = |label: &str, cx_b: &mut TestAppContext| {
+        let (kind, _) = templates
+            .iter()
+            .find(|(_, template)| template.label == label)
+            .unwrap();
+        project_b.update(cx_b, |project, cx| {
+            project.spawn_task_on_host(kind, label.to_string(), cx)
+        })
+    };
+
+    run_task_on_host("build", cx_b).await.unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        requests.lock().drain(..).collect::<Vec<_>>(),
+        [("cargo build".to_string(), true)]
+    );
+    let terminal_b = remote_terminal(&project_b, cx_b);
+    assert!(terminal_b.read_with(cx_b, |terminal, _| terminal.is_read_only()));
+
+    // The task was approved once, so the host isn't asked again.
+    run_task_on_host("build", cx_b).await.unwrap();
+    assert_eq!(
+        requests.lock().drain(..).collect::<Vec<_>>(),
+        [("cargo build".to_string(), false)]
+    );
+
+    assert!(run_task_on_host("deploy", cx_b).await.is_err());
+    assert_eq!(
+        requests.lock().drain(..).collect::<Vec<_>>(),
+        [("./deploy.sh".to_string(), true)]
+    );
+
+    // Tasks are resolved with the variables the host resolved for the guest's location, and
+    // the host is asked again whenever they change the command.
+    let worktree_id = project_b.read_with(cx_b, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    for (path, needs_approval) in [("main.rs", true), ("lib.rs", true), ("lib.rs", false)] {
+        let buffer = project_b
+            .update(cx_b, |project, cx| {
+                project.open_buffer((worktree_id, path), cx)
+            })
+            .await
+            .unwrap();
+        project_b
+            .update(cx_b, |project, cx| {
+                let location = Location {
+                    range: Anchor::MIN..Anchor::MAX,
+                    buffer,
+                };
+                project.task_context_for_location(Default::default(), location, cx)
+            })
+            .await
+            .unwrap();
+        run_task_on_host("check", cx_b).await.unwrap();
+        assert_eq!(
+            requests.lock().drain(..).collect::<Vec<_>>(),
+            [(format!("cargo check {path}"), needs_approval)]
+        );
+    }
+
+    // Editing the environment or the working directory of an approved task makes the host
+    // approve it again.
+    for (build, needs_approval) in [
+        (
+            r#"{"label": "build", "command": "cargo", "args": ["build"], "env": {"PATH": "/tmp/bin"}}"#,
+            true,
+        ),
+        (
+            r#"{"label": "build", "command": "cargo", "args": ["build"], "env": {"PATH": "/tmp/bin"}}"#,
+            false,
+        ),
+        (
+            r#"{"label": "build", "command": "cargo", "args": ["build"], "env": {"PATH": "/tmp/bin"}, "cwd": "/tmp"}"#,
+            true,
+        ),
+    ] {
+        client_a
+            .fs()
+            .insert_file("/a/.zed/tasks.json", format!("[{build}]").into_bytes())
+            .await;
+        executor.run_until_parked();
+        run_task_on_host("build", cx_b).await.unwrap();
+        assert_eq!(
+            requests.lock().drain(..).collect::<Vec<_>>(),
+            [("cargo build".to_string(), needs_approval)]
+        );
+    }
+
+    // Once the host disallows tasks for members, the host isn't asked at all.
+    cx_a.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.guest_tasks.member = Some(GuestTaskPermission::Deny);
+            });
+        });
+    });
+    assert!(run_task_on_host("build", cx_b).await.is_err());
+    assert!(requests.lock().is_empty());
+}
+
+/// Builds a terminal for the host that records its input instead of running a shell.
+fn build_host_terminal(cx: &mut TestAppContext) -> (Model<Terminal>, UnboundedReceiver<Vec<u8>>) {
+    let (input_tx, input_rx) = unbounded();
+    let terminal = cx.new_model(|cx| {
+        TerminalBuilder::new_display_only(
+            "host shell".into(),
+            None,
+            AlternateScroll::On,
+            Some(input_tx),
+        )
+        .subscribe(cx)
+    });
+    (terminal, input_rx)
+}
+
+fn remote_terminal(project: &Model<Project>, cx: &mut TestAppContext) -> Model<Terminal> {
+    project.read_with(cx, |project, _| {
+        let terminals = project.remote_terminals().cloned().collect::<Vec<_>>();
+        assert_eq!(terminals.len(), 1);
+        terminals[0].clone()
+    })
+}
+
+fn first_lines(terminal: &Terminal, count: usize) -> String {
+    terminal
+        .screen_text()
+        .lines()
+        .take(count)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -293,6 +293,7 @@ impl TestServer {
             file_finder::init(cx);
             menu::init();
             dev_server_projects::init(client.clone(), cx);
+            terminal::init(cx);
             settings::KeymapFile::load_asset(os_keymap, cx).unwrap();
         });
 
@@ -459,6 +460,10 @@ impl TestServer {
         deterministic.run_until_parked();
     }
 
+    pub fn rpc_server(&self) -> Arc<Server> {
+        self.server.clone()
+    }
+
     pub fn forbid_connections(&self) {
         self.forbid_connections.store(true, SeqCst);
     }
@@ -626,43 +631,50 @@ impl TestServer {
         Arc::new(AppState {
             db: test_db.db().clone(),
             live_kit_client: Some(Arc::new(live_kit_test_server.create_api_client())),
-            blob_store_client: None,
+            blob_store: None,
             rate_limiter: Arc::new(RateLimiter::new(test_db.db().clone())),
             executor,
             clickhouse_client: None,
-            config: Config {
-                http_port: 0,
-                database_url: "".into(),
-                database_max_connections: 0,
-                api_token: "".into(),
-                invite_link_prefix: "".into(),
-                live_kit_server: None,
-                live_kit_key: None,
-                live_kit_secret: None,
-                rust_log: None,
-                log_json: None,
-                zed_environment: "test".into(),
-                blob_store_url: None,
-                blob_store_region: None,
-                blob_store_access_key: None,
-                blob_store_secret_key: None,
-                blob_store_bucket: None,
-                openai_api_key: None,
-                google_ai_api_key: None,
-                anthropic_api_key: None,
-                clickhouse_url: None,
-                clickhouse_user: None,
-                clickhouse_password: None,
-                clickhouse_database: None,
-                zed_client_checksum_seed: None,
-                slack_panics_webhook: None,
-                auto_join_channel_id: None,
-                migrations_path: None,
-                seed_path: None,
-                supermaven_admin_api_key: None,
-            },
+            config: Self::test_config(),
         })
     }
+
+    pub fn test_config() -> Config {
+        Config {
+            http_port: 0,
+            database_url: "".into(),
+            database_max_connections: 0,
+            api_token: "test-api-token".into(),
+            invite_link_prefix: "".into(),
+            live_kit_server: None,
+            live_kit_key: None,
+            live_kit_secret: None,
+            rust_log: None,
+            log_json: None,
+            zed_environment: "test".into(),
+            blob_store_url: None,
+            blob_store_region: None,
+            blob_store_access_key: None,
+            blob_store_secret_key: None,
+            blob_store_bucket: None,
+            blob_store_path: None,
+            local_auth_enabled: None,
+            telemetry_enabled: None,
+            openai_api_key: None,
+            google_ai_api_key: None,
+            anthropic_api_key: None,
+            clickhouse_url: None,
+            clickhouse_user: None,
+            clickhouse_password: None,
+            clickhouse_database: None,
+            zed_client_checksum_seed: None,
+            slack_panics_webhook: None,
+            auto_join_channel_id: None,
+            migrations_path: None,
+            seed_path: None,
+            supermaven_admin_api_key: None,
+        }
+    }
 }
 
 impl Deref for TestServer {
@@ -30,7 +30,12 @@ use workspace::{
     ItemNavHistory, Pane, SaveIntent, Toast, ViewId, Workspace, WorkspaceId,
 };
 
-actions!(collab, [CopyLink]);
+mod notes_history;
+mod revision_diff;
+
+use notes_history::NotesHistory;
+
+actions!(collab, [CopyLink, ShowNotesHistory]);
 
 pub fn init(cx: &mut AppContext) {
     register_followable_item::<ChannelView>(cx)
@@ -171,8 +176,15 @@ impl ChannelView {
             editor.set_custom_context_menu(move |_, position, cx| {
                 let this = this.clone();
                 Some(ui::ContextMenu::build(cx, move |menu, _| {
-                    menu.entry("Copy link to section", None, move |cx| {
-                        this.update(cx, |this, cx| this.copy_link_for_position(position, cx))
+                    menu.entry("Copy link to section", None, {
+                        let this = this.clone();
+                        move |cx| {
+                            this.update(cx, |this, cx| this.copy_link_for_position(position, cx))
+                                .ok();
+                        }
+                    })
+                    .entry("Show history", None, move |cx| {
+                        this.update(cx, |this, cx| this.show_history(&ShowNotesHistory, cx))
                             .ok();
                     })
                 }))
@@ -283,6 +295,18 @@ impl ChannelView {
             .ok();
     }
 
+    fn show_history(&mut self, _: &ShowNotesHistory, cx: &mut ViewContext<Self>) {
+        let channel_buffer = self.channel_buffer.clone();
+        let weak_workspace = self.workspace.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(cx, |cx| {
+                    NotesHistory::new(channel_buffer, weak_workspace, cx)
+                });
+            })
+            .ok();
+    }
+
     pub fn channel(&self, cx: &AppContext) -> Option<Arc<Channel>> {
         self.channel_buffer.read(cx).channel(cx)
     }
@@ -346,6 +370,7 @@ impl Render for ChannelView {
         div()
             .size_full()
             .on_action(cx.listener(Self::copy_link))
+            .on_action(cx.listener(Self::show_history))
             .child(self.editor.clone())
     }
 }
@@ -0,0 +1,305 @@
+use super::revision_diff::RevisionDiff;
+use channel::{ChannelBuffer, ChannelBufferRevision};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render, Task, View,
+    ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{prelude::*, Avatar, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+/// Lists the revisions of a channel's notes, newest first.
+///
+/// Confirming a revision opens what changed in it, or its difference with the revision
+/// marked as the comparison base via the secondary confirm.
+pub struct NotesHistory {
+    picker: View<Picker<NotesHistoryDelegate>>,
+}
+
+impl NotesHistory {
+    pub fn new(
+        channel_buffer: Model<ChannelBuffer>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = NotesHistoryDelegate {
+            notes_history: cx.view().downgrade(),
+            workspace,
+            channel_buffer: channel_buffer.clone(),
+            entries: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            comparison_base: None,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+
+        let history =
+            channel_buffer.update(cx, |channel_buffer, cx| channel_buffer.load_history(cx));
+        let now = OffsetDateTime::now_utc();
+        let timezone = cx.local_timezone();
+        cx.spawn(|this, mut cx| async move {
+            let revisions = history.await?;
+            let entries = revisions
+                .into_iter()
+                .rev()
+                .map(|revision| {
+                    let author = revision
+                        .author
+                        .as_ref()
+                        .map_or("unknown author", |author| author.github_login.as_str());
+                    let (relative_time, absolute_time) = match revision.timestamp {
+                        Some(timestamp) => (
+                            time_format::format_localized_timestamp(
+                                timestamp,
+                                now,
+                                timezone,
+                                TimestampFormat::Relative,
+                            ),
+                            time_format::format_localized_timestamp(
+                                timestamp,
+                                now,
+                                timezone,
+                                TimestampFormat::EnhancedAbsolute,
+                            ),
+                        ),
+                        None => ("unknown time".into(), String::new()),
+                    };
+                    HistoryEntry {
+                        label: format!("{author} · {relative_time}"),
+                        absolute_time,
+                        revision,
+                    }
+                })
+                .collect();
+            this.update(&mut cx, |this, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker.delegate.entries = entries;
+                    picker.refresh(cx);
+                })
+            })
+        })
+        .detach_and_log_err(cx);
+
+        Self { picker }
+    }
+}
+
+impl Render for NotesHistory {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for NotesHistory {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for NotesHistory {}
+impl ModalView for NotesHistory {}
+
+struct HistoryEntry {
+    revision: ChannelBufferRevision,
+    label: String,
+    absolute_time: String,
+}
+
+pub struct NotesHistoryDelegate {
+    notes_history: WeakView<NotesHistory>,
+    workspace: WeakView<Workspace>,
+    channel_buffer: Model<ChannelBuffer>,
+    entries: Vec<HistoryEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    comparison_base: Option<usize>,
+}
+
+impl NotesHistoryDelegate {
+    fn open_diff(&self, entry_ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let entry = &self.entries[entry_ix];
+        // Entries are newest first, so the previous revision is the next entry.
+        let base_ix = self
+            .comparison_base
+            .filter(|base_ix| *base_ix != entry_ix)
+            .or_else(|| (entry_ix + 1 < self.entries.len()).then_some(entry_ix + 1));
+        let title = match base_ix {
+            Some(base_ix) if Some(base_ix) == self.comparison_base => format!(
+                "{} (compared with {})",
+                entry.label, self.entries[base_ix].label
+            ),
+            _ => entry.label.clone(),
+        };
+
+        let channel_buffer = self.channel_buffer.clone();
+        let (content, base) = channel_buffer.update(cx, |channel_buffer, cx| {
+            let content = channel_buffer.load_revision(&entry.revision, cx);
+            let base = match base_ix {
+                Some(base_ix) => channel_buffer.load_revision(&self.entries[base_ix].revision, cx),
+                None => channel_buffer.load_epoch(0, cx),
+            };
+            (content, base)
+        });
+        cx.spawn(|_, mut cx| async move {
+            let (content, base) = futures::try_join!(content, base)?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let diff = cx.new_view(|cx| {
+                    RevisionDiff::new(channel_buffer, content, base.text, title, cx)
+                });
+                workspace.add_item_to_active_pane(Box::new(diff), None, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for NotesHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search the history of these notes by author...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No revisions of these notes".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// Opens the changes made by the selected revision, or marks it as the base that
+    /// other revisions are compared with when the secondary confirm is used.
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(mat) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        let entry_ix = mat.candidate_id;
+
+        if secondary {
+            if self.comparison_base == Some(entry_ix) {
+                self.comparison_base = None;
+            } else {
+                self.comparison_base = Some(entry_ix);
+            }
+            cx.notify();
+        } else {
+            self.open_diff(entry_ix, cx);
+            self.dismissed(cx);
+        }
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.notes_history
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| StringMatchCandidate::new(id, entry.label.clone()))
+            .collect::<Vec<_>>();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                // Keep the history in chronological order rather than by score.
+                matches.sort_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let entry = &self.entries[mat.candidate_id];
+        let is_comparison_base = self.comparison_base == Some(mat.candidate_id);
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot::<Avatar>(
+                    entry
+                        .revision
+                        .author
+                        .as_ref()
+                        .map(|author| Avatar::new(author.avatar_uri.clone())),
+                )
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .end_slot(
+                    h_flex()
+                        .gap_2()
+                        .when(is_comparison_base, |this| {
+                            this.child(
+                                Label::new("comparison base")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Accent),
+                            )
+                        })
+                        .child(
+                            Label::new(entry.absolute_time.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
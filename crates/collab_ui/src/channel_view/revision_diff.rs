@@ -0,0 +1,233 @@
+use channel::{ChannelBuffer, ChannelBufferRevisionContent};
+use client::User;
+use editor::{actions::ExpandAllHunkDiffs, Editor, EditorEvent};
+use gpui::{
+    AnyElement, AnyView, AppContext, EventEmitter, FocusHandle, FocusableView, Model, Render,
+    SharedString, View,
+};
+use language::Buffer;
+use std::{any::TypeId, sync::Arc};
+use ui::prelude::*;
+use workspace::item::{Item, TabContentParams};
+
+/// Shows a revision of a channel's notes compared with another one, along with who
+/// wrote which parts of it.
+pub struct RevisionDiff {
+    editor: View<Editor>,
+    channel_buffer: Model<ChannelBuffer>,
+    content: ChannelBufferRevisionContent,
+    authors: Vec<(Option<Arc<User>>, usize)>,
+    highlighted_author: Option<usize>,
+    title: SharedString,
+}
+
+enum AuthorshipHighlight {}
+
+impl RevisionDiff {
+    pub fn new(
+        channel_buffer: Model<ChannelBuffer>,
+        content: ChannelBufferRevisionContent,
+        base_text: String,
+        title: impl Into<SharedString>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let language = channel_buffer
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .language()
+            .cloned();
+        let text = content.text.clone();
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::local(text, cx);
+            buffer.set_language(language, cx);
+            buffer.set_diff_base(Some(base_text), cx);
+            buffer
+        });
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer.clone(), None, cx);
+            editor.set_read_only(true);
+            editor
+        });
+
+        let mut authors: Vec<(Option<Arc<User>>, usize)> = Vec::new();
+        for (range, author) in &content.authorship {
+            let author_id = author.as_ref().map(|author| author.id);
+            match authors
+                .iter_mut()
+                .find(|(existing, _)| existing.as_ref().map(|author| author.id) == author_id)
+            {
+                Some((_, len)) => *len += range.len(),
+                None => authors.push((author.clone(), range.len())),
+            }
+        }
+        authors.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        if let Some(recalc) = buffer.update(cx, |buffer, cx| buffer.git_diff_recalc(cx)) {
+            let editor = editor.downgrade();
+            cx.spawn(|_, mut cx| async move {
+                recalc.await;
+                editor.update(&mut cx, |editor, cx| {
+                    editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx)
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+
+        Self {
+            editor,
+            channel_buffer,
+            content,
+            authors,
+            highlighted_author: None,
+            title: title.into(),
+        }
+    }
+
+    fn toggle_author_highlight(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if self.highlighted_author == Some(ix) {
+            self.highlighted_author = None;
+            self.editor.update(cx, |editor, cx| {
+                editor.clear_background_highlights::<AuthorshipHighlight>(cx);
+            });
+            return;
+        }
+
+        self.highlighted_author = Some(ix);
+        let author_id = self.authors[ix].0.as_ref().map(|author| author.id);
+        let authorship = &self.content.authorship;
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let ranges = authorship
+                .iter()
+                .filter(|(_, author)| author.as_ref().map(|author| author.id) == author_id)
+                .map(|(range, _)| {
+                    snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end)
+                })
+                .collect::<Vec<_>>();
+            editor.highlight_background::<AuthorshipHighlight>(
+                &ranges,
+                |colors| colors.editor_document_highlight_read_background,
+                cx,
+            );
+        });
+    }
+
+    fn restore(&mut self, cx: &mut ViewContext<Self>) {
+        self.channel_buffer
+            .update(cx, |channel_buffer, cx| {
+                channel_buffer.restore_revision(&self.content, cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn render_authors(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let total_len = self.content.text.len().max(1);
+        h_flex()
+            .gap_1()
+            .children(self.authors.iter().enumerate().map(|(ix, (author, len))| {
+                let login = author
+                    .as_ref()
+                    .map_or("unknown", |author| author.github_login.as_str());
+                let share = len * 100 / total_len;
+                Button::new(("author", ix), format!("{login} {share}%"))
+                    .style(ButtonStyle::Subtle)
+                    .selected(self.highlighted_author == Some(ix))
+                    .on_click(cx.listener(move |this, _, cx| this.toggle_author_highlight(ix, cx)))
+            }))
+    }
+}
+
+impl EventEmitter<EditorEvent> for RevisionDiff {}
+
+impl FocusableView for RevisionDiff {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for RevisionDiff {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let can_restore = self.channel_buffer.read(cx).is_connected()
+            && !self.channel_buffer.read(cx).buffer().read(cx).read_only();
+        v_flex()
+            .size_full()
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        v_flex()
+                            .gap_1()
+                            .child(Label::new(self.title.clone()))
+                            .child(self.render_authors(cx)),
+                    )
+                    .child(
+                        Button::new("restore", "Restore This Version")
+                            .icon(IconName::HistoryRerun)
+                            .icon_position(IconPosition::Start)
+                            .style(ButtonStyle::Filled)
+                            .disabled(!can_restore)
+                            .on_click(cx.listener(|this, _, cx| this.restore(cx))),
+                    ),
+            )
+            .child(self.editor.clone())
+    }
+}
+
+impl Item for RevisionDiff {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(workspace::item::ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new(self.title.clone())
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some("Notes History".into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("channel notes revision diff")
+    }
+
+    fn display_name(&self) -> Option<&'static str> {
+        Some("channel notes revision diff")
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn navigate(&mut self, data: Box<dyn std::any::Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+}
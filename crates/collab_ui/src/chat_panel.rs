@@ -1,7 +1,10 @@
 use crate::{collab_panel, ChatPanelSettings};
 use anyhow::Result;
 use call::{room, ActiveCall};
-use channel::{ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, ChannelStore};
+use channel::{
+    ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, ChannelMessageSearchResult,
+    ChannelStore,
+};
 use client::{ChannelId, Client};
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
@@ -15,6 +18,7 @@ use gpui::{
 use language::LanguageRegistry;
 use menu::Confirm;
 use message_editor::MessageEditor;
+use message_search::MessageSearch;
 use project::Fs;
 use rich_text::{Highlight, RichText};
 use serde::{Deserialize, Serialize};
@@ -22,8 +26,8 @@ use settings::Settings;
 use std::{sync::Arc, time::Duration};
 use time::{OffsetDateTime, UtcOffset};
 use ui::{
-    prelude::*, Avatar, Button, ContextMenu, IconButton, IconName, KeyBinding, Label, PopoverMenu,
-    TabBar, Tooltip,
+    prelude::*, Avatar, Button, ButtonStyle, ContextMenu, IconButton, IconButtonShape, IconName,
+    KeyBinding, Label, PopoverMenu, TabBar, Tooltip,
 };
 use util::{ResultExt, TryFutureExt};
 use workspace::{
@@ -32,15 +36,24 @@ use workspace::{
 };
 
 mod message_editor;
+mod message_search;
 
 const MESSAGE_LOADING_THRESHOLD: usize = 50;
 const CHAT_PANEL_KEY: &str = "ChatPanel";
+const QUICK_REACTIONS: [&str; 5] = ["👍", "❤️", "😄", "🎉", "👀"];
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(|workspace: &mut Workspace, _| {
         workspace.register_action(|workspace, _: &ToggleFocus, cx| {
             workspace.toggle_panel_focus::<ChatPanel>(cx);
         });
+        workspace.register_action(|workspace, _: &SearchMessages, cx| {
+            let channel_store = ChannelStore::global(cx);
+            let workspace_handle = cx.view().downgrade();
+            workspace.toggle_modal(cx, |cx| {
+                MessageSearch::new(channel_store, workspace_handle, cx)
+            });
+        });
     })
     .detach();
 }
@@ -64,6 +77,8 @@ pub struct ChatPanel {
     open_context_menu: Option<(u64, Subscription)>,
     highlighted_message: Option<(u64, Task<()>)>,
     last_acknowledged_message_id: Option<u64>,
+    /// The message whose thread is open, which new messages are sent to.
+    active_thread: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -71,7 +86,7 @@ struct SerializedChatPanel {
     width: Option<Pixels>,
 }
 
-actions!(chat_panel, [ToggleFocus]);
+actions!(chat_panel, [ToggleFocus, SearchMessages]);
 
 impl ChatPanel {
     pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
@@ -130,6 +145,7 @@ impl ChatPanel {
                 open_context_menu: None,
                 highlighted_message: None,
                 last_acknowledged_message_id: None,
+                active_thread: None,
             };
 
             if let Some(channel_id) = ActiveCall::global(cx)
@@ -234,6 +250,7 @@ impl ChatPanel {
     fn set_active_chat(&mut self, chat: Model<ChannelChat>, cx: &mut ViewContext<Self>) {
         if self.active_chat.as_ref().map(|e| &e.0) != Some(&chat) {
             self.markdown_data.clear();
+            self.active_thread = None;
             self.message_list.reset(chat.read(cx).message_count());
             self.message_editor.update(cx, |editor, cx| {
                 editor.set_channel_chat(chat.clone(), cx);
@@ -248,11 +265,18 @@ impl ChatPanel {
 
     fn channel_did_change(
         &mut self,
-        _: Model<ChannelChat>,
+        chat: Model<ChannelChat>,
         event: &ChannelChatEvent,
         cx: &mut ViewContext<Self>,
     ) {
         match event {
+            ChannelChatEvent::ThreadUpdated { thread_parent_id } => {
+                if let Some(replies) = chat.read(cx).thread(*thread_parent_id) {
+                    for reply in replies {
+                        self.markdown_data.remove(&reply.id);
+                    }
+                }
+            }
             ChannelChatEvent::MessagesUpdated {
                 old_range,
                 new_count,
@@ -541,7 +565,8 @@ impl ChatPanel {
                         .when(self.has_open_menu(message_id), |el| {
                             el.bg(cx.theme().colors().element_selected)
                         })
-                    }),
+                    })
+                    .children(self.render_message_footer(&message, true, cx)),
             )
             .when(
                 self.last_acknowledged_message_id
@@ -571,6 +596,150 @@ impl ChatPanel {
             )
     }
 
+    /// Renders the reactions to a message and, for messages of the channel's list, a
+    /// link to their thread.
+    fn render_message_footer(
+        &self,
+        message: &ChannelMessage,
+        show_thread: bool,
+        cx: &ViewContext<Self>,
+    ) -> Option<impl IntoElement> {
+        let ChannelMessageId::Saved(message_id) = message.id else {
+            return None;
+        };
+        let show_thread = show_thread && message.thread_reply_count > 0;
+        if message.reactions.is_empty() && !show_thread {
+            return None;
+        }
+
+        let current_user_id = self.client.user_id();
+        Some(
+            h_flex()
+                .pt_0p5()
+                .gap_1()
+                .flex_wrap()
+                .children(message.reactions.iter().map(|reaction| {
+                    let emoji = reaction.emoji.clone();
+                    let reacted = current_user_id
+                        .map_or(false, |user_id| reaction.user_ids.contains(&user_id));
+                    Button::new(
+                        ElementId::Name(format!("reaction-{message_id}-{emoji}").into()),
+                        format!("{} {}", reaction.emoji, reaction.user_ids.len()),
+                    )
+                    .style(ButtonStyle::Filled)
+                    .label_size(LabelSize::Small)
+                    .selected(reacted)
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.toggle_reaction(message_id, emoji.clone(), cx);
+                    }))
+                }))
+                .when(show_thread, |el| {
+                    let label = if message.thread_reply_count == 1 {
+                        "1 reply".to_string()
+                    } else {
+                        format!("{} replies", message.thread_reply_count)
+                    };
+                    el.child(
+                        Button::new(("open-thread", message_id), label)
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .color(Color::Accent)
+                            .on_click(
+                                cx.listener(move |this, _, cx| this.open_thread(message_id, cx)),
+                            ),
+                    )
+                }),
+        )
+    }
+
+    fn render_thread(&mut self, thread_parent_id: u64, cx: &mut ViewContext<Self>) -> Div {
+        let chat = self.active_chat.as_ref().map(|(chat, _)| chat.read(cx));
+        let parent = chat
+            .and_then(|chat| chat.find_loaded_message(thread_parent_id))
+            .cloned();
+        let replies = chat
+            .and_then(|chat| chat.thread(thread_parent_id))
+            .map(|replies| replies.to_vec());
+
+        v_flex()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .justify_between()
+                    .child(Label::new("Thread").size(LabelSize::Small))
+                    .child(
+                        IconButton::new("close-thread", IconName::Close)
+                            .shape(IconButtonShape::Square)
+                            .tooltip(|cx| Tooltip::text("Close thread", cx))
+                            .on_click(cx.listener(|this, _, cx| this.close_thread(cx))),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("thread-replies")
+                    .px_2()
+                    .pb_1()
+                    .gap_1()
+                    .max_h(rems(20.))
+                    .overflow_y_scroll()
+                    .children(parent.map(|parent| self.render_thread_message(&parent, cx)))
+                    .map(|el| match replies {
+                        Some(replies) => el.children(
+                            replies
+                                .iter()
+                                .map(|reply| self.render_thread_message(reply, cx))
+                                .collect::<Vec<_>>(),
+                        ),
+                        None => el.child(
+                            Label::new("Loading replies…")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    }),
+            )
+    }
+
+    fn render_thread_message(
+        &mut self,
+        message: &ChannelMessage,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let text = self
+            .markdown_data
+            .entry(message.id)
+            .or_insert_with(|| {
+                Self::render_markdown_with_mentions(
+                    &self.languages,
+                    self.client.id(),
+                    message,
+                    self.local_timezone,
+                    cx,
+                )
+            })
+            .clone();
+        let element_id: ElementId = match message.id {
+            ChannelMessageId::Saved(id) => ("thread-message", id).into(),
+            ChannelMessageId::Pending(id) => ("pending-thread-message", id).into(),
+        };
+
+        v_flex()
+            .w_full()
+            .text_ui_sm(cx)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Avatar::new(message.sender.avatar_uri.clone()).size(rems(1.)))
+                    .child(div().font_weight(FontWeight::BOLD).child(
+                        Label::new(message.sender.github_login.clone()).size(LabelSize::Small),
+                    )),
+            )
+            .child(div().id(element_id).child(text.element("body".into(), cx)))
+            .children(self.render_message_footer(message, false, cx))
+    }
+
     fn has_open_menu(&self, message_id: Option<u64>) -> bool {
         match self.open_context_menu.as_ref() {
             Some((id, _)) => Some(*id) == message_id,
@@ -720,6 +889,11 @@ impl ChatPanel {
                         }
                     }),
                 )
+                .entry(
+                    "Reply in thread",
+                    None,
+                    cx.handler_for(&this, move |this, cx| this.open_thread(message_id, cx)),
+                )
                 .when(can_delete_message, |menu| {
                     menu.entry(
                         "Delete message",
@@ -727,6 +901,19 @@ impl ChatPanel {
                         cx.handler_for(&this, move |this, cx| this.remove_message(message_id, cx)),
                     )
                 })
+                .separator()
+                .map(|mut menu| {
+                    for emoji in QUICK_REACTIONS {
+                        menu = menu.entry(
+                            format!("React with {emoji}"),
+                            None,
+                            cx.handler_for(&this, move |this, cx| {
+                                this.toggle_reaction(message_id, emoji.to_string(), cx)
+                            }),
+                        );
+                    }
+                    menu
+                })
             })
         };
         this.update(cx, |this, cx| {
@@ -808,6 +995,15 @@ impl ChatPanel {
                 {
                     task.detach();
                 }
+            } else if let Some(thread_parent_id) = self.active_thread {
+                if let Some(task) = chat
+                    .update(cx, |chat, cx| {
+                        chat.send_thread_reply(thread_parent_id, message, cx)
+                    })
+                    .log_err()
+                {
+                    task.detach_and_log_err(cx);
+                }
             } else {
                 if let Some(task) = chat
                     .update(cx, |chat, cx| chat.send_message(message, cx))
@@ -819,6 +1015,61 @@ impl ChatPanel {
         }
     }
 
+    fn toggle_reaction(&mut self, message_id: u64, emoji: String, cx: &mut ViewContext<Self>) {
+        if let Some((chat, _)) = self.active_chat.as_ref() {
+            chat.update(cx, |chat, cx| chat.toggle_reaction(message_id, emoji, cx))
+                .detach_and_log_err(cx);
+        }
+    }
+
+    /// Shows the replies in the thread started by the given message, and sends new
+    /// messages to that thread until it's closed.
+    pub fn open_thread(&mut self, thread_parent_id: u64, cx: &mut ViewContext<Self>) {
+        let Some((chat, _)) = self.active_chat.as_ref() else {
+            return;
+        };
+        if let Some(previous_thread_id) = self.active_thread.replace(thread_parent_id) {
+            if previous_thread_id != thread_parent_id {
+                chat.update(cx, |chat, _| chat.close_thread(previous_thread_id));
+            }
+        }
+        chat.update(cx, |chat, cx| chat.load_thread(thread_parent_id, cx))
+            .detach_and_log_err(cx);
+        self.cancel_edit_message(cx);
+        self.message_editor.update(cx, |editor, cx| {
+            editor.clear_reply_to_message_id();
+            editor.focus_handle(cx).focus(cx);
+        });
+        cx.notify();
+    }
+
+    fn close_thread(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(thread_parent_id) = self.active_thread.take() {
+            if let Some((chat, _)) = self.active_chat.as_ref() {
+                chat.update(cx, |chat, _| chat.close_thread(thread_parent_id));
+            }
+            cx.notify();
+        }
+    }
+
+    /// Opens the chat a message was found in, along with its thread if it's a reply in one.
+    pub fn select_search_result(
+        &mut self,
+        result: &ChannelMessageSearchResult,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let thread_parent_id = result.message.thread_parent_id;
+        let message_id = thread_parent_id.or(result.message.id.into());
+        let select_channel = self.select_channel(result.channel_id, message_id, cx);
+        cx.spawn(|this, mut cx| async move {
+            select_channel.await?;
+            if let Some(thread_parent_id) = thread_parent_id {
+                this.update(&mut cx, |this, cx| this.open_thread(thread_parent_id, cx))?;
+            }
+            Ok(())
+        })
+    }
+
     fn remove_message(&mut self, id: u64, cx: &mut ViewContext<Self>) {
         if let Some((chat, _)) = self.active_chat.as_ref() {
             chat.update(cx, |chat, cx| chat.remove_message(id, cx).detach())
@@ -932,6 +1183,9 @@ impl Render for ChatPanel {
             .active_chat
             .as_ref()
             .map(|(c, _)| c.read(cx).channel_id);
+        let thread = self
+            .active_thread
+            .map(|thread_parent_id| self.render_thread(thread_parent_id, cx));
         let message_editor = self.message_editor.read(cx);
 
         let reply_to_message_id = message_editor.reply_to_message_id();
@@ -949,6 +1203,7 @@ impl Render for ChatPanel {
                             .w_full()
                             .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
                             .px_2()
+                            .justify_between()
                             .child(Label::new(
                                 self.active_chat
                                     .as_ref()
@@ -956,7 +1211,17 @@ impl Render for ChatPanel {
                                         Some(format!("#{}", c.0.read(cx).channel(cx)?.name))
                                     })
                                     .unwrap_or("Chat".to_string()),
-                            )),
+                            ))
+                            .child(
+                                IconButton::new("search-messages", IconName::MagnifyingGlass)
+                                    .shape(IconButtonShape::Square)
+                                    .tooltip(|cx| {
+                                        Tooltip::for_action("Search Messages", &SearchMessages, cx)
+                                    })
+                                    .on_click(|_, cx| {
+                                        cx.dispatch_action(SearchMessages.boxed_clone())
+                                    }),
+                            ),
                     ),
                 ),
             )
@@ -994,6 +1259,7 @@ impl Render for ChatPanel {
             .when(!self.is_scrolled_to_bottom, |el| {
                 el.child(div().border_t_1().border_color(cx.theme().colors().border))
             })
+            .children(thread)
             .when_some(edit_message_id, |el, _| {
                 el.child(
                     h_flex()
@@ -1189,6 +1455,9 @@ mod tests {
             mentions: vec![(ranges[0].clone(), 101), (ranges[1].clone(), 102)],
             reply_to_message_id: None,
             edited_at: None,
+            thread_parent_id: None,
+            thread_reply_count: 0,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -1243,6 +1512,9 @@ mod tests {
             mentions: Vec::new(),
             reply_to_message_id: None,
             edited_at: None,
+            thread_parent_id: None,
+            thread_reply_count: 0,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -1290,6 +1562,9 @@ mod tests {
             mentions: Vec::new(),
             reply_to_message_id: None,
             edited_at: None,
+            thread_parent_id: None,
+            thread_reply_count: 0,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
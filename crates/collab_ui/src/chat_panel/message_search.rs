@@ -0,0 +1,173 @@
+use crate::chat_panel::ChatPanel;
+use channel::{ChannelMessageSearchResult, ChannelStore};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render, Task, View,
+    ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::{sync::Arc, time::Duration};
+use ui::{prelude::*, Avatar, ListItem, ListItemSpacing};
+use util::{ResultExt as _, TryFutureExt};
+use workspace::{ModalView, Workspace};
+
+const SEARCH_DEBOUNCE_INTERVAL: Duration = Duration::from_millis(200);
+const MAX_PREVIEW_LEN: usize = 120;
+
+/// A modal that searches the messages of all the channels the user can see.
+pub struct MessageSearch {
+    picker: View<Picker<MessageSearchDelegate>>,
+}
+
+impl MessageSearch {
+    pub fn new(
+        channel_store: Model<ChannelStore>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = MessageSearchDelegate {
+            parent: cx.view().downgrade(),
+            workspace,
+            channel_store,
+            results: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl EventEmitter<DismissEvent> for MessageSearch {}
+impl ModalView for MessageSearch {}
+
+impl FocusableView for MessageSearch {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for MessageSearch {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub struct MessageSearchDelegate {
+    parent: WeakView<MessageSearch>,
+    workspace: WeakView<Workspace>,
+    channel_store: Model<ChannelStore>,
+    results: Vec<ChannelMessageSearchResult>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for MessageSearchDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.results.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search messages in all channels...".into()
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        if query.trim().is_empty() {
+            self.results.clear();
+            self.selected_index = 0;
+            cx.notify();
+            return Task::ready(());
+        }
+
+        let channel_store = self.channel_store.clone();
+        cx.spawn(|picker, mut cx| async move {
+            async {
+                cx.background_executor()
+                    .timer(SEARCH_DEBOUNCE_INTERVAL)
+                    .await;
+                let results = channel_store
+                    .update(&mut cx, |store, cx| store.search_messages(query, None, cx))?
+                    .await?;
+                picker.update(&mut cx, |picker, cx| {
+                    picker.delegate.results = results;
+                    picker.delegate.selected_index = 0;
+                    cx.notify();
+                })?;
+                anyhow::Ok(())
+            }
+            .log_err()
+            .await;
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(result) = self.results.get(self.selected_index).cloned() else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                if let Some(panel) = workspace.focus_panel::<ChatPanel>(cx) {
+                    panel.update(cx, |panel, cx| {
+                        panel
+                            .select_search_result(&result, cx)
+                            .detach_and_log_err(cx);
+                    });
+                }
+            })
+            .log_err();
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.parent
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let result = self.results.get(ix)?;
+        let channel_name = self
+            .channel_store
+            .read(cx)
+            .channel_for_id(result.channel_id)
+            .map_or_else(|| "unknown".into(), |channel| channel.name.clone());
+        let message = &result.message;
+        let mut preview = message.body.lines().next().unwrap_or_default().to_string();
+        if let Some((ix, _)) = preview.char_indices().nth(MAX_PREVIEW_LEN) {
+            preview.truncate(ix);
+            preview.push('…');
+        }
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(Avatar::new(message.sender.avatar_uri.clone()))
+                .child(
+                    v_flex()
+                        .child(
+                            Label::new(format!(
+                                "#{channel_name} · {}",
+                                message.sender.github_login
+                            ))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        )
+                        .child(Label::new(preview)),
+                ),
+        )
+    }
+}
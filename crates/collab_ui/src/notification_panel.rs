@@ -13,11 +13,11 @@ use gpui::{
     WindowContext,
 };
 use notifications::{NotificationEntry, NotificationEvent, NotificationStore};
-use project::Fs;
+use project::{Fs, ProjectPath, WorktreeId};
 use rpc::proto;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
-use std::{sync::Arc, time::Duration};
+use std::{path::Path, sync::Arc, time::Duration};
 use time::{OffsetDateTime, UtcOffset};
 use ui::{h_flex, prelude::*, v_flex, Avatar, Button, Icon, IconButton, IconName, Label, Tooltip};
 use util::{ResultExt, TryFutureExt};
@@ -405,6 +405,24 @@ impl NotificationPanel {
                     can_navigate: true,
                 })
             }
+            Notification::BufferCommentMention {
+                sender_id,
+                ref path,
+                ref body,
+                ..
+            } => {
+                let sender = user_store.get_cached_user(sender_id)?;
+                Some(NotificationPresenter {
+                    icon: "icons/conversations.svg",
+                    text: format!(
+                        "{} mentioned you in a comment on {path}:\n{body}",
+                        sender.github_login,
+                    ),
+                    needs_response: false,
+                    actor: Some(sender),
+                    can_navigate: true,
+                })
+            }
         }
     }
 
@@ -415,7 +433,8 @@ impl NotificationPanel {
         cx: &mut ViewContext<Self>,
     ) {
         let should_mark_as_read = match notification {
-            Notification::ContactRequestAccepted { .. } => true,
+            Notification::ContactRequestAccepted { .. }
+            | Notification::BufferCommentMention { .. } => true,
             Notification::ContactRequest { .. }
             | Notification::ChannelInvitation { .. }
             | Notification::ChannelMessageMention { .. } => false,
@@ -441,25 +460,51 @@ impl NotificationPanel {
     }
 
     fn did_click_notification(&mut self, notification: &Notification, cx: &mut ViewContext<Self>) {
-        if let Notification::ChannelMessageMention {
-            message_id,
-            channel_id,
-            ..
-        } = notification.clone()
-        {
-            if let Some(workspace) = self.workspace.upgrade() {
-                cx.window_context().defer(move |cx| {
-                    workspace.update(cx, |workspace, cx| {
-                        if let Some(panel) = workspace.focus_panel::<ChatPanel>(cx) {
-                            panel.update(cx, |panel, cx| {
-                                panel
-                                    .select_channel(ChannelId(channel_id), Some(message_id), cx)
-                                    .detach_and_log_err(cx);
-                            });
-                        }
+        match notification.clone() {
+            Notification::ChannelMessageMention {
+                message_id,
+                channel_id,
+                ..
+            } => {
+                if let Some(workspace) = self.workspace.upgrade() {
+                    cx.window_context().defer(move |cx| {
+                        workspace.update(cx, |workspace, cx| {
+                            if let Some(panel) = workspace.focus_panel::<ChatPanel>(cx) {
+                                panel.update(cx, |panel, cx| {
+                                    panel
+                                        .select_channel(ChannelId(channel_id), Some(message_id), cx)
+                                        .detach_and_log_err(cx);
+                                });
+                            }
+                        });
                     });
-                });
+                }
+            }
+            Notification::BufferCommentMention {
+                project_id,
+                worktree_id,
+                path,
+                ..
+            } => {
+                if let Some(workspace) = self.workspace.upgrade() {
+                    cx.window_context().defer(move |cx| {
+                        workspace.update(cx, |workspace, cx| {
+                            // The comment can only be shown if this workspace is in the project.
+                            if workspace.project().read(cx).remote_id() != Some(project_id) {
+                                return;
+                            }
+                            let project_path = ProjectPath {
+                                worktree_id: WorktreeId::from_proto(worktree_id),
+                                path: Path::new(&path).into(),
+                            };
+                            workspace
+                                .open_path(project_path, None, true, cx)
+                                .detach_and_log_err(cx);
+                        });
+                    });
+                }
             }
+            _ => {}
         }
     }
 
@@ -1257,5 +1257,9 @@ mod tests {
         fn load(&self, _: &AppContext) -> Task<Result<String>> {
             unimplemented!()
         }
+
+        fn load_bytes(&self, _: &AppContext) -> Task<Result<Vec<u8>>> {
+            unimplemented!()
+        }
     }
 }
@@ -5,7 +5,7 @@ mod toolbar_controls;
 #[cfg(test)]
 mod diagnostics_tests;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use collections::{BTreeSet, HashSet};
 use editor::{
     diagnostic_block_renderer,
@@ -30,6 +30,7 @@ use language::{
 use lsp::LanguageServerId;
 use project::{DiagnosticSummary, Project, ProjectPath};
 use project_diagnostics_settings::ProjectDiagnosticsSettings;
+use rpc::proto::{self, update_view, PeerId};
 use settings::Settings;
 use std::{
     any::{Any, TypeId},
@@ -42,14 +43,17 @@ pub use toolbar_controls::ToolbarControls;
 use ui::{h_flex, prelude::*, Icon, IconName, Label};
 use util::ResultExt;
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, ItemHandle, TabContentParams},
-    ItemNavHistory, Pane, ToolbarItemLocation, Workspace,
+    item::{
+        BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent, ItemHandle, TabContentParams,
+    },
+    ItemNavHistory, Pane, ToolbarItemLocation, ViewId, Workspace,
 };
 
 actions!(diagnostics, [Deploy, ToggleWarnings]);
 
 pub fn init(cx: &mut AppContext) {
     ProjectDiagnosticsSettings::register(cx);
+    workspace::register_followable_item::<ProjectDiagnosticsEditor>(cx);
     cx.observe_new_views(ProjectDiagnosticsEditor::register)
         .detach();
 }
@@ -66,6 +70,10 @@ struct ProjectDiagnosticsEditor {
     include_warnings: bool,
     context: u32,
     update_paths_tx: UnboundedSender<(ProjectPath, Option<LanguageServerId>)>,
+    remote_id: Option<ViewId>,
+    /// The latest selections and scroll position received from the leader being followed,
+    /// re-applied whenever this view's own excerpts change.
+    leader_state: Option<update_view::Editor>,
     _update_excerpts_task: Task<Result<()>>,
     _subscription: Subscription,
 }
@@ -197,6 +205,8 @@ impl ProjectDiagnosticsEditor {
             paths_to_update: Default::default(),
             include_warnings: ProjectDiagnosticsSettings::get_global(cx).include_warnings,
             update_paths_tx: update_excerpts_tx,
+            remote_id: None,
+            leader_state: None,
             _update_excerpts_task: cx.spawn(move |this, mut cx| async move {
                 while let Some((path, language_server_id)) = update_excerpts_rx.next().await {
                     if let Some(buffer) = project_handle
@@ -524,6 +534,11 @@ impl ProjectDiagnosticsEditor {
         }
 
         self.editor.update(cx, |editor, cx| {
+            // Selections of followers mirror the leader's, and are restored below.
+            if editor.leader_peer_id().is_some() {
+                return None;
+            }
+
             let groups;
             let mut selections;
             let new_excerpt_ids_by_selection_id;
@@ -576,6 +591,7 @@ impl ProjectDiagnosticsEditor {
             });
             Some(())
         });
+        self.apply_leader_state(cx);
 
         if self.path_states.is_empty() {
             if self.editor.focus_handle(cx).is_focused(cx) {
@@ -592,6 +608,44 @@ impl ProjectDiagnosticsEditor {
         cx.notify();
     }
 
+    /// Re-applies the leader's selections and scroll position, whose anchors may only resolve
+    /// once this view has built excerpts for the buffers they refer to.
+    fn apply_leader_state(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(state) = self.leader_state.clone() else {
+            return;
+        };
+        let messages = [
+            update_view::Editor {
+                selections: state.selections,
+                pending_selection: state.pending_selection,
+                ..Default::default()
+            },
+            update_view::Editor {
+                scroll_top_anchor: state.scroll_top_anchor,
+                scroll_x: state.scroll_x,
+                scroll_y: state.scroll_y,
+                ..Default::default()
+            },
+        ];
+        let project = self.project.clone();
+        let editor = self.editor.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            for message in messages {
+                editor
+                    .update(&mut cx, |editor, cx| {
+                        editor.apply_update_proto(
+                            &project,
+                            update_view::Variant::Editor(message),
+                            cx,
+                        )
+                    })?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     #[cfg(test)]
     fn check_invariants(&self, cx: &mut ViewContext<Self>) {
         let mut excerpts = Vec::new();
@@ -687,6 +741,10 @@ impl Item for ProjectDiagnosticsEditor {
         Some("project diagnostics")
     }
 
+    fn display_name(&self) -> Option<&'static str> {
+        Some("project diagnostics")
+    }
+
     fn for_each_project_item(
         &self,
         cx: &AppContext,
@@ -795,6 +853,135 @@ impl Item for ProjectDiagnosticsEditor {
     }
 }
 
+impl FollowableItem for ProjectDiagnosticsEditor {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        let Some(proto::view::Variant::Editor(mut editor)) =
+            self.editor.read(cx).to_state_proto(cx)
+        else {
+            return None;
+        };
+        // Followers build their own excerpts from the project's diagnostics.
+        editor.excerpts.clear();
+
+        Some(proto::view::Variant::ProjectDiagnostics(
+            proto::view::ProjectDiagnostics {
+                include_warnings: self.include_warnings,
+                editor: Some(editor),
+            },
+        ))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<Result<View<Self>>>> {
+        let Some(proto::view::Variant::ProjectDiagnostics(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::ProjectDiagnostics(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let existing = pane.read(cx).items_of_type::<Self>().next();
+        let diagnostics = existing.unwrap_or_else(|| {
+            let project = workspace.read(cx).project().clone();
+            let workspace = workspace.downgrade();
+            cx.new_view(|cx| Self::new(project, workspace, cx))
+        });
+        diagnostics.update(cx, |this, cx| {
+            this.remote_id = Some(remote_id);
+            if this.include_warnings != state.include_warnings {
+                this.include_warnings = state.include_warnings;
+                this.enqueue_update_all_excerpts(cx);
+            }
+            if let Some(editor) = state.editor {
+                this.leader_state = Some(update_view::Editor {
+                    selections: editor.selections,
+                    pending_selection: editor.pending_selection,
+                    scroll_top_anchor: editor.scroll_top_anchor,
+                    scroll_x: editor.scroll_x,
+                    scroll_y: editor.scroll_y,
+                    ..Default::default()
+                });
+                this.apply_leader_state(cx);
+            }
+        });
+
+        Some(Task::ready(Ok(diagnostics)))
+    }
+
+    fn to_follow_event(event: &EditorEvent) -> Option<FollowEvent> {
+        Editor::to_follow_event(event)
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &EditorEvent,
+        update: &mut Option<update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        match event {
+            EditorEvent::ExcerptsAdded { .. } | EditorEvent::ExcerptsRemoved { .. } => false,
+            _ => self
+                .editor
+                .read(cx)
+                .add_event_to_update_proto(event, update, cx),
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        project: &Model<Project>,
+        message: update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let update_view::Variant::Editor(message) = message else {
+            return Task::ready(Err(anyhow!(
+                "received a non-editor update for project diagnostics"
+            )));
+        };
+
+        let leader_state = self.leader_state.get_or_insert_with(Default::default);
+        if !message.selections.is_empty() || message.pending_selection.is_some() {
+            leader_state.selections.clone_from(&message.selections);
+            leader_state
+                .pending_selection
+                .clone_from(&message.pending_selection);
+        }
+        if message.scroll_top_anchor.is_some() {
+            leader_state
+                .scroll_top_anchor
+                .clone_from(&message.scroll_top_anchor);
+            leader_state.scroll_x = message.scroll_x;
+            leader_state.scroll_y = message.scroll_y;
+        }
+
+        self.editor.update(cx, |editor, cx| {
+            editor.apply_update_proto(project, update_view::Variant::Editor(message), cx)
+        })
+    }
+
+    fn is_project_item(&self, _cx: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, leader_peer_id: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        if leader_peer_id.is_none() {
+            self.leader_state = None;
+        }
+        self.editor.update(cx, |editor, cx| {
+            editor.set_leader_peer_id(leader_peer_id, cx)
+        })
+    }
+}
+
 const DIAGNOSTIC_HEADER: &'static str = "diagnostic header";
 
 fn diagnostic_header_renderer(diagnostic: Diagnostic) -> RenderBlock {
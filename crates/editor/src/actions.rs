@@ -166,6 +166,7 @@ gpui::actions!(
         AcceptPartialCopilotSuggestion,
         AcceptInlineCompletion,
         AcceptPartialInlineCompletion,
+        AddComment,
         AddSelectionAbove,
         AddSelectionBelow,
         Backspace,
@@ -208,13 +209,17 @@ gpui::actions!(
         Fold,
         FoldSelectedRanges,
         Format,
+        GoToDeclaration,
+        GoToDeclarationSplit,
         GoToDefinition,
         GoToDefinitionSplit,
         GoToDiagnostic,
         GoToHunk,
+        GoToNextBookmark,
         GoToImplementation,
         GoToImplementationSplit,
         GoToPrevDiagnostic,
+        GoToPrevBookmark,
         GoToPrevHunk,
         GoToTypeDefinition,
         GoToTypeDefinitionSplit,
@@ -292,12 +297,14 @@ gpui::actions!(
         SplitSelectionIntoLines,
         Tab,
         TabPrev,
+        ToggleBookmark,
         ToggleGitBlame,
         ToggleGitBlameInline,
         ToggleSelectionMenu,
         ToggleHunkDiff,
         ToggleInlayHints,
         ToggleLineNumbers,
+        ToggleReadOnly,
         ToggleIndentGuides,
         ToggleSoftWrap,
         ToggleTabBar,
@@ -0,0 +1,257 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use gpui::{AppContext, Model, Task, ViewContext};
+use language::{Buffer, Point};
+use multi_buffer::{Anchor, MultiBufferRow, ToPoint};
+use project::{bookmark_store::BookmarkId, File, ProjectPath};
+use ui::{prelude::*, IconButton, IconButtonShape, IconName, IconSize};
+use workspace::Workspace;
+
+use crate::{
+    display_map::ToDisplayPoint, scroll::Autoscroll, DisplayRow, Editor, EditorSnapshot,
+    GoToNextBookmark, GoToPrevBookmark, ToggleBookmark,
+};
+
+impl Editor {
+    pub fn toggle_bookmark(&mut self, _: &ToggleBookmark, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let mut rows = Vec::<(Model<Buffer>, u32)>::new();
+        for selection in self.selections.all::<Point>(cx) {
+            let Some((buffer, anchor)) = self
+                .buffer
+                .read(cx)
+                .text_anchor_for_position(selection.head(), cx)
+            else {
+                continue;
+            };
+            let row = buffer.read(cx).summary_for_anchor::<Point>(&anchor).row;
+            if !rows.contains(&(buffer.clone(), row)) {
+                rows.push((buffer, row));
+            }
+        }
+
+        let store = project.read(cx).bookmark_store().clone();
+        store.update(cx, |store, cx| {
+            for (buffer, row) in rows {
+                store.toggle_bookmark(&buffer, row, cx);
+            }
+        });
+    }
+
+    pub fn go_to_next_bookmark(&mut self, _: &GoToNextBookmark, cx: &mut ViewContext<Self>) {
+        self.go_to_adjacent_bookmark(false, cx);
+    }
+
+    pub fn go_to_prev_bookmark(&mut self, _: &GoToPrevBookmark, cx: &mut ViewContext<Self>) {
+        self.go_to_adjacent_bookmark(true, cx);
+    }
+
+    fn go_to_adjacent_bookmark(&mut self, reverse: bool, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let head = self.selections.newest::<Point>(cx).head();
+        let (abs_path, row) = match self.buffer.read(cx).text_anchor_for_position(head, cx) {
+            Some((buffer, anchor)) => {
+                let buffer = buffer.read(cx);
+                let abs_path = File::from_dyn(buffer.file()).map(|file| file.abs_path(cx));
+                let row = buffer.summary_for_anchor::<Point>(&anchor).row;
+                (abs_path.unwrap_or_default(), row)
+            }
+            None => (PathBuf::new(), 0),
+        };
+
+        let store = project.read(cx).bookmark_store().read(cx);
+        let bookmark_id = if reverse {
+            store.prev_bookmark(&abs_path, row, cx)
+        } else {
+            store.next_bookmark(&abs_path, row, cx)
+        };
+        if let Some(bookmark_id) = bookmark_id {
+            self.go_to_bookmark(bookmark_id, cx);
+        }
+    }
+
+    /// Moves the cursor to a bookmark, opening its file if it isn't shown in this editor.
+    pub fn go_to_bookmark(&mut self, bookmark_id: BookmarkId, cx: &mut ViewContext<Self>) {
+        if let Some(position) = self.bookmark_position(bookmark_id, cx) {
+            self.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                selections.select_anchor_ranges([position..position])
+            });
+        } else if let Some(workspace) = self.workspace() {
+            cx.defer(move |_, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    open_bookmark(workspace, bookmark_id, cx).detach_and_log_err(cx);
+                });
+            });
+        }
+    }
+
+    /// Places the bookmark for a vim mark at the given position. Returns false if this
+    /// editor doesn't belong to a project.
+    pub fn set_bookmark_mark(
+        &mut self,
+        mark: char,
+        position: Anchor,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        let Some(project) = self.project.clone() else {
+            return false;
+        };
+        let Some((buffer, anchor)) = self.buffer.read(cx).text_anchor_for_position(position, cx)
+        else {
+            return false;
+        };
+        let point = buffer.read(cx).summary_for_anchor::<Point>(&anchor);
+        let store = project.read(cx).bookmark_store().clone();
+        store
+            .update(cx, |store, cx| store.set_mark(mark, &buffer, point, cx))
+            .is_some()
+    }
+
+    pub fn bookmark_for_mark(&self, mark: char, cx: &AppContext) -> Option<BookmarkId> {
+        let project = self.project.as_ref()?;
+        let store = project.read(cx).bookmark_store().read(cx);
+        store.bookmark_for_mark(mark).map(|bookmark| bookmark.id)
+    }
+
+    /// Returns the position of a bookmark in this editor, if its buffer is shown here.
+    pub fn bookmark_position(&self, bookmark_id: BookmarkId, cx: &AppContext) -> Option<Anchor> {
+        let project = self.project.as_ref()?;
+        let store = project.read(cx).bookmark_store().read(cx);
+        let bookmark = store.bookmark(bookmark_id)?;
+        let buffer = store.buffer(bookmark)?;
+        let point = store.point(bookmark, cx);
+        let text_anchor = buffer.read(cx).anchor_before(point);
+        let (excerpt_id, _) = self
+            .buffer
+            .read(cx)
+            .excerpts_for_buffer(&buffer, cx)
+            .into_iter()
+            .find(|(_, range)| {
+                let buffer = buffer.read(cx);
+                range.context.start.cmp(&text_anchor, buffer).is_le()
+                    && range.context.end.cmp(&text_anchor, buffer).is_ge()
+            })?;
+        self.buffer
+            .read(cx)
+            .snapshot(cx)
+            .anchor_in_excerpt(excerpt_id, text_anchor)
+    }
+
+    /// Returns the display rows of the bookmarks shown in this editor.
+    pub(crate) fn bookmark_rows(
+        &self,
+        snapshot: &EditorSnapshot,
+        cx: &AppContext,
+    ) -> Vec<(BookmarkId, DisplayRow)> {
+        let Some(project) = self.project.as_ref() else {
+            return Vec::new();
+        };
+        let store = project.read(cx).bookmark_store().read(cx);
+        if store.is_empty() {
+            return Vec::new();
+        }
+
+        let mut rows = Vec::new();
+        for (excerpt_id, buffer, range) in snapshot.buffer_snapshot.excerpts() {
+            for (bookmark_id, anchor) in store.anchors_for_buffer(buffer.remote_id()) {
+                if range.context.start.cmp(&anchor, buffer).is_gt()
+                    || range.context.end.cmp(&anchor, buffer).is_lt()
+                {
+                    continue;
+                }
+                let Some(anchor) = snapshot
+                    .buffer_snapshot
+                    .anchor_in_excerpt(excerpt_id, anchor)
+                else {
+                    continue;
+                };
+                let point = anchor.to_point(&snapshot.buffer_snapshot);
+                if snapshot.is_line_folded(MultiBufferRow(point.row)) {
+                    continue;
+                }
+                rows.push((bookmark_id, point.to_display_point(snapshot).row()));
+            }
+        }
+        rows
+    }
+
+    pub(crate) fn render_bookmark_indicator(
+        &self,
+        bookmark_id: BookmarkId,
+        row: DisplayRow,
+        cx: &mut ViewContext<Self>,
+    ) -> IconButton {
+        IconButton::new(("bookmark_indicator", row.0 as usize), IconName::Bookmark)
+            .shape(IconButtonShape::Square)
+            .icon_size(IconSize::XSmall)
+            .icon_color(Color::Accent)
+            .on_click(cx.listener(move |editor, _, cx| {
+                if let Some(project) = editor.project.as_ref() {
+                    project
+                        .read(cx)
+                        .bookmark_store()
+                        .clone()
+                        .update(cx, |store, cx| {
+                            store.remove_bookmark(bookmark_id, cx);
+                        });
+                }
+            }))
+    }
+}
+
+/// Opens the file containing a bookmark and moves the cursor to it.
+pub fn open_bookmark(
+    workspace: &mut Workspace,
+    bookmark_id: BookmarkId,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let project = workspace.project().clone();
+    let store = project.read(cx).bookmark_store().clone();
+    let Some((abs_path, buffer)) = store
+        .read(cx)
+        .bookmark(bookmark_id)
+        .map(|bookmark| (bookmark.abs_path.clone(), store.read(cx).buffer(bookmark)))
+    else {
+        return Task::ready(Err(anyhow!("bookmark no longer exists")));
+    };
+
+    let buffer = match buffer {
+        Some(buffer) => Task::ready(Ok(buffer)),
+        None => {
+            let project_path = project.read(cx).worktrees().find_map(|worktree| {
+                let worktree = worktree.read(cx);
+                let path = abs_path.strip_prefix(worktree.abs_path()).ok()?;
+                Some(ProjectPath {
+                    worktree_id: worktree.id(),
+                    path: path.into(),
+                })
+            });
+            project.update(cx, |project, cx| match project_path {
+                Some(project_path) => project.open_buffer(project_path, cx),
+                None => project.open_local_buffer(&abs_path, cx),
+            })
+        }
+    };
+
+    cx.spawn(|workspace, mut cx| async move {
+        let buffer = buffer.await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let store = store.read(cx);
+            let point = store
+                .bookmark(bookmark_id)
+                .map_or(Point::zero(), |bookmark| store.point(bookmark, cx));
+            let pane = workspace.active_pane().clone();
+            let editor = workspace.open_project_item::<Editor>(pane, buffer, cx);
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                    selections.select_ranges([point..point])
+                });
+            });
+        })
+    })
+}
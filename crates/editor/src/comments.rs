@@ -0,0 +1,493 @@
+use collections::{HashMap, HashSet};
+use gpui::{
+    AppContext, FocusHandle, Model, Render, Subscription, Task, View, ViewContext, WeakView,
+};
+use language::Buffer;
+use multi_buffer::Anchor;
+use project::{
+    buffer_comment_store::{BufferCommentStore, BufferCommentThread, BufferCommentThreadId},
+    File, Project, ProjectPath,
+};
+use time::OffsetDateTime;
+use ui::{prelude::*, Avatar, Button, Tooltip};
+use util::ResultExt;
+
+use crate::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    AddComment, Editor, EditorMode,
+};
+
+/// A comment thread, or the draft of a new one, shown below a line of the editor.
+pub(crate) struct CommentBlock {
+    block_id: BlockId,
+    position: Anchor,
+    height: u8,
+}
+
+#[derive(Default)]
+pub(crate) struct CommentBlocks {
+    threads: HashMap<BufferCommentThreadId, CommentBlock>,
+    draft: Option<CommentBlock>,
+}
+
+impl Editor {
+    pub fn add_comment(&mut self, _: &AddComment, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let store = project.read(cx).buffer_comment_store().clone();
+        if !store.read(cx).is_available() || self.mode != EditorMode::Full {
+            return;
+        }
+
+        // Comments go below the line containing the cursor.
+        let position = self.selections.newest_anchor().head();
+        let Some((buffer, text_position)) =
+            self.buffer.read(cx).text_anchor_for_position(position, cx)
+        else {
+            return;
+        };
+
+        if let Some(draft) = self.comment_blocks.draft.take() {
+            self.remove_blocks(HashSet::from_iter([draft.block_id]), None, cx);
+        }
+        let editor = cx.view().downgrade();
+        let view = cx.new_view(|cx| {
+            CommentThreadView::new(
+                editor,
+                store,
+                CommentThreadState::Draft {
+                    buffer,
+                    position: text_position,
+                },
+                cx,
+            )
+        });
+        let height = 2;
+        let block_id = self.insert_comment_block(position, height, view.clone(), cx);
+        let focus_handle = view.read(cx).focus_handle(cx);
+        cx.focus(&focus_handle);
+        self.comment_blocks.draft = Some(CommentBlock {
+            block_id,
+            position,
+            height,
+        });
+    }
+
+    pub(crate) fn subscribe_to_buffer_comments(
+        project: &Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Subscription {
+        let store = project.read(cx).buffer_comment_store().clone();
+        cx.observe(&store, |editor, _, cx| editor.refresh_comment_blocks(cx))
+    }
+
+    /// Shows the comment threads left on the buffers of this editor, removing the blocks of
+    /// the threads that moved or are no longer shown.
+    pub(crate) fn refresh_comment_blocks(&mut self, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let store = project.read(cx).buffer_comment_store().clone();
+        let positions = self.comment_thread_positions(&store, cx);
+
+        let mut blocks_to_remove = HashSet::default();
+        self.comment_blocks.threads.retain(|thread_id, block| {
+            let keep = positions
+                .get(thread_id)
+                .map_or(false, |(position, height)| {
+                    *position == block.position && *height == block.height
+                });
+            if !keep {
+                blocks_to_remove.insert(block.block_id);
+            }
+            keep
+        });
+        if !store.read(cx).is_available() {
+            if let Some(draft) = self.comment_blocks.draft.take() {
+                blocks_to_remove.insert(draft.block_id);
+            }
+        }
+        if !blocks_to_remove.is_empty() {
+            self.remove_blocks(blocks_to_remove, None, cx);
+        }
+
+        let editor = cx.view().downgrade();
+        for (thread_id, (position, height)) in positions {
+            if self.comment_blocks.threads.contains_key(&thread_id) {
+                continue;
+            }
+            let view = cx.new_view(|cx| {
+                CommentThreadView::new(
+                    editor.clone(),
+                    store.clone(),
+                    CommentThreadState::Thread(thread_id),
+                    cx,
+                )
+            });
+            let block_id = self.insert_comment_block(position, height, view, cx);
+            self.comment_blocks.threads.insert(
+                thread_id,
+                CommentBlock {
+                    block_id,
+                    position,
+                    height,
+                },
+            );
+        }
+        cx.notify();
+    }
+
+    /// Returns where the comment threads shown in this editor go, and how tall they are.
+    fn comment_thread_positions(
+        &self,
+        store: &Model<BufferCommentStore>,
+        cx: &AppContext,
+    ) -> HashMap<BufferCommentThreadId, (Anchor, u8)> {
+        let store = store.read(cx);
+        let mut positions = HashMap::default();
+        if !store.is_available() {
+            return positions;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        for (excerpt_id, buffer, range) in snapshot.excerpts() {
+            let Some(file) = File::from_dyn(buffer.file()) else {
+                continue;
+            };
+            let project_path = ProjectPath {
+                worktree_id: file.worktree_id(cx),
+                path: file.path.clone(),
+            };
+            for thread in store.threads_for_path(&project_path) {
+                let anchor = store.anchor_in_buffer(thread, buffer);
+                if range.context.start.cmp(&anchor, buffer).is_gt()
+                    || range.context.end.cmp(&anchor, buffer).is_lt()
+                {
+                    continue;
+                }
+                let Some(anchor) = snapshot.anchor_in_excerpt(excerpt_id, anchor) else {
+                    continue;
+                };
+                positions.insert(thread.id, (anchor, comment_thread_height(thread)));
+            }
+        }
+        positions
+    }
+
+    fn insert_comment_block(
+        &mut self,
+        position: Anchor,
+        height: u8,
+        view: View<CommentThreadView>,
+        cx: &mut ViewContext<Self>,
+    ) -> BlockId {
+        self.insert_blocks(
+            [BlockProperties {
+                position,
+                height,
+                style: BlockStyle::Sticky,
+                render: Box::new(move |cx: &mut BlockContext| {
+                    div()
+                        .pl(cx.gutter_dimensions.full_width())
+                        .w_full()
+                        .child(view.clone())
+                        .into_any_element()
+                }),
+                disposition: BlockDisposition::Below,
+            }],
+            None,
+            cx,
+        )[0]
+    }
+
+    fn dismiss_comment_draft(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(draft) = self.comment_blocks.draft.take() {
+            self.remove_blocks(HashSet::from_iter([draft.block_id]), None, cx);
+            cx.focus(&self.focus_handle);
+        }
+    }
+
+    /// Returns the ids of the project's collaborators, who can be mentioned in comments.
+    fn collaborator_user_ids(&self, cx: &AppContext) -> Vec<u64> {
+        self.project.as_ref().map_or(Vec::new(), |project| {
+            project
+                .read(cx)
+                .collaborators()
+                .values()
+                .map(|collaborator| collaborator.user_id)
+                .collect()
+        })
+    }
+}
+
+/// The height of a thread's block, in lines: one line for the author of each comment
+/// followed by its lines, and one line to reply unless the thread is resolved.
+fn comment_thread_height(thread: &BufferCommentThread) -> u8 {
+    if thread.resolved {
+        return 1;
+    }
+    let comment_lines = thread
+        .comments
+        .iter()
+        .map(|comment| 1 + comment.body.lines().count().max(1))
+        .sum::<usize>();
+    (comment_lines + 1).min(u8::MAX as usize) as u8
+}
+
+enum CommentThreadState {
+    Draft {
+        buffer: Model<Buffer>,
+        position: text::Anchor,
+    },
+    Thread(BufferCommentThreadId),
+}
+
+pub(crate) struct CommentThreadView {
+    editor: WeakView<Editor>,
+    store: Model<BufferCommentStore>,
+    state: CommentThreadState,
+    reply_editor: View<Editor>,
+    pending_request: Option<Task<()>>,
+    _subscription: Subscription,
+}
+
+impl CommentThreadView {
+    fn new(
+        editor: WeakView<Editor>,
+        store: Model<BufferCommentStore>,
+        state: CommentThreadState,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let reply_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text(
+                match state {
+                    CommentThreadState::Draft { .. } => "Add a comment…",
+                    CommentThreadState::Thread(_) => "Reply…",
+                },
+                cx,
+            );
+            editor
+        });
+        let subscription = cx.observe(&store, |_, _, cx| cx.notify());
+        Self {
+            editor,
+            store,
+            state,
+            reply_editor,
+            pending_request: None,
+            _subscription: subscription,
+        }
+    }
+
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.reply_editor.focus_handle(cx)
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        self.submit(cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        if let CommentThreadState::Draft { .. } = self.state {
+            self.editor
+                .update(cx, |editor, cx| editor.dismiss_comment_draft(cx))
+                .ok();
+        }
+    }
+
+    fn submit(&mut self, cx: &mut ViewContext<Self>) {
+        let body = self.reply_editor.read(cx).text(cx).trim().to_string();
+        if body.is_empty() || self.pending_request.is_some() {
+            return;
+        }
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let collaborator_user_ids = editor.read(cx).collaborator_user_ids(cx);
+        let mentioned_user_ids =
+            self.store
+                .read(cx)
+                .mentioned_user_ids(&body, collaborator_user_ids, cx);
+
+        let request = self.store.update(cx, |store, cx| match &self.state {
+            CommentThreadState::Draft { buffer, position } => {
+                store.create_thread(buffer, *position, body, mentioned_user_ids, cx)
+            }
+            CommentThreadState::Thread(thread_id) => {
+                store.reply(*thread_id, body, mentioned_user_ids, cx)
+            }
+        });
+        self.pending_request = Some(cx.spawn(|this, mut cx| async move {
+            let result = request.await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_request = None;
+                if result.log_err().is_some() {
+                    this.reply_editor
+                        .update(cx, |editor, cx| editor.set_text("", cx));
+                    if let CommentThreadState::Draft { .. } = this.state {
+                        this.editor
+                            .update(cx, |editor, cx| editor.dismiss_comment_draft(cx))
+                            .ok();
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn set_resolved(&mut self, resolved: bool, cx: &mut ViewContext<Self>) {
+        let CommentThreadState::Thread(thread_id) = self.state else {
+            return;
+        };
+        self.store
+            .update(cx, |store, cx| store.set_resolved(thread_id, resolved, cx))
+            .detach_and_log_err(cx);
+    }
+
+    fn render_thread(
+        &self,
+        thread: &BufferCommentThread,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let user_store = self.store.read(cx).user_store().read(cx);
+        let now = OffsetDateTime::now_utc();
+        let timezone = cx.local_timezone();
+        let comments = thread
+            .comments
+            .iter()
+            .map(|comment| {
+                let sender = user_store.get_cached_user(comment.sender_id);
+                let timestamp = OffsetDateTime::from_unix_timestamp(comment.timestamp as i64)
+                    .map(|timestamp| {
+                        time_format::format_localized_timestamp(
+                            timestamp,
+                            now,
+                            timezone,
+                            time_format::TimestampFormat::Relative,
+                        )
+                    })
+                    .unwrap_or_default();
+                v_flex()
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .children(
+                                sender
+                                    .as_ref()
+                                    .map(|sender| Avatar::new(sender.avatar_uri.clone())),
+                            )
+                            .child(
+                                Label::new(sender.map_or("Unknown user".to_string(), |sender| {
+                                    sender.github_login.clone()
+                                }))
+                                .size(LabelSize::Small),
+                            )
+                            .child(
+                                Label::new(timestamp)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                    .children(
+                        comment
+                            .body
+                            .lines()
+                            .map(|line| Label::new(line.to_string()).size(LabelSize::Small)),
+                    )
+            })
+            .collect::<Vec<_>>();
+
+        v_flex().children(comments).child(
+            h_flex()
+                .gap_1()
+                .child(div().flex_1().child(self.reply_editor.clone()))
+                .child(
+                    Button::new("reply", "Reply")
+                        .label_size(LabelSize::Small)
+                        .disabled(self.pending_request.is_some())
+                        .on_click(cx.listener(|this, _, cx| this.submit(cx))),
+                )
+                .child(
+                    Button::new("resolve", "Resolve")
+                        .label_size(LabelSize::Small)
+                        .tooltip(|cx| Tooltip::text("Mark this thread as resolved", cx))
+                        .on_click(cx.listener(|this, _, cx| this.set_resolved(true, cx))),
+                ),
+        )
+    }
+
+    fn render_resolved_thread(
+        &self,
+        thread: &BufferCommentThread,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let count = thread.comments.len();
+        h_flex()
+            .gap_1()
+            .child(
+                Label::new(format!(
+                    "Resolved thread with {count} comment{}",
+                    if count == 1 { "" } else { "s" }
+                ))
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .child(
+                Button::new("reopen", "Reopen")
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, cx| this.set_resolved(false, cx))),
+            )
+    }
+
+    fn render_draft(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .gap_1()
+            .child(div().flex_1().child(self.reply_editor.clone()))
+            .child(
+                Button::new("comment", "Comment")
+                    .label_size(LabelSize::Small)
+                    .disabled(self.pending_request.is_some())
+                    .on_click(cx.listener(|this, _, cx| this.submit(cx))),
+            )
+            .child(
+                Button::new("cancel", "Cancel")
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, cx| this.cancel(&menu::Cancel, cx))),
+            )
+    }
+}
+
+impl Render for CommentThreadView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = match self.state {
+            CommentThreadState::Draft { .. } => self.render_draft(cx).into_any_element(),
+            CommentThreadState::Thread(thread_id) => {
+                match self.store.read(cx).thread(thread_id).cloned() {
+                    Some(thread) if thread.resolved => {
+                        self.render_resolved_thread(&thread, cx).into_any_element()
+                    }
+                    Some(thread) => self.render_thread(&thread, cx).into_any_element(),
+                    None => div().into_any_element(),
+                }
+            }
+        };
+
+        v_flex()
+            .key_context("CommentThread")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .px_2()
+            .border_l_2()
+            .border_color(cx.theme().colors().border_focused)
+            .bg(cx.theme().colors().editor_subheader_background)
+            .child(content)
+    }
+}
@@ -15,6 +15,8 @@
 pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
+mod bookmarks;
+mod comments;
 mod debounced_delay;
 pub mod display_map;
 mod editor_settings;
@@ -35,6 +37,7 @@ mod persistence;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod sticky_scroll;
 pub mod tasks;
 
 #[cfg(test)]
@@ -47,6 +50,7 @@ pub(crate) use actions::*;
 use aho_corasick::AhoCorasick;
 use anyhow::{anyhow, Context as _, Result};
 use blink_manager::BlinkManager;
+pub use bookmarks::open_bookmark;
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
 use collections::{BTreeMap, Bound, HashMap, HashSet, VecDeque};
@@ -527,6 +531,7 @@ pub struct Editor {
     show_inline_completions: bool,
     inlay_hint_cache: InlayHintCache,
     expanded_hunks: ExpandedHunks,
+    comment_blocks: comments::CommentBlocks,
     next_inlay_id: usize,
     _subscriptions: Vec<Subscription>,
     pixel_position_of_newest_cursor: Option<gpui::Point<Pixels>>,
@@ -1549,6 +1554,7 @@ pub(crate) struct NavigationData {
 
 enum GotoDefinitionKind {
     Symbol,
+    Declaration,
     Type,
     Implementation,
 }
@@ -1753,6 +1759,9 @@ impl Editor {
                 project_subscriptions.push(cx.observe(&task_inventory, |editor, _, cx| {
                     editor.tasks_update_task = Some(editor.refresh_runnables(cx));
                 }));
+                let bookmark_store = project.read(cx).bookmark_store().clone();
+                project_subscriptions.push(cx.observe(&bookmark_store, |_, _, cx| cx.notify()));
+                project_subscriptions.push(Self::subscribe_to_buffer_comments(project, cx));
             }
         }
 
@@ -1847,6 +1856,7 @@ impl Editor {
             active_inline_completion: None,
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
             expanded_hunks: ExpandedHunks::default(),
+            comment_blocks: Default::default(),
             gutter_hovered: false,
             pixel_position_of_newest_cursor: None,
             last_bounds: None,
@@ -1895,6 +1905,7 @@ impl Editor {
         };
         this.tasks_update_task = Some(this.refresh_runnables(cx));
         this._subscriptions.extend(project_subscriptions);
+        this.refresh_comment_blocks(cx);
 
         this.end_selection(cx);
         this.scroll_manager.show_scrollbar(cx);
@@ -2212,6 +2223,14 @@ impl Editor {
         self.read_only = read_only;
     }
 
+    pub fn toggle_read_only(&mut self, _: &ToggleReadOnly, cx: &mut ViewContext<Self>) {
+        if self.buffer.read(cx).read_only() {
+            return;
+        }
+        self.read_only = !self.read_only;
+        cx.notify();
+    }
+
     pub fn set_use_autoclose(&mut self, autoclose: bool) {
         self.use_autoclose = autoclose;
     }
@@ -3897,7 +3916,7 @@ impl Editor {
                 let worktree_entry = buffer_worktree
                     .read(cx)
                     .entry_for_id(buffer_file.project_entry_id(cx)?)?;
-                if worktree_entry.is_ignored {
+                if worktree_entry.is_ignored || buffer.is_large_file() {
                     return None;
                 }
 
@@ -8866,6 +8885,22 @@ impl Editor {
         self.go_to_definition_of_kind(GotoDefinitionKind::Symbol, false, cx)
     }
 
+    pub fn go_to_declaration(
+        &mut self,
+        _: &GoToDeclaration,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<bool>> {
+        self.go_to_definition_of_kind(GotoDefinitionKind::Declaration, false, cx)
+    }
+
+    pub fn go_to_declaration_split(
+        &mut self,
+        _: &GoToDeclarationSplit,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<bool>> {
+        self.go_to_definition_of_kind(GotoDefinitionKind::Declaration, true, cx)
+    }
+
     pub fn go_to_implementation(
         &mut self,
         _: &GoToImplementation,
@@ -8926,6 +8961,7 @@ impl Editor {
         let project = workspace.read(cx).project().clone();
         let definitions = project.update(cx, |project, cx| match kind {
             GotoDefinitionKind::Symbol => project.definition(&buffer, head, cx),
+            GotoDefinitionKind::Declaration => project.declaration(&buffer, head, cx),
             GotoDefinitionKind::Type => project.type_definition(&buffer, head, cx),
             GotoDefinitionKind::Implementation => project.implementation(&buffer, head, cx),
         });
@@ -9067,6 +9103,7 @@ impl Editor {
                     .update(&mut cx, |editor, cx| {
                         let tab_kind = match kind {
                             Some(GotoDefinitionKind::Implementation) => "Implementations",
+                            Some(GotoDefinitionKind::Declaration) => "Declarations",
                             _ => "Definitions",
                         };
                         let title = definitions
@@ -11124,6 +11161,7 @@ impl Editor {
                 excerpts,
             } => {
                 self.tasks_update_task = Some(self.refresh_runnables(cx));
+                self.refresh_comment_blocks(cx);
                 cx.emit(EditorEvent::ExcerptsAdded {
                     buffer: buffer.clone(),
                     predecessor: *predecessor,
@@ -11133,6 +11171,7 @@ impl Editor {
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                self.refresh_comment_blocks(cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
             multi_buffer::Event::ExcerptsEdited { ids } => {
@@ -11950,13 +11989,14 @@ impl EditorSnapshot {
             .unwrap_or(gutter_settings.code_actions);
 
         let show_runnables = self.show_runnables.unwrap_or(gutter_settings.runnables);
+        let show_bookmarks = gutter_settings.bookmarks && self.project.is_some();
 
         let git_blame_entries_width = self
             .render_git_blame_gutter
             .then_some(em_width * GIT_BLAME_GUTTER_WIDTH_CHARS);
 
         let mut left_padding = git_blame_entries_width.unwrap_or(Pixels::ZERO);
-        left_padding += if show_code_actions || show_runnables {
+        left_padding += if show_code_actions || show_runnables || show_bookmarks {
             em_width * 3.0
         } else if show_git_gutter && show_line_numbers {
             em_width * 2.0
@@ -12372,6 +12372,241 @@ async fn test_active_indent_guide_non_matching_indent(cx: &mut gpui::TestAppCont
     );
 }
 
+#[gpui::test]
+async fn test_sticky_headers(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_outline_query(
+            r#"
+                (mod_item "mod" @context name: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+        )
+        .unwrap(),
+    );
+    let text = r#"
+        mod outer {
+            fn a() {
+                let x = 1;
+                let y = 2;
+                let z = 3;
+            }
+            fn b() {}
+        }
+    "#
+    .unindent();
+
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    editor.update(cx, |editor, cx| {
+        let snapshot = editor.snapshot(cx);
+        let headers = |scroll_top: f32, max_depth: usize| {
+            sticky_scroll::sticky_headers(&snapshot.display_snapshot, scroll_top, max_depth, None)
+                .into_iter()
+                .map(|header| (header.item.text, header.display_row.0))
+                .collect::<Vec<_>>()
+        };
+
+        // Nothing is pinned while the scopes' first lines are visible.
+        assert_eq!(headers(0., 5), []);
+        // The rows hidden under the pinned headers determine which scopes are pinned.
+        assert_eq!(
+            headers(1., 5),
+            [("mod outer".to_string(), 0), ("fn a".to_string(), 1)]
+        );
+        assert_eq!(
+            headers(2., 5),
+            [("mod outer".to_string(), 0), ("fn a".to_string(), 1)]
+        );
+        assert_eq!(headers(2., 1), [("mod outer".to_string(), 0)]);
+        // A scope's header is dropped once the scope ends under it.
+        assert_eq!(headers(4., 5), [("mod outer".to_string(), 0)]);
+        assert_eq!(headers(7., 5), []);
+    });
+}
+
+#[gpui::test]
+async fn test_sticky_headers_in_multibuffer(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_outline_query(
+            r#"
+                (mod_item "mod" @context name: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+        )
+        .unwrap(),
+    );
+    let text = |name: &str| {
+        format!(
+            "mod {name} {{\n    fn {name}_fn() {{\n        let x = 1;\n        let y = 2;\n        let z = 3;\n    }}\n}}\n"
+        )
+    };
+    let buffer_1 =
+        cx.new_model(|cx| Buffer::local(text("one"), cx).with_language(language.clone(), cx));
+    let buffer_2 = cx.new_model(|cx| Buffer::local(text("two"), cx).with_language(language, cx));
+    let multibuffer = cx.new_model(|cx| {
+        let mut multibuffer = MultiBuffer::new(0, ReadWrite);
+        // The first excerpt starts inside the scopes of its buffer.
+        multibuffer.push_excerpts(
+            buffer_1.clone(),
+            [ExcerptRange {
+                context: Point::new(2, 0)..Point::new(5, 0),
+                primary: None,
+            }],
+            cx,
+        );
+        multibuffer.push_excerpts(
+            buffer_2.clone(),
+            [ExcerptRange {
+                context: Point::new(0, 0)..Point::new(7, 0),
+                primary: None,
+            }],
+            cx,
+        );
+        multibuffer
+    });
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(multibuffer, cx));
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    editor.update(cx, |editor, cx| {
+        let snapshot = editor.snapshot(cx);
+        let display_snapshot = &snapshot.display_snapshot;
+        let buffer_snapshot = &display_snapshot.buffer_snapshot;
+        let excerpts = buffer_snapshot
+            .excerpts()
+            .map(|(excerpt_id, _, range)| {
+                let row = |anchor| {
+                    buffer_snapshot
+                        .anchor_in_excerpt(excerpt_id, anchor)
+                        .unwrap()
+                        .to_display_point(display_snapshot)
+                        .row()
+                };
+                (excerpt_id, row(range.context.start)..row(range.context.end))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(excerpts.len(), 2);
+        let excerpt_at = |row: DisplayRow| {
+            let point = DisplayPoint::new(row, 0).to_point(display_snapshot);
+            buffer_snapshot.anchor_before(point).excerpt_id
+        };
+
+        // At every scroll position, the headers belong to the excerpt at the top of the
+        // editor and stay within its rows.
+        for row in 0..=display_snapshot.max_point().row().0 {
+            let top_excerpt = excerpt_at(DisplayRow(row));
+            for header in sticky_scroll::sticky_headers(display_snapshot, row as f32, 5, None) {
+                let (excerpt_id, rows) = excerpts
+                    .iter()
+                    .find(|(excerpt_id, _)| *excerpt_id == header.item.range.start.excerpt_id)
+                    .unwrap();
+                assert_eq!(
+                    *excerpt_id, top_excerpt,
+                    "header {:?} at row {row}",
+                    header.item.text
+                );
+                assert_eq!(header.item.range.end.excerpt_id, *excerpt_id);
+                let end_row = header
+                    .item
+                    .range
+                    .end
+                    .to_display_point(display_snapshot)
+                    .row();
+                assert!(
+                    rows.start <= header.display_row && end_row <= rows.end,
+                    "header {:?} at row {row} is outside of rows {rows:?}",
+                    header.item.text
+                );
+            }
+        }
+
+        let headers = |scroll_top: DisplayRow| {
+            sticky_scroll::sticky_headers(display_snapshot, scroll_top.0 as f32, 5, None)
+                .into_iter()
+                .map(|header| (header.item.text, header.display_row))
+                .collect::<Vec<_>>()
+        };
+        // Scopes that start before their excerpt are pinned at its first row.
+        let first_excerpt_start = excerpts[0].1.start;
+        assert_eq!(
+            headers(DisplayRow(first_excerpt_start.0 + 1)).first(),
+            Some(&("mod one".to_string(), first_excerpt_start))
+        );
+        let second_excerpt_start = excerpts[1].1.start;
+        assert_eq!(headers(second_excerpt_start), []);
+        assert_eq!(
+            headers(DisplayRow(second_excerpt_start.0 + 2)),
+            [
+                ("mod two".to_string(), second_excerpt_start),
+                (
+                    "fn two_fn".to_string(),
+                    DisplayRow(second_excerpt_start.0 + 1)
+                )
+            ]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_go_to_declaration(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            declaration_provider: Some(lsp::DeclarationCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state(indoc! {"
+        mod a { pub fn f(); }
+        fn main() { a::ˇf(); }
+    "});
+    let target_range = cx.lsp_range(indoc! {"
+        mod a { pub fn «f»(); }
+        fn main() { a::f(); }
+    "});
+    let mut requests =
+        cx.handle_request::<lsp::request::GotoDeclaration, _, _>(move |url, _, _| async move {
+            Ok(Some(lsp::GotoDeclarationResponse::Scalar(lsp::Location {
+                uri: url,
+                range: target_range,
+            })))
+        });
+
+    let navigated = cx
+        .update_editor(|editor, cx| editor.go_to_declaration(&GoToDeclaration, cx))
+        .await
+        .unwrap();
+    requests.next().await;
+    cx.run_until_parked();
+    assert!(navigated);
+    cx.assert_editor_state(indoc! {"
+        mod a { pub fn «fˇ»(); }
+        fn main() { a::f(); }
+    "});
+}
+
 #[gpui::test]
 fn test_crease_insertion_and_rendering(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -18,14 +18,14 @@ use crate::{
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MouseContextMenu},
     scroll::scroll_amount::ScrollAmount,
-    CodeActionsMenu, CursorShape, DisplayPoint, DisplayRow, DocumentHighlightRead,
+    sticky_scroll, CodeActionsMenu, CursorShape, DisplayPoint, DisplayRow, DocumentHighlightRead,
     DocumentHighlightWrite, Editor, EditorMode, EditorSettings, EditorSnapshot, EditorStyle,
     ExpandExcerpts, GutterDimensions, HalfPageDown, HalfPageUp, HoveredCursor, HunkToExpand,
     LineDown, LineUp, OpenExcerpts, PageDown, PageUp, Point, RowExt, RowRangeExt, SelectPhase,
     Selection, SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
 };
 use client::ParticipantIndex;
-use collections::{BTreeMap, HashMap};
+use collections::{BTreeMap, HashMap, HashSet};
 use git::{blame::BlameEntry, diff::DiffHunkStatus, Oid};
 use gpui::{
     anchored, deferred, div, fill, outline, point, px, quad, relative, size, svg,
@@ -34,8 +34,8 @@ use gpui::{
     FontId, GlobalElementId, Hitbox, Hsla, InteractiveElement, IntoElement, Length,
     ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad,
     ParentElement, Pixels, ScrollDelta, ScrollWheelEvent, ShapedLine, SharedString, Size,
-    StatefulInteractiveElement, Style, Styled, TextRun, TextStyle, TextStyleRefinement, View,
-    ViewContext, WeakView, WindowContext,
+    StatefulInteractiveElement, Style, Styled, StyledText, TextRun, TextStyle, TextStyleRefinement,
+    View, ViewContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
 use language::language_settings::{
@@ -289,12 +289,22 @@ impl EditorElement {
         register_action(view, cx, Editor::go_to_prev_diagnostic);
         register_action(view, cx, Editor::go_to_hunk);
         register_action(view, cx, Editor::go_to_prev_hunk);
+        register_action(view, cx, Editor::toggle_bookmark);
+        register_action(view, cx, Editor::go_to_next_bookmark);
+        register_action(view, cx, Editor::go_to_prev_bookmark);
+        register_action(view, cx, Editor::add_comment);
         register_action(view, cx, |editor, a, cx| {
             editor.go_to_definition(a, cx).detach_and_log_err(cx);
         });
         register_action(view, cx, |editor, a, cx| {
             editor.go_to_definition_split(a, cx).detach_and_log_err(cx);
         });
+        register_action(view, cx, |editor, a, cx| {
+            editor.go_to_declaration(a, cx).detach_and_log_err(cx);
+        });
+        register_action(view, cx, |editor, a, cx| {
+            editor.go_to_declaration_split(a, cx).detach_and_log_err(cx);
+        });
         register_action(view, cx, |editor, a, cx| {
             editor.go_to_implementation(a, cx).detach_and_log_err(cx);
         });
@@ -324,6 +334,7 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_soft_wrap);
         register_action(view, cx, Editor::toggle_tab_bar);
         register_action(view, cx, Editor::toggle_line_numbers);
+        register_action(view, cx, Editor::toggle_read_only);
         register_action(view, cx, Editor::toggle_indent_guides);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, hover_popover::hover);
@@ -1135,6 +1146,218 @@ impl EditorElement {
         })
     }
 
+    fn minimap_width(&self, snapshot: &EditorSnapshot, cx: &WindowContext) -> Pixels {
+        let minimap_settings = EditorSettings::get_global(cx).minimap;
+        if minimap_settings.enabled && snapshot.mode == EditorMode::Full {
+            px(minimap_settings.width.max(0.))
+        } else {
+            Pixels::ZERO
+        }
+    }
+
+    /// Lays out the minimap, only computing the text and markers of the rows that fit in
+    /// it so that large buffers don't cost more than small ones.
+    fn layout_minimap(
+        &self,
+        snapshot: &EditorSnapshot,
+        bounds: Bounds<Pixels>,
+        scroll_position: gpui::Point<f32>,
+        rows_per_page: f32,
+        max_scroll_top: f32,
+        cx: &mut WindowContext,
+    ) -> Option<MinimapLayout> {
+        let width = self.minimap_width(snapshot, cx);
+        if width <= MinimapLayout::MARKER_WIDTH {
+            return None;
+        }
+        let minimap_settings = EditorSettings::get_global(cx).minimap;
+
+        let minimap_bounds = Bounds::from_corners(
+            point(self.scrollbar_left(&bounds) - width, bounds.origin.y),
+            point(self.scrollbar_left(&bounds), bounds.lower_left().y),
+        );
+        let max_row = snapshot.max_point().row();
+        let total_rows = max_row.next_row().as_f32();
+        let rows_that_fit = bounds.size.height / MinimapLayout::LINE_HEIGHT;
+        let overflows = total_rows > rows_that_fit;
+        let first_row = if overflows && max_scroll_top > 0. {
+            (scroll_position.y / max_scroll_top).clamp(0., 1.) * (total_rows - rows_that_fit)
+        } else {
+            0.
+        };
+
+        let mut layout = MinimapLayout {
+            hitbox: cx.insert_hitbox(minimap_bounds, false),
+            first_row,
+            visible_row_range: scroll_position.y..scroll_position.y + rows_per_page,
+            max_scroll_top,
+            overflows,
+            text_quads: Arc::default(),
+            marker_quads: Arc::default(),
+        };
+        let max_columns =
+            ((width - MinimapLayout::MARKER_WIDTH) / MinimapLayout::COLUMN_WIDTH).floor() as u32;
+        let start_row = DisplayRow(first_row.floor() as u32);
+        let end_row =
+            DisplayRow(((first_row + rows_that_fit).ceil() as u32).min(max_row.next_row().0));
+
+        let mut text_quads = Vec::new();
+        let mut marker_quads = Vec::new();
+        let mut row = start_row.0;
+        let mut column = 0;
+        let mut run: Option<(u32, u32, Hsla)> = None;
+        let mut flush_run = |run: &mut Option<(u32, u32, Hsla)>, row: u32| {
+            if let Some((start_column, end_column, color)) = run.take() {
+                text_quads.push(fill(
+                    Bounds::from_corners(
+                        point(
+                            layout.x_for_column(start_column),
+                            layout.y_for_row(row as f32) + px(0.5),
+                        ),
+                        point(
+                            layout.x_for_column(end_column),
+                            layout.y_for_row(row as f32 + 1.) - px(0.5),
+                        ),
+                    ),
+                    color,
+                ));
+            }
+        };
+        for chunk in snapshot.highlighted_chunks(start_row..end_row, true, &self.style) {
+            let mut color = chunk
+                .style
+                .and_then(|style| style.color)
+                .unwrap_or(self.style.text.color);
+            color.fade_out(0.4);
+            for character in chunk.text.chars() {
+                if character == '\n' {
+                    flush_run(&mut run, row);
+                    row += 1;
+                    column = 0;
+                    continue;
+                }
+                if column < max_columns && !character.is_whitespace() {
+                    match run.as_mut() {
+                        Some((_, end_column, run_color))
+                            if *end_column == column && *run_color == color =>
+                        {
+                            *end_column += 1;
+                        }
+                        _ => {
+                            flush_run(&mut run, row);
+                            run = Some((column, column + 1, color));
+                        }
+                    }
+                } else {
+                    flush_run(&mut run, row);
+                }
+                column += 1;
+            }
+        }
+        flush_run(&mut run, row);
+
+        let start_point = DisplayPoint::new(start_row, 0).to_point(snapshot);
+        let end_point = DisplayPoint::new(end_row, 0).to_point(snapshot);
+        let theme = cx.theme().clone();
+        let row_bounds = |start: DisplayPoint, end: DisplayPoint, layout: &MinimapLayout| {
+            Bounds::from_corners(
+                point(
+                    layout.x_for_column(0),
+                    layout.y_for_row(start.row().as_f32()),
+                ),
+                point(
+                    layout.hitbox.right(),
+                    layout.y_for_row(end.row().as_f32() + 1.),
+                ),
+            )
+        };
+
+        if minimap_settings.search_results {
+            let mut color = theme.status().info;
+            color.fade_out(0.5);
+            let editor = self.editor.read(cx);
+            let search_range = snapshot.buffer_snapshot.anchor_before(start_point)
+                ..snapshot.buffer_snapshot.anchor_after(end_point);
+            for range in editor.background_highlight_row_ranges::<BufferSearchHighlights>(
+                search_range,
+                &snapshot.display_snapshot,
+                MinimapLayout::MAX_SEARCH_RESULTS,
+            ) {
+                let bounds = row_bounds(*range.start(), *range.end(), &layout);
+                marker_quads.push(fill(bounds, color));
+            }
+        }
+
+        if minimap_settings.diagnostics {
+            let diagnostics = snapshot
+                .buffer_snapshot
+                .diagnostics_in_range::<_, Point>(start_point..end_point, false)
+                // Paint the most severe diagnostics last, so they stay visible.
+                .sorted_by_key(|diagnostic| cmp::Reverse(diagnostic.diagnostic.severity));
+            for diagnostic in diagnostics {
+                let start = diagnostic.range.start.to_display_point(snapshot);
+                let end = diagnostic.range.end.to_display_point(snapshot);
+                let color = match diagnostic.diagnostic.severity {
+                    DiagnosticSeverity::ERROR => theme.status().error,
+                    DiagnosticSeverity::WARNING => theme.status().warning,
+                    DiagnosticSeverity::INFORMATION => theme.status().info,
+                    _ => theme.status().hint,
+                };
+                let end_column = if start.row() == end.row() {
+                    end.column().max(start.column() + 1)
+                } else {
+                    max_columns
+                };
+                let top = layout.y_for_row(start.row().as_f32());
+                marker_quads.push(fill(
+                    Bounds::from_corners(
+                        point(layout.x_for_column(start.column()), top),
+                        point(
+                            layout.x_for_column(end_column.min(max_columns)),
+                            top + MinimapLayout::LINE_HEIGHT,
+                        ),
+                    ),
+                    color,
+                ));
+            }
+        }
+
+        if minimap_settings.git_diff {
+            for hunk in snapshot.buffer_snapshot.git_diff_hunks_in_range(
+                MultiBufferRow(start_point.row)..MultiBufferRow(end_point.row + 1),
+            ) {
+                let start_row = MultiBufferPoint::new(hunk.associated_range.start.0, 0)
+                    .to_display_point(snapshot)
+                    .row();
+                let mut end_row = MultiBufferPoint::new(hunk.associated_range.end.0, 0)
+                    .to_display_point(snapshot)
+                    .row();
+                if end_row != start_row {
+                    end_row.0 -= 1;
+                }
+                let color = match hunk_status(&hunk) {
+                    DiffHunkStatus::Added => theme.status().created,
+                    DiffHunkStatus::Modified => theme.status().modified,
+                    DiffHunkStatus::Removed => theme.status().deleted,
+                };
+                marker_quads.push(fill(
+                    Bounds::from_corners(
+                        point(layout.hitbox.left(), layout.y_for_row(start_row.as_f32())),
+                        point(
+                            layout.hitbox.left() + MinimapLayout::MARKER_WIDTH,
+                            layout.y_for_row(end_row.as_f32() + 1.),
+                        ),
+                    ),
+                    color,
+                ));
+            }
+        }
+
+        layout.text_quads = text_quads.into();
+        layout.marker_quads = marker_quads.into();
+        Some(layout)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn prepaint_gutter_fold_toggles(
         &self,
@@ -1607,6 +1830,52 @@ impl EditorElement {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_bookmark_indicators(
+        &self,
+        line_height: Pixels,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        snapshot: &EditorSnapshot,
+        rows: Range<DisplayRow>,
+        show_runnables: bool,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            // Run indicators take precedence over bookmarks on the same row.
+            let task_rows = if show_runnables {
+                editor
+                    .tasks
+                    .values()
+                    .map(|tasks| {
+                        let point = tasks.offset.0.to_point(&snapshot.buffer_snapshot);
+                        point.to_display_point(snapshot).row()
+                    })
+                    .collect::<HashSet<_>>()
+            } else {
+                HashSet::default()
+            };
+            editor
+                .bookmark_rows(snapshot, cx)
+                .into_iter()
+                .filter(|(_, row)| rows.contains(row) && !task_rows.contains(row))
+                .map(|(bookmark_id, row)| {
+                    let button = editor.render_bookmark_indicator(bookmark_id, row, cx);
+                    prepaint_gutter_button(
+                        button,
+                        row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        cx,
+                    )
+                })
+                .collect_vec()
+        })
+    }
+
     fn layout_code_actions_indicator(
         &self,
         line_height: Pixels,
@@ -2406,6 +2675,85 @@ impl EditorElement {
         blocks
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_sticky_headers(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<f32>,
+        text_hitbox: &Hitbox,
+        content_origin: gpui::Point<Pixels>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_height: Pixels,
+        cx: &mut WindowContext,
+    ) -> Option<AnyElement> {
+        let settings = EditorSettings::get_global(cx).sticky_scroll;
+        if !settings.enabled || snapshot.mode != EditorMode::Full {
+            return None;
+        }
+
+        let headers = sticky_scroll::sticky_headers(
+            &snapshot.display_snapshot,
+            scroll_position.y,
+            settings.max_depth,
+            Some(&self.style.syntax),
+        );
+        if headers.is_empty() {
+            return None;
+        }
+
+        let hover_background = cx.theme().colors().editor_active_line_background;
+        let rows = headers
+            .into_iter()
+            .enumerate()
+            .map(|(ix, header)| {
+                let column = header
+                    .item
+                    .range
+                    .start
+                    .to_display_point(&snapshot.display_snapshot)
+                    .column();
+                let indent = (content_origin.x - text_hitbox.origin.x - scroll_pixel_position.x
+                    + self.column_pixels(column as usize, cx))
+                .max(Pixels::ZERO);
+                let text = StyledText::new(header.item.text)
+                    .with_highlights(&self.style.text, header.item.highlight_ranges);
+                let position = header.item.range.start;
+                let editor = self.editor.clone();
+                h_flex()
+                    .id(("sticky-header", ix))
+                    .h(line_height)
+                    .w_full()
+                    .pl(indent)
+                    .cursor_pointer()
+                    .hover(move |style| style.bg(hover_background))
+                    .on_mouse_down(MouseButton::Left, move |_, cx| {
+                        cx.stop_propagation();
+                        editor.update(cx, |editor, cx| {
+                            editor.go_to_sticky_header(position, ix, cx)
+                        });
+                    })
+                    .child(text)
+            })
+            .collect::<Vec<_>>();
+
+        let mut element = v_flex()
+            .w(text_hitbox.size.width)
+            .bg(cx.theme().colors().editor_background)
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .children(rows)
+            .into_any_element();
+        element.prepaint_as_root(
+            text_hitbox.origin,
+            size(
+                AvailableSpace::Definite(text_hitbox.size.width),
+                AvailableSpace::MinContent,
+            ),
+            cx,
+        );
+        Some(element)
+    }
+
     fn layout_blocks(
         &self,
         blocks: &mut Vec<BlockLayout>,
@@ -3009,6 +3357,10 @@ impl EditorElement {
                 }
             });
 
+            for bookmark_indicator in layout.bookmark_indicators.iter_mut() {
+                bookmark_indicator.paint(cx);
+            }
+
             for test_indicators in layout.test_indicators.iter_mut() {
                 test_indicators.paint(cx);
             }
@@ -3217,6 +3569,88 @@ impl EditorElement {
         }
     }
 
+    fn paint_minimap(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        let Some(minimap_layout) = layout.minimap_layout.clone() else {
+            return;
+        };
+
+        cx.paint_layer(minimap_layout.hitbox.bounds, |cx| {
+            cx.paint_quad(fill(
+                minimap_layout.hitbox.bounds,
+                cx.theme().colors().editor_background,
+            ));
+            for quad in minimap_layout
+                .text_quads
+                .iter()
+                .chain(minimap_layout.marker_quads.iter())
+            {
+                cx.paint_quad(quad.clone());
+            }
+            cx.paint_quad(fill(
+                minimap_layout.slider_bounds(),
+                cx.theme().colors().scrollbar_thumb_background,
+            ));
+        });
+
+        cx.set_cursor_style(CursorStyle::Arrow, &minimap_layout.hitbox);
+
+        if self.editor.read(cx).scroll_manager.is_dragging_minimap() {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                let minimap_layout = minimap_layout.clone();
+                move |event: &MouseMoveEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        if event.pressed_button == Some(MouseButton::Left) {
+                            let mut position = editor.scroll_position(cx);
+                            position.y = minimap_layout.scroll_top_for_y(event.position.y);
+                            editor.set_scroll_position(position, cx);
+                            cx.stop_propagation();
+                        } else {
+                            editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                        }
+                    })
+                }
+            });
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        } else {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                move |event: &MouseDownEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture
+                        || event.button != MouseButton::Left
+                        || !minimap_layout.hitbox.is_hovered(cx)
+                    {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(true, cx);
+                        let mut position = editor.scroll_position(cx);
+                        position.y = minimap_layout.scroll_top_for_y(event.position.y);
+                        editor.set_scroll_position(position, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
     fn paint_scrollbar(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
         let Some(scrollbar_layout) = layout.scrollbar_layout.as_ref() else {
             return;
@@ -3603,6 +4037,14 @@ impl EditorElement {
         }
     }
 
+    fn paint_sticky_headers(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        if let Some(mut sticky_headers) = layout.sticky_headers.take() {
+            cx.paint_layer(layout.text_hitbox.bounds, |cx| {
+                sticky_headers.paint(cx);
+            })
+        }
+    }
+
     fn paint_mouse_context_menu(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
         if let Some(mouse_context_menu) = layout.mouse_context_menu.as_mut() {
             mouse_context_menu.paint(cx);
@@ -4619,7 +5061,7 @@ impl Element for EditorElement {
                     let text_width = bounds.size.width - gutter_dimensions.width;
 
                     let right_margin = if snapshot.mode == EditorMode::Full {
-                        EditorElement::SCROLLBAR_WIDTH
+                        EditorElement::SCROLLBAR_WIDTH + self.minimap_width(&snapshot, cx)
                     } else {
                         px(0.)
                     };
@@ -4965,6 +5407,18 @@ impl Element for EditorElement {
                         );
                     });
 
+                    let sticky_headers = cx.with_element_namespace("sticky_headers", |cx| {
+                        self.layout_sticky_headers(
+                            &snapshot,
+                            scroll_position,
+                            &text_hitbox,
+                            content_origin,
+                            scroll_pixel_position,
+                            line_height,
+                            cx,
+                        )
+                    });
+
                     let cursors = self.collect_cursors(&snapshot, cx);
                     let visible_row_range = start_row..end_row;
                     let non_visible_cursors = cursors
@@ -4995,6 +5449,15 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let minimap_layout = self.layout_minimap(
+                        &snapshot,
+                        bounds,
+                        scroll_position,
+                        height_in_lines,
+                        max_scroll_top,
+                        cx,
+                    );
+
                     let gutter_settings = EditorSettings::get_global(cx).gutter;
 
                     let mut _context_menu_visible = false;
@@ -5058,6 +5521,21 @@ impl Element for EditorElement {
                         vec![]
                     };
 
+                    let bookmark_indicators = if gutter_settings.bookmarks {
+                        self.layout_bookmark_indicators(
+                            line_height,
+                            scroll_pixel_position,
+                            &gutter_dimensions,
+                            &gutter_hitbox,
+                            &snapshot,
+                            start_row..end_row,
+                            gutter_settings.runnables,
+                            cx,
+                        )
+                    } else {
+                        vec![]
+                    };
+
                     if !cx.has_active_drag() {
                         self.layout_hover_popovers(
                             &snapshot,
@@ -5140,6 +5618,7 @@ impl Element for EditorElement {
                         gutter_dimensions,
                         content_origin,
                         scrollbar_layout,
+                        minimap_layout,
                         active_rows,
                         highlighted_rows,
                         highlighted_ranges,
@@ -5151,11 +5630,13 @@ impl Element for EditorElement {
                         blamed_display_rows,
                         inline_blame,
                         blocks,
+                        sticky_headers,
                         cursors,
                         visible_cursors,
                         selections,
                         mouse_context_menu,
                         test_indicators,
+                        bookmark_indicators,
                         code_actions_indicator,
                         gutter_fold_toggles,
                         crease_trailers,
@@ -5239,11 +5720,16 @@ impl Element for EditorElement {
                         });
                     }
 
+                    cx.with_element_namespace("sticky_headers", |cx| {
+                        self.paint_sticky_headers(layout, cx);
+                    });
+
                     if layout.gutter_hitbox.size.width > Pixels::ZERO {
                         self.paint_gutter_highlights(layout, cx);
                         self.paint_gutter_indicators(layout, cx);
                     }
 
+                    self.paint_minimap(layout, cx);
                     self.paint_scrollbar(layout, cx);
                     self.paint_mouse_context_menu(layout, cx);
                 });
@@ -5268,6 +5754,7 @@ pub struct EditorLayout {
     gutter_dimensions: GutterDimensions,
     content_origin: gpui::Point<Pixels>,
     scrollbar_layout: Option<ScrollbarLayout>,
+    minimap_layout: Option<MinimapLayout>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     indent_guides: Option<Vec<IndentGuideLayout>>,
@@ -5280,6 +5767,7 @@ pub struct EditorLayout {
     blamed_display_rows: Option<Vec<AnyElement>>,
     inline_blame: Option<AnyElement>,
     blocks: Vec<BlockLayout>,
+    sticky_headers: Option<AnyElement>,
     highlighted_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
     highlighted_gutter_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
     redacted_ranges: Vec<Range<DisplayPoint>>,
@@ -5288,6 +5776,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     code_actions_indicator: Option<AnyElement>,
     test_indicators: Vec<AnyElement>,
+    bookmark_indicators: Vec<AnyElement>,
     gutter_fold_toggles: Vec<Option<AnyElement>>,
     crease_trailers: Vec<Option<CreaseTrailerLayout>>,
     mouse_context_menu: Option<AnyElement>,
@@ -5413,6 +5902,64 @@ impl ScrollbarLayout {
     }
 }
 
+/// A scaled-down view of the buffer's text, drawn as one block per run of characters
+/// with the same color.
+///
+/// When the buffer has more rows than fit, the minimap scrolls proportionally to the
+/// editor, so that both reach their last row together.
+#[derive(Clone)]
+struct MinimapLayout {
+    hitbox: Hitbox,
+    /// The display row shown at the top of the minimap.
+    first_row: f32,
+    visible_row_range: Range<f32>,
+    max_scroll_top: f32,
+    overflows: bool,
+    text_quads: Arc<[PaintQuad]>,
+    marker_quads: Arc<[PaintQuad]>,
+}
+
+impl MinimapLayout {
+    const LINE_HEIGHT: Pixels = px(3.0);
+    const COLUMN_WIDTH: Pixels = px(1.5);
+    const MARKER_WIDTH: Pixels = px(3.0);
+    const MAX_SEARCH_RESULTS: usize = 50_000;
+
+    fn y_for_row(&self, row: f32) -> Pixels {
+        self.hitbox.top() + (row - self.first_row) * Self::LINE_HEIGHT
+    }
+
+    fn x_for_column(&self, column: u32) -> Pixels {
+        self.hitbox.left() + Self::MARKER_WIDTH + column as f32 * Self::COLUMN_WIDTH
+    }
+
+    fn slider_height(&self) -> Pixels {
+        (self.visible_row_range.end - self.visible_row_range.start) * Self::LINE_HEIGHT
+    }
+
+    fn slider_bounds(&self) -> Bounds<Pixels> {
+        let top = self.y_for_row(self.visible_row_range.start);
+        Bounds::from_corners(
+            point(self.hitbox.left(), top),
+            point(self.hitbox.right(), top + self.slider_height()),
+        )
+    }
+
+    /// Returns the scroll position that centers the editor on the given minimap position.
+    fn scroll_top_for_y(&self, y: Pixels) -> f32 {
+        let rows_per_page = self.visible_row_range.end - self.visible_row_range.start;
+        let scroll_top = if self.overflows {
+            let slider_height = self.slider_height();
+            let track_height = (self.hitbox.size.height - slider_height).max(px(1.));
+            ((y - self.hitbox.top() - slider_height / 2.) / track_height).clamp(0., 1.)
+                * self.max_scroll_top
+        } else {
+            (y - self.hitbox.top()) / Self::LINE_HEIGHT - rows_per_page / 2.
+        };
+        scroll_top.clamp(0., self.max_scroll_top)
+    }
+}
+
 struct CreaseTrailerLayout {
     element: AnyElement,
     bounds: Bounds<Pixels>,
@@ -5790,12 +6337,14 @@ mod tests {
     use super::*;
     use crate::{
         display_map::{BlockDisposition, BlockProperties},
+        editor_settings::MinimapContent,
         editor_tests::{init_test, update_test_language_settings},
         Editor, MultiBuffer,
     };
     use gpui::{TestAppContext, VisualTestContext};
     use language::language_settings;
     use log::info;
+    use settings::SettingsStore;
     use std::num::NonZeroU32;
     use ui::Context;
     use util::test::sample_text;
@@ -6073,6 +6622,49 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_minimap_layout(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(MinimapContent {
+                        enabled: Some(true),
+                        width: None,
+                        git_diff: None,
+                        search_results: None,
+                        diagnostics: None,
+                    });
+                });
+            })
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple("fn main() {}\n    let x = 1;\n}", cx);
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style)
+        });
+        let minimap = state.minimap_layout.unwrap();
+        // Each run of non-whitespace characters is drawn as a single block.
+        assert_eq!(minimap.text_quads.len(), 8);
+        assert_eq!(minimap.first_row, 0.);
+        assert!(!minimap.overflows);
+        assert_eq!(
+            minimap.text_quads[3].bounds.origin.x,
+            minimap.x_for_column(4)
+        );
+        assert_eq!(
+            minimap.text_quads[3].bounds.origin.y,
+            minimap.y_for_row(1.) + px(0.5)
+        );
+    }
+
     #[gpui::test]
     fn test_all_invisibles_drawing(cx: &mut TestAppContext) {
         const TAB_SIZE: u32 = 4;
@@ -308,6 +308,7 @@ impl FollowableItem for Editor {
                 }
                 _ => false,
             },
+            _ => false,
         }
     }
 
@@ -317,7 +318,9 @@ impl FollowableItem for Editor {
         message: update_view::Variant,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<()>> {
-        let update_view::Variant::Editor(message) = message;
+        let update_view::Variant::Editor(message) = message else {
+            return Task::ready(Err(anyhow!("received a non-editor update for an editor")));
+        };
         let project = project.clone();
         cx.spawn(|this, mut cx| async move {
             update_editor_from_message(this, project, message, &mut cx).await
@@ -526,14 +529,47 @@ fn deserialize_selection(
 }
 
 fn deserialize_anchor(buffer: &MultiBufferSnapshot, anchor: proto::EditorAnchor) -> Option<Anchor> {
-    let excerpt_id = ExcerptId::from_proto(anchor.excerpt_id);
+    let mut excerpt_id = ExcerptId::from_proto(anchor.excerpt_id);
+    let text_anchor = language::proto::deserialize_anchor(anchor.anchor?)?;
+
+    // Followers of views that build their own excerpts (e.g. project diagnostics) don't share the
+    // leader's excerpt ids, so fall back to an excerpt of the same buffer containing the anchor.
+    if let Some(text_buffer_id) = text_anchor.buffer_id {
+        if buffer.buffer_id_for_excerpt(excerpt_id) != Some(text_buffer_id) {
+            excerpt_id = excerpt_containing_text_anchor(buffer, text_buffer_id, &text_anchor)?;
+        }
+    }
+
     Some(Anchor {
         excerpt_id,
-        text_anchor: language::proto::deserialize_anchor(anchor.anchor?)?,
+        text_anchor,
         buffer_id: buffer.buffer_id_for_excerpt(excerpt_id),
     })
 }
 
+fn excerpt_containing_text_anchor(
+    buffer: &MultiBufferSnapshot,
+    buffer_id: BufferId,
+    text_anchor: &language::Anchor,
+) -> Option<ExcerptId> {
+    let mut first_excerpt_id = None;
+    for (excerpt_id, excerpt_buffer, range) in buffer.excerpts() {
+        if excerpt_buffer.remote_id() != buffer_id {
+            continue;
+        }
+        if !excerpt_buffer.can_resolve(text_anchor) {
+            return Some(excerpt_id);
+        }
+        first_excerpt_id.get_or_insert(excerpt_id);
+        if range.context.start.cmp(text_anchor, excerpt_buffer).is_le()
+            && range.context.end.cmp(text_anchor, excerpt_buffer).is_ge()
+        {
+            return Some(excerpt_id);
+        }
+    }
+    first_excerpt_id
+}
+
 impl Item for Editor {
     type Event = EditorEvent;
 
@@ -983,7 +1019,13 @@ impl ProjectItem for Editor {
         buffer: Model<Buffer>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
-        Self::for_buffer(buffer, Some(project), cx)
+        // Large files are opened read-only, to avoid accidentally editing logs and dumps.
+        let is_large_file = buffer.read(cx).is_large_file();
+        let mut editor = Self::for_buffer(buffer, Some(project), cx);
+        if is_large_file {
+            editor.set_read_only(true);
+        }
+        editor
     }
 }
 
@@ -1,9 +1,9 @@
 use crate::{
-    Copy, CopyPermalinkToLine, Cut, DisplayPoint, Editor, EditorMode, FindAllReferences,
-    GoToDefinition, GoToImplementation, GoToTypeDefinition, Paste, Rename, RevealInFinder,
-    SelectMode, ToggleCodeActions,
+    AddComment, Copy, CopyPermalinkToLine, Cut, DisplayPoint, Editor, EditorMode,
+    FindAllReferences, GoToDeclaration, GoToDefinition, GoToImplementation, GoToTypeDefinition,
+    Paste, Rename, RevealInFinder, SelectMode, ToggleCodeActions,
 };
-use gpui::{DismissEvent, Pixels, Point, Subscription, View, ViewContext};
+use gpui::{prelude::FluentBuilder, DismissEvent, Pixels, Point, Subscription, View, ViewContext};
 use workspace::OpenInTerminal;
 
 pub struct MouseContextMenu {
@@ -72,10 +72,18 @@ pub fn deploy_context_menu(
         });
 
         let focus = cx.focused();
+        let can_comment = editor.project.as_ref().map_or(false, |project| {
+            project
+                .read(cx)
+                .buffer_comment_store()
+                .read(cx)
+                .is_available()
+        });
         ui::ContextMenu::build(cx, |menu, _cx| {
             let builder = menu
                 .action("Rename Symbol", Box::new(Rename))
                 .action("Go to Definition", Box::new(GoToDefinition))
+                .action("Go to Declaration", Box::new(GoToDeclaration))
                 .action("Go to Type Definition", Box::new(GoToTypeDefinition))
                 .action("Go to Implementation", Box::new(GoToImplementation))
                 .action("Find All References", Box::new(FindAllReferences))
@@ -92,7 +100,10 @@ pub fn deploy_context_menu(
                 .separator()
                 .action("Reveal in Finder", Box::new(RevealInFinder))
                 .action("Open in Terminal", Box::new(OpenInTerminal))
-                .action("Copy Permalink", Box::new(CopyPermalinkToLine));
+                .action("Copy Permalink", Box::new(CopyPermalinkToLine))
+                .when(can_comment, |menu| {
+                    menu.separator().action("Add Comment", Box::new(AddComment))
+                });
             match focus {
                 Some(focus) => builder.context(focus),
                 None => builder,
@@ -0,0 +1,99 @@
+use language::OutlineItem;
+use multi_buffer::{Anchor, ExcerptId};
+use theme::SyntaxTheme;
+use ui::ViewContext;
+
+use crate::{
+    display_map::ToDisplayPoint, scroll::Autoscroll, DisplayPoint, DisplayRow, DisplaySnapshot,
+    Editor,
+};
+
+/// A scope enclosing the topmost visible rows, whose header is pinned at the top of the
+/// editor while its own first line is scrolled out of view.
+#[derive(Clone, Debug)]
+pub struct StickyHeader {
+    pub item: OutlineItem<Anchor>,
+    /// The display row of the header's first line.
+    pub display_row: DisplayRow,
+}
+
+impl Editor {
+    /// Scrolls to the first line of a sticky header, leaving room above it for the
+    /// headers of the scopes enclosing it.
+    pub(crate) fn go_to_sticky_header(
+        &mut self,
+        position: Anchor,
+        enclosing_headers: usize,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.change_selections(
+            Some(Autoscroll::top_relative(enclosing_headers)),
+            cx,
+            |selections| selections.select_ranges([position..position]),
+        );
+    }
+}
+
+/// Returns the headers to pin at the top of the editor when it is scrolled to the given
+/// row, outermost first.
+///
+/// The pinned headers cover the rows underneath them, so the scopes are looked up at the
+/// first row that remains visible below the headers. A scope's header is dropped once
+/// the scope ends under it. In multibuffers, scopes are clipped to their excerpt, so
+/// each excerpt only shows the headers of its own content.
+pub(crate) fn sticky_headers(
+    snapshot: &DisplaySnapshot,
+    scroll_top: f32,
+    max_depth: usize,
+    theme: Option<&SyntaxTheme>,
+) -> Vec<StickyHeader> {
+    if max_depth == 0 {
+        return Vec::new();
+    }
+
+    let top_row = DisplayRow(scroll_top.max(0.).floor() as u32);
+    let mut scopes = enclosing_scopes(snapshot, top_row, max_depth, theme);
+    let covered_row = DisplayRow(top_row.0 + scopes.len() as u32);
+    // The headers always belong to the excerpt at the top of the editor, even when the
+    // rows they cover reach into the next one.
+    if covered_row <= snapshot.max_point().row()
+        && excerpt_at(snapshot, covered_row) == excerpt_at(snapshot, top_row)
+    {
+        scopes = enclosing_scopes(snapshot, covered_row, max_depth, theme);
+    }
+
+    let mut headers = Vec::new();
+    for header in scopes {
+        let end_row = header.item.range.end.to_display_point(snapshot).row();
+        if end_row.0 > top_row.0 + headers.len() as u32 {
+            headers.push(header);
+        }
+    }
+    headers
+}
+
+fn excerpt_at(snapshot: &DisplaySnapshot, row: DisplayRow) -> ExcerptId {
+    let point = DisplayPoint::new(row, 0).to_point(snapshot);
+    snapshot.buffer_snapshot.anchor_before(point).excerpt_id
+}
+
+/// Returns the scopes containing the given row whose first line is above it.
+fn enclosing_scopes(
+    snapshot: &DisplaySnapshot,
+    row: DisplayRow,
+    max_depth: usize,
+    theme: Option<&SyntaxTheme>,
+) -> Vec<StickyHeader> {
+    let point = DisplayPoint::new(row, 0).to_point(snapshot);
+    let Some((_, items)) = snapshot.buffer_snapshot.symbols_containing(point, theme) else {
+        return Vec::new();
+    };
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let display_row = item.range.start.to_display_point(snapshot).row();
+            (display_row < row).then_some(StickyHeader { item, display_row })
+        })
+        .take(max_depth)
+        .collect()
+}
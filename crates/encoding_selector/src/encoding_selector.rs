@@ -0,0 +1,315 @@
+mod active_buffer_encoding;
+
+pub use active_buffer_encoding::ActiveBufferEncoding;
+use editor::Editor;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    ParentElement, Render, Styled, View, ViewContext, VisualContext, WeakView,
+};
+use language::{Buffer, Encoding};
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(
+    encoding_selector,
+    [Toggle, ReopenWithEncoding, SaveWithEncoding]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(EncodingSelector::register).detach();
+}
+
+/// What to do with the buffer once an encoding has been picked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EncodingAction {
+    /// Decode the file on disk again, discarding the current text.
+    Reopen,
+    /// Write the current text to disk in the new encoding.
+    Save,
+}
+
+impl EncodingAction {
+    fn label(&self) -> &'static str {
+        match self {
+            EncodingAction::Reopen => "Reopen with Encoding",
+            EncodingAction::Save => "Save with Encoding",
+        }
+    }
+}
+
+pub struct EncodingSelector {
+    picker: View<Picker<EncodingSelectorDelegate>>,
+}
+
+impl EncodingSelector {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(move |workspace, _: &Toggle, cx| {
+            Self::toggle(workspace, None, cx);
+        });
+        workspace.register_action(move |workspace, _: &ReopenWithEncoding, cx| {
+            Self::toggle(workspace, Some(EncodingAction::Reopen), cx);
+        });
+        workspace.register_action(move |workspace, _: &SaveWithEncoding, cx| {
+            Self::toggle(workspace, Some(EncodingAction::Save), cx);
+        });
+    }
+
+    fn toggle(
+        workspace: &mut Workspace,
+        action: Option<EncodingAction>,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Option<()> {
+        let buffer = workspace
+            .active_item(cx)?
+            .act_as::<Editor>(cx)?
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()?;
+        // Encodings are only applied when a buffer is read from or written to the local disk.
+        buffer.read(cx).file()?.as_local()?;
+        let project = workspace.project().clone();
+
+        workspace.toggle_modal(cx, move |cx| {
+            EncodingSelector::new(buffer, project, action, cx)
+        });
+        Some(())
+    }
+
+    fn new(
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+        action: Option<EncodingAction>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate =
+            EncodingSelectorDelegate::new(cx.view().downgrade(), buffer, project, action);
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for EncodingSelector {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for EncodingSelector {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for EncodingSelector {}
+impl ModalView for EncodingSelector {}
+
+pub struct EncodingSelectorDelegate {
+    encoding_selector: WeakView<EncodingSelector>,
+    buffer: Model<Buffer>,
+    project: Model<Project>,
+    /// The chosen action, or `None` while the user is still picking one.
+    action: Option<EncodingAction>,
+    encodings: Vec<Encoding>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl EncodingSelectorDelegate {
+    fn new(
+        encoding_selector: WeakView<EncodingSelector>,
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+        action: Option<EncodingAction>,
+    ) -> Self {
+        Self {
+            encoding_selector,
+            buffer,
+            project,
+            action,
+            encodings: Encoding::all(),
+            matches: vec![],
+            selected_index: 0,
+        }
+    }
+
+    fn candidates(&self) -> Vec<StringMatchCandidate> {
+        match self.action {
+            None => [EncodingAction::Reopen, EncodingAction::Save]
+                .iter()
+                .enumerate()
+                .map(|(id, action)| StringMatchCandidate::new(id, action.label().to_string()))
+                .collect(),
+            Some(_) => self
+                .encodings
+                .iter()
+                .enumerate()
+                .map(|(id, encoding)| StringMatchCandidate::new(id, encoding.name().to_string()))
+                .collect(),
+        }
+    }
+
+    fn apply(&self, action: EncodingAction, encoding: Encoding, cx: &mut AppContext) {
+        match action {
+            EncodingAction::Reopen => {
+                self.buffer.update(cx, |buffer, cx| {
+                    buffer.set_encoding(encoding, cx);
+                    buffer.reload(cx);
+                });
+            }
+            EncodingAction::Save => {
+                self.buffer
+                    .update(cx, |buffer, cx| buffer.set_encoding(encoding, cx));
+                self.project
+                    .update(cx, |project, cx| {
+                        project.save_buffer(self.buffer.clone(), cx)
+                    })
+                    .detach_and_log_err(cx);
+            }
+        }
+    }
+}
+
+impl PickerDelegate for EncodingSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        match self.action {
+            None => "Reopen or save with encoding...".into(),
+            Some(_) => "Select an encoding...".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(mat) = self.matches.get(self.selected_index) else {
+            return;
+        };
+
+        match self.action {
+            None => {
+                let action = if mat.candidate_id == 0 {
+                    EncodingAction::Reopen
+                } else {
+                    EncodingAction::Save
+                };
+                if action == EncodingAction::Reopen && self.buffer.read(cx).is_dirty() {
+                    return;
+                }
+                self.action = Some(action);
+                self.selected_index = 0;
+                cx.defer(|picker, cx| {
+                    picker.set_query("", cx);
+                    picker.refresh(cx);
+                });
+            }
+            Some(action) => {
+                let encoding = self.encodings[mat.candidate_id];
+                if action == EncodingAction::Reopen && self.buffer.read(cx).is_dirty() {
+                    // Reopening would silently discard the unsaved edits.
+                    return;
+                }
+                self.apply(action, encoding, cx);
+                self.dismissed(cx);
+            }
+        }
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.encoding_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let mut label = mat.string.clone();
+        let mut disabled = false;
+        match self.action {
+            None => {
+                disabled = mat.candidate_id == 0 && self.buffer.read(cx).is_dirty();
+                if disabled {
+                    label.push_str(" (save or discard changes first)");
+                }
+            }
+            Some(_) => {
+                if self.encodings[mat.candidate_id] == self.buffer.read(cx).encoding() {
+                    label.push_str(" (current)");
+                }
+            }
+        }
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .disabled(disabled)
+                .child(HighlightedLabel::new(label, mat.positions.clone())),
+        )
+    }
+}
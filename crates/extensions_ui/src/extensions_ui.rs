@@ -979,6 +979,10 @@ impl Item for ExtensionsPage {
         Some("extensions page")
     }
 
+    fn display_name(&self) -> Option<&'static str> {
+        Some("extensions page")
+    }
+
     fn show_toolbar(&self) -> bool {
         false
     }
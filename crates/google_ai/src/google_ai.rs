@@ -1,26 +1,72 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use anyhow::{anyhow, Result};
 use futures::{io::BufReader, stream::BoxStream, AsyncBufReadExt, AsyncReadExt, StreamExt};
-use http::HttpClient;
+use http::{AsyncBody, HttpClient, Method, Request as HttpRequest};
+use isahc::config::Configurable;
 use serde::{Deserialize, Serialize};
 
 pub const API_URL: &str = "https://generativelanguage.googleapis.com";
 
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Model {
+    pub name: String,
+    pub max_tokens: usize,
+}
+
+impl Model {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            max_tokens: 32_768,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.name
+    }
+
+    pub fn display_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn max_token_count(&self) -> usize {
+        self.max_tokens
+    }
+}
+
+impl Default for Model {
+    fn default() -> Self {
+        Self {
+            name: "gemini-1.5-pro".into(),
+            max_tokens: 1_048_576,
+        }
+    }
+}
+
 pub async fn stream_generate_content(
     client: Arc<dyn HttpClient>,
     api_url: &str,
     api_key: &str,
     model: &str,
     request: GenerateContentRequest,
+    low_speed_timeout: Option<Duration>,
 ) -> Result<BoxStream<'static, Result<GenerateContentResponse>>> {
     let uri = format!(
         "{}/v1beta/models/{model}:streamGenerateContent?alt=sse&key={}",
         api_url, api_key
     );
+    let mut request_builder = HttpRequest::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    if let Some(low_speed_timeout) = low_speed_timeout {
+        request_builder = request_builder.low_speed_timeout(100, low_speed_timeout);
+    };
 
-    let request = serde_json::to_string(&request)?;
-    let mut response = client.post_json(&uri, request.into()).await?;
+    let request = request_builder.body(AsyncBody::from(serde_json::to_string(&request)?))?;
+    let mut response = client.send(request).await?;
     if response.status().is_success() {
         let reader = BufReader::new(response.into_body());
         Ok(reader
@@ -52,14 +98,15 @@ pub async fn stream_generate_content(
     }
 }
 
-pub async fn count_tokens<T: HttpClient>(
-    client: &T,
+pub async fn count_tokens(
+    client: &dyn HttpClient,
     api_url: &str,
     api_key: &str,
+    model: &str,
     request: CountTokensRequest,
 ) -> Result<CountTokensResponse> {
     let uri = format!(
-        "{}/v1beta/models/gemini-pro:countTokens?key={}",
+        "{}/v1beta/models/{model}:countTokens?key={}",
         api_url, api_key
     );
     let request = serde_json::to_string(&request)?;
@@ -77,6 +124,37 @@ pub async fn count_tokens<T: HttpClient>(
     }
 }
 
+/// Lists the models available to the given API key that can generate content.
+pub async fn list_models(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: &str,
+) -> Result<Vec<ModelListing>> {
+    let uri = format!("{}/v1beta/models?pageSize=1000&key={}", api_url, api_key);
+    let mut response = client.get(&uri, AsyncBody::default(), true).await?;
+    let mut text = String::new();
+    response.body_mut().read_to_string(&mut text).await?;
+    if response.status().is_success() {
+        let response = serde_json::from_str::<ListModelsResponse>(&text)?;
+        Ok(response
+            .models
+            .into_iter()
+            .filter(|model| {
+                model
+                    .supported_generation_methods
+                    .iter()
+                    .any(|method| method == "generateContent")
+            })
+            .collect())
+    } else {
+        Err(anyhow!(
+            "error during listModels, status code: {:?}, body: {}",
+            response.status(),
+            text
+        ))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Task {
     #[serde(rename = "generateContent")]
@@ -267,3 +345,28 @@ pub struct CountTokensRequest {
 pub struct CountTokensResponse {
     pub total_tokens: usize,
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListModelsResponse {
+    #[serde(default)]
+    pub models: Vec<ModelListing>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelListing {
+    /// The resource name of the model, e.g. `models/gemini-1.5-pro`.
+    pub name: String,
+    pub display_name: Option<String>,
+    pub input_token_limit: Option<usize>,
+    #[serde(default)]
+    pub supported_generation_methods: Vec<String>,
+}
+
+impl ModelListing {
+    /// The id used to refer to the model in requests, e.g. `gemini-1.5-pro`.
+    pub fn id(&self) -> &str {
+        self.name.strip_prefix("models/").unwrap_or(&self.name)
+    }
+}
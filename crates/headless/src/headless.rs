@@ -13,6 +13,12 @@ use settings::Settings;
 use std::{collections::HashMap, sync::Arc};
 use util::{ResultExt, TryFutureExt};
 
+mod ssh_server;
+#[cfg(test)]
+mod ssh_server_tests;
+
+pub use ssh_server::serve_ssh_project;
+
 pub struct DevServer {
     client: Arc<Client>,
     app_state: AppState,
@@ -0,0 +1,365 @@
+use crate::AppState;
+use anyhow::{anyhow, Result};
+use client::Client;
+use futures::{
+    channel::mpsc,
+    future::{self, LocalBoxFuture},
+    FutureExt as _, StreamExt as _,
+};
+use gpui::{AppContext, AsyncAppContext, Model, Task};
+use project::{Project, SSH_PROJECT_ID};
+use rpc::{
+    proto::{self, AnyTypedEnvelope, EnvelopedMessage, RequestMessage},
+    Connection, ConnectionId, ErrorExt as _, Peer, TypedEnvelope,
+};
+use std::{any::TypeId, cell::Cell, collections::HashMap, sync::Arc};
+use util::ResultExt as _;
+
+/// Serves the project at the given path on this machine to a guest connected to this
+/// process's standard input and output, as started by `zed ssh://host/path` on another machine.
+///
+/// Resolves once the guest leaves the project or disconnects.
+pub fn serve_ssh_project(
+    path: String,
+    client: Arc<Client>,
+    app_state: AppState,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    let connection = Connection::framed(
+        smol::Unblock::new(std::io::stdin()),
+        smol::Unblock::new(std::io::stdout()),
+    );
+    serve_project(connection, path, client, app_state, cx)
+}
+
+/// Serves a local project to a single guest over the given connection, standing in for the
+/// collaboration server between the guest and the project, which is shared with the guest
+/// through the given client.
+pub(crate) fn serve_project(
+    guest_connection: Connection,
+    path: String,
+    client: Arc<Client>,
+    app_state: AppState,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    let project = Project::local(
+        client.clone(),
+        app_state.node_runtime,
+        app_state.user_store,
+        app_state.languages,
+        app_state.fs,
+        cx,
+    );
+
+    cx.spawn(|mut cx| async move {
+        let path = shellexpand::tilde(&path).to_string();
+        let (worktree, _) = project
+            .update(&mut cx, |project, cx| {
+                project.find_or_create_local_worktree(&path, true, cx)
+            })?
+            .await?;
+        worktree.update(&mut cx, |worktree, cx| {
+            worktree.as_local_mut().unwrap().share_private_files(cx)
+        })?;
+
+        let peer = Peer::new(0);
+        let executor = cx.background_executor().clone();
+        let (host_connection, client_connection) = Connection::in_process();
+        let (guest_id, guest_io, guest_messages) = peer.add_connection(guest_connection, {
+            let executor = executor.clone();
+            move |duration| executor.timer(duration)
+        });
+        let (host_id, host_io, host_messages) = peer.add_connection(host_connection, {
+            let executor = executor.clone();
+            move |duration| executor.timer(duration)
+        });
+        let mut guest_io = executor.spawn(guest_io).fuse();
+        let _host_io = executor.spawn(host_io);
+
+        peer.send(
+            host_id,
+            proto::Hello {
+                peer_id: Some(host_id.into()),
+            },
+        )?;
+        client.connect_with(client_connection, &cx).await?;
+        peer.send(
+            guest_id,
+            proto::Hello {
+                peer_id: Some(guest_id.into()),
+            },
+        )?;
+
+        let (leave_tx, mut leave_rx) = mpsc::unbounded();
+        let relay = Relay {
+            peer: peer.clone(),
+            guest_id,
+            host_id,
+            project: project.clone(),
+            joined: Cell::new(false),
+            leave_tx,
+        };
+        let guest_handlers = MessageHandlers::from_guest();
+        let host_handlers = MessageHandlers::from_host();
+        let mut guest_messages = guest_messages.fuse();
+        let mut host_messages = host_messages.fuse();
+        loop {
+            futures::select_biased! {
+                _ = leave_rx.next() => break,
+                result = guest_io => {
+                    result.log_err();
+                    break;
+                }
+                message = guest_messages.next() => match message {
+                    Some(message) => guest_handlers.handle(message, &relay, &mut cx),
+                    None => break,
+                },
+                message = host_messages.next() => match message {
+                    Some(message) => host_handlers.handle(message, &relay, &mut cx),
+                    None => break,
+                },
+            }
+        }
+
+        log::info!("guest left the project");
+        project.update(&mut cx, |project, cx| project.unshare(cx).log_err())?;
+        peer.teardown();
+        Ok(())
+    })
+}
+
+/// The two ends of the project: the guest, and the host client of the project on this
+/// machine.
+struct Relay {
+    peer: Arc<Peer>,
+    guest_id: ConnectionId,
+    host_id: ConnectionId,
+    project: Model<Project>,
+    joined: Cell<bool>,
+    leave_tx: mpsc::UnboundedSender<()>,
+}
+
+impl Relay {
+    fn join_project(
+        &self,
+        envelope: TypedEnvelope<proto::JoinProject>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let receipt = envelope.receipt();
+        if self.joined.replace(true) {
+            return self
+                .peer
+                .respond_with_error(receipt, anyhow!("project was already joined").to_proto());
+        }
+
+        self.peer.send(
+            self.host_id,
+            proto::AddProjectCollaborator {
+                project_id: SSH_PROJECT_ID,
+                collaborator: Some(proto::Collaborator {
+                    peer_id: Some(self.guest_id.into()),
+                    replica_id: 1,
+                    user_id: 0,
+                }),
+            },
+        )?;
+        let worktrees = self
+            .project
+            .read_with(cx, |project, cx| project.worktree_metadata_protos(cx))?;
+        // Respond before sharing, so that the guest knows the project by the time the
+        // worktrees' entries are sent to it.
+        self.peer.respond(
+            receipt,
+            proto::JoinProjectResponse {
+                project_id: SSH_PROJECT_ID,
+                replica_id: 1,
+                worktrees,
+                collaborators: Vec::new(),
+                language_servers: Vec::new(),
+                role: proto::ChannelRole::Admin.into(),
+                dev_server_project_id: None,
+            },
+        )?;
+        self.project
+            .update(cx, |project, cx| project.shared(SSH_PROJECT_ID, cx))?
+    }
+}
+
+type MessageHandler = Box<
+    dyn Fn(
+        Box<dyn AnyTypedEnvelope>,
+        &Relay,
+        &mut AsyncAppContext,
+    ) -> LocalBoxFuture<'static, Result<()>>,
+>;
+
+/// What to do with each kind of message received from one end of the project. Handlers
+/// send whatever they forward before returning, so that messages reach the other end in
+/// the order they were received.
+#[derive(Default)]
+struct MessageHandlers(HashMap<TypeId, MessageHandler>);
+
+impl MessageHandlers {
+    fn from_guest() -> Self {
+        let mut handlers = Self::default();
+        handlers
+            .add_message_handler::<proto::JoinProject>(|envelope, relay, cx| {
+                future::ready(relay.join_project(envelope, cx)).boxed_local()
+            })
+            .add_message_handler::<proto::LeaveProject>(|_, relay, _| {
+                relay.leave_tx.unbounded_send(()).ok();
+                future::ready(Ok(())).boxed_local()
+            })
+            .add_request_handler::<proto::GetBufferCommentThreads>(|_, _, _| {
+                future::ready(Ok(proto::GetBufferCommentThreadsResponse::default())).boxed_local()
+            })
+            .forward_request_to_host::<proto::ApplyCodeAction>()
+            .forward_request_to_host::<proto::ApplyCompletionAdditionalEdits>()
+            .forward_request_to_host::<proto::BlameBuffer>()
+            .forward_request_to_host::<proto::CopyProjectEntry>()
+            .forward_request_to_host::<proto::CreateProjectEntry>()
+            .forward_request_to_host::<proto::DeleteProjectEntry>()
+            .forward_request_to_host::<proto::ExpandProjectEntry>()
+            .forward_request_to_host::<proto::FormatBuffers>()
+            .forward_request_to_host::<proto::GetCodeActions>()
+            .forward_request_to_host::<proto::GetCompletions>()
+            .forward_request_to_host::<proto::GetDeclaration>()
+            .forward_request_to_host::<proto::GetDefinition>()
+            .forward_request_to_host::<proto::GetDocumentHighlights>()
+            .forward_request_to_host::<proto::GetHover>()
+            .forward_request_to_host::<proto::GetProjectSymbols>()
+            .forward_request_to_host::<proto::GetReferences>()
+            .forward_request_to_host::<proto::GetTypeDefinition>()
+            .forward_request_to_host::<proto::InlayHints>()
+            .forward_request_to_host::<proto::LinkedEditingRange>()
+            .forward_request_to_host::<proto::MultiLspQuery>()
+            .forward_request_to_host::<proto::OnTypeFormatting>()
+            .forward_request_to_host::<proto::OpenBufferById>()
+            .forward_request_to_host::<proto::OpenBufferByPath>()
+            .forward_request_to_host::<proto::OpenBufferForSymbol>()
+            .forward_request_to_host::<proto::OpenNewBuffer>()
+            .forward_request_to_host::<proto::PerformRename>()
+            .forward_request_to_host::<proto::PrepareRename>()
+            .forward_request_to_host::<proto::ReloadBuffers>()
+            .forward_request_to_host::<proto::RenameProjectEntry>()
+            .forward_request_to_host::<proto::ResolveCompletionDocumentation>()
+            .forward_request_to_host::<proto::RestartLanguageServers>()
+            .forward_request_to_host::<proto::SaveBuffer>()
+            .forward_request_to_host::<proto::SearchProject>()
+            .forward_request_to_host::<proto::SynchronizeBuffers>()
+            .forward_request_to_host::<proto::TaskContextForLocation>()
+            .forward_request_to_host::<proto::TaskTemplates>()
+            .forward_request_to_host::<proto::TerminalInput>()
+            .forward_request_to_host::<proto::UpdateBuffer>();
+        handlers
+    }
+
+    fn from_host() -> Self {
+        let mut handlers = Self::default();
+        handlers
+            .add_request_handler::<proto::GetBufferCommentThreads>(|_, _, _| {
+                future::ready(Ok(proto::GetBufferCommentThreadsResponse::default())).boxed_local()
+            })
+            .forward_update_to_guest::<proto::UpdateProject>()
+            .forward_update_to_guest::<proto::UpdateWorktree>()
+            .forward_update_to_guest::<proto::UpdateBuffer>()
+            .forward_message_to_guest::<proto::BufferReloaded>()
+            .forward_message_to_guest::<proto::BufferSaved>()
+            .forward_message_to_guest::<proto::CreateBufferForPeer>()
+            .forward_message_to_guest::<proto::RefreshInlayHints>()
+            .forward_message_to_guest::<proto::ShareTerminal>()
+            .forward_message_to_guest::<proto::StartLanguageServer>()
+            .forward_message_to_guest::<proto::UnshareProject>()
+            .forward_message_to_guest::<proto::UnshareTerminal>()
+            .forward_message_to_guest::<proto::UpdateBufferFile>()
+            .forward_message_to_guest::<proto::UpdateDiagnosticSummary>()
+            .forward_message_to_guest::<proto::UpdateDiffBase>()
+            .forward_message_to_guest::<proto::UpdateLanguageServer>()
+            .forward_message_to_guest::<proto::UpdateTerminal>()
+            .forward_message_to_guest::<proto::UpdateWorktreeSettings>();
+        handlers
+    }
+
+    fn handle(&self, message: Box<dyn AnyTypedEnvelope>, relay: &Relay, cx: &mut AsyncAppContext) {
+        let Some(handler) = self.0.get(&message.payload_type_id()) else {
+            log::warn!("unhandled message {}", message.payload_type_name());
+            relay.peer.respond_with_unhandled_message(message).log_err();
+            return;
+        };
+        let handled = handler(message, relay, cx);
+        cx.foreground_executor()
+            .spawn(async move { handled.await.log_err() })
+            .detach();
+    }
+
+    fn add_message_handler<M: EnvelopedMessage>(
+        &mut self,
+        handler: impl 'static
+            + Fn(
+                TypedEnvelope<M>,
+                &Relay,
+                &mut AsyncAppContext,
+            ) -> LocalBoxFuture<'static, Result<()>>,
+    ) -> &mut Self {
+        self.0.insert(
+            TypeId::of::<M>(),
+            Box::new(move |envelope, relay, cx| {
+                let envelope = envelope.into_any().downcast::<TypedEnvelope<M>>().unwrap();
+                handler(*envelope, relay, cx)
+            }),
+        );
+        self
+    }
+
+    fn add_request_handler<M: RequestMessage>(
+        &mut self,
+        handler: impl 'static
+            + Fn(
+                TypedEnvelope<M>,
+                &Relay,
+                &mut AsyncAppContext,
+            ) -> LocalBoxFuture<'static, Result<M::Response>>,
+    ) -> &mut Self {
+        self.add_message_handler::<M>(move |envelope, relay, cx| {
+            let receipt = envelope.receipt();
+            let response = handler(envelope, relay, cx);
+            let peer = relay.peer.clone();
+            async move {
+                match response.await {
+                    Ok(response) => peer.respond(receipt, response),
+                    Err(error) => peer.respond_with_error(receipt, error.to_proto()),
+                }
+            }
+            .boxed_local()
+        })
+    }
+
+    fn forward_request_to_host<M: RequestMessage>(&mut self) -> &mut Self {
+        self.add_request_handler::<M>(|envelope, relay, _| {
+            relay
+                .peer
+                .forward_request(relay.guest_id, relay.host_id, envelope.payload)
+                .boxed_local()
+        })
+    }
+
+    fn forward_update_to_guest<M: RequestMessage<Response = proto::Ack>>(&mut self) -> &mut Self {
+        self.add_request_handler::<M>(|envelope, relay, _| {
+            let forwarded = relay
+                .peer
+                .forward_send(relay.host_id, relay.guest_id, envelope.payload)
+                .map(|_| proto::Ack {});
+            future::ready(forwarded).boxed_local()
+        })
+    }
+
+    fn forward_message_to_guest<M: EnvelopedMessage>(&mut self) -> &mut Self {
+        self.add_message_handler::<M>(|envelope, relay, _| {
+            let forwarded =
+                relay
+                    .peer
+                    .forward_send(relay.host_id, relay.guest_id, envelope.payload);
+            future::ready(forwarded).boxed_local()
+        })
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{ssh_server::serve_project, AppState};
+use client::{Client, UserStore};
+use clock::FakeSystemClock;
+use fs::{FakeFs, Fs as _};
+use futures::{channel::mpsc, AsyncRead, AsyncWrite, StreamExt as _};
+use gpui::{Context as _, SemanticVersion, TestAppContext};
+use http::FakeHttpClient;
+use language::LanguageRegistry;
+use node_runtime::FakeNodeRuntime;
+use project::Project;
+use rpc::Connection;
+use serde_json::json;
+use settings::SettingsStore;
+use std::{
+    io,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+#[gpui::test]
+async fn test_open_project_over_ssh(cx: &mut TestAppContext, guest_cx: &mut TestAppContext) {
+    init_test(cx);
+    init_test(guest_cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project": {
+                "README.md": "# Project",
+                "src": {
+                    "main.rs": "fn main() {}",
+                },
+            },
+        }),
+    )
+    .await;
+
+    let (host_connection, guest_connection) = stdio_connections();
+    let host_client = test_client(cx);
+    let user_store = cx.new_model(|cx| UserStore::new(host_client.clone(), cx));
+    let app_state = AppState {
+        node_runtime: FakeNodeRuntime::new(),
+        user_store,
+        languages: Arc::new(LanguageRegistry::test(cx.executor())),
+        fs: fs.clone(),
+    };
+    let server = cx.update(|cx| {
+        serve_project(
+            host_connection,
+            "/code/project".to_string(),
+            host_client,
+            app_state,
+            cx,
+        )
+    });
+
+    let guest_client = test_client(guest_cx);
+    guest_client
+        .connect_with(guest_connection, &guest_cx.to_async())
+        .await
+        .unwrap();
+    let user_store = guest_cx.new_model(|cx| UserStore::new(guest_client.clone(), cx));
+    let project = Project::ssh(
+        "ssh example.com".into(),
+        guest_client,
+        user_store,
+        Arc::new(LanguageRegistry::test(guest_cx.executor())),
+        FakeFs::new(guest_cx.executor()),
+        guest_cx.to_async(),
+    )
+    .await
+    .unwrap();
+    guest_cx.executor().run_until_parked();
+
+    let worktree_id = project.read_with(guest_cx, |project, cx| {
+        assert!(project.is_remote());
+        assert!(project.supports_remote_terminal(cx));
+        assert!(!project.runs_tasks_on_host());
+
+        let worktree = project.worktrees().next().unwrap().read(cx);
+        assert_eq!(worktree.abs_path().as_ref(), Path::new("/code/project"));
+        assert_eq!(
+            worktree.paths().map(AsRef::as_ref).collect::<Vec<_>>(),
+            [
+                Path::new("README.md"),
+                Path::new("src"),
+                Path::new("src/main.rs")
+            ]
+        );
+        worktree.id()
+    });
+
+    let buffer = project
+        .update(guest_cx, |project, cx| {
+            project.open_buffer((worktree_id, "src/main.rs"), cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(guest_cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "fn main() {}");
+        buffer.edit([(0..0, "// served over ssh\n")], None, cx);
+    });
+    project
+        .update(guest_cx, |project, cx| project.save_buffer(buffer, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load(Path::new("/code/project/src/main.rs"))
+            .await
+            .unwrap(),
+        "// served over ssh\nfn main() {}"
+    );
+
+    // The server stops once the guest leaves the project.
+    guest_cx.update(|_| drop(project));
+    server.await.unwrap();
+}
+
+fn init_test(cx: &mut TestAppContext) {
+    cx.update(|cx| {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+        release_channel::init(SemanticVersion::default(), cx);
+        language::init(cx);
+        Project::init_settings(cx);
+    });
+}
+
+fn test_client(cx: &mut TestAppContext) -> Arc<Client> {
+    let client = cx.update(|cx| {
+        Client::new(
+            Arc::new(FakeSystemClock::default()),
+            FakeHttpClient::with_404_response(),
+            cx,
+        )
+    });
+    Project::init_client(&client);
+    client
+}
+
+/// Connects a host and a guest as if the guest had started the host over `ssh`, with the
+/// messages framed over pipes standing in for the host's standard input and output.
+fn stdio_connections() -> (Connection, Connection) {
+    let (host_stdin_tx, host_stdin_rx) = mpsc::unbounded();
+    let (host_stdout_tx, host_stdout_rx) = mpsc::unbounded();
+    (
+        Connection::framed(PipeReader::new(host_stdin_rx), PipeWriter(host_stdout_tx)),
+        Connection::framed(PipeReader::new(host_stdout_rx), PipeWriter(host_stdin_tx)),
+    )
+}
+
+struct PipeWriter(mpsc::UnboundedSender<Vec<u8>>);
+
+impl AsyncWrite for PipeWriter {
+    fn poll_write(self: Pin<&mut Self>, _: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Poll::Ready(
+            self.0
+                .unbounded_send(buf.to_vec())
+                .map(|_| buf.len())
+                .map_err(|_| io::ErrorKind::BrokenPipe.into()),
+        )
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        self.0.close_channel();
+        Poll::Ready(Ok(()))
+    }
+}
+
+struct PipeReader {
+    rx: mpsc::UnboundedReceiver<Vec<u8>>,
+    pending: Vec<u8>,
+}
+
+impl PipeReader {
+    fn new(rx: mpsc::UnboundedReceiver<Vec<u8>>) -> Self {
+        Self {
+            rx,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl AsyncRead for PipeReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.pending.is_empty() {
+            match self.rx.poll_next_unpin(cx) {
+                Poll::Ready(Some(bytes)) => self.pending = bytes,
+                Poll::Ready(None) => return Poll::Ready(Ok(0)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        let len = buf.len().min(self.pending.len());
+        buf[..len].copy_from_slice(&self.pending[..len]);
+        self.pending.drain(..len);
+        Poll::Ready(Ok(len))
+    }
+}
@@ -0,0 +1,856 @@
+use anyhow::{anyhow, Result};
+use gpui::{
+    actions, div, uniform_list, AnyElement, AppContext, Context, EventEmitter, FocusHandle,
+    FocusableView, InteractiveElement, IntoElement, KeyDownEvent, Model, ModelContext, MouseButton,
+    ParentElement, Render, Styled, Subscription, Task, UniformListScrollHandle, View, ViewContext,
+    VisualContext, WeakView, WindowContext,
+};
+use language::Encoding;
+use persistence::HEX_EDITOR;
+use project::{search::SearchQuery, Project, ProjectEntryId, ProjectPath};
+use rpc::{ErrorCode, ErrorCodeExt};
+use settings::Settings;
+use std::{ops::Range, path::PathBuf, sync::Arc};
+use theme::ThemeSettings;
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent, ProjectItem, TabContentParams},
+    searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
+    ItemId, Pane, Workspace, WorkspaceId,
+};
+
+const HEX_EDITOR_KIND: &str = "HexEditor";
+const BYTES_PER_ROW: usize = 16;
+const ROWS_PER_PAGE: usize = 32;
+
+actions!(
+    hex_editor,
+    [
+        MoveLeft,
+        MoveRight,
+        MoveUp,
+        MoveDown,
+        PageUp,
+        PageDown,
+        MoveToRowStart,
+        MoveToRowEnd,
+        MoveToBeginning,
+        MoveToEnd,
+        ToggleColumn,
+    ]
+);
+
+/// Registers the hex editor for files that can't be opened as text.
+pub fn init(cx: &mut AppContext) {
+    workspace::register_project_item::<HexEditor>(cx);
+    workspace::register_deserializable_item::<HexEditor>(cx);
+}
+
+/// The contents of a binary file, shared by every hex editor showing it.
+pub struct HexItem {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    bytes: Vec<u8>,
+    is_dirty: bool,
+}
+
+impl project::Item for HexItem {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Model<Self>>>> {
+        // Files are read straight from disk, which isn't possible for remote projects.
+        if !project.read(cx).is_local() {
+            return None;
+        }
+
+        let project = project.read(cx);
+        let abs_path = project.absolute_path(path, cx)?;
+        let entry_id = project.entry_for_path(path, cx).map(|entry| entry.id);
+        let fs = project.fs().clone();
+        let project_path = path.clone();
+        Some(cx.spawn(|mut cx| async move {
+            let bytes = fs.load_bytes(&abs_path).await?;
+            // Text files are left to the editor, even when this opener is tried first.
+            let (bytes, is_binary) = cx
+                .background_executor()
+                .spawn(async move {
+                    let is_binary = Encoding::detect(&bytes).is_none();
+                    (bytes, is_binary)
+                })
+                .await;
+            if !is_binary {
+                return Err(
+                    ErrorCode::TextFile.message(format!("{abs_path:?} looks like a text file"))
+                );
+            }
+            cx.new_model(|_| HexItem {
+                abs_path,
+                project_path,
+                entry_id,
+                bytes,
+                is_dirty: false,
+            })
+        }))
+    }
+
+    fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+}
+
+impl HexItem {
+    fn write_byte(&mut self, offset: usize, byte: u8, cx: &mut ModelContext<Self>) {
+        if let Some(existing) = self.bytes.get_mut(offset) {
+            if *existing != byte {
+                *existing = byte;
+                self.is_dirty = true;
+                cx.notify();
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Ascii,
+}
+
+pub enum HexEditorEvent {
+    Edited,
+    Saved,
+}
+
+/// Shows a file as rows of offsets, hex bytes and their ASCII representation, and
+/// overwrites bytes in place as hex digits or characters are typed.
+pub struct HexEditor {
+    item: Model<HexItem>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    cursor: usize,
+    /// Whether the next hex digit replaces the low half of the byte under the cursor.
+    low_nibble: bool,
+    column: Column,
+    matches: Vec<Range<usize>>,
+    _observe_item: Subscription,
+}
+
+impl HexEditor {
+    fn new(item: Model<HexItem>, cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            _observe_item: cx.observe(&item, |_, _, cx| cx.notify()),
+            item,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            cursor: 0,
+            low_nibble: false,
+            column: Column::Hex,
+            matches: Vec::new(),
+        }
+    }
+
+    fn len(&self, cx: &AppContext) -> usize {
+        self.item.read(cx).bytes.len()
+    }
+
+    fn move_to(&mut self, offset: usize, cx: &mut ViewContext<Self>) {
+        self.cursor = offset.min(self.len(cx).saturating_sub(1));
+        self.low_nibble = false;
+        self.scroll_handle
+            .scroll_to_item(self.cursor / BYTES_PER_ROW);
+        cx.emit(SearchEvent::ActiveMatchChanged);
+        cx.notify();
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, cx: &mut ViewContext<Self>) {
+        self.move_to(self.cursor.saturating_sub(1), cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, cx: &mut ViewContext<Self>) {
+        self.move_to(self.cursor + 1, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, cx: &mut ViewContext<Self>) {
+        if let Some(offset) = self.cursor.checked_sub(BYTES_PER_ROW) {
+            self.move_to(offset, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, cx: &mut ViewContext<Self>) {
+        if self.cursor + BYTES_PER_ROW < self.len(cx) {
+            self.move_to(self.cursor + BYTES_PER_ROW, cx);
+        }
+    }
+
+    fn page_up(&mut self, _: &PageUp, cx: &mut ViewContext<Self>) {
+        let offset = self.cursor.saturating_sub(BYTES_PER_ROW * ROWS_PER_PAGE);
+        self.move_to(offset, cx);
+    }
+
+    fn page_down(&mut self, _: &PageDown, cx: &mut ViewContext<Self>) {
+        self.move_to(self.cursor + BYTES_PER_ROW * ROWS_PER_PAGE, cx);
+    }
+
+    fn move_to_row_start(&mut self, _: &MoveToRowStart, cx: &mut ViewContext<Self>) {
+        self.move_to(self.cursor - self.cursor % BYTES_PER_ROW, cx);
+    }
+
+    fn move_to_row_end(&mut self, _: &MoveToRowEnd, cx: &mut ViewContext<Self>) {
+        self.move_to(
+            self.cursor - self.cursor % BYTES_PER_ROW + BYTES_PER_ROW - 1,
+            cx,
+        );
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, cx: &mut ViewContext<Self>) {
+        self.move_to(0, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, cx: &mut ViewContext<Self>) {
+        self.move_to(usize::MAX, cx);
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, cx: &mut ViewContext<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.low_nibble = false;
+        cx.notify();
+    }
+
+    fn handle_key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.control || keystroke.modifiers.alt || keystroke.modifiers.platform {
+            return;
+        }
+        let Some(byte) = self.item.read(cx).bytes.get(self.cursor).copied() else {
+            return;
+        };
+
+        match self.column {
+            Column::Hex => {
+                let Some(digit) = single_char(&keystroke.key).and_then(|c| c.to_digit(16)) else {
+                    return;
+                };
+                let digit = digit as u8;
+                let byte = if self.low_nibble {
+                    (byte & 0xf0) | digit
+                } else {
+                    (digit << 4) | (byte & 0x0f)
+                };
+                self.write_byte(byte, cx);
+                if self.low_nibble {
+                    self.move_to(self.cursor + 1, cx);
+                } else {
+                    self.low_nibble = true;
+                }
+            }
+            Column::Ascii => {
+                let text = keystroke.ime_key.as_deref().unwrap_or(&keystroke.key);
+                let Some(c) = single_char(text).filter(|c| *c == ' ' || c.is_ascii_graphic())
+                else {
+                    return;
+                };
+                self.write_byte(c as u8, cx);
+                self.move_to(self.cursor + 1, cx);
+            }
+        }
+        cx.stop_propagation();
+    }
+
+    fn write_byte(&mut self, byte: u8, cx: &mut ViewContext<Self>) {
+        let offset = self.cursor;
+        self.item
+            .update(cx, |item, cx| item.write_byte(offset, byte, cx));
+        cx.emit(HexEditorEvent::Edited);
+    }
+
+    fn render_row(&self, row: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        let start = row * BYTES_PER_ROW;
+        let bytes = (start..start + BYTES_PER_ROW)
+            .map(|offset| self.item.read(cx).bytes.get(offset).copied())
+            .collect::<Vec<_>>();
+
+        let mut hex = h_flex().gap_1();
+        let mut ascii = h_flex();
+        for (offset, byte) in (start..).zip(bytes) {
+            let (hex_text, ascii_text) = match byte {
+                Some(byte) => (format!("{byte:02X}"), printable(byte).to_string()),
+                None => ("  ".to_string(), " ".to_string()),
+            };
+            if offset % BYTES_PER_ROW == BYTES_PER_ROW / 2 {
+                hex = hex.child(div().w_1());
+            }
+            hex = hex.child(self.render_byte(offset, Column::Hex, hex_text, cx));
+            ascii = ascii.child(self.render_byte(offset, Column::Ascii, ascii_text, cx));
+        }
+
+        h_flex()
+            .gap_4()
+            .px_2()
+            .child(
+                div()
+                    .text_color(cx.theme().colors().editor_line_number)
+                    .child(format!("{start:08X}")),
+            )
+            .child(hex)
+            .child(ascii)
+            .into_any_element()
+    }
+
+    fn render_byte(
+        &self,
+        offset: usize,
+        column: Column,
+        text: String,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let len = self.len(cx);
+        let mut selection = cx.theme().players().local().selection;
+        let background = if offset == self.cursor && offset < len {
+            if column != self.column {
+                selection.fade_out(0.6);
+            }
+            Some(selection)
+        } else if self.matches.iter().any(|range| range.contains(&offset)) {
+            Some(cx.theme().colors().search_match_background)
+        } else {
+            None
+        };
+
+        div()
+            .when_some(background, |div, background| div.bg(background))
+            .child(text)
+            .when(offset < len, |div| {
+                div.on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _, cx| {
+                        this.column = column;
+                        this.move_to(offset, cx);
+                        cx.focus(&this.focus_handle);
+                    }),
+                )
+            })
+    }
+}
+
+fn single_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    let c = chars.next()?;
+    chars.next().is_none().then_some(c)
+}
+
+fn printable(byte: u8) -> char {
+    if byte == b' ' || byte.is_ascii_graphic() {
+        byte as char
+    } else {
+        '.'
+    }
+}
+
+/// Interprets a search query as a byte pattern. Queries made up only of pairs of hex
+/// digits, optionally separated by whitespace, match those bytes; anything else
+/// matches the query's UTF-8 encoding.
+fn parse_byte_pattern(query: &str) -> Vec<u8> {
+    let digits = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    let is_hex =
+        !digits.is_empty() && digits.len() % 2 == 0 && digits.iter().all(|c| c.is_ascii_hexdigit());
+    if is_hex {
+        digits
+            .chunks(2)
+            .map(|pair| (pair[0].to_digit(16).unwrap() << 4 | pair[1].to_digit(16).unwrap()) as u8)
+            .collect()
+    } else {
+        query.as_bytes().to_vec()
+    }
+}
+
+fn find_byte_pattern(bytes: &[u8], pattern: &[u8]) -> Vec<Range<usize>> {
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+    bytes
+        .windows(pattern.len())
+        .enumerate()
+        .filter(|(_, window)| *window == pattern)
+        .map(|(offset, _)| offset..offset + pattern.len())
+        .collect()
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+impl EventEmitter<SearchEvent> for HexEditor {}
+
+impl FocusableView for HexEditor {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let font_family = settings.buffer_font.family.clone();
+        let font_size = settings.buffer_font_size(cx);
+        let row_count = self.len(cx).div_ceil(BYTES_PER_ROW);
+
+        v_flex()
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::page_up))
+            .on_action(cx.listener(Self::page_down))
+            .on_action(cx.listener(Self::move_to_row_start))
+            .on_action(cx.listener(Self::move_to_row_end))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::toggle_column))
+            .on_key_down(cx.listener(Self::handle_key_down))
+            .size_full()
+            .py_1()
+            .bg(cx.theme().colors().editor_background)
+            .font_family(font_family)
+            .text_size(font_size)
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "hex-rows",
+                    row_count,
+                    |this, range, cx| range.map(|row| this.render_row(row, cx)).collect(),
+                )
+                .size_full()
+                .track_scroll(self.scroll_handle.clone()),
+            )
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &HexEditorEvent, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            HexEditorEvent::Saved => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        let item = self.item.read(cx);
+        let title = item
+            .abs_path
+            .file_name()
+            .unwrap_or_else(|| item.abs_path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+        Label::new(title)
+            .single_line()
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        Some(
+            self.item
+                .read(cx)
+                .abs_path
+                .to_string_lossy()
+                .to_string()
+                .into(),
+        )
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("hex editor")
+    }
+
+    fn display_name(&self) -> Option<&'static str> {
+        Some("hex editor")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        f(self.item.entity_id(), self.item.read(cx))
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        Some(cx.new_view(|cx| Self::new(self.item.clone(), cx)))
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.item.read(cx).is_dirty
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let abs_path = self.item.read(cx).abs_path.clone();
+        self.write_to(abs_path, project, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        project: Model<Project>,
+        path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(abs_path) = project.read(cx).absolute_path(&path, cx) else {
+            return Task::ready(Err(anyhow!("no absolute path for {:?}", path.path)));
+        };
+        self.item.update(cx, |item, _| {
+            item.abs_path = abs_path.clone();
+            item.project_path = path;
+        });
+        self.write_to(abs_path, project, cx)
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let abs_path = self.item.read(cx).abs_path.clone();
+        let fs = project.read(cx).fs().clone();
+        let item = self.item.clone();
+        cx.spawn(|this, mut cx| async move {
+            let bytes = fs.load_bytes(&abs_path).await?;
+            item.update(&mut cx, |item, cx| {
+                item.bytes = bytes;
+                item.is_dirty = false;
+                cx.notify();
+            })?;
+            this.update(&mut cx, |this, cx| {
+                let cursor = this.cursor;
+                this.move_to(cursor, cx);
+                cx.emit(HexEditorEvent::Saved);
+            })
+        })
+    }
+
+    fn as_searchable(&self, handle: &View<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(handle.clone()))
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        let item_id = cx.entity_id().as_u64();
+        let Some(workspace_id) = workspace.database_id() else {
+            return;
+        };
+        let abs_path = self.item.read(cx).abs_path.clone();
+        cx.background_executor()
+            .spawn(async move {
+                HEX_EDITOR
+                    .save_path(item_id, workspace_id, abs_path)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+
+    fn serialized_item_kind() -> Option<&'static str> {
+        Some(HEX_EDITOR_KIND)
+    }
+
+    fn deserialize(
+        project: Model<Project>,
+        _workspace: WeakView<Workspace>,
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+        cx: &mut ViewContext<Pane>,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|_pane, mut cx| async move {
+            let abs_path = HEX_EDITOR
+                .get_path(item_id, workspace_id)?
+                .ok_or_else(|| anyhow!("no path found for hex editor"))?;
+            let (worktree, path) = project
+                .update(&mut cx, |project, cx| {
+                    project.find_or_create_local_worktree(&abs_path, false, cx)
+                })?
+                .await?;
+            let project_path = ProjectPath {
+                worktree_id: worktree.update(&mut cx, |worktree, _| worktree.id())?,
+                path: path.into(),
+            };
+            let open_item = cx
+                .update(|cx| <HexItem as project::Item>::try_open(&project, &project_path, cx))?
+                .ok_or_else(|| anyhow!("can't open {abs_path:?} in a hex editor"))?;
+            let item = open_item.await?;
+            cx.new_view(|cx| HexEditor::new(item, cx))
+        })
+    }
+}
+
+impl HexEditor {
+    fn write_to(
+        &mut self,
+        abs_path: PathBuf,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let fs = project.read(cx).fs().clone();
+        let bytes = self.item.read(cx).bytes.clone();
+        let item = self.item.clone();
+        cx.spawn(|this, mut cx| async move {
+            fs.atomic_write(abs_path, bytes).await?;
+            item.update(&mut cx, |item, cx| {
+                item.is_dirty = false;
+                cx.notify();
+            })?;
+            this.update(&mut cx, |_, cx| cx.emit(HexEditorEvent::Saved))
+        })
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = HexItem;
+
+    fn for_project_item(
+        _project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        Self::new(item, cx)
+    }
+}
+
+impl SearchableItem for HexEditor {
+    type Match = Range<usize>;
+
+    fn supported_options() -> SearchOptions {
+        SearchOptions {
+            case: false,
+            word: false,
+            regex: false,
+            replacement: false,
+            selection: false,
+        }
+    }
+
+    fn clear_matches(&mut self, cx: &mut ViewContext<Self>) {
+        self.matches.clear();
+        cx.notify();
+    }
+
+    fn update_matches(&mut self, matches: &[Self::Match], cx: &mut ViewContext<Self>) {
+        self.matches = matches.to_vec();
+        cx.notify();
+    }
+
+    fn query_suggestion(&mut self, cx: &mut ViewContext<Self>) -> String {
+        self.item
+            .read(cx)
+            .bytes
+            .get(self.cursor)
+            .map(|byte| format!("{byte:02X}"))
+            .unwrap_or_default()
+    }
+
+    fn activate_match(
+        &mut self,
+        index: usize,
+        matches: &[Self::Match],
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(range) = matches.get(index) {
+            self.move_to(range.start, cx);
+        }
+    }
+
+    fn select_matches(&mut self, matches: &[Self::Match], cx: &mut ViewContext<Self>) {
+        self.activate_match(0, matches, cx);
+    }
+
+    fn replace(&mut self, _: &Self::Match, _: &SearchQuery, _: &mut ViewContext<Self>) {
+        // Replacing would change the file's length, which overwrite editing never does.
+    }
+
+    fn find_matches(
+        &mut self,
+        query: Arc<SearchQuery>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Vec<Self::Match>> {
+        let bytes = self.item.read(cx).bytes.clone();
+        let pattern = parse_byte_pattern(query.as_str());
+        cx.background_executor()
+            .spawn(async move { find_byte_pattern(&bytes, &pattern) })
+    }
+
+    fn active_match_index(
+        &mut self,
+        matches: &[Self::Match],
+        _: &mut ViewContext<Self>,
+    ) -> Option<usize> {
+        if matches.is_empty() {
+            return None;
+        }
+        let ix = matches.partition_point(|range| range.end <= self.cursor);
+        Some(ix.min(matches.len() - 1))
+    }
+}
+
+mod persistence {
+    use std::path::PathBuf;
+
+    use db::{define_connection, query, sqlez_macros::sql};
+    use workspace::{ItemId, WorkspaceDb, WorkspaceId};
+
+    define_connection! {
+        pub static ref HEX_EDITOR: HexEditorDb<WorkspaceDb> =
+            &[sql!(
+                CREATE TABLE hex_editors (
+                    workspace_id INTEGER,
+                    item_id INTEGER UNIQUE,
+
+                    abs_path BLOB,
+
+                    PRIMARY KEY(workspace_id, item_id),
+                    FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                    ON DELETE CASCADE
+                ) STRICT;
+            )];
+    }
+
+    impl HexEditorDb {
+        query! {
+            pub async fn save_path(
+                item_id: ItemId,
+                workspace_id: WorkspaceId,
+                abs_path: PathBuf
+            ) -> Result<()> {
+                INSERT OR REPLACE INTO hex_editors(item_id, workspace_id, abs_path)
+                VALUES (?, ?, ?)
+            }
+        }
+
+        query! {
+            pub fn get_path(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<PathBuf>> {
+                SELECT abs_path
+                FROM hex_editors
+                WHERE item_id = ? AND workspace_id = ?
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Fs};
+    use serde_json::json;
+    use workspace::{AppState, SaveIntent};
+
+    #[gpui::test]
+    async fn test_open_edit_and_save_binary_file(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({ "text.txt": "hello" })).await;
+        fs.insert_file("/dir/data.bin", vec![0x7f, b'E', b'L', b'F', 0x00, 0x01])
+            .await;
+
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+
+        // Text files aren't opened as hex.
+        let open_text = workspace.update(cx, |workspace, cx| {
+            workspace.open_path((worktree_id, "text.txt"), None, true, cx)
+        });
+        let error = open_text.await.err().unwrap();
+        assert_eq!(error.error_code(), ErrorCode::TextFile);
+
+        let hex_editor = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "data.bin"), None, true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<HexEditor>()
+            .unwrap();
+
+        hex_editor.update(cx, |hex_editor, cx| {
+            hex_editor.move_to(4, cx);
+            hex_editor.write_byte(0xab, cx);
+            assert!(hex_editor.is_dirty(cx));
+        });
+
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.save_active_item(SaveIntent::Save, cx)
+            })
+            .await
+            .unwrap();
+        hex_editor.update(cx, |hex_editor, cx| assert!(!hex_editor.is_dirty(cx)));
+        assert_eq!(
+            fs.load_bytes("/dir/data.bin".as_ref()).await.unwrap(),
+            vec![0x7f, b'E', b'L', b'F', 0xab, 0x01]
+        );
+    }
+
+    #[test]
+    fn test_parse_byte_pattern() {
+        assert_eq!(parse_byte_pattern("DEADBEEF"), vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(
+            parse_byte_pattern("7f 45 4c 46"),
+            vec![0x7f, b'E', b'L', b'F']
+        );
+        assert_eq!(parse_byte_pattern("ELF"), b"ELF".to_vec());
+        assert_eq!(parse_byte_pattern("abc"), b"abc".to_vec());
+    }
+
+    #[test]
+    fn test_find_byte_pattern() {
+        let bytes = [0x00, 0xde, 0xad, 0x00, 0xde, 0xad, 0xde];
+        assert_eq!(find_byte_pattern(&bytes, &[0xde, 0xad]), vec![1..3, 4..6]);
+        assert_eq!(
+            find_byte_pattern(&bytes, &[0xff]),
+            Vec::<Range<usize>>::new()
+        );
+        assert_eq!(find_byte_pattern(&bytes, &[]), Vec::<Range<usize>>::new());
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+        });
+    }
+}
@@ -92,6 +92,12 @@ pub struct Buffer {
     /// The mtime of the file when this buffer was last loaded from
     /// or saved to disk.
     saved_mtime: Option<SystemTime>,
+    /// The encoding of the file on disk, which the text is converted
+    /// from when loading and to when saving.
+    encoding: Encoding,
+    /// Whether the buffer was opened in large-file mode, in which it is
+    /// neither parsed nor diffed against git.
+    is_large_file: bool,
     /// The version vector when this buffer was last loaded from
     /// or saved to disk.
     saved_version: clock::Global,
@@ -378,6 +384,9 @@ pub trait LocalFile: File {
     /// Loads the file's contents from disk.
     fn load(&self, cx: &AppContext) -> Task<Result<String>>;
 
+    /// Loads the file's raw contents from disk, to be decoded with the buffer's [Encoding].
+    fn load_bytes(&self, cx: &AppContext) -> Task<Result<Vec<u8>>>;
+
     /// Returns true if the file should not be shared with collaborators.
     fn is_private(&self, _: &AppContext) -> bool {
         false
@@ -669,6 +678,39 @@ impl Buffer {
         self
     }
 
+    /// Assign the [Encoding] of the buffer's file, returning the buffer.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Marks the buffer as holding a large file, returning the buffer.
+    pub fn with_large_file_mode(mut self, is_large_file: bool) -> Self {
+        self.is_large_file = is_large_file;
+        if is_large_file {
+            self.diff_base = None;
+        }
+        self
+    }
+
+    /// Whether the buffer was opened in large-file mode.
+    pub fn is_large_file(&self) -> bool {
+        self.is_large_file
+    }
+
+    /// The [Encoding] of the buffer's file on disk.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Sets the [Encoding] used the next time the buffer is saved or reloaded.
+    pub fn set_encoding(&mut self, encoding: Encoding, cx: &mut ModelContext<Self>) {
+        if self.encoding != encoding {
+            self.encoding = encoding;
+            cx.notify();
+        }
+    }
+
     /// Returns the [Capability] of this buffer.
     pub fn capability(&self) -> Capability {
         self.capability
@@ -690,6 +732,8 @@ impl Buffer {
 
         Self {
             saved_mtime,
+            encoding: Encoding::default(),
+            is_large_file: false,
             saved_version: buffer.version(),
             reload_task: None,
             transaction_depth: 0,
@@ -820,15 +864,19 @@ impl Buffer {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(|this, mut cx| async move {
-            let Some((new_mtime, new_text)) = this.update(&mut cx, |this, cx| {
+            let Some((new_mtime, new_bytes, encoding)) = this.update(&mut cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
-                Some((file.mtime(), file.load(cx)))
+                Some((file.mtime(), file.load_bytes(cx), this.encoding))
             })?
             else {
                 return Ok(());
             };
 
-            let new_text = new_text.await?;
+            let new_bytes = new_bytes.await?;
+            let new_text = cx
+                .background_executor()
+                .spawn(async move { encoding.decode(&new_bytes).into_owned() })
+                .await;
             let diff = this
                 .update(&mut cx, |this, cx| this.diff(new_text.clone(), cx))?
                 .await;
@@ -922,6 +970,9 @@ impl Buffer {
     /// Sets the text that will be used to compute a Git diff
     /// against the buffer text.
     pub fn set_diff_base(&mut self, diff_base: Option<String>, cx: &mut ModelContext<Self>) {
+        if self.is_large_file {
+            return;
+        }
         self.diff_base = diff_base
             .map(|mut raw_diff_base| {
                 LineEnding::normalize(&mut raw_diff_base);
@@ -1032,7 +1083,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut ModelContext<Self>) {
-        if self.parsing_in_background {
+        if self.parsing_in_background || self.is_large_file {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
@@ -2096,6 +2147,27 @@ impl Buffer {
         redone
     }
 
+    /// Returns the buffer to a state from its undo tree, which may be on a branch that was
+    /// abandoned by editing after an undo. `None` is the state the buffer was opened with.
+    pub fn jump_to_undo_state(
+        &mut self,
+        target: Option<TransactionId>,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        let was_dirty = self.is_dirty();
+        let old_version = self.version.clone();
+
+        let operations = self.text.jump_to_undo_state(target);
+        let changed = !operations.is_empty();
+        for operation in operations {
+            self.send_operation(Operation::Buffer(operation), cx);
+        }
+        if changed {
+            self.did_edit(&old_version, was_dirty, cx)
+        }
+        changed
+    }
+
     /// Override current completion triggers with the user-provided completion triggers.
     pub fn set_completion_triggers(&mut self, triggers: Vec<String>, cx: &mut ModelContext<Self>) {
         self.completion_triggers.clone_from(&triggers);
@@ -73,7 +73,7 @@ pub use language_registry::{
 pub use lsp::LanguageServerId;
 pub use outline::{render_item, Outline, OutlineItem};
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer};
-pub use text::{AnchorRangeExt, LineEnding};
+pub use text::{AnchorRangeExt, Encoding, LineEnding};
 pub use tree_sitter::{Node, Parser, Tree, TreeCursor};
 
 /// Initializes the `language` crate.
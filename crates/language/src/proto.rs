@@ -6,6 +6,7 @@ use clock::ReplicaId;
 use lsp::{DiagnosticSeverity, LanguageServerId};
 use rpc::proto;
 use std::{ops::Range, sync::Arc};
+use sum_tree::TreeMap;
 use text::*;
 
 pub use proto::{BufferState, Operation};
@@ -540,3 +541,87 @@ pub fn serialize_version(version: &clock::Global) -> Vec<proto::VectorClockEntry
         })
         .collect()
 }
+
+/// Serializes a buffer's undo history so that it can be persisted.
+pub fn serialize_history(
+    history: &SerializedHistory,
+    content_hash: Vec<u8>,
+) -> proto::BufferHistory {
+    proto::BufferHistory {
+        content_hash,
+        base_text: history.base_text.to_string(),
+        operations: history
+            .operations
+            .values()
+            .map(|operation| serialize_operation(&crate::Operation::Buffer(operation.clone())))
+            .collect(),
+        undo_stack: history
+            .undo_stack
+            .iter()
+            .map(serialize_transaction)
+            .collect(),
+        redo_stack: history
+            .redo_stack
+            .iter()
+            .map(serialize_transaction)
+            .collect(),
+        undo_tree: history
+            .undo_tree
+            .nodes()
+            .map(|node| proto::buffer_history::UndoTreeNode {
+                transaction: Some(serialize_transaction(&node.transaction)),
+                parent: node.parent.map(serialize_timestamp),
+                sequence: node.sequence as u64,
+                created_at: Some(node.created_at.into()),
+            })
+            .collect(),
+        current: history.undo_tree.current().map(serialize_timestamp),
+    }
+}
+
+/// Deserializes a buffer's undo history from its persisted representation.
+pub fn deserialize_history(message: proto::BufferHistory) -> Result<SerializedHistory> {
+    let mut operations = TreeMap::default();
+    for operation in message.operations {
+        match deserialize_operation(operation)? {
+            crate::Operation::Buffer(operation) => {
+                operations.insert(operation.timestamp(), operation)
+            }
+            _ => return Err(anyhow!("unexpected operation in buffer history")),
+        }
+    }
+    let undo_tree = message
+        .undo_tree
+        .into_iter()
+        .map(|node| {
+            Ok(UndoTreeNode {
+                transaction: deserialize_transaction(
+                    node.transaction
+                        .ok_or_else(|| anyhow!("missing undo tree transaction"))?,
+                )?,
+                parent: node.parent.map(deserialize_timestamp),
+                sequence: node.sequence as usize,
+                created_at: node
+                    .created_at
+                    .map(Into::into)
+                    .unwrap_or(std::time::UNIX_EPOCH),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(SerializedHistory {
+        base_text: Rope::from(message.base_text.as_str()),
+        operations,
+        undo_stack: message
+            .undo_stack
+            .into_iter()
+            .map(deserialize_transaction)
+            .collect::<Result<_>>()?,
+        redo_stack: message
+            .redo_stack
+            .into_iter()
+            .map(deserialize_transaction)
+            .collect::<Result<_>>()?,
+        undo_tree: UndoTree::from_nodes(undo_tree, message.current.map(deserialize_timestamp)),
+    })
+}
@@ -0,0 +1,281 @@
+use crate::{
+    persistence::{SnapshotEntry, LOCAL_HISTORY},
+    restore_snapshot, ShowFileHistory, SnapshotDiff,
+};
+use editor::Editor;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, ParentElement,
+    Render, Styled, View, ViewContext, VisualContext, WeakView,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use std::{path::PathBuf, sync::Arc};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+/// Lists the snapshots in the local history of the active file, newest first.
+pub struct HistoryPicker {
+    picker: View<Picker<HistoryPickerDelegate>>,
+}
+
+impl HistoryPicker {
+    pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &ShowFileHistory, cx| {
+            Self::toggle(workspace, cx);
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> Option<()> {
+        let buffer = workspace
+            .active_item(cx)?
+            .act_as::<Editor>(cx)?
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()?;
+        let abs_path = buffer.read(cx).file()?.as_local()?.abs_path(cx);
+        let snapshots = LOCAL_HISTORY.snapshots(abs_path.clone()).log_err()?;
+        let weak_workspace = workspace.weak_handle();
+
+        workspace.toggle_modal(cx, move |cx| {
+            HistoryPicker::new(buffer, abs_path, snapshots, weak_workspace, cx)
+        });
+        Some(())
+    }
+
+    fn new(
+        buffer: Model<Buffer>,
+        abs_path: PathBuf,
+        snapshots: Vec<SnapshotEntry>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let now = OffsetDateTime::now_utc();
+        let timezone = cx.local_timezone();
+        let entries = snapshots
+            .into_iter()
+            .map(|snapshot| {
+                let relative = time_format::format_localized_timestamp(
+                    snapshot.timestamp,
+                    now,
+                    timezone,
+                    TimestampFormat::Relative,
+                );
+                let absolute = time_format::format_localized_timestamp(
+                    snapshot.timestamp,
+                    now,
+                    timezone,
+                    TimestampFormat::EnhancedAbsolute,
+                );
+                HistoryEntry {
+                    label: format!("{relative} · {}", snapshot.source.label()),
+                    absolute_time: absolute,
+                    snapshot,
+                }
+            })
+            .collect();
+
+        let delegate = HistoryPickerDelegate {
+            history_picker: cx.view().downgrade(),
+            workspace,
+            buffer,
+            abs_path,
+            entries,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for HistoryPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for HistoryPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for HistoryPicker {}
+impl ModalView for HistoryPicker {}
+
+struct HistoryEntry {
+    snapshot: SnapshotEntry,
+    label: String,
+    absolute_time: String,
+}
+
+pub struct HistoryPickerDelegate {
+    history_picker: WeakView<HistoryPicker>,
+    workspace: WeakView<Workspace>,
+    buffer: Model<Buffer>,
+    abs_path: PathBuf,
+    entries: Vec<HistoryEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for HistoryPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        let file_name = self
+            .abs_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        format!("Local history of {file_name}...").into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No snapshots of this file".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// Opens the selected snapshot compared with the buffer, or restores it right away
+    /// when the secondary confirm is used.
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(mat) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        let entry = &self.entries[mat.candidate_id];
+
+        if secondary {
+            restore_snapshot(&self.buffer, entry.snapshot.id, cx).detach_and_log_err(cx);
+        } else if let Some(workspace) = self.workspace.upgrade() {
+            let buffer = self.buffer.clone();
+            let snapshot = entry.snapshot.clone();
+            let title = format!(
+                "{} ({})",
+                self.abs_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                entry.label
+            );
+            let snapshot_id = snapshot.id;
+            let workspace = workspace.downgrade();
+            let content = cx
+                .background_executor()
+                .spawn(async move { LOCAL_HISTORY.snapshot_content(snapshot_id.0) });
+            cx.spawn(|_, mut cx| async move {
+                let Some(content) = content.await.log_err().flatten() else {
+                    return;
+                };
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        let project = workspace.project().clone();
+                        let diff = cx.new_view(|cx| {
+                            SnapshotDiff::new(buffer, snapshot, content, title, project, cx)
+                        });
+                        workspace.add_item_to_active_pane(Box::new(diff), None, cx);
+                    })
+                    .log_err();
+            })
+            .detach();
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.history_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| StringMatchCandidate::new(id, entry.label.clone()))
+            .collect::<Vec<_>>();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                // Keep the timeline in chronological order rather than by score.
+                matches.sort_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let entry = &self.entries[mat.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .end_slot(
+                    Label::new(entry.absolute_time.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        )
+    }
+}
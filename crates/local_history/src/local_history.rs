@@ -0,0 +1,387 @@
+mod history_picker;
+mod persistence;
+mod snapshot_diff;
+
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use editor::{Editor, EditorEvent};
+use gpui::{
+    actions, AppContext, Context, EntityId, Global, Model, ModelContext, Subscription, Task,
+    ViewContext,
+};
+use language::Buffer;
+use persistence::{Retention, LOCAL_HISTORY};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use std::time::Duration;
+use time::OffsetDateTime;
+use util::ResultExt;
+
+pub use history_picker::HistoryPicker;
+pub use persistence::{SnapshotEntry, SnapshotId, SnapshotSource};
+pub use snapshot_diff::SnapshotDiff;
+
+actions!(local_history, [ShowFileHistory, RestoreSnapshot]);
+
+pub fn init(cx: &mut AppContext) {
+    LocalHistorySettings::register(cx);
+
+    let local_history = cx.new_model(|_| LocalHistory::default());
+    cx.set_global(GlobalLocalHistory(local_history));
+
+    cx.observe_new_views(|editor: &mut Editor, cx: &mut ViewContext<Editor>| {
+        // Multibuffers can save any of the files they show, including ones added to them later.
+        let buffers = editor.buffer().read(cx).all_buffers();
+        LocalHistory::global(cx).update(cx, |history, cx| {
+            for buffer in buffers {
+                history.track_buffer(buffer, cx);
+            }
+        });
+        let editor = cx.view().clone();
+        cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+            if let EditorEvent::ExcerptsAdded { buffer, .. } = event {
+                LocalHistory::global(cx)
+                    .update(cx, |history, cx| history.track_buffer(buffer.clone(), cx));
+            }
+        })
+        .detach();
+    })
+    .detach();
+    cx.observe_new_views(HistoryPicker::register).detach();
+}
+
+/// Settings for the local history of files, which is kept independently of version control.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LocalHistorySettings {
+    /// Whether to take snapshots of files when they are opened, saved or changed on disk.
+    ///
+    /// Default: true
+    pub enabled: bool,
+    /// The number of snapshots to keep for each file. Older snapshots are discarded first.
+    ///
+    /// Default: 50
+    pub max_snapshots_per_file: usize,
+    /// The number of days after which snapshots are discarded.
+    ///
+    /// Default: 30
+    pub max_age_days: u64,
+    /// Files larger than this many kilobytes are not snapshotted.
+    ///
+    /// Default: 1024
+    pub max_file_size_kb: u64,
+}
+
+impl Default for LocalHistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_snapshots_per_file: 50,
+            max_age_days: 30,
+            max_file_size_kb: 1024,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LocalHistorySettingsContent {
+    /// Whether to take snapshots of files when they are opened, saved or changed on disk.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The number of snapshots to keep for each file. Older snapshots are discarded first.
+    ///
+    /// Default: 50
+    pub max_snapshots_per_file: Option<usize>,
+    /// The number of days after which snapshots are discarded.
+    ///
+    /// Default: 30
+    pub max_age_days: Option<u64>,
+    /// Files larger than this many kilobytes are not snapshotted.
+    ///
+    /// Default: 1024
+    pub max_file_size_kb: Option<u64>,
+}
+
+impl Settings for LocalHistorySettings {
+    const KEY: Option<&'static str> = Some("local_history");
+
+    type FileContent = LocalHistorySettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
+struct GlobalLocalHistory(Model<LocalHistory>);
+
+impl Global for GlobalLocalHistory {}
+
+/// Records a snapshot of every local buffer's file whenever it is opened, saved, or
+/// changed by another program.
+#[derive(Default)]
+pub struct LocalHistory {
+    tracked_buffers: HashMap<EntityId, [Subscription; 2]>,
+}
+
+impl LocalHistory {
+    pub fn global(cx: &AppContext) -> Model<Self> {
+        cx.global::<GlobalLocalHistory>().0.clone()
+    }
+
+    fn track_buffer(&mut self, buffer: Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer_id = buffer.entity_id();
+        if self.tracked_buffers.contains_key(&buffer_id) {
+            return;
+        }
+
+        let subscriptions = [
+            cx.subscribe(&buffer, |this, buffer, event, cx| match event {
+                language::Event::Saved => this.record(&buffer, SnapshotSource::Saved, cx),
+                language::Event::Reloaded => {
+                    this.record(&buffer, SnapshotSource::ExternalChange, cx)
+                }
+                // Buffers with unsaved edits aren't reloaded when their file changes, so the
+                // new contents have to be read from disk to be captured.
+                language::Event::FileHandleChanged if buffer.read(cx).is_dirty() => {
+                    this.record_from_disk(&buffer, cx)
+                }
+                _ => {}
+            }),
+            cx.observe_release(&buffer, move |this, _, _| {
+                this.tracked_buffers.remove(&buffer_id);
+            }),
+        ];
+        self.tracked_buffers.insert(buffer_id, subscriptions);
+
+        // The contents a file had when it was opened are kept too, so that the very first
+        // save can be undone.
+        self.record(&buffer, SnapshotSource::Opened, cx);
+    }
+
+    fn record(
+        &mut self,
+        buffer: &Model<Buffer>,
+        source: SnapshotSource,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let text = buffer.read(cx).text();
+        self.record_text(buffer, text, source, cx);
+    }
+
+    fn record_from_disk(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let Some(file) = buffer.read(cx).file().and_then(|file| file.as_local()) else {
+            return;
+        };
+        let load = file.load(cx);
+        let buffer = buffer.downgrade();
+        cx.spawn(|this, mut cx| async move {
+            // The file may have been deleted, which leaves nothing to record.
+            let Some(text) = load.await.ok() else {
+                return;
+            };
+            if let Some(buffer) = buffer.upgrade() {
+                this.update(&mut cx, |this, cx| {
+                    this.record_text(&buffer, text, SnapshotSource::ExternalChange, cx)
+                })
+                .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn record_text(
+        &mut self,
+        buffer: &Model<Buffer>,
+        text: String,
+        source: SnapshotSource,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let settings = LocalHistorySettings::get_global(cx);
+        let buffer = buffer.read(cx);
+        if !settings.enabled
+            || buffer.is_large_file()
+            || text.len() as u64 > settings.max_file_size_kb * 1024
+        {
+            return;
+        }
+        let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+            return;
+        };
+        // Private files, such as ones holding credentials, are never copied into the history.
+        if file.is_private() {
+            return;
+        }
+
+        let abs_path = file.abs_path(cx);
+        let timestamp = OffsetDateTime::now_utc();
+        let retention = Retention {
+            max_snapshots_per_file: settings.max_snapshots_per_file,
+            oldest_timestamp: timestamp - Duration::from_secs(settings.max_age_days * 24 * 60 * 60),
+        };
+        cx.background_executor()
+            .spawn(async move {
+                LOCAL_HISTORY
+                    .record_snapshot(abs_path, text, source, timestamp, retention)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+}
+
+/// Replaces the buffer's text with the content of a snapshot, as an edit that can be undone
+/// and isn't written to disk until the buffer is saved.
+pub fn restore_snapshot(
+    buffer: &Model<Buffer>,
+    snapshot_id: SnapshotId,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    let content = cx
+        .background_executor()
+        .spawn(async move { LOCAL_HISTORY.snapshot_content(snapshot_id.0) });
+    let buffer = buffer.clone();
+    cx.spawn(|mut cx| async move {
+        let content = content
+            .await?
+            .ok_or_else(|| anyhow!("snapshot {snapshot_id:?} no longer exists"))?;
+        buffer.update(&mut cx, |buffer, cx| {
+            buffer.finalize_last_transaction();
+            buffer.set_text(content, cx);
+            buffer.finalize_last_transaction();
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+
+    #[gpui::test]
+    async fn test_snapshots_on_save_and_external_change(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/history", json!({ "a.txt": "one" })).await;
+        let project = Project::test(fs.clone(), ["/history".as_ref()], cx).await;
+        let buffer = open_tracked_buffer(&project, "/history/a.txt", cx).await;
+
+        buffer.update(cx, |buffer, cx| buffer.set_text("two", cx));
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+
+        // Unsaved edits are kept in the buffer, and the new contents are read from disk.
+        buffer.update(cx, |buffer, cx| buffer.set_text("three", cx));
+        fs.insert_file("/history/a.txt", b"four".to_vec()).await;
+        let snapshots = snapshot_contents("/history/a.txt", cx).await;
+        assert_eq!(
+            snapshots
+                .iter()
+                .map(|(_, source, content)| (*source, content.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (SnapshotSource::ExternalChange, "four"),
+                (SnapshotSource::Saved, "two"),
+                (SnapshotSource::Opened, "one"),
+            ]
+        );
+        assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), "three");
+
+        // Restoring a snapshot is an edit of the buffer that can be undone.
+        let (opened_id, _, _) = snapshots[2];
+        cx.update(|cx| restore_snapshot(&buffer, opened_id, cx))
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| {
+            assert_eq!(buffer.text(), "one");
+            assert!(buffer.is_dirty());
+            buffer.undo(cx);
+            assert_eq!(buffer.text(), "three");
+        });
+    }
+
+    #[gpui::test]
+    async fn test_private_and_large_files_are_not_snapshotted(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/skipped",
+            json!({
+                ".env": "TOKEN=old",
+                "large.txt": "x".repeat(2048),
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/skipped".as_ref()], cx).await;
+
+        for path in ["/skipped/.env", "/skipped/large.txt"] {
+            let buffer = open_tracked_buffer(&project, path, cx).await;
+            buffer.update(cx, |buffer, cx| buffer.set_text("y".repeat(2048), cx));
+            project
+                .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+                .await
+                .unwrap();
+            assert_eq!(snapshot_contents(path, cx).await, [], "{path}");
+        }
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            init(cx);
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<LocalHistorySettings>(cx, |settings| {
+                    settings.max_file_size_kb = Some(1);
+                });
+            });
+        });
+    }
+
+    async fn open_tracked_buffer(
+        project: &Model<Project>,
+        path: &str,
+        cx: &mut TestAppContext,
+    ) -> Model<Buffer> {
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        cx.update(|cx| {
+            LocalHistory::global(cx)
+                .update(cx, |history, cx| history.track_buffer(buffer.clone(), cx))
+        });
+        buffer
+    }
+
+    /// The snapshots of a file, newest first, once the ones recorded so far are written.
+    async fn snapshot_contents(
+        path: &str,
+        cx: &mut TestAppContext,
+    ) -> Vec<(SnapshotId, SnapshotSource, String)> {
+        cx.run_until_parked();
+        // Writes are queued on the database's thread, so this one completes after them.
+        LOCAL_HISTORY.write(|_| ()).await;
+        LOCAL_HISTORY
+            .snapshots(Path::new(path).to_path_buf())
+            .unwrap()
+            .into_iter()
+            .map(|snapshot| {
+                let content = LOCAL_HISTORY
+                    .snapshot_content(snapshot.id.0)
+                    .unwrap()
+                    .unwrap();
+                (snapshot.id, snapshot.source, content)
+            })
+            .collect()
+    }
+}
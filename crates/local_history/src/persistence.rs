@@ -0,0 +1,214 @@
+use anyhow::Result;
+use db::{define_connection, query, sqlez_macros::sql};
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+define_connection!(pub static ref LOCAL_HISTORY: LocalHistoryDb<()> =
+    &[sql!(
+        CREATE TABLE IF NOT EXISTS file_snapshots(
+            snapshot_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            abs_path BLOB NOT NULL,
+            timestamp INTEGER NOT NULL,
+            source TEXT NOT NULL,
+            content TEXT NOT NULL
+        ) STRICT;
+        CREATE INDEX IF NOT EXISTS file_snapshots_by_path ON file_snapshots(abs_path, snapshot_id);
+    )];
+);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SnapshotId(pub i64);
+
+/// What caused a snapshot of a file to be taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotSource {
+    /// The file was opened, and this is the content it had on disk.
+    Opened,
+    /// The file was saved.
+    Saved,
+    /// The file was changed on disk by another program.
+    ExternalChange,
+}
+
+impl SnapshotSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotSource::Opened => "opened",
+            SnapshotSource::Saved => "saved",
+            SnapshotSource::ExternalChange => "external_change",
+        }
+    }
+
+    fn from_str(source: &str) -> Self {
+        match source {
+            "saved" => SnapshotSource::Saved,
+            "external_change" => SnapshotSource::ExternalChange,
+            _ => SnapshotSource::Opened,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SnapshotSource::Opened => "Opened",
+            SnapshotSource::Saved => "Saved",
+            SnapshotSource::ExternalChange => "Changed on disk",
+        }
+    }
+}
+
+/// A snapshot of a file's content, without the content itself.
+#[derive(Clone, Debug)]
+pub struct SnapshotEntry {
+    pub id: SnapshotId,
+    pub timestamp: OffsetDateTime,
+    pub source: SnapshotSource,
+}
+
+/// Limits on how many snapshots are kept.
+pub struct Retention {
+    pub max_snapshots_per_file: usize,
+    pub oldest_timestamp: OffsetDateTime,
+}
+
+impl LocalHistoryDb {
+    /// Stores a snapshot of a file unless it matches the file's latest snapshot, then
+    /// discards the snapshots that are no longer retained. Returns whether a snapshot
+    /// was stored.
+    pub async fn record_snapshot(
+        &self,
+        abs_path: PathBuf,
+        content: String,
+        source: SnapshotSource,
+        timestamp: OffsetDateTime,
+        retention: Retention,
+    ) -> Result<bool> {
+        self.write(move |conn| {
+            conn.with_savepoint("record_snapshot", || {
+                let latest_content = conn.select_row_bound::<&Path, String>(sql!(
+                    SELECT content FROM file_snapshots
+                    WHERE abs_path = ?
+                    ORDER BY snapshot_id DESC
+                    LIMIT 1
+                ))?(abs_path.as_path())?;
+                if latest_content.as_deref() == Some(content.as_str()) {
+                    return Ok(false);
+                }
+
+                conn.exec_bound(sql!(
+                    INSERT INTO file_snapshots(abs_path, timestamp, source, content)
+                    VALUES (?, ?, ?, ?)
+                ))?((
+                    abs_path.as_path(),
+                    timestamp.unix_timestamp(),
+                    source.as_str(),
+                    content.as_str(),
+                ))?;
+                conn.exec_bound(sql!(
+                    DELETE FROM file_snapshots
+                    WHERE abs_path = ?1 AND snapshot_id NOT IN (
+                        SELECT snapshot_id FROM file_snapshots
+                        WHERE abs_path = ?1
+                        ORDER BY snapshot_id DESC
+                        LIMIT ?2
+                    )
+                ))?((abs_path.as_path(), retention.max_snapshots_per_file))?;
+                conn.exec_bound(sql!(
+                    DELETE FROM file_snapshots WHERE timestamp < ?
+                ))?(retention.oldest_timestamp.unix_timestamp())?;
+                Ok(true)
+            })
+        })
+        .await
+    }
+
+    /// Returns the snapshots of a file, newest first.
+    pub fn snapshots(&self, abs_path: PathBuf) -> Result<Vec<SnapshotEntry>> {
+        Ok(self
+            .snapshot_rows(abs_path)?
+            .into_iter()
+            .filter_map(|(id, timestamp, source)| {
+                Some(SnapshotEntry {
+                    id: SnapshotId(id),
+                    timestamp: OffsetDateTime::from_unix_timestamp(timestamp).ok()?,
+                    source: SnapshotSource::from_str(&source),
+                })
+            })
+            .collect())
+    }
+
+    query! {
+        fn snapshot_rows(abs_path: PathBuf) -> Result<Vec<(i64, i64, String)>> {
+            SELECT snapshot_id, timestamp, source
+            FROM file_snapshots
+            WHERE abs_path = ?
+            ORDER BY snapshot_id DESC
+        }
+    }
+
+    query! {
+        pub fn snapshot_content(snapshot_id: i64) -> Result<Option<String>> {
+            SELECT content FROM file_snapshots WHERE snapshot_id = ?
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[gpui::test]
+    async fn test_record_snapshots() {
+        let db = LocalHistoryDb(db::open_test_db("test_record_snapshots").await);
+        let path = PathBuf::from("/root/a.txt");
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let retention = || Retention {
+            max_snapshots_per_file: 2,
+            oldest_timestamp: now - Duration::from_secs(60),
+        };
+
+        for (content, expected) in [
+            ("one", true),
+            ("one", false),
+            ("two", true),
+            ("three", true),
+        ] {
+            let recorded = db
+                .record_snapshot(
+                    path.clone(),
+                    content.to_string(),
+                    SnapshotSource::Saved,
+                    now,
+                    retention(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(recorded, expected, "recording {content:?}");
+        }
+
+        // Identical consecutive snapshots are stored once, and only the newest are kept.
+        let snapshots = db.snapshots(path.clone()).unwrap();
+        let contents = snapshots
+            .iter()
+            .map(|snapshot| db.snapshot_content(snapshot.id.0).unwrap().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(contents, ["three", "two"]);
+
+        // Snapshots older than the retention period are discarded.
+        let later = now + Duration::from_secs(120);
+        db.record_snapshot(
+            PathBuf::from("/root/b.txt"),
+            "b".to_string(),
+            SnapshotSource::Opened,
+            later,
+            Retention {
+                max_snapshots_per_file: 2,
+                oldest_timestamp: later - Duration::from_secs(60),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(db.snapshots(path).unwrap().is_empty());
+        assert_eq!(db.snapshots(PathBuf::from("/root/b.txt")).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{persistence::SnapshotEntry, restore_snapshot, RestoreSnapshot};
+use editor::{actions::ExpandAllHunkDiffs, Editor, EditorEvent};
+use gpui::{
+    AnyElement, AnyView, AppContext, EventEmitter, FocusHandle, FocusableView, Model, Render,
+    SharedString, Subscription, View,
+};
+use language::Buffer;
+use project::Project;
+use std::any::TypeId;
+use ui::prelude::*;
+use workspace::item::{Item, TabContentParams};
+
+/// Compares a snapshot from the local history with the current content of its buffer.
+///
+/// The buffer's text is copied into a read-only scratch buffer whose diff base is the
+/// snapshot, so the differences are shown as expanded diff hunks without touching the
+/// git diff of the buffer itself. The copy follows edits made to the buffer.
+pub struct SnapshotDiff {
+    editor: View<Editor>,
+    buffer: Model<Buffer>,
+    snapshot: SnapshotEntry,
+    title: SharedString,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl SnapshotDiff {
+    pub fn new(
+        buffer: Model<Buffer>,
+        snapshot: SnapshotEntry,
+        snapshot_content: String,
+        title: impl Into<SharedString>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let language = buffer.read(cx).language().cloned();
+        let text = buffer.read(cx).text();
+        let current = cx.new_model(|cx| {
+            let mut current = Buffer::local(text, cx);
+            current.set_language(language, cx);
+            current.set_diff_base(Some(snapshot_content), cx);
+            current
+        });
+
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(current.clone(), Some(project), cx);
+            editor.set_read_only(true);
+            editor
+        });
+
+        let subscriptions = vec![cx.subscribe(&buffer, {
+            let current = current.clone();
+            move |this, buffer, event, cx| {
+                if let language::Event::Edited | language::Event::Reloaded = event {
+                    let text = buffer.read(cx).text();
+                    current.update(cx, |current, cx| {
+                        current.set_text(text, cx);
+                    });
+                    this.refresh_diff(&current, cx);
+                }
+            }
+        })];
+
+        let this = Self {
+            editor,
+            buffer,
+            snapshot,
+            title: title.into(),
+            _subscriptions: subscriptions,
+        };
+        this.refresh_diff(&current, cx);
+        this
+    }
+
+    fn refresh_diff(&self, current: &Model<Buffer>, cx: &mut ViewContext<Self>) {
+        let Some(recalc) = current.update(cx, |current, cx| current.git_diff_recalc(cx)) else {
+            return;
+        };
+        let editor = self.editor.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            recalc.await;
+            editor.update(&mut cx, |editor, cx| {
+                editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn restore(&mut self, _: &RestoreSnapshot, cx: &mut ViewContext<Self>) {
+        restore_snapshot(&self.buffer, self.snapshot.id, cx).detach_and_log_err(cx);
+    }
+}
+
+impl EventEmitter<EditorEvent> for SnapshotDiff {}
+
+impl FocusableView for SnapshotDiff {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for SnapshotDiff {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("SnapshotDiff")
+            .size_full()
+            .on_action(cx.listener(Self::restore))
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(self.title.clone()))
+                    .child(
+                        Button::new("restore", "Restore This Version")
+                            .icon(IconName::HistoryRerun)
+                            .icon_position(IconPosition::Start)
+                            .style(ButtonStyle::Filled)
+                            .on_click(
+                                cx.listener(|this, _, cx| this.restore(&RestoreSnapshot, cx)),
+                            ),
+                    ),
+            )
+            .child(self.editor.clone())
+    }
+}
+
+impl Item for SnapshotDiff {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(workspace::item::ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new(self.title.clone())
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some("Local History".into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("local history diff")
+    }
+
+    fn display_name(&self) -> Option<&'static str> {
+        Some("local history diff")
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn navigate(&mut self, data: Box<dyn std::any::Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+}
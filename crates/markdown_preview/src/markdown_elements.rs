@@ -2,7 +2,11 @@ use gpui::{
     px, FontStyle, FontWeight, HighlightStyle, SharedString, StrikethroughStyle, UnderlineStyle,
 };
 use language::HighlightId;
-use std::{fmt::Display, ops::Range, path::PathBuf};
+use std::{
+    fmt::Display,
+    ops::Range,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -15,6 +19,11 @@ pub enum ParsedMarkdownElement {
     /// A paragraph of text and other inline elements.
     Paragraph(ParsedMarkdownText),
     HorizontalRule(Range<usize>),
+    Image(ParsedMarkdownImage),
+    /// A collapsible `<details>` section.
+    Details(ParsedMarkdownDetails),
+    /// A footnote definition, moved to the end of the document.
+    Footnote(ParsedMarkdownFootnote),
 }
 
 impl ParsedMarkdownElement {
@@ -27,6 +36,9 @@ impl ParsedMarkdownElement {
             Self::CodeBlock(code_block) => code_block.source_range.clone(),
             Self::Paragraph(text) => text.source_range.clone(),
             Self::HorizontalRule(range) => range.clone(),
+            Self::Image(image) => image.source_range.clone(),
+            Self::Details(details) => details.source_range.clone(),
+            Self::Footnote(footnote) => footnote.source_range.clone(),
         }
     }
 
@@ -129,6 +141,42 @@ pub struct ParsedMarkdownBlockQuote {
     pub children: Vec<ParsedMarkdownElement>,
 }
 
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownImage {
+    pub source_range: Range<usize>,
+    /// Where the image is loaded from, either a URL or a local file.
+    pub link: Link,
+    pub alt_text: Option<SharedString>,
+    /// The width in pixels requested by an HTML `<img>` tag.
+    pub width: Option<f32>,
+    /// The height in pixels requested by an HTML `<img>` tag.
+    pub height: Option<f32>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownDetails {
+    pub source_range: Range<usize>,
+    /// The contents of the `<summary>` tag, shown while the section is collapsed.
+    pub summary: ParsedMarkdownText,
+    /// Whether the section starts out expanded, as with `<details open>`.
+    pub open: bool,
+    pub children: Vec<ParsedMarkdownElement>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownFootnote {
+    pub source_range: Range<usize>,
+    pub label: String,
+    /// The number shown for the footnote, in order of first reference.
+    pub number: usize,
+    pub children: Vec<ParsedMarkdownElement>,
+    /// Where the footnote is first referenced in the source Markdown document.
+    pub backlink: Option<usize>,
+}
+
 #[derive(Debug)]
 pub struct ParsedMarkdownText {
     /// Where the text is located in the source Markdown document.
@@ -228,6 +276,18 @@ pub enum Link {
         /// The absolute path to the item.
         path: PathBuf,
     },
+    /// A reference to a footnote defined at the end of the document.
+    Footnote {
+        /// The label of the footnote, as written in the Markdown document.
+        label: String,
+        /// The number shown for the footnote.
+        number: usize,
+    },
+    /// A link from a footnote back to where it is referenced.
+    FootnoteBacklink {
+        /// The offset of the reference in the source Markdown document.
+        source_offset: usize,
+    },
 }
 
 impl Link {
@@ -254,6 +314,34 @@ impl Link {
 
         None
     }
+
+    /// Resolves the source of an image. Paths starting with `/` are resolved against
+    /// the root of the worktree, other relative paths against the file's directory.
+    /// Remote images are never fetched, so that previewing a file doesn't reach out to
+    /// whoever hosts them.
+    pub fn identify_image(
+        file_location_directory: Option<&Path>,
+        worktree_root: Option<&Path>,
+        text: &str,
+    ) -> Option<Link> {
+        if text.contains("://") || text.starts_with("data:") {
+            return None;
+        }
+
+        let display_path = PathBuf::from(text);
+        let path = if let Some(root_relative) = text.strip_prefix('/') {
+            match worktree_root {
+                Some(worktree_root) => worktree_root.join(root_relative),
+                None => display_path.clone(),
+            }
+        } else if display_path.is_absolute() {
+            display_path.clone()
+        } else {
+            file_location_directory?.join(&display_path)
+        };
+
+        path.exists().then(|| Link::Path { display_path, path })
+    }
 }
 
 impl Display for Link {
@@ -264,6 +352,8 @@ impl Display for Link {
                 display_path,
                 path: _,
             } => write!(f, "{}", display_path.display()),
+            Link::Footnote { label, .. } => write!(f, "Footnote: {}", label),
+            Link::FootnoteBacklink { .. } => write!(f, "Back to reference"),
         }
     }
 }
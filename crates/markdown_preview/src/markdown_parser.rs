@@ -4,11 +4,12 @@ use collections::FxHashMap;
 use gpui::FontWeight;
 use language::LanguageRegistry;
 use pulldown_cmark::{Alignment, Event, Options, Parser, Tag, TagEnd};
-use std::{ops::Range, path::PathBuf, sync::Arc};
+use std::{borrow::Cow, mem, ops::Range, path::PathBuf, sync::Arc};
 
 pub async fn parse_markdown(
     markdown_input: &str,
     file_location_directory: Option<PathBuf>,
+    worktree_root: Option<PathBuf>,
     language_registry: Option<Arc<LanguageRegistry>>,
 ) -> ParsedMarkdown {
     let options = Options::all();
@@ -16,6 +17,7 @@ pub async fn parse_markdown(
     let parser = MarkdownParser::new(
         parser.into_offset_iter().collect(),
         file_location_directory,
+        worktree_root,
         language_registry,
     );
     let renderer = parser.parse_document().await;
@@ -31,21 +33,30 @@ struct MarkdownParser<'a> {
     /// The blocks that we have successfully parsed so far
     parsed: Vec<ParsedMarkdownElement>,
     file_location_directory: Option<PathBuf>,
+    worktree_root: Option<PathBuf>,
     language_registry: Option<Arc<LanguageRegistry>>,
+    /// The number and source offset of the first reference to each footnote label.
+    footnote_references: FxHashMap<String, (usize, usize)>,
+    /// The footnote definitions, which are rendered at the end of the document.
+    footnotes: Vec<ParsedMarkdownFootnote>,
 }
 
 impl<'a> MarkdownParser<'a> {
     fn new(
         tokens: Vec<(Event<'a>, Range<usize>)>,
         file_location_directory: Option<PathBuf>,
+        worktree_root: Option<PathBuf>,
         language_registry: Option<Arc<LanguageRegistry>>,
     ) -> Self {
         Self {
             tokens,
             file_location_directory,
+            worktree_root,
             language_registry,
             cursor: 0,
             parsed: vec![],
+            footnote_references: FxHashMap::default(),
+            footnotes: vec![],
         }
     }
 
@@ -84,6 +95,7 @@ impl<'a> MarkdownParser<'a> {
             // Represent an inline code block
             | Event::Code(_)
             | Event::Html(_)
+            | Event::InlineHtml(_)
             | Event::FootnoteReference(_)
             | Event::Start(Tag::Link { link_type: _, dest_url: _, title: _, id: _ })
             | Event::Start(Tag::Emphasis)
@@ -102,9 +114,27 @@ impl<'a> MarkdownParser<'a> {
                 self.parsed.extend(block);
             }
         }
+        self.append_footnotes();
         self
     }
 
+    fn append_footnotes(&mut self) {
+        let mut footnotes = mem::take(&mut self.footnotes);
+        let mut next_unreferenced_number = self.footnote_references.len() + 1;
+        for footnote in &mut footnotes {
+            if let Some((number, source_offset)) = self.footnote_references.get(&footnote.label) {
+                footnote.number = *number;
+                footnote.backlink = Some(*source_offset);
+            } else {
+                footnote.number = next_unreferenced_number;
+                next_unreferenced_number += 1;
+            }
+        }
+        footnotes.sort_by_key(|footnote| footnote.number);
+        self.parsed
+            .extend(footnotes.into_iter().map(ParsedMarkdownElement::Footnote));
+    }
+
     #[async_recursion]
     async fn parse_block(&mut self) -> Option<Vec<ParsedMarkdownElement>> {
         let (current, source_range) = self.current().unwrap();
@@ -113,8 +143,8 @@ impl<'a> MarkdownParser<'a> {
             Event::Start(tag) => match tag {
                 Tag::Paragraph => {
                     self.cursor += 1;
-                    let text = self.parse_text(false, Some(source_range));
-                    Some(vec![ParsedMarkdownElement::Paragraph(text)])
+                    let paragraph = self.parse_paragraph(source_range).await;
+                    Some(paragraph)
                 }
                 Tag::Heading {
                     level,
@@ -161,6 +191,24 @@ impl<'a> MarkdownParser<'a> {
                     let code_block = self.parse_code_block(language).await;
                     Some(vec![ParsedMarkdownElement::CodeBlock(code_block)])
                 }
+                Tag::Image { dest_url, .. } => {
+                    let dest_url = dest_url.to_string();
+                    self.cursor += 1;
+                    let image = self.parse_image(&dest_url, source_range);
+                    Some(image.into_iter().collect())
+                }
+                Tag::HtmlBlock => {
+                    self.cursor += 1;
+                    let html = self.parse_html_block(source_range).await;
+                    Some(html)
+                }
+                Tag::FootnoteDefinition(label) => {
+                    let label = label.to_string();
+                    self.cursor += 1;
+                    let footnote = self.parse_footnote_definition(label, source_range).await;
+                    self.footnotes.push(footnote);
+                    Some(vec![])
+                }
                 _ => {
                     self.cursor += 1;
                     None
@@ -171,6 +219,11 @@ impl<'a> MarkdownParser<'a> {
                 self.cursor += 1;
                 Some(vec![ParsedMarkdownElement::HorizontalRule(source_range)])
             }
+            Event::InlineHtml(html) => {
+                let tokens = tokenize_html(html);
+                self.cursor += 1;
+                Some(self.html_elements(&tokens, source_range))
+            }
             _ => {
                 self.cursor += 1;
                 None
@@ -178,6 +231,35 @@ impl<'a> MarkdownParser<'a> {
         }
     }
 
+    /// The preview renders images as blocks, so paragraphs containing images are split
+    /// into the text before and after each of them.
+    async fn parse_paragraph(&mut self, source_range: Range<usize>) -> Vec<ParsedMarkdownElement> {
+        let mut elements = vec![];
+
+        loop {
+            let text = self.parse_text(false, Some(source_range.clone()));
+            let paragraph_ended =
+                self.eof() || matches!(self.previous(), Some((Event::End(TagEnd::Paragraph), _)));
+            if !text.contents.trim().is_empty() || (elements.is_empty() && paragraph_ended) {
+                elements.push(ParsedMarkdownElement::Paragraph(text));
+            }
+            if paragraph_ended {
+                break;
+            }
+
+            match self.current_event() {
+                Some(Event::Start(Tag::Image { .. }) | Event::InlineHtml(_)) => {
+                    if let Some(block) = self.parse_block().await {
+                        elements.extend(block);
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        elements
+    }
+
     fn parse_text(
         &mut self,
         should_complete_on_soft_break: bool,
@@ -193,6 +275,8 @@ impl<'a> MarkdownParser<'a> {
         let mut bold_depth = 0;
         let mut italic_depth = 0;
         let mut strikethrough_depth = 0;
+        let mut script: Option<Script> = None;
+        let mut kbd_start: Option<usize> = None;
         let mut link: Option<Link> = None;
         let mut region_ranges: Vec<Range<usize>> = vec![];
         let mut regions: Vec<ParsedRegion> = vec![];
@@ -206,7 +290,7 @@ impl<'a> MarkdownParser<'a> {
                 break;
             }
 
-            let (current, _source_range) = self.current().unwrap();
+            let (current, event_range) = self.current().unwrap();
             let prev_len = text.len();
             match current {
                 Event::SoftBreak => {
@@ -223,7 +307,11 @@ impl<'a> MarkdownParser<'a> {
                 }
 
                 Event::Text(t) => {
-                    text.push_str(t.as_ref());
+                    let t = match script.and_then(|script| script.convert(t)) {
+                        Some(converted) => Cow::Owned(converted),
+                        None => Cow::Borrowed(t.as_ref()),
+                    };
+                    text.push_str(&t);
 
                     let mut style = MarkdownHighlightStyle::default();
 
@@ -327,6 +415,48 @@ impl<'a> MarkdownParser<'a> {
                     });
                 }
 
+                Event::FootnoteReference(label) => {
+                    let label = label.to_string();
+                    let source_offset = event_range.start;
+                    let number = self.footnote_number(&label, source_offset);
+                    text.push_str(&format!("[{}]", number));
+                    region_ranges.push(prev_len..text.len());
+                    regions.push(ParsedRegion {
+                        code: false,
+                        link: Some(Link::Footnote { label, number }),
+                    });
+                    highlights.push((
+                        prev_len..text.len(),
+                        MarkdownHighlight::Style(MarkdownHighlightStyle {
+                            underline: true,
+                            ..Default::default()
+                        }),
+                    ));
+                }
+
+                Event::InlineHtml(html) => match HtmlTag::parse(html) {
+                    // Images are parsed as blocks by `parse_paragraph`.
+                    Some(tag) if tag.name == "img" => break,
+                    Some(tag) => match (tag.name.as_str(), tag.closing) {
+                        ("br", _) => text.push('\n'),
+                        ("kbd", false) => kbd_start = Some(text.len()),
+                        ("kbd", true) => {
+                            if let Some(start) = kbd_start.take() {
+                                region_ranges.push(start..text.len());
+                                regions.push(ParsedRegion {
+                                    code: true,
+                                    link: link.clone(),
+                                });
+                            }
+                        }
+                        ("sup", false) => script = Some(Script::Super),
+                        ("sub", false) => script = Some(Script::Sub),
+                        ("sup" | "sub", true) => script = None,
+                        _ => {}
+                    },
+                    None => {}
+                },
+
                 Event::Start(tag) => match tag {
                     Tag::Emphasis => italic_depth += 1,
                     Tag::Strong => bold_depth += 1,
@@ -718,6 +848,508 @@ impl<'a> MarkdownParser<'a> {
             highlights,
         }
     }
+
+    fn footnote_number(&mut self, label: &str, source_offset: usize) -> usize {
+        let next_number = self.footnote_references.len() + 1;
+        self.footnote_references
+            .entry(label.to_string())
+            .or_insert((next_number, source_offset))
+            .0
+    }
+
+    async fn parse_footnote_definition(
+        &mut self,
+        label: String,
+        source_range: Range<usize>,
+    ) -> ParsedMarkdownFootnote {
+        let mut children = vec![];
+
+        while !self.eof() {
+            if let Some(Event::End(TagEnd::FootnoteDefinition)) = self.current_event() {
+                self.cursor += 1;
+                break;
+            }
+
+            if let Some(block) = self.parse_block().await {
+                children.extend(block);
+            }
+        }
+
+        ParsedMarkdownFootnote {
+            source_range,
+            label,
+            // Numbers and backlinks are assigned once all references have been seen.
+            number: 0,
+            children,
+            backlink: None,
+        }
+    }
+
+    fn parse_image(
+        &mut self,
+        dest_url: &str,
+        source_range: Range<usize>,
+    ) -> Option<ParsedMarkdownElement> {
+        let mut alt_text = String::new();
+
+        while !self.eof() {
+            let (current, _source_range) = self.current().unwrap();
+            match current {
+                Event::Text(text) | Event::Code(text) => {
+                    alt_text.push_str(text);
+                }
+                Event::End(TagEnd::Image) => {
+                    self.cursor += 1;
+                    break;
+                }
+                _ => {}
+            }
+            self.cursor += 1;
+        }
+
+        self.image_element(dest_url, alt_text, None, None, source_range)
+    }
+
+    fn image_element(
+        &self,
+        source: &str,
+        alt_text: String,
+        width: Option<f32>,
+        height: Option<f32>,
+        source_range: Range<usize>,
+    ) -> Option<ParsedMarkdownElement> {
+        let link = Link::identify_image(
+            self.file_location_directory.as_deref(),
+            self.worktree_root.as_deref(),
+            source,
+        );
+
+        match link {
+            Some(link) => Some(ParsedMarkdownElement::Image(ParsedMarkdownImage {
+                source_range,
+                link,
+                alt_text: (!alt_text.is_empty()).then(|| alt_text.into()),
+                width,
+                height,
+            })),
+            // Images that can't be found are replaced by their description.
+            None if !alt_text.is_empty() => Some(ParsedMarkdownElement::Paragraph(plain_text(
+                alt_text,
+                source_range,
+            ))),
+            None => None,
+        }
+    }
+
+    fn collect_html_block(&mut self) -> String {
+        let mut html = String::new();
+
+        while !self.eof() {
+            let (current, _source_range) = self.current().unwrap();
+            match current {
+                Event::Html(text) | Event::Text(text) => {
+                    html.push_str(text);
+                }
+                Event::End(TagEnd::HtmlBlock) => {
+                    self.cursor += 1;
+                    break;
+                }
+                _ => {
+                    break;
+                }
+            }
+            self.cursor += 1;
+        }
+
+        html
+    }
+
+    /// Only a safe subset of HTML is rendered: line breaks, images, `<sub>`/`<sup>` and
+    /// collapsible `<details>` sections. Other tags are dropped and only their text is kept,
+    /// except for the contents of `<script>` and `<style>` tags.
+    ///
+    /// A `<details>` section either ends in the same HTML block, or spans the Markdown blocks
+    /// up to the next HTML block starting with `</details>`.
+    async fn parse_html_block(&mut self, source_range: Range<usize>) -> Vec<ParsedMarkdownElement> {
+        let tokens = tokenize_html(&self.collect_html_block());
+        let Some(details_ix) = tokens
+            .iter()
+            .position(|token| token.is_tag("details", false))
+        else {
+            return self.html_elements(&tokens, source_range);
+        };
+
+        let mut elements = self.html_elements(&tokens[..details_ix], source_range.clone());
+        let open = match &tokens[details_ix] {
+            HtmlToken::Tag(tag) => tag.attribute("open").is_some(),
+            HtmlToken::Text(_) => false,
+        };
+
+        let mut body = &tokens[details_ix + 1..];
+        let mut summary = String::from("Details");
+        let summary_start = body.iter().position(|token| match token {
+            HtmlToken::Text(text) => !text.trim().is_empty(),
+            HtmlToken::Tag(_) => true,
+        });
+        if let Some(summary_start) = summary_start {
+            if body[summary_start].is_tag("summary", false) {
+                let summary_tokens = &body[summary_start + 1..];
+                let summary_end = summary_tokens
+                    .iter()
+                    .position(|token| token.is_tag("summary", true))
+                    .unwrap_or(summary_tokens.len());
+                summary = html_text(&summary_tokens[..summary_end]);
+                body = summary_tokens.get(summary_end + 1..).unwrap_or_default();
+            }
+        }
+
+        let mut details_end = source_range.end;
+        let mut trailing = vec![];
+        let children =
+            if let Some(end) = body.iter().position(|token| token.is_tag("details", true)) {
+                trailing = self.html_elements(&body[end + 1..], source_range.clone());
+                self.html_elements(&body[..end], source_range.clone())
+            } else {
+                let mut children = self.html_elements(body, source_range.clone());
+                while !self.eof() {
+                    if let Some((tokens, range)) = self.parse_details_end() {
+                        details_end = range.end;
+                        trailing = self.html_elements(&tokens, range);
+                        break;
+                    }
+
+                    if let Some(block) = self.parse_block().await {
+                        children.extend(block);
+                    }
+                }
+                children
+            };
+
+        elements.push(ParsedMarkdownElement::Details(ParsedMarkdownDetails {
+            source_range: source_range.start..details_end,
+            summary: plain_text(summary, source_range.clone()),
+            open,
+            children,
+        }));
+        elements.extend(trailing);
+        elements
+    }
+
+    /// Consumes the HTML block closing a `<details>` section, if the cursor is on one, and
+    /// returns the tokens that follow the closing tag.
+    fn parse_details_end(&mut self) -> Option<(Vec<HtmlToken>, Range<usize>)> {
+        let (Event::Start(Tag::HtmlBlock), source_range) = self.current()? else {
+            return None;
+        };
+        let source_range = source_range.clone();
+        let cursor = self.cursor;
+
+        self.cursor += 1;
+        let mut tokens = tokenize_html(&self.collect_html_block());
+        let first_tag = tokens.iter().position(|token| match token {
+            HtmlToken::Text(text) => !text.trim().is_empty(),
+            HtmlToken::Tag(_) => true,
+        });
+        match first_tag {
+            Some(ix) if tokens[ix].is_tag("details", true) => {
+                Some((tokens.split_off(ix + 1), source_range))
+            }
+            _ => {
+                self.cursor = cursor;
+                None
+            }
+        }
+    }
+
+    fn html_elements(
+        &self,
+        tokens: &[HtmlToken],
+        source_range: Range<usize>,
+    ) -> Vec<ParsedMarkdownElement> {
+        let mut elements = vec![];
+        let mut text = String::new();
+        let mut script: Option<Script> = None;
+        let mut skipped_tag: Option<&str> = None;
+
+        let flush = |text: &mut String, elements: &mut Vec<ParsedMarkdownElement>| {
+            let contents = mem::take(text);
+            if !contents.trim().is_empty() {
+                elements.push(ParsedMarkdownElement::Paragraph(plain_text(
+                    contents.trim().to_string(),
+                    source_range.clone(),
+                )));
+            }
+        };
+
+        for token in tokens {
+            match token {
+                HtmlToken::Tag(tag) if skipped_tag.is_some() => {
+                    if tag.closing && skipped_tag == Some(tag.name.as_str()) {
+                        skipped_tag = None;
+                    }
+                }
+                HtmlToken::Text(_) if skipped_tag.is_some() => {}
+                HtmlToken::Text(contents) => {
+                    let converted = script.and_then(|script| script.convert(contents));
+                    push_collapsed_whitespace(&mut text, converted.as_deref().unwrap_or(contents));
+                }
+                HtmlToken::Tag(tag) => match (tag.name.as_str(), tag.closing) {
+                    ("script" | "style", false) => skipped_tag = Some(tag.name.as_str()),
+                    ("br", _) => text.push('\n'),
+                    ("sup", false) => script = Some(Script::Super),
+                    ("sub", false) => script = Some(Script::Sub),
+                    ("sup" | "sub", true) => script = None,
+                    ("img", false) => {
+                        flush(&mut text, &mut elements);
+                        if let Some(source) = tag.attribute("src") {
+                            let alt_text = tag.attribute("alt").unwrap_or_default().to_string();
+                            let width = tag.attribute("width").and_then(|width| width.parse().ok());
+                            let height = tag
+                                .attribute("height")
+                                .and_then(|height| height.parse().ok());
+                            elements.extend(self.image_element(
+                                source,
+                                alt_text,
+                                width,
+                                height,
+                                source_range.clone(),
+                            ));
+                        }
+                    }
+                    (
+                        "p" | "div" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "ul" | "ol" | "li"
+                        | "table" | "tr" | "blockquote" | "pre" | "details" | "summary" | "hr",
+                        _,
+                    ) => flush(&mut text, &mut elements),
+                    _ => {}
+                },
+            }
+        }
+
+        flush(&mut text, &mut elements);
+        elements
+    }
+}
+
+fn plain_text(contents: String, source_range: Range<usize>) -> ParsedMarkdownText {
+    ParsedMarkdownText {
+        source_range,
+        contents,
+        highlights: vec![],
+        region_ranges: vec![],
+        regions: vec![],
+    }
+}
+
+/// Browsers collapse runs of whitespace in HTML text into a single space.
+fn push_collapsed_whitespace(text: &mut String, contents: &str) {
+    for c in contents.chars() {
+        if c.is_whitespace() {
+            if !text.is_empty() && !text.ends_with(char::is_whitespace) {
+                text.push(' ');
+            }
+        } else {
+            text.push(c);
+        }
+    }
+}
+
+fn html_text(tokens: &[HtmlToken]) -> String {
+    let mut text = String::new();
+    for token in tokens {
+        if let HtmlToken::Text(contents) = token {
+            push_collapsed_whitespace(&mut text, contents);
+        }
+    }
+    text.trim().to_string()
+}
+
+/// The preview can't raise or lower text, so the contents of `<sup>` and `<sub>` tags are
+/// shown using Unicode superscript and subscript characters when they all have one.
+#[derive(Clone, Copy)]
+enum Script {
+    Super,
+    Sub,
+}
+
+impl Script {
+    fn convert(self, text: &str) -> Option<String> {
+        text.chars()
+            .map(|c| match self {
+                Script::Super => match c {
+                    '0' => Some('⁰'),
+                    '1' => Some('¹'),
+                    '2' => Some('²'),
+                    '3' => Some('³'),
+                    '4' => Some('⁴'),
+                    '5' => Some('⁵'),
+                    '6' => Some('⁶'),
+                    '7' => Some('⁷'),
+                    '8' => Some('⁸'),
+                    '9' => Some('⁹'),
+                    '+' => Some('⁺'),
+                    '-' => Some('⁻'),
+                    '=' => Some('⁼'),
+                    '(' => Some('⁽'),
+                    ')' => Some('⁾'),
+                    'i' => Some('ⁱ'),
+                    'n' => Some('ⁿ'),
+                    _ => None,
+                },
+                Script::Sub => match c {
+                    '0' => Some('₀'),
+                    '1' => Some('₁'),
+                    '2' => Some('₂'),
+                    '3' => Some('₃'),
+                    '4' => Some('₄'),
+                    '5' => Some('₅'),
+                    '6' => Some('₆'),
+                    '7' => Some('₇'),
+                    '8' => Some('₈'),
+                    '9' => Some('₉'),
+                    '+' => Some('₊'),
+                    '-' => Some('₋'),
+                    '=' => Some('₌'),
+                    '(' => Some('₍'),
+                    ')' => Some('₎'),
+                    'a' => Some('ₐ'),
+                    'e' => Some('ₑ'),
+                    'o' => Some('ₒ'),
+                    'x' => Some('ₓ'),
+                    _ => None,
+                },
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum HtmlToken {
+    Tag(HtmlTag),
+    Text(String),
+}
+
+impl HtmlToken {
+    fn is_tag(&self, name: &str, closing: bool) -> bool {
+        matches!(self, HtmlToken::Tag(tag) if tag.name == name && tag.closing == closing)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct HtmlTag {
+    /// The lowercase name of the tag.
+    name: String,
+    closing: bool,
+    attributes: Vec<(String, String)>,
+}
+
+impl HtmlTag {
+    fn parse(html: &str) -> Option<Self> {
+        let inner = html.trim().strip_prefix('<')?.strip_suffix('>')?;
+        let (closing, inner) = match inner.strip_prefix('/') {
+            Some(inner) => (true, inner),
+            None => (false, inner),
+        };
+        let inner = inner.strip_suffix('/').unwrap_or(inner);
+
+        let name_len = inner
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(inner.len());
+        if name_len == 0 {
+            return None;
+        }
+        let name = inner[..name_len].to_ascii_lowercase();
+
+        let mut attributes = vec![];
+        let mut rest = inner[name_len..].trim_start();
+        while !rest.is_empty() {
+            let key_len = rest
+                .find(|c: char| c.is_whitespace() || c == '=')
+                .unwrap_or(rest.len());
+            if key_len == 0 {
+                break;
+            }
+            let key = rest[..key_len].to_ascii_lowercase();
+            rest = rest[key_len..].trim_start();
+
+            let mut value = String::new();
+            if let Some(after_equals) = rest.strip_prefix('=') {
+                let after_equals = after_equals.trim_start();
+                let (raw_value, remaining) = match after_equals.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let quoted = &after_equals[1..];
+                        let end = quoted.find(quote).unwrap_or(quoted.len());
+                        (&quoted[..end], quoted.get(end + 1..).unwrap_or_default())
+                    }
+                    _ => {
+                        let end = after_equals
+                            .find(char::is_whitespace)
+                            .unwrap_or(after_equals.len());
+                        (&after_equals[..end], &after_equals[end..])
+                    }
+                };
+                value = decode_html_entities(raw_value);
+                rest = remaining.trim_start();
+            }
+
+            attributes.push((key, value));
+        }
+
+        Some(Self {
+            name,
+            closing,
+            attributes,
+        })
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn tokenize_html(html: &str) -> Vec<HtmlToken> {
+    let mut tokens = vec![];
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        if rest.starts_with('<') {
+            if let Some(end) = rest.find('>') {
+                if let Some(tag) = HtmlTag::parse(&rest[..=end]) {
+                    tokens.push(HtmlToken::Tag(tag));
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+            }
+        }
+
+        let text_len = rest
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '<')
+            .map_or(rest.len(), |(ix, _)| ix);
+        tokens.push(HtmlToken::Text(decode_html_entities(&rest[..text_len])));
+        rest = &rest[text_len..];
+    }
+
+    tokens
+}
+
+fn decode_html_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", "\u{a0}")
+        .replace("&amp;", "&")
 }
 
 #[cfg(test)]
@@ -731,7 +1363,7 @@ mod tests {
     use ParsedMarkdownListItemType::*;
 
     async fn parse(input: &str) -> ParsedMarkdown {
-        parse_markdown(input, None, None).await
+        parse_markdown(input, None, None, None).await
     }
 
     #[gpui::test]
@@ -1208,6 +1840,7 @@ fn main() {
 }
 ```
 ",
+            None,
             None,
             Some(language_registry),
         )
@@ -1224,6 +1857,190 @@ fn main() {
         );
     }
 
+    #[gpui::test]
+    async fn test_images_split_paragraphs() {
+        let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let parsed = parse_markdown(
+            "Before ![alt](Cargo.toml) after",
+            Some(crate_dir.clone()),
+            Some(crate_dir.clone()),
+            None,
+        )
+        .await;
+
+        assert_eq!(
+            parsed.children,
+            vec![
+                p("Before ", 0..31),
+                image(
+                    Link::Path {
+                        display_path: PathBuf::from("Cargo.toml"),
+                        path: crate_dir.join("Cargo.toml"),
+                    },
+                    Some("alt"),
+                    7..25
+                ),
+                p(" after", 0..31),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_remote_images_are_not_fetched() {
+        let parsed =
+            parse("![Logo](https://example.com/logo.png)\n\n![](http://example.com/a.png)").await;
+
+        assert_eq!(parsed.children, vec![p("Logo", 0..37)]);
+    }
+
+    #[gpui::test]
+    async fn test_local_images() {
+        let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let parsed = parse_markdown(
+            "![](Cargo.toml)\n\n![](/src/markdown_preview.rs)\n\n![Missing](missing.png)",
+            Some(crate_dir.clone()),
+            Some(crate_dir.clone()),
+            None,
+        )
+        .await;
+
+        assert_eq!(
+            parsed.children,
+            vec![
+                image(
+                    Link::Path {
+                        display_path: PathBuf::from("Cargo.toml"),
+                        path: crate_dir.join("Cargo.toml"),
+                    },
+                    None,
+                    0..15
+                ),
+                image(
+                    Link::Path {
+                        display_path: PathBuf::from("/src/markdown_preview.rs"),
+                        path: crate_dir.join("src/markdown_preview.rs"),
+                    },
+                    None,
+                    17..46
+                ),
+                p("Missing", 48..71),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_footnotes() {
+        let parsed =
+            parse("Second[^b] and first[^a].\n\n[^a]: Defined first.\n\n[^b]: Defined second.\n")
+                .await;
+
+        assert_eq!(parsed.children.len(), 3);
+        assert_eq!(parsed.children[0], p("Second[1] and first[2].", 0..26));
+
+        let footnotes = parsed.children[1..]
+            .iter()
+            .map(|element| match element {
+                ParsedMarkdownElement::Footnote(footnote) => (
+                    footnote.label.as_str(),
+                    footnote.number,
+                    footnote.backlink,
+                    footnote.children.len(),
+                ),
+                element => panic!("expected a footnote, got {:?}", element),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(footnotes, vec![("b", 1, Some(6), 1), ("a", 2, Some(20), 1)]);
+    }
+
+    #[gpui::test]
+    async fn test_inline_html() {
+        let parsed =
+            parse("Press <kbd>Ctrl</kbd> + <kbd>C</kbd><br>x<sup>2</sup> H<sub>2</sub>O").await;
+
+        assert_eq!(parsed.children, vec![p("Press Ctrl + C\nx² H₂O", 0..68)]);
+        let ParsedMarkdownElement::Paragraph(text) = &parsed.children[0] else {
+            panic!("expected a paragraph");
+        };
+        assert_eq!(text.region_ranges, vec![6..10, 13..14]);
+        assert!(text.regions.iter().all(|region| region.code));
+    }
+
+    #[gpui::test]
+    async fn test_details() {
+        let parsed = parse(
+            "<details>\n<summary>More info</summary>\n\nHidden *text*\n\n</details>\n\nAfter",
+        )
+        .await;
+
+        assert_eq!(parsed.children.len(), 2);
+        let ParsedMarkdownElement::Details(details) = &parsed.children[0] else {
+            panic!("expected a details section");
+        };
+        assert_eq!(details.summary.contents, "More info");
+        assert!(!details.open);
+        assert_eq!(details.children.len(), 1);
+        assert_eq!(details.source_range.start, 0);
+        assert_eq!(details.children[0], p("Hidden text", 40..54));
+        assert_eq!(parsed.children[1], p("After", 67..72));
+
+        let parsed = parse("<details open><summary>Title</summary>Body</details>").await;
+        assert_eq!(parsed.children.len(), 1);
+        let ParsedMarkdownElement::Details(details) = &parsed.children[0] else {
+            panic!("expected a details section");
+        };
+        assert!(details.open);
+        assert_eq!(details.summary.contents, "Title");
+        assert_eq!(details.children, vec![p("Body", 0..52)]);
+    }
+
+    #[gpui::test]
+    async fn test_html_block_subset() {
+        let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let parsed = parse_markdown(
+            "<p align=\"center\">\n  <img src=\"Cargo.toml\" alt=\"Logo\" width=\"200\">\n  <br>Tagline &amp; more\n</p>\n\n<script>alert(1)</script>\n",
+            Some(crate_dir.clone()),
+            Some(crate_dir.clone()),
+            None,
+        )
+        .await;
+
+        assert_eq!(parsed.children.len(), 2);
+        let ParsedMarkdownElement::Image(logo) = &parsed.children[0] else {
+            panic!("expected an image");
+        };
+        assert_eq!(
+            logo.link,
+            Link::Path {
+                display_path: PathBuf::from("Cargo.toml"),
+                path: crate_dir.join("Cargo.toml"),
+            }
+        );
+        assert_eq!(logo.alt_text, Some("Logo".into()));
+        assert_eq!(logo.width, Some(200.));
+        let ParsedMarkdownElement::Paragraph(tagline) = &parsed.children[1] else {
+            panic!("expected a paragraph");
+        };
+        assert_eq!(tagline.contents, "Tagline & more");
+    }
+
+    #[test]
+    fn test_html_tag_parsing() {
+        assert_eq!(
+            HtmlTag::parse("<IMG src='a b.png' alt=\"x &lt; y\" hidden />"),
+            Some(HtmlTag {
+                name: "img".into(),
+                closing: false,
+                attributes: vec![
+                    ("src".into(), "a b.png".into()),
+                    ("alt".into(), "x < y".into()),
+                    ("hidden".into(), String::new()),
+                ],
+            })
+        );
+        assert!(HtmlTag::parse("</details>").unwrap().closing);
+        assert_eq!(HtmlTag::parse("<!-- comment -->"), None);
+    }
+
     fn rust_lang() -> Arc<Language> {
         Arc::new(Language::new(
             LanguageConfig {
@@ -1239,6 +2056,20 @@ fn main() {
         ))
     }
 
+    fn image(
+        link: Link,
+        alt_text: Option<&str>,
+        source_range: Range<usize>,
+    ) -> ParsedMarkdownElement {
+        ParsedMarkdownElement::Image(ParsedMarkdownImage {
+            source_range,
+            link,
+            alt_text: alt_text.map(|alt_text| alt_text.to_string().into()),
+            width: None,
+            height: None,
+        })
+    }
+
     fn h1(contents: ParsedMarkdownText, source_range: Range<usize>) -> ParsedMarkdownElement {
         ParsedMarkdownElement::Heading(ParsedMarkdownHeading {
             source_range,
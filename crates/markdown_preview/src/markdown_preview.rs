@@ -9,6 +9,7 @@ pub mod markdown_renderer;
 actions!(markdown, [OpenPreview, OpenPreviewToTheSide]);
 
 pub fn init(cx: &mut AppContext) {
+    workspace::register_followable_item::<markdown_preview_view::MarkdownPreviewView>(cx);
     cx.observe_new_views(|workspace: &mut Workspace, cx| {
         markdown_preview_view::MarkdownPreviewView::register(workspace, cx);
     })
@@ -1,21 +1,27 @@
 use std::sync::Arc;
 use std::time::Duration;
-use std::{ops::Range, path::PathBuf};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use collections::HashSet;
 use editor::scroll::{Autoscroll, AutoscrollStrategy};
 use editor::{Editor, EditorEvent};
 use gpui::{
     list, AnyElement, AppContext, ClickEvent, EventEmitter, FocusHandle, FocusableView,
-    InteractiveElement, IntoElement, ListState, ParentElement, Render, Styled, Subscription, Task,
-    View, ViewContext, WeakView,
+    InteractiveElement, IntoElement, ListOffset, ListScrollEvent, ListState, Model, ParentElement,
+    Render, Styled, Subscription, Task, View, ViewContext, WeakView,
 };
-use language::LanguageRegistry;
+use language::{BufferId, LanguageRegistry};
+use project::Project;
+use rpc::proto::{self, update_view, PeerId};
 use ui::prelude::*;
-use workspace::item::{Item, ItemHandle, TabContentParams};
-use workspace::{Pane, Workspace};
+use workspace::item::{FollowEvent, FollowableItem, Item, ItemHandle, TabContentParams};
+use workspace::{Pane, ViewId, Workspace};
 
-use crate::markdown_elements::ParsedMarkdownElement;
+use crate::markdown_elements::{Link, ParsedMarkdownElement};
 use crate::OpenPreviewToTheSide;
 use crate::{
     markdown_elements::ParsedMarkdown,
@@ -33,10 +39,13 @@ pub struct MarkdownPreviewView {
     contents: Option<ParsedMarkdown>,
     selected_block: usize,
     list_state: ListState,
+    /// The start offsets of the `<details>` sections that were toggled from their initial state.
+    toggled_details: HashSet<usize>,
     tab_description: Option<String>,
     fallback_tab_description: SharedString,
     language_registry: Arc<LanguageRegistry>,
     parsing_markdown_task: Option<Task<Result<()>>>,
+    remote_id: Option<ViewId>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -177,6 +186,22 @@ impl MarkdownPreviewView {
                                                 }
                                             })
                                         }
+                                    })
+                                    .with_anchor_clicked_callback({
+                                        let view = view.clone();
+                                        move |link, cx| {
+                                            view.update(cx, |view, cx| {
+                                                view.scroll_to_anchor(link, cx);
+                                            })
+                                        }
+                                    })
+                                    .with_details_toggled_callback(this.toggled_details.clone(), {
+                                        let view = view.clone();
+                                        move |source_offset, cx| {
+                                            view.update(cx, |view, cx| {
+                                                view.toggle_details(source_offset, cx);
+                                            })
+                                        }
                                     });
                             let block = contents.children.get(ix).unwrap();
                             let rendered_block = render_markdown_block(block, &mut render_cx);
@@ -229,6 +254,9 @@ impl MarkdownPreviewView {
                         div().into_any()
                     }
                 });
+            list_state.set_scroll_handler(cx.listener(|_, _: &ListScrollEvent, cx| {
+                cx.emit(PreviewEvent::ScrollPositionChanged);
+            }));
 
             let mut this = Self {
                 selected_block: 0,
@@ -237,11 +265,13 @@ impl MarkdownPreviewView {
                 workspace: workspace.clone(),
                 contents: None,
                 list_state,
+                toggled_details: HashSet::default(),
                 tab_description: None,
                 language_registry,
                 fallback_tab_description: fallback_description
                     .unwrap_or_else(|| "Markdown Preview".into()),
                 parsing_markdown_task: None,
+                remote_id: None,
             };
 
             this.set_editor(active_editor, cx);
@@ -294,7 +324,8 @@ impl MarkdownPreviewView {
 
         let subscription = cx.subscribe(&editor, |this, editor, event: &EditorEvent, cx| {
             match event {
-                EditorEvent::Edited { .. } => {
+                // Also covers edits made by collaborators, so followers see the leader's changes.
+                EditorEvent::BufferEdited => {
                     this.parse_markdown_from_active_editor(true, cx);
                 }
                 EditorEvent::SelectionsChanged { .. } => {
@@ -302,6 +333,7 @@ impl MarkdownPreviewView {
                     let selection_range = editor.selections.last::<usize>(cx).range();
                     this.selected_block = this.get_block_index_under_cursor(selection_range);
                     this.list_state.scroll_to_reveal_item(this.selected_block);
+                    cx.emit(PreviewEvent::SelectedBlockChanged);
                     cx.notify();
                 }
                 _ => {}
@@ -317,6 +349,7 @@ impl MarkdownPreviewView {
             editor,
             _subscription: subscription,
         });
+        self.toggled_details.clear();
 
         self.parse_markdown_from_active_editor(false, cx);
     }
@@ -349,15 +382,23 @@ impl MarkdownPreviewView {
                 cx.background_executor().timer(REPARSE_DEBOUNCE).await;
             }
 
-            let (contents, file_location) = view.update(&mut cx, |_, cx| {
+            let (contents, file_location, worktree_root) = view.update(&mut cx, |_, cx| {
                 let editor = editor.read(cx);
                 let contents = editor.buffer().read(cx).snapshot(cx).text();
                 let file_location = MarkdownPreviewView::get_folder_for_active_editor(editor, cx);
-                (contents, file_location)
+                let worktree_root =
+                    MarkdownPreviewView::get_worktree_root_for_active_editor(editor, cx);
+                (contents, file_location, worktree_root)
             })?;
 
             let parsing_task = cx.background_executor().spawn(async move {
-                parse_markdown(&contents, file_location, Some(language_registry)).await
+                parse_markdown(
+                    &contents,
+                    file_location,
+                    worktree_root,
+                    Some(language_registry),
+                )
+                .await
             });
             let contents = parsing_task.await;
             view.update(&mut cx, move |view, cx| {
@@ -400,6 +441,83 @@ impl MarkdownPreviewView {
         }
     }
 
+    /// The root of the worktree containing the file that is currently being previewed.
+    fn get_worktree_root_for_active_editor(
+        editor: &Editor,
+        cx: &ViewContext<MarkdownPreviewView>,
+    ) -> Option<PathBuf> {
+        let file = editor.file_at(0, cx)?;
+        let abs_path = file.as_local()?.abs_path(cx);
+        abs_path
+            .ancestors()
+            .nth(file.path().components().count())
+            .map(Path::to_path_buf)
+    }
+
+    fn scroll_to_anchor(&mut self, link: &Link, cx: &mut ViewContext<Self>) {
+        let Some(contents) = &self.contents else {
+            return;
+        };
+
+        let block_index = match link {
+            Link::Footnote { label, .. } => contents.children.iter().position(|block| {
+                matches!(block, ParsedMarkdownElement::Footnote(footnote) if &footnote.label == label)
+            }),
+            Link::FootnoteBacklink { source_offset } => {
+                Some(self.get_block_index_under_cursor(*source_offset..*source_offset))
+            }
+            Link::Web { .. } | Link::Path { .. } => None,
+        };
+
+        if let Some(block_index) = block_index {
+            self.selected_block = block_index;
+            self.list_state.scroll_to_reveal_item(block_index);
+            cx.emit(PreviewEvent::SelectedBlockChanged);
+            cx.notify();
+        }
+    }
+
+    /// The buffer shown by this preview, if it previews a single buffer.
+    fn previewed_buffer(&self, cx: &AppContext) -> Option<Model<language::Buffer>> {
+        let editor = &self.active_editor.as_ref()?.editor;
+        editor.read(cx).buffer().read(cx).as_singleton()
+    }
+
+    fn apply_leader_position(
+        &mut self,
+        scroll_item_ix: u32,
+        scroll_offset_in_item: f32,
+        selected_block: Option<u32>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        // Blocks that aren't parsed yet are clamped by the list, and the position is
+        // restored once parsing resets it.
+        self.list_state.scroll_to(ListOffset {
+            item_ix: scroll_item_ix as usize,
+            offset_in_item: px(scroll_offset_in_item),
+        });
+        if let Some(selected_block) = selected_block {
+            self.selected_block = selected_block as usize;
+        }
+        cx.notify();
+    }
+
+    fn toggle_details(&mut self, source_offset: usize, cx: &mut ViewContext<Self>) {
+        if !self.toggled_details.remove(&source_offset) {
+            self.toggled_details.insert(source_offset);
+        }
+
+        // The height of the toggled block changed, so the list needs to measure it again.
+        let markdown_blocks_count = self
+            .contents
+            .as_ref()
+            .map_or(0, |contents| contents.children.len());
+        let scroll_top = self.list_state.logical_scroll_top();
+        self.list_state.reset(markdown_blocks_count);
+        self.list_state.scroll_to(scroll_top);
+        cx.notify();
+    }
+
     fn get_block_index_under_cursor(&self, selection_range: Range<usize>) -> usize {
         let mut block_index = None;
         let cursor = selection_range.start;
@@ -445,7 +563,11 @@ impl FocusableView for MarkdownPreviewView {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub enum PreviewEvent {}
+pub enum PreviewEvent {
+    /// The preview was scrolled by the user.
+    ScrollPositionChanged,
+    SelectedBlockChanged,
+}
 
 impl EventEmitter<PreviewEvent> for MarkdownPreviewView {}
 
@@ -479,9 +601,155 @@ impl Item for MarkdownPreviewView {
         Some("markdown preview")
     }
 
+    fn display_name(&self) -> Option<&'static str> {
+        Some("markdown preview")
+    }
+
     fn to_item_events(_event: &Self::Event, _f: impl FnMut(workspace::item::ItemEvent)) {}
 }
 
+impl FollowableItem for MarkdownPreviewView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        let buffer = self.previewed_buffer(cx)?;
+        let buffer = buffer.read(cx);
+        if buffer.file().map_or(false, |file| file.is_private()) {
+            return None;
+        }
+
+        let scroll_top = self.list_state.logical_scroll_top();
+        Some(proto::view::Variant::MarkdownPreview(
+            proto::view::MarkdownPreview {
+                buffer_id: buffer.remote_id().into(),
+                scroll_item_ix: scroll_top.item_ix as u32,
+                scroll_offset_in_item: scroll_top.offset_in_item.0,
+                selected_block: Some(self.selected_block as u32),
+            },
+        ))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<Result<View<Self>>>> {
+        let Some(proto::view::Variant::MarkdownPreview(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::MarkdownPreview(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let project = workspace.read(cx).project().clone();
+        let open_buffer = BufferId::new(state.buffer_id).map(|buffer_id| {
+            project.update(cx, |project, cx| project.open_buffer_by_id(buffer_id, cx))
+        });
+        Some(cx.spawn(|mut cx| async move {
+            let buffer = open_buffer?.await?;
+            let existing = pane.update(&mut cx, |pane, cx| {
+                pane.items_of_type::<Self>().find(|preview| {
+                    let preview = preview.read(cx);
+                    preview.remote_id == Some(remote_id)
+                        || preview.previewed_buffer(cx).as_ref() == Some(&buffer)
+                })
+            })?;
+            let preview = match existing {
+                Some(preview) => preview,
+                None => {
+                    let editor = cx.new_view(|cx| {
+                        Editor::for_buffer(buffer.clone(), Some(project.clone()), cx)
+                    })?;
+                    workspace.update(&mut cx, |workspace, cx| {
+                        let language_registry = workspace.project().read(cx).languages().clone();
+                        MarkdownPreviewView::new(
+                            MarkdownPreviewMode::Default,
+                            editor,
+                            workspace.weak_handle(),
+                            language_registry,
+                            None,
+                            cx,
+                        )
+                    })?
+                }
+            };
+            preview.update(&mut cx, |preview, cx| {
+                preview.remote_id = Some(remote_id);
+                preview.apply_leader_position(
+                    state.scroll_item_ix,
+                    state.scroll_offset_in_item,
+                    state.selected_block,
+                    cx,
+                );
+            })?;
+            Ok(preview)
+        }))
+    }
+
+    fn to_follow_event(event: &PreviewEvent) -> Option<FollowEvent> {
+        match event {
+            PreviewEvent::ScrollPositionChanged => Some(FollowEvent::Unfollow),
+            PreviewEvent::SelectedBlockChanged => None,
+        }
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &PreviewEvent,
+        update: &mut Option<update_view::Variant>,
+        _: &WindowContext,
+    ) -> bool {
+        let update =
+            update.get_or_insert_with(|| update_view::Variant::MarkdownPreview(Default::default()));
+        let update_view::Variant::MarkdownPreview(update) = update else {
+            return false;
+        };
+
+        match event {
+            PreviewEvent::ScrollPositionChanged | PreviewEvent::SelectedBlockChanged => {
+                let scroll_top = self.list_state.logical_scroll_top();
+                update.scroll_item_ix = scroll_top.item_ix as u32;
+                update.scroll_offset_in_item = scroll_top.offset_in_item.0;
+                update.selected_block = Some(self.selected_block as u32);
+                true
+            }
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        message: update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let update_view::Variant::MarkdownPreview(message) = message else {
+            return Task::ready(Err(anyhow!(
+                "received a non-markdown-preview update for a markdown preview"
+            )));
+        };
+
+        self.apply_leader_position(
+            message.scroll_item_ix,
+            message.scroll_offset_in_item,
+            message.selected_block,
+            cx,
+        );
+        Task::ready(Ok(()))
+    }
+
+    fn is_project_item(&self, _cx: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, _: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        cx.notify();
+    }
+}
+
 impl Render for MarkdownPreviewView {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         v_flex()
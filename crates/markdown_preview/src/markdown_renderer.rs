@@ -1,13 +1,14 @@
 use crate::markdown_elements::{
     HeadingLevel, Link, ParsedMarkdown, ParsedMarkdownBlockQuote, ParsedMarkdownCodeBlock,
-    ParsedMarkdownElement, ParsedMarkdownHeading, ParsedMarkdownListItem,
-    ParsedMarkdownListItemType, ParsedMarkdownTable, ParsedMarkdownTableAlignment,
-    ParsedMarkdownTableRow, ParsedMarkdownText,
+    ParsedMarkdownDetails, ParsedMarkdownElement, ParsedMarkdownFootnote, ParsedMarkdownHeading,
+    ParsedMarkdownImage, ParsedMarkdownListItem, ParsedMarkdownListItemType, ParsedMarkdownTable,
+    ParsedMarkdownTableAlignment, ParsedMarkdownTableRow, ParsedMarkdownText,
 };
+use collections::HashSet;
 use gpui::{
-    div, px, rems, AbsoluteLength, AnyElement, DefiniteLength, Div, Element, ElementId,
-    HighlightStyle, Hsla, InteractiveText, IntoElement, Keystroke, Modifiers, ParentElement,
-    SharedString, Styled, StyledText, TextStyle, WeakView, WindowContext,
+    div, img, px, rems, AbsoluteLength, AnyElement, DefiniteLength, Div, Element, ElementId,
+    HighlightStyle, Hsla, ImageSource, InteractiveText, IntoElement, Keystroke, Modifiers,
+    ParentElement, SharedString, Styled, StyledText, TextStyle, WeakView, WindowContext,
 };
 use std::{
     ops::{Mul, Range},
@@ -15,12 +16,14 @@ use std::{
 };
 use theme::{ActiveTheme, SyntaxTheme};
 use ui::{
-    h_flex, v_flex, Checkbox, FluentBuilder, InteractiveElement, LinkPreview, Selection,
-    StatefulInteractiveElement, Tooltip,
+    h_flex, v_flex, Checkbox, Clickable, Disclosure, FluentBuilder, InteractiveElement,
+    LinkPreview, Selection, StatefulInteractiveElement, Tooltip,
 };
 use workspace::Workspace;
 
 type CheckboxClickedCallback = Arc<Box<dyn Fn(bool, Range<usize>, &mut WindowContext)>>;
+type AnchorClickedCallback = Arc<Box<dyn Fn(&Link, &mut WindowContext)>>;
+type DetailsToggledCallback = Arc<Box<dyn Fn(usize, &mut WindowContext)>>;
 
 pub struct RenderContext {
     workspace: Option<WeakView<Workspace>>,
@@ -34,6 +37,10 @@ pub struct RenderContext {
     syntax_theme: Arc<SyntaxTheme>,
     indent: usize,
     checkbox_clicked_callback: Option<CheckboxClickedCallback>,
+    anchor_clicked_callback: Option<AnchorClickedCallback>,
+    /// The start offsets of the `<details>` sections that were toggled from their initial state.
+    toggled_details: HashSet<usize>,
+    details_toggled_callback: Option<DetailsToggledCallback>,
 }
 
 impl RenderContext {
@@ -52,6 +59,9 @@ impl RenderContext {
             code_block_background_color: theme.colors().surface_background,
             code_span_background_color: theme.colors().editor_document_highlight_read_background,
             checkbox_clicked_callback: None,
+            anchor_clicked_callback: None,
+            toggled_details: HashSet::default(),
+            details_toggled_callback: None,
         }
     }
 
@@ -63,6 +73,25 @@ impl RenderContext {
         self
     }
 
+    /// Handles clicks on links within the document, such as footnote references.
+    pub fn with_anchor_clicked_callback(
+        mut self,
+        callback: impl Fn(&Link, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.anchor_clicked_callback = Some(Arc::new(Box::new(callback)));
+        self
+    }
+
+    pub fn with_details_toggled_callback(
+        mut self,
+        toggled_details: HashSet<usize>,
+        callback: impl Fn(usize, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.toggled_details = toggled_details;
+        self.details_toggled_callback = Some(Arc::new(Box::new(callback)));
+        self
+    }
+
     fn next_id(&mut self, span: &Range<usize>) -> ElementId {
         let id = format!("markdown-{}-{}-{}", self.next_id, span.start, span.end);
         self.next_id += 1;
@@ -116,6 +145,9 @@ pub fn render_markdown_block(block: &ParsedMarkdownElement, cx: &mut RenderConte
         BlockQuote(block_quote) => render_markdown_block_quote(block_quote, cx),
         CodeBlock(code_block) => render_markdown_code_block(code_block, cx),
         HorizontalRule(_) => render_markdown_rule(cx),
+        Image(image) => render_markdown_image(image, cx),
+        Details(details) => render_markdown_details(details, cx),
+        Footnote(footnote) => render_markdown_footnote(footnote, cx),
     }
 }
 
@@ -371,6 +403,7 @@ fn render_markdown_text(parsed: &ParsedMarkdownText, cx: &mut RenderContext) ->
     }
 
     let workspace = cx.workspace.clone();
+    let anchor_clicked_callback = cx.anchor_clicked_callback.clone();
 
     InteractiveText::new(
         element_id,
@@ -402,6 +435,11 @@ fn render_markdown_text(parsed: &ParsedMarkdownText, cx: &mut RenderContext) ->
                     });
                 }
             }
+            link @ (Link::Footnote { .. } | Link::FootnoteBacklink { .. }) => {
+                if let Some(callback) = &anchor_clicked_callback {
+                    callback(link, window_cx);
+                }
+            }
         },
     )
     .into_any_element()
@@ -411,3 +449,98 @@ fn render_markdown_rule(cx: &mut RenderContext) -> AnyElement {
     let rule = div().w_full().h(px(2.)).bg(cx.border_color);
     div().pt_3().pb_3().child(rule).into_any()
 }
+
+fn render_markdown_image(parsed: &ParsedMarkdownImage, cx: &mut RenderContext) -> AnyElement {
+    let source: ImageSource = match &parsed.link {
+        Link::Path { path, .. } => path.clone().into(),
+        Link::Web { .. } | Link::Footnote { .. } | Link::FootnoteBacklink { .. } => {
+            return div().into_any()
+        }
+    };
+
+    let image = img(source)
+        .max_w_full()
+        .map(|image| match (parsed.width, parsed.height) {
+            (Some(width), Some(height)) => image.w(px(width)).h(px(height)),
+            (Some(width), None) => image.max_w(px(width)),
+            (None, Some(height)) => image.max_h(px(height)),
+            (None, None) => image,
+        });
+
+    cx.with_common_p(div())
+        .id(cx.next_id(&parsed.source_range))
+        .child(image)
+        .when_some(parsed.alt_text.clone(), |this, alt_text| {
+            this.tooltip(move |cx| Tooltip::text(alt_text.clone(), cx))
+        })
+        .into_any()
+}
+
+fn render_markdown_details(parsed: &ParsedMarkdownDetails, cx: &mut RenderContext) -> AnyElement {
+    let source_offset = parsed.source_range.start;
+    let is_open = parsed.open != cx.toggled_details.contains(&source_offset);
+
+    let children: Vec<AnyElement> = if is_open {
+        parsed
+            .children
+            .iter()
+            .map(|child| render_markdown_block(child, cx))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let summary = h_flex()
+        .id(cx.next_id(&parsed.source_range))
+        .gap_1()
+        .child(
+            Disclosure::new("details-disclosure", is_open)
+                .when_some(cx.details_toggled_callback.clone(), |this, callback| {
+                    this.on_click(move |_, cx| callback(source_offset, cx))
+                }),
+        )
+        .child(render_markdown_text(&parsed.summary, cx));
+
+    cx.with_common_p(v_flex())
+        .child(summary)
+        .when(is_open, |this| {
+            this.child(div().pl_6().pt_1().children(children))
+        })
+        .into_any()
+}
+
+fn render_markdown_footnote(parsed: &ParsedMarkdownFootnote, cx: &mut RenderContext) -> AnyElement {
+    let children: Vec<AnyElement> = parsed
+        .children
+        .iter()
+        .map(|child| render_markdown_block(child, cx))
+        .collect();
+
+    let backlink =
+        parsed
+            .backlink
+            .zip(cx.anchor_clicked_callback.clone())
+            .map(|(source_offset, callback)| {
+                div()
+                    .id(cx.next_id(&parsed.source_range))
+                    .ml_2()
+                    .text_color(cx.text_muted_color)
+                    .cursor_pointer()
+                    .child("↩")
+                    .tooltip(|cx| Tooltip::text("Back to reference", cx))
+                    .on_click(move |_, cx| callback(&Link::FootnoteBacklink { source_offset }, cx))
+            });
+
+    h_flex()
+        .items_start()
+        .text_sm()
+        .child(
+            div()
+                .mr_2()
+                .text_color(cx.text_muted_color)
+                .child(format!("{}.", parsed.number)),
+        )
+        .child(div().children(children))
+        .children(backlink)
+        .into_any()
+}
@@ -317,6 +317,9 @@ impl NotificationStore {
                     user_ids.push(sender_id);
                     message_ids.push(message_id);
                 }
+                Notification::BufferCommentMention { sender_id, .. } => {
+                    user_ids.push(sender_id);
+                }
             }
         }
 
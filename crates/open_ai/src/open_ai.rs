@@ -135,7 +135,6 @@ pub struct FunctionDefinition {
 #[derive(Serialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ToolDefinition {
-    #[allow(dead_code)]
     Function { function: FunctionDefinition },
 }
 
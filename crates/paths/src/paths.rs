@@ -206,6 +206,14 @@ pub fn languages_dir() -> &'static PathBuf {
     LANGUAGES_DIR.get_or_init(|| support_dir().join("languages"))
 }
 
+/// Returns the path to the undo history directory.
+///
+/// This is where the undo history of files is stored so it survives restarts.
+pub fn undo_history_dir() -> &'static PathBuf {
+    static UNDO_HISTORY_DIR: OnceLock<PathBuf> = OnceLock::new();
+    UNDO_HISTORY_DIR.get_or_init(|| support_dir().join("undo_history"))
+}
+
 /// Returns the path to the Copilot directory.
 pub fn copilot_dir() -> &'static PathBuf {
     static COPILOT_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -0,0 +1,375 @@
+//! Line bookmarks that can be placed in any file of a project.
+//!
+//! While a file is open, its bookmarks are anchored in the buffer so that they stay on
+//! their line as the text around them is edited. The bookmarks of files that aren't open
+//! only remember the position they were last seen at.
+
+use std::{path::Path, sync::Arc};
+
+use collections::HashMap;
+use gpui::{AppContext, Context, EventEmitter, Model, ModelContext, Subscription, WeakModel};
+use language::Buffer;
+use text::{Anchor, Bias, BufferId, Point};
+use util::post_inc;
+
+use crate::File;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BookmarkId(usize);
+
+impl BookmarkId {
+    pub fn to_usize(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Bookmark {
+    pub id: BookmarkId,
+    pub abs_path: Arc<Path>,
+    pub label: Option<String>,
+    /// The vim mark this bookmark was set with. Vim's uppercase marks aren't local to a
+    /// buffer, so they're kept as bookmarks.
+    pub mark: Option<char>,
+    position: BookmarkPosition,
+}
+
+#[derive(Clone, Debug)]
+enum BookmarkPosition {
+    Point(Point),
+    Anchor {
+        buffer: WeakModel<Buffer>,
+        anchor: Anchor,
+    },
+}
+
+/// A bookmark as it's stored between sessions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializedBookmark {
+    pub abs_path: Arc<Path>,
+    pub row: u32,
+    /// The column a vim mark was set at. Other bookmarks are placed at the start of a line.
+    pub column: u32,
+    pub label: Option<String>,
+    pub mark: Option<char>,
+}
+
+pub enum BookmarkStoreEvent {
+    /// Bookmarks were added, removed or changed, or a file containing bookmarks was
+    /// saved, so the stored bookmarks are out of date.
+    BookmarksChanged,
+}
+
+pub struct BookmarkStore {
+    bookmarks: Vec<Bookmark>,
+    next_bookmark_id: usize,
+    buffer_subscriptions: HashMap<BufferId, [Subscription; 2]>,
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+impl BookmarkStore {
+    pub fn new(cx: &mut AppContext) -> Model<Self> {
+        cx.new_model(|_| Self {
+            bookmarks: Vec::new(),
+            next_bookmark_id: 0,
+            buffer_subscriptions: HashMap::default(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty()
+    }
+
+    pub fn bookmark(&self, id: BookmarkId) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|bookmark| bookmark.id == id)
+    }
+
+    /// Returns the bookmarks with their current rows, ordered by path and row.
+    pub fn sorted_bookmarks(&self, cx: &AppContext) -> Vec<(&Bookmark, u32)> {
+        let mut bookmarks = self
+            .bookmarks
+            .iter()
+            .map(|bookmark| (bookmark, self.row(bookmark, cx)))
+            .collect::<Vec<_>>();
+        bookmarks.sort_by(|(a, a_row), (b, b_row)| {
+            (&a.abs_path, a_row, a.id).cmp(&(&b.abs_path, b_row, b.id))
+        });
+        bookmarks
+    }
+
+    /// Returns the row of a bookmark, following the edits made since it was placed.
+    pub fn row(&self, bookmark: &Bookmark, cx: &AppContext) -> u32 {
+        self.point(bookmark, cx).row
+    }
+
+    /// Returns the position of a bookmark, following the edits made since it was placed.
+    pub fn point(&self, bookmark: &Bookmark, cx: &AppContext) -> Point {
+        match &bookmark.position {
+            BookmarkPosition::Point(point) => *point,
+            BookmarkPosition::Anchor { buffer, anchor } => {
+                buffer.upgrade().map_or(Point::zero(), |buffer| {
+                    buffer.read(cx).summary_for_anchor::<Point>(anchor)
+                })
+            }
+        }
+    }
+
+    /// Returns the buffer of a bookmark, if its file is open.
+    pub fn buffer(&self, bookmark: &Bookmark) -> Option<Model<Buffer>> {
+        match &bookmark.position {
+            BookmarkPosition::Point(_) => None,
+            BookmarkPosition::Anchor { buffer, .. } => buffer.upgrade(),
+        }
+    }
+
+    /// Returns the anchors of the bookmarks placed in the given buffer.
+    pub fn anchors_for_buffer(
+        &self,
+        buffer_id: BufferId,
+    ) -> impl '_ + Iterator<Item = (BookmarkId, Anchor)> {
+        self.bookmarks
+            .iter()
+            .filter_map(move |bookmark| match &bookmark.position {
+                BookmarkPosition::Anchor { anchor, .. } if anchor.buffer_id == Some(buffer_id) => {
+                    Some((bookmark.id, *anchor))
+                }
+                _ => None,
+            })
+    }
+
+    pub fn bookmark_for_mark(&self, mark: char) -> Option<&Bookmark> {
+        self.bookmarks
+            .iter()
+            .find(|bookmark| bookmark.mark == Some(mark))
+    }
+
+    /// Returns the bookmark following the given row, wrapping around to the first one.
+    pub fn next_bookmark(&self, abs_path: &Path, row: u32, cx: &AppContext) -> Option<BookmarkId> {
+        let bookmarks = self.sorted_bookmarks(cx);
+        bookmarks
+            .iter()
+            .find(|(bookmark, bookmark_row)| {
+                (bookmark.abs_path.as_ref(), *bookmark_row) > (abs_path, row)
+            })
+            .or(bookmarks.first())
+            .map(|(bookmark, _)| bookmark.id)
+    }
+
+    /// Returns the bookmark preceding the given row, wrapping around to the last one.
+    pub fn prev_bookmark(&self, abs_path: &Path, row: u32, cx: &AppContext) -> Option<BookmarkId> {
+        let bookmarks = self.sorted_bookmarks(cx);
+        bookmarks
+            .iter()
+            .rev()
+            .find(|(bookmark, bookmark_row)| {
+                (bookmark.abs_path.as_ref(), *bookmark_row) < (abs_path, row)
+            })
+            .or(bookmarks.last())
+            .map(|(bookmark, _)| bookmark.id)
+    }
+
+    /// Removes the bookmark on the given row of the buffer, or adds one if there is none.
+    pub fn toggle_bookmark(
+        &mut self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let existing = self
+            .anchors_for_buffer(buffer_id)
+            .find(|(_, anchor)| buffer.read(cx).summary_for_anchor::<Point>(anchor).row == row)
+            .map(|(id, _)| id);
+        match existing {
+            Some(id) => self.remove_bookmark(id, cx),
+            None => {
+                self.insert_bookmark(buffer, Point::new(row, 0), None, cx);
+            }
+        }
+    }
+
+    /// Places the bookmark for a vim mark at the given position, moving it if it was
+    /// already placed elsewhere.
+    pub fn set_mark(
+        &mut self,
+        mark: char,
+        buffer: &Model<Buffer>,
+        point: Point,
+        cx: &mut ModelContext<Self>,
+    ) -> Option<BookmarkId> {
+        // The old position is only dropped once the new one is known, so that a mark isn't
+        // lost when the buffer has no file to bookmark.
+        let id = self.insert_bookmark(buffer, point, Some(mark), cx)?;
+        self.bookmarks
+            .retain(|bookmark| bookmark.mark != Some(mark) || bookmark.id == id);
+        Some(id)
+    }
+
+    fn insert_bookmark(
+        &mut self,
+        buffer: &Model<Buffer>,
+        point: Point,
+        mark: Option<char>,
+        cx: &mut ModelContext<Self>,
+    ) -> Option<BookmarkId> {
+        let abs_path = File::from_dyn(buffer.read(cx).file())?.abs_path(cx);
+        let snapshot = buffer.read(cx);
+        let anchor = snapshot.anchor_after(snapshot.clip_point(point, Bias::Left));
+        let id = BookmarkId(post_inc(&mut self.next_bookmark_id));
+        self.bookmarks.push(Bookmark {
+            id,
+            abs_path: abs_path.into(),
+            label: None,
+            mark,
+            position: BookmarkPosition::Anchor {
+                buffer: buffer.downgrade(),
+                anchor,
+            },
+        });
+        self.watch_buffer(buffer, cx);
+        cx.emit(BookmarkStoreEvent::BookmarksChanged);
+        cx.notify();
+        Some(id)
+    }
+
+    pub fn remove_bookmark(&mut self, id: BookmarkId, cx: &mut ModelContext<Self>) {
+        let len = self.bookmarks.len();
+        self.bookmarks.retain(|bookmark| bookmark.id != id);
+        if self.bookmarks.len() != len {
+            cx.emit(BookmarkStoreEvent::BookmarksChanged);
+            cx.notify();
+        }
+    }
+
+    pub fn set_label(&mut self, id: BookmarkId, label: String, cx: &mut ModelContext<Self>) {
+        if let Some(bookmark) = self.bookmarks.iter_mut().find(|bookmark| bookmark.id == id) {
+            let label = label.trim();
+            bookmark.label = (!label.is_empty()).then(|| label.to_string());
+            cx.emit(BookmarkStoreEvent::BookmarksChanged);
+            cx.notify();
+        }
+    }
+
+    pub fn clear(&mut self, cx: &mut ModelContext<Self>) {
+        self.bookmarks.clear();
+        self.buffer_subscriptions.clear();
+        cx.emit(BookmarkStoreEvent::BookmarksChanged);
+        cx.notify();
+    }
+
+    pub fn serialize(&self, cx: &AppContext) -> Vec<SerializedBookmark> {
+        self.sorted_bookmarks(cx)
+            .into_iter()
+            .map(|(bookmark, _)| {
+                let point = self.point(bookmark, cx);
+                SerializedBookmark {
+                    abs_path: bookmark.abs_path.clone(),
+                    row: point.row,
+                    column: point.column,
+                    label: bookmark.label.clone(),
+                    mark: bookmark.mark,
+                }
+            })
+            .collect()
+    }
+
+    /// Adds stored bookmarks, anchoring them in the given open buffers.
+    ///
+    /// Bookmarks placed while the stored ones were loading are kept, and take precedence
+    /// over stored bookmarks on the same line or for the same mark.
+    pub fn restore(
+        &mut self,
+        bookmarks: Vec<SerializedBookmark>,
+        open_buffers: &[Model<Buffer>],
+        cx: &mut ModelContext<Self>,
+    ) {
+        let existing = self
+            .bookmarks
+            .iter()
+            .map(|bookmark| {
+                (
+                    bookmark.abs_path.clone(),
+                    self.row(bookmark, cx),
+                    bookmark.mark,
+                )
+            })
+            .collect::<Vec<_>>();
+        for bookmark in bookmarks {
+            let is_superseded = existing.iter().any(|(abs_path, row, mark)| {
+                (*abs_path == bookmark.abs_path && *row == bookmark.row)
+                    || (mark.is_some() && *mark == bookmark.mark)
+            });
+            if is_superseded {
+                continue;
+            }
+            let id = BookmarkId(post_inc(&mut self.next_bookmark_id));
+            self.bookmarks.push(Bookmark {
+                id,
+                abs_path: bookmark.abs_path,
+                label: bookmark.label,
+                mark: bookmark.mark,
+                position: BookmarkPosition::Point(Point::new(bookmark.row, bookmark.column)),
+            });
+        }
+        for buffer in open_buffers {
+            self.buffer_opened(buffer, cx);
+        }
+        cx.notify();
+    }
+
+    /// Anchors the bookmarks of a file that was just opened in its buffer.
+    pub(crate) fn buffer_opened(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let Some(abs_path) = File::from_dyn(buffer.read(cx).file()).map(|file| file.abs_path(cx))
+        else {
+            return;
+        };
+        let snapshot = buffer.read(cx);
+        let mut anchored = false;
+        for bookmark in &mut self.bookmarks {
+            if let BookmarkPosition::Point(point) = bookmark.position {
+                if bookmark.abs_path.as_ref() == abs_path {
+                    let point = snapshot.clip_point(point, Bias::Left);
+                    bookmark.position = BookmarkPosition::Anchor {
+                        buffer: buffer.downgrade(),
+                        anchor: snapshot.anchor_after(point),
+                    };
+                    anchored = true;
+                }
+            }
+        }
+        if anchored {
+            self.watch_buffer(buffer, cx);
+            cx.notify();
+        }
+    }
+
+    fn watch_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        if self.buffer_subscriptions.contains_key(&buffer_id) {
+            return;
+        }
+        let subscriptions = [
+            cx.subscribe(buffer, |this, _, event, cx| {
+                if matches!(event, language::Event::Saved)
+                    && this.anchors_for_buffer(buffer_id).next().is_some()
+                {
+                    cx.emit(BookmarkStoreEvent::BookmarksChanged);
+                }
+            }),
+            cx.observe_release(buffer, move |this, buffer, cx| {
+                this.buffer_subscriptions.remove(&buffer_id);
+                for bookmark in &mut this.bookmarks {
+                    if let BookmarkPosition::Anchor { anchor, .. } = &bookmark.position {
+                        if anchor.buffer_id == Some(buffer_id) {
+                            let point = buffer.summary_for_anchor::<Point>(anchor);
+                            bookmark.position = BookmarkPosition::Point(point);
+                        }
+                    }
+                }
+                cx.notify();
+            }),
+        ];
+        self.buffer_subscriptions.insert(buffer_id, subscriptions);
+    }
+}
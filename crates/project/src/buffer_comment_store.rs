@@ -0,0 +1,319 @@
+//! Code review comments left on the buffers of a shared project.
+//!
+//! Comment threads are stored by the collaboration server, so they're only available
+//! while the project is shared. They belong to the file they were left on, and show up
+//! again whenever its host shares it. Each thread is anchored in the buffer it was
+//! created in while that project is shared, and falls back to the position it was
+//! created at once the project was shared again.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, Client, UserId, UserStore};
+use gpui::{AppContext, Context, EventEmitter, Model, ModelContext, Task};
+use language::{proto::deserialize_anchor, proto::serialize_anchor, Buffer};
+use text::{Anchor, Bias, BufferSnapshot, Point};
+use util::ResultExt as _;
+
+use crate::{File, ProjectPath, WorktreeId};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BufferCommentThreadId(u64);
+
+impl BufferCommentThreadId {
+    pub fn from_proto(id: u64) -> Self {
+        Self(id)
+    }
+
+    pub fn to_proto(self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BufferCommentThread {
+    pub id: BufferCommentThreadId,
+    pub project_path: ProjectPath,
+    /// Only known while the project the thread was created in is still shared.
+    pub anchor: Option<Anchor>,
+    /// The position the thread was created at, used when there's no anchor to resolve.
+    pub position: Point,
+    pub resolved: bool,
+    pub comments: Vec<BufferComment>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BufferComment {
+    pub id: u64,
+    pub sender_id: UserId,
+    pub body: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+pub enum BufferCommentStoreEvent {
+    ThreadsChanged,
+}
+
+pub struct BufferCommentStore {
+    client: Arc<Client>,
+    user_store: Model<UserStore>,
+    project_id: Option<u64>,
+    threads: BTreeMap<BufferCommentThreadId, BufferCommentThread>,
+    _load_threads: Option<Task<()>>,
+}
+
+impl EventEmitter<BufferCommentStoreEvent> for BufferCommentStore {}
+
+impl BufferCommentStore {
+    pub fn new(
+        client: Arc<Client>,
+        user_store: Model<UserStore>,
+        cx: &mut AppContext,
+    ) -> Model<Self> {
+        cx.new_model(|_| Self {
+            client,
+            user_store,
+            project_id: None,
+            threads: BTreeMap::default(),
+            _load_threads: None,
+        })
+    }
+
+    pub fn user_store(&self) -> &Model<UserStore> {
+        &self.user_store
+    }
+
+    /// Whether comments can be left on the project, which requires it to be shared.
+    pub fn is_available(&self) -> bool {
+        self.project_id.is_some()
+    }
+
+    pub fn thread(&self, id: BufferCommentThreadId) -> Option<&BufferCommentThread> {
+        self.threads.get(&id)
+    }
+
+    pub fn threads(&self) -> impl '_ + Iterator<Item = &BufferCommentThread> {
+        self.threads.values()
+    }
+
+    /// Returns the threads left on the given file.
+    pub fn threads_for_path<'a>(
+        &'a self,
+        project_path: &'a ProjectPath,
+    ) -> impl 'a + Iterator<Item = &'a BufferCommentThread> {
+        self.threads
+            .values()
+            .filter(move |thread| &thread.project_path == project_path)
+    }
+
+    /// Returns the position of a thread in a buffer of its file.
+    pub fn anchor_in_buffer(
+        &self,
+        thread: &BufferCommentThread,
+        buffer: &BufferSnapshot,
+    ) -> Anchor {
+        match thread.anchor {
+            Some(anchor) if buffer.can_resolve(&anchor) => anchor,
+            _ => buffer.anchor_before(buffer.clip_point(thread.position, Bias::Left)),
+        }
+    }
+
+    /// Starts loading the comment threads of the project, after it was shared or joined
+    /// with the given id. Clears the threads when the project is no longer shared.
+    pub(crate) fn set_project_id(&mut self, project_id: Option<u64>, cx: &mut ModelContext<Self>) {
+        if self.project_id == project_id {
+            return;
+        }
+        self.project_id = project_id;
+        self.threads.clear();
+        self._load_threads = project_id.map(|project_id| {
+            let request = self
+                .client
+                .request(proto::GetBufferCommentThreads { project_id });
+            cx.spawn(|this, mut cx| async move {
+                let Some(response) = request.await.log_err() else {
+                    return;
+                };
+                for thread in response.threads {
+                    let Ok(task) = this.update(&mut cx, |this, cx| this.apply_thread(thread, cx))
+                    else {
+                        return;
+                    };
+                    task.await.log_err();
+                }
+            })
+        });
+        cx.emit(BufferCommentStoreEvent::ThreadsChanged);
+        cx.notify();
+    }
+
+    /// Starts a comment thread at the given position in a buffer.
+    pub fn create_thread(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: Anchor,
+        body: String,
+        mentioned_user_ids: Vec<UserId>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<BufferCommentThreadId>> {
+        let Some(project_id) = self.project_id else {
+            return Task::ready(Err(anyhow!("comments require the project to be shared")));
+        };
+        let buffer = buffer.read(cx);
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return Task::ready(Err(anyhow!("can't comment on a buffer without a file")));
+        };
+        let point = buffer.summary_for_anchor::<Point>(&position);
+        let request = self.client.request(proto::CreateBufferCommentThread {
+            project_id,
+            worktree_id: file.worktree_id(cx).to_proto(),
+            path: file.path.to_string_lossy().into(),
+            anchor: Some(serialize_anchor(&position)),
+            row: point.row,
+            column: point.column,
+            body,
+            mentioned_user_ids,
+        });
+        self.apply_thread_response(request, cx)
+    }
+
+    /// Adds a reply to a comment thread.
+    pub fn reply(
+        &mut self,
+        thread_id: BufferCommentThreadId,
+        body: String,
+        mentioned_user_ids: Vec<UserId>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<BufferCommentThreadId>> {
+        let Some(project_id) = self.project_id else {
+            return Task::ready(Err(anyhow!("comments require the project to be shared")));
+        };
+        let request = self.client.request(proto::CreateBufferComment {
+            project_id,
+            thread_id: thread_id.to_proto(),
+            body,
+            mentioned_user_ids,
+        });
+        self.apply_thread_response(request, cx)
+    }
+
+    /// Marks a comment thread as resolved, or reopens it.
+    pub fn set_resolved(
+        &mut self,
+        thread_id: BufferCommentThreadId,
+        resolved: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<BufferCommentThreadId>> {
+        let Some(project_id) = self.project_id else {
+            return Task::ready(Err(anyhow!("comments require the project to be shared")));
+        };
+        let request = self.client.request(proto::SetBufferCommentThreadResolved {
+            project_id,
+            thread_id: thread_id.to_proto(),
+            resolved,
+        });
+        self.apply_thread_response(request, cx)
+    }
+
+    /// Returns the ids of the users mentioned with `@login` in a comment, among the
+    /// given ones.
+    pub fn mentioned_user_ids(
+        &self,
+        body: &str,
+        user_ids: impl IntoIterator<Item = UserId>,
+        cx: &AppContext,
+    ) -> Vec<UserId> {
+        let mentions = body
+            .split(|c: char| c.is_whitespace() || c == ',' || c == ':' || c == '.')
+            .filter_map(|word| word.strip_prefix('@'))
+            .filter(|login| !login.is_empty())
+            .collect::<Vec<_>>();
+        if mentions.is_empty() {
+            return Vec::new();
+        }
+
+        let user_store = self.user_store.read(cx);
+        let mut mentioned_user_ids = Vec::new();
+        for user_id in user_ids {
+            if mentioned_user_ids.contains(&user_id) {
+                continue;
+            }
+            if let Some(user) = user_store.get_cached_user(user_id) {
+                if mentions.contains(&user.github_login.as_str()) {
+                    mentioned_user_ids.push(user_id);
+                }
+            }
+        }
+        mentioned_user_ids
+    }
+
+    fn apply_thread_response(
+        &mut self,
+        request: impl 'static + std::future::Future<Output = Result<proto::BufferCommentThreadResponse>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<BufferCommentThreadId>> {
+        cx.spawn(|this, mut cx| async move {
+            let thread = request.await?.thread.context("missing comment thread")?;
+            this.update(&mut cx, |this, cx| this.apply_thread(thread, cx))?
+                .await
+        })
+    }
+
+    /// Inserts or replaces a thread received from the server, once the users who left
+    /// its comments are loaded.
+    pub(crate) fn apply_thread(
+        &mut self,
+        thread: proto::BufferCommentThread,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<BufferCommentThreadId>> {
+        let user_ids = thread
+            .comments
+            .iter()
+            .map(|comment| comment.sender_id)
+            .collect();
+        let load_users = self
+            .user_store
+            .update(cx, |user_store, cx| user_store.get_users(user_ids, cx));
+        let project_id = self.project_id;
+        cx.spawn(|this, mut cx| async move {
+            load_users.await?;
+            let anchor = match thread.anchor {
+                Some(anchor) => {
+                    Some(deserialize_anchor(anchor).context("invalid comment thread anchor")?)
+                }
+                None => None,
+            };
+            let thread = BufferCommentThread {
+                id: BufferCommentThreadId(thread.id),
+                project_path: ProjectPath {
+                    worktree_id: WorktreeId::from_proto(thread.worktree_id),
+                    path: std::path::Path::new(&thread.path).into(),
+                },
+                anchor,
+                position: Point::new(thread.row, thread.column),
+                resolved: thread.resolved,
+                comments: thread
+                    .comments
+                    .into_iter()
+                    .map(|comment| BufferComment {
+                        id: comment.id,
+                        sender_id: comment.sender_id,
+                        body: comment.body,
+                        timestamp: comment.timestamp,
+                    })
+                    .collect(),
+            };
+            let thread_id = thread.id;
+            this.update(&mut cx, |this, cx| {
+                // Ignore threads that arrive after the project stopped being shared.
+                if this.project_id == project_id {
+                    this.threads.insert(thread_id, thread);
+                    cx.emit(BufferCommentStoreEvent::ThreadsChanged);
+                    cx.notify();
+                }
+            })?;
+            Ok(thread_id)
+        })
+    }
+}
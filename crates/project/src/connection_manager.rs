@@ -12,7 +12,8 @@ use util::{ResultExt, TryFutureExt};
 impl Global for GlobalManager {}
 struct GlobalManager(Model<Manager>);
 
-pub const RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const INITIAL_REJOIN_DELAY: Duration = Duration::from_millis(500);
+const MAX_REJOIN_DELAY: Duration = Duration::from_secs(30);
 
 pub struct Manager {
     client: Arc<Client>,
@@ -70,6 +71,14 @@ impl Manager {
         }
     }
 
+    fn reconnecting(&mut self, cx: &mut ModelContext<Self>) {
+        for project in &self.projects {
+            if let Some(project) = project.upgrade() {
+                project.update(cx, |project, cx| project.reconnecting(cx));
+            }
+        }
+    }
+
     fn reconnected(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
         let mut projects = HashMap::default();
 
@@ -80,6 +89,9 @@ impl Manager {
                 .filter_map(|project| {
                     if let Some(handle) = project.upgrade() {
                         let project = handle.read(cx);
+                        if project.is_disconnected() {
+                            return None;
+                        }
                         let project_id = project.remote_id()?;
                         projects.insert(project_id, handle.clone());
                         Some(proto::RejoinProject {
@@ -108,12 +120,18 @@ impl Manager {
 
             this.update(&mut cx, |_, cx| {
                 for rejoined_project in response.payload.rejoined_projects {
-                    if let Some(project) = projects.get(&rejoined_project.id) {
+                    if let Some(project) = projects.remove(&rejoined_project.id) {
                         project.update(cx, |project, cx| {
                             project.rejoined(rejoined_project, message_id, cx).log_err();
                         });
                     }
                 }
+
+                // The host stopped sharing the projects that couldn't be rejoined while we
+                // were away, so their buffers can't be reconciled anymore.
+                for project in projects.into_values() {
+                    project.update(cx, |project, cx| project.disconnected_from_host(cx));
+                }
             })
         })
     }
@@ -121,10 +139,7 @@ impl Manager {
     fn connection_lost(&mut self, cx: &mut ModelContext<Self>) {
         for project in self.projects.drain() {
             if let Some(project) = project.upgrade() {
-                project.update(cx, |project, cx| {
-                    project.disconnected_from_host(cx);
-                    project.close(cx);
-                });
+                project.update(cx, |project, cx| project.disconnected_from_host(cx));
             }
         }
         self.maintain_connection.take();
@@ -141,72 +156,46 @@ impl Manager {
 
             let is_connected = client_status.borrow().is_connected();
             // Even if we're initially connected, any future change of the status means we momentarily disconnected.
-            if !is_connected || client_status.next().await.is_some() {
-                log::info!("detected client disconnection");
-
-                // Wait for client to re-establish a connection to the server.
-                {
-                    let mut reconnection_timeout =
-                        cx.background_executor().timer(RECONNECT_TIMEOUT).fuse();
-                    let client_reconnection = async {
-                        let mut remaining_attempts = 3;
-                        while remaining_attempts > 0 {
-                            if client_status.borrow().is_connected() {
-                                log::info!("client reconnected, attempting to rejoin projects");
-
-                                let Some(this) = this.upgrade() else { break };
-                                match this.update(&mut cx, |this, cx| this.reconnected(cx)) {
-                                    Ok(task) => {
-                                        if task.await.log_err().is_some() {
-                                            return true;
-                                        } else {
-                                            remaining_attempts -= 1;
-                                        }
-                                    }
-                                    Err(_app_dropped) => return false,
-                                }
-                            } else if client_status.borrow().is_signed_out() {
-                                return false;
-                            }
+            if is_connected && client_status.next().await.is_none() {
+                break;
+            }
 
-                            log::info!(
-                                "waiting for client status change, remaining attempts {}",
-                                remaining_attempts
-                            );
-                            client_status.next().await;
-                        }
-                        false
+            log::info!("detected client disconnection");
+            this.update(&mut cx, |this, cx| this.reconnecting(cx))?;
+
+            // The client keeps reconnecting to the server on its own, so keep trying to rejoin
+            // the projects until it signs out, backing off when rejoining fails.
+            let mut rejoin_delay = INITIAL_REJOIN_DELAY;
+            loop {
+                if client_status.borrow().is_connected() {
+                    log::info!("client reconnected, attempting to rejoin projects");
+                    let rejoin = this.update(&mut cx, |this, cx| this.reconnected(cx))?;
+                    if rejoin.await.log_err().is_some() {
+                        log::info!("successfully rejoined projects");
+                        break;
                     }
-                    .fuse();
-                    futures::pin_mut!(client_reconnection);
 
+                    log::info!("failed to rejoin projects, retrying in {rejoin_delay:?}");
+                    let mut retry = cx.background_executor().timer(rejoin_delay).fuse();
                     futures::select_biased! {
-                        reconnected = client_reconnection => {
-                            if reconnected {
-                                log::info!("successfully reconnected");
-                                // If we successfully joined the room, go back around the loop
-                                // waiting for future connection status changes.
-                                continue;
-                            }
-                        }
-                        _ = reconnection_timeout => {
-                            log::info!("rejoin project reconnection timeout expired");
-                        }
+                        _ = client_status.next().fuse() => {}
+                        _ = retry => {}
                     }
+                    rejoin_delay = (rejoin_delay * 2).min(MAX_REJOIN_DELAY);
+                    continue;
+                } else if client_status.borrow().is_signed_out() {
+                    log::info!("client signed out, disconnecting projects");
+                    this.update(&mut cx, |this, cx| this.connection_lost(cx))?;
+                    return Ok(());
                 }
 
-                break;
+                log::info!("waiting for client status change");
+                if client_status.next().await.is_none() {
+                    return Ok(());
+                }
             }
         }
 
-        // The client failed to re-establish a connection to the server
-        // or an error occurred while trying to re-join the room. Either way
-        // we leave the room and return an error.
-        if let Some(this) = this.upgrade() {
-            log::info!("reconnection failed, disconnecting projects");
-            let _ = this.update(&mut cx, |this, cx| this.connection_lost(cx))?;
-        }
-
         Ok(())
     }
 }
@@ -0,0 +1,258 @@
+use crate::{
+    deserialize_task_source_kind, project_settings::GuestTaskPermission,
+    project_settings::ProjectSettings, serialize_task_source_kind, Event, Project, TaskSourceKind,
+};
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, TypedEnvelope, User, UserId};
+use collections::{HashMap, HashSet};
+use gpui::{AsyncAppContext, Model, ModelContext, Task};
+use settings::Settings;
+use std::sync::Arc;
+use task::{SpawnInTerminal, TaskContext, TerminalWorkDir};
+use terminal::Terminal;
+
+/// The task contexts the host of a shared project resolved for each of its guests, and the
+/// tasks each guest was allowed to run without asking the host again. Forgotten when the
+/// project stops being shared.
+#[derive(Default)]
+pub(crate) struct GuestTasks {
+    contexts: HashMap<proto::PeerId, TaskContext>,
+    sessions: HashSet<UserId>,
+    tasks: HashSet<ApprovedTask>,
+}
+
+/// A task the host allowed a guest to run, as it was resolved on the host. Changing its
+/// command, the environment its template sets or the directory it runs in makes it a
+/// different task.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ApprovedTask {
+    user_id: UserId,
+    kind: TaskSourceKind,
+    command: String,
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+    cwd: Option<TerminalWorkDir>,
+}
+
+impl GuestTasks {
+    /// Remembers the task context the host resolved for a guest, which the tasks that guest
+    /// asks for are resolved with.
+    pub(crate) fn set_context(&mut self, guest_id: proto::PeerId, context: TaskContext) {
+        self.contexts.insert(guest_id, context);
+    }
+
+    fn allows(&self, task: &ApprovedTask) -> bool {
+        self.sessions.contains(&task.user_id) || self.tasks.contains(task)
+    }
+
+    fn approve(&mut self, approval: GuestTaskApproval, task: ApprovedTask) {
+        match approval {
+            GuestTaskApproval::Task => {
+                self.tasks.insert(task);
+            }
+            GuestTaskApproval::Session => {
+                self.sessions.insert(task.user_id);
+            }
+        }
+    }
+}
+
+/// How long the host allowed a guest to run tasks without asking again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GuestTaskApproval {
+    /// Only the task the guest asked for, for as long as it resolves to the same command,
+    /// environment and working directory.
+    Task,
+    /// Every task, until the project stops being shared.
+    Session,
+}
+
+/// A task a guest of this project asked the host to run.
+#[derive(Clone, Debug)]
+pub struct GuestTaskRequest {
+    requester: Option<Arc<User>>,
+    spawn_in_terminal: SpawnInTerminal,
+    template_env: Vec<(String, String)>,
+    needs_approval: bool,
+    response_tx: smol::channel::Sender<Result<(GuestTaskApproval, Model<Terminal>)>>,
+}
+
+impl GuestTaskRequest {
+    /// The guest asking, if they're known to the host.
+    pub fn requester(&self) -> Option<&Arc<User>> {
+        self.requester.as_ref()
+    }
+
+    /// The task, resolved on the host.
+    pub fn spawn_in_terminal(&self) -> &SpawnInTerminal {
+        &self.spawn_in_terminal
+    }
+
+    /// The environment variables the task's template sets, as resolved on the host, unlike
+    /// the task variables every task is given.
+    pub fn template_env(&self) -> &[(String, String)] {
+        &self.template_env
+    }
+
+    /// Whether the host has to approve the task before it's run.
+    pub fn needs_approval(&self) -> bool {
+        self.needs_approval
+    }
+
+    /// Answers the guest with the terminal the task was spawned in, or with the reason it
+    /// was not.
+    pub fn respond(&self, response: Result<(GuestTaskApproval, Model<Terminal>)>) {
+        self.response_tx.try_send(response).ok();
+    }
+}
+
+impl PartialEq for GuestTaskRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.spawn_in_terminal == other.spawn_in_terminal
+            && self.needs_approval == other.needs_approval
+    }
+}
+
+impl Project {
+    /// Asks the host of this project to run the task with the given label from one of its
+    /// task sources. The host resolves the task with the task context it last resolved for
+    /// this guest, and shares the terminal running it with this guest only.
+    pub fn spawn_task_on_host(
+        &self,
+        kind: &TaskSourceKind,
+        label: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project_id) = self.remote_id().filter(|_| self.runs_tasks_on_host()) else {
+            return Task::ready(Err(anyhow!("project does not run tasks on its host")));
+        };
+        if *kind == TaskSourceKind::UserInput {
+            return Task::ready(Err(anyhow!(
+                "only the host's own tasks can be run on the host"
+            )));
+        }
+
+        let request = self.client.request(proto::SpawnHostTask {
+            project_id,
+            kind: Some(serialize_task_source_kind(kind)),
+            label,
+            requester_role: proto::ChannelRole::Guest.into(),
+        });
+        cx.background_executor().spawn(async move {
+            request.await?;
+            Ok(())
+        })
+    }
+
+    pub(crate) async fn handle_spawn_host_task(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::SpawnHostTask>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::SpawnHostTaskResponse> {
+        let guest_id = envelope.original_sender_id()?;
+        let role = envelope.payload.requester_role();
+        let label = envelope.payload.label;
+        let kind = envelope
+            .payload
+            .kind
+            .and_then(deserialize_task_source_kind)
+            .context("invalid task source")?;
+        anyhow::ensure!(
+            kind != TaskSourceKind::UserInput,
+            "only the host's own tasks can be run on the host"
+        );
+
+        let (user_id, permission, language) = this.update(&mut cx, |this, cx| {
+            anyhow::ensure!(
+                this.is_local() && this.is_shared(),
+                "project is not shared by this host"
+            );
+            let user_id = this
+                .collaborators
+                .get(&guest_id)
+                .context("task requested by an unknown collaborator")?
+                .user_id;
+            let permission = ProjectSettings::get_global(cx)
+                .guest_tasks
+                .permission_for(role);
+            anyhow::ensure!(
+                permission != GuestTaskPermission::Deny,
+                "the host does not allow guests to run tasks"
+            );
+            let language = match &kind {
+                TaskSourceKind::Language { name } => Some(this.languages.language_for_name(name)),
+                _ => None,
+            };
+            Ok((user_id, permission, language))
+        })??;
+        let language = match language {
+            Some(language) => Some(language.await?),
+            None => None,
+        };
+
+        let (response_tx, response_rx) = smol::channel::bounded(1);
+        let approved_task = this.update(&mut cx, |this, cx| {
+            let template = this
+                .task_inventory()
+                .read(cx)
+                .list_tasks(None, language, kind.worktree(), cx)
+                .into_iter()
+                .find(|(template_kind, template)| *template_kind == kind && template.label == label)
+                .map(|(_, template)| template)
+                .context("no such task on the host")?;
+
+            // Variables are never taken from the guest, as they end up in the command.
+            let mut context = this
+                .guest_tasks
+                .contexts
+                .get(&guest_id)
+                .cloned()
+                .unwrap_or_default();
+            if context.cwd.is_none() {
+                context.cwd = this.task_cwd(cx)?;
+            }
+            let spawn_in_terminal = template
+                .resolve_task(&kind.to_id_base(), &context)
+                .and_then(|task| task.resolved)
+                .context("failed to resolve the task on the host")?;
+
+            let mut template_env = spawn_in_terminal
+                .env
+                .iter()
+                .filter(|(name, _)| template.env.contains_key(*name))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect::<Vec<_>>();
+            template_env.sort();
+            let approved_task = ApprovedTask {
+                user_id,
+                kind,
+                command: spawn_in_terminal.command.clone(),
+                args: spawn_in_terminal.args.clone(),
+                env: template_env.clone(),
+                cwd: spawn_in_terminal.cwd.clone(),
+            };
+
+            let needs_approval =
+                permission == GuestTaskPermission::Ask && !this.guest_tasks.allows(&approved_task);
+            let requester = this.user_store.read(cx).get_cached_user(user_id);
+            cx.emit(Event::GuestTaskRequested(GuestTaskRequest {
+                requester,
+                spawn_in_terminal,
+                template_env,
+                needs_approval,
+                response_tx,
+            }));
+            anyhow::Ok(approved_task)
+        })??;
+
+        let (approval, terminal) = response_rx
+            .recv()
+            .await
+            .map_err(|_| anyhow!("the host did not run the task"))??;
+        this.update(&mut cx, |this, cx| {
+            this.guest_tasks.approve(approval, approved_task);
+            let terminal_id = this.share_terminal_with_guest(&terminal, guest_id, cx)?;
+            Ok(proto::SpawnHostTaskResponse { terminal_id })
+        })?
+    }
+}
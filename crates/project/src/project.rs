@@ -1,5 +1,8 @@
+pub mod bookmark_store;
+pub mod buffer_comment_store;
 pub mod connection_manager;
 pub mod debounced_delay;
+pub mod guest_tasks;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
@@ -7,6 +10,7 @@ pub mod project_settings;
 pub mod search;
 mod task_inventory;
 pub mod terminals;
+mod undo_history;
 
 #[cfg(test)]
 mod project_tests;
@@ -14,6 +18,8 @@ pub mod search_history;
 
 use anyhow::{anyhow, bail, Context as _, Result};
 use async_trait::async_trait;
+use bookmark_store::{BookmarkStore, SerializedBookmark};
+use buffer_comment_store::BufferCommentStore;
 use client::{
     proto, Client, Collaborator, DevServerProjectId, PendingEntitySubscription, ProjectId,
     TypedEnvelope, UserStore,
@@ -39,6 +45,7 @@ use gpui::{
     AnyModel, AppContext, AsyncAppContext, BackgroundExecutor, BorrowAppContext, Context, Entity,
     EventEmitter, Model, ModelContext, PromptLevel, SharedString, Task, WeakModel, WindowContext,
 };
+use guest_tasks::GuestTasks;
 use http::{HttpClient, Url};
 use itertools::Itertools;
 use language::{
@@ -117,6 +124,7 @@ use util::{
 use worktree::{CreatedEntry, RemoteWorktreeClient, Snapshot, Traversal};
 
 pub use fs::*;
+pub use guest_tasks::{GuestTaskApproval, GuestTaskRequest};
 pub use language::Location;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
@@ -124,9 +132,9 @@ pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, Inventory, TaskSourceKind,
 };
 pub use worktree::{
-    Entry, EntryKind, File, LocalWorktree, PathChange, ProjectEntryId, RepositoryEntry,
-    UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId, WorktreeSettings,
-    FS_WATCH_LATENCY,
+    Entry, EntryKind, File, LoadProgress, LocalWorktree, PathChange, ProjectEntryId,
+    RepositoryEntry, UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId,
+    WorktreeSettings, FS_WATCH_LATENCY,
 };
 
 const MAX_SERVER_REINSTALL_ATTEMPT_COUNT: u64 = 4;
@@ -210,6 +218,8 @@ pub struct Project {
     loading_local_worktrees:
         HashMap<Arc<Path>, Shared<Task<Result<Model<Worktree>, Arc<anyhow::Error>>>>>,
     opened_buffers: HashMap<BufferId, OpenBuffer>,
+    /// How far along the large files that are being opened are.
+    large_file_loads: HashMap<ProjectPath, LoadProgress>,
     local_buffer_ids_by_path: HashMap<ProjectPath, BufferId>,
     local_buffer_ids_by_entry_id: HashMap<ProjectEntryId, BufferId>,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
@@ -220,14 +230,18 @@ pub struct Project {
     _maintain_buffer_languages: Task<()>,
     _maintain_workspace_config: Task<Result<()>>,
     terminals: Terminals,
+    guest_tasks: GuestTasks,
     current_lsp_settings: HashMap<Arc<str>, LspSettings>,
     node: Option<Arc<dyn NodeRuntime>>,
     default_prettier: DefaultPrettier,
     prettiers_per_worktree: HashMap<WorktreeId, HashSet<Option<PathBuf>>>,
     prettier_instances: HashMap<PathBuf, PrettierInstance>,
     tasks: Model<Inventory>,
+    bookmarks: Model<BookmarkStore>,
+    buffer_comments: Model<BufferCommentStore>,
     hosted_project_id: Option<ProjectId>,
     dev_server_project_id: Option<client::DevServerProjectId>,
+    ssh_connection: Option<SharedString>,
     search_history: SearchHistory,
 }
 
@@ -286,6 +300,7 @@ enum ProjectClientState {
     },
     Remote {
         sharing_has_stopped: bool,
+        is_reconnecting: bool,
         capability: Capability,
         remote_id: u64,
         replica_id: ReplicaId,
@@ -344,6 +359,10 @@ pub enum Event {
         language_server_id: LanguageServerId,
     },
     RemoteIdChanged(Option<u64>),
+    /// The connection to the host of this remote project was lost, and is being re-established.
+    Reconnecting,
+    /// The connection to the host of this remote project was re-established after being lost.
+    Reconnected,
     DisconnectedFromHost,
     Closed,
     DeletedEntry(ProjectEntryId),
@@ -356,6 +375,14 @@ pub enum Event {
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
+    /// The host of this project shared a terminal with this guest.
+    TerminalShared(Model<terminal::Terminal>),
+    /// The host of this project stopped sharing a terminal.
+    TerminalUnshared(Model<terminal::Terminal>),
+    /// A guest of this project asked the host to run one of its tasks.
+    GuestTaskRequested(GuestTaskRequest),
+    /// The host of this project started a task this guest asked for, in the given terminal.
+    HostTaskStarted(Model<terminal::Terminal>),
 }
 
 pub enum LanguageServerState {
@@ -640,6 +667,9 @@ pub const DEFAULT_COMPLETION_CONTEXT: CompletionContext = CompletionContext {
     trigger_character: None,
 };
 
+/// The id of the project served by a `zed --ssh-server` process, which only ever serves one.
+pub const SSH_PROJECT_ID: u64 = 1;
+
 impl Project {
     pub fn init_settings(cx: &mut AppContext) {
         WorktreeSettings::register(cx);
@@ -649,7 +679,12 @@ impl Project {
     pub fn init(client: &Arc<Client>, cx: &mut AppContext) {
         connection_manager::init(client.clone(), cx);
         Self::init_settings(cx);
+        Self::init_client(client);
+    }
 
+    /// Registers the handlers of the messages a project receives from its host or guests
+    /// with the given client. [`Project::init`] does this for the app's own client.
+    pub fn init_client(client: &Arc<Client>) {
         client.add_model_message_handler(Self::handle_add_collaborator);
         client.add_model_message_handler(Self::handle_update_project_collaborator);
         client.add_model_message_handler(Self::handle_remove_collaborator);
@@ -684,6 +719,7 @@ impl Project {
         client.add_model_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetHover>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDefinition>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDeclaration>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetTypeDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetReferences>);
@@ -704,6 +740,12 @@ impl Project {
         client.add_model_request_handler(Self::handle_task_context_for_location);
         client.add_model_request_handler(Self::handle_task_templates);
         client.add_model_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_model_message_handler(Self::handle_share_terminal);
+        client.add_model_message_handler(Self::handle_unshare_terminal);
+        client.add_model_message_handler(Self::handle_update_terminal);
+        client.add_model_request_handler(Self::handle_terminal_input);
+        client.add_model_request_handler(Self::handle_spawn_host_task);
+        client.add_model_message_handler(Self::handle_update_buffer_comment_thread);
     }
 
     pub fn local(
@@ -719,6 +761,8 @@ impl Project {
             cx.spawn(move |this, cx| Self::send_buffer_ordered_messages(this, rx, cx))
                 .detach();
             let tasks = Inventory::new(cx);
+            let bookmarks = BookmarkStore::new(cx);
+            let buffer_comments = BufferCommentStore::new(client.clone(), user_store.clone(), cx);
 
             Self {
                 worktrees: Vec::new(),
@@ -728,6 +772,7 @@ impl Project {
                 opened_buffers: Default::default(),
                 shared_buffers: Default::default(),
                 loading_buffers_by_path: Default::default(),
+                large_file_loads: Default::default(),
                 loading_local_worktrees: Default::default(),
                 local_buffer_ids_by_path: Default::default(),
                 local_buffer_ids_by_entry_id: Default::default(),
@@ -765,17 +810,19 @@ impl Project {
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 nonce: StdRng::from_entropy().gen(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
+                guest_tasks: GuestTasks::default(),
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
                 node: Some(node),
                 default_prettier: DefaultPrettier::default(),
                 prettiers_per_worktree: HashMap::default(),
                 prettier_instances: HashMap::default(),
                 tasks,
+                bookmarks,
+                buffer_comments,
                 hosted_project_id: None,
                 dev_server_project_id: None,
+                ssh_connection: None,
                 search_history: Self::new_search_history(),
             }
         })
@@ -841,6 +888,8 @@ impl Project {
         let this = cx.new_model(|cx| {
             let replica_id = response.payload.replica_id as ReplicaId;
             let tasks = Inventory::new(cx);
+            let bookmarks = BookmarkStore::new(cx);
+            let buffer_comments = BufferCommentStore::new(client.clone(), user_store.clone(), cx);
             // BIG CAUTION NOTE: The order in which we initialize fields here matters and it should match what's done in Self::local.
             // Otherwise, you might run into issues where worktree id on remote is different than what's on local host.
             // That's because Worktree's identifier is entity id, which should probably be changed.
@@ -864,6 +913,7 @@ impl Project {
                 worktrees_reordered: false,
                 buffer_ordered_messages_tx: tx,
                 loading_buffers_by_path: Default::default(),
+                large_file_loads: Default::default(),
                 loading_buffers: Default::default(),
                 shared_buffers: Default::default(),
                 incomplete_remote_buffers: Default::default(),
@@ -890,6 +940,7 @@ impl Project {
                 client: client.clone(),
                 client_state: ProjectClientState::Remote {
                     sharing_has_stopped: false,
+                    is_reconnecting: false,
                     capability: Capability::ReadWrite,
                     remote_id,
                     replica_id,
@@ -924,23 +975,27 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 buffer_snapshots: Default::default(),
                 nonce: StdRng::from_entropy().gen(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
+                guest_tasks: GuestTasks::default(),
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
                 node: None,
                 default_prettier: DefaultPrettier::default(),
                 prettiers_per_worktree: HashMap::default(),
                 prettier_instances: HashMap::default(),
                 tasks,
+                bookmarks,
+                buffer_comments,
                 hosted_project_id: None,
                 dev_server_project_id: response
                     .payload
                     .dev_server_project_id
                     .map(|dev_server_project_id| DevServerProjectId(dev_server_project_id)),
+                ssh_connection: None,
                 search_history: Self::new_search_history(),
             };
             this.set_role(role, cx);
+            this.buffer_comments
+                .update(cx, |store, cx| store.set_project_id(Some(remote_id), cx));
             for worktree in worktrees {
                 let _ = this.add_worktree(&worktree, cx);
             }
@@ -995,6 +1050,54 @@ impl Project {
         .await
     }
 
+    /// Joins the project served by a `zed --ssh-server` process on another machine, which
+    /// the given client is already [connected to](Client::connect_with) over SSH.
+    pub async fn ssh(
+        ssh_connection: SharedString,
+        client: Arc<Client>,
+        user_store: Model<UserStore>,
+        languages: Arc<LanguageRegistry>,
+        fs: Arc<dyn Fs>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Model<Self>> {
+        let subscription = client.subscribe_to_entity(SSH_PROJECT_ID)?;
+        let response = client
+            .request_envelope(proto::JoinProject {
+                project_id: SSH_PROJECT_ID,
+            })
+            .await?;
+        let project = Self::from_join_project_response(
+            response,
+            subscription,
+            client.clone(),
+            user_store,
+            languages,
+            fs,
+            cx.clone(),
+        )
+        .await?;
+        project.update(&mut cx, |project, _| {
+            project.ssh_connection = Some(ssh_connection);
+        })?;
+
+        let mut status = client.status();
+        let project_handle = project.downgrade();
+        cx.spawn(|mut cx| async move {
+            while let Some(status) = status.next().await {
+                if status.is_connected() {
+                    continue;
+                }
+                project_handle
+                    .update(&mut cx, |project, cx| project.disconnected_from_host(cx))
+                    .ok();
+                break;
+            }
+        })
+        .detach();
+
+        Ok(project)
+    }
+
     fn new_search_history() -> SearchHistory {
         SearchHistory::new(
             Some(MAX_PROJECT_SEARCH_HISTORY_SIZE),
@@ -1290,7 +1393,19 @@ impl Project {
         self.dev_server_project_id
     }
 
+    /// Whether tasks spawned in this project run on the machine of its host, which is the
+    /// case for guests of a project shared over collab.
+    pub fn runs_tasks_on_host(&self) -> bool {
+        self.is_remote()
+            && self.dev_server_project_id.is_none()
+            && self.hosted_project_id.is_none()
+            && self.ssh_connection.is_none()
+    }
+
     pub fn supports_remote_terminal(&self, cx: &AppContext) -> bool {
+        if self.ssh_connection.is_some() {
+            return true;
+        }
         let Some(id) = self.dev_server_project_id else {
             return false;
         };
@@ -1307,6 +1422,9 @@ impl Project {
         if self.is_local() {
             return None;
         }
+        if let Some(ssh_connection) = &self.ssh_connection {
+            return Some(ssh_connection.clone());
+        }
 
         let dev_server_id = self.dev_server_project_id()?;
         dev_server_projects::Store::global(cx)
@@ -1336,6 +1454,26 @@ impl Project {
         &self.tasks
     }
 
+    pub fn bookmark_store(&self) -> &Model<BookmarkStore> {
+        &self.bookmarks
+    }
+
+    pub fn buffer_comment_store(&self) -> &Model<BufferCommentStore> {
+        &self.buffer_comments
+    }
+
+    /// Adds stored bookmarks to the ones placed since the project was opened.
+    pub fn restore_bookmarks(
+        &mut self,
+        bookmarks: Vec<SerializedBookmark>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let open_buffers = self.opened_buffers();
+        self.bookmarks.update(cx, |store, cx| {
+            store.restore(bookmarks, &open_buffers, cx);
+        });
+    }
+
     pub fn search_history(&self) -> &SearchHistory {
         &self.search_history
     }
@@ -1689,6 +1827,8 @@ impl Project {
         };
 
         self.metadata_changed(cx);
+        self.buffer_comments
+            .update(cx, |store, cx| store.set_project_id(Some(project_id), cx));
         cx.emit(Event::RemoteIdChanged(Some(project_id)));
         cx.notify();
         Ok(())
@@ -1739,10 +1879,37 @@ impl Project {
             .collect();
         self.enqueue_buffer_ordered_message(BufferOrderedMessage::Resync)
             .unwrap();
+        if let ProjectClientState::Remote {
+            is_reconnecting, ..
+        } = &mut self.client_state
+        {
+            if *is_reconnecting {
+                *is_reconnecting = false;
+                cx.emit(Event::Reconnected);
+            }
+        }
         cx.notify();
         Ok(())
     }
 
+    /// Marks this remote project as waiting for its connection to the host to be
+    /// re-established. The project stays editable in the meantime: edits are kept in the
+    /// buffers' histories and sent to the host when the project is rejoined.
+    pub fn reconnecting(&mut self, cx: &mut ModelContext<Self>) {
+        if let ProjectClientState::Remote {
+            sharing_has_stopped: false,
+            is_reconnecting,
+            ..
+        } = &mut self.client_state
+        {
+            if !*is_reconnecting {
+                *is_reconnecting = true;
+                cx.emit(Event::Reconnecting);
+                cx.notify();
+            }
+        }
+    }
+
     pub fn unshare(&mut self, cx: &mut ModelContext<Self>) -> Result<()> {
         self.unshare_internal(cx)?;
         self.metadata_changed(cx);
@@ -1767,6 +1934,10 @@ impl Project {
             self.collaborators.clear();
             self.shared_buffers.clear();
             self.client_subscriptions.clear();
+            self.clear_terminals();
+            self.guest_tasks = GuestTasks::default();
+            self.buffer_comments
+                .update(cx, |store, cx| store.set_project_id(None, cx));
 
             for worktree_handle in self.worktrees.iter_mut() {
                 if let WorktreeHandle::Strong(worktree) = worktree_handle {
@@ -1832,12 +2003,17 @@ impl Project {
     fn disconnected_from_host_internal(&mut self, cx: &mut AppContext) {
         if let ProjectClientState::Remote {
             sharing_has_stopped,
+            is_reconnecting,
             ..
         } = &mut self.client_state
         {
             *sharing_has_stopped = true;
+            *is_reconnecting = false;
 
             self.collaborators.clear();
+            self.clear_terminals();
+            self.buffer_comments
+                .update(cx, |store, cx| store.set_project_id(None, cx));
 
             for worktree in &self.worktrees {
                 if let Some(worktree) = worktree.upgrade() {
@@ -1884,6 +2060,15 @@ impl Project {
         }
     }
 
+    pub fn is_reconnecting(&self) -> bool {
+        match &self.client_state {
+            ProjectClientState::Remote {
+                is_reconnecting, ..
+            } => *is_reconnecting,
+            _ => false,
+        }
+    }
+
     pub fn capability(&self) -> Capability {
         match &self.client_state {
             ProjectClientState::Remote { capability, .. } => *capability,
@@ -1895,6 +2080,13 @@ impl Project {
         self.is_disconnected() || self.capability() == Capability::ReadOnly
     }
 
+    /// The large files that are being read from disk, with how much of each has been read.
+    pub fn large_file_loads(&self) -> impl Iterator<Item = (&ProjectPath, LoadProgress)> {
+        self.large_file_loads
+            .iter()
+            .map(|(path, progress)| (path, *progress))
+    }
+
     pub fn is_local(&self) -> bool {
         match &self.client_state {
             ProjectClientState::Local | ProjectClientState::Shared { .. } => true,
@@ -2059,15 +2251,72 @@ impl Project {
         worktree: Model<Worktree>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Model<Buffer>>> {
+        let (progress_tx, mut progress_rx) = postage::watch::channel();
+        let project_path = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: path.clone(),
+        };
+        cx.spawn(|this, mut cx| async move {
+            while let Some(progress) = progress_rx.next().await {
+                if progress == LoadProgress::default() {
+                    continue;
+                }
+                this.update(&mut cx, |this, cx| {
+                    this.large_file_loads.insert(project_path.clone(), progress);
+                    cx.notify();
+                })?;
+            }
+            this.update(&mut cx, |this, cx| {
+                if this.large_file_loads.remove(&project_path).is_some() {
+                    cx.notify();
+                }
+            })
+        })
+        .detach();
+
+        let fs = self.fs.clone();
+        let persistent_undo = ProjectSettings::get_global(cx)
+            .persistent_undo
+            .unwrap_or(true);
         let load_buffer = worktree.update(cx, |worktree, cx| {
-            let load_file = worktree.load_file(path.as_ref(), cx);
+            let load_file = worktree.load_file_with_progress(path.as_ref(), Some(progress_tx), cx);
+            let history_path = worktree.absolutize(&path).ok().filter(|_| persistent_undo);
             let reservation = cx.reserve_model();
             let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
             cx.spawn(move |_, mut cx| async move {
                 let loaded = load_file.await?;
+                let encoding = loaded.encoding;
+                let is_large = loaded.is_large;
+                let is_private = loaded.file.is_private;
                 let text_buffer = cx
                     .background_executor()
-                    .spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
+                    .spawn(async move {
+                        let text_buffer = text::Buffer::new(0, buffer_id, loaded.text);
+                        let Some(abs_path) = history_path.filter(|_| !is_large && !is_private)
+                        else {
+                            return text_buffer;
+                        };
+                        let text = text_buffer.text();
+                        let Some(history) = undo_history::load(fs.as_ref(), &abs_path, &text).await
+                        else {
+                            return text_buffer;
+                        };
+                        match text::Buffer::from_serialized_history(
+                            0,
+                            buffer_id,
+                            text_buffer.line_ending(),
+                            history,
+                        ) {
+                            Ok(restored) if restored.text() == text => restored,
+                            Ok(_) => text_buffer,
+                            Err(error) => {
+                                log::error!(
+                                    "failed to restore undo history of {abs_path:?}: {error}"
+                                );
+                                text_buffer
+                            }
+                        }
+                    })
                     .await;
                 cx.insert_model(reservation, |_| {
                     Buffer::build(
@@ -2076,6 +2325,8 @@ impl Project {
                         Some(loaded.file),
                         Capability::ReadWrite,
                     )
+                    .with_encoding(encoding)
+                    .with_large_file_mode(is_large)
                 })
             })
         });
@@ -2281,19 +2532,44 @@ impl Project {
         let buffer_id = buffer.remote_id();
         let text = buffer.as_rope().clone();
         let line_ending = buffer.line_ending();
+        let encoding = buffer.encoding();
         let version = buffer.version();
         if buffer.file().is_some_and(|file| !file.is_created()) {
             has_changed_file = true;
         }
+        let history = if ProjectSettings::get_global(cx)
+            .persistent_undo
+            .unwrap_or(true)
+            && !buffer.is_large_file()
+        {
+            worktree
+                .read(cx)
+                .absolutize(&path)
+                .ok()
+                .map(|abs_path| (abs_path, text.clone(), buffer.serialize_history()))
+        } else {
+            None
+        };
 
         let save = worktree.update(cx, |worktree, cx| {
-            worktree.write_file(path.as_ref(), text, line_ending, cx)
+            worktree.write_file(path.as_ref(), text, line_ending, encoding, cx)
         });
 
+        let fs = self.fs.clone();
         let client = self.client.clone();
         let project_id = self.remote_id();
         cx.spawn(move |_, mut cx| async move {
             let new_file = save.await?;
+            // Private files, such as ones holding credentials, are never copied elsewhere.
+            if let Some((abs_path, text, history)) = history.filter(|_| !new_file.is_private) {
+                cx.background_executor()
+                    .spawn(async move {
+                        undo_history::save(fs.as_ref(), &abs_path, &text.to_string(), history)
+                            .await
+                            .log_err();
+                    })
+                    .detach();
+            }
             let mtime = new_file.mtime;
             if has_changed_file {
                 if let Some(project_id) = project_id {
@@ -2419,6 +2695,9 @@ impl Project {
         })
         .detach();
 
+        self.bookmarks
+            .update(cx, |bookmarks, cx| bookmarks.buffer_opened(buffer, cx));
+
         if let Some(file) = File::from_dyn(buffer.read(cx).file()) {
             if file.is_local {
                 self.local_buffer_ids_by_path.insert(
@@ -2472,6 +2751,10 @@ impl Project {
     ) {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        // Large files are not sent to language servers.
+        if buffer.is_large_file() {
+            return;
+        }
 
         if let Some(file) = File::from_dyn(buffer.file()) {
             if !file.is_local() {
@@ -2603,6 +2886,14 @@ impl Project {
             is_local: bool,
             cx: &mut AsyncAppContext,
         ) -> Result<()> {
+            if !is_local && this.update(cx, |this, _| this.is_reconnecting())? {
+                // The operations stay in the buffers' histories while the host can't be
+                // reached, and are sent when the buffers are synchronized on rejoin.
+                operations_by_buffer_id.clear();
+                *needs_resync_with_host = true;
+                return Ok(());
+            }
+
             for (buffer_id, operations) in operations_by_buffer_id.drain() {
                 let request = this.update(cx, |this, _| {
                     let project_id = this.remote_id()?;
@@ -3146,6 +3437,10 @@ impl Project {
     ) {
         // If the buffer has a language, set it and start the language server if we haven't already.
         let buffer = buffer_handle.read(cx);
+        // Large files are not highlighted, which also keeps language servers from starting for them.
+        if buffer.is_large_file() {
+            return;
+        }
         let Some(file) = buffer.file() else {
             return;
         };
@@ -5424,6 +5719,30 @@ impl Project {
         self.definition_impl(buffer, position, cx)
     }
 
+    fn declaration_impl(
+        &self,
+        buffer: &Model<Buffer>,
+        position: PointUtf16,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<LocationLink>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetDeclaration { position },
+            cx,
+        )
+    }
+
+    pub fn declaration<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<LocationLink>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.declaration_impl(buffer, position, cx)
+    }
+
     fn type_definition_impl(
         &self,
         buffer: &Model<Buffer>,
@@ -5594,6 +5913,9 @@ impl Project {
                 };
 
                 let mut symbols = Vec::new();
+                // Several servers can report the same symbol, e.g. when a language has more
+                // than one server or a server is shared between worktrees.
+                let mut seen_symbols = HashSet::default();
                 for (adapter, adapter_language, source_worktree, worktree_abs_path, lsp_symbols) in
                     responses
                 {
@@ -5623,13 +5945,21 @@ impl Project {
                                     path: path.into(),
                                 };
                                 let signature = this.symbol_signature(&project_path);
+                                let range = range_from_lsp(symbol_location.range);
+                                if !seen_symbols.insert((
+                                    project_path.clone(),
+                                    range.clone(),
+                                    symbol_name.clone(),
+                                )) {
+                                    return None;
+                                }
                                 Some(CoreSymbol {
                                     language_server_name: adapter.name.clone(),
                                     source_worktree_id,
                                     path: project_path,
                                     kind: symbol_kind,
                                     name: symbol_name,
-                                    range: range_from_lsp(symbol_location.range),
+                                    range,
                                     signature,
                                 })
                             })
@@ -8730,6 +9060,9 @@ impl Project {
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
+            if this.is_local() {
+                this.resend_shared_terminals(cx);
+            }
             cx.notify();
         })?;
 
@@ -9208,6 +9541,23 @@ impl Project {
         })?
     }
 
+    async fn handle_update_buffer_comment_thread(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateBufferCommentThread>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let thread = envelope
+            .payload
+            .thread
+            .ok_or_else(|| anyhow!("missing comment thread"))?;
+        this.update(&mut cx, |this, cx| {
+            this.buffer_comments
+                .update(cx, |store, cx| store.apply_thread(thread, cx))
+        })?
+        .await?;
+        Ok(())
+    }
+
     async fn handle_update_buffer_file(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::UpdateBufferFile>,
@@ -9696,6 +10046,7 @@ impl Project {
         envelope: TypedEnvelope<proto::TaskContextForLocation>,
         mut cx: AsyncAppContext,
     ) -> Result<proto::TaskContext> {
+        let guest_id = envelope.original_sender_id;
         let location = envelope
             .payload
             .location
@@ -9721,6 +10072,16 @@ impl Project {
             project.task_context_for_location(captured_variables, location, cx)
         })?;
         let task_context = context_task.await.unwrap_or_default();
+        // Tasks guests ask the host to run are resolved with the context the host resolved.
+        if let Some(guest_id) = guest_id {
+            project.update(&mut cx, |project, _| {
+                if project.collaborators.contains_key(&guest_id) {
+                    project
+                        .guest_tasks
+                        .set_context(guest_id, task_context.clone());
+                }
+            })?;
+        }
         Ok(proto::TaskContext {
             cwd: task_context
                 .cwd
@@ -9756,34 +10117,7 @@ impl Project {
             .context("receiving task templates")?
             .into_iter()
             .map(|(kind, template)| {
-                let kind = Some(match kind {
-                    TaskSourceKind::UserInput => proto::task_source_kind::Kind::UserInput(
-                        proto::task_source_kind::UserInput {},
-                    ),
-                    TaskSourceKind::Worktree {
-                        id,
-                        abs_path,
-                        id_base,
-                    } => {
-                        proto::task_source_kind::Kind::Worktree(proto::task_source_kind::Worktree {
-                            id: id.to_proto(),
-                            abs_path: abs_path.to_string_lossy().to_string(),
-                            id_base: id_base.to_string(),
-                        })
-                    }
-                    TaskSourceKind::AbsPath { id_base, abs_path } => {
-                        proto::task_source_kind::Kind::AbsPath(proto::task_source_kind::AbsPath {
-                            abs_path: abs_path.to_string_lossy().to_string(),
-                            id_base: id_base.to_string(),
-                        })
-                    }
-                    TaskSourceKind::Language { name } => {
-                        proto::task_source_kind::Kind::Language(proto::task_source_kind::Language {
-                            name: name.to_string(),
-                        })
-                    }
-                });
-                let kind = Some(proto::TaskSourceKind { kind });
+                let kind = Some(serialize_task_source_kind(&kind));
                 let template = Some(proto::TaskTemplate {
                     label: template.label,
                     command: template.command,
@@ -10820,7 +11154,7 @@ impl Project {
             })
         } else if let Some(project_id) = self
             .remote_id()
-            .filter(|_| self.ssh_connection_string(cx).is_some())
+            .filter(|_| self.ssh_connection_string(cx).is_some() || self.runs_tasks_on_host())
         {
             let task_context = self.client().request(proto::TaskContextForLocation {
                 project_id,
@@ -10876,7 +11210,7 @@ impl Project {
                 .list_tasks(file, language, worktree, cx)))
         } else if let Some(project_id) = self
             .remote_id()
-            .filter(|_| self.ssh_connection_string(cx).is_some())
+            .filter(|_| self.ssh_connection_string(cx).is_some() || self.runs_tasks_on_host())
         {
             let remote_templates =
                 self.query_remote_task_templates(project_id, worktree, location.as_ref(), cx);
@@ -10908,27 +11242,7 @@ impl Project {
                 .templates
                 .into_iter()
                 .filter_map(|template_pair| {
-                    let task_source_kind = match template_pair.kind?.kind? {
-                        proto::task_source_kind::Kind::UserInput(_) => TaskSourceKind::UserInput,
-                        proto::task_source_kind::Kind::Worktree(worktree) => {
-                            TaskSourceKind::Worktree {
-                                id: WorktreeId::from_proto(worktree.id),
-                                abs_path: PathBuf::from(worktree.abs_path),
-                                id_base: Cow::Owned(worktree.id_base),
-                            }
-                        }
-                        proto::task_source_kind::Kind::AbsPath(abs_path) => {
-                            TaskSourceKind::AbsPath {
-                                id_base: Cow::Owned(abs_path.id_base),
-                                abs_path: PathBuf::from(abs_path.abs_path),
-                            }
-                        }
-                        proto::task_source_kind::Kind::Language(language) => {
-                            TaskSourceKind::Language {
-                                name: language.name.into(),
-                            }
-                        }
-                    };
+                    let task_source_kind = deserialize_task_source_kind(template_pair.kind?)?;
 
                     let proto_template = template_pair.template?;
                     let reveal = match proto::RevealStrategy::from_i32(proto_template.reveal)
@@ -11886,6 +12200,53 @@ fn serialize_location(location: &Location, cx: &AppContext) -> proto::Location {
     }
 }
 
+fn serialize_task_source_kind(kind: &TaskSourceKind) -> proto::TaskSourceKind {
+    let kind = match kind {
+        TaskSourceKind::UserInput => {
+            proto::task_source_kind::Kind::UserInput(proto::task_source_kind::UserInput {})
+        }
+        TaskSourceKind::Worktree {
+            id,
+            abs_path,
+            id_base,
+        } => proto::task_source_kind::Kind::Worktree(proto::task_source_kind::Worktree {
+            id: id.to_proto(),
+            abs_path: abs_path.to_string_lossy().to_string(),
+            id_base: id_base.to_string(),
+        }),
+        TaskSourceKind::AbsPath { id_base, abs_path } => {
+            proto::task_source_kind::Kind::AbsPath(proto::task_source_kind::AbsPath {
+                abs_path: abs_path.to_string_lossy().to_string(),
+                id_base: id_base.to_string(),
+            })
+        }
+        TaskSourceKind::Language { name } => {
+            proto::task_source_kind::Kind::Language(proto::task_source_kind::Language {
+                name: name.to_string(),
+            })
+        }
+    };
+    proto::TaskSourceKind { kind: Some(kind) }
+}
+
+fn deserialize_task_source_kind(kind: proto::TaskSourceKind) -> Option<TaskSourceKind> {
+    Some(match kind.kind? {
+        proto::task_source_kind::Kind::UserInput(_) => TaskSourceKind::UserInput,
+        proto::task_source_kind::Kind::Worktree(worktree) => TaskSourceKind::Worktree {
+            id: WorktreeId::from_proto(worktree.id),
+            abs_path: PathBuf::from(worktree.abs_path),
+            id_base: Cow::Owned(worktree.id_base),
+        },
+        proto::task_source_kind::Kind::AbsPath(abs_path) => TaskSourceKind::AbsPath {
+            id_base: Cow::Owned(abs_path.id_base),
+            abs_path: PathBuf::from(abs_path.abs_path),
+        },
+        proto::task_source_kind::Kind::Language(language) => TaskSourceKind::Language {
+            name: language.name.into(),
+        },
+    })
+}
+
 fn deserialize_location(
     project: &Model<Project>,
     location: proto::Location,
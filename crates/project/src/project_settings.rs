@@ -1,3 +1,4 @@
+use client::proto;
 use collections::HashMap;
 use gpui::AppContext;
 use schemars::JsonSchema;
@@ -20,6 +21,57 @@ pub struct ProjectSettings {
     /// Configuration for Git-related features
     #[serde(default)]
     pub git: GitSettings,
+
+    /// Whether to keep the undo history of files after they are closed, so edits
+    /// can still be undone when a file is reopened with the same content.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub persistent_undo: Option<bool>,
+
+    /// Whether guests of a project you share can run its tasks on your machine,
+    /// by their role in the channel.
+    ///
+    /// Only your user settings are used, never the settings of the shared project.
+    #[serde(default)]
+    pub guest_tasks: GuestTaskSettings,
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct GuestTaskSettings {
+    /// Default: ask
+    pub admin: Option<GuestTaskPermission>,
+    /// Default: ask
+    pub member: Option<GuestTaskPermission>,
+    /// Applies to talkers as well.
+    ///
+    /// Default: deny
+    pub guest: Option<GuestTaskPermission>,
+}
+
+impl GuestTaskSettings {
+    pub fn permission_for(&self, role: proto::ChannelRole) -> GuestTaskPermission {
+        match role {
+            proto::ChannelRole::Admin => self.admin.unwrap_or(GuestTaskPermission::Ask),
+            proto::ChannelRole::Member => self.member.unwrap_or(GuestTaskPermission::Ask),
+            proto::ChannelRole::Guest | proto::ChannelRole::Talker => {
+                self.guest.unwrap_or(GuestTaskPermission::Deny)
+            }
+            proto::ChannelRole::Banned => GuestTaskPermission::Deny,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GuestTaskPermission {
+    /// Guests can't run tasks.
+    Deny,
+    /// You're asked before a task runs, and can allow it or every task for the
+    /// rest of the session.
+    Ask,
+    /// Tasks run without asking.
+    Allow,
 }
 
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -4,7 +4,7 @@ use futures::{future, StreamExt};
 use gpui::{AppContext, SemanticVersion, UpdateGlobal};
 use language::{
     language_settings::{AllLanguageSettings, LanguageSettingsContent},
-    tree_sitter_rust, tree_sitter_typescript, Diagnostic, FakeLspAdapter, LanguageConfig,
+    tree_sitter_rust, tree_sitter_typescript, Diagnostic, Encoding, FakeLspAdapter, LanguageConfig,
     LanguageMatcher, LineEnding, OffsetRangeExt, Point, ToPoint,
 };
 use lsp::NumberOrString;
@@ -2795,6 +2795,491 @@ async fn test_save_file(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_persistent_undo_history(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "file1": "the old contents",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/file1", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(4..7, "new")], None, cx);
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    drop(buffer);
+    cx.executor().run_until_parked();
+
+    // The edit made before the file was closed can be undone after reopening it.
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/file1", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "the new contents");
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "the old contents");
+        buffer.redo(cx);
+        assert_eq!(buffer.text(), "the new contents");
+    });
+    drop(buffer);
+    cx.executor().run_until_parked();
+
+    // The history is ignored once the file is changed by another program.
+    fs.insert_file("/dir/file1", b"other contents".to_vec())
+        .await;
+    cx.executor().run_until_parked();
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/file1", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "other contents");
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "other contents");
+    });
+}
+
+#[gpui::test]
+async fn test_bookmarks(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.txt": "one\ntwo\nthree\n",
+            "b.txt": "four\nfive\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let store = project.read_with(cx, |project, _| project.bookmark_store().clone());
+    let bookmark_rows = |cx: &mut gpui::TestAppContext| {
+        store.read_with(cx, |store, cx| {
+            store
+                .sorted_bookmarks(cx)
+                .into_iter()
+                .map(|(bookmark, row)| (bookmark.abs_path.to_path_buf(), row))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let buffer_a = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.txt", cx))
+        .await
+        .unwrap();
+    let buffer_b = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/b.txt", cx))
+        .await
+        .unwrap();
+    store.update(cx, |store, cx| {
+        store.toggle_bookmark(&buffer_a, 1, cx);
+        store.toggle_bookmark(&buffer_a, 2, cx);
+        store.toggle_bookmark(&buffer_b, 0, cx);
+        // Toggling a bookmarked row removes its bookmark.
+        store.toggle_bookmark(&buffer_a, 2, cx);
+    });
+
+    // Bookmarks stay on their line when lines are inserted above it.
+    buffer_a.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+    assert_eq!(
+        bookmark_rows(cx),
+        [
+            (PathBuf::from("/dir/a.txt"), 2),
+            (PathBuf::from("/dir/b.txt"), 0)
+        ]
+    );
+    store.read_with(cx, |store, cx| {
+        let next = store.next_bookmark(Path::new("/dir/a.txt"), 2, cx).unwrap();
+        assert_eq!(
+            store.bookmark(next).unwrap().abs_path.as_ref(),
+            Path::new("/dir/b.txt")
+        );
+        // Navigation wraps around.
+        let next = store.next_bookmark(Path::new("/dir/b.txt"), 0, cx).unwrap();
+        assert_eq!(
+            store.bookmark(next).unwrap().abs_path.as_ref(),
+            Path::new("/dir/a.txt")
+        );
+    });
+
+    // Closing a file keeps the row its bookmarks were on.
+    drop(buffer_a);
+    cx.executor().run_until_parked();
+    assert_eq!(
+        bookmark_rows(cx),
+        [
+            (PathBuf::from("/dir/a.txt"), 2),
+            (PathBuf::from("/dir/b.txt"), 0)
+        ]
+    );
+
+    // Restored bookmarks are anchored in the files that are open, or opened later.
+    let serialized = store.read_with(cx, |store, cx| store.serialize(cx));
+    project.update(cx, |project, cx| project.restore_bookmarks(serialized, cx));
+    assert_eq!(
+        bookmark_rows(cx),
+        [
+            (PathBuf::from("/dir/a.txt"), 2),
+            (PathBuf::from("/dir/b.txt"), 0)
+        ]
+    );
+    let buffer_a = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.txt", cx))
+        .await
+        .unwrap();
+    buffer_a.update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+    buffer_b.update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+    assert_eq!(
+        bookmark_rows(cx),
+        [
+            (PathBuf::from("/dir/a.txt"), 3),
+            (PathBuf::from("/dir/b.txt"), 1)
+        ]
+    );
+
+    // Restoring keeps the bookmarks placed while the stored ones were loading, which win
+    // over stored bookmarks for the same mark.
+    store.update(cx, |store, cx| {
+        store.set_mark('A', &buffer_b, Point::new(2, 2), cx);
+    });
+    let stored = vec![
+        bookmark_store::SerializedBookmark {
+            abs_path: Path::new("/dir/a.txt").into(),
+            row: 0,
+            column: 0,
+            label: None,
+            mark: None,
+        },
+        bookmark_store::SerializedBookmark {
+            abs_path: Path::new("/dir/a.txt").into(),
+            row: 1,
+            column: 1,
+            label: None,
+            mark: Some('A'),
+        },
+    ];
+    project.update(cx, |project, cx| project.restore_bookmarks(stored, cx));
+    assert_eq!(
+        bookmark_rows(cx),
+        [
+            (PathBuf::from("/dir/a.txt"), 0),
+            (PathBuf::from("/dir/a.txt"), 3),
+            (PathBuf::from("/dir/b.txt"), 1),
+            (PathBuf::from("/dir/b.txt"), 2)
+        ]
+    );
+    store.read_with(cx, |store, cx| {
+        let mark = store.bookmark_for_mark('A').unwrap();
+        assert_eq!(mark.abs_path.as_ref(), Path::new("/dir/b.txt"));
+        assert_eq!(store.point(mark, cx), Point::new(2, 2));
+    });
+}
+
+#[gpui::test]
+async fn test_persistent_undo_history_skips_private_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".env": "TOKEN=old",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/.env", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert!(buffer.file().unwrap().is_private());
+        buffer.edit([(6..9, "new")], None, cx);
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    drop(buffer);
+    cx.executor().run_until_parked();
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/.env", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "TOKEN=new");
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "TOKEN=new");
+    });
+}
+
+#[gpui::test]
+async fn test_open_large_file(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<WorktreeSettings>(cx, |settings| {
+                settings.large_file_threshold_mb = Some(1);
+            });
+        })
+    });
+
+    let large_text = "fn main() {}\n".repeat(100_000);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "large.rs": large_text,
+            "small.rs": "fn main() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+    let large_buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/large.rs", cx))
+        .await
+        .unwrap();
+    let small_buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/small.rs", cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    large_buffer.update(cx, |buffer, cx| {
+        assert!(buffer.is_large_file());
+        assert_eq!(buffer.text(), large_text);
+        assert!(buffer.language().is_none());
+        assert!(buffer.diff_base().is_none());
+
+        // Large buffers can still be edited once the editor is made writable.
+        assert!(!buffer.read_only());
+        buffer.edit([(0..0, "// ")], None, cx);
+    });
+    small_buffer.read_with(cx, |buffer, _| {
+        assert!(!buffer.is_large_file());
+        assert_eq!(
+            buffer.language().map(|language| language.name()),
+            Some("Rust".into())
+        );
+    });
+    project.read_with(cx, |project, _| {
+        assert_eq!(project.large_file_loads().count(), 0)
+    });
+
+    project
+        .update(cx, |project, cx| {
+            project.save_buffer(large_buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load(Path::new("/dir/large.rs")).await.unwrap(),
+        format!("// {large_text}")
+    );
+}
+
+#[gpui::test]
+async fn test_large_file_never_attaches_language_servers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<WorktreeSettings>(cx, |settings| {
+                settings.large_file_threshold_mb = Some(1);
+            });
+        })
+    });
+
+    let large_text = "fn main() {}\n".repeat(100_000);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "large.txt": large_text,
+            "other.txt": large_text,
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers =
+        language_registry.register_fake_lsp_adapter("Rust", FakeLspAdapter::default());
+
+    let renamed_buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/large.txt", cx))
+        .await
+        .unwrap();
+    let saved_as_buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/other.txt", cx))
+        .await
+        .unwrap();
+
+    // Renaming a large file to a Rust file doesn't start the Rust language server.
+    fs.rename(
+        Path::new("/dir/large.txt"),
+        Path::new("/dir/large.rs"),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+
+    // Neither does saving one as a Rust file.
+    project
+        .update(cx, |project, cx| {
+            let worktree_id = project.worktrees().next().unwrap().read(cx).id();
+            let path = ProjectPath {
+                worktree_id,
+                path: Arc::from(Path::new("other.rs")),
+            };
+            project.save_buffer_as(saved_as_buffer.clone(), path, cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    for (buffer, path) in [
+        (&renamed_buffer, "dir/large.rs"),
+        (&saved_as_buffer, "dir/other.rs"),
+    ] {
+        buffer.update(cx, |buffer, cx| {
+            assert_eq!(buffer.file().unwrap().full_path(cx), Path::new(path));
+            assert!(buffer.is_large_file());
+            assert!(buffer.language().is_none());
+        });
+    }
+    assert!(fake_servers.try_next().is_err());
+}
+
+#[gpui::test]
+async fn test_save_file_in_original_encoding(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({})).await;
+    fs.insert_file("/dir/latin1.txt", b"caf\xE9".to_vec()).await;
+    fs.insert_file("/dir/utf16.txt", b"\xFF\xFEh\0\xE9\0".to_vec())
+        .await;
+    fs.insert_file(
+        "/dir/shift_jis.txt",
+        b"\x82\xB1\x82\xF1\x82\xC9\x82\xBF\x82\xCD".to_vec(),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    for (path, encoding, old_text, new_text, new_bytes) in [
+        (
+            "/dir/latin1.txt",
+            "windows-1252",
+            "café",
+            "café crème",
+            b"caf\xE9 cr\xE8me".to_vec(),
+        ),
+        (
+            "/dir/utf16.txt",
+            "UTF-16LE",
+            "hé",
+            "hé!",
+            b"\xFF\xFEh\0\xE9\0!\0".to_vec(),
+        ),
+        (
+            "/dir/shift_jis.txt",
+            "Shift_JIS",
+            "こんにちは",
+            "こんにちは!",
+            b"\x82\xB1\x82\xF1\x82\xC9\x82\xBF\x82\xCD!".to_vec(),
+        ),
+    ] {
+        let buffer = project
+            .update(cx, |p, cx| p.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| {
+            assert_eq!(buffer.text(), old_text);
+            assert_eq!(buffer.encoding().name(), encoding);
+            buffer.edit(
+                [(buffer.len()..buffer.len(), &new_text[old_text.len()..])],
+                None,
+                cx,
+            );
+        });
+
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+        assert_eq!(fs.read_file_sync(path).unwrap(), new_bytes);
+    }
+}
+
+#[gpui::test]
+async fn test_save_and_reload_with_encoding(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "file.txt": "café" })).await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/file.txt", cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert!(buffer.encoding().is_utf8());
+        buffer.set_encoding(Encoding::for_label("latin1").unwrap(), cx);
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(fs.read_file_sync("/dir/file.txt").unwrap(), b"caf\xE9");
+
+    // Decoding the Latin-1 bytes as UTF-8 replaces the invalid sequence.
+    buffer
+        .update(cx, |buffer, cx| {
+            buffer.set_encoding(Encoding::utf8(), cx);
+            buffer.reload(cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "caf\u{FFFD}"));
+
+    // Characters that can't be represented in the encoding fail to save.
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_encoding(Encoding::for_label("latin1").unwrap(), cx);
+        buffer.edit([(0..buffer.len(), "こんにちは")], None, cx);
+    });
+    assert!(project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .is_err());
+    assert_eq!(fs.read_file_sync("/dir/file.txt").unwrap(), b"caf\xE9");
+}
+
 #[gpui::test(iterations = 30)]
 async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4748,6 +5233,88 @@ async fn test_multiple_language_server_hovers(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_project_symbols_from_multiple_language_servers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.tsx": "a",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(tsx_lang());
+    let mut fake_tsx_language_servers = language_registry.register_specific_fake_lsp_adapter(
+        "tsx",
+        true,
+        FakeLspAdapter {
+            name: "TypeScriptServer",
+            ..FakeLspAdapter::default()
+        },
+    );
+    let _tailwind = language_registry.register_specific_fake_lsp_adapter(
+        "tsx",
+        false,
+        FakeLspAdapter {
+            name: "TailwindServer",
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let _buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.tsx", cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    for _ in 0..2 {
+        let server = fake_tsx_language_servers.next().await.unwrap();
+        let unique_symbol = format!("{}Symbol", server.server.name());
+        server.handle_request::<lsp::request::WorkspaceSymbolRequest, _, _>(move |_, _| {
+            let unique_symbol = unique_symbol.clone();
+            async move {
+                let location = lsp::Location::new(
+                    lsp::Url::from_file_path("/dir/a.tsx").unwrap(),
+                    lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 1)),
+                );
+                #[allow(deprecated)]
+                let symbol = |name: &str| lsp::SymbolInformation {
+                    name: name.to_string(),
+                    kind: lsp::SymbolKind::FUNCTION,
+                    tags: None,
+                    deprecated: None,
+                    container_name: None,
+                    location: location.clone(),
+                };
+                Ok(Some(lsp::WorkspaceSymbolResponse::Flat(vec![
+                    symbol("shared"),
+                    symbol(&unique_symbol),
+                ])))
+            }
+        });
+    }
+
+    let symbols = project
+        .update(cx, |project, cx| project.symbols("", cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        symbols
+            .iter()
+            .map(|symbol| symbol.name.as_str())
+            .sorted()
+            .collect::<Vec<_>>(),
+        ["TailwindServerSymbol", "TypeScriptServerSymbol", "shared"],
+        "Symbols reported by several servers should only be listed once"
+    );
+}
+
 #[gpui::test]
 async fn test_hovers_with_empty_parts(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1,8 +1,11 @@
-use crate::Project;
-use anyhow::Context as _;
+use crate::{Event, Project};
+use anyhow::{Context as _, Result};
+use client::{proto, TypedEnvelope};
 use collections::HashMap;
+use futures::{channel::mpsc::unbounded, StreamExt as _};
 use gpui::{
-    AnyWindowHandle, AppContext, Context, Entity, Model, ModelContext, SharedString, WeakModel,
+    AnyWindowHandle, AppContext, AsyncAppContext, Context, Entity, Model, ModelContext,
+    SharedString, Subscription, Task, WeakModel,
 };
 use itertools::Itertools;
 use settings::{Settings, SettingsLocation};
@@ -12,19 +15,45 @@ use std::{
     fs::File,
     io::Write,
     path::{Path, PathBuf},
+    time::Duration,
 };
 use task::{SpawnInTerminal, TerminalWorkDir};
 use terminal::{
     terminal_settings::{self, Shell, TerminalSettings, VenvSettingsContent},
     TaskState, TaskStatus, Terminal, TerminalBuilder,
 };
-use util::ResultExt;
+use util::{post_inc, ResultExt};
 
 // #[cfg(target_os = "macos")]
 // use std::os::unix::ffi::OsStrExt;
 
+/// How long to wait after a shared terminal changes before sending its screen to the
+/// guests, so that bursts of output are sent once.
+const SHARED_TERMINAL_UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    /// The terminals the host has shared with the guests of this project, by id.
+    shared: HashMap<u64, SharedTerminal>,
+    next_shared_terminal_id: u64,
+    /// The host's terminals shown to this guest, by id.
+    remote: HashMap<u64, RemoteTerminal>,
+}
+
+struct SharedTerminal {
+    terminal: WeakModel<Terminal>,
+    writable: bool,
+    /// The only guest the terminal is shared with, if it runs a task that guest asked for.
+    guest: Option<proto::PeerId>,
+    pending_update: Option<Task<()>>,
+    _subscriptions: [Subscription; 2],
+}
+
+struct RemoteTerminal {
+    terminal: Model<Terminal>,
+    /// Forwards the input typed into a writable terminal to the host.
+    _forward_input: Option<Task<()>>,
 }
 
 #[derive(Debug, Clone)]
@@ -42,6 +71,16 @@ impl Project {
         if self.is_local() {
             return Some(TerminalWorkDir::Local(pathbuf?.to_owned()));
         }
+        if let Some(ssh_command) = &self.ssh_connection {
+            let path = match pathbuf {
+                Some(pathbuf) => pathbuf.to_owned(),
+                None => self.worktrees().next()?.read(cx).abs_path().to_path_buf(),
+            };
+            return Some(TerminalWorkDir::Ssh {
+                ssh_command: ssh_command.to_string(),
+                path: Some(path.to_string_lossy().to_string()),
+            });
+        }
         let dev_server_project_id = self.dev_server_project_id()?;
         let projects_store = dev_server_projects::Store::global(cx).read(cx);
         let ssh_command = projects_store
@@ -303,6 +342,326 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakModel<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    /// Shares a terminal with the guests of this project. Guests can type into a writable
+    /// terminal unless their role only allows them to read the project.
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Model<Terminal>,
+        writable: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<u64> {
+        self.share_terminal_internal(terminal, writable, None, cx)
+    }
+
+    /// Shares a terminal with a single guest of this project, read-only.
+    pub(crate) fn share_terminal_with_guest(
+        &mut self,
+        terminal: &Model<Terminal>,
+        guest: proto::PeerId,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<u64> {
+        self.share_terminal_internal(terminal, false, Some(guest), cx)
+    }
+
+    fn share_terminal_internal(
+        &mut self,
+        terminal: &Model<Terminal>,
+        writable: bool,
+        guest: Option<proto::PeerId>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<u64> {
+        anyhow::ensure!(
+            self.is_local() && self.is_shared(),
+            "only the host of a shared project can share terminals"
+        );
+        if let Some(terminal_id) = self.shared_terminal_id(terminal) {
+            self.unshare_terminal(terminal_id, cx)?;
+        }
+
+        let terminal_id = post_inc(&mut self.terminals.next_shared_terminal_id);
+        let subscriptions = [
+            cx.subscribe(terminal, move |this, _, event, cx| {
+                if matches!(
+                    event,
+                    terminal::Event::Wakeup | terminal::Event::TitleChanged
+                ) {
+                    this.schedule_terminal_update(terminal_id, cx);
+                }
+            }),
+            cx.observe_release(terminal, move |this, _, cx| {
+                this.unshare_terminal(terminal_id, cx).log_err();
+            }),
+        ];
+        self.terminals.shared.insert(
+            terminal_id,
+            SharedTerminal {
+                terminal: terminal.downgrade(),
+                writable,
+                guest,
+                pending_update: None,
+                _subscriptions: subscriptions,
+            },
+        );
+        self.send_shared_terminal(terminal_id, cx)?;
+        Ok(terminal_id)
+    }
+
+    pub fn unshare_terminal(
+        &mut self,
+        terminal_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let Some(shared) = self.terminals.shared.remove(&terminal_id) else {
+            return Ok(());
+        };
+        cx.notify();
+        if let Some(project_id) = self.remote_id() {
+            self.client.send(proto::UnshareTerminal {
+                project_id,
+                terminal_id,
+                guest_id: shared.guest,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Returns the id of a terminal if it's shared with the guests of this project.
+    pub fn shared_terminal_id(&self, terminal: &Model<Terminal>) -> Option<u64> {
+        self.terminals
+            .shared
+            .iter()
+            .find(|(_, shared)| shared.terminal == terminal.downgrade())
+            .map(|(terminal_id, _)| *terminal_id)
+    }
+
+    /// Returns the id a terminal is shared under, both for the host sharing it and for the
+    /// guests it's shared with.
+    pub fn terminal_share_id(&self, terminal: &Model<Terminal>) -> Option<u64> {
+        self.shared_terminal_id(terminal).or_else(|| {
+            self.terminals
+                .remote
+                .iter()
+                .find(|(_, remote)| remote.terminal == *terminal)
+                .map(|(terminal_id, _)| *terminal_id)
+        })
+    }
+
+    /// Returns the terminal shared under the given id, both for the host sharing it and for
+    /// the guests it's shared with.
+    pub fn terminal_for_share_id(&self, terminal_id: u64) -> Option<Model<Terminal>> {
+        if let Some(shared) = self.terminals.shared.get(&terminal_id) {
+            return shared.terminal.upgrade();
+        }
+        self.terminals
+            .remote
+            .get(&terminal_id)
+            .map(|remote| remote.terminal.clone())
+    }
+
+    /// Returns the terminals the host of this project currently shares with this guest.
+    pub fn remote_terminals(&self) -> impl '_ + Iterator<Item = &Model<Terminal>> {
+        self.terminals
+            .remote
+            .values()
+            .map(|remote_terminal| &remote_terminal.terminal)
+    }
+
+    /// Sends every shared terminal to the guests, so that a guest that just joined can
+    /// see them.
+    pub(crate) fn resend_shared_terminals(&mut self, cx: &mut ModelContext<Self>) {
+        let terminal_ids = self.terminals.shared.keys().copied().collect::<Vec<_>>();
+        for terminal_id in terminal_ids {
+            self.send_shared_terminal(terminal_id, cx).log_err();
+        }
+    }
+
+    pub(crate) fn clear_terminals(&mut self) {
+        self.terminals.shared.clear();
+        self.terminals.remote.clear();
+    }
+
+    fn send_shared_terminal(
+        &mut self,
+        terminal_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let project_id = self.remote_id().context("project is not shared")?;
+        let shared = self
+            .terminals
+            .shared
+            .get(&terminal_id)
+            .context("terminal is not shared")?;
+        let terminal = shared.terminal.upgrade().context("terminal was closed")?;
+        self.client.send(proto::ShareTerminal {
+            project_id,
+            terminal_id,
+            title: terminal.read(cx).title(false),
+            writable: shared.writable,
+            guest_id: shared.guest,
+        })?;
+        self.send_terminal_update(terminal_id, cx);
+        Ok(())
+    }
+
+    fn schedule_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(shared) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        if shared.pending_update.is_none() {
+            shared.pending_update = Some(cx.spawn(|this, mut cx| async move {
+                cx.background_executor()
+                    .timer(SHARED_TERMINAL_UPDATE_DEBOUNCE)
+                    .await;
+                this.update(&mut cx, |this, cx| {
+                    this.send_terminal_update(terminal_id, cx)
+                })
+                .ok();
+            }));
+        }
+    }
+
+    fn send_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let Some(shared) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        shared.pending_update = None;
+        let Some(terminal) = shared.terminal.upgrade() else {
+            return;
+        };
+        let guest_id = shared.guest;
+        let terminal = terminal.read(cx);
+        self.client
+            .send(proto::UpdateTerminal {
+                project_id,
+                terminal_id,
+                title: terminal.title(false),
+                screen: terminal.encode_screen(),
+                guest_id,
+            })
+            .log_err();
+    }
+
+    pub(crate) async fn handle_share_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ShareTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            // Only the host shares terminals, so a local project never shows shared ones.
+            if this.is_local() {
+                return;
+            }
+            let terminal_id = envelope.payload.terminal_id;
+            if this.terminals.remote.contains_key(&terminal_id) {
+                return;
+            }
+
+            let (input_tx, forward_input) = if envelope.payload.writable && !this.is_read_only() {
+                let (input_tx, mut input_rx) = unbounded::<Vec<u8>>();
+                let forward_input = cx.spawn(|this, mut cx| async move {
+                    while let Some(input) = input_rx.next().await {
+                        let Ok(Some(request)) = this.update(&mut cx, |this, _| {
+                            let project_id = this.remote_id()?;
+                            Some(this.client.request(proto::TerminalInput {
+                                project_id,
+                                terminal_id,
+                                input,
+                            }))
+                        }) else {
+                            break;
+                        };
+                        request.await.log_err();
+                    }
+                });
+                (Some(input_tx), Some(forward_input))
+            } else {
+                (None, None)
+            };
+
+            let settings = TerminalSettings::get_global(cx);
+            let builder = TerminalBuilder::new_display_only(
+                envelope.payload.title,
+                Some(settings.blinking),
+                settings.alternate_scroll,
+                input_tx,
+            );
+            let terminal = cx.new_model(|cx| builder.subscribe(cx));
+            this.terminals.remote.insert(
+                terminal_id,
+                RemoteTerminal {
+                    terminal: terminal.clone(),
+                    _forward_input: forward_input,
+                },
+            );
+            if envelope.payload.guest_id.is_some() {
+                cx.emit(Event::HostTaskStarted(terminal));
+            } else {
+                cx.emit(Event::TerminalShared(terminal));
+            }
+            cx.notify();
+        })
+    }
+
+    pub(crate) async fn handle_unshare_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UnshareTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            if this.is_local() {
+                return;
+            }
+            if let Some(remote_terminal) =
+                this.terminals.remote.remove(&envelope.payload.terminal_id)
+            {
+                cx.emit(Event::TerminalUnshared(remote_terminal.terminal));
+                cx.notify();
+            }
+        })
+    }
+
+    pub(crate) async fn handle_update_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            if this.is_local() {
+                return;
+            }
+            if let Some(remote_terminal) = this.terminals.remote.get(&envelope.payload.terminal_id)
+            {
+                remote_terminal.terminal.update(cx, |terminal, cx| {
+                    terminal.set_title(envelope.payload.title, cx);
+                    terminal.write_output(&envelope.payload.screen, cx);
+                });
+            }
+        })
+    }
+
+    pub(crate) async fn handle_terminal_input(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::TerminalInput>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            let shared = this
+                .terminals
+                .shared
+                .get(&envelope.payload.terminal_id)
+                .context("terminal is not shared")?;
+            anyhow::ensure!(shared.writable, "terminal is shared read-only");
+            let terminal = shared.terminal.upgrade().context("terminal was closed")?;
+            terminal.update(cx, |terminal, _| {
+                terminal.input_bytes(envelope.payload.input)
+            });
+            Ok(proto::Ack {})
+        })?
+    }
 }
 
 fn prepare_ssh_shell(
@@ -0,0 +1,98 @@
+//! Keeps the undo history of local files on disk, so that edits can still be undone
+//! after a file is closed and reopened.
+//!
+//! Each file's history is stored alongside a hash of the content the file had when it
+//! was saved. If the file is changed by another program in the meantime, the hash no
+//! longer matches and the history is ignored, because its operations can't be applied
+//! to the new content.
+
+use anyhow::Result;
+use client::proto::{self, Message as _};
+use fs::{Fs, RemoveOptions};
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering::SeqCst},
+    time::{Duration, SystemTime},
+};
+use text::SerializedHistory;
+use util::ResultExt;
+
+/// Histories that are larger than this once encoded are not stored, because replaying
+/// them would slow down reopening the file.
+const MAX_HISTORY_LEN: usize = 8 * 1024 * 1024;
+
+/// Histories of files that haven't been saved for this long are deleted.
+const MAX_HISTORY_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+static PRUNED: AtomicBool = AtomicBool::new(false);
+
+fn history_path(abs_path: &Path) -> PathBuf {
+    let path_hash = Sha256::digest(abs_path.to_string_lossy().as_bytes());
+    paths::undo_history_dir().join(format!("{path_hash:x}"))
+}
+
+fn content_hash(text: &str) -> Vec<u8> {
+    Sha256::digest(text.as_bytes()).to_vec()
+}
+
+/// Stores the history of the file at the given path, which was saved with the given text.
+pub(crate) async fn save(
+    fs: &dyn Fs,
+    abs_path: &Path,
+    text: &str,
+    history: SerializedHistory,
+) -> Result<()> {
+    let message = language::proto::serialize_history(&history, content_hash(text));
+    let bytes = message.encode_to_vec();
+    let history_path = history_path(abs_path);
+    if bytes.len() > MAX_HISTORY_LEN {
+        // Any history stored earlier describes older content, so it can't be used either.
+        return fs
+            .remove_file(
+                &history_path,
+                RemoveOptions {
+                    ignore_if_not_exists: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+    }
+
+    fs.create_dir(paths::undo_history_dir()).await?;
+    fs.atomic_write(history_path, bytes).await?;
+    if !PRUNED.swap(true, SeqCst) {
+        prune(fs).await.log_err();
+    }
+    Ok(())
+}
+
+/// Deletes the histories that haven't been written for a while, since the files they
+/// belong to may no longer exist.
+async fn prune(fs: &dyn Fs) -> Result<()> {
+    let oldest = SystemTime::now() - MAX_HISTORY_AGE;
+    let mut paths = fs.read_dir(paths::undo_history_dir()).await?;
+    while let Some(path) = paths.next().await {
+        let path = path?;
+        if let Some(metadata) = fs.metadata(&path).await? {
+            if metadata.mtime < oldest {
+                fs.remove_file(&path, RemoveOptions::default())
+                    .await
+                    .log_err();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Loads the stored history of the file at the given path, if it was saved while the
+/// file had the given text.
+pub(crate) async fn load(fs: &dyn Fs, abs_path: &Path, text: &str) -> Option<SerializedHistory> {
+    let bytes = fs.load_bytes(&history_path(abs_path)).await.ok()?;
+    let message = proto::BufferHistory::decode(bytes.as_slice()).log_err()?;
+    if message.content_hash != content_hash(text) {
+        return None;
+    }
+    language::proto::deserialize_history(message).log_err()
+}
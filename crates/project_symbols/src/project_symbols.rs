@@ -1,17 +1,20 @@
 use editor::{scroll::Autoscroll, styled_runs_for_code_label, Bias, Editor};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
-    rems, AppContext, DismissEvent, FontWeight, Model, ParentElement, StyledText, Task, View,
-    ViewContext, WeakView, WindowContext,
+    rems, AnyElement, AppContext, DismissEvent, FontWeight, IntoElement, Model, ParentElement,
+    Styled, StyledText, Task, View, ViewContext, WeakView, WindowContext,
 };
 use ordered_float::OrderedFloat;
 use picker::{Picker, PickerDelegate};
-use project::{Project, Symbol};
+use project::{Project, Symbol, WorktreeId};
 use std::{borrow::Cow, cmp::Reverse, sync::Arc};
 use theme::ActiveTheme;
 use util::ResultExt;
 use workspace::{
-    ui::{v_flex, Color, Label, LabelCommon, LabelLike, ListItem, ListItemSpacing, Selectable},
+    ui::{
+        h_flex, v_flex, CheckboxWithLabel, Color, Label, LabelCommon, LabelLike, LabelSize,
+        ListItem, ListItemSpacing, Selectable, Selection,
+    },
     Workspace,
 };
 
@@ -20,9 +23,13 @@ pub fn init(cx: &mut AppContext) {
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
             workspace.register_action(|workspace, _: &workspace::ToggleProjectSymbols, cx| {
                 let project = workspace.project().clone();
+                let current_worktree = workspace
+                    .active_item(cx)
+                    .and_then(|item| item.project_path(cx))
+                    .map(|project_path| project_path.worktree_id);
                 let handle = cx.view().downgrade();
                 workspace.toggle_modal(cx, move |cx| {
-                    let delegate = ProjectSymbolsDelegate::new(handle, project);
+                    let delegate = ProjectSymbolsDelegate::new(handle, project, current_worktree);
                     Picker::uniform_list(delegate, cx).width(rems(34.))
                 })
             });
@@ -41,11 +48,67 @@ pub struct ProjectSymbolsDelegate {
     visible_match_candidates: Vec<StringMatchCandidate>,
     external_match_candidates: Vec<StringMatchCandidate>,
     show_worktree_root_name: bool,
+    /// The worktree of the item that was active when the picker was opened.
+    current_worktree: Option<WorktreeId>,
+    current_worktree_only: bool,
     matches: Vec<StringMatch>,
 }
 
+/// A query typed into the project symbols picker, such as `#fn #struct parse`, split into
+/// the kinds of symbols to show and the text to search for.
+#[derive(Debug, Default, PartialEq)]
+struct SymbolQuery {
+    text: String,
+    /// The kinds of symbols to show, or all kinds if empty.
+    kinds: Vec<lsp::SymbolKind>,
+}
+
+impl SymbolQuery {
+    fn parse(query: &str) -> Self {
+        let mut words = Vec::new();
+        let mut kinds = Vec::new();
+        for word in query.split_whitespace() {
+            match word.strip_prefix('#').and_then(symbol_kinds_for_filter) {
+                Some(filter_kinds) => kinds.extend_from_slice(filter_kinds),
+                None => words.push(word),
+            }
+        }
+        Self {
+            text: words.join(" "),
+            kinds,
+        }
+    }
+
+    fn matches_kind(&self, kind: lsp::SymbolKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+}
+
+fn symbol_kinds_for_filter(filter: &str) -> Option<&'static [lsp::SymbolKind]> {
+    use lsp::SymbolKind as Kind;
+    Some(match filter {
+        "fn" | "function" => &[Kind::FUNCTION, Kind::METHOD, Kind::CONSTRUCTOR],
+        "method" => &[Kind::METHOD, Kind::CONSTRUCTOR],
+        "struct" => &[Kind::STRUCT],
+        "class" => &[Kind::CLASS],
+        "enum" => &[Kind::ENUM],
+        "variant" => &[Kind::ENUM_MEMBER],
+        "trait" | "interface" => &[Kind::INTERFACE],
+        "type" => &[Kind::TYPE_PARAMETER],
+        "const" | "constant" => &[Kind::CONSTANT],
+        "var" | "variable" => &[Kind::VARIABLE],
+        "field" => &[Kind::FIELD, Kind::PROPERTY],
+        "mod" | "module" => &[Kind::MODULE, Kind::NAMESPACE, Kind::PACKAGE],
+        _ => return None,
+    })
+}
+
 impl ProjectSymbolsDelegate {
-    fn new(workspace: WeakView<Workspace>, project: Model<Project>) -> Self {
+    fn new(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        current_worktree: Option<WorktreeId>,
+    ) -> Self {
         Self {
             workspace,
             project,
@@ -55,6 +118,8 @@ impl ProjectSymbolsDelegate {
             external_match_candidates: Default::default(),
             matches: Default::default(),
             show_worktree_root_name: false,
+            current_worktree,
+            current_worktree_only: false,
         }
     }
 
@@ -161,16 +226,25 @@ impl PickerDelegate for ProjectSymbolsDelegate {
     }
 
     fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
-        self.filter(&query, cx);
+        let query = SymbolQuery::parse(&query);
+        self.filter(&query.text, cx);
         self.show_worktree_root_name = self.project.read(cx).visible_worktrees(cx).count() > 1;
         let symbols = self
             .project
-            .update(cx, |project, cx| project.symbols(&query, cx));
+            .update(cx, |project, cx| project.symbols(&query.text, cx));
         cx.spawn(|this, mut cx| async move {
             let symbols = symbols.await.log_err();
-            if let Some(symbols) = symbols {
+            if let Some(mut symbols) = symbols {
                 this.update(&mut cx, |this, cx| {
                     let delegate = &mut this.delegate;
+                    let worktree_filter = delegate
+                        .current_worktree
+                        .filter(|_| delegate.current_worktree_only);
+                    symbols.retain(|symbol| {
+                        query.matches_kind(symbol.kind)
+                            && worktree_filter
+                                .map_or(true, |worktree_id| symbol.path.worktree_id == worktree_id)
+                    });
                     let project = delegate.project.read(cx);
                     let (visible_match_candidates, external_match_candidates) = symbols
                         .iter()
@@ -190,13 +264,38 @@ impl PickerDelegate for ProjectSymbolsDelegate {
                     delegate.visible_match_candidates = visible_match_candidates;
                     delegate.external_match_candidates = external_match_candidates;
                     delegate.symbols = symbols;
-                    delegate.filter(&query, cx);
+                    delegate.filter(&query.text, cx);
                 })
                 .log_err();
             }
         })
     }
 
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        if !self.show_worktree_root_name || self.current_worktree.is_none() {
+            return None;
+        }
+        Some(
+            h_flex()
+                .px_3()
+                .pb_2()
+                .child(CheckboxWithLabel::new(
+                    "current-worktree-only",
+                    Label::new("Only the current worktree").size(LabelSize::Small),
+                    if self.current_worktree_only {
+                        Selection::Selected
+                    } else {
+                        Selection::Unselected
+                    },
+                    cx.listener(|picker, selection: &Selection, cx| {
+                        picker.delegate.current_worktree_only = *selection == Selection::Selected;
+                        picker.refresh(cx);
+                    }),
+                ))
+                .into_any_element(),
+        )
+    }
+
     fn render_match(
         &self,
         ix: usize,
@@ -343,7 +442,7 @@ mod tests {
         // Create the project symbols view.
         let symbols = cx.new_view(|cx| {
             Picker::uniform_list(
-                ProjectSymbolsDelegate::new(workspace.downgrade(), project.clone()),
+                ProjectSymbolsDelegate::new(workspace.downgrade(), project.clone(), None),
                 cx,
             )
         });
@@ -376,6 +475,18 @@ mod tests {
             assert_eq!(delegate.matches[1].string, "one");
         });
 
+        // Kind filters are not sent to the language server, and hide symbols of other kinds.
+        symbols.update(cx, |p, cx| p.update_matches("#struct on".to_string(), cx));
+        cx.run_until_parked();
+        symbols.update(cx, |symbols, _| {
+            assert_eq!(symbols.delegate.matches.len(), 0);
+        });
+        symbols.update(cx, |p, cx| p.update_matches("#fn on".to_string(), cx));
+        cx.run_until_parked();
+        symbols.update(cx, |symbols, _| {
+            assert_eq!(symbols.delegate.matches.len(), 2);
+        });
+
         // Spawn more updates such that in the end, there are again no matches.
         symbols.update(cx, |p, cx| {
             p.update_matches("o".to_string(), cx);
@@ -388,6 +499,30 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_symbol_query_parsing() {
+        assert_eq!(
+            SymbolQuery::parse("#fn  parse #struct"),
+            SymbolQuery {
+                text: "parse".to_string(),
+                kinds: vec![
+                    lsp::SymbolKind::FUNCTION,
+                    lsp::SymbolKind::METHOD,
+                    lsp::SymbolKind::CONSTRUCTOR,
+                    lsp::SymbolKind::STRUCT,
+                ],
+            }
+        );
+        // Unknown filters are searched for like any other text.
+        assert_eq!(
+            SymbolQuery::parse("#[test] foo"),
+            SymbolQuery {
+                text: "#[test] foo".to_string(),
+                kinds: Vec::new(),
+            }
+        );
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let store = SettingsStore::test(cx);
@@ -181,15 +181,26 @@ messages!(
     (FuzzySearchUsers, Foreground),
     (GetCachedEmbeddings, Background),
     (GetCachedEmbeddingsResponse, Background),
+    (GetChannelBufferHistory, Background),
+    (GetChannelBufferHistoryResponse, Background),
+    (GetChannelBufferRevision, Background),
+    (GetChannelBufferRevisionResponse, Background),
     (GetChannelMembers, Foreground),
     (GetChannelMembersResponse, Foreground),
     (GetChannelMessages, Background),
     (GetChannelMessagesById, Background),
+    (GetChannelMessageThread, Background),
+    (SetChannelMessageReaction, Foreground),
+    (ChannelMessageReactionsUpdated, Foreground),
+    (SearchChannelMessages, Background),
+    (SearchChannelMessagesResponse, Background),
     (GetChannelMessagesResponse, Background),
     (GetCodeActions, Background),
     (GetCodeActionsResponse, Background),
     (GetCompletions, Background),
     (GetCompletionsResponse, Background),
+    (GetDeclaration, Background),
+    (GetDeclarationResponse, Background),
     (GetDefinition, Background),
     (GetDefinitionResponse, Background),
     (GetDocumentHighlights, Background),
@@ -278,7 +289,10 @@ messages!(
     (SendChannelMessageResponse, Background),
     (ShareProject, Foreground),
     (ShareProjectResponse, Foreground),
+    (ShareTerminal, Foreground),
     (ShowContacts, Foreground),
+    (SpawnHostTask, Foreground),
+    (SpawnHostTaskResponse, Foreground),
     (StartLanguageServer, Foreground),
     (SubscribeToChannels, Foreground),
     (SynchronizeBuffers, Foreground),
@@ -287,9 +301,11 @@ messages!(
     (TaskContext, Background),
     (TaskTemplates, Background),
     (TaskTemplatesResponse, Background),
+    (TerminalInput, Foreground),
     (Test, Foreground),
     (Unfollow, Foreground),
     (UnshareProject, Foreground),
+    (UnshareTerminal, Foreground),
     (UpdateBuffer, Foreground),
     (UpdateBufferFile, Foreground),
     (UpdateChannelBuffer, Foreground),
@@ -305,6 +321,7 @@ messages!(
     (UpdateParticipantLocation, Foreground),
     (UpdateProject, Foreground),
     (UpdateProjectCollaborator, Foreground),
+    (UpdateTerminal, Foreground),
     (UpdateWorktree, Foreground),
     (UpdateWorktreeSettings, Foreground),
     (UsersResponse, Foreground),
@@ -312,6 +329,13 @@ messages!(
     (LspExtExpandMacroResponse, Background),
     (SetRoomParticipantRole, Foreground),
     (BlameBuffer, Foreground),
+    (GetBufferCommentThreads, Background),
+    (GetBufferCommentThreadsResponse, Background),
+    (CreateBufferCommentThread, Foreground),
+    (CreateBufferComment, Foreground),
+    (SetBufferCommentThreadResolved, Foreground),
+    (BufferCommentThreadResponse, Foreground),
+    (UpdateBufferCommentThread, Foreground),
     (BlameBufferResponse, Foreground),
     (CreateDevServerProject, Background),
     (CreateDevServerProjectResponse, Foreground),
@@ -363,11 +387,17 @@ request_messages!(
     (FormatBuffers, FormatBuffersResponse),
     (FuzzySearchUsers, UsersResponse),
     (GetCachedEmbeddings, GetCachedEmbeddingsResponse),
+    (GetChannelBufferHistory, GetChannelBufferHistoryResponse),
+    (GetChannelBufferRevision, GetChannelBufferRevisionResponse),
     (GetChannelMembers, GetChannelMembersResponse),
     (GetChannelMessages, GetChannelMessagesResponse),
     (GetChannelMessagesById, GetChannelMessagesResponse),
+    (GetChannelMessageThread, GetChannelMessagesResponse),
+    (SetChannelMessageReaction, Ack),
+    (SearchChannelMessages, SearchChannelMessagesResponse),
     (GetCodeActions, GetCodeActionsResponse),
     (GetCompletions, GetCompletionsResponse),
+    (GetDeclaration, GetDeclarationResponse),
     (GetDefinition, GetDefinitionResponse),
     (GetImplementation, GetImplementationResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
@@ -425,9 +455,11 @@ request_messages!(
     (SetChannelMemberRole, Ack),
     (SetChannelVisibility, Ack),
     (ShareProject, ShareProjectResponse),
+    (SpawnHostTask, SpawnHostTaskResponse),
     (SynchronizeBuffers, SynchronizeBuffersResponse),
     (TaskContextForLocation, TaskContext),
     (TaskTemplates, TaskTemplatesResponse),
+    (TerminalInput, Ack),
     (Test, Test),
     (UpdateBuffer, Ack),
     (UpdateParticipantLocation, Ack),
@@ -435,6 +467,10 @@ request_messages!(
     (UpdateWorktree, Ack),
     (LspExtExpandMacro, LspExtExpandMacroResponse),
     (SetRoomParticipantRole, Ack),
+    (GetBufferCommentThreads, GetBufferCommentThreadsResponse),
+    (CreateBufferCommentThread, BufferCommentThreadResponse),
+    (CreateBufferComment, BufferCommentThreadResponse),
+    (SetBufferCommentThreadResolved, BufferCommentThreadResponse),
     (BlameBuffer, BlameBufferResponse),
     (CreateDevServerProject, CreateDevServerProjectResponse),
     (CreateDevServer, CreateDevServerResponse),
@@ -468,6 +504,7 @@ entity_messages!(
     FormatBuffers,
     GetCodeActions,
     GetCompletions,
+    GetDeclaration,
     GetDefinition,
     GetImplementation,
     GetDocumentHighlights,
@@ -500,7 +537,11 @@ entity_messages!(
     SynchronizeBuffers,
     TaskContextForLocation,
     TaskTemplates,
+    TerminalInput,
+    ShareTerminal,
+    SpawnHostTask,
     UnshareProject,
+    UnshareTerminal,
     UpdateBuffer,
     UpdateBufferFile,
     UpdateDiagnosticSummary,
@@ -508,15 +549,22 @@ entity_messages!(
     UpdateLanguageServer,
     UpdateProject,
     UpdateProjectCollaborator,
+    UpdateTerminal,
     UpdateWorktree,
     UpdateWorktreeSettings,
     LspExtExpandMacro,
+    GetBufferCommentThreads,
+    CreateBufferCommentThread,
+    CreateBufferComment,
+    SetBufferCommentThreadResolved,
+    UpdateBufferCommentThread,
 );
 
 entity_messages!(
     {channel_id, Channel},
     ChannelMessageSent,
     ChannelMessageUpdate,
+    ChannelMessageReactionsUpdated,
     RemoveChannelMessage,
     UpdateChannelMessage,
     UpdateChannelBuffer,
@@ -1,10 +1,9 @@
 mod dev_servers;
-pub mod disconnected_overlay;
+pub mod remote_connection_indicator;
 
 use client::{DevServerProjectId, ProjectId};
 use dev_servers::reconnect_to_dev_server_project;
 pub use dev_servers::DevServerProjects;
-use disconnected_overlay::DisconnectedOverlay;
 use feature_flags::FeatureFlagAppExt;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
@@ -16,6 +15,7 @@ use picker::{
     highlighted_match_with_paths::{HighlightedMatchWithPaths, HighlightedText},
     Picker, PickerDelegate,
 };
+use remote_connection_indicator::RemoteConnectionIndicator;
 use rpc::proto::DevServerStatus;
 use serde::Deserialize;
 use std::{
@@ -47,7 +47,8 @@ gpui::actions!(projects, [OpenRemote]);
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(RecentProjects::register).detach();
     cx.observe_new_views(DevServerProjects::register).detach();
-    cx.observe_new_views(DisconnectedOverlay::register).detach();
+    cx.observe_new_views(RemoteConnectionIndicator::register)
+        .detach();
 }
 
 pub struct RecentProjects {
@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use gpui::{
+    percentage, Animation, AnimationExt, AppContext, ClickEvent, Model, Subscription,
+    Transformation, WeakView,
+};
+use project::Project;
+use ui::{prelude::*, ButtonLike, Tooltip};
+use workspace::{item::ItemHandle, notifications::DetachAndPromptErr, StatusItemView, Workspace};
+
+use crate::{
+    dev_servers::reconnect_to_dev_server_project, open_dev_server_project, DevServerProjects,
+};
+
+/// Shows in the status bar whether a remote project can currently reach its host.
+///
+/// Remote projects stay editable while their connection is being re-established, so this
+/// only needs the user's attention once the project can't be rejoined anymore.
+pub struct RemoteConnectionIndicator {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    _project_subscription: Subscription,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemoteConnectionStatus {
+    Connected,
+    Reconnecting,
+    Disconnected,
+}
+
+impl RemoteConnectionIndicator {
+    pub fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        if !workspace.project().read(cx).is_remote() {
+            return;
+        }
+
+        let handle = cx.view().downgrade();
+        let project = workspace.project().clone();
+        let indicator = cx.new_view(|cx| {
+            let subscription = cx.subscribe(&project, |_, _, event, cx| match event {
+                project::Event::Reconnecting
+                | project::Event::Reconnected
+                | project::Event::DisconnectedFromHost => cx.notify(),
+                _ => {}
+            });
+            Self {
+                workspace: handle,
+                project,
+                _project_subscription: subscription,
+            }
+        });
+        workspace
+            .status_bar()
+            .update(cx, |status_bar, cx| status_bar.add_left_item(indicator, cx));
+    }
+
+    pub fn status(&self, cx: &AppContext) -> RemoteConnectionStatus {
+        let project = self.project.read(cx);
+        if project.is_disconnected() {
+            RemoteConnectionStatus::Disconnected
+        } else if project.is_reconnecting() {
+            RemoteConnectionStatus::Reconnecting
+        } else {
+            RemoteConnectionStatus::Connected
+        }
+    }
+
+    fn handle_reconnect(&mut self, _: &ClickEvent, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let Some(dev_server_project_id) = self.project.read(cx).dev_server_project_id() else {
+            return;
+        };
+        let store = dev_server_projects::Store::global(cx);
+
+        if let Some(project_id) = store
+            .read(cx)
+            .dev_server_project(dev_server_project_id)
+            .and_then(|project| project.project_id)
+        {
+            return workspace.update(cx, move |_, cx| {
+                open_dev_server_project(true, dev_server_project_id, project_id, cx)
+                    .detach_and_prompt_err("Failed to reconnect", cx, |_, _| None)
+            });
+        }
+
+        let dev_server = store
+            .read(cx)
+            .dev_server_for_project(dev_server_project_id)
+            .cloned();
+        match dev_server {
+            Some(dev_server) if dev_server.ssh_connection_string.is_some() => {
+                let task = workspace.update(cx, |_, cx| {
+                    reconnect_to_dev_server_project(
+                        cx.view().clone(),
+                        dev_server,
+                        dev_server_project_id,
+                        true,
+                        cx,
+                    )
+                });
+                task.detach_and_prompt_err("Failed to reconnect", cx, |_, _| None);
+            }
+            _ => workspace.update(cx, |workspace, cx| {
+                let handle = cx.view().downgrade();
+                workspace.toggle_modal(cx, |cx| DevServerProjects::new(cx, handle))
+            }),
+        }
+    }
+}
+
+impl Render for RemoteConnectionIndicator {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        match self.status(cx) {
+            RemoteConnectionStatus::Connected => div().into_any_element(),
+            RemoteConnectionStatus::Reconnecting => h_flex()
+                .id("remote-connection-indicator")
+                .gap_1()
+                .child(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .color(Color::Muted)
+                        .with_animation(
+                            "arrow-circle",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        ),
+                )
+                .child(
+                    Label::new("Reconnecting…")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .tooltip(|cx| {
+                    Tooltip::text(
+                        "The connection to the host was lost. Your edits will be sent once it's back.",
+                        cx,
+                    )
+                })
+                .into_any_element(),
+            RemoteConnectionStatus::Disconnected => {
+                let can_reconnect = self.project.read(cx).dev_server_project_id().is_some();
+                ButtonLike::new("remote-connection-indicator")
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Icon::new(IconName::ExclamationTriangle)
+                                    .size(IconSize::Small)
+                                    .color(Color::Warning),
+                            )
+                            .child(Label::new("Disconnected").size(LabelSize::Small)),
+                    )
+                    .disabled(!can_reconnect)
+                    .tooltip(move |cx| {
+                        if can_reconnect {
+                            Tooltip::text("Reconnect to the remote project", cx)
+                        } else {
+                            Tooltip::text("The connection to the host has been lost", cx)
+                        }
+                    })
+                    .on_click(cx.listener(Self::handle_reconnect))
+                    .into_any_element()
+            }
+        }
+    }
+}
+
+impl StatusItemView for RemoteConnectionIndicator {
+    fn set_active_pane_item(&mut self, _: Option<&dyn ItemHandle>, _: &mut ViewContext<Self>) {}
+}
@@ -0,0 +1,3 @@
+mod ssh_session;
+
+pub use ssh_session::{start_ssh_server, SshConnectionOptions};
@@ -0,0 +1,376 @@
+use anyhow::{anyhow, Context as _, Result};
+use futures::{AsyncBufReadExt as _, StreamExt as _};
+use gpui::AsyncAppContext;
+use release_channel::{AppVersion, ReleaseChannel};
+use rpc::Connection;
+use smol::process::{Child, Command, Stdio};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+const MASTER_STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+const SERVER_DIR: &str = ".zed_server";
+
+/// Where to reach a machine over SSH.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SshConnectionOptions {
+    pub host: String,
+    pub username: Option<String>,
+    pub port: Option<u16>,
+}
+
+impl SshConnectionOptions {
+    /// Parses an `ssh://[user@]host[:port]/path` url into the machine to connect to and the
+    /// path of the project on it. Paths starting with `~` are relative to the home directory
+    /// of the user on that machine, as is an empty path.
+    pub fn parse_url(url: &str) -> Result<(Self, String)> {
+        let rest = url
+            .strip_prefix("ssh://")
+            .with_context(|| format!("not an ssh url: {url}"))?;
+        let (authority, path) = match rest.find('/') {
+            Some(ix) => rest.split_at(ix),
+            None => (rest, ""),
+        };
+        let (username, host) = match authority.rsplit_once('@') {
+            Some((username, host)) => (Some(username.to_string()), host),
+            None => (None, authority),
+        };
+        let (host, port) = match host.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                Some(
+                    port.parse()
+                        .with_context(|| format!("invalid port: {port}"))?,
+                ),
+            ),
+            None => (host, None),
+        };
+        if host.is_empty() {
+            return Err(anyhow!("missing host in ssh url: {url}"));
+        }
+        // Both end up as arguments to `ssh` and `scp`, which must not read them as options.
+        for part in std::iter::once(host).chain(username.as_deref()) {
+            if part.is_empty()
+                || part.starts_with('-')
+                || part.chars().any(|c| c.is_whitespace() || c.is_control())
+            {
+                return Err(anyhow!("invalid ssh url: {url}"));
+            }
+        }
+
+        let path = match path.strip_prefix("/~") {
+            Some(path) => format!("~{path}"),
+            None if path.is_empty() || path == "/" => "~".to_string(),
+            None => path.to_string(),
+        };
+        Ok((
+            Self {
+                host: host.to_string(),
+                username,
+                port,
+            },
+            path,
+        ))
+    }
+
+    /// The `ssh` command that opens a shell on the machine, as used for remote terminals.
+    pub fn connection_string(&self) -> String {
+        let mut command = "ssh".to_string();
+        if let Some(port) = self.port {
+            command.push_str(&format!(" -p {port}"));
+        }
+        command.push(' ');
+        command.push_str(&self.destination());
+        command
+    }
+
+    fn destination(&self) -> String {
+        match &self.username {
+            Some(username) => format!("{username}@{}", self.host),
+            None => self.host.clone(),
+        }
+    }
+}
+
+/// Starts a Zed server for the project at the given path on another machine, uploading this
+/// binary there first when that machine doesn't have a server of this version yet.
+///
+/// Authentication is left to `ssh`, which has to be able to log in without prompting, for
+/// example with keys from an agent. The server is stopped when the returned connection is
+/// dropped.
+pub async fn start_ssh_server(
+    options: &SshConnectionOptions,
+    path: &str,
+    cx: &AsyncAppContext,
+) -> Result<Connection> {
+    let (version, release_channel) =
+        cx.update(|cx| (AppVersion::global(cx), ReleaseChannel::global(cx)))?;
+    let session = SshSession::start(options.clone(), cx).await?;
+
+    let server_path = format!("{SERVER_DIR}/zed-{}-{version}", release_channel.dev_name());
+    if !session.succeeds(&["test", "-x", &server_path]).await? {
+        session.upload_server(&server_path).await?;
+    }
+
+    let mut server = session
+        .command(&[&server_path, "--ssh-server", path])?
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start the Zed server over ssh")?;
+    let stdin = server.stdin.take().context("no stdin for the Zed server")?;
+    let stdout = server
+        .stdout
+        .take()
+        .context("no stdout for the Zed server")?;
+    let stderr = server
+        .stderr
+        .take()
+        .context("no stderr for the Zed server")?;
+
+    let executor = cx.background_executor().clone();
+    executor
+        .spawn(async move {
+            let mut lines = smol::io::BufReader::new(stderr).lines();
+            while let Some(Ok(line)) = lines.next().await {
+                log::info!("{}: {line}", session.options.host);
+            }
+            match server.status().await {
+                Ok(status) => log::info!("Zed server on {} exited: {status}", session.options.host),
+                Err(error) => log::error!("Zed server on {}: {error}", session.options.host),
+            }
+        })
+        .detach();
+
+    Ok(Connection::framed(stdout, stdin))
+}
+
+/// A master `ssh` connection which the commands run on the other machine are multiplexed
+/// over, so that they don't each have to log in.
+struct SshSession {
+    options: SshConnectionOptions,
+    socket_path: PathBuf,
+    _master: Child,
+    _socket_dir: tempfile::TempDir,
+}
+
+impl SshSession {
+    async fn start(options: SshConnectionOptions, cx: &AsyncAppContext) -> Result<Self> {
+        let socket_dir = tempfile::Builder::new().prefix("zed-ssh").tempdir()?;
+        let socket_path = socket_dir.path().join("ssh.sock");
+
+        let mut master = Command::new("ssh");
+        if let Some(port) = options.port {
+            master.arg("-p").arg(port.to_string());
+        }
+        let mut master = master
+            .args(["-N", "-o", "ControlMaster=yes", "-o"])
+            .arg(format!("ControlPath={}", socket_path.display()))
+            .arg("--")
+            .arg(options.destination())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to run ssh")?;
+
+        let started_at = Instant::now();
+        while !socket_path.exists() {
+            if let Some(status) = master.try_status()? {
+                let mut error = String::new();
+                if let Some(mut stderr) = master.stderr.take() {
+                    use futures::AsyncReadExt as _;
+                    stderr.read_to_string(&mut error).await.ok();
+                }
+                return Err(anyhow!(
+                    "failed to connect to {} ({status}): {}",
+                    options.host,
+                    error.trim()
+                ));
+            }
+            if started_at.elapsed() > MASTER_STARTUP_TIMEOUT {
+                return Err(anyhow!("timed out connecting to {}", options.host));
+            }
+            cx.background_executor()
+                .timer(Duration::from_millis(50))
+                .await;
+        }
+
+        Ok(Self {
+            options,
+            socket_path,
+            _master: master,
+            _socket_dir: socket_dir,
+        })
+    }
+
+    fn command(&self, remote_command: &[&str]) -> Result<Command> {
+        let mut command = Command::new("ssh");
+        if let Some(port) = self.options.port {
+            command.arg("-p").arg(port.to_string());
+        }
+        command
+            .args(["-o", "ControlMaster=no", "-o"])
+            .arg(format!("ControlPath={}", self.socket_path.display()))
+            .arg("--")
+            .arg(self.options.destination())
+            .arg(shlex::try_join(remote_command.iter().copied())?);
+        Ok(command)
+    }
+
+    async fn run(&self, remote_command: &[&str]) -> Result<String> {
+        let output = self
+            .command(remote_command)?
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "`{}` failed on {}: {}",
+                remote_command.join(" "),
+                self.options.host,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    async fn succeeds(&self, remote_command: &[&str]) -> Result<bool> {
+        let status = self
+            .command(remote_command)?
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await?;
+        Ok(status.success())
+    }
+
+    /// Copies this binary to the other machine, which only works when both run the same
+    /// operating system on the same architecture.
+    async fn upload_server(&self, server_path: &str) -> Result<()> {
+        let platform = self.run(&["uname", "-sm"]).await?;
+        let (os, arch) = platform
+            .split_once(' ')
+            .with_context(|| format!("unexpected output from uname: {platform}"))?;
+        let os = match os {
+            "Darwin" => "macos",
+            "Linux" => "linux",
+            os => os,
+        };
+        let arch = match arch {
+            "arm64" => "aarch64",
+            arch => arch,
+        };
+        if os != std::env::consts::OS || arch != std::env::consts::ARCH {
+            return Err(anyhow!(
+                "no Zed server is installed on {} and this {} {} build can't run on its {os} {arch}",
+                self.options.host,
+                std::env::consts::OS,
+                std::env::consts::ARCH,
+            ));
+        }
+
+        log::info!("uploading the Zed server to {}", self.options.host);
+        let temp_path = format!("{server_path}.tmp");
+        self.run(&["mkdir", "-p", SERVER_DIR]).await?;
+        self.copy(&std::env::current_exe()?, &temp_path).await?;
+        self.run(&["chmod", "755", &temp_path]).await?;
+        self.run(&["mv", &temp_path, server_path]).await?;
+        Ok(())
+    }
+
+    async fn copy(&self, local_path: &Path, remote_path: &str) -> Result<()> {
+        let mut command = Command::new("scp");
+        if let Some(port) = self.options.port {
+            command.arg("-P").arg(port.to_string());
+        }
+        let output = command
+            .args(["-q", "-o", "ControlMaster=no", "-o"])
+            .arg(format!("ControlPath={}", self.socket_path.display()))
+            .arg("--")
+            .arg(local_path)
+            .arg(format!("{}:{remote_path}", self.options.destination()))
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "failed to copy {} to {}: {}",
+                local_path.display(),
+                self.options.host,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        assert_eq!(
+            SshConnectionOptions::parse_url("ssh://example.com/home/me/project").unwrap(),
+            (
+                SshConnectionOptions {
+                    host: "example.com".to_string(),
+                    username: None,
+                    port: None,
+                },
+                "/home/me/project".to_string()
+            )
+        );
+        assert_eq!(
+            SshConnectionOptions::parse_url("ssh://me@example.com:2222/~/project").unwrap(),
+            (
+                SshConnectionOptions {
+                    host: "example.com".to_string(),
+                    username: Some("me".to_string()),
+                    port: Some(2222),
+                },
+                "~/project".to_string()
+            )
+        );
+        assert_eq!(
+            SshConnectionOptions::parse_url("ssh://example.com")
+                .unwrap()
+                .1,
+            "~"
+        );
+        assert!(SshConnectionOptions::parse_url("ssh://example.com:port/").is_err());
+        assert!(SshConnectionOptions::parse_url("ssh:///tmp").is_err());
+        assert!(SshConnectionOptions::parse_url("file:///tmp").is_err());
+    }
+
+    #[test]
+    fn test_parse_url_rejects_options() {
+        for url in [
+            "ssh://-oProxyCommand=touch%20pwned/x",
+            "ssh://-oProxyCommand=touch pwned/x",
+            "ssh://-oProxyCommand=sh@example.com/x",
+            "ssh://@example.com/x",
+            "ssh://exam ple.com/x",
+            "ssh://me@example.com\tfoo/x",
+            "ssh://me\n@example.com/x",
+            "ssh://me@-example.com:2222/x",
+        ] {
+            assert!(
+                SshConnectionOptions::parse_url(url).is_err(),
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_connection_string() {
+        let (options, _) = SshConnectionOptions::parse_url("ssh://me@example.com:2222/").unwrap();
+        assert_eq!(options.connection_string(), "ssh -p 2222 me@example.com");
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{get_active_editor, notebook::active_notebook, RuntimeManager, SelectKernel};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, DismissEvent, EntityId, EventEmitter, FocusHandle, FocusableView, Model, View,
+    WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+/// Lets the user pick which of the installed runtimes runs the code of the active buffer.
+pub struct KernelPicker {
+    picker: View<Picker<KernelPickerDelegate>>,
+}
+
+pub fn toggle(workspace: &mut Workspace, _: &SelectKernel, cx: &mut ViewContext<Workspace>) {
+    let Some(runtime_manager) = RuntimeManager::global(cx) else {
+        return;
+    };
+    // Notebooks pick the kernel for all of their cells at once.
+    let buffer_id = if let Some(notebook) = active_notebook(workspace, cx) {
+        notebook.entity_id()
+    } else {
+        let Some(buffer) = get_active_editor(workspace, cx)
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+        else {
+            return;
+        };
+        buffer.entity_id()
+    };
+    workspace.toggle_modal(cx, move |cx| {
+        KernelPicker::new(runtime_manager, buffer_id, cx)
+    });
+}
+
+impl KernelPicker {
+    fn new(
+        runtime_manager: Model<RuntimeManager>,
+        buffer_id: EntityId,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate =
+            KernelPickerDelegate::new(cx.view().downgrade(), runtime_manager, buffer_id, cx);
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for KernelPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for KernelPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for KernelPicker {}
+impl ModalView for KernelPicker {}
+
+pub struct KernelPickerDelegate {
+    kernel_picker: WeakView<KernelPicker>,
+    runtime_manager: Model<RuntimeManager>,
+    buffer_id: EntityId,
+    /// The names of the runtime specifications, in the order they were listed.
+    runtime_names: Vec<String>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl KernelPickerDelegate {
+    fn new(
+        kernel_picker: WeakView<KernelPicker>,
+        runtime_manager: Model<RuntimeManager>,
+        buffer_id: EntityId,
+        cx: &AppContext,
+    ) -> Self {
+        let runtime_specifications = runtime_manager.read(cx).runtime_specifications();
+        let runtime_names = runtime_specifications
+            .iter()
+            .map(|runtime_specification| runtime_specification.name.clone())
+            .collect();
+        let candidates = runtime_specifications
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, runtime_specification)| {
+                StringMatchCandidate::new(
+                    candidate_id,
+                    format!(
+                        "{} ({})",
+                        runtime_specification.display_name(),
+                        runtime_specification.kernelspec.language
+                    ),
+                )
+            })
+            .collect();
+
+        Self {
+            kernel_picker,
+            runtime_manager,
+            buffer_id,
+            runtime_names,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for KernelPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Select a kernel...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let runtime_name = self.runtime_names[mat.candidate_id].clone();
+            let buffer_id = self.buffer_id;
+            self.runtime_manager.update(cx, |runtime_manager, cx| {
+                runtime_manager.select_kernel(buffer_id, runtime_name, cx)
+            });
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.kernel_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let selected_kernel = self
+            .runtime_manager
+            .read(cx)
+            .selected_kernel(self.buffer_id);
+        let mut label = mat.string.clone();
+        if selected_kernel == Some(self.runtime_names[mat.candidate_id].as_str()) {
+            label.push_str(" (current)");
+        }
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(label, mat.positions.clone())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtimes::RuntimeSpecification;
+    use gpui::{Context as _, TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_kernel_picker_selects_runtime(cx: &mut TestAppContext) {
+        init_test(cx);
+        let runtime_manager = runtime_manager(cx);
+        let buffer_id = cx.new_model(|_| ()).entity_id();
+
+        let window = cx.add_window(|cx| KernelPicker::new(runtime_manager.clone(), buffer_id, cx));
+        let picker = window
+            .update(cx, |kernel_picker, _| kernel_picker.picker.clone())
+            .unwrap();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+
+        picker
+            .update(cx, |picker, cx| {
+                picker.delegate.update_matches(String::new(), cx)
+            })
+            .await;
+        assert_eq!(
+            match_strings_for(&picker, cx),
+            ["Python 3 (python)", "Deno (typescript)"]
+        );
+
+        picker
+            .update(cx, |picker, cx| {
+                picker.delegate.update_matches("deno".into(), cx)
+            })
+            .await;
+        assert_eq!(match_strings_for(&picker, cx), ["Deno (typescript)"]);
+
+        picker.update(cx, |picker, cx| picker.delegate.confirm(false, cx));
+        runtime_manager.read_with(cx, |runtime_manager, _| {
+            assert_eq!(runtime_manager.selected_kernel(buffer_id), Some("deno"));
+        });
+    }
+
+    #[gpui::test]
+    fn test_runtime_selection(cx: &mut TestAppContext) {
+        init_test(cx);
+        let runtime_manager = runtime_manager(cx);
+        let buffer_id = cx.new_model(|_| ()).entity_id();
+        let other_buffer_id = cx.new_model(|_| ()).entity_id();
+
+        runtime_manager.update(cx, |runtime_manager, cx| {
+            // Without a selection, the first runtime for the buffer's language is used.
+            assert_eq!(
+                runtime_name(runtime_manager.runtime_specification_for(Some(buffer_id), "python")),
+                Some("python3")
+            );
+            assert_eq!(
+                runtime_name(runtime_manager.runtime_specification_for(None, "ruby")),
+                None
+            );
+
+            // A selected runtime is used whatever the language, for that buffer only.
+            runtime_manager.select_kernel(buffer_id, "deno".into(), cx);
+            assert_eq!(
+                runtime_name(runtime_manager.runtime_specification_for(Some(buffer_id), "python")),
+                Some("deno")
+            );
+            assert_eq!(
+                runtime_name(
+                    runtime_manager.runtime_specification_for(Some(other_buffer_id), "python")
+                ),
+                Some("python3")
+            );
+
+            // Selections of runtimes that are no longer installed are ignored.
+            runtime_manager.select_kernel(buffer_id, "removed".into(), cx);
+            assert_eq!(
+                runtime_name(runtime_manager.runtime_specification_for(Some(buffer_id), "python")),
+                Some("python3")
+            );
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init_settings(cx);
+        });
+    }
+
+    fn runtime_manager(cx: &mut TestAppContext) -> Model<RuntimeManager> {
+        let fs = FakeFs::new(cx.executor());
+        cx.new_model(|cx| {
+            let mut runtime_manager = RuntimeManager::new(fs, cx);
+            runtime_manager.runtime_specifications = vec![
+                runtime_specification("python3", "Python 3", "python"),
+                runtime_specification("deno", "Deno", "typescript"),
+            ];
+            runtime_manager
+        })
+    }
+
+    fn runtime_specification(
+        name: &str,
+        display_name: &str,
+        language: &str,
+    ) -> RuntimeSpecification {
+        RuntimeSpecification {
+            name: name.into(),
+            path: format!("/jupyter/kernels/{name}").into(),
+            kernelspec: serde_json::from_value(json!({
+                "display_name": display_name,
+                "language": language,
+                "argv": [name, "{connection_file}"],
+                "env": {},
+            }))
+            .unwrap(),
+        }
+    }
+
+    fn runtime_name(runtime_specification: Option<&RuntimeSpecification>) -> Option<&str> {
+        runtime_specification.map(|runtime_specification| runtime_specification.name.as_str())
+    }
+
+    fn match_strings_for(
+        picker: &View<Picker<KernelPickerDelegate>>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<String> {
+        picker.update(cx, |picker, _| {
+            picker
+                .delegate
+                .matches
+                .iter()
+                .map(|mat| mat.string.clone())
+                .collect()
+        })
+    }
+}
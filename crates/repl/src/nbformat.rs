@@ -0,0 +1,418 @@
+use crate::outputs::{ErrorView, OutputType};
+use crate::stdio::TerminalOutput;
+use anyhow::{anyhow, Result};
+use runtimelib::{JupyterMessageContent, MimeBundle};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use util::ResultExt as _;
+
+/// A Jupyter notebook in the nbformat v4 format (https://nbformat.readthedocs.io).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Notebook {
+    pub cells: Vec<Cell>,
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+    pub nbformat: u32,
+    pub nbformat_minor: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "cell_type", rename_all = "lowercase")]
+pub enum Cell {
+    Code(CodeCell),
+    Markdown(TextCell),
+    Raw(TextCell),
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CodeCell {
+    pub execution_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+    #[serde(default)]
+    pub outputs: Vec<Output>,
+    pub source: MultilineString,
+    /// Set when the kernel asked to clear the outputs once the next one arrives.
+    #[serde(skip)]
+    clear_pending: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TextCell {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+    pub source: MultilineString,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "output_type", rename_all = "snake_case")]
+pub enum Output {
+    DisplayData {
+        data: Map<String, Value>,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    Error {
+        ename: String,
+        evalue: String,
+        traceback: Vec<String>,
+    },
+    ExecuteResult {
+        data: Map<String, Value>,
+        execution_count: Option<u64>,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    Stream {
+        name: String,
+        text: MultilineString,
+    },
+}
+
+/// Text that nbformat stores either as a single string or as a list of lines.
+/// It is always written back as a list of lines, like Jupyter does.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MultilineString(pub String);
+
+impl Notebook {
+    pub fn parse(text: &str) -> Result<Self> {
+        let notebook: Self = serde_json::from_str(text)?;
+        if notebook.nbformat != 4 {
+            return Err(anyhow!(
+                "unsupported notebook format version {}",
+                notebook.nbformat
+            ));
+        }
+        Ok(notebook)
+    }
+
+    /// Serializes the notebook the way Jupyter does: sorted keys, one space of indentation
+    /// and a trailing newline. This keeps the diffs of notebooks edited in both tools small.
+    pub fn to_json(&self) -> Result<String> {
+        let value = sort_keys(serde_json::to_value(self)?);
+        let mut bytes = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+        let mut serializer = serde_json::Serializer::with_formatter(&mut bytes, formatter);
+        value.serialize(&mut serializer)?;
+        bytes.push(b'\n');
+        Ok(String::from_utf8(bytes)?)
+    }
+
+    /// The name of the kernel the notebook was last run with.
+    pub fn kernelspec_name(&self) -> Option<&str> {
+        self.metadata.get("kernelspec")?.get("name")?.as_str()
+    }
+
+    /// The language of the notebook's code cells.
+    pub fn language_name(&self) -> Option<&str> {
+        self.metadata
+            .get("kernelspec")
+            .and_then(|kernelspec| kernelspec.get("language"))
+            .or_else(|| self.metadata.get("language_info")?.get("name"))
+            .and_then(Value::as_str)
+    }
+}
+
+impl Cell {
+    pub fn source(&self) -> &str {
+        match self {
+            Cell::Code(cell) => &cell.source.0,
+            Cell::Markdown(cell) | Cell::Raw(cell) => &cell.source.0,
+        }
+    }
+
+    pub fn set_source(&mut self, source: String) {
+        match self {
+            Cell::Code(cell) => cell.source.0 = source,
+            Cell::Markdown(cell) | Cell::Raw(cell) => cell.source.0 = source,
+        }
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Cell::Code(cell) => cell.id.as_deref(),
+            Cell::Markdown(cell) | Cell::Raw(cell) => cell.id.as_deref(),
+        }
+    }
+
+    pub fn set_id(&mut self, id: String) {
+        match self {
+            Cell::Code(cell) => cell.id = Some(id),
+            Cell::Markdown(cell) | Cell::Raw(cell) => cell.id = Some(id),
+        }
+    }
+}
+
+impl CodeCell {
+    /// Drops the outputs of a previous execution before running the cell again.
+    pub fn clear_outputs(&mut self) {
+        self.outputs.clear();
+        self.execution_count = None;
+        self.clear_pending = false;
+    }
+
+    /// Records a message the kernel sent while executing this cell, returning whether the
+    /// cell changed.
+    pub fn apply_message(&mut self, message: &JupyterMessageContent) -> bool {
+        let output = match message {
+            JupyterMessageContent::ExecuteInput(input) => {
+                self.execution_count = serialized_field(input, "execution_count")
+                    .as_ref()
+                    .and_then(Value::as_u64);
+                return true;
+            }
+            JupyterMessageContent::ClearOutput(options) => {
+                if options.wait {
+                    self.clear_pending = true;
+                } else {
+                    self.outputs.clear();
+                }
+                return true;
+            }
+            JupyterMessageContent::ExecuteResult(result) => {
+                output_from_content("execute_result", result)
+            }
+            JupyterMessageContent::DisplayData(data) => output_from_content("display_data", data),
+            JupyterMessageContent::StreamContent(stream) => output_from_content("stream", stream),
+            JupyterMessageContent::ErrorOutput(error) => output_from_content("error", error),
+            _ => None,
+        };
+        let Some(output) = output else {
+            return false;
+        };
+
+        if self.clear_pending {
+            self.outputs.clear();
+            self.clear_pending = false;
+        }
+
+        // Consecutive writes to the same stream are stored as a single output.
+        if let (
+            Some(Output::Stream {
+                name: last_name,
+                text: last_text,
+            }),
+            Output::Stream { name, text },
+        ) = (self.outputs.last_mut(), &output)
+        {
+            if last_name == name {
+                last_text.0.push_str(&text.0);
+                return true;
+            }
+        }
+
+        self.outputs.push(output);
+        true
+    }
+}
+
+impl Output {
+    /// Converts the output into the representation rendered by the REPL.
+    pub fn to_output_type(&self) -> OutputType {
+        match self {
+            Output::Stream { text, .. } => OutputType::Stream(TerminalOutput::from(&text.0)),
+            Output::DisplayData { data, .. } | Output::ExecuteResult { data, .. } => {
+                match mime_bundle(data) {
+                    Ok(bundle) => (&bundle).into(),
+                    Err(error) => OutputType::Message(format!("Failed to load output: {}", error)),
+                }
+            }
+            Output::Error {
+                ename,
+                evalue,
+                traceback,
+            } => {
+                let mut terminal = TerminalOutput::new();
+                terminal.append_text(&traceback.join("\n"));
+                OutputType::ErrorOutput(ErrorView {
+                    ename: ename.clone(),
+                    evalue: evalue.clone(),
+                    traceback: terminal,
+                })
+            }
+        }
+    }
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+/// nbformat splits textual media types into lines, whereas kernels send them whole.
+fn mime_bundle(data: &Map<String, Value>) -> Result<MimeBundle> {
+    let data = data
+        .iter()
+        .map(|(mime_type, value)| {
+            let value = match value {
+                Value::Array(lines) if !mime_type.ends_with("json") => Value::String(
+                    lines
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .concat(),
+                ),
+                value => value.clone(),
+            };
+            (mime_type.clone(), value)
+        })
+        .collect::<Map<_, _>>();
+    Ok(serde_json::from_value(Value::Object(data))?)
+}
+
+/// Kernel messages carry the same fields as the corresponding nbformat outputs, so they are
+/// converted by round-tripping them through JSON.
+fn output_from_content(output_type: &str, content: &impl Serialize) -> Option<Output> {
+    let mut value = serde_json::to_value(content).log_err()?;
+    value
+        .as_object_mut()?
+        .insert("output_type".into(), output_type.into());
+    serde_json::from_value(value).log_err()
+}
+
+fn serialized_field(content: &impl Serialize, field: &str) -> Option<Value> {
+    serde_json::to_value(content)
+        .ok()?
+        .get_mut(field)
+        .map(Value::take)
+}
+
+impl Serialize for MultilineString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.split_inclusive('\n'))
+    }
+}
+
+impl<'de> Deserialize<'de> for MultilineString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Single(String),
+            Lines(Vec<String>),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Single(text) => Self(text),
+            Repr::Lines(lines) => Self(lines.concat()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_notebook_round_trip() {
+        let text = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "id": "a1",
+   "metadata": {},
+   "source": [
+    "# Title\n",
+    "Some text"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 2,
+   "id": "b2",
+   "metadata": {
+    "collapsed": false
+   },
+   "outputs": [
+    {
+     "name": "stdout",
+     "output_type": "stream",
+     "text": [
+      "hello\n"
+     ]
+    },
+    {
+     "data": {
+      "text/plain": [
+       "3"
+      ]
+     },
+     "execution_count": 2,
+     "metadata": {},
+     "output_type": "execute_result"
+    }
+   ],
+   "source": [
+    "print('hello')\n",
+    "1 + 2"
+   ]
+  }
+ ],
+ "metadata": {
+  "kernelspec": {
+   "display_name": "Python 3",
+   "language": "python",
+   "name": "python3"
+  }
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5
+}
+"##;
+
+        let notebook = Notebook::parse(text).unwrap();
+        assert_eq!(notebook.kernelspec_name(), Some("python3"));
+        assert_eq!(notebook.language_name(), Some("python"));
+        assert_eq!(notebook.cells.len(), 2);
+        assert_eq!(notebook.cells[0].source(), "# Title\nSome text");
+        let Cell::Code(code_cell) = &notebook.cells[1] else {
+            panic!("expected a code cell");
+        };
+        assert_eq!(code_cell.execution_count, Some(2));
+        assert_eq!(code_cell.outputs.len(), 2);
+
+        let saved = notebook.to_json().unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&saved).unwrap(),
+            serde_json::from_str::<Value>(text).unwrap()
+        );
+        assert_eq!(saved, text);
+    }
+
+    #[test]
+    fn test_multiline_string() {
+        let single: MultilineString = serde_json::from_value(json!("a\nb")).unwrap();
+        let lines: MultilineString = serde_json::from_value(json!(["a\n", "b"])).unwrap();
+        assert_eq!(single, lines);
+        assert_eq!(serde_json::to_value(&single).unwrap(), json!(["a\n", "b"]));
+        assert_eq!(
+            serde_json::to_value(MultilineString::default()).unwrap(),
+            json!([])
+        );
+    }
+
+    #[test]
+    fn test_unsupported_format_version() {
+        let text = r#"{"cells": [], "metadata": {}, "nbformat": 3, "nbformat_minor": 0}"#;
+        assert!(Notebook::parse(text).is_err());
+    }
+}
@@ -0,0 +1,1027 @@
+use crate::{
+    nbformat::{Cell, CodeCell, Notebook, Output, TextCell},
+    outputs::{ExecutionStatus, ExecutionView},
+    runtime_settings::JupyterSettings,
+    Interrupt, Restart, RuntimeManager, Shutdown,
+};
+use anyhow::Result;
+use collections::HashSet;
+use editor::{Editor, EditorEvent, EditorMode, MultiBuffer};
+use futures::StreamExt as _;
+use gpui::{
+    actions, Action, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Subscription, Task, TextStyleRefinement, View,
+};
+use language::{Buffer, Language};
+use markdown::{Markdown, MarkdownStyle};
+use project::{Project, ProjectEntryId, ProjectPath};
+use runtimelib::JupyterMessageContent;
+use settings::Settings as _;
+use std::{ffi::OsStr, sync::Arc};
+use ui::{prelude::*, Tooltip};
+use util::ResultExt as _;
+use workspace::{
+    item::{Item, ItemEvent, ProjectItem, TabContentParams},
+    Workspace,
+};
+
+actions!(
+    notebook,
+    [
+        RunCell,
+        RunAllCells,
+        AddCodeCell,
+        AddMarkdownCell,
+        DeleteCell,
+        SelectNextCell,
+        SelectPreviousCell
+    ]
+);
+
+const MAX_CELL_LINES: usize = 1024;
+
+pub fn init(cx: &mut AppContext) {
+    workspace::register_project_item::<NotebookEditor>(cx);
+}
+
+/// The `.ipynb` file backing a [`NotebookEditor`]. The notebook is read from and saved
+/// through the file's buffer, so the usual dirty tracking and file watching apply.
+pub struct NotebookItem {
+    buffer: Model<Buffer>,
+    project_path: ProjectPath,
+}
+
+impl project::Item for NotebookItem {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Model<Self>>>> {
+        if !JupyterSettings::get_global(cx).enabled
+            || path.path.extension().and_then(OsStr::to_str) != Some("ipynb")
+        {
+            return None;
+        }
+
+        let project_path = path.clone();
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(path.clone(), cx));
+        Some(cx.spawn(|mut cx| async move {
+            let buffer = open_buffer.await?;
+            cx.new_model(|_| NotebookItem {
+                buffer,
+                project_path,
+            })
+        }))
+    }
+
+    fn entry_id(&self, cx: &AppContext) -> Option<ProjectEntryId> {
+        project::File::from_dyn(self.buffer.read(cx).file())
+            .and_then(|file| file.project_entry_id(cx))
+    }
+
+    fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+}
+
+pub enum NotebookEvent {
+    Edited,
+    Saved,
+}
+
+/// Edits a Jupyter notebook as a list of cells, each with its own editor, and runs its
+/// code cells in a kernel launched for the notebook.
+pub struct NotebookEditor {
+    project: Model<Project>,
+    notebook_item: Model<NotebookItem>,
+    /// The notebook as it was loaded, without its cells, which live in `cells`.
+    notebook: Notebook,
+    cells: Vec<NotebookCell>,
+    selected_cell_ix: usize,
+    load_error: Option<SharedString>,
+    code_language: Option<Arc<Language>>,
+    markdown_language: Option<Arc<Language>>,
+    dirty: bool,
+    focus_handle: FocusHandle,
+    _load_languages: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct NotebookCell {
+    cell: Cell,
+    editor: View<Editor>,
+    /// The rendered markdown of a markdown cell, shown while the cell isn't being edited.
+    markdown: Option<View<Markdown>>,
+    editing: bool,
+    execution_view: View<ExecutionView>,
+    _subscription: Subscription,
+}
+
+impl NotebookEditor {
+    fn new(
+        project: Model<Project>,
+        notebook_item: Model<NotebookItem>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let buffer = notebook_item.read(cx).buffer.clone();
+        let subscriptions = vec![cx.subscribe(&buffer, |this, buffer, event, cx| {
+            // Pick up changes made to the file on disk, unless they would discard edits.
+            if let language::Event::Reloaded = event {
+                if !this.dirty {
+                    let text = buffer.read(cx).text();
+                    this.load(&text, cx);
+                }
+            }
+        })];
+
+        let mut this = Self {
+            project,
+            notebook_item,
+            notebook: Notebook {
+                cells: Vec::new(),
+                metadata: Default::default(),
+                nbformat: 4,
+                nbformat_minor: 5,
+            },
+            cells: Vec::new(),
+            selected_cell_ix: 0,
+            load_error: None,
+            code_language: None,
+            markdown_language: None,
+            dirty: false,
+            focus_handle: cx.focus_handle(),
+            _load_languages: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        let text = buffer.read(cx).text();
+        this.load(&text, cx);
+        this
+    }
+
+    fn load(&mut self, text: &str, cx: &mut ViewContext<Self>) {
+        let mut notebook = match Notebook::parse(text) {
+            Ok(notebook) => notebook,
+            Err(error) => {
+                self.load_error = Some(format!("Failed to open notebook: {error}").into());
+                self.cells.clear();
+                cx.notify();
+                return;
+            }
+        };
+
+        let cells = std::mem::take(&mut notebook.cells);
+        let kernelspec_name = notebook.kernelspec_name().map(ToString::to_string);
+        let language_name = notebook.language_name().map(ToString::to_string);
+        self.notebook = notebook;
+        self.load_error = None;
+        self.cells = cells
+            .into_iter()
+            .map(|cell| self.build_cell(cell, cx))
+            .collect();
+        self.selected_cell_ix = 0;
+
+        if let Some((runtime_manager, kernelspec_name)) =
+            RuntimeManager::global(cx).zip(kernelspec_name)
+        {
+            let notebook_id = cx.entity_id();
+            runtime_manager.update(cx, |runtime_manager, cx| {
+                if runtime_manager.selected_kernel(notebook_id).is_none() {
+                    runtime_manager.select_kernel(notebook_id, kernelspec_name, cx);
+                }
+            });
+        }
+
+        let languages = self.project.read(cx).languages().clone();
+        self._load_languages = cx.spawn(|this, mut cx| async move {
+            let code_language = match language_name {
+                Some(language_name) => languages.language_for_name(&language_name).await.ok(),
+                None => None,
+            };
+            let markdown_language = languages.language_for_name("Markdown").await.log_err();
+            this.update(&mut cx, |this, cx| {
+                this.code_language = code_language;
+                this.markdown_language = markdown_language;
+                for cell in &this.cells {
+                    let language = this.language_for_cell(&cell.cell);
+                    if let Some(buffer) = cell.editor.read(cx).buffer().read(cx).as_singleton() {
+                        buffer.update(cx, |buffer, cx| buffer.set_language(language, cx));
+                    }
+                }
+            })
+            .ok();
+        });
+        cx.notify();
+    }
+
+    fn language_for_cell(&self, cell: &Cell) -> Option<Arc<Language>> {
+        match cell {
+            Cell::Code(_) => self.code_language.clone(),
+            Cell::Markdown(_) => self.markdown_language.clone(),
+            Cell::Raw(_) => None,
+        }
+    }
+
+    fn build_cell(&self, cell: Cell, cx: &mut ViewContext<Self>) -> NotebookCell {
+        let language = self.language_for_cell(&cell);
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::local(cell.source(), cx);
+            buffer.set_language(language, cx);
+            buffer
+        });
+        let editor = cx.new_view(|cx| {
+            let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+            let mut editor = Editor::new(
+                EditorMode::AutoHeight {
+                    max_lines: MAX_CELL_LINES,
+                },
+                buffer,
+                None,
+                false,
+                cx,
+            );
+            editor.set_show_gutter(false, cx);
+            editor
+        });
+        let subscription = cx.subscribe(&editor, |this, editor, event, cx| match event {
+            EditorEvent::BufferEdited => this.mark_dirty(cx),
+            EditorEvent::Focused => {
+                if let Some(ix) = this.cells.iter().position(|cell| cell.editor == editor) {
+                    this.selected_cell_ix = ix;
+                    cx.notify();
+                }
+            }
+            _ => {}
+        });
+
+        let markdown = if let Cell::Markdown(_) = &cell {
+            let style = markdown_style(cx);
+            let languages = self.project.read(cx).languages().clone();
+            let source = cell.source().to_string();
+            Some(cx.new_view(|cx| Markdown::new(source, style, Some(languages), cx)))
+        } else {
+            None
+        };
+
+        let execution_view = cx.new_view(|cx| {
+            let mut execution_view = ExecutionView::new(cx);
+            if let Cell::Code(code_cell) = &cell {
+                execution_view.outputs = code_cell
+                    .outputs
+                    .iter()
+                    .map(Output::to_output_type)
+                    .collect();
+                if !execution_view.outputs.is_empty() {
+                    execution_view.status = ExecutionStatus::Finished;
+                }
+            }
+            execution_view
+        });
+
+        NotebookCell {
+            editing: markdown.is_none(),
+            cell,
+            editor,
+            markdown,
+            execution_view,
+            _subscription: subscription,
+        }
+    }
+
+    fn mark_dirty(&mut self, cx: &mut ViewContext<Self>) {
+        self.dirty = true;
+        cx.emit(NotebookEvent::Edited);
+        cx.notify();
+    }
+
+    fn to_notebook(&self, cx: &AppContext) -> Notebook {
+        let mut notebook = self.notebook.clone();
+        notebook.cells = self
+            .cells
+            .iter()
+            .map(|cell| {
+                let mut saved_cell = cell.cell.clone();
+                saved_cell.set_source(cell.editor.read(cx).text(cx));
+                saved_cell
+            })
+            .collect();
+        notebook
+    }
+
+    fn buffer(&self, cx: &AppContext) -> Model<Buffer> {
+        self.notebook_item.read(cx).buffer.clone()
+    }
+
+    /// Writes the notebook into its buffer, so that saving the buffer saves the notebook.
+    fn write_to_buffer(&self, cx: &mut ViewContext<Self>) -> Result<Model<Buffer>> {
+        let json = self.to_notebook(cx).to_json()?;
+        let buffer = self.buffer(cx);
+        buffer.update(cx, |buffer, cx| {
+            if buffer.text() != json {
+                buffer.set_text(json, cx);
+            }
+        });
+        Ok(buffer)
+    }
+
+    fn select_cell(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(cell) = self.cells.get(ix) else {
+            return;
+        };
+        self.selected_cell_ix = ix;
+        if cell.editing {
+            cell.editor.focus_handle(cx).focus(cx);
+        } else {
+            self.focus_handle.focus(cx);
+        }
+        cx.notify();
+    }
+
+    fn edit_cell(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if let Some(cell) = self.cells.get_mut(ix) {
+            cell.editing = true;
+        }
+        self.select_cell(ix, cx);
+    }
+
+    fn run_cell(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let language_name = self.kernel_language_name();
+        let Some(cell) = self.cells.get_mut(ix) else {
+            return;
+        };
+        let source = cell.editor.read(cx).text(cx);
+
+        match &mut cell.cell {
+            Cell::Code(code_cell) => code_cell.clear_outputs(),
+            Cell::Markdown(_) => {
+                cell.editing = false;
+                if let Some(markdown) = &cell.markdown {
+                    markdown.update(cx, |markdown, cx| markdown.reset(source, cx));
+                }
+                cx.notify();
+                return;
+            }
+            Cell::Raw(_) => return,
+        }
+
+        let Some(runtime_manager) = RuntimeManager::global(cx) else {
+            log::warn!("No runtime manager found");
+            return;
+        };
+        let Some(language_name) = language_name else {
+            log::warn!("The notebook does not declare the language of its code cells");
+            return;
+        };
+
+        let execution_view = cell.execution_view.clone();
+        execution_view.update(cx, |execution_view, cx| {
+            execution_view.outputs.clear();
+            execution_view.set_status(ExecutionStatus::ConnectingToKernel, cx);
+        });
+        self.mark_dirty(cx);
+
+        let notebook_id = cx.entity_id();
+        let receiver = runtime_manager.update(cx, |runtime_manager, cx| {
+            runtime_manager.execute_code(notebook_id, Some(notebook_id), language_name, source, cx)
+        });
+
+        cx.spawn(|this, mut cx| async move {
+            let mut receiver = receiver.await?;
+            while let Some(content) = receiver.next().await {
+                if let JupyterMessageContent::Status(status) = &content {
+                    runtime_manager.update(&mut cx, |runtime_manager, cx| {
+                        runtime_manager.set_execution_state(
+                            notebook_id,
+                            status.execution_state.clone(),
+                            cx,
+                        )
+                    })?;
+                }
+
+                execution_view.update(&mut cx, |execution_view, cx| {
+                    execution_view.push_message(&content, cx)
+                })?;
+
+                this.update(&mut cx, |this, cx| {
+                    let changed = this
+                        .cells
+                        .iter_mut()
+                        .find(|cell| cell.execution_view == execution_view)
+                        .map_or(false, |cell| match &mut cell.cell {
+                            Cell::Code(code_cell) => code_cell.apply_message(&content),
+                            _ => false,
+                        });
+                    if changed {
+                        this.mark_dirty(cx);
+                    }
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// The language the kernel should run, as named by the kernelspecs.
+    fn kernel_language_name(&self) -> Option<Arc<str>> {
+        self.notebook.language_name().map(Arc::from).or_else(|| {
+            self.code_language
+                .as_ref()
+                .map(|language| language.code_fence_block_name())
+        })
+    }
+
+    fn insert_cell(&mut self, mut cell: Cell, cx: &mut ViewContext<Self>) {
+        // Every cell has a unique id since nbformat 4.5.
+        if self.notebook.nbformat_minor >= 5 {
+            cell.set_id(uuid::Uuid::new_v4().to_string());
+        }
+        let ix = if self.cells.is_empty() {
+            0
+        } else {
+            self.selected_cell_ix + 1
+        };
+        let cell = self.build_cell(cell, cx);
+        self.cells.insert(ix, cell);
+        self.edit_cell(ix, cx);
+        self.mark_dirty(cx);
+    }
+
+    fn run_selected_cell(&mut self, _: &RunCell, cx: &mut ViewContext<Self>) {
+        let ix = self.selected_cell_ix;
+        self.run_cell(ix, cx);
+        if ix + 1 < self.cells.len() {
+            self.select_cell(ix + 1, cx);
+        }
+    }
+
+    fn run_all_cells(&mut self, _: &RunAllCells, cx: &mut ViewContext<Self>) {
+        for ix in 0..self.cells.len() {
+            self.run_cell(ix, cx);
+        }
+    }
+
+    fn add_code_cell(&mut self, _: &AddCodeCell, cx: &mut ViewContext<Self>) {
+        self.insert_cell(Cell::Code(CodeCell::default()), cx);
+    }
+
+    fn add_markdown_cell(&mut self, _: &AddMarkdownCell, cx: &mut ViewContext<Self>) {
+        self.insert_cell(Cell::Markdown(TextCell::default()), cx);
+    }
+
+    fn delete_cell(&mut self, _: &DeleteCell, cx: &mut ViewContext<Self>) {
+        if self.selected_cell_ix >= self.cells.len() {
+            return;
+        }
+        self.cells.remove(self.selected_cell_ix);
+        self.selected_cell_ix = self
+            .selected_cell_ix
+            .min(self.cells.len().saturating_sub(1));
+        self.select_cell(self.selected_cell_ix, cx);
+        self.mark_dirty(cx);
+    }
+
+    fn select_next_cell(&mut self, _: &SelectNextCell, cx: &mut ViewContext<Self>) {
+        self.select_cell(self.selected_cell_ix + 1, cx);
+    }
+
+    fn select_previous_cell(&mut self, _: &SelectPreviousCell, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_cell_ix.checked_sub(1) {
+            self.select_cell(ix, cx);
+        }
+    }
+
+    fn interrupt(&mut self, _: &Interrupt, cx: &mut ViewContext<Self>) {
+        let notebook_id = cx.entity_id();
+        if let Some(runtime_manager) = RuntimeManager::global(cx) {
+            runtime_manager.update(cx, |runtime_manager, cx| {
+                runtime_manager.interrupt(notebook_id, cx)
+            });
+        }
+    }
+
+    fn restart(&mut self, _: &Restart, cx: &mut ViewContext<Self>) {
+        let notebook_id = cx.entity_id();
+        if let Some(runtime_manager) = RuntimeManager::global(cx) {
+            runtime_manager
+                .update(cx, |runtime_manager, cx| {
+                    runtime_manager.restart(notebook_id, cx)
+                })
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn shutdown(&mut self, _: &Shutdown, cx: &mut ViewContext<Self>) {
+        let notebook_id = cx.entity_id();
+        if let Some(runtime_manager) = RuntimeManager::global(cx) {
+            runtime_manager
+                .update(cx, |runtime_manager, cx| {
+                    runtime_manager.shutdown(notebook_id, cx)
+                })
+                .detach();
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .p_1()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                IconButton::new("run-all-cells", IconName::Play)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::for_action("Run All Cells", &RunAllCells, cx))
+                    .on_click(|_, cx| cx.dispatch_action(RunAllCells.boxed_clone())),
+            )
+            .child(
+                IconButton::new("interrupt-kernel", IconName::Stop)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::for_action("Interrupt Kernel", &Interrupt, cx))
+                    .on_click(|_, cx| cx.dispatch_action(Interrupt.boxed_clone())),
+            )
+            .child(
+                IconButton::new("restart-kernel", IconName::RotateCw)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::for_action("Restart Kernel", &Restart, cx))
+                    .on_click(|_, cx| cx.dispatch_action(Restart.boxed_clone())),
+            )
+            .child(
+                Button::new("add-code-cell", "Code")
+                    .icon(IconName::Plus)
+                    .icon_position(IconPosition::Start)
+                    .icon_size(IconSize::Small)
+                    .label_size(LabelSize::Small)
+                    .on_click(|_, cx| cx.dispatch_action(AddCodeCell.boxed_clone())),
+            )
+            .child(
+                Button::new("add-markdown-cell", "Markdown")
+                    .icon(IconName::Plus)
+                    .icon_position(IconPosition::Start)
+                    .icon_size(IconSize::Small)
+                    .label_size(LabelSize::Small)
+                    .on_click(|_, cx| cx.dispatch_action(AddMarkdownCell.boxed_clone())),
+            )
+    }
+
+    fn render_cell(
+        &self,
+        ix: usize,
+        cell: &NotebookCell,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        let colors = cx.theme().colors();
+        let is_selected = ix == self.selected_cell_ix;
+        let prompt = match &cell.cell {
+            Cell::Code(code_cell) => match code_cell.execution_count {
+                Some(execution_count) => format!("[{execution_count}]:"),
+                None => "[ ]:".to_string(),
+            },
+            Cell::Markdown(_) | Cell::Raw(_) => String::new(),
+        };
+
+        let content = match &cell.markdown {
+            Some(markdown) if !cell.editing => div()
+                .id(("rendered-markdown", ix))
+                .w_full()
+                .px_2()
+                .on_click(cx.listener(move |this, _, cx| this.edit_cell(ix, cx)))
+                .child(markdown.clone())
+                .into_any_element(),
+            _ => div()
+                .w_full()
+                .p_1()
+                .bg(colors.editor_background)
+                .border_1()
+                .border_color(colors.border_variant)
+                .rounded_md()
+                .child(cell.editor.clone())
+                .into_any_element(),
+        };
+
+        let has_outputs = !matches!(
+            cell.execution_view.read(cx).status,
+            ExecutionStatus::Unknown
+        );
+
+        h_flex()
+            .id(("notebook-cell", ix))
+            .w_full()
+            .items_start()
+            .py_1()
+            .pr_2()
+            .border_l_2()
+            .border_color(if is_selected {
+                colors.border_focused
+            } else {
+                gpui::transparent_black()
+            })
+            .on_mouse_down(
+                gpui::MouseButton::Left,
+                cx.listener(move |this, _, cx| {
+                    this.selected_cell_ix = ix;
+                    cx.notify();
+                }),
+            )
+            .child(
+                div().w(rems(4.)).pt_1().flex_none().child(
+                    Label::new(prompt)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .gap_1()
+                    .child(content)
+                    .when(has_outputs, |this| {
+                        this.child(div().w_full().px_1().child(cell.execution_view.clone()))
+                    }),
+            )
+            .into_any_element()
+    }
+}
+
+fn markdown_style(cx: &WindowContext) -> MarkdownStyle {
+    MarkdownStyle {
+        code_block: TextStyleRefinement {
+            font_family: Some("Zed Plex Mono".into()),
+            color: Some(cx.theme().colors().editor_foreground),
+            background_color: Some(cx.theme().colors().editor_background),
+            ..Default::default()
+        },
+        inline_code: Default::default(),
+        block_quote: Default::default(),
+        link: TextStyleRefinement {
+            color: Some(Color::Accent.color(cx)),
+            ..Default::default()
+        },
+        rule_color: cx.theme().colors().border,
+        block_quote_border_color: cx.theme().colors().border,
+        syntax: cx.theme().syntax().clone(),
+        selection_background_color: cx.theme().players().local().selection,
+    }
+}
+
+impl EventEmitter<NotebookEvent> for NotebookEditor {}
+
+impl FocusableView for NotebookEditor {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for NotebookEditor {
+    type Event = NotebookEvent;
+
+    fn to_item_events(event: &NotebookEvent, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            NotebookEvent::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            NotebookEvent::Saved => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        let project_path = &self.notebook_item.read(cx).project_path;
+        let title = project_path
+            .path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Label::new(title)
+            .single_line()
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        let project_path = &self.notebook_item.read(cx).project_path;
+        Some(project_path.path.to_string_lossy().to_string().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("notebook")
+    }
+
+    fn display_name(&self) -> Option<&'static str> {
+        Some("notebook")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        f(self.notebook_item.entity_id(), self.notebook_item.read(cx))
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn is_dirty(&self, _: &AppContext) -> bool {
+        self.dirty
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.buffer(cx).read(cx).has_conflict()
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let buffer = match self.write_to_buffer(cx) {
+            Ok(buffer) => buffer,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let save = project.update(cx, |project, cx| project.save_buffer(buffer, cx));
+        cx.spawn(|this, mut cx| async move {
+            save.await?;
+            this.update(&mut cx, |this, cx| {
+                this.dirty = false;
+                cx.emit(NotebookEvent::Saved);
+            })
+        })
+    }
+
+    fn save_as(
+        &mut self,
+        project: Model<Project>,
+        path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let buffer = match self.write_to_buffer(cx) {
+            Ok(buffer) => buffer,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let save = project.update(cx, |project, cx| {
+            project.save_buffer_as(buffer, path.clone(), cx)
+        });
+        let notebook_item = self.notebook_item.clone();
+        cx.spawn(|this, mut cx| async move {
+            save.await?;
+            notebook_item.update(&mut cx, |notebook_item, _| {
+                notebook_item.project_path = path;
+            })?;
+            this.update(&mut cx, |this, cx| {
+                this.dirty = false;
+                cx.emit(NotebookEvent::Saved);
+            })
+        })
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let buffer = self.buffer(cx);
+        let reload = project.update(cx, |project, cx| {
+            project.reload_buffers(HashSet::from_iter([buffer.clone()]), false, cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            reload.await?;
+            this.update(&mut cx, |this, cx| {
+                let text = buffer.read(cx).text();
+                this.dirty = false;
+                this.load(&text, cx);
+                cx.emit(NotebookEvent::Saved);
+            })
+        })
+    }
+}
+
+impl ProjectItem for NotebookEditor {
+    type Item = NotebookItem;
+
+    fn for_project_item(
+        project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(project, item, cx)
+    }
+}
+
+impl Render for NotebookEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let cells = if let Some(load_error) = self.load_error.clone() {
+            vec![div()
+                .p_4()
+                .child(Label::new(load_error).color(Color::Error))
+                .into_any_element()]
+        } else {
+            self.cells
+                .iter()
+                .enumerate()
+                .map(|(ix, cell)| self.render_cell(ix, cell, cx))
+                .collect()
+        };
+
+        v_flex()
+            .key_context("NotebookEditor")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::run_selected_cell))
+            .on_action(cx.listener(Self::run_all_cells))
+            .on_action(cx.listener(Self::add_code_cell))
+            .on_action(cx.listener(Self::add_markdown_cell))
+            .on_action(cx.listener(Self::delete_cell))
+            .on_action(cx.listener(Self::select_next_cell))
+            .on_action(cx.listener(Self::select_previous_cell))
+            .on_action(cx.listener(Self::interrupt))
+            .on_action(cx.listener(Self::restart))
+            .on_action(cx.listener(Self::shutdown))
+            .child(self.render_toolbar(cx))
+            .child(
+                v_flex()
+                    .id("notebook-cells")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .py_2()
+                    .gap_1()
+                    .children(cells),
+            )
+    }
+}
+
+/// Returns the notebook open in the workspace's active pane, if any.
+pub(crate) fn active_notebook(
+    workspace: &Workspace,
+    cx: &AppContext,
+) -> Option<View<NotebookEditor>> {
+    workspace.active_item_as::<NotebookEditor>(cx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbformat::MultilineString;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Fs as _};
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore};
+
+    #[gpui::test]
+    async fn test_notebook_editor_saves_nbformat(cx: &mut TestAppContext) {
+        init_test(cx);
+        let notebook_json = Notebook {
+            cells: vec![
+                Cell::Markdown(TextCell {
+                    source: MultilineString("# Title".into()),
+                    ..Default::default()
+                }),
+                Cell::Code(CodeCell {
+                    execution_count: Some(1),
+                    source: MultilineString("print('hi')".into()),
+                    outputs: vec![Output::Stream {
+                        name: "stdout".into(),
+                        text: MultilineString("hi\n".into()),
+                    }],
+                    ..Default::default()
+                }),
+            ],
+            metadata: serde_json::from_value(json!({
+                "kernelspec": {"name": "python3", "language": "python", "display_name": "Python 3"}
+            }))
+            .unwrap(),
+            nbformat: 4,
+            nbformat_minor: 5,
+        }
+        .to_json()
+        .unwrap();
+        let (fs, project, notebook_editor, cx) = open_notebook(notebook_json, cx).await;
+
+        notebook_editor.update(cx, |notebook_editor, cx| {
+            assert_eq!(notebook_editor.cells.len(), 2);
+            assert!(!notebook_editor.is_dirty(cx));
+            notebook_editor.cells[1]
+                .editor
+                .update(cx, |editor, cx| editor.set_text("print('hello')", cx));
+            assert!(notebook_editor.is_dirty(cx));
+        });
+
+        notebook_editor
+            .update(cx, |notebook_editor, cx| {
+                notebook_editor.save(false, project.clone(), cx)
+            })
+            .await
+            .unwrap();
+
+        let saved = fs.load("/root/notebook.ipynb".as_ref()).await.unwrap();
+        let saved = Notebook::parse(&saved).unwrap();
+        assert_eq!(saved.cells[0].source(), "# Title");
+        assert_eq!(saved.cells[1].source(), "print('hello')");
+        let Cell::Code(code_cell) = &saved.cells[1] else {
+            panic!("expected a code cell");
+        };
+        assert_eq!(code_cell.execution_count, Some(1));
+        assert_eq!(code_cell.outputs.len(), 1);
+        assert_eq!(saved.kernelspec_name(), Some("python3"));
+        notebook_editor.update(cx, |notebook_editor, cx| {
+            assert!(!notebook_editor.is_dirty(cx));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_inserted_cells_have_ids(cx: &mut TestAppContext) {
+        init_test(cx);
+        let notebook_json = Notebook {
+            cells: vec![Cell::Code(CodeCell {
+                id: Some("existing".into()),
+                source: MultilineString("x = 1".into()),
+                ..Default::default()
+            })],
+            metadata: Default::default(),
+            nbformat: 4,
+            nbformat_minor: 5,
+        }
+        .to_json()
+        .unwrap();
+        let (fs, project, notebook_editor, cx) = open_notebook(notebook_json, cx).await;
+
+        notebook_editor.update(cx, |notebook_editor, cx| {
+            notebook_editor.add_code_cell(&AddCodeCell, cx);
+            notebook_editor.add_markdown_cell(&AddMarkdownCell, cx);
+            notebook_editor.add_code_cell(&AddCodeCell, cx);
+        });
+        notebook_editor
+            .update(cx, |notebook_editor, cx| {
+                notebook_editor.save(false, project.clone(), cx)
+            })
+            .await
+            .unwrap();
+
+        let saved = fs.load("/root/notebook.ipynb".as_ref()).await.unwrap();
+        let saved = Notebook::parse(&saved).unwrap();
+        assert_eq!(saved.cells.len(), 4);
+        assert_eq!(saved.cells[0].id(), Some("existing"));
+        let ids = saved
+            .cells
+            .iter()
+            .map(|cell| cell.id().expect("every cell should have an id"))
+            .collect::<HashSet<_>>();
+        assert_eq!(ids.len(), 4);
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            editor::init_settings(cx);
+            workspace::init_settings(cx);
+            JupyterSettings::register(cx);
+        });
+    }
+
+    async fn open_notebook(
+        notebook_json: String,
+        cx: &mut TestAppContext,
+    ) -> (
+        Arc<FakeFs>,
+        Model<Project>,
+        View<NotebookEditor>,
+        &mut VisualTestContext,
+    ) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "notebook.ipynb": notebook_json }))
+            .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_buffer((worktree_id, "notebook.ipynb"), cx)
+            })
+            .await
+            .unwrap();
+        let notebook_item = cx.new_model(|_| NotebookItem {
+            buffer,
+            project_path: (worktree_id, "notebook.ipynb").into(),
+        });
+
+        let (notebook_editor, cx) = cx.add_window_view({
+            let project = project.clone();
+            |cx| NotebookEditor::new(project, notebook_item, cx)
+        });
+        (fs, project, notebook_editor, cx)
+    }
+}
@@ -28,17 +28,34 @@ use std::{ops::Range, time::Instant};
 use std::{sync::Arc, time::Duration};
 use theme::{ActiveTheme, ThemeSettings};
 use ui::prelude::*;
+use util::ResultExt as _;
 use workspace::Workspace;
 
+mod kernel_picker;
+mod nbformat;
+mod notebook;
 mod outputs;
-// mod runtime_panel;
+mod runtime_panel;
 mod runtime_settings;
 mod runtimes;
 mod stdio;
 
+pub use notebook::NotebookEditor;
+pub use runtime_panel::RuntimePanel;
 use runtimes::{get_runtime_specifications, Request, RunningKernel, RuntimeSpecification};
 
-actions!(repl, [Run]);
+actions!(
+    repl,
+    [
+        Run,
+        Interrupt,
+        Restart,
+        Shutdown,
+        ClearOutputs,
+        SelectKernel,
+        ToggleFocus
+    ]
+);
 
 #[derive(Clone)]
 pub struct RuntimeManagerGlobal(Model<RuntimeManager>);
@@ -74,10 +91,20 @@ pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
     JupyterSettings::register(cx);
 
     observe_jupyter_settings_changes(fs.clone(), cx);
+    notebook::init(cx);
 
     cx.observe_new_views(
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
-            workspace.register_action(run);
+            workspace
+                .register_action(run)
+                .register_action(interrupt)
+                .register_action(restart)
+                .register_action(shutdown)
+                .register_action(clear_outputs)
+                .register_action(kernel_picker::toggle)
+                .register_action(|workspace, _: &ToggleFocus, cx| {
+                    workspace.toggle_panel_focus::<RuntimePanel>(cx);
+                });
         },
     )
     .detach();
@@ -100,8 +127,9 @@ fn initialize_runtime_manager(fs: Arc<dyn Fs>, cx: &mut AppContext) {
 
         let runtime_specifications = get_runtime_specifications(fs).await?;
 
-        runtime_manager.update(&mut cx, |this, _cx| {
+        runtime_manager.update(&mut cx, |this, cx| {
             this.runtime_specifications = runtime_specifications;
+            cx.notify();
         })?;
 
         anyhow::Ok(())
@@ -126,6 +154,7 @@ fn observe_jupyter_settings_changes(fs: Arc<dyn Fs>, cx: &mut AppContext) {
 pub enum Kernel {
     RunningKernel(RunningKernel),
     StartingKernel(Shared<Task<()>>),
+    ShuttingDown,
     FailedLaunch,
 }
 
@@ -133,6 +162,8 @@ pub enum Kernel {
 pub struct RuntimeManager {
     fs: Arc<dyn Fs>,
     runtime_specifications: Vec<RuntimeSpecification>,
+    /// The name of the runtime specification picked for a buffer, keyed by the buffer's entity.
+    kernel_selections: HashMap<EntityId, String>,
 
     instances: HashMap<EntityId, Kernel>,
     editors: HashMap<WeakView<Editor>, EditorRuntimeState>,
@@ -162,15 +193,201 @@ impl RuntimeManager {
         Self {
             fs,
             runtime_specifications: Default::default(),
+            kernel_selections: Default::default(),
             instances: Default::default(),
             editors: Default::default(),
             _execution_views_by_id: Default::default(),
         }
     }
 
+    pub fn runtime_specifications(&self) -> &[RuntimeSpecification] {
+        &self.runtime_specifications
+    }
+
+    pub fn kernels(&self) -> impl Iterator<Item = (EntityId, &Kernel)> {
+        self.instances
+            .iter()
+            .map(|(entity_id, kernel)| (*entity_id, kernel))
+    }
+
+    /// Returns the editor a kernel was launched for, if it is still open.
+    pub fn editor_for_kernel(&self, entity_id: EntityId) -> Option<View<Editor>> {
+        self.editors
+            .keys()
+            .filter(|editor| editor.entity_id() == entity_id)
+            .find_map(|editor| editor.upgrade())
+    }
+
+    /// The runtime that will be used to run code from the given buffer: the one
+    /// picked for it, or else the first one matching its language.
+    fn runtime_specification_for(
+        &self,
+        buffer_id: Option<EntityId>,
+        language_name: &str,
+    ) -> Option<&RuntimeSpecification> {
+        let selected_name =
+            buffer_id.and_then(|buffer_id| self.kernel_selections.get(&buffer_id));
+        if let Some(selected_name) = selected_name {
+            if let Some(runtime_specification) = self
+                .runtime_specifications
+                .iter()
+                .find(|runtime_specification| &runtime_specification.name == selected_name)
+            {
+                return Some(runtime_specification);
+            }
+        }
+
+        self.runtime_specifications
+            .iter()
+            .find(|runtime_specification| {
+                runtime_specification.kernelspec.language == language_name
+            })
+    }
+
+    pub fn selected_kernel(&self, buffer_id: EntityId) -> Option<&str> {
+        self.kernel_selections.get(&buffer_id).map(String::as_str)
+    }
+
+    /// Picks the runtime used for a buffer. Kernels already running a different runtime
+    /// for editors of that buffer are shut down, so the next run launches the new one.
+    pub fn select_kernel(
+        &mut self,
+        buffer_id: EntityId,
+        runtime_name: String,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let kernels_to_shut_down = self
+            .editors
+            .keys()
+            .filter_map(|editor| editor.upgrade())
+            .filter(|editor| {
+                editor
+                    .read(cx)
+                    .buffer()
+                    .read(cx)
+                    .as_singleton()
+                    .map_or(false, |buffer| buffer.entity_id() == buffer_id)
+            })
+            .map(|editor| editor.entity_id())
+            // Notebooks run their cells in a kernel keyed by the notebook itself.
+            .chain(Some(buffer_id))
+            .filter(|entity_id| match self.instances.get(entity_id) {
+                Some(Kernel::RunningKernel(kernel)) => {
+                    kernel.runtime_specification().name != runtime_name
+                }
+                _ => false,
+            })
+            .collect::<Vec<_>>();
+
+        self.kernel_selections.insert(buffer_id, runtime_name);
+        for entity_id in kernels_to_shut_down {
+            self.shutdown(entity_id, cx).detach();
+        }
+        cx.notify();
+    }
+
+    fn set_execution_state(
+        &mut self,
+        entity_id: EntityId,
+        execution_state: runtimelib::ExecutionState,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(Kernel::RunningKernel(kernel)) = self.instances.get_mut(&entity_id) {
+            kernel.execution_state = execution_state;
+            cx.notify();
+        }
+    }
+
+    /// Asks the kernel to stop the code it is currently executing.
+    pub fn interrupt(&mut self, entity_id: EntityId, _cx: &mut ModelContext<Self>) {
+        if let Some(Kernel::RunningKernel(kernel)) = self.instances.get(&entity_id) {
+            let (tx, _rx) = mpsc::unbounded();
+            kernel
+                .control_tx
+                .unbounded_send(Request {
+                    request: runtimelib::InterruptRequest {}.into(),
+                    responses_rx: tx,
+                })
+                .log_err();
+        }
+    }
+
+    /// Asks the kernel to shut down, then stops its process.
+    pub fn shutdown(&mut self, entity_id: EntityId, cx: &mut ModelContext<Self>) -> Task<()> {
+        let kernel = match self.instances.remove(&entity_id) {
+            Some(Kernel::RunningKernel(kernel)) => kernel,
+            Some(_) | None => {
+                cx.notify();
+                return Task::ready(());
+            }
+        };
+        self.instances.insert(entity_id, Kernel::ShuttingDown);
+        cx.notify();
+
+        let mut control_tx = kernel.control_tx.clone();
+        cx.spawn(|this, mut cx| async move {
+            let (tx, rx) = mpsc::unbounded();
+            let shutdown_request = Request {
+                request: runtimelib::ShutdownRequest { restart: false }.into(),
+                responses_rx: tx,
+            };
+            if control_tx.send(shutdown_request).await.is_ok() {
+                let mut rx = rx.fuse();
+                timeout(Duration::from_secs(1), rx.next()).await.ok();
+            }
+
+            // Dropping the kernel kills its process if it did not exit on its own.
+            drop(kernel);
+
+            this.update(&mut cx, |this, cx| {
+                if let Some(Kernel::ShuttingDown) = this.instances.get(&entity_id) {
+                    this.instances.remove(&entity_id);
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    /// Shuts the kernel down and launches a new one from the same runtime.
+    pub fn restart(
+        &mut self,
+        entity_id: EntityId,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let runtime_specification = match self.instances.get(&entity_id) {
+            Some(Kernel::RunningKernel(kernel)) => kernel.runtime_specification().clone(),
+            _ => return Task::ready(Err(anyhow!("no running kernel to restart"))),
+        };
+
+        let shutdown = self.shutdown(entity_id, cx);
+        cx.spawn(|this, mut cx| async move {
+            shutdown.await;
+            this.update(&mut cx, |this, cx| {
+                this.start_kernel(entity_id, Ok(runtime_specification), cx)
+            })?
+            .await;
+            anyhow::Ok(())
+        })
+    }
+
+    /// Removes the outputs of every execution in the editor.
+    pub fn clear_outputs(&mut self, editor: &View<Editor>, cx: &mut ModelContext<Self>) {
+        let Some(editor_runtime_state) = self.editors.get_mut(&editor.downgrade()) else {
+            return;
+        };
+        let blocks_to_remove = editor_runtime_state
+            .blocks
+            .drain(..)
+            .map(|block| block.block_id)
+            .collect::<HashSet<_>>();
+        editor.update(cx, |editor, cx| editor.remove_blocks(blocks_to_remove, None, cx));
+    }
+
     fn get_or_launch_kernel(
         &mut self,
         entity_id: EntityId,
+        buffer_id: Option<EntityId>,
         language_name: Arc<str>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<UnboundedSender<Request>>> {
@@ -180,34 +397,12 @@ impl RuntimeManager {
                 return Task::ready(anyhow::Ok(running_kernel.request_tx.clone()));
             }
             Some(Kernel::StartingKernel(task)) => task.clone(),
-            Some(Kernel::FailedLaunch) | None => {
-                self.instances.remove(&entity_id);
-
-                let kernel = self.launch_kernel(entity_id, language_name, cx);
-                let pending_kernel = cx
-                    .spawn(|this, mut cx| async move {
-                        let running_kernel = kernel.await;
-
-                        match running_kernel {
-                            Ok(running_kernel) => {
-                                let _ = this.update(&mut cx, |this, _cx| {
-                                    this.instances
-                                        .insert(entity_id, Kernel::RunningKernel(running_kernel));
-                                });
-                            }
-                            Err(_err) => {
-                                let _ = this.update(&mut cx, |this, _cx| {
-                                    this.instances.insert(entity_id, Kernel::FailedLaunch);
-                                });
-                            }
-                        }
-                    })
-                    .shared();
-
-                self.instances
-                    .insert(entity_id, Kernel::StartingKernel(pending_kernel.clone()));
-
-                pending_kernel
+            Some(Kernel::ShuttingDown) | Some(Kernel::FailedLaunch) | None => {
+                let runtime_specification = self
+                    .runtime_specification_for(buffer_id, &language_name)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("No runtime found for language {}", language_name));
+                self.start_kernel(entity_id, runtime_specification, cx)
             }
         };
 
@@ -228,32 +423,52 @@ impl RuntimeManager {
         })
     }
 
-    fn launch_kernel(
+    fn start_kernel(
         &mut self,
         entity_id: EntityId,
-        language_name: Arc<str>,
+        runtime_specification: Result<RuntimeSpecification>,
         cx: &mut ModelContext<Self>,
-    ) -> Task<Result<RunningKernel>> {
-        // Get first runtime that matches the language name (for now)
-        let runtime_specification =
-            self.runtime_specifications
-                .iter()
-                .find(|runtime_specification| {
-                    runtime_specification.kernelspec.language == language_name.to_string()
-                });
-
-        let runtime_specification = match runtime_specification {
-            Some(runtime_specification) => runtime_specification,
-            None => {
-                return Task::ready(Err(anyhow::anyhow!(
-                    "No runtime found for language {}",
-                    language_name
-                )));
-            }
+    ) -> Shared<Task<()>> {
+        let kernel = match runtime_specification {
+            Ok(runtime_specification) => self.launch_kernel(entity_id, runtime_specification, cx),
+            Err(error) => Task::ready(Err(error)),
         };
+        let pending_kernel = cx
+            .spawn(|this, mut cx| async move {
+                let running_kernel = kernel.await;
+
+                match running_kernel {
+                    Ok(running_kernel) => {
+                        let _ = this.update(&mut cx, |this, cx| {
+                            this.instances
+                                .insert(entity_id, Kernel::RunningKernel(running_kernel));
+                            cx.notify();
+                        });
+                    }
+                    Err(err) => {
+                        log::error!("failed to launch kernel: {err:?}");
+                        let _ = this.update(&mut cx, |this, cx| {
+                            this.instances.insert(entity_id, Kernel::FailedLaunch);
+                            cx.notify();
+                        });
+                    }
+                }
+            })
+            .shared();
+
+        self.instances
+            .insert(entity_id, Kernel::StartingKernel(pending_kernel.clone()));
+        cx.notify();
 
-        let runtime_specification = runtime_specification.clone();
+        pending_kernel
+    }
 
+    fn launch_kernel(
+        &mut self,
+        entity_id: EntityId,
+        runtime_specification: RuntimeSpecification,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<RunningKernel>> {
         let fs = self.fs.clone();
 
         cx.spawn(|_, cx| async move {
@@ -314,13 +529,14 @@ impl RuntimeManager {
     fn execute_code(
         &mut self,
         entity_id: EntityId,
+        buffer_id: Option<EntityId>,
         language_name: Arc<str>,
         code: String,
         cx: &mut ModelContext<Self>,
     ) -> impl Future<Output = Result<mpsc::UnboundedReceiver<JupyterMessageContent>>> {
         let (tx, rx) = mpsc::unbounded();
 
-        let request_tx = self.get_or_launch_kernel(entity_id, language_name, cx);
+        let request_tx = self.get_or_launch_kernel(entity_id, buffer_id, language_name, cx);
 
         async move {
             let request_tx = request_tx.await?;
@@ -448,6 +664,12 @@ pub fn run(workspace: &mut Workspace, _: &Run, cx: &mut ViewContext<Workspace>)
     };
 
     let entity_id = editor.entity_id();
+    let buffer_id = editor
+        .read(cx)
+        .buffer()
+        .read(cx)
+        .as_singleton()
+        .map(|buffer| buffer.entity_id());
 
     let execution_view = cx.new_view(|cx| ExecutionView::new(cx));
 
@@ -507,7 +729,13 @@ pub fn run(workspace: &mut Workspace, _: &Run, cx: &mut ViewContext<Workspace>)
             .blocks
             .push(editor_runtime_block.clone());
 
-        runtime_manager.execute_code(entity_id, language_name, selected_text.clone(), cx)
+        runtime_manager.execute_code(
+            entity_id,
+            buffer_id,
+            language_name,
+            selected_text.clone(),
+            cx,
+        )
     });
 
     cx.spawn(|_this, mut cx| async move {
@@ -518,6 +746,16 @@ pub fn run(workspace: &mut Workspace, _: &Run, cx: &mut ViewContext<Workspace>)
 
         let execution_view = execution_view.clone();
         while let Some(content) = receiver.next().await {
+            if let JupyterMessageContent::Status(status) = &content {
+                runtime_manager.update(&mut cx, |runtime_manager, cx| {
+                    runtime_manager.set_execution_state(
+                        entity_id,
+                        status.execution_state.clone(),
+                        cx,
+                    )
+                })?;
+            }
+
             execution_view.update(&mut cx, |execution_view, cx| {
                 execution_view.push_message(&content, cx)
             })?;
@@ -539,6 +777,59 @@ pub fn run(workspace: &mut Workspace, _: &Run, cx: &mut ViewContext<Workspace>)
     .detach_and_log_err(cx);
 }
 
+fn active_editor_and_runtime_manager(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) -> Option<(View<Editor>, Model<RuntimeManager>)> {
+    let editor = get_active_editor(workspace, cx)?;
+    let runtime_manager = RuntimeManager::global(cx)?;
+    Some((editor, runtime_manager))
+}
+
+pub fn interrupt(workspace: &mut Workspace, _: &Interrupt, cx: &mut ViewContext<Workspace>) {
+    let Some((editor, runtime_manager)) = active_editor_and_runtime_manager(workspace, cx) else {
+        return;
+    };
+    runtime_manager.update(cx, |runtime_manager, cx| {
+        runtime_manager.interrupt(editor.entity_id(), cx)
+    });
+}
+
+pub fn restart(workspace: &mut Workspace, _: &Restart, cx: &mut ViewContext<Workspace>) {
+    let Some((editor, runtime_manager)) = active_editor_and_runtime_manager(workspace, cx) else {
+        return;
+    };
+    runtime_manager
+        .update(cx, |runtime_manager, cx| {
+            runtime_manager.restart(editor.entity_id(), cx)
+        })
+        .detach_and_log_err(cx);
+}
+
+pub fn shutdown(workspace: &mut Workspace, _: &Shutdown, cx: &mut ViewContext<Workspace>) {
+    let Some((editor, runtime_manager)) = active_editor_and_runtime_manager(workspace, cx) else {
+        return;
+    };
+    runtime_manager
+        .update(cx, |runtime_manager, cx| {
+            runtime_manager.shutdown(editor.entity_id(), cx)
+        })
+        .detach();
+}
+
+pub fn clear_outputs(
+    workspace: &mut Workspace,
+    _: &ClearOutputs,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some((editor, runtime_manager)) = active_editor_and_runtime_manager(workspace, cx) else {
+        return;
+    };
+    runtime_manager.update(cx, |runtime_manager, cx| {
+        runtime_manager.clear_outputs(&editor, cx)
+    });
+}
+
 fn create_output_area_render(execution_view: View<ExecutionView>) -> RenderBlock {
     let render = move |cx: &mut BlockContext| {
         let execution_view = execution_view.clone();
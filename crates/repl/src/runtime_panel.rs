@@ -0,0 +1,371 @@
+use crate::{
+    runtime_settings::{JupyterSettings, RuntimesDockPosition},
+    Kernel, RuntimeManager, ToggleFocus,
+};
+use editor::Editor;
+use gpui::{
+    px, Action, AppContext, AsyncWindowContext, EntityId, EventEmitter, FocusHandle, FocusableView,
+    Pixels, Subscription, View, WeakView,
+};
+use project::Fs;
+use runtimelib::ExecutionState;
+use settings::{Settings as _, SettingsStore};
+use std::sync::Arc;
+use ui::{prelude::*, Tooltip};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+/// Lists the kernels launched from the workspace's editors, along with the runtimes
+/// that are available to launch.
+pub struct RuntimePanel {
+    fs: Arc<dyn Fs>,
+    workspace: WeakView<Workspace>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    runtime_manager_subscription: Option<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl RuntimePanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<View<Self>> {
+        workspace.update(&mut cx, |workspace, cx| {
+            let fs = workspace.app_state().fs.clone();
+            let workspace = workspace.weak_handle();
+            cx.new_view(|cx| {
+                let subscriptions = vec![cx.observe_global::<SettingsStore>(|this, cx| {
+                    this.observe_runtime_manager(cx);
+                    cx.notify();
+                })];
+                let mut panel = Self {
+                    fs,
+                    workspace,
+                    focus_handle: cx.focus_handle(),
+                    width: None,
+                    runtime_manager_subscription: None,
+                    _subscriptions: subscriptions,
+                };
+                panel.observe_runtime_manager(cx);
+                panel
+            })
+        })
+    }
+
+    /// The runtime manager comes and goes as Jupyter support is toggled in the settings.
+    fn observe_runtime_manager(&mut self, cx: &mut ViewContext<Self>) {
+        self.runtime_manager_subscription = RuntimeManager::global(cx)
+            .map(|runtime_manager| cx.observe(&runtime_manager, |_, _, cx| cx.notify()));
+    }
+
+    fn activate_editor(&mut self, editor: View<Editor>, cx: &mut ViewContext<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.activate_item(&editor, cx);
+                editor.focus_handle(cx).focus(cx);
+            })
+            .ok();
+    }
+
+    fn render_kernel(&self, kernel: KernelEntry, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let entity_id = kernel.entity_id;
+        let editor = kernel.editor;
+
+        v_flex()
+            .id(("kernel", entity_id))
+            .p_2()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Indicator::dot().color(kernel.status_color))
+                            .child(Label::new(kernel.runtime_name)),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                IconButton::new(("interrupt", entity_id), IconName::Stop)
+                                    .icon_size(IconSize::Small)
+                                    .disabled(!kernel.is_running)
+                                    .tooltip(|cx| Tooltip::text("Interrupt", cx))
+                                    .on_click(cx.listener(move |_, _, cx| {
+                                        update_runtime_manager(cx, |runtime_manager, cx| {
+                                            runtime_manager.interrupt(entity_id, cx)
+                                        });
+                                    })),
+                            )
+                            .child(
+                                IconButton::new(("restart", entity_id), IconName::RotateCw)
+                                    .icon_size(IconSize::Small)
+                                    .disabled(!kernel.is_running)
+                                    .tooltip(|cx| Tooltip::text("Restart", cx))
+                                    .on_click(cx.listener(move |_, _, cx| {
+                                        if let Some(task) =
+                                            update_runtime_manager(cx, |runtime_manager, cx| {
+                                                runtime_manager.restart(entity_id, cx)
+                                            })
+                                        {
+                                            task.detach_and_log_err(cx);
+                                        }
+                                    })),
+                            )
+                            .child(
+                                IconButton::new(("shutdown", entity_id), IconName::XCircle)
+                                    .icon_size(IconSize::Small)
+                                    .disabled(!kernel.is_running)
+                                    .tooltip(|cx| Tooltip::text("Shut Down", cx))
+                                    .on_click(cx.listener(move |_, _, cx| {
+                                        if let Some(task) =
+                                            update_runtime_manager(cx, |runtime_manager, cx| {
+                                                runtime_manager.shutdown(entity_id, cx)
+                                            })
+                                        {
+                                            task.detach();
+                                        }
+                                    })),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(kernel.status)
+                            .size(LabelSize::Small)
+                            .color(kernel.status_color),
+                    )
+                    .child(
+                        Button::new(("editor", entity_id), kernel.editor_title)
+                            .label_size(LabelSize::Small)
+                            .disabled(editor.is_none())
+                            .on_click(cx.listener(move |this, _, cx| {
+                                if let Some(editor) = editor.clone() {
+                                    this.activate_editor(editor, cx);
+                                }
+                            })),
+                    ),
+            )
+    }
+}
+
+/// What the panel shows about a single kernel.
+struct KernelEntry {
+    entity_id: EntityId,
+    runtime_name: SharedString,
+    status: &'static str,
+    status_color: Color,
+    is_running: bool,
+    editor: Option<View<Editor>>,
+    editor_title: SharedString,
+}
+
+impl KernelEntry {
+    fn new(
+        entity_id: EntityId,
+        kernel: &Kernel,
+        runtime_manager: &RuntimeManager,
+        cx: &AppContext,
+    ) -> Self {
+        let (status, status_color) = match kernel {
+            Kernel::RunningKernel(kernel) => match kernel.execution_state {
+                ExecutionState::Idle => ("Idle", Color::Success),
+                ExecutionState::Busy => ("Busy", Color::Modified),
+            },
+            Kernel::StartingKernel(_) => ("Starting", Color::Muted),
+            Kernel::ShuttingDown => ("Shutting Down", Color::Muted),
+            Kernel::FailedLaunch => ("Failed to Launch", Color::Error),
+        };
+        let runtime_name = match kernel {
+            Kernel::RunningKernel(kernel) => kernel
+                .runtime_specification()
+                .display_name()
+                .to_string()
+                .into(),
+            _ => "Kernel".into(),
+        };
+        let editor = runtime_manager.editor_for_kernel(entity_id);
+        let editor_title = editor
+            .as_ref()
+            .map(|editor| {
+                editor
+                    .read(cx)
+                    .buffer()
+                    .read(cx)
+                    .title(cx)
+                    .to_string()
+                    .into()
+            })
+            .unwrap_or_else(|| "Closed editor".into());
+
+        Self {
+            entity_id,
+            runtime_name,
+            status,
+            status_color,
+            is_running: matches!(kernel, Kernel::RunningKernel(_)),
+            editor,
+            editor_title,
+        }
+    }
+}
+
+fn update_runtime_manager<R>(
+    cx: &mut WindowContext,
+    update: impl FnOnce(&mut RuntimeManager, &mut gpui::ModelContext<RuntimeManager>) -> R,
+) -> Option<R> {
+    let runtime_manager = RuntimeManager::global(cx)?;
+    Some(runtime_manager.update(cx, update))
+}
+
+impl EventEmitter<PanelEvent> for RuntimePanel {}
+
+impl FocusableView for RuntimePanel {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for RuntimePanel {
+    fn persistent_name() -> &'static str {
+        "RuntimePanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        match JupyterSettings::get_global(cx).dock {
+            RuntimesDockPosition::Left => DockPosition::Left,
+            RuntimesDockPosition::Right => DockPosition::Right,
+            RuntimesDockPosition::Bottom => DockPosition::Bottom,
+        }
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<JupyterSettings>(self.fs.clone(), cx, move |settings| {
+            let dock = match position {
+                DockPosition::Left => RuntimesDockPosition::Left,
+                DockPosition::Right => RuntimesDockPosition::Right,
+                DockPosition::Bottom => RuntimesDockPosition::Bottom,
+            };
+            settings.dock = Some(dock);
+        });
+    }
+
+    fn size(&self, _: &WindowContext) -> Pixels {
+        self.width.unwrap_or(px(360.))
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        JupyterSettings::get_global(cx)
+            .enabled
+            .then_some(IconName::Code)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Runtime Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
+
+impl Render for RuntimePanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let panel = v_flex()
+            .key_context("RuntimePanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(
+                h_flex()
+                    .p_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Kernels")),
+            );
+
+        let Some(runtime_manager) = RuntimeManager::global(cx) else {
+            return panel.child(
+                div().p_2().child(
+                    Label::new("Enable Jupyter in your settings to run code in kernels.")
+                        .color(Color::Muted),
+                ),
+            );
+        };
+
+        let runtime_manager = runtime_manager.read(cx);
+        let mut kernel_entries = runtime_manager
+            .kernels()
+            .map(|(entity_id, kernel)| KernelEntry::new(entity_id, kernel, runtime_manager, cx))
+            .collect::<Vec<_>>();
+        kernel_entries.sort_by_key(|kernel| kernel.entity_id);
+
+        let available_runtimes = runtime_manager
+            .runtime_specifications()
+            .iter()
+            .map(|runtime_specification| {
+                h_flex()
+                    .px_2()
+                    .gap_2()
+                    .child(Label::new(runtime_specification.display_name().to_string()))
+                    .child(
+                        Label::new(runtime_specification.kernelspec.language.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .into_any_element()
+            })
+            .collect::<Vec<_>>();
+
+        let kernels = kernel_entries
+            .into_iter()
+            .map(|kernel| self.render_kernel(kernel, cx).into_any_element())
+            .collect::<Vec<_>>();
+
+        panel
+            .child(
+                v_flex()
+                    .id("kernels")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .when(kernels.is_empty(), |this| {
+                        this.child(
+                            div().p_2().child(
+                                Label::new("Run code from an editor to start a kernel.")
+                                    .color(Color::Muted),
+                            ),
+                        )
+                    })
+                    .children(kernels),
+            )
+            .child(
+                v_flex()
+                    .py_2()
+                    .gap_1()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        div()
+                            .px_2()
+                            .child(Label::new("Available Kernels").size(LabelSize::Small)),
+                    )
+                    .children(available_runtimes),
+            )
+    }
+}
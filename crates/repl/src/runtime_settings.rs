@@ -22,11 +22,11 @@ pub struct JupyterSettingsContent {
     /// Whether the Runtimes feature is enabled.
     ///
     /// Default: `false`
-    enabled: Option<bool>,
+    pub enabled: Option<bool>,
     /// Where to dock the runtimes panel.
     ///
     /// Default: `right`
-    dock: Option<RuntimesDockPosition>,
+    pub dock: Option<RuntimesDockPosition>,
 }
 
 impl Default for JupyterSettingsContent {
@@ -2,9 +2,11 @@ use anyhow::{Context as _, Result};
 use collections::HashMap;
 use futures::lock::Mutex;
 use futures::{channel::mpsc, SinkExt as _, StreamExt as _};
-use gpui::{AsyncAppContext, EntityId};
+use gpui::{AsyncAppContext, EntityId, Task};
 use project::Fs;
-use runtimelib::{dirs, ConnectionInfo, JupyterKernelspec, JupyterMessage, JupyterMessageContent};
+use runtimelib::{
+    dirs, ConnectionInfo, ExecutionState, JupyterKernelspec, JupyterMessage, JupyterMessageContent,
+};
 use smol::{net::TcpListener, process::Command};
 use std::fmt::Debug;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
@@ -24,6 +26,10 @@ pub struct RuntimeSpecification {
 }
 
 impl RuntimeSpecification {
+    pub fn display_name(&self) -> &str {
+        &self.kernelspec.display_name
+    }
+
     #[must_use]
     fn command(&self, connection_path: &PathBuf) -> Result<Command> {
         let argv = &self.kernelspec.argv;
@@ -77,11 +83,17 @@ async fn peek_ports(ip: IpAddr) -> anyhow::Result<[u16; 5]> {
 
 #[derive(Debug)]
 pub struct RunningKernel {
-    #[allow(unused)]
     runtime: RuntimeSpecification,
     #[allow(unused)]
     process: smol::process::Child,
     pub request_tx: mpsc::UnboundedSender<Request>,
+    /// Requests sent here go over the control channel, which the kernel services even
+    /// while the shell channel is busy executing code.
+    pub control_tx: mpsc::UnboundedSender<Request>,
+    pub execution_state: ExecutionState,
+    _iopub_task: Task<anyhow::Result<()>>,
+    _shell_task: Task<anyhow::Result<()>>,
+    _control_task: Task<anyhow::Result<()>>,
 }
 
 impl RunningKernel {
@@ -108,7 +120,7 @@ impl RunningKernel {
         };
 
         let connection_path = dirs::runtime_dir().join(format!("kernel-zed-{}.json", entity_id));
-        let content = serde_json::to_string(&connection_info)?;
+        let content = serde_json::to_vec(&connection_info)?;
         // write out file to disk for kernel
         fs.atomic_write(connection_path.clone(), content).await?;
 
@@ -122,6 +134,7 @@ impl RunningKernel {
 
         let mut iopub = connection_info.create_client_iopub_connection("").await?;
         let mut shell = connection_info.create_client_shell_connection().await?;
+        let mut control = connection_info.create_client_control_connection().await?;
 
         // Spawn a background task to handle incoming messages from the kernel as well
         // as outgoing messages to the kernel
@@ -131,68 +144,89 @@ impl RunningKernel {
         > = Default::default();
 
         let (request_tx, mut request_rx) = mpsc::unbounded::<Request>();
+        let (control_tx, mut control_rx) = mpsc::unbounded::<Request>();
 
-        cx.background_executor()
-            .spawn({
-                let child_messages = child_messages.clone();
+        let iopub_task = cx.background_executor().spawn({
+            let child_messages = child_messages.clone();
 
-                async move {
-                    let child_messages = child_messages.clone();
-                    while let Ok(message) = iopub.read().await {
-                        if let Some(parent_header) = message.parent_header {
-                            let child_messages = child_messages.lock().await;
+            async move {
+                let child_messages = child_messages.clone();
+                while let Ok(message) = iopub.read().await {
+                    if let Some(parent_header) = message.parent_header {
+                        let child_messages = child_messages.lock().await;
 
-                            let sender = child_messages.get(&parent_header.msg_id);
+                        let sender = child_messages.get(&parent_header.msg_id);
 
-                            match sender {
-                                Some(mut sender) => {
-                                    sender.send(message.content).await?;
-                                }
-                                None => {}
+                        match sender {
+                            Some(mut sender) => {
+                                sender.send(message.content).await?;
                             }
+                            None => {}
                         }
                     }
-
-                    anyhow::Ok(())
                 }
-            })
-            .detach();
 
-        cx.background_executor()
-            .spawn({
-                let child_messages = child_messages.clone();
-                async move {
-                    while let Some(request) = request_rx.next().await {
-                        let rx = request.responses_rx.clone();
+                anyhow::Ok(())
+            }
+        });
 
-                        let request: JupyterMessage = request.request.into();
-                        let msg_id = request.header.msg_id.clone();
+        let shell_task = cx.background_executor().spawn({
+            let child_messages = child_messages.clone();
+            async move {
+                while let Some(request) = request_rx.next().await {
+                    let rx = request.responses_rx.clone();
 
-                        let mut sender = rx.clone();
+                    let request: JupyterMessage = request.request.into();
+                    let msg_id = request.header.msg_id.clone();
 
-                        child_messages
-                            .lock()
-                            .await
-                            .insert(msg_id.clone(), sender.clone());
+                    let mut sender = rx.clone();
 
-                        shell.send(request).await?;
+                    child_messages
+                        .lock()
+                        .await
+                        .insert(msg_id.clone(), sender.clone());
 
-                        let response = shell.read().await?;
+                    shell.send(request).await?;
 
-                        sender.send(response.content).await?;
-                    }
+                    let response = shell.read().await?;
 
-                    anyhow::Ok(())
+                    sender.send(response.content).await?;
                 }
-            })
-            .detach();
+
+                anyhow::Ok(())
+            }
+        });
+
+        let control_task = cx.background_executor().spawn(async move {
+            while let Some(request) = control_rx.next().await {
+                let mut sender = request.responses_rx.clone();
+                let request: JupyterMessage = request.request.into();
+
+                control.send(request).await?;
+                let response = control.read().await?;
+
+                // Interrupts are fire-and-forget, so nobody may be listening for the reply.
+                sender.send(response.content).await.ok();
+            }
+
+            anyhow::Ok(())
+        });
 
         Ok(Self {
             runtime,
             process,
             request_tx,
+            control_tx,
+            execution_state: ExecutionState::Idle,
+            _iopub_task: iopub_task,
+            _shell_task: shell_task,
+            _control_task: control_task,
         })
     }
+
+    pub fn runtime_specification(&self) -> &RuntimeSpecification {
+        &self.runtime
+    }
 }
 
 async fn read_kernelspec_at(
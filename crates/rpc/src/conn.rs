@@ -1,5 +1,15 @@
+use anyhow::anyhow;
 use async_tungstenite::tungstenite::Message as WebSocketMessage;
-use futures::{SinkExt as _, StreamExt as _};
+use futures::{AsyncReadExt as _, AsyncWriteExt as _, SinkExt as _, StreamExt as _};
+
+const FRAME_BINARY: u8 = 0;
+const FRAME_PING: u8 = 1;
+const FRAME_PONG: u8 = 2;
+const FRAME_CLOSE: u8 = 3;
+
+/// The largest payload a framed connection will read or write, matching the default
+/// maximum message size of a WebSocket connection.
+const MAX_FRAME_LEN: usize = 64 << 20;
 
 pub struct Connection {
     pub(crate) tx:
@@ -28,6 +38,70 @@ impl Connection {
         }
     }
 
+    /// Carries messages over a pair of byte streams, such as the standard input and output
+    /// of an `ssh` process, for peers that aren't connected through a WebSocket.
+    ///
+    /// Each message is written as a one-byte kind, its length as a little-endian `u32`,
+    /// and its payload. The connection closes when the reader reaches the end of its stream.
+    pub fn framed<R, W>(reader: R, writer: W) -> Self
+    where
+        R: 'static + Send + Unpin + futures::AsyncRead,
+        W: 'static + Send + Unpin + futures::AsyncWrite,
+    {
+        let tx = futures::sink::unfold(writer, |mut writer, message| async move {
+            let (kind, payload) = match message {
+                WebSocketMessage::Binary(payload) => (FRAME_BINARY, payload),
+                WebSocketMessage::Ping(payload) => (FRAME_PING, payload),
+                WebSocketMessage::Pong(payload) => (FRAME_PONG, payload),
+                WebSocketMessage::Close(_) => (FRAME_CLOSE, Vec::new()),
+                message => return Err(anyhow!("cannot frame message {message:?}")),
+            };
+            if payload.len() > MAX_FRAME_LEN {
+                return Err(anyhow!("frame of {} bytes is too large", payload.len()));
+            }
+            let len = payload.len() as u32;
+            let mut frame = Vec::with_capacity(5 + payload.len());
+            frame.push(kind);
+            frame.extend_from_slice(&len.to_le_bytes());
+            frame.extend_from_slice(&payload);
+            writer.write_all(&frame).await?;
+            writer.flush().await?;
+            Ok(writer)
+        });
+
+        let rx = futures::stream::unfold(Some(reader), |reader| async move {
+            let mut reader = reader?;
+            match read_frame(&mut reader).await {
+                Ok(Some(message)) => Some((Ok(message), Some(reader))),
+                Ok(None) => None,
+                Err(error) => Some((Err(error), None)),
+            }
+        });
+
+        Self {
+            tx: Box::new(Box::pin(tx)),
+            rx: Box::new(Box::pin(rx)),
+        }
+    }
+
+    /// Connects two peers running in the same process.
+    pub fn in_process() -> (Self, Self) {
+        use futures::channel::mpsc;
+
+        let (a_tx, a_rx) = mpsc::unbounded::<WebSocketMessage>();
+        let (b_tx, b_rx) = mpsc::unbounded::<WebSocketMessage>();
+        (
+            Self {
+                tx: Box::new(a_tx.sink_map_err(|error| anyhow!(error))),
+                rx: Box::new(b_rx.map(Ok)),
+            },
+            Self {
+                tx: Box::new(b_tx.sink_map_err(|error| anyhow!(error))),
+                rx: Box::new(a_rx.map(Ok)),
+            },
+        )
+    }
+
     pub async fn send(&mut self, message: WebSocketMessage) -> Result<(), anyhow::Error> {
         self.tx.send(message).await
     }
@@ -58,7 +132,6 @@ impl Connection {
             Box<dyn Send + Unpin + futures::Sink<WebSocketMessage, Error = anyhow::Error>>,
             Box<dyn Send + Unpin + futures::Stream<Item = Result<WebSocketMessage, anyhow::Error>>>,
         ) {
-            use anyhow::anyhow;
             use futures::channel::mpsc;
             use std::io::{Error, ErrorKind};
 
@@ -105,3 +178,56 @@ impl Connection {
         }
     }
 }
+
+async fn read_frame(
+    reader: &mut (impl futures::AsyncRead + Unpin),
+) -> anyhow::Result<Option<WebSocketMessage>> {
+    let mut kind = [0; 1];
+    if reader.read(&mut kind).await? == 0 {
+        return Ok(None);
+    }
+    let mut len = [0; 4];
+    reader.read_exact(&mut len).await?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(anyhow!("frame of {len} bytes is too large"));
+    }
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload).await?;
+    match kind[0] {
+        FRAME_BINARY => Ok(Some(WebSocketMessage::Binary(payload))),
+        FRAME_PING => Ok(Some(WebSocketMessage::Ping(payload))),
+        FRAME_PONG => Ok(Some(WebSocketMessage::Pong(payload))),
+        FRAME_CLOSE => Ok(Some(WebSocketMessage::Close(None))),
+        kind => Err(anyhow!("unknown frame kind {kind}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_framed_connection_rejects_oversized_frames() {
+        let mut frame = vec![FRAME_BINARY];
+        frame.extend_from_slice(&(MAX_FRAME_LEN as u32 + 1).to_le_bytes());
+        let mut connection =
+            Connection::framed(futures::io::Cursor::new(frame), futures::io::sink());
+        assert!(connection.rx.next().await.unwrap().is_err());
+        assert!(connection.rx.next().await.is_none());
+
+        let mut frame = vec![FRAME_BINARY];
+        frame.extend_from_slice(&3u32.to_le_bytes());
+        frame.extend_from_slice(b"abc");
+        let mut connection =
+            Connection::framed(futures::io::Cursor::new(frame), futures::io::sink());
+        assert_eq!(
+            connection.rx.next().await.unwrap().unwrap(),
+            WebSocketMessage::Binary(b"abc".to_vec())
+        );
+        assert!(connection
+            .send(WebSocketMessage::Binary(vec![0; MAX_FRAME_LEN + 1]))
+            .await
+            .is_err());
+    }
+}
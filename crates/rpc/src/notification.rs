@@ -38,6 +38,15 @@ pub enum Notification {
         sender_id: u64,
         channel_id: u64,
     },
+    BufferCommentMention {
+        #[serde(rename = "entity_id")]
+        comment_id: u64,
+        sender_id: u64,
+        project_id: u64,
+        worktree_id: u64,
+        path: String,
+        body: String,
+    },
 }
 
 impl Notification {
@@ -3,6 +3,7 @@ use crate::{
     SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex,
     ToggleReplace, ToggleWholeWord,
 };
+use anyhow::anyhow;
 use collections::{HashMap, HashSet};
 use editor::{
     actions::SelectAll,
@@ -19,6 +20,7 @@ use gpui::{
 };
 use menu::Confirm;
 use project::{search::SearchQuery, search_history::SearchHistoryCursor, Project, ProjectPath};
+use rpc::proto::{self, update_view, PeerId};
 use settings::Settings;
 use smol::stream::StreamExt;
 use std::{
@@ -32,12 +34,14 @@ use ui::{
     h_flex, prelude::*, v_flex, Icon, IconButton, IconName, Label, LabelCommon, LabelSize,
     Selectable, Tooltip,
 };
-use util::paths::PathMatcher;
+use util::{paths::PathMatcher, ResultExt};
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, ItemHandle, TabContentParams},
+    item::{
+        BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent, ItemHandle, TabContentParams,
+    },
     searchable::{Direction, SearchableItem, SearchableItemHandle},
     DeploySearch, ItemNavHistory, NewSearch, Pane, ToolbarItemEvent, ToolbarItemLocation,
-    ToolbarItemView, Workspace, WorkspaceId,
+    ToolbarItemView, ViewId, Workspace, WorkspaceId,
 };
 
 const MIN_INPUT_WIDTH_REMS: f32 = 15.;
@@ -55,6 +59,7 @@ impl Global for ActiveSettings {}
 
 pub fn init(cx: &mut AppContext) {
     cx.set_global(ActiveSettings::default());
+    workspace::register_followable_item::<ProjectSearchView>(cx);
     cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         register_workspace_action(workspace, move |search_bar, _: &FocusSearch, cx| {
             search_bar.focus_search(cx);
@@ -148,6 +153,10 @@ pub struct ProjectSearchView {
     excluded_files_editor: View<Editor>,
     filters_enabled: bool,
     replace_enabled: bool,
+    remote_id: Option<ViewId>,
+    /// The latest selections and scroll position received from the leader being followed,
+    /// re-applied as results for the leader's query arrive.
+    leader_state: Option<update_view::Editor>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -269,6 +278,7 @@ pub enum ViewEvent {
     Activate,
     EditorEvent(editor::EditorEvent),
     Dismiss,
+    SearchStarted,
 }
 
 impl EventEmitter<ViewEvent> for ProjectSearchView {}
@@ -401,6 +411,10 @@ impl Item for ProjectSearchView {
         Some("project search")
     }
 
+    fn display_name(&self) -> Option<&'static str> {
+        Some("project search")
+    }
+
     fn for_each_project_item(
         &self,
         cx: &AppContext,
@@ -719,6 +733,8 @@ impl ProjectSearchView {
             excluded_files_editor,
             filters_enabled,
             replace_enabled: false,
+            remote_id: None,
+            leader_state: None,
             _subscriptions: subscriptions,
         };
         this.model_changed(cx);
@@ -853,7 +869,75 @@ impl ProjectSearchView {
     fn search(&mut self, cx: &mut ViewContext<Self>) {
         if let Some(query) = self.build_search_query(cx) {
             self.model.update(cx, |model, cx| model.search(query, cx));
+            cx.emit(ViewEvent::SearchStarted);
+        }
+    }
+
+    fn query_to_proto(&self, cx: &AppContext) -> Option<proto::SearchProject> {
+        let model = self.model.read(cx);
+        let project_id = model.project.read(cx).remote_id()?;
+        Some(model.active_query.as_ref()?.to_proto(project_id))
+    }
+
+    /// Runs the leader's query, unless it's the one whose results are already shown.
+    fn follow_query(&mut self, message: proto::SearchProject, cx: &mut ViewContext<Self>) {
+        if self.query_to_proto(cx).as_ref() == Some(&message) {
+            return;
+        }
+        let Some(query) = SearchQuery::from_proto(message).log_err() else {
+            return;
+        };
+
+        self.query_editor
+            .update(cx, |editor, cx| editor.set_text(query.as_str(), cx));
+        let files_to_include = query.files_to_include().sources().join(",");
+        let files_to_exclude = query.files_to_exclude().sources().join(",");
+        self.filters_enabled = !files_to_include.is_empty() || !files_to_exclude.is_empty();
+        self.included_files_editor
+            .update(cx, |editor, cx| editor.set_text(files_to_include, cx));
+        self.excluded_files_editor
+            .update(cx, |editor, cx| editor.set_text(files_to_exclude, cx));
+        self.search_options = SearchOptions::from_query(&query);
+        if let Some(leader_state) = self.leader_state.as_mut() {
+            *leader_state = Default::default();
         }
+        self.model.update(cx, |model, cx| model.search(query, cx));
+    }
+
+    /// Re-applies the leader's selections and scroll position, whose anchors only resolve once
+    /// this view has received the results containing them.
+    fn apply_leader_state(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(state) = self.leader_state.clone() else {
+            return;
+        };
+        let project = self.model.read(cx).project.clone();
+        let editor = self.results_editor.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            let selections = update_view::Editor {
+                selections: state.selections,
+                pending_selection: state.pending_selection,
+                ..Default::default()
+            };
+            let scroll = update_view::Editor {
+                scroll_top_anchor: state.scroll_top_anchor,
+                scroll_x: state.scroll_x,
+                scroll_y: state.scroll_y,
+                ..Default::default()
+            };
+            for message in [selections, scroll] {
+                editor
+                    .update(&mut cx, |editor, cx| {
+                        editor.apply_update_proto(
+                            &project,
+                            update_view::Variant::Editor(message),
+                            cx,
+                        )
+                    })?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
     }
 
     fn build_search_query(&mut self, cx: &mut ViewContext<Self>) -> Option<SearchQuery> {
@@ -1018,8 +1102,9 @@ impl ProjectSearchView {
             self.update_match_index(cx);
             let prev_search_id = mem::replace(&mut self.search_id, self.model.read(cx).search_id);
             let is_new_search = self.search_id != prev_search_id;
+            let is_following = self.leader_state.is_some();
             self.results_editor.update(cx, |editor, cx| {
-                if is_new_search {
+                if is_new_search && !is_following {
                     let range_to_select = match_ranges
                         .first()
                         .map(|range| editor.range_for_match(range));
@@ -1037,6 +1122,7 @@ impl ProjectSearchView {
             if is_new_search && self.query_editor.focus_handle(cx).is_focused(cx) {
                 self.focus_results_editor(cx);
             }
+            self.apply_leader_state(cx);
         }
 
         cx.emit(ViewEvent::UpdateTab);
@@ -1082,6 +1168,179 @@ impl ProjectSearchView {
     }
 }
 
+impl FollowableItem for ProjectSearchView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        let Some(proto::view::Variant::Editor(mut results_editor)) =
+            self.results_editor.read(cx).to_state_proto(cx)
+        else {
+            return None;
+        };
+        // Followers run the query themselves rather than mirroring the leader's results.
+        results_editor.excerpts.clear();
+
+        Some(proto::view::Variant::ProjectSearch(
+            proto::view::ProjectSearch {
+                query: self.query_to_proto(cx),
+                results_editor: Some(results_editor),
+            },
+        ))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<anyhow::Result<View<Self>>>> {
+        let Some(proto::view::Variant::ProjectSearch(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::ProjectSearch(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let existing = pane
+            .read(cx)
+            .items_of_type::<Self>()
+            .find(|search_view| search_view.read(cx).remote_id == Some(remote_id));
+        let search_view = existing.unwrap_or_else(|| {
+            let project = workspace.read(cx).project().clone();
+            let model = cx.new_model(|cx| ProjectSearch::new(project, cx));
+            cx.new_view(|cx| Self::new(model, cx, None))
+        });
+        search_view.update(cx, |this, cx| {
+            this.remote_id = Some(remote_id);
+            if let Some(query) = state.query {
+                this.follow_query(query, cx);
+            }
+            let results_editor = state.results_editor.unwrap_or_default();
+            this.leader_state = Some(update_view::Editor {
+                selections: results_editor.selections,
+                pending_selection: results_editor.pending_selection,
+                scroll_top_anchor: results_editor.scroll_top_anchor,
+                scroll_x: results_editor.scroll_x,
+                scroll_y: results_editor.scroll_y,
+                ..Default::default()
+            });
+            this.apply_leader_state(cx);
+        });
+
+        Some(Task::ready(Ok(search_view)))
+    }
+
+    fn to_follow_event(event: &ViewEvent) -> Option<FollowEvent> {
+        match event {
+            // Query editors are edited on behalf of the leader, so only moving around
+            // the results takes a follower out of follow mode.
+            ViewEvent::EditorEvent(
+                event @ (EditorEvent::SelectionsChanged { .. }
+                | EditorEvent::ScrollPositionChanged { .. }),
+            ) => Editor::to_follow_event(event),
+            _ => None,
+        }
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &ViewEvent,
+        update: &mut Option<update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        let update =
+            update.get_or_insert_with(|| update_view::Variant::ProjectSearch(Default::default()));
+        let update_view::Variant::ProjectSearch(update) = update else {
+            return false;
+        };
+
+        match event {
+            ViewEvent::SearchStarted => {
+                update.query = self.query_to_proto(cx);
+                update.query.is_some()
+            }
+            ViewEvent::EditorEvent(
+                event @ (EditorEvent::SelectionsChanged { .. }
+                | EditorEvent::ScrollPositionChanged { .. }),
+            ) => {
+                let mut results_editor_update = update
+                    .results_editor
+                    .take()
+                    .map(update_view::Variant::Editor);
+                let changed = self.results_editor.read(cx).add_event_to_update_proto(
+                    event,
+                    &mut results_editor_update,
+                    cx,
+                );
+                if let Some(update_view::Variant::Editor(results_editor_update)) =
+                    results_editor_update
+                {
+                    update.results_editor = Some(results_editor_update);
+                }
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        project: &Model<Project>,
+        message: update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let update_view::Variant::ProjectSearch(message) = message else {
+            return Task::ready(Err(anyhow!(
+                "received a non-project-search update for a project search"
+            )));
+        };
+
+        if let Some(query) = message.query {
+            self.follow_query(query, cx);
+        }
+        let Some(results_editor) = message.results_editor else {
+            return Task::ready(Ok(()));
+        };
+
+        let leader_state = self.leader_state.get_or_insert_with(Default::default);
+        if !results_editor.selections.is_empty() || results_editor.pending_selection.is_some() {
+            leader_state
+                .selections
+                .clone_from(&results_editor.selections);
+            leader_state
+                .pending_selection
+                .clone_from(&results_editor.pending_selection);
+        }
+        if results_editor.scroll_top_anchor.is_some() {
+            leader_state
+                .scroll_top_anchor
+                .clone_from(&results_editor.scroll_top_anchor);
+            leader_state.scroll_x = results_editor.scroll_x;
+            leader_state.scroll_y = results_editor.scroll_y;
+        }
+
+        self.results_editor.update(cx, |editor, cx| {
+            editor.apply_update_proto(project, update_view::Variant::Editor(results_editor), cx)
+        })
+    }
+
+    fn is_project_item(&self, _cx: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, leader_peer_id: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        if leader_peer_id.is_none() {
+            self.leader_state = None;
+        }
+        self.results_editor.update(cx, |editor, cx| {
+            editor.set_leader_peer_id(leader_peer_id, cx)
+        })
+    }
+}
+
 impl ProjectSearchBar {
     pub fn new() -> Self {
         Self {
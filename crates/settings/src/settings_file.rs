@@ -121,11 +121,11 @@ pub fn update_settings_file<T: Settings>(
                 format!("Failed to canonicalize settings path {:?}", initial_path)
             })?;
 
-            fs.atomic_write(resolved_path.clone(), new_text)
+            fs.atomic_write(resolved_path.clone(), new_text.into_bytes())
                 .await
                 .with_context(|| format!("Failed to write settings to file {:?}", resolved_path))?;
         } else {
-            fs.atomic_write(initial_path.to_path_buf(), new_text)
+            fs.atomic_write(initial_path.to_path_buf(), new_text.into_bytes())
                 .await
                 .with_context(|| format!("Failed to write settings to file {:?}", initial_path))?;
         }
@@ -21,7 +21,7 @@ pub use vscode_format::VsCodeTaskFile;
 pub struct TaskId(pub String);
 
 /// TerminalWorkDir describes where a task should be run
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TerminalWorkDir {
     /// Local is on this machine
     Local(PathBuf),
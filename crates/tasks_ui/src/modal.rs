@@ -221,24 +221,25 @@ impl PickerDelegate for TasksModalDelegate {
 
                             let resolved_task =
                                 picker.delegate.project.update(cx, |project, cx| {
-                                    let ssh_connection_string = project.ssh_connection_string(cx);
-                                    if project.is_remote() && ssh_connection_string.is_none() {
+                                    let runs_remote_tasks =
+                                        project.ssh_connection_string(cx).is_some()
+                                            || project.runs_tasks_on_host();
+                                    if project.is_remote() && !runs_remote_tasks {
                                         Task::ready((Vec::new(), Vec::new()))
                                     } else {
                                         let remote_templates = if project.is_local() {
                                             None
                                         } else {
-                                            project
-                                                .remote_id()
-                                                .filter(|_| ssh_connection_string.is_some())
-                                                .map(|project_id| {
+                                            project.remote_id().filter(|_| runs_remote_tasks).map(
+                                                |project_id| {
                                                     project.query_remote_task_templates(
                                                         project_id,
                                                         worktree,
                                                         location.as_ref(),
                                                         cx,
                                                     )
-                                                })
+                                                },
+                                            )
                                         };
                                         project
                                             .task_inventory()
@@ -1,3 +1,4 @@
 pub mod colors;
 pub mod keys;
 pub mod mouse;
+pub mod screen;
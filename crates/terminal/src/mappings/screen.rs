@@ -0,0 +1,173 @@
+use std::fmt::Write as _;
+
+use alacritty_terminal::{
+    grid::Dimensions,
+    index::{Column, Line},
+    term::{
+        cell::{Cell, Flags},
+        TermMode,
+    },
+    vte::ansi::{Color as AnsiColor, NamedColor},
+    Term,
+};
+
+/// The private modes that change how input is encoded, or how the screen is displayed.
+const ENCODED_MODES: &[(TermMode, u16)] = &[
+    (TermMode::APP_CURSOR, 1),
+    (TermMode::SHOW_CURSOR, 25),
+    (TermMode::MOUSE_REPORT_CLICK, 1000),
+    (TermMode::MOUSE_DRAG, 1002),
+    (TermMode::MOUSE_MOTION, 1003),
+    (TermMode::UTF8_MOUSE, 1005),
+    (TermMode::SGR_MOUSE, 1006),
+    (TermMode::ALTERNATE_SCROLL, 1007),
+    (TermMode::BRACKETED_PASTE, 2004),
+];
+
+/// Encodes the visible screen of a terminal as escape sequences that redraw it from scratch,
+/// replacing whatever the receiving terminal displayed before.
+pub fn encode_screen<T>(term: &Term<T>) -> Vec<u8> {
+    let mode = *term.mode();
+    let mut output = String::new();
+
+    output.push_str(if mode.contains(TermMode::ALT_SCREEN) {
+        "\x1b[?1049h"
+    } else {
+        "\x1b[?1049l"
+    });
+    output.push_str(if mode.contains(TermMode::APP_KEYPAD) {
+        "\x1b="
+    } else {
+        "\x1b>"
+    });
+    // Hide the cursor while drawing, and clear the screen and the scrollback.
+    output.push_str("\x1b[?25l\x1b[0m\x1b[H\x1b[2J\x1b[3J");
+
+    let grid = term.grid();
+    let mut current_style = None;
+    for line in 0..term.screen_lines() {
+        if line > 0 {
+            output.push_str("\r\n");
+        }
+        let row = &grid[Line(line as i32)];
+        let len = (0..term.columns())
+            .rev()
+            .find(|column| !is_blank(&row[Column(*column)]))
+            .map_or(0, |column| column + 1);
+        for column in 0..len {
+            let cell = &row[Column(column)];
+            if cell
+                .flags
+                .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+            {
+                continue;
+            }
+
+            let style = (cell.fg, cell.bg, cell.flags & STYLE_FLAGS);
+            if current_style != Some(style) {
+                push_style(&mut output, cell);
+                current_style = Some(style);
+            }
+            output.push(cell.c);
+            if let Some(zerowidth) = cell.zerowidth() {
+                output.extend(zerowidth);
+            }
+        }
+    }
+    output.push_str("\x1b[0m");
+
+    let cursor = grid.cursor.point;
+    write!(
+        output,
+        "\x1b[{};{}H",
+        cursor.line.0 + 1,
+        cursor.column.0 + 1
+    )
+    .ok();
+    for (flag, code) in ENCODED_MODES {
+        let action = if mode.contains(*flag) { 'h' } else { 'l' };
+        write!(output, "\x1b[?{code}{action}").ok();
+    }
+
+    output.into_bytes()
+}
+
+const STYLE_FLAGS: Flags = Flags::BOLD
+    .union(Flags::DIM)
+    .union(Flags::ITALIC)
+    .union(Flags::ALL_UNDERLINES)
+    .union(Flags::INVERSE)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT);
+
+fn is_blank(cell: &Cell) -> bool {
+    cell.c == ' '
+        && cell.bg == AnsiColor::Named(NamedColor::Background)
+        && !cell
+            .flags
+            .intersects(Flags::ALL_UNDERLINES | Flags::INVERSE | Flags::STRIKEOUT)
+}
+
+fn push_style(output: &mut String, cell: &Cell) {
+    output.push_str("\x1b[0");
+    for (flag, code) in [
+        (Flags::BOLD, 1),
+        (Flags::DIM, 2),
+        (Flags::ITALIC, 3),
+        (Flags::ALL_UNDERLINES, 4),
+        (Flags::INVERSE, 7),
+        (Flags::HIDDEN, 8),
+        (Flags::STRIKEOUT, 9),
+    ] {
+        if cell.flags.intersects(flag) {
+            write!(output, ";{code}").ok();
+        }
+    }
+    push_color(output, cell.fg, true);
+    push_color(output, cell.bg, false);
+    output.push('m');
+}
+
+fn push_color(output: &mut String, color: AnsiColor, foreground: bool) {
+    let (base, bright_base, extended) = if foreground {
+        (30, 90, 38)
+    } else {
+        (40, 100, 48)
+    };
+    match color {
+        AnsiColor::Named(named) => match named_color_index(named) {
+            Some(index @ 0..=7) => write!(output, ";{}", base + index).ok(),
+            Some(index) => write!(output, ";{}", bright_base + index - 8).ok(),
+            // The receiving terminal's own foreground and background are used instead.
+            None => None,
+        },
+        AnsiColor::Indexed(index) => write!(output, ";{extended};5;{index}").ok(),
+        AnsiColor::Spec(rgb) => write!(output, ";{extended};2;{};{};{}", rgb.r, rgb.g, rgb.b).ok(),
+    };
+}
+
+fn named_color_index(color: NamedColor) -> Option<u8> {
+    Some(match color {
+        NamedColor::Black | NamedColor::DimBlack => 0,
+        NamedColor::Red | NamedColor::DimRed => 1,
+        NamedColor::Green | NamedColor::DimGreen => 2,
+        NamedColor::Yellow | NamedColor::DimYellow => 3,
+        NamedColor::Blue | NamedColor::DimBlue => 4,
+        NamedColor::Magenta | NamedColor::DimMagenta => 5,
+        NamedColor::Cyan | NamedColor::DimCyan => 6,
+        NamedColor::White | NamedColor::DimWhite => 7,
+        NamedColor::BrightBlack => 8,
+        NamedColor::BrightRed => 9,
+        NamedColor::BrightGreen => 10,
+        NamedColor::BrightYellow => 11,
+        NamedColor::BrightBlue => 12,
+        NamedColor::BrightMagenta => 13,
+        NamedColor::BrightCyan => 14,
+        NamedColor::BrightWhite => 15,
+        NamedColor::Foreground
+        | NamedColor::Background
+        | NamedColor::Cursor
+        | NamedColor::BrightForeground
+        | NamedColor::DimForeground => return None,
+    })
+}
@@ -18,7 +18,7 @@ use alacritty_terminal::{
         Config, RenderableCursor, TermMode,
     },
     tty::{self, setup_env},
-    vte::ansi::{ClearMode, Handler, NamedPrivateMode, PrivateMode, Rgb},
+    vte::ansi::{ClearMode, Handler, NamedPrivateMode, PrivateMode, Processor, Rgb},
     Term,
 };
 use anyhow::{bail, Result};
@@ -38,7 +38,7 @@ use futures::StreamExt;
 use pty_info::PtyProcessInfo;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use smol::channel::{Receiver, Sender};
+use smol::channel::{bounded, Receiver, Sender};
 use task::TaskId;
 use terminal_settings::{AlternateScroll, Shell, TerminalBlink, TerminalSettings};
 use theme::{ActiveTheme, Theme};
@@ -366,24 +366,7 @@ impl TerminalBuilder {
         //Spawn a task so the Alacritty EventLoop can communicate with us in a view context
         //TODO: Remove with a bounded sender which can be dispatched on &self
         let (events_tx, events_rx) = unbounded();
-        //Set up the terminal...
-        let mut term = Term::new(
-            config,
-            &TerminalSize::default(),
-            ZedListener(events_tx.clone()),
-        );
-
-        //Start off blinking if we need to
-        if let Some(TerminalBlink::On) = blink_settings {
-            term.set_private_mode(PrivateMode::Named(NamedPrivateMode::BlinkingCursor));
-        }
-
-        //Alacritty defaults to alternate scrolling being on, so we just need to turn it off.
-        if let AlternateScroll::Off = alternate_scroll {
-            term.unset_private_mode(PrivateMode::Named(NamedPrivateMode::AlternateScroll));
-        }
-
-        let term = Arc::new(FairMutex::new(term));
+        let term = new_term(config, blink_settings, alternate_scroll, events_tx.clone());
 
         //Setup the pty...
         let pty = match tty::new(
@@ -416,30 +399,15 @@ impl TerminalBuilder {
         let pty_tx = event_loop.channel();
         let _io_thread = event_loop.spawn(); // DANGER
 
-        let url_regex = RegexSearch::new(r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#).unwrap();
-        let word_regex = RegexSearch::new(r#"[\$\+\w.\[\]:/\\@\-~]+"#).unwrap();
-
-        let terminal = Terminal {
+        let terminal = Terminal::new(
+            term,
+            TerminalType::Pty {
+                pty_tx: Notifier(pty_tx),
+                info: pty_info,
+            },
             task,
-            pty_tx: Notifier(pty_tx),
             completion_tx,
-            term,
-            events: VecDeque::with_capacity(10), //Should never get this high.
-            last_content: Default::default(),
-            last_mouse: None,
-            matches: Vec::new(),
-            selection_head: None,
-            pty_info,
-            breadcrumb_text: String::new(),
-            scroll_px: px(0.),
-            last_mouse_position: None,
-            next_link_id: 0,
-            selection_phase: SelectionPhase::Ended,
-            secondary_pressed: false,
-            hovered_word: false,
-            url_regex,
-            word_regex,
-        };
+        );
 
         Ok(TerminalBuilder {
             terminal,
@@ -447,6 +415,40 @@ impl TerminalBuilder {
         })
     }
 
+    /// Creates a terminal that doesn't run a process, and only displays the output written
+    /// to it with [`Terminal::write_output`]. Its input is sent to `input_tx`, or dropped
+    /// if there is none.
+    pub fn new_display_only(
+        title: String,
+        blink_settings: Option<TerminalBlink>,
+        alternate_scroll: AlternateScroll,
+        input_tx: Option<UnboundedSender<Vec<u8>>>,
+    ) -> TerminalBuilder {
+        let (events_tx, events_rx) = unbounded();
+        let term = new_term(
+            Config::default(),
+            blink_settings,
+            alternate_scroll,
+            events_tx,
+        );
+        let (completion_tx, _) = bounded(1);
+        let terminal = Terminal::new(
+            term,
+            TerminalType::DisplayOnly {
+                processor: Processor::new(),
+                input_tx,
+                title,
+            },
+            None,
+            completion_tx,
+        );
+
+        TerminalBuilder {
+            terminal,
+            events_rx,
+        }
+    }
+
     pub fn subscribe(mut self, cx: &mut ModelContext<Terminal>) -> Terminal {
         //Event loop
         cx.spawn(|terminal, mut cx| async move {
@@ -510,6 +512,28 @@ impl TerminalBuilder {
     }
 }
 
+fn new_term(
+    config: Config,
+    blink_settings: Option<TerminalBlink>,
+    alternate_scroll: AlternateScroll,
+    events_tx: UnboundedSender<AlacTermEvent>,
+) -> Arc<FairMutex<Term<ZedListener>>> {
+    //Set up the terminal...
+    let mut term = Term::new(config, &TerminalSize::default(), ZedListener(events_tx));
+
+    //Start off blinking if we need to
+    if let Some(TerminalBlink::On) = blink_settings {
+        term.set_private_mode(PrivateMode::Named(NamedPrivateMode::BlinkingCursor));
+    }
+
+    //Alacritty defaults to alternate scrolling being on, so we just need to turn it off.
+    if let AlternateScroll::Off = alternate_scroll {
+        term.unset_private_mode(PrivateMode::Named(NamedPrivateMode::AlternateScroll));
+    }
+
+    Arc::new(FairMutex::new(term))
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct IndexedCell {
     pub point: AlacPoint,
@@ -571,8 +595,24 @@ pub enum SelectionPhase {
     Ended,
 }
 
+/// Where a terminal's output comes from, and where its input goes.
+enum TerminalType {
+    /// A process running in a local PTY.
+    Pty {
+        pty_tx: Notifier,
+        info: PtyProcessInfo,
+    },
+    /// A terminal that only displays the output written to it, such as a terminal shared
+    /// by a collaborator.
+    DisplayOnly {
+        processor: Processor,
+        input_tx: Option<UnboundedSender<Vec<u8>>>,
+        title: String,
+    },
+}
+
 pub struct Terminal {
-    pty_tx: Notifier,
+    terminal_type: TerminalType,
     completion_tx: Sender<()>,
     term: Arc<FairMutex<Term<ZedListener>>>,
     events: VecDeque<InternalEvent>,
@@ -584,7 +624,6 @@ pub struct Terminal {
     pub last_content: TerminalContent,
     pub selection_head: Option<AlacPoint>,
     pub breadcrumb_text: String,
-    pub pty_info: PtyProcessInfo,
     scroll_px: Pixels,
     next_link_id: usize,
     selection_phase: SelectionPhase,
@@ -631,6 +670,37 @@ impl TaskStatus {
 }
 
 impl Terminal {
+    fn new(
+        term: Arc<FairMutex<Term<ZedListener>>>,
+        terminal_type: TerminalType,
+        task: Option<TaskState>,
+        completion_tx: Sender<()>,
+    ) -> Self {
+        let url_regex = RegexSearch::new(r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#).unwrap();
+        let word_regex = RegexSearch::new(r#"[\$\+\w.\[\]:/\\@\-~]+"#).unwrap();
+
+        Terminal {
+            task,
+            terminal_type,
+            completion_tx,
+            term,
+            events: VecDeque::with_capacity(10), //Should never get this high.
+            last_content: Default::default(),
+            last_mouse: None,
+            matches: Vec::new(),
+            selection_head: None,
+            breadcrumb_text: String::new(),
+            scroll_px: px(0.),
+            last_mouse_position: None,
+            next_link_id: 0,
+            selection_phase: SelectionPhase::Ended,
+            secondary_pressed: false,
+            hovered_word: false,
+            url_regex,
+            word_regex,
+        }
+    }
+
     fn process_event(&mut self, event: &AlacTermEvent, cx: &mut ModelContext<Self>) {
         match event {
             AlacTermEvent::Title(title) => {
@@ -666,8 +736,10 @@ impl Terminal {
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
 
-                if self.pty_info.has_changed() {
-                    cx.emit(Event::TitleChanged);
+                if let TerminalType::Pty { info, .. } = &self.terminal_type {
+                    if info.has_changed() {
+                        cx.emit(Event::TitleChanged);
+                    }
                 }
             }
             AlacTermEvent::ColorRequest(idx, fun_ptr) => {
@@ -685,7 +757,7 @@ impl Terminal {
     }
 
     pub fn get_cwd(&self) -> Option<PathBuf> {
-        self.pty_info.current.as_ref().map(|info| info.cwd.clone())
+        self.working_directory()
     }
 
     ///Takes events from Alacritty and translates them to behavior on this view
@@ -708,7 +780,9 @@ impl Terminal {
 
                 self.last_content.size = new_size;
 
-                self.pty_tx.0.send(Msg::Resize(new_size.into())).ok();
+                if let TerminalType::Pty { pty_tx, .. } = &self.terminal_type {
+                    pty_tx.0.send(Msg::Resize(new_size.into())).ok();
+                }
 
                 term.resize(new_size);
             }
@@ -1055,11 +1129,18 @@ impl Terminal {
 
     ///Write the Input payload to the tty.
     fn write_to_pty(&self, input: String) {
-        self.pty_tx.notify(input.into_bytes());
+        self.write_bytes_to_pty(input.into_bytes());
     }
 
     fn write_bytes_to_pty(&self, input: Vec<u8>) {
-        self.pty_tx.notify(input);
+        match &self.terminal_type {
+            TerminalType::Pty { pty_tx, .. } => pty_tx.notify(input),
+            TerminalType::DisplayOnly { input_tx, .. } => {
+                if let Some(input_tx) = input_tx {
+                    input_tx.unbounded_send(input).ok();
+                }
+            }
+        }
     }
 
     pub fn input(&mut self, input: String) {
@@ -1218,7 +1299,7 @@ impl Terminal {
 
             if self.mouse_changed(point, side) {
                 if let Some(bytes) = mouse_moved_report(point, e, self.last_content.mode) {
-                    self.pty_tx.notify(bytes);
+                    self.write_bytes_to_pty(bytes);
                 }
             }
         } else if self.secondary_pressed {
@@ -1297,7 +1378,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, true, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             match e.button {
@@ -1356,7 +1437,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, false, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             if e.button == MouseButton::Left && setting.copy_on_select {
@@ -1394,7 +1475,7 @@ impl Terminal {
                 if let Some(scrolls) = scroll_report(point, scroll_lines, e, self.last_content.mode)
                 {
                     for scroll in scrolls {
-                        self.pty_tx.notify(scroll);
+                        self.write_bytes_to_pty(scroll);
                     }
                 };
             } else if self
@@ -1403,7 +1484,7 @@ impl Terminal {
                 .contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL)
                 && !e.shift
             {
-                self.pty_tx.notify(alt_scroll(scroll_lines))
+                self.write_bytes_to_pty(alt_scroll(scroll_lines))
             } else {
                 if scroll_lines != 0 {
                     let scroll = AlacScroll::Delta(scroll_lines);
@@ -1459,14 +1540,87 @@ impl Terminal {
     }
 
     pub fn working_directory(&self) -> Option<PathBuf> {
-        self.pty_info
-            .current
-            .as_ref()
-            .map(|process| process.cwd.clone())
+        match &self.terminal_type {
+            TerminalType::Pty { info, .. } => {
+                info.current.as_ref().map(|process| process.cwd.clone())
+            }
+            TerminalType::DisplayOnly { .. } => None,
+        }
+    }
+
+    /// Whether this terminal only displays output written to it, rather than running a
+    /// process.
+    pub fn is_display_only(&self) -> bool {
+        matches!(self.terminal_type, TerminalType::DisplayOnly { .. })
+    }
+
+    /// Whether input typed into this terminal is discarded.
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self.terminal_type,
+            TerminalType::DisplayOnly { input_tx: None, .. }
+        )
+    }
+
+    /// Displays output in a display-only terminal, as if a process had written it.
+    pub fn write_output(&mut self, output: &[u8], cx: &mut ModelContext<Self>) {
+        let TerminalType::DisplayOnly { processor, .. } = &mut self.terminal_type else {
+            return;
+        };
+        let mut term = self.term.lock();
+        for byte in output {
+            processor.advance(&mut *term, *byte);
+        }
+        drop(term);
+        cx.emit(Event::Wakeup);
+        cx.notify();
+    }
+
+    /// Sets the title of a display-only terminal.
+    pub fn set_title(&mut self, new_title: String, cx: &mut ModelContext<Self>) {
+        if let TerminalType::DisplayOnly { title, .. } = &mut self.terminal_type {
+            if *title != new_title {
+                *title = new_title;
+                cx.emit(Event::TitleChanged);
+            }
+        }
+    }
+
+    /// Encodes the visible screen as escape sequences that redraw it from scratch, so that
+    /// it can be displayed by another terminal with [`Terminal::write_output`].
+    pub fn encode_screen(&self) -> Vec<u8> {
+        mappings::screen::encode_screen(&*self.term.lock())
+    }
+
+    /// Returns the text of the visible screen, with the trailing whitespace of each line
+    /// removed.
+    pub fn screen_text(&self) -> String {
+        let term = self.term.lock();
+        let grid = term.grid();
+        (0..term.screen_lines())
+            .map(|line| {
+                let row = &grid[Line(line as i32)];
+                let text = (0..term.columns())
+                    .map(|column| row[Column(column)].c)
+                    .collect::<String>();
+                text.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 
     pub fn title(&self, truncate: bool) -> String {
         const MAX_CHARS: usize = 25;
+        let info = match &self.terminal_type {
+            TerminalType::Pty { info, .. } => info,
+            TerminalType::DisplayOnly { title, .. } => {
+                return if truncate {
+                    truncate_and_trailoff(title, MAX_CHARS)
+                } else {
+                    title.clone()
+                };
+            }
+        };
         match &self.task {
             Some(task_state) => {
                 if truncate {
@@ -1475,8 +1629,7 @@ impl Terminal {
                     task_state.full_label.clone()
                 }
             }
-            None => self
-                .pty_info
+            None => info
                 .current
                 .as_ref()
                 .map(|fpi| {
@@ -1624,7 +1777,9 @@ unsafe fn append_text_to_term(term: &mut Term<ZedListener>, text_lines: &[&str])
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.pty_tx.0.send(Msg::Shutdown).ok();
+        if let TerminalType::Pty { pty_tx, .. } = &self.terminal_type {
+            pty_tx.0.send(Msg::Shutdown).ok();
+        }
     }
 }
 
@@ -1764,14 +1919,22 @@ pub fn rgba_color(r: u8, g: u8, b: u8) -> Hsla {
 #[cfg(test)]
 mod tests {
     use alacritty_terminal::{
+        event::VoidListener,
+        grid::Dimensions,
         index::{Column, Line, Point as AlacPoint},
-        term::cell::Cell,
+        term::{
+            cell::{Cell, Flags},
+            Config, TermMode,
+        },
+        vte::ansi::{Processor, StdSyncHandler},
+        Term,
     };
     use gpui::{point, size, Pixels};
     use rand::{distributions::Alphanumeric, rngs::ThreadRng, thread_rng, Rng};
 
     use crate::{
-        content_index_for_mouse, rgb_for_index, IndexedCell, TerminalContent, TerminalSize,
+        content_index_for_mouse, mappings::screen::encode_screen, rgb_for_index, IndexedCell,
+        TerminalContent, TerminalSize,
     };
 
     #[test]
@@ -1859,6 +2022,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_encode_screen() {
+        let size = crate::TerminalSize {
+            cell_width: Pixels::from(10.),
+            line_height: Pixels::from(10.),
+            size: size(Pixels::from(200.), Pixels::from(50.)),
+        };
+        let mut source = Term::new(Config::default(), &size, VoidListener);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        for byte in b"$ ls\r\n\x1b[1;31mred\x1b[0m plain\r\n\x1b[?2004h$ " {
+            processor.advance(&mut source, *byte);
+        }
+
+        let mut destination = Term::new(Config::default(), &size, VoidListener);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        for byte in b"previous output\r\nmore previous output" {
+            processor.advance(&mut destination, *byte);
+        }
+        for byte in encode_screen(&source) {
+            processor.advance(&mut destination, byte);
+        }
+
+        let text = |term: &Term<VoidListener>| {
+            (0..term.screen_lines())
+                .map(|line| {
+                    (0..term.columns())
+                        .map(|column| term.grid()[Line(line as i32)][Column(column)].c)
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(text(&destination), text(&source));
+        assert_eq!(
+            destination.grid()[Line(1)][Column(0)].fg,
+            source.grid()[Line(1)][Column(0)].fg
+        );
+        assert!(destination.grid()[Line(1)][Column(0)]
+            .flags
+            .contains(Flags::BOLD));
+        assert_eq!(destination.grid().cursor.point, source.grid().cursor.point);
+        assert!(destination.mode().contains(TermMode::BRACKETED_PASTE));
+    }
+
     fn get_cells(size: TerminalSize, rng: &mut ThreadRng) -> Vec<Vec<char>> {
         let mut cells = Vec::new();
 
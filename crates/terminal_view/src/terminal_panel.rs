@@ -6,11 +6,12 @@ use db::kvp::KEY_VALUE_STORE;
 use futures::future::join_all;
 use gpui::{
     actions, Action, AppContext, AsyncWindowContext, DismissEvent, Entity, EventEmitter,
-    ExternalPaths, FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels, Render,
-    Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    ExternalPaths, FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels,
+    PromptLevel, Render, Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView,
+    WindowContext,
 };
 use itertools::Itertools;
-use project::{Fs, ProjectEntryId};
+use project::{Fs, GuestTaskApproval, GuestTaskRequest, Project, ProjectEntryId};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
@@ -29,7 +30,7 @@ use workspace::{
     item::Item,
     pane,
     ui::IconName,
-    DraggedTab, NewTerminal, Pane, ToggleZoom, Workspace,
+    DraggedTab, NewTerminal, Pane, SaveIntent, ToggleZoom, Workspace, WorkspaceId,
 };
 
 use anyhow::Result;
@@ -47,7 +48,10 @@ pub fn init(cx: &mut AppContext) {
                 if workspace
                     .panel::<TerminalPanel>(cx)
                     .as_ref()
-                    .is_some_and(|panel| panel.read(cx).enabled)
+                    .is_some_and(|panel| {
+                        let panel = panel.read(cx);
+                        panel.enabled || !panel.has_no_terminals(cx)
+                    })
                 {
                     workspace.toggle_panel_focus::<TerminalPanel>(cx);
                 }
@@ -196,10 +200,12 @@ impl TerminalPanel {
         let subscriptions = vec![
             cx.observe(&pane, |_, _, cx| cx.notify()),
             cx.subscribe(&pane, Self::handle_pane_event),
+            cx.subscribe(workspace.project(), Self::handle_project_event),
         ];
         let project = workspace.project().read(cx);
         let enabled = project.is_local() || project.supports_remote_terminal(cx);
-        let this = Self {
+        let remote_terminals = project.remote_terminals().cloned().collect::<Vec<_>>();
+        let mut this = Self {
             pane,
             fs: workspace.app_state().fs.clone(),
             workspace: workspace.weak_handle(),
@@ -211,6 +217,9 @@ impl TerminalPanel {
             _subscriptions: subscriptions,
             enabled,
         };
+        for terminal in remote_terminals {
+            this.add_remote_terminal(terminal, workspace.project(), workspace.database_id(), cx);
+        }
         this
     }
 
@@ -308,7 +317,14 @@ impl TerminalPanel {
         cx: &mut ViewContext<Self>,
     ) {
         match event {
-            pane::Event::ActivateItem { .. } => self.serialize(cx),
+            pane::Event::ActivateItem { .. } => {
+                self.serialize(cx);
+                if let Some(workspace) = self.workspace.upgrade() {
+                    workspace.update(cx, |workspace, cx| {
+                        workspace.update_active_view_for_followers(cx)
+                    });
+                }
+            }
             pane::Event::RemoveItem { .. } => self.serialize(cx),
             pane::Event::Remove => cx.emit(PanelEvent::Close),
             pane::Event::ZoomIn => cx.emit(PanelEvent::ZoomIn),
@@ -347,30 +363,10 @@ impl TerminalPanel {
     }
 
     fn spawn_task(&mut self, spawn_in_terminal: &SpawnInTerminal, cx: &mut ViewContext<Self>) {
-        let mut spawn_task = spawn_in_terminal.clone();
-        // Set up shell args unconditionally, as tasks are always spawned inside of a shell.
-        let Some((shell, mut user_args)) = (match TerminalSettings::get_global(cx).shell.clone() {
-            Shell::System => std::env::var("SHELL").ok().map(|shell| (shell, Vec::new())),
-            Shell::Program(shell) => Some((shell, Vec::new())),
-            Shell::WithArguments { program, args } => Some((program, args)),
-        }) else {
+        let Some(spawn_task) = spawn_in_shell(spawn_in_terminal, cx) else {
             return;
         };
 
-        spawn_task.command_label = format!("{shell} -i -c `{}`", spawn_task.command_label);
-        let task_command = std::mem::replace(&mut spawn_task.command, shell);
-        let task_args = std::mem::take(&mut spawn_task.args);
-        let combined_command = task_args
-            .into_iter()
-            .fold(task_command, |mut command, arg| {
-                command.push(' ');
-                command.push_str(&arg);
-                command
-            });
-        user_args.extend(["-i".to_owned(), "-c".to_owned(), combined_command]);
-        spawn_task.args = user_args;
-        let spawn_task = spawn_task;
-
         let allow_concurrent_runs = spawn_in_terminal.allow_concurrent_runs;
         let use_new_terminal = spawn_in_terminal.use_new_terminal;
 
@@ -517,6 +513,7 @@ impl TerminalPanel {
                     TerminalView::new(
                         terminal.clone(),
                         workspace.weak_handle(),
+                        workspace.project().downgrade(),
                         workspace.database_id(),
                         cx,
                     )
@@ -539,6 +536,123 @@ impl TerminalPanel {
         })
     }
 
+    fn handle_project_event(
+        &mut self,
+        project: Model<Project>,
+        event: &project::Event,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            project::Event::TerminalShared(terminal) => {
+                let workspace_id = self
+                    .workspace
+                    .upgrade()
+                    .and_then(|workspace| workspace.read(cx).database_id());
+                self.add_remote_terminal(terminal.clone(), &project, workspace_id, cx);
+            }
+            project::Event::HostTaskStarted(terminal) => {
+                let workspace_id = self
+                    .workspace
+                    .upgrade()
+                    .and_then(|workspace| workspace.read(cx).database_id());
+                self.add_remote_terminal(terminal.clone(), &project, workspace_id, cx);
+                self.pane.update(cx, |pane, cx| {
+                    let index = pane.items_len().saturating_sub(1);
+                    pane.activate_item(index, true, false, cx);
+                });
+                let workspace = self.workspace.clone();
+                cx.spawn(|_, mut cx| async move {
+                    workspace
+                        .update(&mut cx, |workspace, cx| workspace.focus_panel::<Self>(cx))
+                        .ok()
+                })
+                .detach();
+            }
+            project::Event::GuestTaskRequested(request) => {
+                self.run_guest_task(request.clone(), cx);
+            }
+            project::Event::TerminalUnshared(terminal) => {
+                let item_id = self.pane.read(cx).items().find_map(|item| {
+                    let terminal_view = item.act_as::<TerminalView>(cx)?;
+                    (terminal_view.read(cx).terminal() == terminal).then(|| item.item_id())
+                });
+                if let Some(item_id) = item_id {
+                    self.pane.update(cx, |pane, cx| {
+                        pane.close_item_by_id(item_id, SaveIntent::Skip, cx)
+                            .detach_and_log_err(cx);
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Runs a task a guest of the shared project asked for, asking the host first if needed.
+    fn run_guest_task(&mut self, request: GuestTaskRequest, cx: &mut ViewContext<Self>) {
+        let Some(spawn_task) = spawn_in_shell(request.spawn_in_terminal(), cx) else {
+            request.respond(Err(anyhow::anyhow!("no shell to run the task in")));
+            return;
+        };
+        let answer = request.needs_approval().then(|| {
+            let requester = request
+                .requester()
+                .map_or_else(|| "A guest".to_string(), |user| user.github_login.clone());
+            cx.prompt(
+                PromptLevel::Warning,
+                &format!(
+                    "{requester} wants to run \"{}\" on your machine",
+                    spawn_task.label
+                ),
+                Some(&guest_task_detail(&request)),
+                &["Allow This Task", "Allow All Tasks", "Deny"],
+            )
+        });
+
+        cx.spawn(|terminal_panel, mut cx| async move {
+            let approval = match answer {
+                Some(answer) => match answer.await {
+                    Ok(0) => GuestTaskApproval::Task,
+                    Ok(1) => GuestTaskApproval::Session,
+                    _ => {
+                        request.respond(Err(anyhow::anyhow!("the host denied the task")));
+                        return Ok(());
+                    }
+                },
+                None => GuestTaskApproval::Task,
+            };
+            let terminal = terminal_panel
+                .update(&mut cx, |terminal_panel, cx| {
+                    terminal_panel.add_terminal(
+                        spawn_task.cwd.clone(),
+                        Some(spawn_task),
+                        RevealStrategy::Never,
+                        cx,
+                    )
+                })?
+                .await;
+            request.respond(terminal.map(|terminal| (approval, terminal)));
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Adds a tab for a terminal shared by the host of the project.
+    fn add_remote_terminal(
+        &mut self,
+        terminal: Model<Terminal>,
+        project: &Model<Project>,
+        workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let workspace = self.workspace.clone();
+        let project = project.downgrade();
+        let terminal_view =
+            cx.new_view(|cx| TerminalView::new(terminal, workspace, project, workspace_id, cx));
+        self.pane.update(cx, |pane, cx| {
+            pane.add_item(Box::new(terminal_view), false, false, None, cx);
+        });
+    }
+
     fn serialize(&mut self, cx: &mut ViewContext<Self>) {
         let mut items_to_serialize = HashSet::default();
         let items = self
@@ -547,7 +661,8 @@ impl TerminalPanel {
             .items()
             .filter_map(|item| {
                 let terminal_view = item.act_as::<TerminalView>(cx)?;
-                if terminal_view.read(cx).terminal().read(cx).task().is_some() {
+                let terminal = terminal_view.read(cx).terminal().read(cx);
+                if terminal.task().is_some() || terminal.is_display_only() {
                     None
                 } else {
                     let id = item.item_id().as_u64();
@@ -632,6 +747,52 @@ impl TerminalPanel {
     }
 }
 
+/// What the host is shown when a guest asks to run a task: the command, and the directory
+/// and environment it would run with, since any of them may have been edited by a guest.
+fn guest_task_detail(request: &GuestTaskRequest) -> String {
+    let task = request.spawn_in_terminal();
+    let mut detail = task.command_label.clone();
+    match &task.cwd {
+        Some(TerminalWorkDir::Local(path)) => {
+            detail.push_str(&format!("\nin {}", path.display()));
+        }
+        Some(TerminalWorkDir::Ssh {
+            path: Some(path), ..
+        }) => {
+            detail.push_str(&format!("\nin {path}"));
+        }
+        Some(TerminalWorkDir::Ssh { path: None, .. }) | None => {}
+    }
+    for (name, value) in request.template_env() {
+        detail.push_str(&format!("\n{name}={value}"));
+    }
+    detail
+}
+
+/// Wraps a task into the user's shell, as tasks are always spawned inside of a shell.
+fn spawn_in_shell(spawn_in_terminal: &SpawnInTerminal, cx: &AppContext) -> Option<SpawnInTerminal> {
+    let mut spawn_task = spawn_in_terminal.clone();
+    let (shell, mut user_args) = match TerminalSettings::get_global(cx).shell.clone() {
+        Shell::System => std::env::var("SHELL").ok().map(|shell| (shell, Vec::new())),
+        Shell::Program(shell) => Some((shell, Vec::new())),
+        Shell::WithArguments { program, args } => Some((program, args)),
+    }?;
+
+    spawn_task.command_label = format!("{shell} -i -c `{}`", spawn_task.command_label);
+    let task_command = std::mem::replace(&mut spawn_task.command, shell);
+    let task_args = std::mem::take(&mut spawn_task.args);
+    let combined_command = task_args
+        .into_iter()
+        .fold(task_command, |mut command, arg| {
+            command.push(' ');
+            command.push_str(&arg);
+            command
+        });
+    user_args.extend(["-i".to_owned(), "-c".to_owned(), combined_command]);
+    spawn_task.args = user_args;
+    Some(spawn_task)
+}
+
 async fn wait_for_terminals_tasks(
     terminals_for_task: Vec<(usize, View<TerminalView>)>,
     cx: &mut AsyncWindowContext,
@@ -776,6 +937,10 @@ impl Panel for TerminalPanel {
     fn toggle_action(&self) -> Box<dyn gpui::Action> {
         Box::new(ToggleFocus)
     }
+
+    fn pane(&self) -> Option<View<Pane>> {
+        Some(self.pane.clone())
+    }
 }
 
 #[derive(Serialize, Deserialize)]
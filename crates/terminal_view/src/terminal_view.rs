@@ -6,14 +6,15 @@ use collections::HashSet;
 use editor::{scroll::Autoscroll, Editor};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
-    anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent, EventEmitter,
-    FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model, MouseButton,
-    MouseDownEvent, Pixels, Render, ScrollWheelEvent, Styled, Subscription, Task, View,
-    VisualContext, WeakView,
+    actions, anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent,
+    EventEmitter, FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model,
+    MouseButton, MouseDownEvent, Pixels, Render, ScrollWheelEvent, Styled, Subscription, Task,
+    View, VisualContext, WeakModel, WeakView,
 };
 use language::Bias;
 use persistence::TERMINAL_DB;
 use project::{search::SearchQuery, Fs, LocalWorktree, Metadata, Project};
+use rpc::proto::{self, update_view, PeerId};
 use task::TerminalWorkDir;
 use terminal::{
     alacritty_terminal::{
@@ -29,15 +30,15 @@ use terminal_element::{is_blank, TerminalElement};
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label, Tooltip};
 use util::{paths::PathLikeWithPosition, ResultExt};
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, TabContentParams},
+    item::{BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent, TabContentParams},
     notifications::NotifyResultExt,
-    register_deserializable_item,
+    register_deserializable_item, register_followable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
-    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, Workspace,
+    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, ViewId, Workspace,
     WorkspaceId,
 };
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use dirs::home_dir;
 use serde::Deserialize;
 use settings::{Settings, SettingsStore};
@@ -69,11 +70,17 @@ pub struct SendKeystroke(String);
 
 impl_actions!(terminal, [SendText, SendKeystroke]);
 
+actions!(
+    terminal,
+    [ShareTerminal, ShareTerminalReadOnly, UnshareTerminal]
+);
+
 pub fn init(cx: &mut AppContext) {
     terminal_panel::init(cx);
     terminal::init(cx);
 
     register_deserializable_item::<TerminalView>(cx);
+    register_followable_item::<TerminalView>(cx);
 
     cx.observe_new_views(|workspace: &mut Workspace, _| {
         workspace.register_action(TerminalView::deploy);
@@ -95,6 +102,7 @@ pub struct BlockContext<'a, 'b> {
 pub struct TerminalView {
     terminal: Model<Terminal>,
     workspace: WeakView<Workspace>,
+    project: WeakModel<Project>,
     focus_handle: FocusHandle,
     //Currently using iTerm bell, show bell emoji in tab until input is received
     has_bell: bool,
@@ -108,6 +116,7 @@ pub struct TerminalView {
     show_title: bool,
     block_below_cursor: Option<Arc<BlockProperties>>,
     scroll_top: Pixels,
+    remote_id: Option<ViewId>,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -146,6 +155,7 @@ impl TerminalView {
                 TerminalView::new(
                     terminal,
                     workspace.weak_handle(),
+                    workspace.project().downgrade(),
                     workspace.database_id(),
                     cx,
                 )
@@ -157,6 +167,7 @@ impl TerminalView {
     pub fn new(
         terminal: Model<Terminal>,
         workspace: WeakView<Workspace>,
+        project: WeakModel<Project>,
         workspace_id: Option<WorkspaceId>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
@@ -174,6 +185,7 @@ impl TerminalView {
         Self {
             terminal,
             workspace: workspace_handle,
+            project,
             has_bell: false,
             focus_handle,
             context_menu: None,
@@ -186,6 +198,7 @@ impl TerminalView {
             show_title: TerminalSettings::get_global(cx).toolbar.title,
             block_below_cursor: None,
             scroll_top: Pixels::ZERO,
+            remote_id: None,
             _subscriptions: vec![
                 focus_in,
                 focus_out,
@@ -213,9 +226,32 @@ impl TerminalView {
         position: gpui::Point<Pixels>,
         cx: &mut ViewContext<Self>,
     ) {
+        let (can_share, is_shared) = self
+            .workspace
+            .upgrade()
+            .map_or((false, false), |workspace| {
+                let project = workspace.read(cx).project().read(cx);
+                let can_share = project.is_local()
+                    && project.is_shared()
+                    && !self.terminal.read(cx).is_display_only();
+                (
+                    can_share,
+                    project.shared_terminal_id(&self.terminal).is_some(),
+                )
+            });
         let context_menu = ContextMenu::build(cx, |menu, _| {
             menu.action("Clear", Box::new(Clear))
                 .action("Close", Box::new(CloseActiveItem { save_intent: None }))
+                .when(can_share, |menu| {
+                    menu.separator().when_else(
+                        is_shared,
+                        |menu| menu.action("Stop Sharing", Box::new(UnshareTerminal)),
+                        |menu| {
+                            menu.action("Share with Collaborators", Box::new(ShareTerminal))
+                                .action("Share Read-Only", Box::new(ShareTerminalReadOnly))
+                        },
+                    )
+                })
         });
 
         cx.focus_view(&context_menu);
@@ -233,6 +269,38 @@ impl TerminalView {
         self.context_menu = Some((context_menu, position, subscription));
     }
 
+    fn share_terminal(&mut self, _: &ShareTerminal, cx: &mut ViewContext<Self>) {
+        self.set_shared(Some(true), cx);
+    }
+
+    fn share_terminal_read_only(&mut self, _: &ShareTerminalReadOnly, cx: &mut ViewContext<Self>) {
+        self.set_shared(Some(false), cx);
+    }
+
+    fn unshare_terminal(&mut self, _: &UnshareTerminal, cx: &mut ViewContext<Self>) {
+        self.set_shared(None, cx);
+    }
+
+    /// Shares this terminal with the collaborators in the project, writable or read-only,
+    /// or stops sharing it if `writable` is `None`.
+    fn set_shared(&mut self, writable: Option<bool>, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let project = workspace.read(cx).project().clone();
+        let terminal = self.terminal.clone();
+        let result = project.update(cx, |project, cx| match writable {
+            Some(writable) => project.share_terminal(&terminal, writable, cx).map(|_| ()),
+            None => match project.shared_terminal_id(&terminal) {
+                Some(terminal_id) => project.unshare_terminal(terminal_id, cx),
+                None => Ok(()),
+            },
+        });
+        workspace.update(cx, |workspace, cx| {
+            result.notify_err(workspace, cx);
+        });
+    }
+
     fn settings_changed(&mut self, cx: &mut ViewContext<Self>) {
         let settings = TerminalSettings::get_global(cx);
         self.show_title = settings.toolbar.title;
@@ -910,6 +978,9 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
+            .on_action(cx.listener(TerminalView::share_terminal))
+            .on_action(cx.listener(TerminalView::share_terminal_read_only))
+            .on_action(cx.listener(TerminalView::unshare_terminal))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -1019,6 +1090,10 @@ impl Item for TerminalView {
         None
     }
 
+    fn display_name(&self) -> Option<&'static str> {
+        Some("terminal")
+    }
+
     fn clone_on_split(
         &self,
         _workspace_id: Option<WorkspaceId>,
@@ -1109,7 +1184,15 @@ impl Item for TerminalView {
                 project.create_terminal(cwd, None, window, cx)
             })??;
             pane.update(&mut cx, |_, cx| {
-                cx.new_view(|cx| TerminalView::new(terminal, workspace, Some(workspace_id), cx))
+                cx.new_view(|cx| {
+                    TerminalView::new(
+                        terminal,
+                        workspace,
+                        project.downgrade(),
+                        Some(workspace_id),
+                        cx,
+                    )
+                })
             })
         })
     }
@@ -1130,6 +1213,91 @@ impl Item for TerminalView {
     }
 }
 
+impl FollowableItem for TerminalView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        // Only shared terminals can be followed, as their screen is already streamed to guests.
+        let project = self.project.upgrade()?;
+        let terminal_id = project.read(cx).terminal_share_id(&self.terminal)?;
+        Some(proto::view::Variant::Terminal(proto::view::Terminal {
+            terminal_id,
+        }))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<anyhow::Result<View<Self>>>> {
+        let Some(proto::view::Variant::Terminal(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::Terminal(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let project = workspace.read(cx).project().clone();
+        let Some(terminal) = project.read(cx).terminal_for_share_id(state.terminal_id) else {
+            return Some(Task::ready(Err(anyhow!(
+                "terminal {} is not shared in this project",
+                state.terminal_id
+            ))));
+        };
+
+        let existing = pane
+            .read(cx)
+            .items_of_type::<Self>()
+            .find(|terminal_view| terminal_view.read(cx).terminal == terminal);
+        let terminal_view = existing.unwrap_or_else(|| {
+            let workspace_id = workspace.read(cx).database_id();
+            let workspace = workspace.downgrade();
+            cx.new_view(|cx| {
+                TerminalView::new(terminal, workspace, project.downgrade(), workspace_id, cx)
+            })
+        });
+        terminal_view.update(cx, |terminal_view, _| {
+            terminal_view.remote_id = Some(remote_id);
+        });
+
+        Some(Task::ready(Ok(terminal_view)))
+    }
+
+    fn to_follow_event(_: &ItemEvent) -> Option<FollowEvent> {
+        None
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        _: &ItemEvent,
+        _: &mut Option<update_view::Variant>,
+        _: &WindowContext,
+    ) -> bool {
+        false
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        _: update_view::Variant,
+        _: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        Task::ready(Ok(()))
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, _: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        cx.notify();
+    }
+}
+
 impl SearchableItem for TerminalView {
     type Match = RangeInclusive<Point>;
 
@@ -0,0 +1,275 @@
+use anyhow::{anyhow, Result};
+use std::{borrow::Cow, fmt};
+
+const UTF_8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF_16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF_16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// The character encoding of a file on disk.
+///
+/// Buffers always contain UTF-8 text, which is decoded from this encoding when the file is
+/// loaded and encoded back into it when the file is saved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Encoding {
+    encoding: &'static encoding_rs::Encoding,
+    /// Whether the file starts with a byte order mark.
+    bom: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::utf8()
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Encoding {
+    pub fn utf8() -> Self {
+        Self {
+            encoding: encoding_rs::UTF_8,
+            bom: false,
+        }
+    }
+
+    fn new(encoding: &'static encoding_rs::Encoding) -> Self {
+        // UTF-16 can't be told apart from other encodings without a byte order mark,
+        // so one is always written.
+        let bom = encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE;
+        Self { encoding, bom }
+    }
+
+    /// The encodings that can be chosen when reopening or saving a file.
+    pub fn all() -> Vec<Self> {
+        let mut encodings = vec![
+            Self::utf8(),
+            Self {
+                encoding: encoding_rs::UTF_8,
+                bom: true,
+            },
+        ];
+        encodings.extend(
+            [
+                encoding_rs::UTF_16LE,
+                encoding_rs::UTF_16BE,
+                encoding_rs::WINDOWS_1252,
+                encoding_rs::ISO_8859_2,
+                encoding_rs::ISO_8859_15,
+                encoding_rs::WINDOWS_1250,
+                encoding_rs::WINDOWS_1251,
+                encoding_rs::WINDOWS_1253,
+                encoding_rs::WINDOWS_1254,
+                encoding_rs::WINDOWS_1255,
+                encoding_rs::WINDOWS_1256,
+                encoding_rs::WINDOWS_1257,
+                encoding_rs::WINDOWS_1258,
+                encoding_rs::WINDOWS_874,
+                encoding_rs::KOI8_R,
+                encoding_rs::KOI8_U,
+                encoding_rs::IBM866,
+                encoding_rs::SHIFT_JIS,
+                encoding_rs::EUC_JP,
+                encoding_rs::ISO_2022_JP,
+                encoding_rs::GBK,
+                encoding_rs::GB18030,
+                encoding_rs::BIG5,
+                encoding_rs::EUC_KR,
+            ]
+            .into_iter()
+            .map(Self::new),
+        );
+        encodings
+    }
+
+    /// Looks up an encoding by one of its names, such as `"latin1"` or `"shift_jis"`.
+    pub fn for_label(label: &str) -> Option<Self> {
+        if label.eq_ignore_ascii_case("utf-8 with bom") {
+            return Some(Self {
+                encoding: encoding_rs::UTF_8,
+                bom: true,
+            });
+        }
+        encoding_rs::Encoding::for_label(label.as_bytes()).map(Self::new)
+    }
+
+    pub fn name(&self) -> &'static str {
+        if self.is_utf8() && self.bom {
+            "UTF-8 with BOM"
+        } else {
+            self.encoding.name()
+        }
+    }
+
+    pub fn is_utf8(&self) -> bool {
+        self.encoding == encoding_rs::UTF_8
+    }
+
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    /// Guesses the encoding of a file's contents, returning `None` if they look binary.
+    ///
+    /// A byte order mark always wins. Otherwise, text whose every other byte is zero is
+    /// assumed to be UTF-16, and valid UTF-8 to be UTF-8. Other content containing zero bytes
+    /// is considered binary. Text that decodes as Shift_JIS and contains kana is assumed to be
+    /// Japanese, and anything else to be windows-1252, which decodes any input and is a
+    /// superset of Latin-1.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if let Some((encoding, _)) = encoding_rs::Encoding::for_bom(bytes) {
+            return Some(Self {
+                encoding,
+                bom: true,
+            });
+        }
+
+        if let Some(encoding) = detect_utf16_without_bom(bytes) {
+            return Some(Self {
+                encoding,
+                bom: false,
+            });
+        }
+
+        if std::str::from_utf8(bytes).is_ok() {
+            return Some(Self::utf8());
+        }
+
+        if bytes.contains(&0) {
+            return None;
+        }
+
+        let (text, had_errors) = encoding_rs::SHIFT_JIS.decode_without_bom_handling(bytes);
+        if !had_errors && text.chars().any(|c| matches!(c, '\u{3040}'..='\u{30ff}')) {
+            return Some(Self::new(encoding_rs::SHIFT_JIS));
+        }
+
+        Some(Self::new(encoding_rs::WINDOWS_1252))
+    }
+
+    /// Decodes the contents of a file, replacing malformed sequences with U+FFFD.
+    pub fn decode<'a>(&self, bytes: &'a [u8]) -> Cow<'a, str> {
+        if self.bom {
+            self.encoding.decode_with_bom_removal(bytes).0
+        } else {
+            self.encoding.decode_without_bom_handling(bytes).0
+        }
+    }
+
+    /// Encodes text to be written to a file, failing if the text contains characters that
+    /// the encoding can't represent.
+    pub fn encode<'a>(&self, text: &'a str) -> Result<Cow<'a, [u8]>> {
+        if self.encoding == encoding_rs::UTF_16LE || self.encoding == encoding_rs::UTF_16BE {
+            let little_endian = self.encoding == encoding_rs::UTF_16LE;
+            let mut bytes = Vec::with_capacity(text.len() * 2 + 2);
+            if self.bom {
+                bytes.extend_from_slice(if little_endian {
+                    UTF_16LE_BOM
+                } else {
+                    UTF_16BE_BOM
+                });
+            }
+            for unit in text.encode_utf16() {
+                bytes.extend_from_slice(&if little_endian {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                });
+            }
+            return Ok(Cow::Owned(bytes));
+        }
+
+        if self.is_utf8() {
+            return Ok(if self.bom {
+                Cow::Owned([UTF_8_BOM, text.as_bytes()].concat())
+            } else {
+                Cow::Borrowed(text.as_bytes())
+            });
+        }
+
+        let (bytes, _, had_unmappable_characters) = self.encoding.encode(text);
+        if had_unmappable_characters {
+            return Err(anyhow!(
+                "the text contains characters that can't be saved as {}",
+                self.name()
+            ));
+        }
+        Ok(bytes)
+    }
+}
+
+/// Text in UTF-16 without a byte order mark is recognized by the zero high bytes of ASCII
+/// characters, which are all on odd offsets for little endian and even ones for big endian.
+fn detect_utf16_without_bom(bytes: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    let sample = &bytes[..bytes.len().min(1024) & !1];
+    if sample.is_empty() {
+        return None;
+    }
+
+    let pairs = sample.len() / 2;
+    let even_zeros = sample.iter().step_by(2).filter(|byte| **byte == 0).count();
+    let odd_zeros = sample
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .filter(|byte| **byte == 0)
+        .count();
+
+    if odd_zeros * 2 > pairs && even_zeros == 0 {
+        Some(encoding_rs::UTF_16LE)
+    } else if even_zeros * 2 > pairs && odd_zeros == 0 {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let detect = |bytes: &[u8]| Encoding::detect(bytes).map(|encoding| encoding.name());
+
+        assert_eq!(detect(b"plain ascii"), Some("UTF-8"));
+        assert_eq!(detect("h\u{e9}llo".as_bytes()), Some("UTF-8"));
+        assert_eq!(detect(b"\xEF\xBB\xBFhello"), Some("UTF-8 with BOM"));
+        assert_eq!(detect(b"\xFF\xFEh\0i\0"), Some("UTF-16LE"));
+        assert_eq!(detect(b"h\0e\0l\0l\0o\0"), Some("UTF-16LE"));
+        assert_eq!(detect(b"\0h\0e\0l\0l\0o"), Some("UTF-16BE"));
+        assert_eq!(detect(b"caf\xE9 au lait"), Some("windows-1252"));
+        assert_eq!(
+            detect(b"\x82\xB1\x82\xF1\x82\xC9\x82\xBF\x82\xCD"),
+            Some("Shift_JIS")
+        );
+        assert_eq!(detect(b"\x7FELF\x02\x01\x01\0\0\0\xFF"), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let samples = [
+            (Encoding::utf8(), "héllo wörld"),
+            (Encoding::for_label("utf-8 with bom").unwrap(), "héllo"),
+            (Encoding::for_label("utf-16le").unwrap(), "héllo 🌍"),
+            (Encoding::for_label("utf-16be").unwrap(), "héllo 🌍"),
+            (Encoding::for_label("latin1").unwrap(), "café"),
+            (Encoding::for_label("shift_jis").unwrap(), "こんにちは"),
+        ];
+
+        for (encoding, text) in samples {
+            let bytes = encoding.encode(text).unwrap();
+            assert_eq!(Encoding::detect(&bytes), Some(encoding), "{}", encoding);
+            assert_eq!(encoding.decode(&bytes), text, "{}", encoding);
+        }
+    }
+
+    #[test]
+    fn test_unmappable_characters() {
+        let latin1 = Encoding::for_label("latin1").unwrap();
+        assert!(latin1.encode("こんにちは").is_err());
+    }
+}
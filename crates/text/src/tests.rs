@@ -604,6 +604,125 @@ fn test_history() {
     assert_eq!(buffer.text(), "X12cde6");
 }
 
+#[test]
+fn test_undo_tree() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc".into());
+    buffer.set_group_interval(Duration::from_secs(0));
+
+    buffer.edit([(3..3, "d")]);
+    let transaction_1 = buffer.peek_undo_stack().unwrap().transaction_id();
+    buffer.edit([(4..4, "e")]);
+    let transaction_2 = buffer.peek_undo_stack().unwrap().transaction_id();
+    assert_eq!(buffer.text(), "abcde");
+
+    // Editing after an undo abandons the redo branch, but keeps it in the tree.
+    buffer.undo();
+    buffer.edit([(4..4, "x")]);
+    let transaction_3 = buffer.peek_undo_stack().unwrap().transaction_id();
+    assert_eq!(buffer.text(), "abcdx");
+    assert!(buffer.peek_redo_stack().is_none());
+
+    let tree = buffer.undo_tree();
+    assert_eq!(tree.current(), Some(transaction_3));
+    assert_eq!(
+        tree.children(Some(transaction_1))
+            .map(|node| node.transaction.id)
+            .collect::<Vec<_>>(),
+        [transaction_2, transaction_3]
+    );
+
+    buffer.jump_to_undo_state(Some(transaction_2));
+    assert_eq!(buffer.text(), "abcde");
+    assert_eq!(buffer.undo_tree().current(), Some(transaction_2));
+
+    // Undo follows the path that leads to the state that was jumped to.
+    buffer.undo();
+    assert_eq!(buffer.text(), "abcd");
+    buffer.undo();
+    assert_eq!(buffer.text(), "abc");
+    assert_eq!(buffer.undo_tree().current(), None);
+
+    buffer.jump_to_undo_state(Some(transaction_3));
+    assert_eq!(buffer.text(), "abcdx");
+    buffer.jump_to_undo_state(None);
+    assert_eq!(buffer.text(), "abc");
+}
+
+#[test]
+fn test_undo_tree_remove() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
+    buffer.set_group_interval(Duration::from_secs(0));
+    let mut transactions = Vec::new();
+    for text in ["a", "b", "c"] {
+        let len = buffer.len();
+        buffer.edit([(len..len, text)]);
+        transactions.push(buffer.peek_undo_stack().unwrap().transaction_id());
+    }
+    buffer.undo();
+    buffer.edit([(2..2, "d")]);
+    let transaction_4 = buffer.peek_undo_stack().unwrap().transaction_id();
+
+    // Removing a node attaches its children to its parent, keeping them in creation order.
+    let mut tree = buffer.undo_tree().clone();
+    tree.remove(transactions[1]);
+    assert!(tree.node(transactions[1]).is_none());
+    assert_eq!(
+        tree.children(Some(transactions[0]))
+            .map(|node| node.transaction.id)
+            .collect::<Vec<_>>(),
+        [transactions[2], transaction_4]
+    );
+    assert_eq!(
+        tree.path_to(Some(transaction_4)),
+        [transactions[0], transaction_4]
+    );
+    assert_eq!(tree.current(), Some(transaction_4));
+
+    tree.remove(transaction_4);
+    assert_eq!(tree.current(), Some(transactions[0]));
+    assert_eq!(tree.nodes().count(), 2);
+
+    // The buffer's own tree is unaffected.
+    assert_eq!(buffer.undo_tree().nodes().count(), 4);
+}
+
+#[test]
+fn test_serialized_history() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc".into());
+    buffer.set_group_interval(Duration::from_secs(0));
+    buffer.edit([(3..3, "d")]);
+    buffer.edit([(4..4, "e")]);
+    let transaction_2 = buffer.peek_undo_stack().unwrap().transaction_id();
+    buffer.undo();
+    buffer.edit([(0..1, "A")]);
+    assert_eq!(buffer.text(), "Abcd");
+
+    let mut restored = Buffer::from_serialized_history(
+        0,
+        BufferId::new(2).unwrap(),
+        LineEnding::Unix,
+        buffer.serialize_history(),
+    )
+    .unwrap();
+    assert_eq!(restored.text(), "Abcd");
+    assert_eq!(restored.undo_tree().nodes().count(), 3);
+
+    restored.undo();
+    assert_eq!(restored.text(), "abcd");
+    restored.jump_to_undo_state(Some(transaction_2));
+    assert_eq!(restored.text(), "abcde");
+
+    // New transactions don't reuse ids from the restored history.
+    restored.edit([(5..5, "f")]);
+    let transaction_4 = restored.peek_undo_stack().unwrap().transaction_id();
+    assert!(restored
+        .undo_tree()
+        .nodes()
+        .take(3)
+        .all(|node| node.transaction.id != transaction_4));
+    assert_eq!(restored.text(), "abcdef");
+}
+
 #[test]
 fn test_finalize_last_transaction() {
     let now = Instant::now();
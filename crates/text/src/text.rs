@@ -1,4 +1,5 @@
 mod anchor;
+mod encoding;
 pub mod locator;
 #[cfg(any(test, feature = "test-support"))]
 pub mod network;
@@ -9,11 +10,13 @@ pub mod subscription;
 #[cfg(test)]
 mod tests;
 mod undo_map;
+mod undo_tree;
 
 pub use anchor::*;
 use anyhow::{anyhow, Context as _, Result};
 pub use clock::ReplicaId;
 use collections::{HashMap, HashSet};
+pub use encoding::Encoding;
 use locator::Locator;
 use operation_queue::OperationQueue;
 pub use patch::Patch;
@@ -33,12 +36,13 @@ use std::{
     ops::{self, Deref, Range, Sub},
     str,
     sync::Arc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 pub use subscription::*;
 pub use sum_tree::Bias;
 use sum_tree::{FilterCursor, SumTree, TreeMap};
 use undo_map::UndoMap;
+pub use undo_tree::{UndoTree, UndoTreeNode};
 use util::ResultExt;
 
 #[cfg(any(test, feature = "test-support"))]
@@ -136,12 +140,26 @@ impl HistoryEntry {
     }
 }
 
+/// A buffer's undo history in a form that can be stored and restored later.
+///
+/// The text and operations are kept in persistent trees, so capturing the history is cheap
+/// and encoding it can happen on a background thread.
+#[derive(Clone, Debug)]
+pub struct SerializedHistory {
+    pub base_text: Rope,
+    pub operations: TreeMap<clock::Lamport, Operation>,
+    pub undo_stack: Vec<Transaction>,
+    pub redo_stack: Vec<Transaction>,
+    pub undo_tree: UndoTree,
+}
+
 struct History {
     base_text: Rope,
     operations: TreeMap<clock::Lamport, Operation>,
     insertion_slices: HashMap<clock::Lamport, Vec<InsertionSlice>>,
     undo_stack: Vec<HistoryEntry>,
     redo_stack: Vec<HistoryEntry>,
+    undo_tree: UndoTree,
     transaction_depth: usize,
     group_interval: Duration,
 }
@@ -160,6 +178,7 @@ impl History {
             insertion_slices: Default::default(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            undo_tree: UndoTree::default(),
             transaction_depth: 0,
             // Don't group transactions in tests unless we opt in, because it's a footgun.
             #[cfg(any(test, feature = "test-support"))]
@@ -216,6 +235,8 @@ impl History {
                 self.redo_stack.clear();
                 let entry = self.undo_stack.last_mut().unwrap();
                 entry.last_edit_at = now;
+                self.undo_tree
+                    .push(entry.transaction.clone(), SystemTime::now());
                 Some(entry)
             }
         } else {
@@ -268,6 +289,11 @@ impl History {
             if let Some(entry) = entries_to_merge.last_mut() {
                 last_entry.last_edit_at = entry.last_edit_at;
             }
+
+            for entry in &*entries_to_merge {
+                self.undo_tree.remove(entry.transaction.id);
+            }
+            self.undo_tree.update(&last_entry.transaction);
         }
 
         self.undo_stack.truncate(new_len);
@@ -290,6 +316,8 @@ impl History {
             suppress_grouping: false,
         });
         self.redo_stack.clear();
+        let transaction = &self.undo_stack.last().unwrap().transaction;
+        self.undo_tree.push(transaction.clone(), SystemTime::now());
     }
 
     fn push_undo(&mut self, op_id: clock::Lamport) {
@@ -304,6 +332,7 @@ impl History {
         assert_eq!(self.transaction_depth, 0);
         if let Some(entry) = self.undo_stack.pop() {
             self.redo_stack.push(entry);
+            self.sync_undo_tree();
             self.redo_stack.last()
         } else {
             None
@@ -318,7 +347,11 @@ impl History {
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)?;
         let entry = self.undo_stack.remove(entry_ix);
+        // Undoing a transaction out of order leaves a state that no node of the tree
+        // describes, so the transaction is taken out of it.
+        self.undo_tree.remove(entry.transaction.id);
         self.redo_stack.push(entry);
+        self.sync_undo_tree();
         self.redo_stack.last()
     }
 
@@ -333,12 +366,14 @@ impl History {
         {
             self.redo_stack
                 .extend(self.undo_stack.drain(entry_ix..).rev());
+            self.sync_undo_tree();
         }
         &self.redo_stack[redo_stack_start_len..]
     }
 
     fn forget(&mut self, transaction_id: TransactionId) -> Option<Transaction> {
         assert_eq!(self.transaction_depth, 0);
+        self.undo_tree.remove(transaction_id);
         if let Some(entry_ix) = self
             .undo_stack
             .iter()
@@ -386,6 +421,8 @@ impl History {
         if let Some(transaction) = self.forget(transaction) {
             if let Some(destination) = self.transaction_mut(destination) {
                 destination.edit_ids.extend(transaction.edit_ids);
+                let destination = destination.clone();
+                self.undo_tree.update(&destination);
             }
         }
     }
@@ -394,6 +431,7 @@ impl History {
         assert_eq!(self.transaction_depth, 0);
         if let Some(entry) = self.redo_stack.pop() {
             self.undo_stack.push(entry);
+            self.sync_undo_tree();
             self.undo_stack.last()
         } else {
             None
@@ -411,9 +449,54 @@ impl History {
         {
             self.undo_stack
                 .extend(self.redo_stack.drain(entry_ix..).rev());
+            self.sync_undo_tree();
         }
         &self.undo_stack[undo_stack_start_len..]
     }
+
+    fn sync_undo_tree(&mut self) {
+        let top = self.undo_stack.last().map(|entry| &entry.transaction);
+        self.undo_tree.set_current(top, SystemTime::now());
+    }
+
+    /// Makes the given node of the undo tree the current state, returning the transactions
+    /// whose undo state has to be toggled to get there.
+    fn jump_to(&mut self, target: Option<TransactionId>) -> Vec<Transaction> {
+        assert_eq!(self.transaction_depth, 0);
+        let current_path = self.undo_tree.path_to(self.undo_tree.current());
+        let target_path = self.undo_tree.path_to(target);
+        let common_len = current_path
+            .iter()
+            .zip(&target_path)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let transactions = current_path[common_len..]
+            .iter()
+            .rev()
+            .chain(&target_path[common_len..])
+            .filter_map(|transaction_id| self.undo_tree.node(*transaction_id))
+            .map(|node| node.transaction.clone())
+            .collect();
+
+        // The stacks are rebuilt so that undo walks back along the target's path. Redo only
+        // follows a linear history, so it starts out empty.
+        let now = Instant::now();
+        self.undo_stack = target_path
+            .iter()
+            .filter_map(|transaction_id| self.undo_tree.node(*transaction_id))
+            .map(|node| HistoryEntry {
+                transaction: node.transaction.clone(),
+                first_edit_at: now,
+                last_edit_at: now,
+                suppress_grouping: true,
+            })
+            .collect();
+        self.redo_stack.clear();
+        self.sync_undo_tree();
+
+        transactions
+    }
 }
 
 struct Edits<'a, D: TextDimension, F: FnMut(&FragmentSummary) -> bool> {
@@ -1379,6 +1462,84 @@ impl Buffer {
             .collect()
     }
 
+    pub fn undo_tree(&self) -> &UndoTree {
+        &self.history.undo_tree
+    }
+
+    /// Returns the buffer to the state after the given transaction in the undo tree, or to
+    /// its initial state if `None` is given, even if that state is on an abandoned branch.
+    pub fn jump_to_undo_state(&mut self, target: Option<TransactionId>) -> Vec<Operation> {
+        self.history
+            .jump_to(target)
+            .into_iter()
+            .map(|transaction| self.undo_or_redo(transaction).unwrap())
+            .collect()
+    }
+
+    /// Captures everything needed to rebuild this buffer's undo history in a new buffer
+    /// with [`Buffer::from_serialized_history`].
+    pub fn serialize_history(&self) -> SerializedHistory {
+        SerializedHistory {
+            base_text: self.history.base_text.clone(),
+            operations: self.history.operations.clone(),
+            undo_stack: self
+                .history
+                .undo_stack
+                .iter()
+                .map(|entry| entry.transaction.clone())
+                .collect(),
+            redo_stack: self
+                .history
+                .redo_stack
+                .iter()
+                .map(|entry| entry.transaction.clone())
+                .collect(),
+            undo_tree: self.history.undo_tree.clone(),
+        }
+    }
+
+    /// Rebuilds a buffer by replaying the operations of a serialized history on top of
+    /// its base text.
+    pub fn from_serialized_history(
+        replica_id: ReplicaId,
+        remote_id: BufferId,
+        line_ending: LineEnding,
+        history: SerializedHistory,
+    ) -> Result<Buffer> {
+        let mut base_text = history.base_text.to_string();
+        LineEnding::normalize(&mut base_text);
+        let mut buffer =
+            Self::new_normalized(replica_id, remote_id, line_ending, Rope::from(base_text));
+        buffer.apply_ops(history.operations.values().cloned())?;
+        if !buffer.deferred_ops.is_empty() {
+            return Err(anyhow!("serialized history is missing operations"));
+        }
+
+        let now = Instant::now();
+        let entry = |transaction: Transaction| HistoryEntry {
+            transaction,
+            first_edit_at: now,
+            last_edit_at: now,
+            suppress_grouping: true,
+        };
+        let transaction_ids = history
+            .undo_stack
+            .iter()
+            .chain(&history.redo_stack)
+            .chain(history.undo_tree.nodes().map(|node| &node.transaction))
+            .map(|transaction| transaction.id)
+            .collect::<Vec<_>>();
+        for transaction_id in transaction_ids {
+            // Transaction ids come from the same clock as operations, so they must not be
+            // handed out again.
+            buffer.lamport_clock.observe(transaction_id);
+        }
+        buffer.history.undo_stack = history.undo_stack.into_iter().map(entry).collect();
+        buffer.history.redo_stack = history.redo_stack.into_iter().map(entry).collect();
+        buffer.history.undo_tree = history.undo_tree;
+        Ok(buffer)
+    }
+
     fn undo_or_redo(&mut self, transaction: Transaction) -> Result<Operation> {
         let mut counts = HashMap::default();
         for edit_id in transaction.edit_ids {
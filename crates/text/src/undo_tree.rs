@@ -0,0 +1,157 @@
+use crate::{Transaction, TransactionId};
+use std::time::SystemTime;
+use sum_tree::TreeMap;
+
+/// A state the buffer can return to, reached by applying a transaction on top of its
+/// parent state.
+#[derive(Clone, Debug)]
+pub struct UndoTreeNode {
+    pub transaction: Transaction,
+    /// The state this one was created from, or `None` for the buffer's initial text.
+    pub parent: Option<TransactionId>,
+    /// The order in which states were created, starting at 1.
+    pub sequence: usize,
+    pub created_at: SystemTime,
+}
+
+/// Every state the buffer's history has passed through, including the ones that can no
+/// longer be reached with redo because an edit was made after undoing.
+///
+/// The transactions on the path from the root to the current node are applied and all
+/// others are undone, so moving to another node only requires undoing and redoing the
+/// transactions between the two.
+///
+/// Nodes are kept in persistent trees, so looking them up is logarithmic and cloning the
+/// whole tree is cheap.
+#[derive(Clone, Debug, Default)]
+pub struct UndoTree {
+    /// Nodes keyed by their sequence number, which is the order they were created in.
+    nodes: TreeMap<usize, UndoTreeNode>,
+    sequences: TreeMap<TransactionId, usize>,
+    /// The sequence numbers of every node's children, keyed by their parent first.
+    children: TreeMap<(Option<TransactionId>, usize), ()>,
+    current: Option<TransactionId>,
+    next_sequence: usize,
+}
+
+impl UndoTree {
+    pub fn from_nodes(
+        nodes: impl IntoIterator<Item = UndoTreeNode>,
+        current: Option<TransactionId>,
+    ) -> Self {
+        let mut tree = Self::default();
+        for node in nodes {
+            tree.next_sequence = tree.next_sequence.max(node.sequence);
+            tree.insert(node);
+        }
+        tree.current = current;
+        tree
+    }
+
+    /// Returns the nodes in the order they were created.
+    pub fn nodes(&self) -> impl '_ + Iterator<Item = &UndoTreeNode> {
+        self.nodes.values()
+    }
+
+    /// Returns the node for the buffer's current state, or `None` if no transaction is applied.
+    pub fn current(&self) -> Option<TransactionId> {
+        self.current
+    }
+
+    pub fn node(&self, transaction_id: TransactionId) -> Option<&UndoTreeNode> {
+        self.nodes.get(self.sequences.get(&transaction_id)?)
+    }
+
+    /// Returns the children of the given node, oldest first.
+    pub fn children(
+        &self,
+        parent: Option<TransactionId>,
+    ) -> impl '_ + Iterator<Item = &UndoTreeNode> {
+        self.child_sequences(parent)
+            .into_iter()
+            .filter_map(|sequence| self.nodes.get(&sequence))
+    }
+
+    /// Returns the transactions leading from the initial state to the given node.
+    pub fn path_to(&self, transaction_id: Option<TransactionId>) -> Vec<TransactionId> {
+        let mut path = Vec::new();
+        let mut next = transaction_id;
+        while let Some(transaction_id) = next {
+            let Some(node) = self.node(transaction_id) else {
+                break;
+            };
+            path.push(transaction_id);
+            next = node.parent;
+        }
+        path.reverse();
+        path
+    }
+
+    pub(crate) fn push(&mut self, transaction: Transaction, now: SystemTime) {
+        self.next_sequence += 1;
+        let transaction_id = transaction.id;
+        self.insert(UndoTreeNode {
+            transaction,
+            parent: self.current,
+            sequence: self.next_sequence,
+            created_at: now,
+        });
+        self.current = Some(transaction_id);
+    }
+
+    /// Replaces a node's transaction after edits were merged into it.
+    pub(crate) fn update(&mut self, transaction: &Transaction) {
+        if let Some(sequence) = self.sequences.get(&transaction.id) {
+            self.nodes
+                .update(sequence, |node| node.transaction = transaction.clone());
+        }
+    }
+
+    /// Removes a node, attaching its children to its parent.
+    pub(crate) fn remove(&mut self, transaction_id: TransactionId) {
+        let Some(sequence) = self.sequences.remove(&transaction_id) else {
+            return;
+        };
+        let Some(node) = self.nodes.remove(&sequence) else {
+            return;
+        };
+        self.children.remove(&(node.parent, sequence));
+        for child_sequence in self.child_sequences(Some(transaction_id)) {
+            self.children
+                .remove(&(Some(transaction_id), child_sequence));
+            self.children.insert((node.parent, child_sequence), ());
+            self.nodes
+                .update(&child_sequence, |child| child.parent = node.parent);
+        }
+        if self.current == Some(transaction_id) {
+            self.current = node.parent;
+        }
+    }
+
+    /// Moves to the state at the top of the undo stack, adding it to the tree if it was
+    /// removed by an out-of-order undo.
+    pub(crate) fn set_current(&mut self, top: Option<&Transaction>, now: SystemTime) {
+        match top {
+            Some(transaction) if self.node(transaction.id).is_none() => {
+                self.push(transaction.clone(), now)
+            }
+            _ => self.current = top.map(|transaction| transaction.id),
+        }
+    }
+
+    fn insert(&mut self, node: UndoTreeNode) {
+        self.sequences.insert(node.transaction.id, node.sequence);
+        self.children.insert((node.parent, node.sequence), ());
+        self.nodes.insert(node.sequence, node);
+    }
+
+    fn child_sequences(&self, parent: Option<TransactionId>) -> Vec<usize> {
+        let start = (parent, 0);
+        self.children
+            .iter_from(&start)
+            .map(|((child_parent, sequence), _)| (*child_parent, *sequence))
+            .take_while(|(child_parent, _)| *child_parent == parent)
+            .map(|(_, sequence)| sequence)
+            .collect()
+    }
+}
@@ -100,6 +100,7 @@ pub enum IconName {
     Book,
     BookCopy,
     BookPlus,
+    Bookmark,
     CaseSensitive,
     Check,
     ChevronDown,
@@ -248,6 +249,7 @@ impl IconName {
             IconName::Book => "icons/book.svg",
             IconName::BookCopy => "icons/book_copy.svg",
             IconName::BookPlus => "icons/book_plus.svg",
+            IconName::Bookmark => "icons/bookmark.svg",
             IconName::CaseSensitive => "icons/case_insensitive.svg",
             IconName::Check => "icons/check.svg",
             IconName::ChevronDown => "icons/chevron_down.svg",
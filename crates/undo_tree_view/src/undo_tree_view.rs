@@ -0,0 +1,303 @@
+use editor::Editor;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    ParentElement, Render, Styled, View, ViewContext, VisualContext, WeakView,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use text::{TransactionId, UndoTree};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(undo_tree, [Toggle]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(UndoTreeView::register).detach();
+}
+
+/// Lists every state the active buffer's undo history has passed through, including the
+/// branches that were abandoned by editing after an undo, and returns the buffer to the
+/// chosen one.
+pub struct UndoTreeView {
+    picker: View<Picker<UndoTreeDelegate>>,
+}
+
+impl UndoTreeView {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &Toggle, cx| {
+            Self::toggle(workspace, cx);
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> Option<()> {
+        let buffer = workspace
+            .active_item(cx)?
+            .act_as::<Editor>(cx)?
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()?;
+        workspace.toggle_modal(cx, move |cx| UndoTreeView::new(buffer, cx));
+        Some(())
+    }
+
+    fn new(buffer: Model<Buffer>, cx: &mut ViewContext<Self>) -> Self {
+        let tree = buffer.read(cx).undo_tree().clone();
+        let now = OffsetDateTime::now_utc();
+        let timezone = cx.local_timezone();
+        let entries = tree_rows(&tree)
+            .into_iter()
+            .map(|(state, depth)| {
+                let label = match state.and_then(|id| tree.node(id)) {
+                    Some(node) => {
+                        let created_at = OffsetDateTime::from(node.created_at);
+                        let relative = time_format::format_localized_timestamp(
+                            created_at,
+                            now,
+                            timezone,
+                            TimestampFormat::Relative,
+                        );
+                        format!("#{} · {relative}", node.sequence)
+                    }
+                    None => "Original".to_string(),
+                };
+                UndoTreeEntry {
+                    state,
+                    depth,
+                    is_current: state == tree.current(),
+                    label,
+                }
+            })
+            .collect();
+
+        let delegate = UndoTreeDelegate {
+            undo_tree_view: cx.view().downgrade(),
+            buffer,
+            entries,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+/// Returns the states of the tree with their indentation, newest first.
+///
+/// The most recent child of each state continues its parent's column, while older
+/// children start indented branches directly above their parent.
+fn tree_rows(tree: &UndoTree) -> Vec<(Option<TransactionId>, usize)> {
+    fn visit(
+        tree: &UndoTree,
+        state: Option<TransactionId>,
+        depth: usize,
+        rows: &mut Vec<(Option<TransactionId>, usize)>,
+    ) {
+        rows.push((state, depth));
+        let mut children = tree.children(state).collect::<Vec<_>>();
+        children.sort_by_key(|node| node.sequence);
+        if let Some((newest, older)) = children.split_last() {
+            for child in older {
+                visit(tree, Some(child.transaction.id), depth + 1, rows);
+            }
+            visit(tree, Some(newest.transaction.id), depth, rows);
+        }
+    }
+
+    let mut rows = Vec::new();
+    visit(tree, None, 0, &mut rows);
+    rows.reverse();
+    rows
+}
+
+impl Render for UndoTreeView {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for UndoTreeView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for UndoTreeView {}
+impl ModalView for UndoTreeView {}
+
+struct UndoTreeEntry {
+    state: Option<TransactionId>,
+    depth: usize,
+    is_current: bool,
+    label: String,
+}
+
+pub struct UndoTreeDelegate {
+    undo_tree_view: WeakView<UndoTreeView>,
+    buffer: Model<Buffer>,
+    entries: Vec<UndoTreeEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for UndoTreeDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Go to undo state...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No undo history".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let state = self.entries[mat.candidate_id].state;
+            self.buffer.update(cx, |buffer, cx| {
+                buffer.jump_to_undo_state(state, cx);
+            });
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.undo_tree_view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| StringMatchCandidate::new(id, entry.label.clone()))
+            .collect::<Vec<_>>();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                // Keep the tree's layout rather than ordering by score.
+                matches.sort_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.selected_index = matches
+                    .iter()
+                    .position(|mat| delegate.entries[mat.candidate_id].is_current)
+                    .unwrap_or(0);
+                delegate.matches = matches;
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let entry = &self.entries[mat.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .indent_level(entry.depth)
+                .start_slot(Label::new(if entry.is_current { "●" } else { "○" }).color(
+                    if entry.is_current {
+                        Color::Accent
+                    } else {
+                        Color::Muted
+                    },
+                ))
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                )),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{Context, TestAppContext};
+    use std::time::Duration;
+
+    #[gpui::test]
+    fn test_tree_rows(cx: &mut TestAppContext) {
+        let buffer = cx.new_model(|cx| Buffer::local("", cx));
+        let transactions = buffer.update(cx, |buffer, cx| {
+            buffer.set_group_interval(Duration::ZERO);
+            let mut edit = |text: &str, buffer: &mut Buffer| {
+                buffer.start_transaction();
+                let len = buffer.len();
+                buffer.edit([(len..len, text)], None, cx);
+                buffer.end_transaction(cx).unwrap()
+            };
+            let a = edit("a", buffer);
+            let b = edit("b", buffer);
+            buffer.undo(cx);
+            let c = edit("c", buffer);
+            [a, b, c]
+        });
+        let [a, b, c] = transactions;
+
+        let rows = buffer.read_with(cx, |buffer, _| tree_rows(buffer.undo_tree()));
+        assert_eq!(rows, [(Some(c), 0), (Some(b), 1), (Some(a), 0), (None, 0)]);
+
+        buffer.update(cx, |buffer, cx| {
+            assert!(buffer.jump_to_undo_state(Some(b), cx));
+            assert_eq!(buffer.text(), "ab");
+            assert!(buffer.jump_to_undo_state(None, cx));
+            assert_eq!(buffer.text(), "");
+        });
+    }
+}
@@ -26,10 +26,28 @@ pub fn create_mark(vim: &mut Vim, text: Arc<str>, tail: bool, cx: &mut WindowCon
     }) else {
         return;
     };
+    // Uppercase marks aren't local to a file, so they're kept as project bookmarks.
+    if let Some(mark) = global_mark(&text) {
+        let placed = vim.update_active_editor(cx, |_, editor, cx| {
+            anchors
+                .last()
+                .is_some_and(|anchor| editor.set_bookmark_mark(mark, *anchor, cx))
+        });
+        if placed == Some(true) {
+            vim.clear_operator(cx);
+            return;
+        }
+    }
     vim.update_state(|state| state.marks.insert(text.to_string(), anchors));
     vim.clear_operator(cx);
 }
 
+fn global_mark(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    let mark = chars.next()?;
+    (mark.is_ascii_uppercase() && chars.next().is_none()).then_some(mark)
+}
+
 pub fn create_visual_marks(vim: &mut Vim, mode: Mode, cx: &mut WindowContext) {
     let mut starts = vec![];
     let mut ends = vec![];
@@ -80,7 +98,27 @@ pub fn jump(text: Arc<str>, line: bool, cx: &mut WindowContext) {
                     .collect::<Vec<Anchor>>()
             }),
             "." => vim.state().change_list.last().cloned(),
-            _ => vim.state().marks.get(&*text).cloned(),
+            _ => {
+                let bookmark = global_mark(&text).and_then(|mark| {
+                    vim.update_active_editor(cx, |_, editor, cx| {
+                        let bookmark_id = editor.bookmark_for_mark(mark, cx)?;
+                        Some((bookmark_id, editor.bookmark_position(bookmark_id, cx)))
+                    })
+                    .flatten()
+                });
+                match bookmark {
+                    Some((_, Some(anchor))) => Some(vec![anchor]),
+                    // The mark is in another file, so the jump can't be used as a motion.
+                    Some((bookmark_id, None)) => {
+                        vim.clear_operator(cx);
+                        vim.update_active_editor(cx, |_, editor, cx| {
+                            editor.go_to_bookmark(bookmark_id, cx)
+                        });
+                        None
+                    }
+                    None => vim.state().marks.get(&*text).cloned(),
+                }
+            }
         }
     });
 
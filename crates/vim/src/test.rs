@@ -1073,6 +1073,38 @@ async fn test_lowercase_marks(cx: &mut TestAppContext) {
         .assert_eq("line one\nˇtwo\nline three");
 }
 
+#[gpui::test]
+async fn test_uppercase_marks(cx: &mut TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state("line one\nline ˇtwo\nline three", Mode::Normal);
+    cx.simulate_keystrokes("m shift-a");
+    cx.update_editor(|editor, cx| {
+        let bookmark_id = editor.bookmark_for_mark('A', cx);
+        assert!(
+            bookmark_id.is_some(),
+            "uppercase marks are kept as bookmarks"
+        );
+    });
+
+    // Backtick jumps go to the mark's column, and quote jumps to the start of its line.
+    cx.simulate_keystrokes("g g ' shift-a");
+    cx.assert_state("line one\nˇline two\nline three", Mode::Normal);
+    cx.simulate_keystrokes("g g ` shift-a");
+    cx.assert_state("line one\nline ˇtwo\nline three", Mode::Normal);
+
+    // The bookmark follows its line as lines are inserted above it.
+    cx.simulate_keystrokes("g g shift-o z e r o escape ` shift-a");
+    cx.assert_state("zero\nline one\nline ˇtwo\nline three", Mode::Normal);
+
+    // Setting the mark again moves it.
+    cx.simulate_keystrokes("g g l m shift-a j ` shift-a");
+    cx.assert_state("zˇero\nline one\nline two\nline three", Mode::Normal);
+
+    cx.simulate_keystrokes("j j m shift-a k d ' shift-a");
+    cx.assert_state("zero\nˇline three", Mode::Normal);
+}
+
 #[gpui::test]
 async fn test_lt_gt_marks(cx: &mut TestAppContext) {
     let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -309,6 +309,10 @@ impl Item for WelcomePage {
         Some("welcome page")
     }
 
+    fn display_name(&self) -> Option<&'static str> {
+        Some("welcome page")
+    }
+
     fn show_toolbar(&self) -> bool {
         false
     }
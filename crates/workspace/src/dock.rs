@@ -1,5 +1,5 @@
 use crate::persistence::model::DockData;
-use crate::{status_bar::StatusItemView, Workspace};
+use crate::{status_bar::StatusItemView, Pane, Workspace};
 use crate::{DraggedDock, Event};
 use gpui::{
     deferred, div, px, Action, AnchorCorner, AnyView, AppContext, Axis, Entity, EntityId,
@@ -44,6 +44,11 @@ pub trait Panel: FocusableView + EventEmitter<PanelEvent> {
     }
     fn set_zoomed(&mut self, _zoomed: bool, _cx: &mut ViewContext<Self>) {}
     fn set_active(&mut self, _active: bool, _cx: &mut ViewContext<Self>) {}
+    /// The pane hosting this panel's items, if it has one.
+    /// Followers are taken to the active item of this pane while the panel has focus.
+    fn pane(&self) -> Option<View<Pane>> {
+        None
+    }
 }
 
 pub trait PanelHandle: Send + Sync {
@@ -62,6 +67,7 @@ pub trait PanelHandle: Send + Sync {
     fn toggle_action(&self, cx: &WindowContext) -> Box<dyn Action>;
     fn icon_label(&self, cx: &WindowContext) -> Option<String>;
     fn focus_handle(&self, cx: &AppContext) -> FocusHandle;
+    fn pane(&self, cx: &WindowContext) -> Option<View<Pane>>;
     fn to_any(&self) -> AnyView;
 }
 
@@ -125,6 +131,10 @@ where
         self.read(cx).icon_label(cx)
     }
 
+    fn pane(&self, cx: &WindowContext) -> Option<View<Pane>> {
+        self.read(cx).pane()
+    }
+
     fn to_any(&self) -> AnyView {
         self.clone().into()
     }
@@ -159,6 +159,12 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
         None
     }
 
+    /// A short name for this kind of item, such as "project search", shown to the
+    /// followers of someone who is in an item that can't be followed.
+    fn display_name(&self) -> Option<&'static str> {
+        None
+    }
+
     /// (model id, Item)
     fn for_each_project_item(
         &self,
@@ -274,6 +280,7 @@ pub trait ItemHandle: 'static + Send {
     fn tab_description(&self, detail: usize, cx: &AppContext) -> Option<SharedString>;
     fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement;
     fn telemetry_event_text(&self, cx: &WindowContext) -> Option<&'static str>;
+    fn display_name(&self, cx: &AppContext) -> Option<&'static str>;
     fn dragged_tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement;
     fn project_path(&self, cx: &AppContext) -> Option<ProjectPath>;
     fn project_entry_ids(&self, cx: &AppContext) -> SmallVec<[ProjectEntryId; 3]>;
@@ -373,6 +380,10 @@ impl<T: Item> ItemHandle for View<T> {
         self.read(cx).telemetry_event_text()
     }
 
+    fn display_name(&self, cx: &AppContext) -> Option<&'static str> {
+        self.read(cx).display_name()
+    }
+
     fn tab_description(&self, detail: usize, cx: &AppContext) -> Option<SharedString> {
         self.read(cx).tab_description(detail, cx)
     }
@@ -193,6 +193,8 @@ impl Member {
                         !state.items_by_leader_view_id.contains_key(&view_id)
                     })
                 });
+                let unfollowable_view =
+                    follower_state.and_then(|state| state.unfollowable_view.clone());
 
                 let mut leader_border = None;
                 let mut leader_status_box = None;
@@ -211,7 +213,12 @@ impl Member {
                             project_id: leader_project_id,
                         } => {
                             if Some(leader_project_id) == project.read(cx).remote_id() {
-                                if is_in_unshared_view {
+                                if let Some(unfollowable_view) = unfollowable_view {
+                                    Some(Label::new(format!(
+                                        "{} is in the {}, which can't be followed",
+                                        leader.user.github_login, unfollowable_view
+                                    )))
+                                } else if is_in_unshared_view {
                                     Some(Label::new(format!(
                                         "{} is in an unshared pane",
                                         leader.user.github_login
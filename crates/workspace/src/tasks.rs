@@ -2,7 +2,7 @@ use project::TaskSourceKind;
 use task::{ResolvedTask, TaskContext, TaskTemplate};
 use ui::ViewContext;
 
-use crate::Workspace;
+use crate::{notifications::DetachAndPromptErr, Workspace};
 
 pub fn schedule_task(
     workspace: &Workspace,
@@ -33,14 +33,27 @@ pub fn schedule_resolved_task(
     cx: &mut ViewContext<'_, Workspace>,
 ) {
     if let Some(spawn_in_terminal) = resolved_task.resolved.take() {
+        let project = workspace.project().clone();
+        // Guests of a shared project ask its host to run the task instead.
+        let spawn_on_host = project.read(cx).runs_tasks_on_host().then(|| {
+            let label = resolved_task.original_task().label.clone();
+            project.update(cx, |project, cx| {
+                project.spawn_task_on_host(&task_source_kind, label, cx)
+            })
+        });
         if !omit_history {
             resolved_task.resolved = Some(spawn_in_terminal.clone());
-            workspace.project().update(cx, |project, cx| {
+            project.update(cx, |project, cx| {
                 project.task_inventory().update(cx, |inventory, _| {
                     inventory.task_scheduled(task_source_kind, resolved_task);
                 })
             });
         }
-        cx.emit(crate::Event::SpawnTask(spawn_in_terminal));
+        match spawn_on_host {
+            Some(task) => {
+                task.detach_and_prompt_err("Failed to run task on the host", cx, |_, _| None)
+            }
+            None => cx.emit(crate::Event::SpawnTask(spawn_in_terminal)),
+        }
     }
 }
@@ -588,6 +588,7 @@ pub struct Workspace {
     active_pane: View<Pane>,
     last_active_center_pane: Option<WeakView<Pane>>,
     last_active_view_id: Option<proto::ViewId>,
+    last_unfollowable_view: Option<String>,
     status_bar: View<StatusBar>,
     modal_layer: View<ModalLayer>,
     titlebar_item: Option<AnyView>,
@@ -626,6 +627,8 @@ pub struct ViewId {
 struct FollowerState {
     leader_id: PeerId,
     active_view_id: Option<ViewId>,
+    /// A description of the view the leader is focused on, when it can't be followed.
+    unfollowable_view: Option<SharedString>,
     items_by_leader_view_id: HashMap<ViewId, Box<dyn FollowableItemHandle>>,
 }
 
@@ -863,6 +866,7 @@ impl Workspace {
             active_pane: center_pane.clone(),
             last_active_center_pane: Some(center_pane.downgrade()),
             last_active_view_id: None,
+            last_unfollowable_view: None,
             status_bar,
             modal_layer,
             titlebar_item: None,
@@ -2291,14 +2295,31 @@ impl Workspace {
     ) -> Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>> {
         let project = self.project().clone();
         let project_item_builders = cx.default_global::<ProjectItemOpeners>().clone();
-        let Some(open_project_item) = project_item_builders
-            .iter()
-            .rev()
-            .find_map(|open_project_item| open_project_item(&project, &path, cx))
+        let mut openers = project_item_builders.0.into_iter().rev();
+        let Some(open_project_item) =
+            openers.find_map(|open_project_item| open_project_item(&project, &path, cx))
         else {
             return Task::ready(Err(anyhow!("cannot open file {:?}", path.path)));
         };
-        open_project_item
+
+        cx.spawn(|mut cx| async move {
+            let mut result = open_project_item.await;
+            // Openers that can't display a file's contents, such as editors given a binary
+            // file, hand it to the remaining openers, which may display it in another way.
+            while matches!(
+                &result,
+                Err(error) if matches!(error.error_code(), ErrorCode::BinaryFile | ErrorCode::TextFile)
+            ) {
+                let Some(open_project_item) = cx.update(|cx| {
+                    openers.find_map(|open_project_item| open_project_item(&project, &path, cx))
+                })?
+                else {
+                    break;
+                };
+                result = open_project_item.await;
+            }
+            result
+        })
     }
 
     pub fn open_project_item<T>(
@@ -2802,6 +2823,7 @@ impl Workspace {
             FollowerState {
                 leader_id,
                 active_view_id: None,
+                unfollowable_view: None,
                 items_by_leader_view_id: Default::default(),
             },
         );
@@ -2827,6 +2849,7 @@ impl Workspace {
                 } else {
                     None
                 };
+                state.unfollowable_view = response.unfollowable_view.map(Into::into);
                 Ok::<_, anyhow::Error>(())
             })??;
             if let Some(view) = response.active_view {
@@ -3053,32 +3076,82 @@ impl Workspace {
 
     // RPC handlers
 
-    fn active_view_for_follower(
+    /// Returns the item that followers should be shown, which is the active item of whichever
+    /// pane has focus, including panes hosted by docked panels. When focus is on a panel
+    /// without items, a description of that panel is returned instead.
+    fn focused_item_for_followers(
         &self,
-        follower_project_id: Option<u64>,
-        cx: &mut ViewContext<Self>,
-    ) -> Option<proto::View> {
-        let item = self.active_item(cx)?;
+        cx: &WindowContext,
+    ) -> Option<Result<Box<dyn ItemHandle>, SharedString>> {
+        if let Some(item) = self.active_item(cx) {
+            if item.focus_handle(cx).contains_focused(cx) {
+                return Some(Ok(item));
+            }
+        }
+
+        let panel = [&self.left_dock, &self.bottom_dock, &self.right_dock]
+            .into_iter()
+            .find_map(|dock| {
+                let panel = dock.read(cx).visible_panel()?;
+                panel
+                    .focus_handle(cx)
+                    .contains_focused(cx)
+                    .then(|| panel.clone())
+            })?;
+        let item = panel
+            .pane(cx)
+            .and_then(|pane| pane.read(cx).active_item())
+            .filter(|item| item.focus_handle(cx).contains_focused(cx));
+        Some(item.ok_or_else(|| unfollowable_view_description(panel.icon_tooltip(cx))))
+    }
+
+    /// Builds the view that followers should be shown for the given item, or a description
+    /// of the item when it can't be followed.
+    fn view_for_followers(
+        &self,
+        item: &dyn ItemHandle,
+        cx: &WindowContext,
+    ) -> Result<(proto::View, bool), SharedString> {
         let leader_id = self
-            .pane_for(&*item)
+            .pane_for(item)
             .and_then(|pane| self.leader_for_pane(&pane));
+        let view = item.to_followable_item_handle(cx).and_then(|item| {
+            let id = item.remote_id(&self.app_state.client, cx)?;
+            let variant = item.to_state_proto(cx)?;
+            let view = proto::View {
+                id: Some(id.to_proto()),
+                leader_id,
+                variant: Some(variant),
+            };
+            Some((view, item.is_project_item(cx)))
+        });
+        view.ok_or_else(|| unfollowable_view_description(item.display_name(cx)))
+    }
 
-        let item_handle = item.to_followable_item_handle(cx)?;
-        let id = item_handle.remote_id(&self.app_state.client, cx)?;
-        let variant = item_handle.to_state_proto(cx)?;
+    fn active_view_for_follower(
+        &self,
+        follower_project_id: Option<u64>,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<Option<proto::View>, SharedString> {
+        let item = match self.focused_item_for_followers(cx) {
+            Some(item) => item?,
+            None => {
+                let Some(item) = self.active_item(cx) else {
+                    return Ok(None);
+                };
+                item
+            }
+        };
+        let (view, is_project_item) = self.view_for_followers(item.as_ref(), cx)?;
 
-        if item_handle.is_project_item(cx)
+        if is_project_item
             && (follower_project_id.is_none()
                 || follower_project_id != self.project.read(cx).remote_id())
         {
-            return None;
+            return Ok(None);
         }
 
-        Some(proto::View {
-            id: Some(id.to_proto()),
-            leader_id,
-            variant: Some(variant),
-        })
+        Ok(Some(view))
     }
 
     fn handle_follow(
@@ -3089,13 +3162,18 @@ impl Workspace {
         let client = &self.app_state.client;
         let project_id = self.project.read(cx).remote_id();
 
-        let active_view = self.active_view_for_follower(follower_project_id, cx);
+        let (active_view, unfollowable_view) =
+            match self.active_view_for_follower(follower_project_id, cx) {
+                Ok(active_view) => (active_view, None),
+                Err(description) => (None, Some(description.to_string())),
+            };
         let active_view_id = active_view.as_ref().and_then(|view| view.id.clone());
 
         cx.notify();
 
         proto::FollowResponse {
             active_view,
+            unfollowable_view,
             // TODO: once v0.124.0 is retired we can stop sending these
             active_view_id,
             views: self
@@ -3167,6 +3245,8 @@ impl Workspace {
                             } else {
                                 None
                             };
+                        state.unfollowable_view =
+                            update_active_view.unfollowable_view.clone().map(Into::into);
 
                         if state.active_view_id.is_some_and(|view_id| {
                             !state.items_by_leader_view_id.contains_key(&view_id)
@@ -3338,41 +3418,33 @@ impl Workspace {
         let mut is_project_item = true;
         let mut update = proto::UpdateActiveView::default();
         if cx.is_window_active() {
-            if let Some(item) = self.active_item(cx) {
-                if item.focus_handle(cx).contains_focused(cx) {
-                    let leader_id = self
-                        .pane_for(&*item)
-                        .and_then(|pane| self.leader_for_pane(&pane));
-
-                    if let Some(item) = item.to_followable_item_handle(cx) {
-                        let id = item
-                            .remote_id(&self.app_state.client, cx)
-                            .map(|id| id.to_proto());
-
-                        if let Some(id) = id.clone() {
-                            if let Some(variant) = item.to_state_proto(cx) {
-                                let view = Some(proto::View {
-                                    id: Some(id.clone()),
-                                    leader_id,
-                                    variant: Some(variant),
-                                });
-
-                                is_project_item = item.is_project_item(cx);
-                                update = proto::UpdateActiveView {
-                                    view,
-                                    // TODO: once v0.124.0 is retired we can stop sending these
-                                    id: Some(id),
-                                    leader_id,
-                                };
-                            }
-                        };
-                    }
+            let view = self
+                .focused_item_for_followers(cx)
+                .map(|item| self.view_for_followers(item?.as_ref(), cx));
+            match view {
+                Some(Ok((view, view_is_project_item))) => {
+                    is_project_item = view_is_project_item;
+                    update = proto::UpdateActiveView {
+                        // TODO: once v0.124.0 is retired we can stop sending these
+                        id: view.id.clone(),
+                        leader_id: view.leader_id,
+                        view: Some(view),
+                        unfollowable_view: None,
+                    };
+                }
+                Some(Err(description)) => {
+                    update.unfollowable_view = Some(description.to_string());
                 }
+                None => {}
             }
         }
 
-        if &update.id != &self.last_active_view_id {
+        if &update.id != &self.last_active_view_id
+            || update.unfollowable_view != self.last_unfollowable_view
+        {
             self.last_active_view_id.clone_from(&update.id);
+            self.last_unfollowable_view
+                .clone_from(&update.unfollowable_view);
             self.update_followers(
                 is_project_item,
                 proto::update_followers::Variant::UpdateActiveView(update),
@@ -3405,6 +3477,13 @@ impl Workspace {
         self.follower_states.get(pane).map(|state| state.leader_id)
     }
 
+    /// Describes the view the leader of the given pane is in, if that view can't be followed.
+    pub fn unfollowable_view_for_pane(&self, pane: &View<Pane>) -> Option<SharedString> {
+        self.follower_states
+            .get(pane)
+            .and_then(|state| state.unfollowable_view.clone())
+    }
+
     fn leader_updated(&mut self, leader_id: PeerId, cx: &mut ViewContext<Self>) -> Option<()> {
         cx.notify();
 
@@ -3997,6 +4076,10 @@ impl Workspace {
     }
 }
 
+fn unfollowable_view_description(name: Option<&'static str>) -> SharedString {
+    name.map_or_else(|| "current view".into(), |name| name.to_lowercase().into())
+}
+
 fn window_bounds_env_override() -> Option<Bounds<Pixels>> {
     ZED_WINDOW_POSITION
         .zip(*ZED_WINDOW_SIZE)
@@ -37,7 +37,10 @@ use postage::{
     prelude::{Sink as _, Stream as _},
     watch,
 };
-use rpc::proto::{self, EnvelopedMessage as _, RequestMessage};
+use rpc::{
+    proto::{self, EnvelopedMessage as _, RequestMessage},
+    ErrorCode, ErrorCodeExt,
+};
 use settings::{Settings, SettingsLocation, SettingsStore};
 use smol::channel::{self, Sender};
 use std::{
@@ -47,6 +50,7 @@ use std::{
     ffi::OsStr,
     fmt,
     future::Future,
+    io::Read as _,
     mem,
     ops::{AddAssign, Deref, DerefMut, Sub},
     path::{Path, PathBuf},
@@ -58,7 +62,7 @@ use std::{
     time::{Duration, Instant, SystemTime},
 };
 use sum_tree::{Bias, Edit, SeekTarget, SumTree, TreeMap, TreeSet};
-use text::{LineEnding, Rope};
+use text::{Encoding, LineEnding, Rope};
 use util::{paths::home_dir, ResultExt};
 pub use worktree_settings::WorktreeSettings;
 
@@ -104,9 +108,33 @@ pub enum CreatedEntry {
 pub struct LoadedFile {
     pub file: Arc<File>,
     pub text: String,
+    /// The encoding the text was decoded from.
+    pub encoding: Encoding,
     pub diff_base: Option<String>,
+    /// Whether the file exceeds [WorktreeSettings::large_file_threshold], in which case
+    /// it was streamed from disk and no diff base was loaded for it.
+    pub is_large: bool,
+}
+
+/// How much of a large file has been read from disk while loading it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LoadProgress {
+    pub loaded: u64,
+    pub total: u64,
 }
 
+impl LoadProgress {
+    pub fn percentage(&self) -> u64 {
+        if self.total == 0 {
+            100
+        } else {
+            self.loaded * 100 / self.total
+        }
+    }
+}
+
+const LARGE_FILE_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
 pub struct LocalWorktree {
     snapshot: LocalSnapshot,
     scan_requests_tx: channel::Sender<ScanRequest>,
@@ -670,9 +698,19 @@ impl Worktree {
         &self,
         path: &Path,
         cx: &mut ModelContext<Worktree>,
+    ) -> Task<Result<LoadedFile>> {
+        self.load_file_with_progress(path, None, cx)
+    }
+
+    /// Loads a file, reporting how much of it has been read if it's a large file.
+    pub fn load_file_with_progress(
+        &self,
+        path: &Path,
+        progress: Option<watch::Sender<LoadProgress>>,
+        cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<LoadedFile>> {
         match self {
-            Worktree::Local(this) => this.load_file(path, cx),
+            Worktree::Local(this) => this.load_file(path, progress, cx),
             Worktree::Remote(_) => {
                 Task::ready(Err(anyhow!("remote worktrees can't yet load files")))
             }
@@ -684,10 +722,11 @@ impl Worktree {
         path: &Path,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         match self {
-            Worktree::Local(this) => this.write_file(path, text, line_ending, cx),
+            Worktree::Local(this) => this.write_file(path, text, line_ending, encoding, cx),
             Worktree::Remote(_) => {
                 Task::ready(Err(anyhow!("remote worktree can't yet write files")))
             }
@@ -1245,19 +1284,65 @@ impl LocalWorktree {
         self.git_repositories.get(&repo.work_directory.0)
     }
 
-    fn load_file(&self, path: &Path, cx: &mut ModelContext<Worktree>) -> Task<Result<LoadedFile>> {
+    fn load_file(
+        &self,
+        path: &Path,
+        mut progress: Option<watch::Sender<LoadProgress>>,
+        cx: &mut ModelContext<Worktree>,
+    ) -> Task<Result<LoadedFile>> {
         let path = Arc::from(path);
         let abs_path = self.absolutize(&path);
         let fs = self.fs.clone();
         let entry = self.refresh_entry(path.clone(), None, cx);
         let is_private = self.is_path_private(path.as_ref());
+        let settings = self.settings.clone();
 
         cx.spawn(|this, mut cx| async move {
             let abs_path = abs_path?;
-            let text = fs.load(&abs_path).await?;
+            let len = fs
+                .metadata(&abs_path)
+                .await?
+                .map_or(0, |metadata| metadata.len);
+            let is_large = settings.is_large_file(len);
+            let bytes = if is_large {
+                // Read large files in chunks, so that progress can be reported while they load.
+                let mut reader = fs.open_sync(&abs_path).await?;
+                cx.background_executor()
+                    .spawn(async move {
+                        let mut bytes = Vec::with_capacity(len as usize);
+                        let mut chunk = vec![0; LARGE_FILE_CHUNK_SIZE];
+                        loop {
+                            let read = reader.read(&mut chunk)?;
+                            if read == 0 {
+                                break;
+                            }
+                            bytes.extend_from_slice(&chunk[..read]);
+                            if let Some(progress) = progress.as_mut() {
+                                *progress.borrow_mut() = LoadProgress {
+                                    loaded: bytes.len() as u64,
+                                    total: len,
+                                };
+                            }
+                        }
+                        anyhow::Ok(bytes)
+                    })
+                    .await?
+            } else {
+                fs.load_bytes(&abs_path).await?
+            };
+            let (text, encoding) = cx
+                .background_executor()
+                .spawn(async move {
+                    let encoding = Encoding::detect(&bytes)?;
+                    Some((encoding.decode(&bytes).into_owned(), encoding))
+                })
+                .await
+                .ok_or_else(|| {
+                    ErrorCode::BinaryFile.message(format!("{abs_path:?} looks like a binary file"))
+                })?;
             let mut index_task = None;
             let snapshot = this.update(&mut cx, |this, _| this.as_local().unwrap().snapshot())?;
-            if let Some(repo) = snapshot.repository_for_path(&path) {
+            if let Some(repo) = snapshot.repository_for_path(&path).filter(|_| !is_large) {
                 if let Some(repo_path) = repo.relativize(&snapshot, &path).log_err() {
                     if let Some(git_repo) = snapshot.git_repositories.get(&*repo.work_directory) {
                         let git_repo = git_repo.repo_ptr.clone();
@@ -1320,7 +1405,9 @@ impl LocalWorktree {
             Ok(LoadedFile {
                 file,
                 text,
+                encoding,
                 diff_base,
+                is_large,
             })
         })
     }
@@ -1407,6 +1494,7 @@ impl LocalWorktree {
         path: impl Into<Arc<Path>>,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         let path = path.into();
@@ -1419,7 +1507,10 @@ impl LocalWorktree {
         let write = cx.background_executor().spawn({
             let fs = fs.clone();
             let abs_path = abs_path.clone();
-            async move { fs.save(&abs_path, &text, line_ending).await }
+            async move {
+                fs.save_with_encoding(&abs_path, &text, line_ending, encoding)
+                    .await
+            }
         });
 
         cx.spawn(move |this, mut cx| async move {
@@ -3043,6 +3134,14 @@ impl language::LocalFile for File {
         cx.background_executor()
             .spawn(async move { fs.load(&abs_path?).await })
     }
+
+    fn load_bytes(&self, cx: &AppContext) -> Task<Result<Vec<u8>>> {
+        let worktree = self.worktree.read(cx).as_local().unwrap();
+        let abs_path = worktree.absolutize(&self.path);
+        let fs = worktree.fs.clone();
+        cx.background_executor()
+            .spawn(async move { fs.load_bytes(&abs_path?).await })
+    }
 }
 
 impl File {
@@ -7,10 +7,14 @@ use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
 use util::paths::PathMatcher;
 
+const DEFAULT_LARGE_FILE_THRESHOLD_MB: u64 = 20;
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct WorktreeSettings {
     pub file_scan_exclusions: PathMatcher,
     pub private_files: PathMatcher,
+    /// Files at least this many bytes in size are opened in large-file mode.
+    pub large_file_threshold: u64,
 }
 
 impl WorktreeSettings {
@@ -23,6 +27,10 @@ impl WorktreeSettings {
         path.ancestors()
             .any(|ancestor| self.file_scan_exclusions.is_match(&ancestor))
     }
+
+    pub fn is_large_file(&self, len: u64) -> bool {
+        len >= self.large_file_threshold
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -45,6 +53,13 @@ pub struct WorktreeSettingsContent {
     /// Treat the files matching these globs as `.env` files.
     /// Default: [ "**/.env*" ]
     pub private_files: Option<Vec<String>>,
+
+    /// Open files of at least this many megabytes in large-file mode: they are read-only
+    /// by default, and aren't syntax highlighted, sent to language servers, compared
+    /// against the git index or given inlay hints.
+    ///
+    /// Default: 20
+    pub large_file_threshold_mb: Option<u64>,
 }
 
 impl Settings for WorktreeSettings {
@@ -64,6 +79,10 @@ impl Settings for WorktreeSettings {
         Ok(Self {
             file_scan_exclusions: path_matchers(&file_scan_exclusions, "file_scan_exclusions")?,
             private_files: path_matchers(&private_files, "private_files")?,
+            large_file_threshold: result
+                .large_file_threshold_mb
+                .unwrap_or(DEFAULT_LARGE_FILE_THRESHOLD_MB)
+                .saturating_mul(1024 * 1024),
         })
     }
 }
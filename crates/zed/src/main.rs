@@ -13,7 +13,7 @@ use client::{parse_zed_link, Client, DevServerToken, UserStore};
 use collab_ui::channel_view::ChannelView;
 use db::kvp::KEY_VALUE_STORE;
 use editor::Editor;
-use env_logger::Builder;
+use env_logger::{Builder, Target};
 use fs::RealFs;
 use futures::{future, StreamExt};
 use git::GitHostingProviderRegistry;
@@ -46,7 +46,7 @@ use welcome::{show_welcome_view, BaseKeymap, FIRST_OPEN};
 use workspace::{AppState, WorkspaceSettings, WorkspaceStore};
 use zed::{
     app_menus, build_window_options, handle_cli_connection, handle_keymap_file_changes,
-    initialize_workspace, open_paths_with_positions, OpenListener, OpenRequest,
+    initialize_workspace, open_paths_with_positions, open_ssh_project, OpenListener, OpenRequest,
 };
 
 use crate::zed::inline_completion_registry;
@@ -116,6 +116,7 @@ fn fail_to_open_window(e: anyhow::Error, _cx: &mut AppContext) {
 
 enum AppMode {
     Headless(DevServerToken),
+    SshServer,
     Ui,
 }
 impl Global for AppMode {}
@@ -151,8 +152,33 @@ fn init_headless(
     )
 }
 
+fn init_ssh_server(
+    path: String,
+    app_state: Arc<AppState>,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    cx.set_global(AppMode::SshServer);
+    headless::serve_ssh_project(
+        path,
+        app_state.client.clone(),
+        headless::AppState {
+            languages: app_state.languages.clone(),
+            user_store: app_state.user_store.clone(),
+            fs: app_state.fs.clone(),
+            node_runtime: app_state.node_runtime.clone(),
+        },
+        cx,
+    )
+}
+
 fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     match cx.try_global::<AppMode>() {
+        Some(AppMode::SshServer) => {
+            return Err(anyhow!(
+                "zed is serving a project over ssh. Use `kill {}` to stop it",
+                process::id()
+            ))
+        }
         Some(AppMode::Headless(_)) => {
             return Err(anyhow!(
                 "zed is already running in headless mode. Use `kill {}` to stop it",
@@ -174,6 +200,7 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     theme::init(theme::LoadThemes::All(Box::new(Assets)), cx);
     app_state.languages.set_theme(cx.theme().clone());
     command_palette::init(cx);
+    hex_editor::init(cx);
     editor::init(cx);
     image_viewer::init(cx);
     diagnostics::init(cx);
@@ -189,6 +216,7 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     project_symbols::init(cx);
     project_panel::init(Assets, cx);
     outline_panel::init(Assets, cx);
+    bookmarks_panel::init(cx);
     tasks_ui::init(cx);
     channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
     search::init(cx);
@@ -197,8 +225,11 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
 
     journal::init(app_state.clone(), cx);
     language_selector::init(cx);
+    encoding_selector::init(cx);
     theme_selector::init(cx);
     language_tools::init(cx);
+    local_history::init(cx);
+    undo_tree_view::init(cx);
     call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
     notifications::init(app_state.client.clone(), app_state.user_store.clone(), cx);
     collab_ui::init(&app_state, cx);
@@ -290,7 +321,8 @@ fn main() {
         return;
     }
 
-    init_logger();
+    let args = Args::parse();
+    init_logger(args.ssh_server.is_some());
 
     log::info!("========== starting zed ==========");
     let app = App::new().with_assets(Assets);
@@ -307,23 +339,27 @@ fn main() {
 
     let (open_listener, mut open_rx) = OpenListener::new();
 
-    #[cfg(target_os = "linux")]
-    {
-        if env::var("ZED_STATELESS").is_err() {
-            if crate::zed::listen_for_cli_connections(open_listener.clone()).is_err() {
+    // A server started over ssh talks to its guest over stdout, and runs alongside any Zed
+    // already running on this machine.
+    if args.ssh_server.is_none() {
+        #[cfg(target_os = "linux")]
+        {
+            if env::var("ZED_STATELESS").is_err() {
+                if crate::zed::listen_for_cli_connections(open_listener.clone()).is_err() {
+                    println!("zed is already running");
+                    return;
+                }
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            use zed::only_instance::*;
+            if ensure_only_instance() != IsOnlyInstance::Yes {
                 println!("zed is already running");
                 return;
             }
         }
     }
-    #[cfg(not(target_os = "linux"))]
-    {
-        use zed::only_instance::*;
-        if ensure_only_instance() != IsOnlyInstance::Yes {
-            println!("zed is already running");
-            return;
-        }
-    }
 
     let git_hosting_provider_registry = Arc::new(GitHostingProviderRegistry::new());
     let git_binary_path =
@@ -438,7 +474,18 @@ fn main() {
 
         reliability::init(client.http_client(), installation_id, cx);
 
-        let args = Args::parse();
+        if let Some(path) = args.ssh_server {
+            let task = init_ssh_server(path, app_state.clone(), cx);
+            cx.spawn(|cx| async move {
+                if let Err(e) = task.await {
+                    log::error!("{:?}", e);
+                }
+                cx.update(|cx| cx.quit()).log_err();
+            })
+            .detach();
+            return;
+        }
+
         let urls: Vec<_> = args
             .paths_or_urls
             .iter()
@@ -514,6 +561,16 @@ fn handle_open_request(request: OpenRequest, app_state: Arc<AppState>, cx: &mut
         return;
     };
 
+    for url in request.open_ssh_projects {
+        let app_state = app_state.clone();
+        cx.spawn(|mut cx| async move {
+            if let Err(err) = open_ssh_project(&url, app_state, &mut cx).await {
+                fail_to_open_window_async(err, &mut cx);
+            }
+        })
+        .detach();
+    }
+
     let mut task = None;
     if !request.open_paths.is_empty() {
         let app_state = app_state.clone();
@@ -683,9 +740,11 @@ fn init_paths() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn init_logger() {
-    if stdout_is_a_pty() {
-        init_stdout_logger();
+/// A server started with `--ssh-server` speaks to its guest over stdout, so it never logs
+/// there, falling back to stderr when the log file can't be opened.
+fn init_logger(ssh_server: bool) {
+    if stdout_is_a_pty() && !ssh_server {
+        init_env_logger(Target::Stdout);
     } else {
         let level = LevelFilter::Info;
 
@@ -721,8 +780,15 @@ fn init_logger() {
                 simplelog::WriteLogger::init(level, config, log_file)
                     .expect("could not initialize logger");
             }
+            Err(err) if ssh_server => {
+                init_env_logger(Target::Stderr);
+                log::error!(
+                    "could not open log file, defaulting to stderr logging: {}",
+                    err
+                );
+            }
             Err(err) => {
-                init_stdout_logger();
+                init_env_logger(Target::Stdout);
                 log::error!(
                     "could not open log file, defaulting to stdout logging: {}",
                     err
@@ -732,9 +798,10 @@ fn init_logger() {
     }
 }
 
-fn init_stdout_logger() {
+fn init_env_logger(target: Target) {
     Builder::new()
         .parse_default_env()
+        .target(target)
         .format(|buf, record| {
             use env_logger::fmt::Color;
 
@@ -872,19 +939,27 @@ struct Args {
     /// Use `path:line:row` syntax to open a file at a specific location.
     /// Non-existing paths and directories will ignore `:line:row` suffix.
     ///
-    /// URLs can either be `file://` or `zed://` scheme, or relative to <https://zed.dev>.
+    /// URLs can either be `file://`, `ssh://` or `zed://` scheme, or relative to <https://zed.dev>.
     paths_or_urls: Vec<String>,
 
     /// Instructs zed to run as a dev server on this machine. (not implemented)
     #[arg(long)]
     dev_server_token: Option<String>,
+
+    /// Instructs zed to serve the project at the given path over its standard input and
+    /// output, as done on the other machine for `zed ssh://host/path`.
+    #[arg(long, hide = true)]
+    ssh_server: Option<String>,
 }
 
 fn parse_url_arg(arg: &str, cx: &AppContext) -> Result<String> {
     match std::fs::canonicalize(Path::new(&arg)) {
         Ok(path) => Ok(format!("file://{}", path.to_string_lossy())),
         Err(error) => {
-            if arg.starts_with("file://") || arg.starts_with("zed-cli://") {
+            if arg.starts_with("file://")
+                || arg.starts_with("zed-cli://")
+                || arg.starts_with("ssh://")
+            {
                 Ok(arg.into())
             } else if let Some(_) = parse_zed_link(&arg, cx) {
                 Ok(arg.into())
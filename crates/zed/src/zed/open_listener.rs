@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Context, Result};
 use cli::{ipc, IpcHandshake};
 use cli::{ipc::IpcSender, CliRequest, CliResponse};
-use client::parse_zed_link;
+use client::{parse_zed_link, Client};
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
 use editor::scroll::Autoscroll;
@@ -9,8 +9,10 @@ use editor::Editor;
 use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures::channel::{mpsc, oneshot};
 use futures::{FutureExt, SinkExt, StreamExt};
-use gpui::{AppContext, AsyncAppContext, Global, WindowHandle};
+use gpui::{AppContext, AsyncAppContext, Global, VisualContext, WindowHandle};
 use language::{Bias, Point};
+use project::Project;
+use remote::SshConnectionOptions;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -28,6 +30,7 @@ use crate::{init_headless, init_ui};
 pub struct OpenRequest {
     pub cli_connection: Option<(mpsc::Receiver<CliRequest>, IpcSender<CliResponse>)>,
     pub open_paths: Vec<PathLikeWithPosition<PathBuf>>,
+    pub open_ssh_projects: Vec<String>,
     pub open_channel_notes: Vec<(u64, Option<String>)>,
     pub join_channel: Option<u64>,
 }
@@ -42,6 +45,8 @@ impl OpenRequest {
                 this.parse_file_path(file)
             } else if let Some(file) = url.strip_prefix("zed://file") {
                 this.parse_file_path(file)
+            } else if url.starts_with("ssh://") {
+                this.open_ssh_projects.push(url)
             } else if let Some(request_path) = parse_zed_link(&url, cx) {
                 this.parse_request_path(request_path).log_err();
             } else {
@@ -215,6 +220,43 @@ pub async fn open_paths_with_positions(
     Ok((workspace, items))
 }
 
+/// Opens the project at an `ssh://[user@]host[:port]/path` url in a new window. The project
+/// is served by a Zed server started on that host over `ssh`, without going through the
+/// collaboration server.
+pub async fn open_ssh_project(
+    url: &str,
+    app_state: Arc<AppState>,
+    cx: &mut AsyncAppContext,
+) -> Result<WindowHandle<Workspace>> {
+    let (options, path) = SshConnectionOptions::parse_url(url)?;
+    let connection = remote::start_ssh_server(&options, &path, cx).await?;
+
+    let client = cx.update(|cx| Client::production(cx))?;
+    Project::init_client(&client);
+    client.connect_with(connection, cx).await?;
+    let project = Project::ssh(
+        options.connection_string().into(),
+        client,
+        app_state.user_store.clone(),
+        app_state.languages.clone(),
+        app_state.fs.clone(),
+        cx.clone(),
+    )
+    .await?;
+
+    let window = cx.update(|cx| {
+        let options = (app_state.build_window_options)(None, cx);
+        cx.open_window(options, |cx| {
+            cx.new_view(|cx| Workspace::new(None, project, app_state.clone(), cx))
+        })
+    })??;
+    window.update(cx, |_, cx| {
+        cx.activate(true);
+        cx.activate_window();
+    })?;
+    Ok(window)
+}
+
 pub async fn handle_cli_connection(
     (mut requests, responses): (mpsc::Receiver<CliRequest>, IpcSender<CliResponse>),
     app_state: Arc<AppState>,
@@ -270,6 +312,29 @@ pub async fn handle_cli_connection(
                     return;
                 }
 
+                let mut errored = false;
+                let (ssh_urls, paths): (Vec<_>, Vec<_>) = paths
+                    .into_iter()
+                    .partition(|path| path.starts_with("ssh://"));
+                for url in &ssh_urls {
+                    if let Err(error) = open_ssh_project(url, app_state.clone(), &mut cx).await {
+                        responses
+                            .send(CliResponse::Stderr {
+                                message: format!("error opening {url}: {error}"),
+                            })
+                            .log_err();
+                        errored = true;
+                    }
+                }
+                if !ssh_urls.is_empty() && paths.is_empty() {
+                    responses
+                        .send(CliResponse::Exit {
+                            status: i32::from(errored),
+                        })
+                        .log_err();
+                    return;
+                }
+
                 let paths = if paths.is_empty() {
                     if open_new_workspace == Some(true) {
                         vec![]
@@ -306,8 +371,6 @@ pub async fn handle_cli_connection(
                         .collect()
                 };
 
-                let mut errored = false;
-
                 if !paths.is_empty() {
                     match open_paths_with_positions(
                         &paths,